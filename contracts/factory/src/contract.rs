@@ -3,8 +3,9 @@ use std::collections::HashSet;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, to_json_binary, Binary, CosmosMsg, Deps, DepsMut, Empty, Env, MessageInfo, Order, Reply,
-    ReplyOn, Response, StdError, StdResult, SubMsg, SubMsgResponse, SubMsgResult, WasmMsg,
+    attr, to_json_binary, wasm_execute, Addr, Binary, CosmosMsg, Decimal, Deps, DepsMut, Empty,
+    Env, Event, MessageInfo, Order, Reply, ReplyOn, Response, StdError, StdResult, Storage, SubMsg,
+    SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
 };
 use cw2::{get_contract_version, set_contract_version};
 use cw_utils::parse_instantiate_response_data;
@@ -13,18 +14,34 @@ use itertools::Itertools;
 use astroport::asset::{addr_opt_validate, AssetInfo, PairInfo};
 use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
 use astroport::factory::{
-    Config, ConfigResponse, ExecuteMsg, FeeInfoResponse, InstantiateMsg, PairConfig, PairType,
-    PairsResponse, QueryMsg,
+    BatchStatusResponse, BlacklistReason, Config, ConfigResponse, CreatePairParams, ExecuteMsg,
+    FeeInfoResponse, FeeTierGovernance, FeeTierProposal, HopCandidate, InstantiateMsg,
+    NextHopResponse, PairAddressResponse, PairConfig, PairType, PairsResponse, QueryMsg, Role,
+    MAX_BATCH_SIZE, MAX_PAIR_INFO_BATCH_SIZE,
 };
 use astroport::generator::ExecuteMsg::DeactivatePool;
-use astroport::pair::InstantiateMsg as PairInstantiateMsg;
+use astroport::pair::{
+    CustomHookConfig, ExecuteMsg as PairExecuteMsg, InstantiateMsg as PairInstantiateMsg,
+};
+use astroport::pair_xyk::ExecuteMsg as XykPairExecuteMsg;
+use astroport::xastro_token::QueryMsg as XastroQueryMsg;
+use cw20::BalanceResponse;
 
 use crate::error::ContractError;
-use crate::migration::migrate_pair_configs;
-use crate::querier::query_pair_info;
+use crate::migration::{
+    approve_pair_config_code_ids, init_pair_creation_info, init_total_pairs, migrate_pair_configs,
+};
+use crate::querier::{query_pair_info, query_pool};
 use crate::state::{
-    check_asset_infos, pair_key, read_pairs, TmpPairInfo, CONFIG, OWNERSHIP_PROPOSAL, PAIRS,
-    PAIR_CONFIGS, TMP_PAIR_INFO,
+    assert_authorized, check_asset_infos, has_role, pagination_limit, pair_key,
+    read_blacklisted_pairs, read_pair_configs, read_pairs, read_pairs_by_asset,
+    read_pairs_by_creator, read_pairs_by_volume, set_tvl_contribution, TmpBatchInfo, TmpBatchPair,
+    TmpPairInfo, ALL_PAIRS_PAUSED, APPROVED_CODE_IDS, BATCH_FAILURES, BATCH_STATE,
+    BLACKLISTED_CREATORS, BLACKLISTED_PAIRS, CONFIG, FEE_TIER_GOVERNANCE, FEE_TIER_PROPOSALS,
+    FEE_TIER_PROPOSAL_COUNT, FEE_TIER_VOTES, GAS_BENCHMARKS, LP_TO_PAIR, NEXT_BATCH_ID,
+    NEXT_PAIR_ID, OWNERSHIP_PROPOSAL, PAIRS, PAIRS_BY_ASSET, PAIRS_BY_CREATOR, PAIR_ADMINS,
+    PAIR_CONFIGS, PAIR_CREATION_INFO, PAIR_CREATORS, PAIR_FEE_TIERS, PAIR_VOLUME_REGISTRY,
+    PAUSED_PAIRS, ROLES, TMP_BATCH_INFO, TMP_PAIR_INFO, TOTAL_PAIRS, TOTAL_TVL,
 };
 
 /// Contract name that is used for migration.
@@ -33,6 +50,12 @@ const CONTRACT_NAME: &str = "astroport-factory";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 /// A `reply` call code ID used in a sub-message.
 const INSTANTIATE_PAIR_REPLY_ID: u64 = 1;
+/// A `reply` call code ID used when pushing the pause flag to a pair.
+const SET_PAIR_PAUSED_REPLY_ID: u64 = 2;
+/// The maximum sum of total and maker fee bps (100%)
+const MAX_FEE_BPS: u32 = 10_000;
+/// A `reply` call code ID offset used in batch sub-messages. The pair's index in the batch is added to it.
+const BATCH_PAIR_REPLY_ID_OFFSET: u64 = 1000;
 
 /// Creates a new contract with the specified parameters packed in the `msg` variable.
 ///
@@ -53,6 +76,7 @@ pub fn instantiate(
         generator_address: None,
         whitelist_code_id: msg.whitelist_code_id,
         coin_registry_address: deps.api.addr_validate(&msg.coin_registry_address)?,
+        multisig_module: None,
     };
 
     config.generator_address = addr_opt_validate(deps.api, &msg.generator_address)?;
@@ -71,12 +95,13 @@ pub fn instantiate(
 
     for pc in msg.pair_configs.iter() {
         // Validate total and maker fee bps
-        if !pc.valid_fee_bps() {
-            return Err(ContractError::PairConfigInvalidFeeBps {});
-        }
+        validate_fee_config(pc)?;
         PAIR_CONFIGS.save(deps.storage, pc.pair_type.to_string(), pc)?;
+        // Code IDs of the initial pair types are approved by the instantiator
+        APPROVED_CODE_IDS.save(deps.storage, pc.code_id, &true)?;
     }
     CONFIG.save(deps.storage, &config)?;
+    TOTAL_PAIRS.save(deps.storage, &0)?;
 
     Ok(Response::new())
 }
@@ -104,15 +129,25 @@ pub struct UpdateConfig {
 ///             generator_address,
 ///         }** Updates general contract parameters.
 ///
-/// * **ExecuteMsg::UpdatePairConfig { config }** Updates a pair type
+/// * **ExecuteMsg::UpdatePairConfig { config, proposal_id }** Updates a pair type
 /// * configuration or creates a new pair type if a [`Custom`] name is used (which hasn't been used before).
 ///
 /// * **ExecuteMsg::CreatePair {
 ///             pair_type,
 ///             asset_infos,
 ///             init_params,
+///             proposal_id,
 ///         }** Creates a new pair with the specified input parameters.
 ///
+/// * **ExecuteMsg::GetOrCreatePair {
+///             pair_type,
+///             asset_infos,
+///             init_params,
+///             proposal_id,
+///         }** Returns the existing pair for the assets or creates a new one.
+///
+/// * **ExecuteMsg::BatchCreatePairs { pairs, proposal_id }** Creates up to [`MAX_BATCH_SIZE`] pairs at once.
+///
 /// * **ExecuteMsg::Deregister { asset_infos }** Removes an existing pair from the factory.
 /// * The asset information is for the assets that are traded in the pair.
 ///
@@ -121,6 +156,40 @@ pub struct UpdateConfig {
 /// * **ExecuteMsg::DropOwnershipProposal {}** Removes a request to change contract ownership.
 ///
 /// * **ExecuteMsg::ClaimOwnership {}** Claims contract ownership.
+///
+/// * **ExecuteMsg::RevokeCreatorPairs { creator }** Blacklists a creator and removes their pairs from the creator index.
+///
+/// * **ExecuteMsg::GrantRole { address, role }** Grants an administrative role to an address.
+///
+/// * **ExecuteMsg::RevokeRole { address, role }** Revokes an administrative role from an address.
+///
+/// * **ExecuteMsg::PausePair { asset_infos, proposal_id }** Pauses a specific pair.
+///
+/// * **ExecuteMsg::UnpausePair { asset_infos, proposal_id }** Unpauses a specific pair.
+///
+/// * **ExecuteMsg::PauseAllPairs { proposal_id }** Pauses all pairs at once.
+///
+/// * **ExecuteMsg::UnpauseAllPairs { proposal_id }** Lifts the global pause.
+///
+/// * **ExecuteMsg::SetMultisigModule { multisig_module }** Sets or removes the multisig module.
+///
+/// * **ExecuteMsg::ApproveCodeId { code_id }** Allows pairs to be instantiated from a code ID.
+///
+/// * **ExecuteMsg::RevokeCodeId { code_id }** Prevents new pairs from being instantiated from a code ID.
+///
+/// * **ExecuteMsg::UpdateTvlContribution { pair, tvl_usd }** Reports the USD value locked in a pair.
+///
+/// * **ExecuteMsg::SetPairAdmin { pair, admin }** Sets the admin of a specific pair.
+///
+/// * **ExecuteMsg::UpdatePairParams { pair, params }** Forwards a config update to a pair on behalf of its admin.
+///
+/// * **ExecuteMsg::SetPairCustomHook { pair, config }** Sets the contract notified after actions in a pair.
+///
+/// * **ExecuteMsg::UpdatePairVolumeRankings { rankings }** Replaces the pair volume rankings.
+///
+/// * **ExecuteMsg::SetGasBenchmark { pair_type, gas_per_swap }** Sets the gas used by a swap in a pair type.
+///
+/// * **ExecuteMsg::BlacklistPair { pair, reason, proposal_id }** Delists a pair in an emergency.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -146,13 +215,42 @@ pub fn execute(
                 coin_registry_address,
             },
         ),
-        ExecuteMsg::UpdatePairConfig { config } => execute_update_pair_config(deps, info, config),
+        ExecuteMsg::UpdatePairConfig {
+            config,
+            proposal_id,
+        } => execute_update_pair_config(deps, info, config, proposal_id),
         ExecuteMsg::CreatePair {
             pair_type,
             asset_infos,
             init_params,
-        } => execute_create_pair(deps, info, env, pair_type, asset_infos, init_params),
-        ExecuteMsg::Deregister { asset_infos } => deregister(deps, info, asset_infos),
+            proposal_id,
+        } => execute_create_pair(
+            deps,
+            info,
+            env,
+            pair_type,
+            asset_infos,
+            init_params,
+            proposal_id,
+        ),
+        ExecuteMsg::GetOrCreatePair {
+            pair_type,
+            asset_infos,
+            init_params,
+            proposal_id,
+        } => execute_get_or_create_pair(
+            deps,
+            info,
+            env,
+            pair_type,
+            asset_infos,
+            init_params,
+            proposal_id,
+        ),
+        ExecuteMsg::BatchCreatePairs { pairs, proposal_id } => {
+            execute_batch_create_pairs(deps, info, env, pairs, proposal_id)
+        }
+        ExecuteMsg::Deregister { asset_infos } => deregister(deps, env, info, asset_infos),
         ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
             let config = CONFIG.load(deps.storage)?;
 
@@ -184,6 +282,66 @@ pub fn execute(
             })
             .map_err(Into::into)
         }
+        ExecuteMsg::RevokeCreatorPairs { creator } => revoke_creator_pairs(deps, info, creator),
+        ExecuteMsg::GrantRole { address, role } => update_role(deps, info, address, role, true),
+        ExecuteMsg::RevokeRole { address, role } => update_role(deps, info, address, role, false),
+        ExecuteMsg::PausePair {
+            asset_infos,
+            proposal_id,
+        } => set_pair_paused(deps, info, asset_infos, proposal_id, true),
+        ExecuteMsg::UnpausePair {
+            asset_infos,
+            proposal_id,
+        } => set_pair_paused(deps, info, asset_infos, proposal_id, false),
+        ExecuteMsg::PauseAllPairs { proposal_id } => {
+            set_all_pairs_paused(deps, info, proposal_id, true)
+        }
+        ExecuteMsg::UnpauseAllPairs { proposal_id } => {
+            set_all_pairs_paused(deps, info, proposal_id, false)
+        }
+        ExecuteMsg::SetMultisigModule { multisig_module } => {
+            set_multisig_module(deps, info, multisig_module)
+        }
+        ExecuteMsg::ApproveCodeId { code_id } => update_code_id_approval(deps, info, code_id, true),
+        ExecuteMsg::RevokeCodeId { code_id } => update_code_id_approval(deps, info, code_id, false),
+        ExecuteMsg::UpdateTvlContribution { pair, tvl_usd } => {
+            update_tvl_contribution(deps, info, pair, tvl_usd)
+        }
+        ExecuteMsg::SetPairAdmin { pair, admin } => set_pair_admin(deps, info, pair, admin),
+        ExecuteMsg::UpdatePairParams { pair, params } => {
+            update_pair_params(deps, info, pair, params)
+        }
+        ExecuteMsg::SetPairCustomHook { pair, config } => {
+            set_pair_custom_hook(deps, info, pair, config)
+        }
+        ExecuteMsg::UpdatePairVolumeRankings { rankings } => {
+            update_pair_volume_rankings(deps, info, rankings)
+        }
+        ExecuteMsg::SetGasBenchmark {
+            pair_type,
+            gas_per_swap,
+        } => set_gas_benchmark(deps, info, pair_type, gas_per_swap),
+        ExecuteMsg::UpdateFeeTierGovernance {
+            xastro_token,
+            min_stake,
+            voting_period,
+            quorum,
+        } => update_fee_tier_governance(deps, info, xastro_token, min_stake, voting_period, quorum),
+        ExecuteMsg::ProposeFeeTierChange {
+            pair,
+            new_total_fee_bps,
+        } => propose_fee_tier_change(deps, env, info, pair, new_total_fee_bps),
+        ExecuteMsg::VoteOnFeeTierProposal { proposal_id, vote } => {
+            vote_on_fee_tier_proposal(deps, env, info, proposal_id, vote)
+        }
+        ExecuteMsg::ExecuteFeeTierProposal { proposal_id } => {
+            execute_fee_tier_proposal(deps, env, proposal_id)
+        }
+        ExecuteMsg::BlacklistPair {
+            pair,
+            reason,
+            proposal_id,
+        } => blacklist_pair(deps, env, info, pair, reason, proposal_id),
     }
 }
 
@@ -201,9 +359,14 @@ pub fn execute_update_config(
     let mut config = CONFIG.load(deps.storage)?;
 
     // Permission check
-    if info.sender != config.owner {
-        return Err(ContractError::Unauthorized {});
-    }
+    assert_authorized(
+        deps.storage,
+        deps.querier,
+        &config,
+        &info.sender,
+        None,
+        None,
+    )?;
 
     if let Some(fee_address) = param.fee_address {
         // Validate address format
@@ -236,24 +399,30 @@ pub fn execute_update_config(
 ///
 /// * **pair_config** is an object of type [`PairConfig`] that contains the pair type information to update.
 ///
+/// * **proposal_id** is the passed multisig proposal approving this operation.
+///
 /// ## Executor
-/// Only the owner can execute this.
+/// Only the owner or a [`Role::FeeAdmin`] can execute this.
 pub fn execute_update_pair_config(
     deps: DepsMut,
     info: MessageInfo,
     pair_config: PairConfig,
+    proposal_id: Option<u64>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
     // Permission check
-    if info.sender != config.owner {
-        return Err(ContractError::Unauthorized {});
-    }
+    assert_authorized(
+        deps.storage,
+        deps.querier,
+        &config,
+        &info.sender,
+        Some(Role::FeeAdmin),
+        proposal_id,
+    )?;
 
     // Validate total and maker fee bps
-    if !pair_config.valid_fee_bps() {
-        return Err(ContractError::PairConfigInvalidFeeBps {});
-    }
+    validate_fee_config(&pair_config)?;
 
     PAIR_CONFIGS.save(
         deps.storage,
@@ -264,6 +433,22 @@ pub fn execute_update_pair_config(
     Ok(Response::new().add_attribute("action", "update_pair_config"))
 }
 
+/// Validates the total and maker fee bps of a pair type.
+/// Their sum must not exceed 10,000 bps (100%).
+fn validate_fee_config(pair_config: &PairConfig) -> Result<(), ContractError> {
+    if !pair_config.valid_fee_bps() {
+        return Err(ContractError::PairConfigInvalidFeeBps {});
+    }
+
+    if pair_config.total_fee_bps as u32 + pair_config.maker_fee_bps as u32 > MAX_FEE_BPS {
+        return Err(ContractError::InvalidFeeConfig(format!(
+            "total_fee_bps + maker_fee_bps must not exceed {MAX_FEE_BPS} bps"
+        )));
+    }
+
+    Ok(())
+}
+
 /// Creates a new pair of `pair_type` with the assets specified in `asset_infos`.
 ///
 /// * **pair_type** is the pair type of the newly created pair.
@@ -271,6 +456,8 @@ pub fn execute_update_pair_config(
 /// * **asset_infos** is a vector with assets for which we create a pair.
 ///
 /// * **init_params** These are packed params used for custom pair types that need extra data to be instantiated.
+///
+/// * **proposal_id** is the passed multisig proposal approving the creation of a permissioned pair.
 pub fn execute_create_pair(
     deps: DepsMut,
     info: MessageInfo,
@@ -278,47 +465,41 @@ pub fn execute_create_pair(
     pair_type: PairType,
     asset_infos: Vec<AssetInfo>,
     init_params: Option<Binary>,
+    proposal_id: Option<u64>,
 ) -> Result<Response, ContractError> {
-    check_asset_infos(deps.api, &asset_infos)?;
-
     let config = CONFIG.load(deps.storage)?;
 
-    if PAIRS.has(deps.storage, &pair_key(&asset_infos)) {
-        return Err(ContractError::PairWasCreated {});
-    }
-
-    // Get pair type from config
-    let pair_config = PAIR_CONFIGS
-        .load(deps.storage, pair_type.to_string())
-        .map_err(|_| ContractError::PairConfigNotFound {})?;
-
-    if pair_config.permissioned && info.sender != config.owner {
-        return Err(ContractError::Unauthorized {});
-    }
+    let pair_config = validate_create_pair(
+        deps.as_ref(),
+        &config,
+        &info.sender,
+        &pair_type,
+        &asset_infos,
+    )?;
 
-    // Check if pair config is disabled
-    if pair_config.is_disabled {
-        return Err(ContractError::PairConfigDisabled {});
+    if pair_config.permissioned {
+        assert_authorized(
+            deps.storage,
+            deps.querier,
+            &config,
+            &info.sender,
+            None,
+            proposal_id,
+        )?;
     }
 
     let pair_key = pair_key(&asset_infos);
-    TMP_PAIR_INFO.save(deps.storage, &TmpPairInfo { pair_key })?;
+    TMP_PAIR_INFO.save(
+        deps.storage,
+        &TmpPairInfo {
+            pair_key,
+            creator: info.sender,
+        },
+    )?;
 
     let sub_msg: Vec<SubMsg> = vec![SubMsg {
         id: INSTANTIATE_PAIR_REPLY_ID,
-        msg: WasmMsg::Instantiate {
-            admin: Some(config.owner.to_string()),
-            code_id: pair_config.code_id,
-            msg: to_json_binary(&PairInstantiateMsg {
-                asset_infos: asset_infos.clone(),
-                token_code_id: config.token_code_id,
-                factory_addr: env.contract.address.to_string(),
-                init_params,
-            })?,
-            funds: vec![],
-            label: "Astroport pair".to_string(),
-        }
-        .into(),
+        msg: instantiate_pair_msg(&env, &config, &pair_config, &asset_infos, init_params)?.into(),
         gas_limit: None,
         reply_on: ReplyOn::Success,
     }];
@@ -328,12 +509,222 @@ pub fn execute_create_pair(
         .add_attributes(vec![
             attr("action", "create_pair"),
             attr("pair", asset_infos.iter().join("-")),
+            attr("pair_type", pair_type.to_string()),
+        ]))
+}
+
+/// Returns the pair registered for `asset_infos` or creates a new one with the specified parameters.
+/// Pairs are matched by their assets only, so an existing pair is returned regardless of its type.
+///
+/// * **pair_type** is the type of the pair to create if it doesn't exist yet.
+///
+/// * **asset_infos** is a vector with assets to find or create the pair for.
+///
+/// * **init_params** these are packed params used for custom pair types that need extra data to be instantiated.
+///
+/// * **proposal_id** is the passed multisig proposal approving the creation of a permissioned pair.
+pub fn execute_get_or_create_pair(
+    deps: DepsMut,
+    info: MessageInfo,
+    env: Env,
+    pair_type: PairType,
+    asset_infos: Vec<AssetInfo>,
+    init_params: Option<Binary>,
+    proposal_id: Option<u64>,
+) -> Result<Response, ContractError> {
+    check_asset_infos(deps.api, &asset_infos)?;
+
+    if let Some(pair_addr) = PAIRS.may_load(deps.storage, &pair_key(&asset_infos))? {
+        return Ok(Response::new().add_attributes(vec![
+            attr("action", "found"),
+            attr("pair_address", pair_addr),
+        ]));
+    }
+
+    let pair = asset_infos.iter().join("-");
+    let response = execute_create_pair(
+        deps,
+        info,
+        env,
+        pair_type,
+        asset_infos,
+        init_params,
+        proposal_id,
+    )?;
+
+    // The pair address is emitted by the reply once the pair is instantiated
+    Ok(Response::new()
+        .add_submessages(response.messages)
+        .add_attributes(vec![attr("action", "created"), attr("pair", pair)]))
+}
+
+/// Creates multiple pairs at once. Each pair is instantiated in a separate submessage,
+/// so a failing pair doesn't revert the whole batch. Results are stored under the returned `batch_id`.
+///
+/// * **pairs** is a vector with parameters of the pairs to create.
+///
+/// * **proposal_id** is the passed multisig proposal approving the creation of permissioned pairs.
+pub fn execute_batch_create_pairs(
+    deps: DepsMut,
+    info: MessageInfo,
+    env: Env,
+    pairs: Vec<CreatePairParams>,
+    proposal_id: Option<u64>,
+) -> Result<Response, ContractError> {
+    if pairs.is_empty() || pairs.len() > MAX_BATCH_SIZE {
+        return Err(ContractError::InvalidBatchSize(MAX_BATCH_SIZE));
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut pair_keys = HashSet::new();
+    let mut permissioned_authorized = false;
+    let mut tmp_pairs = vec![];
+    let mut sub_msgs = vec![];
+    for (idx, params) in pairs.into_iter().enumerate() {
+        let pair_config = validate_create_pair(
+            deps.as_ref(),
+            &config,
+            &info.sender,
+            &params.pair_type,
+            &params.asset_infos,
+        )?;
+
+        // A single authorization covers all permissioned pairs in the batch
+        if pair_config.permissioned && !permissioned_authorized {
+            assert_authorized(
+                deps.storage,
+                deps.querier,
+                &config,
+                &info.sender,
+                None,
+                proposal_id,
+            )?;
+            permissioned_authorized = true;
+        }
+
+        let pair_key = pair_key(&params.asset_infos);
+        if !pair_keys.insert(pair_key.clone()) {
+            return Err(ContractError::DuplicatePairInBatch(
+                params.asset_infos.iter().join("-"),
+            ));
+        }
+
+        sub_msgs.push(SubMsg::reply_always(
+            instantiate_pair_msg(
+                &env,
+                &config,
+                &pair_config,
+                &params.asset_infos,
+                params.init_params,
+            )?,
+            BATCH_PAIR_REPLY_ID_OFFSET + idx as u64,
+        ));
+        tmp_pairs.push(TmpBatchPair {
+            pair_key,
+            asset_infos: params.asset_infos,
+        });
+    }
+
+    let batch_id = NEXT_BATCH_ID.may_load(deps.storage)?.unwrap_or_default();
+    NEXT_BATCH_ID.save(deps.storage, &(batch_id + 1))?;
+    BATCH_STATE.save(deps.storage, batch_id, &vec![])?;
+    BATCH_FAILURES.save(deps.storage, batch_id, &vec![])?;
+
+    let pairs_count = tmp_pairs.len();
+    TMP_BATCH_INFO.save(
+        deps.storage,
+        &TmpBatchInfo {
+            batch_id,
+            creator: info.sender,
+            pairs: tmp_pairs,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_submessages(sub_msgs)
+        .add_attributes(vec![
+            attr("action", "batch_create_pairs"),
+            attr("batch_id", batch_id.to_string()),
+            attr("pairs", pairs_count.to_string()),
         ]))
 }
 
+/// Checks whether `sender` is allowed to create a pair of `pair_type` with the assets specified in `asset_infos`.
+/// Returns the pair type's configuration. Permissioned pair types must be authorized by the caller.
+fn validate_create_pair(
+    deps: Deps,
+    config: &Config,
+    sender: &Addr,
+    pair_type: &PairType,
+    asset_infos: &[AssetInfo],
+) -> Result<PairConfig, ContractError> {
+    check_asset_infos(deps.api, asset_infos)?;
+
+    if PAIRS
+        .may_load(deps.storage, &pair_key(asset_infos))?
+        .is_some()
+    {
+        return Err(ContractError::PairWasCreated {});
+    }
+
+    if BLACKLISTED_CREATORS.has(deps.storage, sender) {
+        return Err(ContractError::CreatorBlacklisted(sender.to_string()));
+    }
+
+    // Get pair type from config
+    let pair_config = PAIR_CONFIGS
+        .load(deps.storage, pair_type.to_string())
+        .map_err(|_| ContractError::PairConfigNotFound {})?;
+
+    // Check if pair config is disabled
+    if pair_config.is_disabled {
+        return Err(ContractError::PairConfigDisabled {});
+    }
+
+    // Check if pairs can be instantiated from the pair type's code ID
+    if !APPROVED_CODE_IDS
+        .may_load(deps.storage, pair_config.code_id)?
+        .unwrap_or_default()
+    {
+        return Err(ContractError::CodeIdNotApproved(pair_config.code_id));
+    }
+
+    Ok(pair_config)
+}
+
+/// Builds a message which instantiates a new pair contract.
+fn instantiate_pair_msg(
+    env: &Env,
+    config: &Config,
+    pair_config: &PairConfig,
+    asset_infos: &[AssetInfo],
+    init_params: Option<Binary>,
+) -> StdResult<WasmMsg> {
+    Ok(WasmMsg::Instantiate {
+        admin: Some(config.owner.to_string()),
+        code_id: pair_config.code_id,
+        msg: to_json_binary(&PairInstantiateMsg {
+            asset_infos: asset_infos.to_vec(),
+            token_code_id: config.token_code_id,
+            factory_addr: env.contract.address.to_string(),
+            init_params,
+        })?,
+        funds: vec![],
+        label: "Astroport pair".to_string(),
+    })
+}
+
+/// Builds the event which lets off-chain services know that the metadata of a new pair
+/// can be registered in the pair registry.
+fn registry_hint_event(pair_contract: &Addr, creator: &Addr) -> Event {
+    Event::new("registry_hint")
+        .add_attributes([attr("pair", pair_contract), attr("creator", creator)])
+}
+
 /// The entry point to the contract for processing replies from submessages.
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+pub fn reply(mut deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
     match msg {
         Reply {
             id: INSTANTIATE_PAIR_REPLY_ID,
@@ -343,24 +734,116 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
                 }),
         } => {
             let tmp = TMP_PAIR_INFO.load(deps.storage)?;
-            if PAIRS.has(deps.storage, &tmp.pair_key) {
-                return Err(ContractError::PairWasRegistered {});
+            let pair_contract = register_pair(deps, &env, &tmp.pair_key, &tmp.creator, &data)?;
+
+            Ok(Response::new()
+                .add_event(registry_hint_event(&pair_contract, &tmp.creator))
+                .add_attributes(vec![
+                    attr("action", "register"),
+                    attr("pair_contract_addr", pair_contract),
+                    attr("creator", tmp.creator),
+                ]))
+        }
+        // Pair types without the pause flag keep querying the factory
+        Reply {
+            id: SET_PAIR_PAUSED_REPLY_ID,
+            result: SubMsgResult::Err(err),
+        } => Ok(Response::new().add_attributes(vec![
+            attr("action", "set_pair_paused_failed"),
+            attr("error", err),
+        ])),
+        Reply { id, result } if id >= BATCH_PAIR_REPLY_ID_OFFSET => {
+            let tmp = TMP_BATCH_INFO.load(deps.storage)?;
+            let tmp_pair = tmp
+                .pairs
+                .get((id - BATCH_PAIR_REPLY_ID_OFFSET) as usize)
+                .ok_or(ContractError::FailedToParseReply {})?;
+
+            match result {
+                SubMsgResult::Ok(SubMsgResponse {
+                    data: Some(data), ..
+                }) => {
+                    let pair_contract = register_pair(
+                        deps.branch(),
+                        &env,
+                        &tmp_pair.pair_key,
+                        &tmp.creator,
+                        &data,
+                    )?;
+                    BATCH_STATE.update::<_, StdError>(deps.storage, tmp.batch_id, |pairs| {
+                        let mut pairs = pairs.unwrap_or_default();
+                        pairs.push(pair_contract.clone());
+                        Ok(pairs)
+                    })?;
+
+                    Ok(Response::new()
+                        .add_event(registry_hint_event(&pair_contract, &tmp.creator))
+                        .add_attributes(vec![
+                            attr("action", "register"),
+                            attr("pair_contract_addr", pair_contract),
+                            attr("creator", tmp.creator),
+                            attr("batch_id", tmp.batch_id.to_string()),
+                        ]))
+                }
+                SubMsgResult::Ok(_) => Err(ContractError::FailedToParseReply {}),
+                SubMsgResult::Err(err) => {
+                    BATCH_FAILURES.update::<_, StdError>(deps.storage, tmp.batch_id, |failed| {
+                        let mut failed = failed.unwrap_or_default();
+                        failed.push(tmp_pair.asset_infos.clone());
+                        Ok(failed)
+                    })?;
+
+                    Ok(Response::new().add_attributes(vec![
+                        attr("action", "register_failed"),
+                        attr("pair", tmp_pair.asset_infos.iter().join("-")),
+                        attr("batch_id", tmp.batch_id.to_string()),
+                        attr("error", err),
+                    ]))
+                }
             }
+        }
+        _ => Err(ContractError::FailedToParseReply {}),
+    }
+}
 
-            let init_response = parse_instantiate_response_data(data.as_slice())
-                .map_err(|e| StdError::generic_err(format!("{e}")))?;
+/// Saves a newly instantiated pair using the instantiate response `data`. Returns the pair address.
+fn register_pair(
+    deps: DepsMut,
+    env: &Env,
+    pair_key: &[u8],
+    creator: &Addr,
+    data: &Binary,
+) -> Result<Addr, ContractError> {
+    if PAIRS.may_load(deps.storage, pair_key)?.is_some() {
+        return Err(ContractError::PairWasRegistered {});
+    }
 
-            let pair_contract = deps.api.addr_validate(&init_response.contract_address)?;
+    let init_response = parse_instantiate_response_data(data.as_slice())
+        .map_err(|e| StdError::generic_err(format!("{e}")))?;
 
-            PAIRS.save(deps.storage, &tmp.pair_key, &pair_contract)?;
+    let pair_contract = deps.api.addr_validate(&init_response.contract_address)?;
 
-            Ok(Response::new().add_attributes(vec![
-                attr("action", "register"),
-                attr("pair_contract_addr", pair_contract),
-            ]))
-        }
-        _ => Err(ContractError::FailedToParseReply {}),
+    PAIRS.save(deps.storage, pair_key, &pair_contract, env.block.height)?;
+
+    // The pair has already stored its LP token in its own instantiation reply
+    let pair_info = query_pair_info(&deps.querier, &pair_contract)?;
+    LP_TO_PAIR.save(deps.storage, &pair_info.liquidity_token, &pair_contract)?;
+    for asset_info in &pair_info.asset_infos {
+        PAIRS_BY_ASSET.save(deps.storage, (asset_info.to_string(), &pair_contract), &())?;
     }
+
+    let pair_id = NEXT_PAIR_ID.may_load(deps.storage)?.unwrap_or_default();
+    NEXT_PAIR_ID.save(deps.storage, &(pair_id + 1))?;
+    PAIRS_BY_CREATOR.save(deps.storage, (creator, pair_id), &pair_contract)?;
+    PAIR_CREATORS.save(deps.storage, &pair_contract, &(creator.clone(), pair_id))?;
+    PAIR_CREATION_INFO.save(
+        deps.storage,
+        &pair_contract,
+        &(env.block.height, env.block.time.seconds()),
+    )?;
+    TOTAL_PAIRS.update::<_, StdError>(deps.storage, |total| Ok(total + 1))?;
+
+    Ok(pair_contract)
 }
 
 /// Removes an existing pair from the factory.
@@ -371,6 +854,7 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
 /// Only the owner can execute this.
 pub fn deregister(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     asset_infos: Vec<AssetInfo>,
 ) -> Result<Response, ContractError> {
@@ -378,17 +862,35 @@ pub fn deregister(
 
     let config = CONFIG.load(deps.storage)?;
 
-    if info.sender != config.owner {
-        return Err(ContractError::Unauthorized {});
-    }
+    assert_authorized(
+        deps.storage,
+        deps.querier,
+        &config,
+        &info.sender,
+        None,
+        None,
+    )?;
 
     let pair_addr = PAIRS.load(deps.storage, &pair_key(&asset_infos))?;
-    PAIRS.remove(deps.storage, &pair_key(&asset_infos));
+    PAIRS.remove(deps.storage, &pair_key(&asset_infos), env.block.height)?;
+
+    if let Some((creator, pair_id)) = PAIR_CREATORS.may_load(deps.storage, &pair_addr)? {
+        PAIRS_BY_CREATOR.remove(deps.storage, (&creator, pair_id));
+        PAIR_CREATORS.remove(deps.storage, &pair_addr);
+    }
+
+    TOTAL_PAIRS.update::<_, StdError>(deps.storage, |total| Ok(total.saturating_sub(1)))?;
+    set_tvl_contribution(deps.storage, &pair_addr, Uint128::zero())?;
+    PAIR_ADMINS.remove(deps.storage, &pair_addr);
+
+    let pair_info = query_pair_info(&deps.querier, &pair_addr)?;
+    LP_TO_PAIR.remove(deps.storage, &pair_info.liquidity_token);
+    for asset_info in &pair_info.asset_infos {
+        PAIRS_BY_ASSET.remove(deps.storage, (asset_info.to_string(), &pair_addr));
+    }
 
     let mut messages: Vec<CosmosMsg> = vec![];
     if let Some(generator) = config.generator_address {
-        let pair_info = query_pair_info(&deps.querier, &pair_addr)?;
-
         // sets the allocation point to zero for the lp_token
         messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: generator.to_string(),
@@ -405,36 +907,945 @@ pub fn deregister(
     ]))
 }
 
-/// Exposes all the queries available in the contract.
+/// Blacklists a creator and removes all pairs they created from [`PAIRS_BY_CREATOR`].
+/// The pairs themselves stay registered in the factory and remain fully functional.
 ///
-/// ## Queries
-/// * **QueryMsg::Config {}** Returns general contract parameters using a custom [`ConfigResponse`] structure.
+/// * **creator** is the address to blacklist.
 ///
-/// * **QueryMsg::Pair { asset_infos }** Returns a [`PairInfo`] object with information about a specific Astroport pair.
+/// ## Executor
+/// Only the owner can execute this.
+pub fn revoke_creator_pairs(
+    deps: DepsMut,
+    info: MessageInfo,
+    creator: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    assert_authorized(
+        deps.storage,
+        deps.querier,
+        &config,
+        &info.sender,
+        None,
+        None,
+    )?;
+
+    let creator = deps.api.addr_validate(&creator)?;
+
+    let pair_ids = PAIRS_BY_CREATOR
+        .prefix(&creator)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for pair_id in &pair_ids {
+        PAIRS_BY_CREATOR.remove(deps.storage, (&creator, *pair_id));
+    }
+
+    BLACKLISTED_CREATORS.save(deps.storage, &creator, &())?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "revoke_creator_pairs"),
+        attr("creator", creator),
+        attr("revoked_pairs", pair_ids.len().to_string()),
+    ]))
+}
+
+/// Grants or revokes an administrative role.
 ///
-/// * **QueryMsg::Pairs { start_after, limit }** Returns an array that contains items of type [`PairInfo`].
-/// This returns information about multiple Astroport pairs
+/// * **address** is the address which receives or loses the role.
 ///
-/// * **QueryMsg::FeeInfo { pair_type }** Returns the fee structure (total and maker fees) for a specific pair type.
+/// * **role** is the role to update.
 ///
-/// * **QueryMsg::BlacklistedPairTypes {}** Returns a vector that contains blacklisted pair types (pair types that cannot get ASTRO emissions).
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
-        QueryMsg::Pair { asset_infos } => to_json_binary(&query_pair(deps, asset_infos)?),
-        QueryMsg::Pairs { start_after, limit } => {
-            to_json_binary(&query_pairs(deps, start_after, limit)?)
-        }
-        QueryMsg::FeeInfo { pair_type } => to_json_binary(&query_fee_info(deps, pair_type)?),
-        QueryMsg::BlacklistedPairTypes {} => to_json_binary(&query_blacklisted_pair_types(deps)?),
-    }
+/// * **grant** defines whether the role is granted or revoked.
+///
+/// ## Executor
+/// Only the owner can execute this.
+pub fn update_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    role: Role,
+    grant: bool,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    assert_authorized(
+        deps.storage,
+        deps.querier,
+        &config,
+        &info.sender,
+        None,
+        None,
+    )?;
+
+    let address = deps.api.addr_validate(&address)?;
+
+    let action = if grant {
+        ROLES.save(deps.storage, (&address, role.as_str()), &())?;
+        "grant_role"
+    } else {
+        ROLES.remove(deps.storage, (&address, role.as_str()));
+        "revoke_role"
+    };
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", action),
+        attr("address", address),
+        attr("role", role.to_string()),
+    ]))
 }
 
-/// Returns a vector that contains blacklisted pair types
-pub fn query_blacklisted_pair_types(deps: Deps) -> StdResult<Vec<PairType>> {
-    PAIR_CONFIGS
-        .range(deps.storage, None, None, Order::Ascending)
+/// Pauses or unpauses a specific pair.
+///
+/// * **asset_infos** is a vector with assets of the pair.
+///
+/// * **proposal_id** is the passed multisig proposal approving this operation.
+///
+/// * **paused** defines whether the pair is paused or unpaused.
+///
+/// ## Executor
+/// Only the owner, a [`Role::PairAdmin`] or the admin of the pair can execute this.
+pub fn set_pair_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_infos: Vec<AssetInfo>,
+    proposal_id: Option<u64>,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    check_asset_infos(deps.api, &asset_infos)?;
+
+    let pair_addr = PAIRS.load(deps.storage, &pair_key(&asset_infos))?;
+
+    // The pair admin manages its own pair without a factory-wide role
+    if PAIR_ADMINS.may_load(deps.storage, &pair_addr)? != Some(info.sender.clone()) {
+        let config = CONFIG.load(deps.storage)?;
+        assert_authorized(
+            deps.storage,
+            deps.querier,
+            &config,
+            &info.sender,
+            Some(Role::PairAdmin),
+            proposal_id,
+        )?;
+    }
+
+    let action = if paused {
+        PAUSED_PAIRS.save(deps.storage, &pair_addr, &())?;
+        "pause_pair"
+    } else {
+        PAUSED_PAIRS.remove(deps.storage, &pair_addr);
+        "unpause_pair"
+    };
+
+    Ok(Response::new()
+        .add_submessage(set_pair_paused_msg(deps.storage, &pair_addr)?)
+        .add_attributes(vec![
+            attr("action", action),
+            attr("pair_contract_addr", pair_addr),
+        ]))
+}
+
+/// Pauses or unpauses all pairs at once.
+///
+/// * **proposal_id** is the passed multisig proposal approving this operation.
+///
+/// * **paused** defines whether the pairs are paused or unpaused.
+///
+/// ## Executor
+/// Only the owner or an [`Role::EmergencyAdmin`] can execute this.
+pub fn set_all_pairs_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    proposal_id: Option<u64>,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_authorized(
+        deps.storage,
+        deps.querier,
+        &config,
+        &info.sender,
+        Some(Role::EmergencyAdmin),
+        proposal_id,
+    )?;
+
+    ALL_PAIRS_PAUSED.save(deps.storage, &paused)?;
+
+    let storage = deps.as_ref().storage;
+    let messages = PAIRS
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| set_pair_paused_msg(storage, &item?.1))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let action = if paused {
+        "pause_all_pairs"
+    } else {
+        "unpause_all_pairs"
+    };
+
+    Ok(Response::new()
+        .add_submessages(messages)
+        .add_attribute("action", action))
+}
+
+/// Builds the sub-message pushing whether the pair is paused either individually or globally to
+/// the pair. Pairs which don't support the message keep querying [`QueryMsg::IsPairPaused`].
+fn set_pair_paused_msg(storage: &dyn Storage, pair_addr: &Addr) -> StdResult<SubMsg> {
+    Ok(SubMsg::reply_on_error(
+        wasm_execute(
+            pair_addr,
+            &XykPairExecuteMsg::SetPaused {
+                paused: is_pair_paused(storage, pair_addr)?,
+            },
+            vec![],
+        )?,
+        SET_PAIR_PAUSED_REPLY_ID,
+    ))
+}
+
+/// Blacklists a registered pair. The router refuses to swap through blacklisted pairs.
+///
+/// * **pair** is the pair contract address.
+///
+/// * **reason** explains why the pair is blacklisted.
+///
+/// * **proposal_id** is the passed multisig proposal approving this operation.
+///
+/// ## Executor
+/// Only the owner or a [`Role::EmergencyAdmin`] can execute this.
+pub fn blacklist_pair(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pair: String,
+    reason: String,
+    proposal_id: Option<u64>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_authorized(
+        deps.storage,
+        deps.querier,
+        &config,
+        &info.sender,
+        Some(Role::EmergencyAdmin),
+        proposal_id,
+    )?;
+
+    let pair = validate_registered_pair(deps.as_ref(), &pair)?;
+    BLACKLISTED_PAIRS.save(
+        deps.storage,
+        &pair,
+        &BlacklistReason {
+            reason: reason.clone(),
+            blacklisted_at: env.block.time.seconds(),
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "blacklist_pair"),
+        attr("pair_contract_addr", pair),
+        attr("reason", reason),
+    ]))
+}
+
+/// Sets or removes the CW3 multisig contract which must approve sensitive operations.
+///
+/// * **multisig_module** is the multisig contract address. [`None`] removes the multisig module.
+///
+/// ## Executor
+/// Only the owner can execute this. Once the multisig module is set, only the multisig contract can execute this.
+pub fn set_multisig_module(
+    deps: DepsMut,
+    info: MessageInfo,
+    multisig_module: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    assert_authorized(
+        deps.storage,
+        deps.querier,
+        &config,
+        &info.sender,
+        None,
+        None,
+    )?;
+
+    config.multisig_module = addr_opt_validate(deps.api, &multisig_module)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_multisig_module"),
+        attr("multisig_module", multisig_module.unwrap_or_default()),
+    ]))
+}
+
+/// Approves or revokes a pair contract code ID. Pairs can only be created for pair types whose
+/// code ID is approved.
+///
+/// * **code_id** is the pair contract code ID.
+///
+/// * **approved** defines whether the code ID is approved or revoked.
+///
+/// ## Executor
+/// Only the owner can execute this.
+pub fn update_code_id_approval(
+    deps: DepsMut,
+    info: MessageInfo,
+    code_id: u64,
+    approved: bool,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    assert_authorized(
+        deps.storage,
+        deps.querier,
+        &config,
+        &info.sender,
+        None,
+        None,
+    )?;
+
+    APPROVED_CODE_IDS.save(deps.storage, code_id, &approved)?;
+
+    let action = if approved {
+        "approve_code_id"
+    } else {
+        "revoke_code_id"
+    };
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", action),
+        attr("code_id", code_id.to_string()),
+    ]))
+}
+
+/// Sets the USD value locked in a registered pair. The sum of all reported values is returned by [`QueryMsg::TotalTvl`].
+///
+/// * **pair** is the pair contract address.
+///
+/// * **tvl_usd** is the USD value locked in the pair.
+///
+/// ## Executor
+/// Only the owner or a [`Role::TvlOracle`] can execute this.
+pub fn update_tvl_contribution(
+    deps: DepsMut,
+    info: MessageInfo,
+    pair: String,
+    tvl_usd: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if !has_role(deps.storage, &config, &info.sender, &Role::TvlOracle)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let pair = validate_registered_pair(deps.as_ref(), &pair)?;
+    let total_tvl = set_tvl_contribution(deps.storage, &pair, tvl_usd)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_tvl_contribution"),
+        attr("pair_contract_addr", pair),
+        attr("tvl_usd", tvl_usd),
+        attr("total_tvl", total_tvl),
+    ]))
+}
+
+/// Replaces the pair volume rankings returned by [`QueryMsg::PairsByVolume`]. Pairs are ranked
+/// by their volume in descending order, pairs with equal volumes keep the order they were passed in.
+///
+/// * **rankings** the registered pairs with their 24h trading volume.
+///
+/// ## Executor
+/// Only the owner or a [`Role::VolumeKeeper`] can execute this.
+pub fn update_pair_volume_rankings(
+    deps: DepsMut,
+    info: MessageInfo,
+    rankings: Vec<(String, Uint128)>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if !has_role(deps.storage, &config, &info.sender, &Role::VolumeKeeper)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut pairs = HashSet::new();
+    let mut rankings = rankings
+        .into_iter()
+        .map(|(pair, volume)| {
+            let pair = validate_registered_pair(deps.as_ref(), &pair)?;
+            if !pairs.insert(pair.clone()) {
+                return Err(ContractError::DuplicatePairInRankings(pair.to_string()));
+            }
+            Ok((pair, volume))
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+    rankings.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+    let stale_ranks = PAIR_VOLUME_REGISTRY
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for rank in stale_ranks {
+        PAIR_VOLUME_REGISTRY.remove(deps.storage, rank);
+    }
+    for (rank, ranking) in (1..).zip(&rankings) {
+        PAIR_VOLUME_REGISTRY.save(deps.storage, rank, ranking)?;
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_pair_volume_rankings"),
+        attr("ranked_pairs", rankings.len().to_string()),
+    ]))
+}
+
+/// Sets the gas used by a single swap in a pair of the specified type.
+///
+/// * **pair_type** is the pair type.
+///
+/// * **gas_per_swap** is the gas used by a single swap.
+///
+/// ## Executor
+/// Only the owner can execute this.
+pub fn set_gas_benchmark(
+    deps: DepsMut,
+    info: MessageInfo,
+    pair_type: PairType,
+    gas_per_swap: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    assert_authorized(
+        deps.storage,
+        deps.querier,
+        &config,
+        &info.sender,
+        None,
+        None,
+    )?;
+
+    GAS_BENCHMARKS.save(deps.storage, pair_type.to_string(), &gas_per_swap)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_gas_benchmark"),
+        attr("pair_type", pair_type.to_string()),
+        attr("gas_per_swap", gas_per_swap.to_string()),
+    ]))
+}
+
+/// Sets the parameters of the fee tier proposals.
+///
+/// * **xastro_token** is the xASTRO token contract whose historical balances are used as the voting power.
+///
+/// * **min_stake** is the minimum xASTRO balance required to create a proposal.
+///
+/// * **voting_period** is the voting period in seconds.
+///
+/// * **quorum** is the minimum share of the xASTRO supply which must vote for a proposal to pass.
+///
+/// ## Executor
+/// Only the owner can execute this.
+pub fn update_fee_tier_governance(
+    deps: DepsMut,
+    info: MessageInfo,
+    xastro_token: String,
+    min_stake: Uint128,
+    voting_period: u64,
+    quorum: Decimal,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    assert_authorized(
+        deps.storage,
+        deps.querier,
+        &config,
+        &info.sender,
+        None,
+        None,
+    )?;
+
+    if quorum > Decimal::one() {
+        return Err(StdError::generic_err("Quorum must not exceed 1").into());
+    }
+
+    FEE_TIER_GOVERNANCE.save(
+        deps.storage,
+        &FeeTierGovernance {
+            xastro_token: deps.api.addr_validate(&xastro_token)?,
+            min_stake,
+            voting_period,
+            quorum,
+        },
+    )?;
+
+    Ok(Response::new().add_attribute("action", "update_fee_tier_governance"))
+}
+
+/// Returns the xASTRO balance of `address` at the beginning of `block`.
+fn query_voting_power(
+    deps: Deps,
+    governance: &FeeTierGovernance,
+    address: &Addr,
+    block: u64,
+) -> StdResult<Uint128> {
+    let res: BalanceResponse = deps.querier.query_wasm_smart(
+        &governance.xastro_token,
+        &XastroQueryMsg::BalanceAt {
+            address: address.to_string(),
+            block,
+        },
+    )?;
+
+    Ok(res.balance)
+}
+
+/// Creates a proposal to change the total fee of an XYK pair. The voting power of every voter
+/// is their xASTRO balance at the block the proposal is created in.
+///
+/// * **pair** is the pair contract address.
+///
+/// * **new_total_fee_bps** is the new total fee (in bps) charged on a swap in the pair.
+///
+/// ## Executor
+/// Only addresses holding at least [`FeeTierGovernance::min_stake`] xASTRO can execute this.
+pub fn propose_fee_tier_change(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pair: String,
+    new_total_fee_bps: u16,
+) -> Result<Response, ContractError> {
+    let governance = FEE_TIER_GOVERNANCE
+        .may_load(deps.storage)?
+        .ok_or(ContractError::FeeTierGovernanceNotSet {})?;
+
+    let pair = validate_registered_pair(deps.as_ref(), &pair)?;
+    let pair_type = query_pair_info(&deps.querier, &pair)?.pair_type;
+    if pair_type != (PairType::Xyk {}) {
+        return Err(ContractError::FeeTierNotSupported {});
+    }
+    let pair_config = PAIR_CONFIGS.load(deps.storage, pair_type.to_string())?;
+    validate_fee_config(&PairConfig {
+        total_fee_bps: new_total_fee_bps,
+        ..pair_config
+    })?;
+
+    let start_block = env.block.height;
+    let stake = query_voting_power(deps.as_ref(), &governance, &info.sender, start_block)?;
+    if stake < governance.min_stake {
+        return Err(ContractError::InsufficientStake {
+            required: governance.min_stake,
+            available: stake,
+        });
+    }
+    let total_voting_power: Uint128 = deps.querier.query_wasm_smart(
+        &governance.xastro_token,
+        &XastroQueryMsg::TotalSupplyAt { block: start_block },
+    )?;
+
+    let proposal_id = FEE_TIER_PROPOSAL_COUNT
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    FEE_TIER_PROPOSAL_COUNT.save(deps.storage, &(proposal_id + 1))?;
+    FEE_TIER_PROPOSALS.save(
+        deps.storage,
+        proposal_id,
+        &FeeTierProposal {
+            pair: pair.clone(),
+            new_total_fee_bps,
+            proposer: info.sender,
+            start_block,
+            end_time: env.block.time.seconds() + governance.voting_period,
+            total_voting_power,
+            yes_votes: Uint128::zero(),
+            no_votes: Uint128::zero(),
+            executed: false,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "propose_fee_tier_change"),
+        attr("proposal_id", proposal_id.to_string()),
+        attr("pair", pair),
+        attr("new_total_fee_bps", new_total_fee_bps.to_string()),
+    ]))
+}
+
+/// Votes on a fee tier proposal with the xASTRO balance the voter held when the proposal was created.
+///
+/// * **proposal_id** is the proposal identifier.
+///
+/// * **vote** is whether the voter supports the proposal.
+///
+/// ## Executor
+/// Anyone who held xASTRO when the proposal was created can vote once during the voting period.
+pub fn vote_on_fee_tier_proposal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+    vote: bool,
+) -> Result<Response, ContractError> {
+    let governance = FEE_TIER_GOVERNANCE
+        .may_load(deps.storage)?
+        .ok_or(ContractError::FeeTierGovernanceNotSet {})?;
+    let mut proposal = FEE_TIER_PROPOSALS.load(deps.storage, proposal_id)?;
+
+    if env.block.time.seconds() >= proposal.end_time {
+        return Err(ContractError::VotingPeriodEnded(proposal_id));
+    }
+    if FEE_TIER_VOTES.has(deps.storage, (proposal_id, &info.sender)) {
+        return Err(ContractError::AlreadyVoted(proposal_id));
+    }
+
+    let voting_power = query_voting_power(
+        deps.as_ref(),
+        &governance,
+        &info.sender,
+        proposal.start_block,
+    )?;
+    if voting_power.is_zero() {
+        return Err(ContractError::NoVotingPower(proposal_id));
+    }
+
+    if vote {
+        proposal.yes_votes += voting_power;
+    } else {
+        proposal.no_votes += voting_power;
+    }
+    FEE_TIER_VOTES.save(deps.storage, (proposal_id, &info.sender), &vote)?;
+    FEE_TIER_PROPOSALS.save(deps.storage, proposal_id, &proposal)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "vote_on_fee_tier_proposal"),
+        attr("proposal_id", proposal_id.to_string()),
+        attr("voter", info.sender),
+        attr("vote", vote.to_string()),
+        attr("voting_power", voting_power),
+    ]))
+}
+
+/// Sets the total fee of the pair to the proposed one. The votes must reach
+/// [`FeeTierGovernance::quorum`] of the xASTRO supply and most of them must support the proposal.
+///
+/// * **proposal_id** is the proposal identifier.
+///
+/// ## Executor
+/// Anyone can execute this once the voting period is over.
+pub fn execute_fee_tier_proposal(
+    deps: DepsMut,
+    env: Env,
+    proposal_id: u64,
+) -> Result<Response, ContractError> {
+    let governance = FEE_TIER_GOVERNANCE
+        .may_load(deps.storage)?
+        .ok_or(ContractError::FeeTierGovernanceNotSet {})?;
+    let mut proposal = FEE_TIER_PROPOSALS.load(deps.storage, proposal_id)?;
+
+    if env.block.time.seconds() < proposal.end_time {
+        return Err(ContractError::VotingPeriodActive(proposal_id));
+    }
+    if proposal.executed {
+        return Err(ContractError::FeeTierProposalExecuted(proposal_id));
+    }
+
+    let votes = proposal.yes_votes + proposal.no_votes;
+    if votes.is_zero()
+        || votes < proposal.total_voting_power * governance.quorum
+        || proposal.yes_votes <= proposal.no_votes
+    {
+        return Err(ContractError::FeeTierProposalRejected(proposal_id));
+    }
+
+    PAIR_FEE_TIERS.save(deps.storage, &proposal.pair, &proposal.new_total_fee_bps)?;
+    proposal.executed = true;
+    FEE_TIER_PROPOSALS.save(deps.storage, proposal_id, &proposal)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "execute_fee_tier_proposal"),
+        attr("proposal_id", proposal_id.to_string()),
+        attr("pair", proposal.pair),
+        attr("new_total_fee_bps", proposal.new_total_fee_bps.to_string()),
+    ]))
+}
+
+/// Sets the admin of a specific pair. The pair admin can pause and unpause the pair and update its
+/// parameters via [`ExecuteMsg::UpdatePairParams`].
+///
+/// * **pair** is the pair contract address.
+///
+/// * **admin** is the new pair admin.
+///
+/// ## Executor
+/// Only the owner or the current pair admin can execute this.
+pub fn set_pair_admin(
+    deps: DepsMut,
+    info: MessageInfo,
+    pair: String,
+    admin: String,
+) -> Result<Response, ContractError> {
+    let pair = validate_registered_pair(deps.as_ref(), &pair)?;
+
+    if PAIR_ADMINS.may_load(deps.storage, &pair)? != Some(info.sender.clone()) {
+        let config = CONFIG.load(deps.storage)?;
+        assert_authorized(
+            deps.storage,
+            deps.querier,
+            &config,
+            &info.sender,
+            None,
+            None,
+        )?;
+    }
+
+    let admin = deps.api.addr_validate(&admin)?;
+    PAIR_ADMINS.save(deps.storage, &pair, &admin)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_pair_admin"),
+        attr("pair_contract_addr", pair),
+        attr("admin", admin),
+    ]))
+}
+
+/// Forwards a config update to a pair. The pair must accept config updates from the factory and
+/// only applies the pool tuning ones, e.g. the XYK pair accepts max spread and LP cooldown updates.
+///
+/// * **pair** is the pair contract address.
+///
+/// * **params** are the parameters passed to the pair `UpdateConfig` message.
+///
+/// ## Executor
+/// Only the pair admin can execute this.
+pub fn update_pair_params(
+    deps: DepsMut,
+    info: MessageInfo,
+    pair: String,
+    params: Binary,
+) -> Result<Response, ContractError> {
+    let pair = validate_registered_pair(deps.as_ref(), &pair)?;
+    if PAIR_ADMINS.may_load(deps.storage, &pair)? != Some(info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let msg = wasm_execute(&pair, &PairExecuteMsg::UpdateConfig { params }, vec![])?;
+
+    Ok(Response::new().add_message(msg).add_attributes(vec![
+        attr("action", "update_pair_params"),
+        attr("pair_contract_addr", pair),
+    ]))
+}
+
+/// Sets the contract notified after actions in a pair.
+///
+/// * **pair** is the pair contract address.
+///
+/// * **custom_hook** is the hook configuration. [`None`] removes the hook.
+///
+/// ## Executor
+/// Only the owner or the pair admin can execute this.
+pub fn set_pair_custom_hook(
+    deps: DepsMut,
+    info: MessageInfo,
+    pair: String,
+    custom_hook: Option<CustomHookConfig>,
+) -> Result<Response, ContractError> {
+    let pair = validate_registered_pair(deps.as_ref(), &pair)?;
+
+    if PAIR_ADMINS.may_load(deps.storage, &pair)? != Some(info.sender.clone()) {
+        let config = CONFIG.load(deps.storage)?;
+        assert_authorized(
+            deps.storage,
+            deps.querier,
+            &config,
+            &info.sender,
+            None,
+            None,
+        )?;
+    }
+
+    let msg = wasm_execute(
+        &pair,
+        &XykPairExecuteMsg::SetCustomHook {
+            config: custom_hook,
+        },
+        vec![],
+    )?;
+
+    Ok(Response::new().add_message(msg).add_attributes(vec![
+        attr("action", "set_pair_custom_hook"),
+        attr("pair_contract_addr", pair),
+    ]))
+}
+
+/// Returns the address of a pair registered in the factory.
+///
+/// * **pair** is the pair contract address.
+fn validate_registered_pair(deps: Deps, pair: &str) -> Result<Addr, ContractError> {
+    let pair = deps.api.addr_validate(pair)?;
+    let pair_info = query_pair_info(&deps.querier, &pair)?;
+    if PAIRS.may_load(deps.storage, &pair_key(&pair_info.asset_infos))? != Some(pair.clone()) {
+        return Err(ContractError::PairNotRegistered(pair.to_string()));
+    }
+
+    Ok(pair)
+}
+
+/// Exposes all the queries available in the contract.
+///
+/// ## Queries
+/// * **QueryMsg::Config {}** Returns general contract parameters using a custom [`ConfigResponse`] structure.
+///
+/// * **QueryMsg::Pair { asset_infos }** Returns a [`PairInfo`] object with information about a specific Astroport pair.
+///
+/// * **QueryMsg::PairAddress { asset_infos }** Returns the address of the pair registered for the
+/// assets using a [`PairAddressResponse`] object.
+///
+/// * **QueryMsg::PairAtHeight { asset_infos, height }** Returns the [`PairInfo`] of the pair which
+/// was registered for the assets at the end of the block at `height`.
+///
+/// * **QueryMsg::Pairs { start_after, limit }** Returns an array that contains items of type [`PairInfo`].
+/// This returns information about multiple Astroport pairs
+///
+/// * **QueryMsg::FeeInfo { pair_type }** Returns the fee structure (total and maker fees) for a specific pair type.
+///
+/// * **QueryMsg::BlacklistedPairTypes {}** Returns a vector that contains blacklisted pair types (pair types that cannot get ASTRO emissions).
+///
+/// * **QueryMsg::PairsByCreator { creator, start_after, limit }** Returns an array that contains items of type [`PairInfo`].
+/// This returns information about pairs created by the specified address
+///
+/// * **QueryMsg::HasRole { address, role }** Returns whether the address holds the specified role.
+///
+/// * **QueryMsg::IsPairPaused { pair_addr }** Returns whether the pair is paused.
+///
+/// * **QueryMsg::BatchStatus { batch_id }** Returns which pairs of a batch were created and which failed.
+///
+/// * **QueryMsg::IsCodeIdApproved { code_id }** Returns whether pairs can be instantiated from the code ID.
+///
+/// * **QueryMsg::PairCount {}** Returns the number of pairs registered in the factory.
+///
+/// * **QueryMsg::TotalTvl {}** Returns the sum of the USD values reported for all pairs.
+///
+/// * **QueryMsg::PairAdmin { pair }** Returns the admin of the specified pair if it is set.
+///
+/// * **QueryMsg::AllPairTypes { start_after, limit }** Returns the registered pair type configurations.
+///
+/// * **QueryMsg::IsPairTypeEnabled { pair_type }** Returns whether new pairs of the specified type can be created.
+///
+/// * **QueryMsg::PairsByVolume { start_after, limit }** Returns the ranked pairs ordered by their 24h volume
+/// using a vector of [`astroport::factory::PairVolumeResponse`] objects.
+///
+/// * **QueryMsg::GasBenchmark { pair_type }** Returns the gas used by a swap in a pair of the specified type.
+///
+/// * **QueryMsg::PairFeeInfo { pair_addr, pair_type }** Returns the fee structure of a specific pair
+/// including its fee tier.
+///
+/// * **QueryMsg::FeeTierGovernance {}** Returns the parameters of the fee tier proposals.
+///
+/// * **QueryMsg::FeeTierProposal { proposal_id }** Returns a fee tier proposal.
+///
+/// * **QueryMsg::PairByLpToken { lp_token }** Returns a [`PairInfo`] object with information about
+/// the pair which minted the LP token.
+///
+/// * **QueryMsg::PairInfoBatch { lp_tokens }** Returns information about the pairs which minted
+/// the LP tokens, or `None` for unknown LP tokens.
+///
+/// * **QueryMsg::ContractInfo {}** Returns the cw2 contract name and version.
+///
+/// * **QueryMsg::IsPairBlacklisted { pair }** Returns whether the pair is blacklisted.
+///
+/// * **QueryMsg::BlacklistedPairs { start_after, limit }** Returns the blacklisted pairs with the
+/// reasons they were blacklisted for.
+///
+/// * **QueryMsg::NextHopCandidates { from_asset, limit }** Returns the assets which can be received
+/// for `from_asset` in one swap ordered by their pair reserves.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::Pair { asset_infos } => to_json_binary(&query_pair(deps, asset_infos)?),
+        QueryMsg::PairAddress { asset_infos } => {
+            to_json_binary(&query_pair_address(deps, asset_infos)?)
+        }
+        QueryMsg::PairAtHeight {
+            asset_infos,
+            height,
+        } => to_json_binary(&query_pair_at_height(deps, asset_infos, height)?),
+        QueryMsg::Pairs { start_after, limit } => {
+            to_json_binary(&query_pairs(deps, start_after, limit)?)
+        }
+        QueryMsg::FeeInfo { pair_type } => to_json_binary(&query_fee_info(deps, pair_type)?),
+        QueryMsg::BlacklistedPairTypes {} => to_json_binary(&query_blacklisted_pair_types(deps)?),
+        QueryMsg::PairsByCreator {
+            creator,
+            start_after,
+            limit,
+        } => to_json_binary(&query_pairs_by_creator(deps, creator, start_after, limit)?),
+        QueryMsg::HasRole { address, role } => {
+            let config = CONFIG.load(deps.storage)?;
+            let address = deps.api.addr_validate(&address)?;
+            to_json_binary(&has_role(deps.storage, &config, &address, &role)?)
+        }
+        QueryMsg::IsPairPaused { pair_addr } => {
+            to_json_binary(&query_pair_paused(deps, pair_addr)?)
+        }
+        QueryMsg::BatchStatus { batch_id } => to_json_binary(&query_batch_status(deps, batch_id)?),
+        QueryMsg::IsCodeIdApproved { code_id } => to_json_binary(
+            &APPROVED_CODE_IDS
+                .may_load(deps.storage, code_id)?
+                .unwrap_or_default(),
+        ),
+        QueryMsg::PairCount {} => {
+            to_json_binary(&TOTAL_PAIRS.may_load(deps.storage)?.unwrap_or_default())
+        }
+        QueryMsg::TotalTvl {} => {
+            to_json_binary(&TOTAL_TVL.may_load(deps.storage)?.unwrap_or_default())
+        }
+        QueryMsg::PairAdmin { pair } => {
+            let pair = deps.api.addr_validate(&pair)?;
+            to_json_binary(&PAIR_ADMINS.may_load(deps.storage, &pair)?)
+        }
+        QueryMsg::AllPairTypes { start_after, limit } => {
+            to_json_binary(&read_pair_configs(deps, start_after, limit)?)
+        }
+        QueryMsg::IsPairTypeEnabled { pair_type } => {
+            to_json_binary(&query_pair_type_enabled(deps, pair_type)?)
+        }
+        QueryMsg::PairsByVolume { start_after, limit } => {
+            to_json_binary(&read_pairs_by_volume(deps, start_after, limit)?)
+        }
+        QueryMsg::GasBenchmark { pair_type } => {
+            to_json_binary(&GAS_BENCHMARKS.may_load(deps.storage, pair_type.to_string())?)
+        }
+        QueryMsg::PairFeeInfo {
+            pair_addr,
+            pair_type,
+        } => to_json_binary(&query_pair_fee_info(deps, pair_addr, pair_type)?),
+        QueryMsg::FeeTierGovernance {} => {
+            to_json_binary(&FEE_TIER_GOVERNANCE.may_load(deps.storage)?)
+        }
+        QueryMsg::FeeTierProposal { proposal_id } => {
+            to_json_binary(&FEE_TIER_PROPOSALS.load(deps.storage, proposal_id)?)
+        }
+        QueryMsg::PairByLpToken { lp_token } => {
+            to_json_binary(&query_pair_by_lp_token(deps, lp_token)?)
+        }
+        QueryMsg::PairInfoBatch { lp_tokens } => {
+            to_json_binary(&query_pair_info_batch(deps, lp_tokens)?)
+        }
+        QueryMsg::ContractInfo {} => to_json_binary(&get_contract_version(deps.storage)?),
+        QueryMsg::IsPairBlacklisted { pair } => {
+            let pair = deps.api.addr_validate(&pair)?;
+            to_json_binary(&BLACKLISTED_PAIRS.has(deps.storage, &pair))
+        }
+        QueryMsg::BlacklistedPairs { start_after, limit } => {
+            to_json_binary(&read_blacklisted_pairs(deps, start_after, limit)?)
+        }
+        QueryMsg::NextHopCandidates { from_asset, limit } => {
+            to_json_binary(&query_next_hop_candidates(deps, from_asset, limit)?)
+        }
+    }
+}
+
+/// Returns a vector that contains blacklisted pair types
+pub fn query_blacklisted_pair_types(deps: Deps) -> StdResult<Vec<PairType>> {
+    PAIR_CONFIGS
+        .range(deps.storage, None, None, Order::Ascending)
         .filter_map(|result| match result {
             Ok(v) => {
                 if v.1.is_disabled || v.1.is_generator_disabled {
@@ -462,6 +1873,7 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         generator_address: config.generator_address,
         whitelist_code_id: config.whitelist_code_id,
         coin_registry_address: config.coin_registry_address,
+        multisig_module: config.multisig_module,
     };
 
     Ok(resp)
@@ -471,7 +1883,104 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
 /// * **asset_infos** is a vector with assets traded in the pair.
 pub fn query_pair(deps: Deps, asset_infos: Vec<AssetInfo>) -> StdResult<PairInfo> {
     let pair_addr = PAIRS.load(deps.storage, &pair_key(&asset_infos))?;
-    query_pair_info(&deps.querier, pair_addr)
+    query_tracked_pair_info(deps, &pair_addr)
+}
+
+/// Returns the address of the pair registered for `asset_infos`. Unlike [`query_pair`] it only
+/// reads the factory storage.
+pub fn query_pair_address(
+    deps: Deps,
+    asset_infos: Vec<AssetInfo>,
+) -> StdResult<PairAddressResponse> {
+    let pair_addr = PAIRS.load(deps.storage, &pair_key(&asset_infos))?;
+
+    Ok(PairAddressResponse {
+        address: pair_addr.into_string(),
+    })
+}
+
+/// Returns the data of the pair which was registered for `asset_infos` at the end of the block at
+/// `height` if there was one.
+/// * **height** is the block height.
+pub fn query_pair_at_height(
+    deps: Deps,
+    asset_infos: Vec<AssetInfo>,
+    height: u64,
+) -> StdResult<Option<PairInfo>> {
+    // Snapshots return the values at the beginning of a block
+    PAIRS
+        .may_load_at_height(
+            deps.storage,
+            &pair_key(&asset_infos),
+            height.saturating_add(1),
+        )?
+        .map(|pair_addr| query_tracked_pair_info(deps, &pair_addr))
+        .transpose()
+}
+
+/// Returns the assets which can be received for `from_asset` in a single swap ordered by the
+/// reserves of the received assets from the deepest one.
+/// * **from_asset** is the asset to swap.
+///
+/// * **limit** sets the number of candidates to be retrieved.
+pub fn query_next_hop_candidates(
+    deps: Deps,
+    from_asset: AssetInfo,
+    limit: Option<u32>,
+) -> StdResult<NextHopResponse> {
+    let mut candidates = vec![];
+    for pair in read_pairs_by_asset(deps, &from_asset)? {
+        let pool = query_pool(&deps.querier, &pair)?;
+        candidates.extend(
+            pool.assets
+                .into_iter()
+                .filter(|asset| !asset.info.equal(&from_asset))
+                .map(|asset| HopCandidate {
+                    pair: pair.clone(),
+                    to_asset: asset.info,
+                    reserve_depth: asset.amount,
+                }),
+        );
+    }
+
+    candidates.sort_by(|a, b| b.reserve_depth.cmp(&a.reserve_depth));
+    candidates.truncate(pagination_limit(limit));
+
+    Ok(NextHopResponse { candidates })
+}
+
+/// Returns the data of the pair which minted `lp_token`.
+/// * **lp_token** is the LP token contract address.
+pub fn query_pair_by_lp_token(deps: Deps, lp_token: String) -> StdResult<PairInfo> {
+    let lp_token = deps.api.addr_validate(&lp_token)?;
+    let pair_addr = LP_TO_PAIR
+        .may_load(deps.storage, &lp_token)?
+        .ok_or_else(|| StdError::generic_err(format!("No pair found for LP token {lp_token}")))?;
+    query_tracked_pair_info(deps, &pair_addr)
+}
+
+/// Returns the data of the pairs which minted `lp_tokens` in the same order.
+/// * **lp_tokens** are the LP token contract addresses. Unknown LP tokens return `None`.
+pub fn query_pair_info_batch(
+    deps: Deps,
+    lp_tokens: Vec<String>,
+) -> StdResult<Vec<Option<PairInfo>>> {
+    if lp_tokens.len() > MAX_PAIR_INFO_BATCH_SIZE {
+        return Err(StdError::generic_err(
+            ContractError::BatchTooLarge(MAX_PAIR_INFO_BATCH_SIZE).to_string(),
+        ));
+    }
+
+    lp_tokens
+        .iter()
+        .map(|lp_token| {
+            let lp_token = deps.api.addr_validate(lp_token)?;
+            LP_TO_PAIR
+                .may_load(deps.storage, &lp_token)?
+                .map(|pair_addr| query_tracked_pair_info(deps, &pair_addr))
+                .transpose()
+        })
+        .collect()
 }
 
 /// Returns a vector with pair data that contains items of type [`PairInfo`]. Querying starts at `start_after` and returns `limit` pairs.
@@ -486,12 +1995,83 @@ pub fn query_pairs(
 ) -> StdResult<PairsResponse> {
     let pairs = read_pairs(deps, start_after, limit)?
         .iter()
-        .map(|pair_addr| query_pair_info(&deps.querier, pair_addr))
+        .map(|pair_addr| query_tracked_pair_info(deps, pair_addr))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(PairsResponse { pairs })
+}
+
+/// Returns a vector with data about pairs created by `creator`. Querying starts at `start_after` and returns `limit` pairs.
+/// * **creator** is the address which created the pairs.
+///
+/// * **start_after** is the pair identifier from which we start a query.
+///
+/// * **limit** sets the number of pairs to be retrieved.
+pub fn query_pairs_by_creator(
+    deps: Deps,
+    creator: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<PairsResponse> {
+    let creator = deps.api.addr_validate(&creator)?;
+    let pairs = read_pairs_by_creator(deps, &creator, start_after, limit)?
+        .iter()
+        .map(|pair_addr| query_tracked_pair_info(deps, pair_addr))
         .collect::<StdResult<Vec<_>>>()?;
 
     Ok(PairsResponse { pairs })
 }
 
+/// Returns whether the pair type is registered, not disabled and its code ID is approved.
+pub fn query_pair_type_enabled(deps: Deps, pair_type: PairType) -> StdResult<bool> {
+    if let Some(pair_config) = PAIR_CONFIGS.may_load(deps.storage, pair_type.to_string())? {
+        Ok(!pair_config.is_disabled
+            && APPROVED_CODE_IDS
+                .may_load(deps.storage, pair_config.code_id)?
+                .unwrap_or_default())
+    } else {
+        Ok(false)
+    }
+}
+
+/// Returns whether the pair is paused either individually or globally.
+pub fn query_pair_paused(deps: Deps, pair_addr: String) -> StdResult<bool> {
+    let pair_addr = deps.api.addr_validate(&pair_addr)?;
+
+    is_pair_paused(deps.storage, &pair_addr)
+}
+
+/// Returns whether the pair is paused either individually or globally.
+fn is_pair_paused(storage: &dyn Storage, pair_addr: &Addr) -> StdResult<bool> {
+    Ok(ALL_PAIRS_PAUSED.may_load(storage)?.unwrap_or_default()
+        || PAUSED_PAIRS.has(storage, pair_addr))
+}
+
+/// Returns the pairs which were created and which failed within the batch `batch_id`.
+pub fn query_batch_status(deps: Deps, batch_id: u64) -> StdResult<BatchStatusResponse> {
+    Ok(BatchStatusResponse {
+        succeeded: BATCH_STATE.load(deps.storage, batch_id)?,
+        failed: BATCH_FAILURES
+            .may_load(deps.storage, batch_id)?
+            .unwrap_or_default(),
+    })
+}
+
+/// Returns a pair's data extended with the pair creator and creation block tracked by the factory.
+fn query_tracked_pair_info(deps: Deps, pair_addr: &Addr) -> StdResult<PairInfo> {
+    let mut pair_info = query_pair_info(&deps.querier, pair_addr)?;
+    pair_info.creator = PAIR_CREATORS
+        .may_load(deps.storage, pair_addr)?
+        .map(|(creator, _)| creator);
+    let (creation_height, creation_time) = PAIR_CREATION_INFO
+        .may_load(deps.storage, pair_addr)?
+        .unwrap_or_default();
+    pair_info.creation_height = creation_height;
+    pair_info.creation_time = creation_time;
+
+    Ok(pair_info)
+}
+
 /// Returns the fee setup for a specific pair type using a [`FeeInfoResponse`] struct.
 /// * **pair_type** is a struct that represents the fee information (total and maker fees) for a specific pair type.
 pub fn query_fee_info(deps: Deps, pair_type: PairType) -> StdResult<FeeInfoResponse> {
@@ -505,6 +2085,26 @@ pub fn query_fee_info(deps: Deps, pair_type: PairType) -> StdResult<FeeInfoRespo
     })
 }
 
+/// Returns the fee setup for a specific pair using a [`FeeInfoResponse`] struct. The total fee
+/// set by an executed fee tier proposal overrides the total fee of the pair type.
+///
+/// * **pair_addr** is the pair contract address.
+///
+/// * **pair_type** is the type of the pair.
+pub fn query_pair_fee_info(
+    deps: Deps,
+    pair_addr: String,
+    pair_type: PairType,
+) -> StdResult<FeeInfoResponse> {
+    let pair_addr = deps.api.addr_validate(&pair_addr)?;
+    let mut fee_info = query_fee_info(deps, pair_type)?;
+    if let Some(total_fee_bps) = PAIR_FEE_TIERS.may_load(deps.storage, &pair_addr)? {
+        fee_info.total_fee_bps = total_fee_bps;
+    }
+
+    Ok(fee_info)
+}
+
 /// Manages the contract migration.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
@@ -517,6 +2117,9 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, Contra
             // atlantic-2: 1.3.1
             "1.3.1" | "1.5.1" | "1.6.0" => {
                 migrate_pair_configs(deps.storage)?;
+                approve_pair_config_code_ids(deps.storage)?;
+                init_total_pairs(deps.storage)?;
+                init_pair_creation_info(deps.storage)?;
             }
             _ => return Err(ContractError::MigrationError {}),
         },
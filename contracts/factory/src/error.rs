@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint128};
 use thiserror::Error;
 
 /// This enum describes factory contract errors
@@ -22,6 +22,9 @@ pub enum ContractError {
     #[error("Fee bps in pair config must be smaller than or equal to 10,000")]
     PairConfigInvalidFeeBps {},
 
+    #[error("Invalid fee config: {0}")]
+    InvalidFeeConfig(String),
+
     #[error("Pair config not found")]
     PairConfigNotFound {},
 
@@ -36,4 +39,64 @@ pub enum ContractError {
 
     #[error("Failed to parse or process reply message")]
     FailedToParseReply {},
+
+    #[error("Creator {0} is blacklisted")]
+    CreatorBlacklisted(String),
+
+    #[error("A passed multisig proposal is required to execute this operation")]
+    ProposalRequired {},
+
+    #[error("Multisig proposal {0} has not passed")]
+    ProposalNotPassed(u64),
+
+    #[error("Multisig proposal {0} was already used")]
+    ProposalAlreadyUsed(u64),
+
+    #[error("Batch must contain from 1 to {0} pairs")]
+    InvalidBatchSize(usize),
+
+    #[error("Pair {0} is duplicated in the batch")]
+    DuplicatePairInBatch(String),
+
+    #[error("Pair code ID {0} is not approved")]
+    CodeIdNotApproved(u64),
+
+    #[error("Pair {0} is not registered in the factory")]
+    PairNotRegistered(String),
+
+    #[error("Pair {0} is duplicated in the volume rankings")]
+    DuplicatePairInRankings(String),
+
+    #[error("Fee tier governance is not set")]
+    FeeTierGovernanceNotSet {},
+
+    #[error("Fee tiers are only supported by XYK pairs")]
+    FeeTierNotSupported {},
+
+    #[error("Insufficient xASTRO stake: required {required}, available {available}")]
+    InsufficientStake {
+        required: Uint128,
+        available: Uint128,
+    },
+
+    #[error("Voting period of fee tier proposal {0} is over")]
+    VotingPeriodEnded(u64),
+
+    #[error("Voting period of fee tier proposal {0} is not over yet")]
+    VotingPeriodActive(u64),
+
+    #[error("Address already voted on fee tier proposal {0}")]
+    AlreadyVoted(u64),
+
+    #[error("Address had no voting power when fee tier proposal {0} was created")]
+    NoVotingPower(u64),
+
+    #[error("Fee tier proposal {0} was already executed")]
+    FeeTierProposalExecuted(u64),
+
+    #[error("Fee tier proposal {0} didn't reach the quorum or the majority")]
+    FeeTierProposalRejected(u64),
+
+    #[error("Batch must contain at most {0} LP tokens")]
+    BatchTooLarge(usize),
 }
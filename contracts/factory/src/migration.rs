@@ -4,7 +4,7 @@ use cw_storage_plus::Map;
 
 use astroport::factory::{PairConfig, PairType};
 
-use crate::state::PAIR_CONFIGS;
+use crate::state::{APPROVED_CODE_IDS, PAIRS, PAIR_CONFIGS, PAIR_CREATION_INFO, TOTAL_PAIRS};
 
 #[cw_serde]
 pub enum OldPairType {
@@ -66,3 +66,41 @@ pub fn migrate_pair_configs(storage: &mut dyn Storage) -> StdResult<()> {
 
     Ok(())
 }
+
+/// Approves the code IDs of all existing pair types so pairs can still be created after the migration.
+pub fn approve_pair_config_code_ids(storage: &mut dyn Storage) -> StdResult<()> {
+    let code_ids = PAIR_CONFIGS
+        .range(storage, None, None, cosmwasm_std::Order::Ascending {})
+        .map(|item| Ok(item?.1.code_id))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for code_id in code_ids {
+        APPROVED_CODE_IDS.save(storage, code_id, &true)?;
+    }
+
+    Ok(())
+}
+
+/// Initializes the pair counter with the number of pairs registered before the migration.
+pub fn init_total_pairs(storage: &mut dyn Storage) -> StdResult<()> {
+    let total_pairs = PAIRS
+        .keys(storage, None, None, cosmwasm_std::Order::Ascending {})
+        .count() as u64;
+
+    TOTAL_PAIRS.save(storage, &total_pairs)
+}
+
+/// Sets a zero creation height and time for the pairs registered before the migration
+/// as their creation block is unknown.
+pub fn init_pair_creation_info(storage: &mut dyn Storage) -> StdResult<()> {
+    let pairs = PAIRS
+        .range(storage, None, None, cosmwasm_std::Order::Ascending {})
+        .map(|item| Ok(item?.1))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for pair in pairs {
+        PAIR_CREATION_INFO.save(storage, &pair, &(0, 0))?;
+    }
+
+    Ok(())
+}
@@ -1,11 +1,14 @@
-use astroport::asset::PairInfo;
-use astroport::pair::QueryMsg;
+use astroport::asset::{AssetInfoExt, PairInfo};
+use astroport::factory::MultisigQueryMsg;
+use astroport::pair::{PoolResponse, QueryMsg};
+use astroport::xastro_token::QueryMsg as XastroQueryMsg;
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
     from_json, to_json_binary, Coin, Empty, OwnedDeps, Querier, QuerierResult, QueryRequest,
-    SystemError, SystemResult, WasmQuery,
+    SystemError, SystemResult, Uint128, WasmQuery,
 };
-use std::collections::HashMap;
+use cw20::BalanceResponse;
+use std::collections::{HashMap, HashSet};
 
 /// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies.
 /// This uses the Astroport CustomQuerier.
@@ -26,6 +29,9 @@ pub fn mock_dependencies(
 pub struct WasmMockQuerier {
     base: MockQuerier<Empty>,
     astroport_pair_querier: AstroportPairQuerier,
+    passed_proposals: HashSet<u64>,
+    xastro_balances: HashMap<String, Uint128>,
+    pool_reserves: HashMap<String, Vec<Uint128>>,
 }
 
 #[derive(Clone, Default)]
@@ -68,6 +74,32 @@ impl Querier for WasmMockQuerier {
 impl WasmMockQuerier {
     pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
         match &request {
+            QueryRequest::Wasm(WasmQuery::Smart { msg, .. })
+                if from_json::<MultisigQueryMsg>(msg).is_ok() =>
+            {
+                let MultisigQueryMsg::IsProposalPassed { proposal_id } = from_json(msg).unwrap();
+                SystemResult::Ok(
+                    to_json_binary(&self.passed_proposals.contains(&proposal_id)).into(),
+                )
+            }
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) if contract_addr == "xastro" => {
+                match from_json(msg).unwrap() {
+                    XastroQueryMsg::BalanceAt { address, .. } => SystemResult::Ok(
+                        to_json_binary(&BalanceResponse {
+                            balance: self
+                                .xastro_balances
+                                .get(&address)
+                                .copied()
+                                .unwrap_or_default(),
+                        })
+                        .into(),
+                    ),
+                    XastroQueryMsg::TotalSupplyAt { .. } => SystemResult::Ok(
+                        to_json_binary(&self.xastro_balances.values().sum::<Uint128>()).into(),
+                    ),
+                    _ => panic!("DO NOT ENTER HERE"),
+                }
+            }
             QueryRequest::Wasm(WasmQuery::Smart {contract_addr, msg})// => {
                 => match from_json(&msg).unwrap() {
                     QueryMsg::Pair {} => {
@@ -83,6 +115,34 @@ impl WasmMockQuerier {
 
                     SystemResult::Ok(to_json_binary(&pair_info).into())
                     }
+                    QueryMsg::Pool {} => {
+                        let pair_info = match self.astroport_pair_querier.pairs.get(contract_addr) {
+                            Some(v) => v,
+                            None => {
+                                return SystemResult::Err(SystemError::NoSuchContract {
+                                    addr: contract_addr.clone(),
+                                })
+                            }
+                        };
+                        let reserves = self
+                            .pool_reserves
+                            .get(contract_addr)
+                            .cloned()
+                            .unwrap_or_default();
+
+                        SystemResult::Ok(
+                            to_json_binary(&PoolResponse {
+                                assets: pair_info
+                                    .asset_infos
+                                    .iter()
+                                    .zip(&reserves)
+                                    .map(|(asset_info, amount)| asset_info.with_balance(*amount))
+                                    .collect(),
+                                total_share: Uint128::zero(),
+                            })
+                            .into(),
+                        )
+                    }
                     _ => panic!("DO NOT ENTER HERE")
             }
             _ => self.base.handle_query(request),
@@ -95,6 +155,9 @@ impl WasmMockQuerier {
         WasmMockQuerier {
             base,
             astroport_pair_querier: AstroportPairQuerier::default(),
+            passed_proposals: HashSet::new(),
+            xastro_balances: HashMap::new(),
+            pool_reserves: HashMap::new(),
         }
     }
 
@@ -102,4 +165,28 @@ impl WasmMockQuerier {
     pub fn with_astroport_pairs(&mut self, pairs: &[(&String, &PairInfo)]) {
         self.astroport_pair_querier = AstroportPairQuerier::new(pairs);
     }
+
+    // Configure the multisig proposals which have passed
+    pub fn with_passed_proposals(&mut self, proposals: &[u64]) {
+        self.passed_proposals = proposals.iter().copied().collect();
+    }
+
+    // Configure the xASTRO balances of the "xastro" token. They don't change between blocks
+    pub fn with_xastro_balances(&mut self, balances: &[(&str, u128)]) {
+        self.xastro_balances = balances
+            .iter()
+            .map(|(address, balance)| (address.to_string(), Uint128::new(*balance)))
+            .collect();
+    }
+
+    // Configure the pool reserves of the Astroport pairs in the order of their assets
+    pub fn with_pool_reserves(&mut self, reserves: &[(&str, &[u128])]) {
+        self.pool_reserves = reserves
+            .iter()
+            .map(|(pair, amounts)| {
+                let amounts = amounts.iter().copied().map(Uint128::new).collect();
+                (pair.to_string(), amounts)
+            })
+            .collect();
+    }
 }
@@ -1,5 +1,5 @@
 use astroport::asset::PairInfo;
-use astroport::pair::QueryMsg;
+use astroport::pair::{PoolResponse, QueryMsg};
 use cosmwasm_std::{QuerierWrapper, StdResult};
 
 /// Returns information about a pair (using the [`PairInfo`] struct).
@@ -11,3 +11,13 @@ pub fn query_pair_info(
 ) -> StdResult<PairInfo> {
     querier.query_wasm_smart(pair_contract, &QueryMsg::Pair {})
 }
+
+/// Returns the assets in a pair together with their amounts.
+///
+/// `pair_contract` is the pair for which to retrieve the reserves.
+pub fn query_pool(
+    querier: &QuerierWrapper,
+    pair_contract: impl Into<String>,
+) -> StdResult<PoolResponse> {
+    querier.query_wasm_smart(pair_contract, &QueryMsg::Pool {})
+}
@@ -1,28 +1,230 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Api, Deps, Order, StdResult};
-use cw_storage_plus::{Bound, Item, Map};
+use cosmwasm_std::{Addr, Api, Deps, Order, QuerierWrapper, StdResult, Storage, Uint128};
+use cw_storage_plus::{Bound, Item, Map, SnapshotMap, Strategy};
 use itertools::Itertools;
 
 use crate::error::ContractError;
 use astroport::asset::AssetInfo;
 use astroport::common::OwnershipProposal;
-use astroport::factory::{Config, PairConfig};
+use astroport::factory::{
+    BlacklistReason, BlacklistedPairResponse, Config, FeeTierGovernance, FeeTierProposal,
+    MultisigQueryMsg, PairConfig, PairType, PairVolumeResponse, Role,
+};
 /// This is an intermediate structure for storing a pair's key. It is used in a submessage response.
 #[cw_serde]
 pub struct TmpPairInfo {
     pub pair_key: Vec<u8>,
+    pub creator: Addr,
 }
 
 /// Saves a pair's key
 pub const TMP_PAIR_INFO: Item<TmpPairInfo> = Item::new("tmp_pair_info");
 
+/// This is an intermediate structure for storing a single pair of a batch. It is used in a submessage response.
+#[cw_serde]
+pub struct TmpBatchPair {
+    pub pair_key: Vec<u8>,
+    pub asset_infos: Vec<AssetInfo>,
+}
+
+/// This is an intermediate structure for storing the batch being created. It is used in submessage responses.
+/// Pairs are stored in the same order as their submessages, so a pair's index is derived from the reply ID.
+#[cw_serde]
+pub struct TmpBatchInfo {
+    pub batch_id: u64,
+    pub creator: Addr,
+    pub pairs: Vec<TmpBatchPair>,
+}
+
+/// Saves the batch being created
+pub const TMP_BATCH_INFO: Item<TmpBatchInfo> = Item::new("tmp_batch_info");
+
+/// Stores the identifier which is assigned to the next batch of pairs
+pub const NEXT_BATCH_ID: Item<u64> = Item::new("next_batch_id");
+
+/// Stores the addresses of pairs successfully created within a batch. Key: batch id -> pair addresses
+pub const BATCH_STATE: Map<u64, Vec<Addr>> = Map::new("batch_state");
+
+/// Stores the assets of pairs which failed to be created within a batch. Key: batch id -> pair assets
+pub const BATCH_FAILURES: Map<u64, Vec<Vec<AssetInfo>>> = Map::new("batch_failures");
+
 /// Saves factory settings
 pub const CONFIG: Item<Config> = Item::new("config");
 
-/// Saves created pairs (from olders to latest)
-pub const PAIRS: Map<&[u8], Addr> = Map::new("pair_info");
+/// The namespace of the current [`PAIRS`] entries
+const PAIRS_NAMESPACE: &str = "pair_info";
+
+/// Saves created pairs (from olders to latest). Every change is recorded with its block height
+/// to return the pairs registered at past heights. The history adds a changelog entry per
+/// registration or deregistration and a checkpoint per block with changes, and it is never
+/// pruned. Pairs registered before the history was introduced have no changelog entries, so
+/// past heights return their current address
+pub const PAIRS: SnapshotMap<&[u8], Addr> = SnapshotMap::new(
+    PAIRS_NAMESPACE,
+    "pair_info__checkpoints",
+    "pair_info__changelog",
+    Strategy::EveryBlock,
+);
+
+/// The current [`PAIRS`] entries without their history. Used by other contracts to read the
+/// registered pairs with raw queries
+pub const CURRENT_PAIRS: Map<&[u8], Addr> = Map::new(PAIRS_NAMESPACE);
+
+/// Stores the identifier which is assigned to the next created pair
+pub const NEXT_PAIR_ID: Item<u64> = Item::new("next_pair_id");
+
+/// Stores pairs by their creators. Key: (creator, pair id) -> pair address
+pub const PAIRS_BY_CREATOR: Map<(&Addr, u64), Addr> = Map::new("pairs_by_creator");
+
+/// Stores the creator of each pair along with the pair identifier used in [`PAIRS_BY_CREATOR`]
+pub const PAIR_CREATORS: Map<&Addr, (Addr, u64)> = Map::new("pair_creators");
+
+/// Stores the pair of every LP token. Key: LP token address -> pair address
+pub const LP_TO_PAIR: Map<&Addr, Addr> = Map::new("lp_to_pair");
+
+/// Stores the registered pairs by each of their assets. Key: (asset, pair address). Pairs
+/// registered before the index was introduced aren't indexed
+pub const PAIRS_BY_ASSET: Map<(String, &Addr), ()> = Map::new("pairs_by_asset");
+
+/// Stores the block height and time (in seconds) at which each pair was created
+pub const PAIR_CREATION_INFO: Map<&Addr, (u64, u64)> = Map::new("pair_creation_info");
+
+/// Stores creators which are not allowed to create new pairs
+pub const BLACKLISTED_CREATORS: Map<&Addr, ()> = Map::new("blacklisted_creators");
+
+/// Stores the granted administrative roles. Key: (address, [`Role::as_str`])
+pub const ROLES: Map<(&Addr, &str), ()> = Map::new("roles");
+
+/// Stores pairs which were paused individually
+pub const PAUSED_PAIRS: Map<&Addr, ()> = Map::new("paused_pairs");
+
+/// Stores pairs delisted in an emergency. Key: pair address -> why and when the pair was blacklisted
+pub const BLACKLISTED_PAIRS: Map<&Addr, BlacklistReason> = Map::new("blacklisted_pairs");
+
+/// Whether all pairs are paused at once
+pub const ALL_PAIRS_PAUSED: Item<bool> = Item::new("all_pairs_paused");
+
+/// Stores multisig proposals which were already used to approve an operation
+pub const USED_PROPOSALS: Map<u64, ()> = Map::new("used_proposals");
+
+/// Stores the pair contract code IDs that new pairs can be instantiated from
+pub const APPROVED_CODE_IDS: Map<u64, bool> = Map::new("approved_code_ids");
+
+/// Stores the admins of individual pairs. Key: pair address -> admin address
+pub const PAIR_ADMINS: Map<&Addr, Addr> = Map::new("pair_admins");
+
+/// Stores the number of pairs registered in the factory
+pub const TOTAL_PAIRS: Item<u64> = Item::new("total_pairs");
+
+/// Stores the USD value locked in each pair as reported by TVL oracles
+pub const TVL_CONTRIBUTIONS: Map<&Addr, Uint128> = Map::new("tvl_contributions");
+
+/// Stores the sum of all [`TVL_CONTRIBUTIONS`]
+pub const TOTAL_TVL: Item<Uint128> = Item::new("total_tvl");
+
+/// Stores the gas used by a single swap for each pair type. Key: pair type name -> gas
+pub const GAS_BENCHMARKS: Map<String, u64> = Map::new("gas_benchmarks");
+
+/// Stores the pairs ranked by their 24h trading volume. Key: rank starting from 1 -> (pair address, volume)
+pub const PAIR_VOLUME_REGISTRY: Map<u64, (Addr, Uint128)> = Map::new("pair_volume_registry");
+
+/// Stores the parameters of the fee tier proposals
+pub const FEE_TIER_GOVERNANCE: Item<FeeTierGovernance> = Item::new("fee_tier_governance");
+
+/// Stores the fee tier proposals. Key: proposal identifier -> proposal
+pub const FEE_TIER_PROPOSALS: Map<u64, FeeTierProposal> = Map::new("fee_tier_proposals");
+
+/// Stores the number of created fee tier proposals which is also the next proposal identifier
+pub const FEE_TIER_PROPOSAL_COUNT: Item<u64> = Item::new("fee_tier_proposal_count");
+
+/// Stores the votes on the fee tier proposals. Key: (proposal identifier, voter) -> vote
+pub const FEE_TIER_VOTES: Map<(u64, &Addr), bool> = Map::new("fee_tier_votes");
+
+/// Stores the total fees (in bps) set by executed fee tier proposals. Key: pair address -> total fee
+pub const PAIR_FEE_TIERS: Map<&Addr, u16> = Map::new("pair_fee_tiers");
+
+/// Returns whether `addr` holds `role`. The owner implicitly holds every role.
+pub fn has_role(
+    storage: &dyn Storage,
+    config: &Config,
+    addr: &Addr,
+    role: &Role,
+) -> StdResult<bool> {
+    if *addr == config.owner {
+        return Ok(true);
+    }
+
+    Ok(ROLES.has(storage, (addr, role.as_str())))
+}
+
+/// Returns an error if `sender` isn't allowed to execute an owner-only operation or an operation requiring `role`.
+/// If the multisig module is set, it is the only account which can execute such operations directly.
+/// Other authorized accounts must reference a passed multisig proposal in `proposal_id`. Each proposal can be used once.
+pub fn assert_authorized(
+    storage: &mut dyn Storage,
+    querier: QuerierWrapper,
+    config: &Config,
+    sender: &Addr,
+    role: Option<Role>,
+    proposal_id: Option<u64>,
+) -> Result<(), ContractError> {
+    if config.multisig_module.as_ref() == Some(sender) {
+        return Ok(());
+    }
+
+    let authorized = match role {
+        Some(role) => has_role(storage, config, sender, &role)?,
+        None => *sender == config.owner,
+    };
+    if !authorized {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(multisig_module) = &config.multisig_module {
+        let proposal_id = proposal_id.ok_or(ContractError::ProposalRequired {})?;
+        if USED_PROPOSALS.has(storage, proposal_id) {
+            return Err(ContractError::ProposalAlreadyUsed(proposal_id));
+        }
+
+        let passed: bool = querier.query_wasm_smart(
+            multisig_module,
+            &MultisigQueryMsg::IsProposalPassed { proposal_id },
+        )?;
+        if !passed {
+            return Err(ContractError::ProposalNotPassed(proposal_id));
+        }
+
+        USED_PROPOSALS.save(storage, proposal_id, &())?;
+    }
+
+    Ok(())
+}
+
+/// Sets the USD value locked in `pair` and updates [`TOTAL_TVL`] accordingly.
+/// Zero `tvl_usd` removes the pair contribution.
+pub fn set_tvl_contribution(
+    storage: &mut dyn Storage,
+    pair: &Addr,
+    tvl_usd: Uint128,
+) -> StdResult<Uint128> {
+    let previous = TVL_CONTRIBUTIONS
+        .may_load(storage, pair)?
+        .unwrap_or_default();
+    if tvl_usd.is_zero() {
+        TVL_CONTRIBUTIONS.remove(storage, pair);
+    } else {
+        TVL_CONTRIBUTIONS.save(storage, pair, &tvl_usd)?;
+    }
+
+    let total_tvl = TOTAL_TVL.may_load(storage)?.unwrap_or_default() - previous + tvl_usd;
+    TOTAL_TVL.save(storage, &total_tvl)?;
+
+    Ok(total_tvl)
+}
 
 /// Calculates a pair key from the specified parameters in the `asset_infos` variable.
+/// The assets are sorted in the canonical order of [`AssetInfo::sort_pair`], so all the
+/// permutations of the same assets map to the same pair.
 ///
 /// `asset_infos` is an array with multiple items of type [`AssetInfo`].
 pub fn pair_key(asset_infos: &[AssetInfo]) -> Vec<u8> {
@@ -83,6 +285,134 @@ pub fn read_pairs(
     }
 }
 
+/// Reads pairs created by `creator` from [`PAIRS_BY_CREATOR`] according to the `start_after` and `limit` variables.
+///
+/// `start_after` is the pair identifier from which the function starts to fetch results.
+///
+/// `limit` is the number of items to retrieve.
+pub fn read_pairs_by_creator(
+    deps: Deps,
+    creator: &Addr,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Addr>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    PAIRS_BY_CREATOR
+        .prefix(creator)
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            let (_, pair_addr) = item?;
+            Ok(pair_addr)
+        })
+        .collect()
+}
+
+/// Reads pair type configurations from [`PAIR_CONFIGS`] according to the `start_after` and `limit` variables.
+///
+/// `start_after` is the pair type from which the function starts to fetch results.
+///
+/// `limit` is the number of items to retrieve.
+pub fn read_pair_configs(
+    deps: Deps,
+    start_after: Option<PairType>,
+    limit: Option<u32>,
+) -> StdResult<Vec<PairConfig>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    PAIR_CONFIGS
+        .range(
+            deps.storage,
+            start_after.map(|pair_type| Bound::exclusive(pair_type.to_string())),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            let (_, pair_config) = item?;
+            Ok(pair_config)
+        })
+        .collect()
+}
+
+/// Reads the pair volume rankings from [`PAIR_VOLUME_REGISTRY`] according to the `start_after` and `limit` variables.
+///
+/// `start_after` is the rank from which the function starts to fetch results.
+///
+/// `limit` is the number of items to retrieve.
+pub fn read_pairs_by_volume(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: u32,
+) -> StdResult<Vec<PairVolumeResponse>> {
+    PAIR_VOLUME_REGISTRY
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit.min(MAX_LIMIT) as usize)
+        .map(|item| {
+            let (rank, (pair, volume)) = item?;
+            Ok(PairVolumeResponse { rank, pair, volume })
+        })
+        .collect()
+}
+
+/// Reads the blacklisted pairs from [`BLACKLISTED_PAIRS`] according to the `start_after` and `limit` variables.
+///
+/// `start_after` is the pair address from which the function starts to fetch results.
+///
+/// `limit` is the number of items to retrieve.
+pub fn read_blacklisted_pairs(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: u32,
+) -> StdResult<Vec<BlacklistedPairResponse>> {
+    let start_after = start_after
+        .map(|pair| deps.api.addr_validate(&pair))
+        .transpose()?;
+
+    BLACKLISTED_PAIRS
+        .range(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit.min(MAX_LIMIT) as usize)
+        .map(|item| {
+            let (pair, reason) = item?;
+            Ok(BlacklistedPairResponse { pair, reason })
+        })
+        .collect()
+}
+
+/// Reads the addresses of the pairs containing `asset_info` from [`PAIRS_BY_ASSET`].
+/// Blacklisted pairs are skipped.
+pub fn read_pairs_by_asset(deps: Deps, asset_info: &AssetInfo) -> StdResult<Vec<Addr>> {
+    PAIRS_BY_ASSET
+        .prefix(asset_info.to_string())
+        .keys(deps.storage, None, None, Order::Ascending)
+        .filter(|pair| {
+            pair.as_ref()
+                .map_or(true, |pair| !BLACKLISTED_PAIRS.has(deps.storage, pair))
+        })
+        .collect()
+}
+
+/// Returns the number of items to read for `limit` using the default and the maximum limits.
+pub fn pagination_limit(limit: Option<u32>) -> usize {
+    limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize
+}
+
 /// Calculates the key of a pair from which to start reading data.
 ///
 /// `start_after` is an [`Option`] type that accepts [`AssetInfo`] elements.
@@ -1,10 +1,12 @@
 use cosmwasm_std::{
-    attr, from_json, to_json_binary, Addr, Reply, ReplyOn, SubMsg, SubMsgResponse, SubMsgResult,
-    WasmMsg,
+    attr, from_json, to_json_binary, Addr, Binary, Decimal, Deps, Event, Reply, ReplyOn, StdError,
+    SubMsg, SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
 };
 
+use cw2::ContractVersion;
+
 use crate::mock_querier::mock_dependencies;
-use crate::state::CONFIG;
+use crate::state::{pair_key, CONFIG, PAIRS};
 use crate::{
     contract::{execute, instantiate, query},
     error::ContractError,
@@ -12,11 +14,17 @@ use crate::{
 
 use astroport::asset::{AssetInfo, PairInfo};
 use astroport::factory::{
-    ConfigResponse, ExecuteMsg, InstantiateMsg, PairConfig, PairType, PairsResponse, QueryMsg,
+    BatchStatusResponse, BlacklistReason, BlacklistedPairResponse, ConfigResponse,
+    CreatePairParams, ExecuteMsg, FeeInfoResponse, FeeTierProposal, HopCandidate, InstantiateMsg,
+    NextHopResponse, PairAddressResponse, PairConfig, PairType, PairVolumeResponse, PairsResponse,
+    QueryMsg, Role, MAX_BATCH_SIZE, MAX_PAIR_INFO_BATCH_SIZE,
 };
 
 use crate::contract::reply;
-use astroport::pair::InstantiateMsg as PairInstantiateMsg;
+use astroport::pair::{
+    CustomHookConfig, ExecuteMsg as PairExecuteMsg, HookType, InstantiateMsg as PairInstantiateMsg,
+};
+use astroport::pair_xyk::ExecuteMsg as XykPairExecuteMsg;
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 
 use prost::Message;
@@ -141,6 +149,16 @@ fn proper_initialization() {
     assert_eq!(123u64, config_res.token_code_id);
     assert_eq!(msg.pair_configs, config_res.pair_configs);
     assert_eq!(Addr::unchecked(owner), config_res.owner);
+
+    let contract_info: ContractVersion =
+        from_json(&query(deps.as_ref(), mock_env(), QueryMsg::ContractInfo {}).unwrap()).unwrap();
+    assert_eq!(
+        contract_info,
+        ContractVersion {
+            contract: "astroport-factory".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    );
 }
 
 #[test]
@@ -347,6 +365,7 @@ fn update_pair_config() {
     let info = mock_info("wrong-addr0000", &[]);
     let msg = ExecuteMsg::UpdatePairConfig {
         config: pair_config.clone(),
+        proposal_id: None,
     };
 
     let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
@@ -365,14 +384,38 @@ fn update_pair_config() {
             is_generator_disabled: false,
             permissioned: false,
         },
+        proposal_id: None,
     };
 
     let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
     assert_eq!(res, ContractError::PairConfigInvalidFeeBps {});
 
+    let info = mock_info(owner, &[]);
+    let msg = ExecuteMsg::UpdatePairConfig {
+        config: PairConfig {
+            code_id: 123u64,
+            pair_type: PairType::Xyk {},
+            total_fee_bps: 3,
+            maker_fee_bps: 9_998,
+            is_disabled: false,
+            is_generator_disabled: false,
+            permissioned: false,
+        },
+        proposal_id: None,
+    };
+
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::InvalidFeeConfig(
+            "total_fee_bps + maker_fee_bps must not exceed 10000 bps".to_string()
+        )
+    );
+
     let info = mock_info(owner, &[]);
     let msg = ExecuteMsg::UpdatePairConfig {
         config: pair_config.clone(),
+        proposal_id: None,
     };
 
     let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
@@ -397,6 +440,7 @@ fn update_pair_config() {
     let info = mock_info(owner, &[]);
     let msg = ExecuteMsg::UpdatePairConfig {
         config: pair_config_custom.clone(),
+        proposal_id: None,
     };
 
     execute(deps.as_mut(), env.clone(), info, msg).unwrap();
@@ -410,6 +454,274 @@ fn update_pair_config() {
     );
 }
 
+#[test]
+fn admin_roles() {
+    let mut deps = mock_dependencies(&[]);
+    let owner = "owner0000";
+    let pair_config = PairConfig {
+        code_id: 123u64,
+        pair_type: PairType::Xyk {},
+        total_fee_bps: 100,
+        maker_fee_bps: 10,
+        is_disabled: false,
+        is_generator_disabled: false,
+        permissioned: false,
+    };
+
+    let msg = InstantiateMsg {
+        pair_configs: vec![pair_config.clone()],
+        token_code_id: 123u64,
+        fee_address: None,
+        owner: owner.to_string(),
+        generator_address: None,
+        whitelist_code_id: 234u64,
+        coin_registry_address: "coin_registry".to_string(),
+    };
+
+    let env = mock_env();
+    instantiate(deps.as_mut(), env.clone(), mock_info(owner, &[]), msg).unwrap();
+
+    let update_msg = ExecuteMsg::UpdatePairConfig {
+        config: PairConfig {
+            total_fee_bps: 30,
+            ..pair_config
+        },
+        proposal_id: None,
+    };
+
+    // Fee admin role is required
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("fee_admin", &[]),
+        update_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // Only the owner can grant roles
+    let grant_msg = ExecuteMsg::GrantRole {
+        address: "fee_admin".to_string(),
+        role: Role::FeeAdmin,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("fee_admin", &[]),
+        grant_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(deps.as_mut(), env.clone(), mock_info(owner, &[]), grant_msg).unwrap();
+
+    let has_role: bool = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::HasRole {
+                address: "fee_admin".to_string(),
+                role: Role::FeeAdmin,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert!(has_role);
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("fee_admin", &[]),
+        update_msg.clone(),
+    )
+    .unwrap();
+
+    // Fee admin can't pause pairs
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("fee_admin", &[]),
+        ExecuteMsg::PauseAllPairs { proposal_id: None },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner, &[]),
+        ExecuteMsg::GrantRole {
+            address: "emergency_admin".to_string(),
+            role: Role::EmergencyAdmin,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("emergency_admin", &[]),
+        ExecuteMsg::PauseAllPairs { proposal_id: None },
+    )
+    .unwrap();
+
+    let paused: bool = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::IsPairPaused {
+                pair_addr: "pair0000".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert!(paused);
+
+    // Revoked role can't be used anymore
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner, &[]),
+        ExecuteMsg::RevokeRole {
+            address: "fee_admin".to_string(),
+            role: Role::FeeAdmin,
+        },
+    )
+    .unwrap();
+    let err = execute(deps.as_mut(), env, mock_info("fee_admin", &[]), update_msg).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+}
+
+#[test]
+fn multisig_module() {
+    let mut deps = mock_dependencies(&[]);
+    let owner = "owner0000";
+    let pair_config = PairConfig {
+        code_id: 123u64,
+        pair_type: PairType::Xyk {},
+        total_fee_bps: 100,
+        maker_fee_bps: 10,
+        is_disabled: false,
+        is_generator_disabled: false,
+        permissioned: false,
+    };
+
+    let msg = InstantiateMsg {
+        pair_configs: vec![pair_config.clone()],
+        token_code_id: 123u64,
+        fee_address: None,
+        owner: owner.to_string(),
+        generator_address: None,
+        whitelist_code_id: 234u64,
+        coin_registry_address: "coin_registry".to_string(),
+    };
+
+    let env = mock_env();
+    instantiate(deps.as_mut(), env.clone(), mock_info(owner, &[]), msg).unwrap();
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner, &[]),
+        ExecuteMsg::SetMultisigModule {
+            multisig_module: Some("multisig".to_string()),
+        },
+    )
+    .unwrap();
+
+    let config_res: ConfigResponse =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(
+        config_res.multisig_module,
+        Some(Addr::unchecked("multisig"))
+    );
+
+    let update_msg = |proposal_id| ExecuteMsg::UpdatePairConfig {
+        config: pair_config.clone(),
+        proposal_id,
+    };
+
+    // The owner needs a passed proposal
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner, &[]),
+        update_msg(None),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::ProposalRequired {});
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner, &[]),
+        update_msg(Some(1)),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::ProposalNotPassed(1));
+
+    // A passed proposal doesn't authorize other accounts
+    deps.querier.with_passed_proposals(&[1]);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("random", &[]),
+        update_msg(Some(1)),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner, &[]),
+        update_msg(Some(1)),
+    )
+    .unwrap();
+
+    // Proposals can't be reused
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner, &[]),
+        update_msg(Some(1)),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::ProposalAlreadyUsed(1));
+
+    // The multisig executes sensitive operations directly
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("multisig", &[]),
+        update_msg(None),
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner, &[]),
+        ExecuteMsg::SetMultisigModule {
+            multisig_module: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::ProposalRequired {});
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("multisig", &[]),
+        ExecuteMsg::SetMultisigModule {
+            multisig_module: None,
+        },
+    )
+    .unwrap();
+
+    execute(deps.as_mut(), env, mock_info(owner, &[]), update_msg(None)).unwrap();
+}
+
 #[test]
 fn create_pair() {
     let mut deps = mock_dependencies(&[]);
@@ -462,6 +774,7 @@ fn create_pair() {
             pair_type: PairType::Stable {},
             asset_infos: asset_infos.clone(),
             init_params: None,
+            proposal_id: None,
         },
     )
     .unwrap_err();
@@ -475,6 +788,7 @@ fn create_pair() {
             pair_type: PairType::Xyk {},
             asset_infos: asset_infos.clone(),
             init_params: None,
+            proposal_id: None,
         },
     )
     .unwrap();
@@ -483,7 +797,8 @@ fn create_pair() {
         res.attributes,
         vec![
             attr("action", "create_pair"),
-            attr("pair", "asset0000-asset0001")
+            attr("pair", "asset0000-asset0001"),
+            attr("pair_type", "xyk")
         ]
     );
     assert_eq!(
@@ -511,13 +826,113 @@ fn create_pair() {
 }
 
 #[test]
-fn register() {
+fn pair_types() {
+    let mut deps = mock_dependencies(&[]);
+
+    let xyk_config = PairConfig {
+        code_id: 321u64,
+        pair_type: PairType::Xyk {},
+        total_fee_bps: 100,
+        maker_fee_bps: 10,
+        is_disabled: false,
+        is_generator_disabled: false,
+        permissioned: false,
+    };
+    let stable_config = PairConfig {
+        code_id: 322u64,
+        pair_type: PairType::Stable {},
+        total_fee_bps: 5,
+        maker_fee_bps: 5000,
+        is_disabled: true,
+        is_generator_disabled: false,
+        permissioned: false,
+    };
+    let custom_config = PairConfig {
+        code_id: 323u64,
+        pair_type: PairType::Custom("concentrated".to_string()),
+        total_fee_bps: 0,
+        maker_fee_bps: 5000,
+        is_disabled: false,
+        is_generator_disabled: false,
+        permissioned: false,
+    };
+
+    let msg = InstantiateMsg {
+        pair_configs: vec![
+            xyk_config.clone(),
+            stable_config.clone(),
+            custom_config.clone(),
+        ],
+        token_code_id: 123u64,
+        fee_address: None,
+        owner: "owner0000".to_string(),
+        generator_address: Some(String::from("generator")),
+        whitelist_code_id: 234u64,
+        coin_registry_address: "coin_registry".to_string(),
+    };
+
+    let env = mock_env();
+    instantiate(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let res: Vec<PairConfig> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::AllPairTypes {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        vec![
+            custom_config.clone(),
+            stable_config.clone(),
+            xyk_config.clone()
+        ]
+    );
+
+    let res: Vec<PairConfig> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::AllPairTypes {
+                start_after: Some(PairType::Custom("concentrated".to_string())),
+                limit: Some(1),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res, vec![stable_config]);
+
+    let is_enabled = |pair_type| -> bool {
+        from_json(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::IsPairTypeEnabled { pair_type },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    assert!(is_enabled(PairType::Xyk {}));
+    assert!(!is_enabled(PairType::Stable {}));
+    assert!(is_enabled(PairType::Custom("concentrated".to_string())));
+    assert!(!is_enabled(PairType::Custom("unknown".to_string())));
+}
+
+#[test]
+fn get_or_create_pair() {
     let mut deps = mock_dependencies(&[]);
-    let owner = "owner0000";
 
     let msg = InstantiateMsg {
         pair_configs: vec![PairConfig {
-            code_id: 123u64,
+            code_id: 321u64,
             pair_type: PairType::Xyk {},
             total_fee_bps: 100,
             maker_fee_bps: 10,
@@ -527,15 +942,15 @@ fn register() {
         }],
         token_code_id: 123u64,
         fee_address: None,
+        owner: "owner0000".to_string(),
         generator_address: Some(String::from("generator")),
-        owner: owner.to_string(),
         whitelist_code_id: 234u64,
         coin_registry_address: "coin_registry".to_string(),
     };
 
     let env = mock_env();
     let info = mock_info("addr0000", &[]);
-    let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+    instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
 
     let asset_infos = vec![
         AssetInfo::Token {
@@ -545,23 +960,406 @@ fn register() {
             contract_addr: Addr::unchecked("asset0001"),
         },
     ];
-
-    let msg = ExecuteMsg::CreatePair {
+    let get_or_create_msg = ExecuteMsg::GetOrCreatePair {
         pair_type: PairType::Xyk {},
         asset_infos: asset_infos.clone(),
         init_params: None,
+        proposal_id: None,
     };
 
-    let env = mock_env();
-    let info = mock_info("addr0000", &[]);
-    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
-
-    let pair0_addr = "pair0000".to_string();
-    let pair0_info = PairInfo {
-        asset_infos: asset_infos.clone(),
-        contract_addr: Addr::unchecked("pair0000"),
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        get_or_create_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "created"),
+            attr("pair", "asset0000-asset0001")
+        ]
+    );
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(res.messages[0].id, 1);
+    assert_eq!(res.messages[0].reply_on, ReplyOn::Success);
+
+    PAIRS
+        .save(
+            deps.as_mut().storage,
+            &pair_key(&asset_infos),
+            &Addr::unchecked("pair0000"),
+            mock_env().block.height,
+        )
+        .unwrap();
+
+    // Running it again finds the registered pair
+    let res = execute(deps.as_mut(), env, info, get_or_create_msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "found"), attr("pair_address", "pair0000")]
+    );
+    assert!(res.messages.is_empty());
+}
+
+#[test]
+fn approved_code_ids() {
+    let mut deps = mock_dependencies(&[]);
+    let owner = "owner0000";
+
+    let pair_config = PairConfig {
+        code_id: 321u64,
+        pair_type: PairType::Xyk {},
+        total_fee_bps: 100,
+        maker_fee_bps: 10,
+        is_disabled: false,
+        is_generator_disabled: false,
+        permissioned: false,
+    };
+
+    let msg = InstantiateMsg {
+        pair_configs: vec![pair_config.clone()],
+        token_code_id: 123u64,
+        fee_address: None,
+        owner: owner.to_string(),
+        generator_address: None,
+        whitelist_code_id: 234u64,
+        coin_registry_address: "coin_registry".to_string(),
+    };
+
+    let env = mock_env();
+    instantiate(deps.as_mut(), env.clone(), mock_info(owner, &[]), msg).unwrap();
+
+    // Code IDs of the initial pair types are approved
+    let approved: bool = from_json(
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::IsCodeIdApproved { code_id: 321 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert!(approved);
+
+    let create_msg = ExecuteMsg::CreatePair {
+        pair_type: PairType::Xyk {},
+        asset_infos: vec![
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked("asset0000"),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked("asset0001"),
+            },
+        ],
+        init_params: None,
+        proposal_id: None,
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::RevokeCodeId { code_id: 321 },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner, &[]),
+        ExecuteMsg::RevokeCodeId { code_id: 321 },
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        create_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::CodeIdNotApproved(321));
+
+    // A new code ID must be approved separately from the pair config update
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner, &[]),
+        ExecuteMsg::UpdatePairConfig {
+            config: PairConfig {
+                code_id: 400,
+                ..pair_config
+            },
+            proposal_id: None,
+        },
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        create_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::CodeIdNotApproved(400));
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::ApproveCodeId { code_id: 400 },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner, &[]),
+        ExecuteMsg::ApproveCodeId { code_id: 400 },
+    )
+    .unwrap();
+
+    let res = execute(deps.as_mut(), env, mock_info("addr0000", &[]), create_msg).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "create_pair"),
+            attr("pair", "asset0000-asset0001"),
+            attr("pair_type", "xyk")
+        ]
+    );
+}
+
+#[test]
+fn batch_create_pairs() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        pair_configs: vec![PairConfig {
+            code_id: 321u64,
+            pair_type: PairType::Xyk {},
+            total_fee_bps: 100,
+            maker_fee_bps: 10,
+            is_disabled: false,
+            is_generator_disabled: false,
+            permissioned: false,
+        }],
+        token_code_id: 123u64,
+        fee_address: None,
+        owner: "owner0000".to_string(),
+        generator_address: Some(String::from("generator")),
+        whitelist_code_id: 234u64,
+        coin_registry_address: "coin_registry".to_string(),
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    let params = |token: &str| CreatePairParams {
+        pair_type: PairType::Xyk {},
+        asset_infos: vec![
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked("asset0000"),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked(token),
+            },
+        ],
+        init_params: None,
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::BatchCreatePairs {
+            pairs: (0..=MAX_BATCH_SIZE)
+                .map(|i| params(&format!("token{i}")))
+                .collect(),
+            proposal_id: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidBatchSize(MAX_BATCH_SIZE));
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::BatchCreatePairs {
+            pairs: vec![params("asset0001"), params("asset0001")],
+            proposal_id: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::DuplicatePairInBatch("asset0000-asset0001".to_string())
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        ExecuteMsg::BatchCreatePairs {
+            pairs: vec![params("asset0001"), params("asset0002")],
+            proposal_id: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "batch_create_pairs"),
+            attr("batch_id", "0"),
+            attr("pairs", "2")
+        ]
+    );
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(res.messages[0].id, 1000);
+    assert_eq!(res.messages[1].id, 1001);
+    assert_eq!(res.messages[0].reply_on, ReplyOn::Always);
+
+    let pair_info = PairInfo {
+        asset_infos: params("asset0001").asset_infos,
+        contract_addr: Addr::unchecked("pair0000"),
+        liquidity_token: Addr::unchecked("liquidity0000"),
+        pair_type: PairType::Xyk {},
+        creator: None,
+        creation_height: 0,
+        creation_time: 0,
+    };
+    deps.querier
+        .with_astroport_pairs(&[(&"pair0000".to_string(), &pair_info)]);
+
+    let instantiate_reply = MsgInstantiateContractResponse {
+        contract_address: String::from("pair0000"),
+        data: vec![],
+    };
+    let mut encoded_instantiate_reply = Vec::<u8>::with_capacity(instantiate_reply.encoded_len());
+    instantiate_reply
+        .encode(&mut encoded_instantiate_reply)
+        .unwrap();
+
+    // The first pair is created while the second one fails
+    reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: 1000,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(encoded_instantiate_reply.into()),
+            }),
+        },
+    )
+    .unwrap();
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: 1001,
+            result: SubMsgResult::Err("instantiate failed".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(res.attributes[0], attr("action", "register_failed"));
+
+    let status: BatchStatusResponse = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::BatchStatus { batch_id: 0 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        status,
+        BatchStatusResponse {
+            succeeded: vec![Addr::unchecked("pair0000")],
+            failed: vec![params("asset0002").asset_infos],
+        }
+    );
+
+    let pair_res: PairInfo = from_json(
+        &query(
+            deps.as_ref(),
+            env,
+            QueryMsg::Pair {
+                asset_infos: params("asset0001").asset_infos,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(pair_res.creator, Some(Addr::unchecked("addr0000")));
+    assert_eq!(pair_res.creation_height, mock_env().block.height);
+    assert_eq!(pair_res.creation_time, mock_env().block.time.seconds());
+}
+
+#[test]
+fn register() {
+    let mut deps = mock_dependencies(&[]);
+    let owner = "owner0000";
+
+    let msg = InstantiateMsg {
+        pair_configs: vec![PairConfig {
+            code_id: 123u64,
+            pair_type: PairType::Xyk {},
+            total_fee_bps: 100,
+            maker_fee_bps: 10,
+            is_disabled: false,
+            is_generator_disabled: false,
+            permissioned: false,
+        }],
+        token_code_id: 123u64,
+        fee_address: None,
+        generator_address: Some(String::from("generator")),
+        owner: owner.to_string(),
+        whitelist_code_id: 234u64,
+        coin_registry_address: "coin_registry".to_string(),
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+    let asset_infos = vec![
+        AssetInfo::Token {
+            contract_addr: Addr::unchecked("asset0000"),
+        },
+        AssetInfo::Token {
+            contract_addr: Addr::unchecked("asset0001"),
+        },
+    ];
+
+    let msg = ExecuteMsg::CreatePair {
+        pair_type: PairType::Xyk {},
+        asset_infos: asset_infos.clone(),
+        init_params: None,
+        proposal_id: None,
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let pair0_addr = "pair0000".to_string();
+    let pair0_info = PairInfo {
+        asset_infos: asset_infos.clone(),
+        contract_addr: Addr::unchecked("pair0000"),
         liquidity_token: Addr::unchecked("liquidity0000"),
         pair_type: PairType::Xyk {},
+        creator: None,
+        creation_height: 0,
+        creation_time: 0,
     };
 
     let mut deployed_pairs = vec![(&pair0_addr, &pair0_info)];
@@ -587,7 +1385,12 @@ fn register() {
         }),
     };
 
-    let _res = reply(deps.as_mut(), mock_env(), reply_msg.clone()).unwrap();
+    let res = reply(deps.as_mut(), mock_env(), reply_msg.clone()).unwrap();
+    assert_eq!(
+        res.events,
+        vec![Event::new("registry_hint")
+            .add_attributes([attr("pair", "pair0000"), attr("creator", "addr0000")])]
+    );
 
     let query_res = query(
         deps.as_ref(),
@@ -606,6 +1409,9 @@ fn register() {
             contract_addr: Addr::unchecked("pair0000"),
             asset_infos: asset_infos.clone(),
             pair_type: PairType::Xyk {},
+            creator: Some(Addr::unchecked("addr0000")),
+            creation_height: env.block.height,
+            creation_time: env.block.time.seconds(),
         }
     );
 
@@ -613,9 +1419,50 @@ fn register() {
     let res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap_err();
     assert_eq!(res, ContractError::PairWasRegistered {});
 
-    // Store one more item to test query pairs
-    let asset_infos_2 = vec![
-        AssetInfo::Token {
+    // The reversed asset order resolves to the same pair and can't create a duplicate
+    let reversed_asset_infos = asset_infos.iter().rev().cloned().collect::<Vec<_>>();
+    let pair_address: PairAddressResponse = from_json(
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::PairAddress {
+                asset_infos: reversed_asset_infos.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(pair_address.address, "pair0000");
+    let pair_res: PairInfo = from_json(
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Pair {
+                asset_infos: reversed_asset_infos.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(pair_res.contract_addr, Addr::unchecked("pair0000"));
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CreatePair {
+            pair_type: PairType::Xyk {},
+            asset_infos: reversed_asset_infos,
+            init_params: None,
+            proposal_id: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::PairWasCreated {});
+
+    // Store one more item to test query pairs
+    let asset_infos_2 = vec![
+        AssetInfo::Token {
             contract_addr: Addr::unchecked("asset0000"),
         },
         AssetInfo::Token {
@@ -627,6 +1474,7 @@ fn register() {
         pair_type: PairType::Xyk {},
         asset_infos: asset_infos_2.clone(),
         init_params: None,
+        proposal_id: None,
     };
 
     let env = mock_env();
@@ -639,6 +1487,9 @@ fn register() {
         contract_addr: Addr::unchecked("pair0001"),
         liquidity_token: Addr::unchecked("liquidity0001"),
         pair_type: PairType::Xyk {},
+        creator: None,
+        creation_height: 0,
+        creation_time: 0,
     };
 
     deployed_pairs.push((&pair1_addr, &pair1_info));
@@ -666,6 +1517,247 @@ fn register() {
 
     let _res = reply(deps.as_mut(), mock_env(), reply_msg.clone()).unwrap();
 
+    let pair_count: u64 =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::PairCount {}).unwrap()).unwrap();
+    assert_eq!(pair_count, 2);
+
+    // Pairs are indexed by their LP tokens
+    let pair_by_lp_token = |deps: Deps, lp_token: &str| {
+        query(
+            deps,
+            mock_env(),
+            QueryMsg::PairByLpToken {
+                lp_token: lp_token.to_string(),
+            },
+        )
+        .map(|res| from_json::<PairInfo>(&res).unwrap())
+    };
+    let pair_res = pair_by_lp_token(deps.as_ref(), "liquidity0001").unwrap();
+    assert_eq!(pair_res.contract_addr, Addr::unchecked("pair0001"));
+    assert_eq!(pair_res.creator, Some(Addr::unchecked("addr0000")));
+    assert_eq!(
+        pair_by_lp_token(deps.as_ref(), "liquidity0000")
+            .unwrap()
+            .contract_addr,
+        Addr::unchecked("pair0000")
+    );
+
+    // Pairs are looked up by a list of LP tokens in the same order
+    let pair_info_batch = |deps: Deps, lp_tokens: Vec<String>| {
+        query(deps, mock_env(), QueryMsg::PairInfoBatch { lp_tokens })
+            .map(|res| from_json::<Vec<Option<PairInfo>>>(&res).unwrap())
+    };
+    let pairs_res = pair_info_batch(
+        deps.as_ref(),
+        vec![
+            "liquidity0001".to_string(),
+            "unknown0000".to_string(),
+            "liquidity0000".to_string(),
+        ],
+    )
+    .unwrap();
+    assert_eq!(
+        pairs_res
+            .iter()
+            .map(|pair| pair.as_ref().map(|pair| pair.contract_addr.as_str()))
+            .collect::<Vec<_>>(),
+        vec![Some("pair0001"), None, Some("pair0000")]
+    );
+    assert_eq!(
+        pairs_res[0].as_ref().unwrap().creator,
+        Some(Addr::unchecked("addr0000"))
+    );
+    assert_eq!(
+        pair_info_batch(
+            deps.as_ref(),
+            vec!["liquidity0000".to_string(); MAX_PAIR_INFO_BATCH_SIZE + 1]
+        )
+        .unwrap_err(),
+        StdError::generic_err(ContractError::BatchTooLarge(MAX_PAIR_INFO_BATCH_SIZE).to_string())
+    );
+
+    // Next hop candidates are ordered by the reserves of the received assets
+    deps.querier
+        .with_pool_reserves(&[("pair0000", &[1000, 500]), ("pair0001", &[100, 2000])]);
+    let next_hops = |deps: Deps, from_asset: &str, limit: Option<u32>| {
+        from_json::<NextHopResponse>(
+            &query(
+                deps,
+                mock_env(),
+                QueryMsg::NextHopCandidates {
+                    from_asset: AssetInfo::Token {
+                        contract_addr: Addr::unchecked(from_asset),
+                    },
+                    limit,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+        .candidates
+    };
+    let candidate = |pair: &str, to_asset: &str, reserve_depth: u128| HopCandidate {
+        pair: Addr::unchecked(pair),
+        to_asset: AssetInfo::Token {
+            contract_addr: Addr::unchecked(to_asset),
+        },
+        reserve_depth: Uint128::new(reserve_depth),
+    };
+    assert_eq!(
+        next_hops(deps.as_ref(), "asset0000", None),
+        vec![
+            candidate("pair0001", "asset0002", 2000),
+            candidate("pair0000", "asset0001", 500),
+        ]
+    );
+    assert_eq!(
+        next_hops(deps.as_ref(), "asset0000", Some(1)),
+        vec![candidate("pair0001", "asset0002", 2000)]
+    );
+    assert_eq!(
+        next_hops(deps.as_ref(), "asset0001", None),
+        vec![candidate("pair0000", "asset0000", 1000)]
+    );
+    assert_eq!(next_hops(deps.as_ref(), "asset0003", None), vec![]);
+
+    // Only TVL oracles can report pair TVL
+    let tvl_msg = |pair: &str, tvl_usd: u128| ExecuteMsg::UpdateTvlContribution {
+        pair: pair.to_string(),
+        tvl_usd: Uint128::new(tvl_usd),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("oracle0000", &[]),
+        tvl_msg("pair0000", 1000),
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner, &[]),
+        ExecuteMsg::GrantRole {
+            address: "oracle0000".to_string(),
+            role: Role::TvlOracle,
+        },
+    )
+    .unwrap();
+    for (pair, tvl_usd) in [("pair0000", 1000), ("pair0001", 700), ("pair0001", 500)] {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("oracle0000", &[]),
+            tvl_msg(pair, tvl_usd),
+        )
+        .unwrap();
+    }
+
+    let total_tvl: Uint128 =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::TotalTvl {}).unwrap()).unwrap();
+    assert_eq!(total_tvl, Uint128::new(1500));
+
+    // Only volume keepers can rank pairs
+    let rankings_msg = |rankings: &[(&str, u128)]| ExecuteMsg::UpdatePairVolumeRankings {
+        rankings: rankings
+            .iter()
+            .map(|(pair, volume)| (pair.to_string(), Uint128::new(*volume)))
+            .collect(),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("oracle0000", &[]),
+        rankings_msg(&[("pair0000", 100)]),
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner, &[]),
+        ExecuteMsg::GrantRole {
+            address: "oracle0000".to_string(),
+            role: Role::VolumeKeeper,
+        },
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("oracle0000", &[]),
+        rankings_msg(&[("pair0000", 100), ("pair0000", 200)]),
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::DuplicatePairInRankings("pair0000".to_string())
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("oracle0000", &[]),
+        rankings_msg(&[("pair0000", 100), ("pair0001", 200)]),
+    )
+    .unwrap();
+
+    let pairs_by_volume = |deps: Deps, start_after: Option<u64>| -> Vec<PairVolumeResponse> {
+        from_json(
+            &query(
+                deps,
+                mock_env(),
+                QueryMsg::PairsByVolume {
+                    start_after,
+                    limit: 10,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    assert_eq!(
+        pairs_by_volume(deps.as_ref(), None),
+        vec![
+            PairVolumeResponse {
+                rank: 1,
+                pair: Addr::unchecked("pair0001"),
+                volume: Uint128::new(200),
+            },
+            PairVolumeResponse {
+                rank: 2,
+                pair: Addr::unchecked("pair0000"),
+                volume: Uint128::new(100),
+            },
+        ]
+    );
+    assert_eq!(
+        pairs_by_volume(deps.as_ref(), Some(1)),
+        vec![PairVolumeResponse {
+            rank: 2,
+            pair: Addr::unchecked("pair0000"),
+            volume: Uint128::new(100),
+        }]
+    );
+
+    // Rankings are replaced entirely
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("oracle0000", &[]),
+        rankings_msg(&[("pair0000", 300)]),
+    )
+    .unwrap();
+    assert_eq!(
+        pairs_by_volume(deps.as_ref(), None),
+        vec![PairVolumeResponse {
+            rank: 1,
+            pair: Addr::unchecked("pair0000"),
+            volume: Uint128::new(300),
+        }]
+    );
+
     let query_msg = QueryMsg::Pairs {
         start_after: None,
         limit: None,
@@ -681,12 +1773,18 @@ fn register() {
                 contract_addr: Addr::unchecked("pair0000"),
                 asset_infos: asset_infos.clone(),
                 pair_type: PairType::Xyk {},
+                creator: Some(Addr::unchecked("addr0000")),
+                creation_height: env.block.height,
+                creation_time: env.block.time.seconds(),
             },
             PairInfo {
                 liquidity_token: Addr::unchecked("liquidity0001"),
                 contract_addr: Addr::unchecked("pair0001"),
                 asset_infos: asset_infos_2.clone(),
                 pair_type: PairType::Xyk {},
+                creator: Some(Addr::unchecked("addr0000")),
+                creation_height: env.block.height,
+                creation_time: env.block.time.seconds(),
             }
         ]
     );
@@ -705,6 +1803,9 @@ fn register() {
             contract_addr: Addr::unchecked("pair0000"),
             asset_infos: asset_infos.clone(),
             pair_type: PairType::Xyk {},
+            creator: Some(Addr::unchecked("addr0000")),
+            creation_height: env.block.height,
+            creation_time: env.block.time.seconds(),
         }]
     );
 
@@ -722,6 +1823,9 @@ fn register() {
             contract_addr: Addr::unchecked("pair0001"),
             asset_infos: asset_infos_2.clone(),
             pair_type: PairType::Xyk {},
+            creator: Some(Addr::unchecked("addr0000")),
+            creation_height: env.block.height,
+            creation_time: env.block.time.seconds(),
         }]
     );
 
@@ -755,6 +1859,22 @@ fn register() {
 
     assert_eq!(res.attributes[0], attr("action", "deregister"));
 
+    // Deregistered pairs are no longer counted
+    let pair_count: u64 =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::PairCount {}).unwrap()).unwrap();
+    assert_eq!(pair_count, 1);
+    assert_eq!(
+        pair_by_lp_token(deps.as_ref(), "liquidity0001").unwrap_err(),
+        StdError::generic_err("No pair found for LP token liquidity0001")
+    );
+    assert_eq!(
+        next_hops(deps.as_ref(), "asset0000", None),
+        vec![candidate("pair0000", "asset0001", 500)]
+    );
+    let total_tvl: Uint128 =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::TotalTvl {}).unwrap()).unwrap();
+    assert_eq!(total_tvl, Uint128::new(1000));
+
     let query_msg = QueryMsg::Pairs {
         start_after: None,
         limit: None,
@@ -769,6 +1889,831 @@ fn register() {
             contract_addr: Addr::unchecked("pair0000"),
             asset_infos: asset_infos.clone(),
             pair_type: PairType::Xyk {},
+            creator: Some(Addr::unchecked("addr0000")),
+            creation_height: env.block.height,
+            creation_time: env.block.time.seconds(),
         },]
     );
+    let query_msg = QueryMsg::PairsByCreator {
+        creator: "addr0000".to_string(),
+        start_after: None,
+        limit: None,
+    };
+
+    let res = query(deps.as_ref(), env.clone(), query_msg.clone()).unwrap();
+    let pairs_res: PairsResponse = from_json(&res).unwrap();
+    assert_eq!(pairs_res.pairs.len(), 1);
+    assert_eq!(
+        pairs_res.pairs[0].contract_addr,
+        Addr::unchecked("pair0000")
+    );
+
+    // Revoke from wrong acc
+    let info = mock_info("wrong_addr0000", &[]);
+    let msg = ExecuteMsg::RevokeCreatorPairs {
+        creator: "addr0000".to_string(),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let info = mock_info(owner, &[]);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), env.clone(), query_msg).unwrap();
+    let pairs_res: PairsResponse = from_json(&res).unwrap();
+    assert_eq!(pairs_res.pairs, vec![]);
+
+    // Revoked pairs are still discoverable via the main index
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Pair {
+            asset_infos: asset_infos.clone(),
+        },
+    )
+    .unwrap();
+    let pair_res: PairInfo = from_json(&res).unwrap();
+    assert_eq!(pair_res.contract_addr, Addr::unchecked("pair0000"));
+
+    // Blacklisted creator can't create pairs anymore
+    let info = mock_info("addr0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        env,
+        info,
+        ExecuteMsg::CreatePair {
+            pair_type: PairType::Xyk {},
+            asset_infos: asset_infos_2,
+            init_params: None,
+            proposal_id: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::CreatorBlacklisted("addr0000".to_string())
+    );
+}
+
+#[test]
+fn pair_admin() {
+    let mut deps = mock_dependencies(&[]);
+    let owner = "owner0000";
+
+    let msg = InstantiateMsg {
+        pair_configs: vec![PairConfig {
+            code_id: 123u64,
+            pair_type: PairType::Xyk {},
+            total_fee_bps: 100,
+            maker_fee_bps: 10,
+            is_disabled: false,
+            is_generator_disabled: false,
+            permissioned: false,
+        }],
+        token_code_id: 123u64,
+        fee_address: None,
+        generator_address: None,
+        owner: owner.to_string(),
+        whitelist_code_id: 234u64,
+        coin_registry_address: "coin_registry".to_string(),
+    };
+
+    let env = mock_env();
+    instantiate(deps.as_mut(), env.clone(), mock_info(owner, &[]), msg).unwrap();
+
+    let asset_infos = vec![
+        AssetInfo::Token {
+            contract_addr: Addr::unchecked("asset0000"),
+        },
+        AssetInfo::Token {
+            contract_addr: Addr::unchecked("asset0001"),
+        },
+    ];
+    let pair_addr = "pair0000".to_string();
+    let pair_info = PairInfo {
+        asset_infos: asset_infos.clone(),
+        contract_addr: Addr::unchecked("pair0000"),
+        liquidity_token: Addr::unchecked("liquidity0000"),
+        pair_type: PairType::Xyk {},
+        creator: None,
+        creation_height: 0,
+        creation_time: 0,
+    };
+    deps.querier
+        .with_astroport_pairs(&[(&pair_addr, &pair_info)]);
+    PAIRS
+        .save(
+            deps.as_mut().storage,
+            &pair_key(&asset_infos),
+            &Addr::unchecked("pair0000"),
+            mock_env().block.height,
+        )
+        .unwrap();
+
+    let set_admin_msg = |admin: &str| ExecuteMsg::SetPairAdmin {
+        pair: pair_addr.clone(),
+        admin: admin.to_string(),
+    };
+
+    // Only the owner can set the first pair admin
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        set_admin_msg("addr0000"),
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner, &[]),
+        set_admin_msg("admin0000"),
+    )
+    .unwrap();
+
+    let admin: Option<Addr> = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::PairAdmin {
+                pair: pair_addr.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(admin, Some(Addr::unchecked("admin0000")));
+
+    // Only the pair admin can update the pair parameters
+    let update_msg = ExecuteMsg::UpdatePairParams {
+        pair: pair_addr.clone(),
+        params: Binary::from(b"{}".to_vec()),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner, &[]),
+        update_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin0000", &[]),
+        update_msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: pair_addr.clone(),
+            msg: to_json_binary(&PairExecuteMsg::UpdateConfig {
+                params: Binary::from(b"{}".to_vec()),
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+
+    // The pair admin can set the pair custom hook
+    let custom_hook = CustomHookConfig {
+        contract: Addr::unchecked("hook0000"),
+        gas_limit: 200_000,
+        hook_type: HookType::AfterSwap,
+    };
+    let set_hook_msg = ExecuteMsg::SetPairCustomHook {
+        pair: pair_addr.clone(),
+        config: Some(custom_hook.clone()),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        set_hook_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin0000", &[]),
+        set_hook_msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: pair_addr.clone(),
+            msg: to_json_binary(&XykPairExecuteMsg::SetCustomHook {
+                config: Some(custom_hook),
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+
+    // The pair admin can pause its pair without the pair admin role
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin0000", &[]),
+        ExecuteMsg::PausePair {
+            asset_infos: asset_infos.clone(),
+            proposal_id: None,
+        },
+    )
+    .unwrap();
+    // The pause flag is pushed to the pair
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_error(
+            WasmMsg::Execute {
+                contract_addr: pair_addr.clone(),
+                msg: to_json_binary(&XykPairExecuteMsg::SetPaused { paused: true }).unwrap(),
+                funds: vec![],
+            },
+            2,
+        )]
+    );
+    let paused: bool = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::IsPairPaused {
+                pair_addr: pair_addr.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert!(paused);
+
+    // The pair admin can hand over the pair to another admin
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("admin0000", &[]),
+        set_admin_msg("admin0001"),
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info("admin0000", &[]),
+        ExecuteMsg::UnpausePair {
+            asset_infos,
+            proposal_id: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::Unauthorized {});
+}
+
+#[test]
+fn blacklist_pair() {
+    let mut deps = mock_dependencies(&[]);
+    let owner = "owner0000";
+
+    let msg = InstantiateMsg {
+        pair_configs: vec![],
+        token_code_id: 123u64,
+        fee_address: None,
+        owner: owner.to_string(),
+        generator_address: None,
+        whitelist_code_id: 234u64,
+        coin_registry_address: "coin_registry".to_string(),
+    };
+
+    let env = mock_env();
+    instantiate(deps.as_mut(), env.clone(), mock_info(owner, &[]), msg).unwrap();
+
+    let mut pairs = vec![];
+    for (pair, token) in [("pair0000", "asset0000"), ("pair0001", "asset0001")] {
+        let asset_infos = vec![
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked(token),
+            },
+        ];
+        PAIRS
+            .save(
+                deps.as_mut().storage,
+                &pair_key(&asset_infos),
+                &Addr::unchecked(pair),
+                env.block.height,
+            )
+            .unwrap();
+        pairs.push((
+            pair.to_string(),
+            PairInfo {
+                asset_infos,
+                contract_addr: Addr::unchecked(pair),
+                liquidity_token: Addr::unchecked(format!("{pair}_lp")),
+                pair_type: PairType::Xyk {},
+                creator: None,
+                creation_height: 0,
+                creation_time: 0,
+            },
+        ));
+    }
+    deps.querier.with_astroport_pairs(
+        &pairs
+            .iter()
+            .map(|(pair, pair_info)| (pair, pair_info))
+            .collect::<Vec<_>>(),
+    );
+
+    let blacklist_msg = |pair: &str| ExecuteMsg::BlacklistPair {
+        pair: pair.to_string(),
+        reason: "Honeypot token".to_string(),
+        proposal_id: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        blacklist_msg("pair0000"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner, &[]),
+        ExecuteMsg::GrantRole {
+            address: "emergency0000".to_string(),
+            role: Role::EmergencyAdmin,
+        },
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("emergency0000", &[]),
+        blacklist_msg("pair0001"),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "blacklist_pair"),
+            attr("pair_contract_addr", "pair0001"),
+            attr("reason", "Honeypot token"),
+        ]
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner, &[]),
+        blacklist_msg("pair0000"),
+    )
+    .unwrap();
+
+    let is_blacklisted = |pair: &str| -> bool {
+        from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::IsPairBlacklisted {
+                    pair: pair.to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    assert!(is_blacklisted("pair0000"));
+    assert!(is_blacklisted("pair0001"));
+    assert!(!is_blacklisted("pair0002"));
+
+    let blacklisted_pairs = |start_after: Option<&str>| -> Vec<BlacklistedPairResponse> {
+        from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::BlacklistedPairs {
+                    start_after: start_after.map(|pair| pair.to_string()),
+                    limit: 10,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    let reason = BlacklistReason {
+        reason: "Honeypot token".to_string(),
+        blacklisted_at: env.block.time.seconds(),
+    };
+    assert_eq!(
+        blacklisted_pairs(None),
+        vec![
+            BlacklistedPairResponse {
+                pair: Addr::unchecked("pair0000"),
+                reason: reason.clone(),
+            },
+            BlacklistedPairResponse {
+                pair: Addr::unchecked("pair0001"),
+                reason,
+            },
+        ]
+    );
+    assert_eq!(blacklisted_pairs(Some("pair0000")).len(), 1);
+}
+
+#[test]
+fn gas_benchmarks() {
+    let mut deps = mock_dependencies(&[]);
+    let owner = "owner0000";
+
+    let msg = InstantiateMsg {
+        pair_configs: vec![],
+        token_code_id: 123u64,
+        fee_address: None,
+        owner: owner.to_string(),
+        generator_address: None,
+        whitelist_code_id: 234u64,
+        coin_registry_address: "coin_registry".to_string(),
+    };
+
+    let env = mock_env();
+    instantiate(deps.as_mut(), env.clone(), mock_info(owner, &[]), msg).unwrap();
+
+    let set_benchmark_msg = ExecuteMsg::SetGasBenchmark {
+        pair_type: PairType::Xyk {},
+        gas_per_swap: 250_000,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        set_benchmark_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner, &[]),
+        set_benchmark_msg,
+    )
+    .unwrap();
+
+    let gas_benchmark = |pair_type: PairType| -> Option<u64> {
+        from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::GasBenchmark { pair_type },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    assert_eq!(gas_benchmark(PairType::Xyk {}), Some(250_000));
+    assert_eq!(gas_benchmark(PairType::Stable {}), None);
+}
+
+#[test]
+fn fee_tier_governance() {
+    let mut deps = mock_dependencies(&[]);
+    let owner = "owner0000";
+
+    let msg = InstantiateMsg {
+        pair_configs: vec![
+            PairConfig {
+                code_id: 123u64,
+                pair_type: PairType::Xyk {},
+                total_fee_bps: 30,
+                maker_fee_bps: 3333,
+                is_disabled: false,
+                is_generator_disabled: false,
+                permissioned: false,
+            },
+            PairConfig {
+                code_id: 321u64,
+                pair_type: PairType::Stable {},
+                total_fee_bps: 5,
+                maker_fee_bps: 5000,
+                is_disabled: false,
+                is_generator_disabled: false,
+                permissioned: false,
+            },
+        ],
+        token_code_id: 123u64,
+        fee_address: None,
+        owner: owner.to_string(),
+        generator_address: None,
+        whitelist_code_id: 234u64,
+        coin_registry_address: "coin_registry".to_string(),
+    };
+
+    let mut env = mock_env();
+    instantiate(deps.as_mut(), env.clone(), mock_info(owner, &[]), msg).unwrap();
+
+    let pair_info = |pair: &str, asset: &str, pair_type: PairType| PairInfo {
+        asset_infos: vec![
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: asset.to_string(),
+            },
+        ],
+        contract_addr: Addr::unchecked(pair),
+        liquidity_token: Addr::unchecked(format!("{pair}_lp")),
+        pair_type,
+        creator: None,
+        creation_height: 0,
+        creation_time: 0,
+    };
+    let xyk_pair = pair_info("pair0000", "uluna", PairType::Xyk {});
+    let stable_pair = pair_info("pair0001", "uusdc", PairType::Stable {});
+    for pair in [&xyk_pair, &stable_pair] {
+        PAIRS
+            .save(
+                deps.as_mut().storage,
+                &pair_key(&pair.asset_infos),
+                &pair.contract_addr,
+                mock_env().block.height,
+            )
+            .unwrap();
+    }
+    deps.querier.with_astroport_pairs(&[
+        (&"pair0000".to_string(), &xyk_pair),
+        (&"pair0001".to_string(), &stable_pair),
+    ]);
+    deps.querier.with_xastro_balances(&[
+        ("proposer", 1_000),
+        ("voter1", 3_000),
+        ("voter2", 2_000),
+        ("holder", 4_000),
+    ]);
+
+    let propose_msg = |pair: &str, new_total_fee_bps: u16| ExecuteMsg::ProposeFeeTierChange {
+        pair: pair.to_string(),
+        new_total_fee_bps,
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("proposer", &[]),
+        propose_msg("pair0000", 10),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::FeeTierGovernanceNotSet {});
+
+    let governance_msg = ExecuteMsg::UpdateFeeTierGovernance {
+        xastro_token: "xastro".to_string(),
+        min_stake: Uint128::new(1_000),
+        voting_period: 86400,
+        quorum: Decimal::percent(40),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("proposer", &[]),
+        governance_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(owner, &[]),
+        governance_msg,
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("proposer", &[]),
+        propose_msg("pair0001", 10),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::FeeTierNotSupported {});
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("voter3", &[]),
+        propose_msg("pair0000", 10),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InsufficientStake {
+            required: Uint128::new(1_000),
+            available: Uint128::zero(),
+        }
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("proposer", &[]),
+        propose_msg("pair0000", 10),
+    )
+    .unwrap();
+    assert_eq!(res.attributes[1], attr("proposal_id", "0"));
+
+    let vote_msg = |vote: bool| ExecuteMsg::VoteOnFeeTierProposal {
+        proposal_id: 0,
+        vote,
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("voter1", &[]),
+        vote_msg(true),
+    )
+    .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("voter1", &[]),
+        vote_msg(false),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AlreadyVoted(0));
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("voter3", &[]),
+        vote_msg(true),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NoVotingPower(0));
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("voter2", &[]),
+        vote_msg(false),
+    )
+    .unwrap();
+
+    let execute_msg = ExecuteMsg::ExecuteFeeTierProposal { proposal_id: 0 };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        execute_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::VotingPeriodActive(0));
+
+    env.block.time = env.block.time.plus_seconds(86400);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("holder", &[]),
+        vote_msg(true),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::VotingPeriodEnded(0));
+
+    // 5,000 of 10,000 xASTRO voted and the majority supports the proposal
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        execute_msg.clone(),
+    )
+    .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        execute_msg,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::FeeTierProposalExecuted(0));
+
+    let fee_info = |pair: &str, pair_type: PairType| -> FeeInfoResponse {
+        from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::PairFeeInfo {
+                    pair_addr: pair.to_string(),
+                    pair_type,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    assert_eq!(fee_info("pair0000", PairType::Xyk {}).total_fee_bps, 10);
+    assert_eq!(fee_info("pair0000", PairType::Xyk {}).maker_fee_bps, 3333);
+    assert_eq!(fee_info("pair0002", PairType::Xyk {}).total_fee_bps, 30);
+
+    // A proposal without the quorum is rejected
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("proposer", &[]),
+        propose_msg("pair0000", 50),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("voter1", &[]),
+        ExecuteMsg::VoteOnFeeTierProposal {
+            proposal_id: 1,
+            vote: true,
+        },
+    )
+    .unwrap();
+    env.block.time = env.block.time.plus_seconds(86400);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::ExecuteFeeTierProposal { proposal_id: 1 },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::FeeTierProposalRejected(1));
+
+    let proposal: FeeTierProposal = from_json(
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::FeeTierProposal { proposal_id: 1 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(proposal.yes_votes, Uint128::new(3_000));
+    assert_eq!(proposal.total_voting_power, Uint128::new(10_000));
+    assert!(!proposal.executed);
+    assert_eq!(fee_info("pair0000", PairType::Xyk {}).total_fee_bps, 10);
+}
+
+#[test]
+fn pair_at_height() {
+    let mut deps = mock_dependencies(&[]);
+
+    let asset_infos = vec![
+        AssetInfo::Token {
+            contract_addr: Addr::unchecked("asset0000"),
+        },
+        AssetInfo::Token {
+            contract_addr: Addr::unchecked("asset0001"),
+        },
+    ];
+    let pair_info = PairInfo {
+        asset_infos: asset_infos.clone(),
+        contract_addr: Addr::unchecked("pair0000"),
+        liquidity_token: Addr::unchecked("liquidity0000"),
+        pair_type: PairType::Xyk {},
+        creator: None,
+        creation_height: 0,
+        creation_time: 0,
+    };
+    deps.querier
+        .with_astroport_pairs(&[(&"pair0000".to_string(), &pair_info)]);
+
+    // The pair is registered at height 100 and deregistered at height 200
+    PAIRS
+        .save(
+            deps.as_mut().storage,
+            &pair_key(&asset_infos),
+            &Addr::unchecked("pair0000"),
+            100,
+        )
+        .unwrap();
+    PAIRS
+        .remove(deps.as_mut().storage, &pair_key(&asset_infos), 200)
+        .unwrap();
+
+    let pair_at_height = |deps: Deps, height: u64| -> Option<PairInfo> {
+        from_json(
+            &query(
+                deps,
+                mock_env(),
+                QueryMsg::PairAtHeight {
+                    asset_infos: asset_infos.clone(),
+                    height,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    assert_eq!(pair_at_height(deps.as_ref(), 99), None);
+    assert_eq!(pair_at_height(deps.as_ref(), 100), Some(pair_info.clone()));
+    assert_eq!(pair_at_height(deps.as_ref(), 199), Some(pair_info));
+    assert_eq!(pair_at_height(deps.as_ref(), 200), None);
+    assert_eq!(pair_at_height(deps.as_ref(), 300), None);
 }
@@ -159,6 +159,7 @@ impl FactoryHelper {
             pair_type,
             asset_infos,
             init_params,
+            proposal_id: None,
         };
 
         router.execute_contract(sender.clone(), self.factory.clone(), &msg, &[])
@@ -206,6 +206,7 @@ fn test_create_pair() {
                 is_generator_disabled: false,
                 permissioned: false,
             },
+            proposal_id: None,
         },
         &[],
     )
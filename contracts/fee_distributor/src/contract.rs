@@ -0,0 +1,492 @@
+use std::str::FromStr;
+
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    attr, to_json_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Order, Response,
+    StdResult, Storage, Uint128, WasmMsg,
+};
+use cw2::set_contract_version;
+use cw4::{Cw4Contract, Cw4QueryMsg, MemberChangedHookMsg, MemberDiff, MemberResponse};
+use cw_storage_plus::Bound;
+
+use astroport::asset::{Asset, AssetInfo, AssetInfoExt};
+use astroport::fee_distributor::{
+    ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg, DEFAULT_CLAWBACK_WINDOW_SECS,
+};
+use astroport::pair::MAX_ALLOWED_SLIPPAGE;
+use astroport::router::{
+    Cw20HookMsg as RouterHookMsg, ExecuteMsg as RouterExecuteMsg, SwapOperation,
+};
+
+use crate::error::ContractError;
+use crate::state::{
+    Config, StakerInfo, CLAIMABLE_PER_SHARE, CLAIMABLE_PER_SHARE_HISTORY, CONFIG, STAKERS,
+    STAKE_CHECKPOINTS, TOTAL_PENDING,
+};
+
+/// Contract name that is used for migration.
+const CONTRACT_NAME: &str = "astroport-fee-distributor";
+/// Contract version that is used for migration.
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Sets the default maximum spread (as a percentage) used when swapping fee tokens to ASTRO.
+const DEFAULT_MAX_SPREAD: u64 = 5; // 5%
+/// The default number of stakers processed by a single clawback.
+const DEFAULT_CLAWBACK_LIMIT: u32 = 10;
+/// The max number of stakers processed by a single clawback.
+const MAX_CLAWBACK_LIMIT: u32 = 30;
+
+/// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let max_spread = if let Some(max_spread) = msg.max_spread {
+        if max_spread.is_zero() || max_spread.gt(&Decimal::from_str(MAX_ALLOWED_SLIPPAGE)?) {
+            return Err(ContractError::IncorrectMaxSpread {});
+        };
+
+        max_spread
+    } else {
+        Decimal::percent(DEFAULT_MAX_SPREAD)
+    };
+
+    msg.astro_token.check(deps.api)?;
+
+    let config = Config {
+        owner: deps.api.addr_validate(&msg.owner)?,
+        treasury: deps.api.addr_validate(&msg.treasury)?,
+        clawback_window_secs: msg
+            .clawback_window_secs
+            .unwrap_or(DEFAULT_CLAWBACK_WINDOW_SECS),
+        astro_token: msg.astro_token,
+        staked_astro: Cw4Contract(deps.api.addr_validate(&msg.staked_astro)?),
+        router: deps.api.addr_validate(&msg.router)?,
+        max_spread,
+    };
+    CONFIG.save(deps.storage, &config)?;
+    CLAIMABLE_PER_SHARE.save(deps.storage, &Decimal::zero())?;
+    TOTAL_PENDING.save(deps.storage, &Uint128::zero())?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "instantiate"),
+        attr("owner", msg.owner),
+        attr("treasury", msg.treasury),
+        attr(
+            "clawback_window_secs",
+            config.clawback_window_secs.to_string(),
+        ),
+        attr("astro_token", config.astro_token.to_string()),
+        attr("staked_astro", msg.staked_astro),
+        attr("router", msg.router),
+        attr("max_spread", max_spread.to_string()),
+    ]))
+}
+
+/// Exposes execute functions available in the contract.
+///
+/// ## Variants
+/// * **ExecuteMsg::ConvertToAstro { assets }** Swaps the contract balances of the specified
+/// assets to ASTRO via the router.
+///
+/// * **ExecuteMsg::Distribute {}** Distributes the undistributed ASTRO balance between the
+/// current ASTRO stakers.
+///
+/// * **ExecuteMsg::MemberChangedHook(MemberChangedHookMsg)** Checkpoints the stakers whose staked
+/// amounts changed in the CW4 contract.
+///
+/// * **ExecuteMsg::Claim {}** Sends the claimable ASTRO to the caller.
+///
+/// * **ExecuteMsg::ClawbackUnclaimedFees { since_block, start_after, limit }** Sends the ASTRO which
+/// stayed unclaimed for too long to the treasury.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::ConvertToAstro { assets } => convert_to_astro(deps, env, assets),
+        ExecuteMsg::Distribute {} => distribute(deps, env),
+        ExecuteMsg::MemberChangedHook(MemberChangedHookMsg { diffs }) => {
+            member_changed_hook(deps, env, info, diffs)
+        }
+        ExecuteMsg::Claim {} => claim(deps, info),
+        ExecuteMsg::ClawbackUnclaimedFees {
+            since_block,
+            start_after,
+            limit,
+        } => clawback_unclaimed_fees(deps, env, info, since_block, start_after, limit),
+    }
+}
+
+/// Swaps the contract balances of the specified assets to ASTRO via the router.
+/// Every asset must have a direct pair with ASTRO. Assets with zero balance are skipped.
+///
+/// * **assets** is the list of fee assets to convert.
+fn convert_to_astro(
+    deps: DepsMut,
+    env: Env,
+    assets: Vec<AssetInfo>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut messages = vec![];
+    for asset_info in assets {
+        asset_info.check(deps.api)?;
+        if asset_info.equal(&config.astro_token) {
+            return Err(ContractError::CannotConvertAstro {});
+        }
+
+        let amount = asset_info.query_pool(&deps.querier, &env.contract.address)?;
+        if amount.is_zero() {
+            continue;
+        }
+
+        let operations = vec![SwapOperation::AstroSwap {
+            offer_asset_info: asset_info.clone(),
+            ask_asset_info: config.astro_token.clone(),
+        }];
+
+        let msg = match &asset_info {
+            AssetInfo::NativeToken { .. } => WasmMsg::Execute {
+                contract_addr: config.router.to_string(),
+                msg: to_json_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                    operations,
+                    minimum_receive: None,
+                    to: None,
+                    max_spread: Some(config.max_spread),
+                    chain_id: None,
+                })?,
+                funds: vec![asset_info.with_balance(amount).as_coin()?],
+            },
+            AssetInfo::Token { contract_addr } => WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: to_json_binary(&cw20::Cw20ExecuteMsg::Send {
+                    contract: config.router.to_string(),
+                    amount,
+                    msg: to_json_binary(&RouterHookMsg::ExecuteSwapOperations {
+                        operations,
+                        minimum_receive: None,
+                        to: None,
+                        max_spread: Some(config.max_spread),
+                        chain_id: None,
+                    })?,
+                })?,
+                funds: vec![],
+            },
+        };
+        messages.push(msg);
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "convert_to_astro"))
+}
+
+/// Distributes the ASTRO that arrived since the last distribution between the stakers
+/// proportionally to their current staked amounts.
+///
+/// Only the global [`CLAIMABLE_PER_SHARE`] is updated. Stakers are checkpointed by
+/// [`member_changed_hook`] before their staked amounts change, so the new rewards are always
+/// split using the staked amounts at the moment of distribution.
+fn distribute(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let total_pending = TOTAL_PENDING.load(deps.storage)?;
+
+    let balance = config
+        .astro_token
+        .query_pool(&deps.querier, &env.contract.address)?;
+    let amount = balance.saturating_sub(total_pending);
+    if amount.is_zero() {
+        return Err(ContractError::NothingToDistribute {});
+    }
+
+    let total_staked = Uint128::from(config.staked_astro.total_weight(&deps.querier)?);
+    if total_staked.is_zero() {
+        return Err(ContractError::NoStakers {});
+    }
+
+    let claimable_per_share = CLAIMABLE_PER_SHARE
+        .update::<_, ContractError>(deps.storage, |claimable_per_share| {
+            Ok(claimable_per_share + Decimal::from_ratio(amount, total_staked))
+        })?;
+    CLAIMABLE_PER_SHARE_HISTORY.save(
+        deps.storage,
+        env.block.time.seconds(),
+        &claimable_per_share,
+    )?;
+    TOTAL_PENDING.save(deps.storage, &(total_pending + amount))?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "distribute"),
+        attr("amount", amount),
+        attr("total_staked", total_staked),
+    ]))
+}
+
+/// Checkpoints the stakers whose staked amounts changed in the CW4 contract. The ASTRO accrued
+/// with the previous staked amounts is kept and the next distributions use the new ones.
+///
+/// * **diffs** the staked amounts of the stakers before and after the change.
+///
+/// ## Executor
+/// Only the CW4 contract holding the staked ASTRO balances can execute this.
+fn member_changed_hook(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    diffs: Vec<MemberDiff>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.staked_astro.addr() {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let claimable_per_share = CLAIMABLE_PER_SHARE.load(deps.storage)?;
+    for diff in &diffs {
+        let staker = deps.api.addr_validate(&diff.key)?;
+        let mut staker_info = match STAKERS.may_load(deps.storage, &staker)? {
+            Some(staker_info) => staker_info,
+            None => new_staker(deps.storage, &staker, diff.old.unwrap_or_default().into())?,
+        };
+        staker_info.update_stake(claimable_per_share, diff.new.unwrap_or_default().into());
+        STAKERS.save(deps.storage, &staker, &staker_info)?;
+        STAKE_CHECKPOINTS.save(
+            deps.storage,
+            (&staker, env.block.time.seconds()),
+            &staker_info,
+        )?;
+    }
+
+    Ok(Response::new().add_attributes([
+        attr("action", "member_changed_hook"),
+        attr("stakers", diffs.len().to_string()),
+    ]))
+}
+
+/// Returns the current staked amount of a staker in the CW4 contract.
+fn query_staked_amount(deps: Deps, config: &Config, staker: &Addr) -> StdResult<Uint128> {
+    let member: MemberResponse = deps.querier.query_wasm_smart(
+        config.staked_astro.addr(),
+        &Cw4QueryMsg::Member {
+            addr: staker.to_string(),
+            at_height: None,
+        },
+    )?;
+
+    Ok(member.weight.unwrap_or_default().into())
+}
+
+/// Records the distribution state of a staker which hasn't been checkpointed yet.
+/// Such a staker has had `staked_amount` since the contract was created.
+fn new_staker(
+    storage: &mut dyn Storage,
+    staker: &Addr,
+    staked_amount: Uint128,
+) -> StdResult<StakerInfo> {
+    let staker_info = StakerInfo {
+        staked_amount,
+        ..Default::default()
+    };
+    STAKE_CHECKPOINTS.save(storage, (staker, 0), &staker_info)?;
+
+    Ok(staker_info)
+}
+
+/// Sends the ASTRO accrued by the caller.
+fn claim(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let claimable_per_share = CLAIMABLE_PER_SHARE.load(deps.storage)?;
+
+    let mut staker_info = match STAKERS.may_load(deps.storage, &info.sender)? {
+        Some(staker_info) => staker_info,
+        None => {
+            let staked_amount = query_staked_amount(deps.as_ref(), &config, &info.sender)?;
+            new_staker(deps.storage, &info.sender, staked_amount)?
+        }
+    };
+    let amount = staker_info.claimable(claimable_per_share);
+    if amount.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+
+    staker_info.withdrawn += amount;
+    STAKERS.save(deps.storage, &info.sender, &staker_info)?;
+    TOTAL_PENDING
+        .update::<_, ContractError>(deps.storage, |total| Ok(total.checked_sub(amount)?))?;
+
+    let transfer_msg = Asset {
+        info: config.astro_token,
+        amount,
+    }
+    .into_msg(&info.sender)?;
+
+    Ok(Response::new().add_message(transfer_msg).add_attributes([
+        attr("action", "claim"),
+        attr("staker", info.sender),
+        attr("amount", amount),
+    ]))
+}
+
+/// Sends the unclaimed ASTRO distributed at `since_block` or earlier to the treasury. Only the
+/// rewards a staker accrued until `since_block` and hasn't withdrawn yet are clawed back, the
+/// rewards distributed later stay claimable. Claims withdraw the oldest rewards first.
+///
+/// Only the stakers checkpointed by [`member_changed_hook`] or a claim are processed.
+///
+/// * **since_block** the block time in seconds. It must be at least `clawback_window_secs` in the past.
+///
+/// * **start_after** the staker to start processing after.
+///
+/// * **limit** the max number of stakers to process.
+///
+/// ## Executor
+/// Only the owner can execute this.
+fn clawback_unclaimed_fees(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    since_block: u64,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let latest = env
+        .block
+        .time
+        .seconds()
+        .saturating_sub(config.clawback_window_secs);
+    if since_block > latest {
+        return Err(ContractError::ClawbackWindowNotPassed(latest));
+    }
+
+    let start_after = start_after
+        .map(|staker| deps.api.addr_validate(&staker))
+        .transpose()?;
+    let limit = limit
+        .unwrap_or(DEFAULT_CLAWBACK_LIMIT)
+        .min(MAX_CLAWBACK_LIMIT) as usize;
+    let stakers = STAKERS
+        .range(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let since_per_share = CLAIMABLE_PER_SHARE_HISTORY
+        .range(
+            deps.storage,
+            None,
+            Some(Bound::inclusive(since_block)),
+            Order::Descending,
+        )
+        .next()
+        .transpose()?
+        .map(|(_, claimable_per_share)| claimable_per_share)
+        .unwrap_or_default();
+
+    let mut amount = Uint128::zero();
+    let mut expired_stakers = 0u32;
+    for (staker, mut staker_info) in stakers {
+        let accrued_since = STAKE_CHECKPOINTS
+            .prefix(&staker)
+            .range(
+                deps.storage,
+                None,
+                Some(Bound::inclusive(since_block)),
+                Order::Descending,
+            )
+            .next()
+            .transpose()?
+            .map(|(_, checkpoint)| checkpoint.accrued_at(since_per_share))
+            .unwrap_or_default();
+        let expired = accrued_since.saturating_sub(staker_info.withdrawn);
+        if expired.is_zero() {
+            continue;
+        }
+
+        staker_info.withdrawn += expired;
+        STAKERS.save(deps.storage, &staker, &staker_info)?;
+        amount += expired;
+        expired_stakers += 1;
+    }
+
+    if amount.is_zero() {
+        return Err(ContractError::NothingToClawback {});
+    }
+
+    TOTAL_PENDING
+        .update::<_, ContractError>(deps.storage, |total| Ok(total.checked_sub(amount)?))?;
+
+    let transfer_msg = Asset {
+        info: config.astro_token,
+        amount,
+    }
+    .into_msg(&config.treasury)?;
+
+    Ok(Response::new().add_message(transfer_msg).add_attributes([
+        attr("action", "clawback_unclaimed_fees"),
+        attr("treasury", config.treasury),
+        attr("stakers", expired_stakers.to_string()),
+        attr("amount", amount),
+    ]))
+}
+
+/// Exposes all the queries available in the contract.
+///
+/// ## Queries
+/// * **QueryMsg::Config {}** Returns the contract configuration using a [`ConfigResponse`] object.
+///
+/// * **QueryMsg::Claimable { staker }** Returns the amount of ASTRO the specified staker can claim.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::Claimable { staker } => to_json_binary(&query_claimable(deps, staker)?),
+    }
+}
+
+/// Returns the contract configuration in a [`ConfigResponse`] object.
+fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        owner: config.owner,
+        treasury: config.treasury,
+        clawback_window_secs: config.clawback_window_secs,
+        astro_token: config.astro_token,
+        staked_astro: config.staked_astro.addr(),
+        router: config.router,
+        max_spread: config.max_spread,
+    })
+}
+
+/// Returns the amount of ASTRO the specified staker can claim.
+///
+/// * **staker** is the address of the staker.
+fn query_claimable(deps: Deps, staker: String) -> StdResult<Uint128> {
+    let staker: Addr = deps.api.addr_validate(&staker)?;
+    let claimable_per_share = CLAIMABLE_PER_SHARE.load(deps.storage)?;
+    let staker_info = match STAKERS.may_load(deps.storage, &staker)? {
+        Some(staker_info) => staker_info,
+        None => StakerInfo {
+            staked_amount: query_staked_amount(deps, &CONFIG.load(deps.storage)?, &staker)?,
+            ..Default::default()
+        },
+    };
+
+    Ok(staker_info.claimable(claimable_per_share))
+}
@@ -0,0 +1,36 @@
+use cosmwasm_std::{OverflowError, StdError};
+use thiserror::Error;
+
+/// This enum describes fee distributor contract errors
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
+    #[error("Incorrect max spread")]
+    IncorrectMaxSpread {},
+
+    #[error("Nothing to distribute")]
+    NothingToDistribute {},
+
+    #[error("There are no ASTRO stakers")]
+    NoStakers {},
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+
+    #[error("Cannot convert ASTRO to itself")]
+    CannotConvertAstro {},
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Rewards can only be clawed back since {0} or earlier")]
+    ClawbackWindowNotPassed(u64),
+
+    #[error("Nothing to claw back")]
+    NothingToClawback {},
+}
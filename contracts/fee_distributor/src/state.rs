@@ -0,0 +1,73 @@
+use astroport::asset::AssetInfo;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use cw4::Cw4Contract;
+use cw_storage_plus::{Item, Map};
+
+/// This structure stores the main parameters for the fee distributor contract.
+#[cw_serde]
+pub struct Config {
+    /// The contract owner
+    pub owner: Addr,
+    /// The address receiving clawed back fees
+    pub treasury: Addr,
+    /// The number of seconds rewards stay claimable before they can be clawed back
+    pub clawback_window_secs: u64,
+    /// The ASTRO token asset info
+    pub astro_token: AssetInfo,
+    /// The CW4 contract which holds the staked ASTRO balances
+    pub staked_astro: Cw4Contract,
+    /// The router contract used to convert collected fees to ASTRO
+    pub router: Addr,
+    /// The maximum spread used when converting fees to ASTRO
+    pub max_spread: Decimal,
+}
+
+/// This structure stores the distribution state of a single staker.
+#[cw_serde]
+#[derive(Default)]
+pub struct StakerInfo {
+    /// The staked amount since the last stake change
+    pub staked_amount: Uint128,
+    /// The value of [`CLAIMABLE_PER_SHARE`] at the last stake change
+    pub checkpoint_per_share: Decimal,
+    /// ASTRO accrued before the last stake change, including the claimed ASTRO
+    pub accrued: Uint128,
+    /// ASTRO claimed by the staker or clawed back to the treasury
+    pub withdrawn: Uint128,
+}
+
+impl StakerInfo {
+    /// Returns the total amount of ASTRO accrued by the staker when [`CLAIMABLE_PER_SHARE`]
+    /// reaches `claimable_per_share`.
+    pub fn accrued_at(&self, claimable_per_share: Decimal) -> Uint128 {
+        self.accrued + self.staked_amount * (claimable_per_share - self.checkpoint_per_share)
+    }
+
+    /// Returns the total amount of ASTRO the staker can claim.
+    pub fn claimable(&self, claimable_per_share: Decimal) -> Uint128 {
+        self.accrued_at(claimable_per_share) - self.withdrawn
+    }
+
+    /// Moves the ASTRO accrued so far to `accrued` and sets a new staked amount.
+    pub fn update_stake(&mut self, claimable_per_share: Decimal, staked_amount: Uint128) {
+        self.accrued = self.accrued_at(claimable_per_share);
+        self.checkpoint_per_share = claimable_per_share;
+        self.staked_amount = staked_amount;
+    }
+}
+
+/// Stores the contract config at the given key
+pub const CONFIG: Item<Config> = Item::new("config");
+/// The amount of ASTRO distributed per staked ASTRO since the contract was created
+pub const CLAIMABLE_PER_SHARE: Item<Decimal> = Item::new("claimable_per_share");
+/// Distributed ASTRO which has not been claimed yet
+pub const TOTAL_PENDING: Item<Uint128> = Item::new("total_pending");
+/// Stores the value of [`CLAIMABLE_PER_SHARE`] after the distributions made at a block time
+pub const CLAIMABLE_PER_SHARE_HISTORY: Map<u64, Decimal> = Map::new("claimable_per_share_history");
+/// Stores the distribution state of every staker
+pub const STAKERS: Map<&Addr, StakerInfo> = Map::new("stakers");
+/// Stores the distribution state of a staker after each stake change.
+/// Key: (staker, block time) -> staker info. The state of a staker before it was first checkpointed
+/// is stored at block time 0
+pub const STAKE_CHECKPOINTS: Map<(&Addr, u64), StakerInfo> = Map::new("stake_checkpoints");
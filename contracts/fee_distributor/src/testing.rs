@@ -0,0 +1,413 @@
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR,
+};
+use cosmwasm_std::{
+    coins, from_json, to_json_binary, Addr, BankMsg, ContractResult, CosmosMsg, Decimal, Env,
+    OwnedDeps, SystemResult, Uint128, WasmQuery,
+};
+use cw4::{Cw4QueryMsg, MemberChangedHookMsg, MemberDiff, MemberResponse, TotalWeightResponse};
+
+use astroport::asset::AssetInfo;
+use astroport::fee_distributor::{
+    ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg, DEFAULT_CLAWBACK_WINDOW_SECS,
+};
+
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+
+fn set_members(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, members: &[(&str, u64)]) {
+    let members: Vec<(String, u64)> = members
+        .iter()
+        .map(|(addr, weight)| (addr.to_string(), *weight))
+        .collect();
+
+    deps.querier.update_wasm(move |query| match query {
+        WasmQuery::Smart { contract_addr, msg } if contract_addr == "staked_astro" => {
+            match from_json(msg).unwrap() {
+                Cw4QueryMsg::Member { addr, .. } => SystemResult::Ok(ContractResult::Ok(
+                    to_json_binary(&MemberResponse {
+                        weight: members
+                            .iter()
+                            .find(|(member, _)| *member == addr)
+                            .map(|(_, weight)| *weight),
+                    })
+                    .unwrap(),
+                )),
+                Cw4QueryMsg::TotalWeight { .. } => SystemResult::Ok(ContractResult::Ok(
+                    to_json_binary(&TotalWeightResponse {
+                        weight: members.iter().map(|(_, weight)| weight).sum(),
+                    })
+                    .unwrap(),
+                )),
+                _ => unimplemented!(),
+            }
+        }
+        _ => unimplemented!(),
+    });
+}
+
+/// Updates the CW4 members and notifies the fee distributor about the changed stakes
+fn change_stakes(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    env: Env,
+    members: &[(&str, u64)],
+    diffs: &[(&str, Option<u64>, Option<u64>)],
+) {
+    set_members(deps, members);
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("staked_astro", &[]),
+        ExecuteMsg::MemberChangedHook(MemberChangedHookMsg {
+            diffs: diffs
+                .iter()
+                .map(|(addr, old, new)| MemberDiff::new(*addr, *old, *new))
+                .collect(),
+        }),
+    )
+    .unwrap();
+}
+
+fn claimable(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, staker: &str) -> Uint128 {
+    from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Claimable {
+                staker: staker.to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn proper_initialization() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        treasury: "treasury".to_string(),
+        clawback_window_secs: None,
+        astro_token: AssetInfo::NativeToken {
+            denom: "uastro".to_string(),
+        },
+        staked_astro: "staked_astro".to_string(),
+        router: "router".to_string(),
+        max_spread: Some(Decimal::percent(60)),
+    };
+    let err = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap_err();
+    assert_eq!(err, ContractError::IncorrectMaxSpread {});
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        treasury: "treasury".to_string(),
+        clawback_window_secs: None,
+        astro_token: AssetInfo::NativeToken {
+            denom: "uastro".to_string(),
+        },
+        staked_astro: "staked_astro".to_string(),
+        router: "router".to_string(),
+        max_spread: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let config: ConfigResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(
+        config,
+        ConfigResponse {
+            owner: Addr::unchecked("owner"),
+            treasury: Addr::unchecked("treasury"),
+            clawback_window_secs: DEFAULT_CLAWBACK_WINDOW_SECS,
+            astro_token: AssetInfo::NativeToken {
+                denom: "uastro".to_string(),
+            },
+            staked_astro: Addr::unchecked("staked_astro"),
+            router: Addr::unchecked("router"),
+            max_spread: Decimal::percent(5),
+        }
+    );
+}
+
+#[test]
+fn distribute_and_claim() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        treasury: "treasury".to_string(),
+        clawback_window_secs: None,
+        astro_token: AssetInfo::NativeToken {
+            denom: "uastro".to_string(),
+        },
+        staked_astro: "staked_astro".to_string(),
+        router: "router".to_string(),
+        max_spread: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    set_members(&mut deps, &[("alice", 100), ("bob", 300)]);
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        ExecuteMsg::Distribute {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NothingToDistribute {});
+
+    deps.querier
+        .update_balance(MOCK_CONTRACT_ADDR, coins(400, "uastro"));
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        ExecuteMsg::Distribute {},
+    )
+    .unwrap();
+    assert_eq!(claimable(&deps, "alice"), Uint128::new(100));
+    assert_eq!(claimable(&deps, "bob"), Uint128::new(300));
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::Claim {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "alice".to_string(),
+            amount: coins(100, "uastro"),
+        })
+    );
+    deps.querier
+        .update_balance(MOCK_CONTRACT_ADDR, coins(300, "uastro"));
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::Claim {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NothingToClaim {});
+
+    // Alice increases her stake and Bob unstakes everything
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        ExecuteMsg::MemberChangedHook(MemberChangedHookMsg { diffs: vec![] }),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    change_stakes(
+        &mut deps,
+        mock_env(),
+        &[("alice", 300)],
+        &[("alice", Some(100), Some(300)), ("bob", Some(300), None)],
+    );
+    deps.querier
+        .update_balance(MOCK_CONTRACT_ADDR, coins(600, "uastro"));
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        ExecuteMsg::Distribute {},
+    )
+    .unwrap();
+
+    // Bob keeps the rewards accrued before unstaking
+    assert_eq!(claimable(&deps, "alice"), Uint128::new(300));
+    assert_eq!(claimable(&deps, "bob"), Uint128::new(300));
+
+    // Already distributed ASTRO is not distributed twice
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        ExecuteMsg::Distribute {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NothingToDistribute {});
+}
+
+#[test]
+fn clawback_unclaimed_fees() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        treasury: "treasury".to_string(),
+        clawback_window_secs: Some(1000),
+        astro_token: AssetInfo::NativeToken {
+            denom: "uastro".to_string(),
+        },
+        staked_astro: "staked_astro".to_string(),
+        router: "router".to_string(),
+        max_spread: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let mut env = mock_env();
+    change_stakes(
+        &mut deps,
+        env.clone(),
+        &[("alice", 100), ("bob", 300)],
+        &[("alice", None, Some(100)), ("bob", None, Some(300))],
+    );
+    let distributed_at = env.block.time.seconds();
+    deps.querier
+        .update_balance(MOCK_CONTRACT_ADDR, coins(400, "uastro"));
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &[]),
+        ExecuteMsg::Distribute {},
+    )
+    .unwrap();
+
+    // Alice claims her rewards while Bob leaves them unclaimed
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        ExecuteMsg::Claim {},
+    )
+    .unwrap();
+    deps.querier
+        .update_balance(MOCK_CONTRACT_ADDR, coins(300, "uastro"));
+
+    let clawback = ExecuteMsg::ClawbackUnclaimedFees {
+        since_block: distributed_at,
+        start_after: None,
+        limit: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &[]),
+        clawback.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        clawback.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ClawbackWindowNotPassed(distributed_at - 1000)
+    );
+
+    // Bob doubles his stake and both stakers get fresh rewards
+    env.block.time = env.block.time.plus_seconds(200);
+    change_stakes(
+        &mut deps,
+        env.clone(),
+        &[("alice", 100), ("bob", 600)],
+        &[("bob", Some(300), Some(600))],
+    );
+    env.block.time = env.block.time.plus_seconds(300);
+    deps.querier
+        .update_balance(MOCK_CONTRACT_ADDR, coins(1000, "uastro"));
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &[]),
+        ExecuteMsg::Distribute {},
+    )
+    .unwrap();
+    assert_eq!(claimable(&deps, "alice"), Uint128::new(100));
+    assert_eq!(claimable(&deps, "bob"), Uint128::new(900));
+
+    // Only the rewards Bob left unclaimed since the first distribution are clawed back
+    env.block.time = env.block.time.plus_seconds(500);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        clawback.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "treasury".to_string(),
+            amount: coins(300, "uastro"),
+        })
+    );
+    assert_eq!(claimable(&deps, "alice"), Uint128::new(100));
+    assert_eq!(claimable(&deps, "bob"), Uint128::new(600));
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        clawback.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NothingToClawback {});
+
+    // The fresh rewards stay claimable
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("bob", &[]),
+        ExecuteMsg::Claim {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "bob".to_string(),
+            amount: coins(600, "uastro"),
+        })
+    );
+
+    // Once the second distribution expires only Alice's unclaimed rewards are clawed back
+    env.block.time = env.block.time.plus_seconds(1000);
+    let clawback = ExecuteMsg::ClawbackUnclaimedFees {
+        since_block: distributed_at + 500,
+        start_after: None,
+        limit: Some(1),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        clawback,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "treasury".to_string(),
+            amount: coins(100, "uastro"),
+        })
+    );
+    assert_eq!(claimable(&deps, "alice"), Uint128::zero());
+
+    let err = execute(
+        deps.as_mut(),
+        env,
+        mock_info("owner", &[]),
+        ExecuteMsg::ClawbackUnclaimedFees {
+            since_block: distributed_at + 500,
+            start_after: Some("alice".to_string()),
+            limit: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NothingToClawback {});
+}
@@ -0,0 +1,10 @@
+use cosmwasm_schema::write_api;
+
+use astroport::lp_manager::{ExecuteMsg, InstantiateMsg};
+
+fn main() {
+    write_api! {
+        instantiate: InstantiateMsg,
+        execute: ExecuteMsg
+    }
+}
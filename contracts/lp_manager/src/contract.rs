@@ -0,0 +1,240 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    attr, to_json_binary, wasm_execute, DepsMut, Env, MessageInfo, Reply, Response, StdResult,
+    SubMsg, SubMsgResult,
+};
+use cw2::set_contract_version;
+use cw20::Cw20ExecuteMsg;
+
+use astroport::asset::{AssetInfoExt, PairInfo};
+use astroport::lp_manager::{CallbackMsg, ExecuteMsg, InstantiateMsg, WithdrawalRequest};
+use astroport::pair::{Cw20HookMsg as PairCw20HookMsg, QueryMsg as PairQueryMsg};
+
+use crate::error::ContractError;
+use crate::state::{PendingWithdrawal, PENDING_WITHDRAWALS};
+
+/// Contract name that is used for migration.
+const CONTRACT_NAME: &str = "astroport-lp-manager";
+/// Contract version that is used for migration.
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// A `reply` call code ID used to report a failed withdrawal.
+const WITHDRAW_LIQUIDITY_REPLY_ID: u64 = 1;
+
+/// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+/// Exposes execute functions available in the contract.
+///
+/// ## Variants
+/// * **ExecuteMsg::BatchWithdrawLiquidity { withdrawals }** Withdraws liquidity from several
+/// pairs and sends the withdrawn assets to the sender.
+///
+/// * **ExecuteMsg::Callback(msg)** Handles the callbacks of a batch withdrawal.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::BatchWithdrawLiquidity { withdrawals } => {
+            batch_withdraw_liquidity(deps, env, info, withdrawals)
+        }
+        ExecuteMsg::Callback(msg) => {
+            if info.sender != env.contract.address {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            match msg {
+                CallbackMsg::CheckWithdrawal { receiver } => {
+                    check_withdrawal(deps, env, receiver.to_string())
+                }
+            }
+        }
+    }
+}
+
+/// Withdraws liquidity from each pair in `withdrawals`. For every withdrawal the LP tokens are
+/// pulled from the sender using the allowance given to the LP manager, sent to the pair and the
+/// returned assets are checked against `min_assets` before being forwarded to the sender.
+/// The whole batch is reverted if any withdrawal fails.
+///
+/// * **withdrawals** withdrawals in execution order.
+pub fn batch_withdraw_liquidity(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    withdrawals: Vec<WithdrawalRequest>,
+) -> Result<Response, ContractError> {
+    if withdrawals.is_empty() {
+        return Err(ContractError::EmptyWithdrawals {});
+    }
+
+    if PENDING_WITHDRAWALS.exists(deps.storage) {
+        return Err(ContractError::WithdrawalInProgress {});
+    }
+
+    let mut pending_withdrawals = vec![];
+    let mut messages = vec![];
+    for withdrawal in withdrawals {
+        let pair = deps.api.addr_validate(&withdrawal.pair)?;
+        if withdrawal.lp_amount.is_zero() {
+            return Err(ContractError::ZeroLpAmount(pair.to_string()));
+        }
+
+        let pair_info: PairInfo = deps
+            .querier
+            .query_wasm_smart(&pair, &PairQueryMsg::Pair {})?;
+
+        for min_asset in &withdrawal.min_assets {
+            if !pair_info.asset_infos.contains(&min_asset.info) {
+                return Err(ContractError::AssetNotInPair {
+                    pair: pair.to_string(),
+                    asset: min_asset.info.to_string(),
+                });
+            }
+        }
+
+        // Every checked withdrawal is forwarded right away, so the balances before each
+        // withdrawal are the balances at the start of the batch
+        let balances_before = pair_info
+            .asset_infos
+            .iter()
+            .map(|asset_info| {
+                let balance = asset_info.query_pool(&deps.querier, &env.contract.address)?;
+                Ok(asset_info.with_balance(balance))
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+
+        messages.push(SubMsg::new(wasm_execute(
+            &pair_info.liquidity_token,
+            &Cw20ExecuteMsg::TransferFrom {
+                owner: info.sender.to_string(),
+                recipient: env.contract.address.to_string(),
+                amount: withdrawal.lp_amount,
+            },
+            vec![],
+        )?));
+        messages.push(SubMsg::reply_on_error(
+            wasm_execute(
+                &pair_info.liquidity_token,
+                &Cw20ExecuteMsg::Send {
+                    contract: pair.to_string(),
+                    amount: withdrawal.lp_amount,
+                    msg: to_json_binary(&PairCw20HookMsg::WithdrawLiquidity { assets: vec![] })?,
+                },
+                vec![],
+            )?,
+            WITHDRAW_LIQUIDITY_REPLY_ID,
+        ));
+        messages.push(SubMsg::new(
+            CallbackMsg::CheckWithdrawal {
+                receiver: info.sender.clone(),
+            }
+            .to_cosmos_msg(&env)?,
+        ));
+
+        pending_withdrawals.push(PendingWithdrawal {
+            pair,
+            balances_before,
+            min_assets: withdrawal.min_assets,
+        });
+    }
+
+    let withdrawals_count = pending_withdrawals.len();
+    PENDING_WITHDRAWALS.save(deps.storage, &pending_withdrawals)?;
+
+    Ok(Response::new().add_submessages(messages).add_attributes([
+        attr("action", "batch_withdraw_liquidity"),
+        attr("sender", info.sender),
+        attr("withdrawals", withdrawals_count.to_string()),
+    ]))
+}
+
+/// Checks the assets returned by the oldest pending withdrawal against its `min_assets` and
+/// forwards them to the receiver.
+///
+/// * **receiver** receiver of the withdrawn assets.
+///
+/// ## Executor
+/// Only the LP manager itself can execute this.
+fn check_withdrawal(deps: DepsMut, env: Env, receiver: String) -> Result<Response, ContractError> {
+    let mut pending_withdrawals = PENDING_WITHDRAWALS.load(deps.storage)?;
+    let withdrawal = pending_withdrawals.remove(0);
+    if pending_withdrawals.is_empty() {
+        PENDING_WITHDRAWALS.remove(deps.storage);
+    } else {
+        PENDING_WITHDRAWALS.save(deps.storage, &pending_withdrawals)?;
+    }
+
+    let mut messages = vec![];
+    let mut attrs = vec![
+        attr("action", "check_withdrawal"),
+        attr("pair", &withdrawal.pair),
+    ];
+    for asset in withdrawal.balances_before {
+        let received = asset
+            .info
+            .query_pool(&deps.querier, &env.contract.address)?
+            .checked_sub(asset.amount)?;
+
+        if let Some(min_asset) = withdrawal
+            .min_assets
+            .iter()
+            .find(|min_asset| min_asset.info == asset.info)
+        {
+            if received < min_asset.amount {
+                return Err(ContractError::WithdrawSlippageViolation {
+                    pair: withdrawal.pair.to_string(),
+                    asset: asset.info.to_string(),
+                    received,
+                    expected: min_asset.amount,
+                });
+            }
+        }
+
+        if !received.is_zero() {
+            let received = asset.info.with_balance(received);
+            attrs.push(attr("withdrawn_asset", received.to_string()));
+            messages.push(received.into_msg(&receiver)?);
+        }
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(attrs)
+        .add_attribute("receiver", receiver))
+}
+
+/// The entry point to the contract for processing replies from submessages.
+/// A failed withdrawal is reported with the pair it targeted and makes the whole batch revert.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg {
+        Reply {
+            id: WITHDRAW_LIQUIDITY_REPLY_ID,
+            result: SubMsgResult::Err(reason),
+        } => {
+            let pending_withdrawals = PENDING_WITHDRAWALS.load(deps.storage)?;
+            let pair = pending_withdrawals
+                .first()
+                .map(|withdrawal| withdrawal.pair.to_string())
+                .unwrap_or_default();
+
+            Err(ContractError::WithdrawalFailed { pair, reason })
+        }
+        _ => Err(ContractError::FailedToParseReply {}),
+    }
+}
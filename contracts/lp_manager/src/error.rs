@@ -0,0 +1,41 @@
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use thiserror::Error;
+
+/// This enum describes LP manager contract errors
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("No withdrawals specified")]
+    EmptyWithdrawals {},
+
+    #[error("Withdrawal from {0} must burn a non-zero amount of LP tokens")]
+    ZeroLpAmount(String),
+
+    #[error("Asset {asset} is not in pair {pair}")]
+    AssetNotInPair { pair: String, asset: String },
+
+    #[error("A batch withdrawal is already in progress")]
+    WithdrawalInProgress {},
+
+    #[error("Withdrawal from {pair} failed: {reason}")]
+    WithdrawalFailed { pair: String, reason: String },
+
+    #[error("Withdrawal from {pair} returned {received} {asset} but expected at least {expected}")]
+    WithdrawSlippageViolation {
+        pair: String,
+        asset: String,
+        received: Uint128,
+        expected: Uint128,
+    },
+
+    #[error("Failed to parse or process reply message")]
+    FailedToParseReply {},
+}
@@ -0,0 +1,18 @@
+use astroport::asset::Asset;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Addr;
+use cw_storage_plus::Item;
+
+/// This structure stores a withdrawal which was not checked yet.
+#[cw_serde]
+pub struct PendingWithdrawal {
+    /// The pair liquidity is withdrawn from
+    pub pair: Addr,
+    /// The pair assets with the LP manager balances before the withdrawal
+    pub balances_before: Vec<Asset>,
+    /// The minimum amounts of pair assets the withdrawal must return
+    pub min_assets: Vec<Asset>,
+}
+
+/// Stores the withdrawals of the batch being executed in execution order
+pub const PENDING_WITHDRAWALS: Item<Vec<PendingWithdrawal>> = Item::new("pending_withdrawals");
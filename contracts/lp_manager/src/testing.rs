@@ -0,0 +1,326 @@
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR,
+};
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, BankMsg, Coin, ContractResult, CosmosMsg, OwnedDeps, Reply,
+    SubMsg, SubMsgResult, SystemResult, Uint128, WasmMsg, WasmQuery,
+};
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg};
+
+use astroport::asset::{native_asset_info, token_asset_info, AssetInfoExt, PairInfo};
+use astroport::factory::PairType;
+use astroport::lp_manager::{CallbackMsg, ExecuteMsg, InstantiateMsg, WithdrawalRequest};
+use astroport::pair::{Cw20HookMsg as PairCw20HookMsg, QueryMsg as PairQueryMsg};
+
+use crate::contract::{execute, instantiate, reply};
+use crate::error::ContractError;
+use crate::state::PENDING_WITHDRAWALS;
+
+/// Mocks two pairs: `pair1` with uusd and a cw20 token and `pair2` with uusd and uluna.
+/// The LP manager holds `token_balance` of the cw20 token.
+fn mock_pairs(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, token_balance: u128) {
+    deps.querier.update_wasm(move |query| match query {
+        WasmQuery::Smart { contract_addr, msg } if contract_addr == "token" => {
+            match from_json(msg).unwrap() {
+                Cw20QueryMsg::Balance { .. } => SystemResult::Ok(ContractResult::Ok(
+                    to_json_binary(&BalanceResponse {
+                        balance: Uint128::new(token_balance),
+                    })
+                    .unwrap(),
+                )),
+                _ => unimplemented!(),
+            }
+        }
+        WasmQuery::Smart { contract_addr, msg } => match from_json(msg).unwrap() {
+            PairQueryMsg::Pair {} => {
+                let (asset_infos, liquidity_token) = match contract_addr.as_str() {
+                    "pair1" => (
+                        vec![
+                            native_asset_info("uusd".to_string()),
+                            token_asset_info(Addr::unchecked("token")),
+                        ],
+                        "lp_token1",
+                    ),
+                    _ => (
+                        vec![
+                            native_asset_info("uusd".to_string()),
+                            native_asset_info("uluna".to_string()),
+                        ],
+                        "lp_token2",
+                    ),
+                };
+
+                SystemResult::Ok(ContractResult::Ok(
+                    to_json_binary(&PairInfo {
+                        asset_infos,
+                        contract_addr: Addr::unchecked(contract_addr),
+                        liquidity_token: Addr::unchecked(liquidity_token),
+                        pair_type: PairType::Xyk {},
+                        creator: None,
+                        creation_height: 0,
+                        creation_time: 0,
+                    })
+                    .unwrap(),
+                ))
+            }
+            _ => unimplemented!(),
+        },
+        _ => unimplemented!(),
+    });
+}
+
+#[test]
+fn batch_withdraw_liquidity() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        InstantiateMsg {},
+    )
+    .unwrap();
+    mock_pairs(&mut deps, 0);
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("user", &[]),
+        ExecuteMsg::BatchWithdrawLiquidity {
+            withdrawals: vec![],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::EmptyWithdrawals {});
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("user", &[]),
+        ExecuteMsg::BatchWithdrawLiquidity {
+            withdrawals: vec![WithdrawalRequest {
+                pair: "pair1".to_string(),
+                lp_amount: Uint128::new(100),
+                min_assets: vec![native_asset_info("uluna".to_string()).with_balance(1u8)],
+            }],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::AssetNotInPair {
+            pair: "pair1".to_string(),
+            asset: "uluna".to_string(),
+        }
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("user", &[]),
+        ExecuteMsg::BatchWithdrawLiquidity {
+            withdrawals: vec![
+                WithdrawalRequest {
+                    pair: "pair1".to_string(),
+                    lp_amount: Uint128::new(100),
+                    min_assets: vec![token_asset_info(Addr::unchecked("token")).with_balance(50u8)],
+                },
+                WithdrawalRequest {
+                    pair: "pair2".to_string(),
+                    lp_amount: Uint128::new(200),
+                    min_assets: vec![native_asset_info("uluna".to_string()).with_balance(300u16)],
+                },
+            ],
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 6);
+    assert_eq!(
+        res.messages[..3],
+        [
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "lp_token1".to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: "user".to_string(),
+                    recipient: MOCK_CONTRACT_ADDR.to_string(),
+                    amount: Uint128::new(100),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            SubMsg::reply_on_error(
+                WasmMsg::Execute {
+                    contract_addr: "lp_token1".to_string(),
+                    msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                        contract: "pair1".to_string(),
+                        amount: Uint128::new(100),
+                        msg: to_json_binary(&PairCw20HookMsg::WithdrawLiquidity { assets: vec![] })
+                            .unwrap(),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                },
+                1
+            ),
+            SubMsg::new(
+                CallbackMsg::CheckWithdrawal {
+                    receiver: Addr::unchecked("user"),
+                }
+                .to_cosmos_msg(&env)
+                .unwrap()
+            ),
+        ]
+    );
+    assert_eq!(
+        PENDING_WITHDRAWALS
+            .load(deps.as_ref().storage)
+            .unwrap()
+            .len(),
+        2
+    );
+
+    let check_withdrawal = ExecuteMsg::Callback(CallbackMsg::CheckWithdrawal {
+        receiver: Addr::unchecked("user"),
+    });
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("user", &[]),
+        check_withdrawal.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // The first pair returns 80 uusd and 60 tokens
+    mock_pairs(&mut deps, 60);
+    deps.querier
+        .update_balance(MOCK_CONTRACT_ADDR, vec![Coin::new(80, "uusd")]);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        check_withdrawal.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        [
+            SubMsg::new(BankMsg::Send {
+                to_address: "user".to_string(),
+                amount: vec![Coin::new(80, "uusd")],
+            }),
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "token".to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "user".to_string(),
+                    amount: Uint128::new(60),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+        ]
+    );
+
+    // The second pair returns less uluna than expected
+    mock_pairs(&mut deps, 0);
+    deps.querier.update_balance(
+        MOCK_CONTRACT_ADDR,
+        vec![Coin::new(150, "uusd"), Coin::new(250, "uluna")],
+    );
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        check_withdrawal,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::WithdrawSlippageViolation {
+            pair: "pair2".to_string(),
+            asset: "uluna".to_string(),
+            received: Uint128::new(250),
+            expected: Uint128::new(300),
+        }
+    );
+
+    // A failed withdrawal reports the pair it was sent to
+    let err = reply(
+        deps.as_mut(),
+        env,
+        Reply {
+            id: 1,
+            result: SubMsgResult::Err("Insufficient allowance".to_string()),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::WithdrawalFailed {
+            pair: "pair2".to_string(),
+            reason: "Insufficient allowance".to_string(),
+        }
+    );
+}
+
+#[test]
+fn withdrawn_assets_are_not_mixed_between_pairs() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        InstantiateMsg {},
+    )
+    .unwrap();
+    mock_pairs(&mut deps, 0);
+
+    // The LP manager holds funds which don't belong to the batch
+    deps.querier
+        .update_balance(MOCK_CONTRACT_ADDR, vec![Coin::new(1000, "uusd")]);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("user", &[]),
+        ExecuteMsg::BatchWithdrawLiquidity {
+            withdrawals: vec![WithdrawalRequest {
+                pair: "pair2".to_string(),
+                lp_amount: Uint128::new(200),
+                min_assets: vec![native_asset_info("uusd".to_string()).with_balance(100u8)],
+            }],
+        },
+    )
+    .unwrap();
+
+    deps.querier.update_balance(
+        MOCK_CONTRACT_ADDR,
+        vec![Coin::new(1100, "uusd"), Coin::new(50, "uluna")],
+    );
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::Callback(CallbackMsg::CheckWithdrawal {
+            receiver: Addr::unchecked("user"),
+        }),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|msg| msg.msg)
+            .collect::<Vec<_>>(),
+        [
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "user".to_string(),
+                amount: vec![Coin::new(100, "uusd")],
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "user".to_string(),
+                amount: vec![Coin::new(50, "uluna")],
+            }),
+        ]
+    );
+    assert!(!PENDING_WITHDRAWALS.exists(deps.as_ref().storage));
+}
@@ -0,0 +1,261 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    attr, to_json_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    Uint128,
+};
+use cw2::set_contract_version;
+use sha2::{Digest, Sha256};
+
+use astroport::asset::{AssetInfo, AssetInfoExt};
+use astroport::merkle_distributor::{
+    ConfigResponse, Distribution, ExecuteMsg, InstantiateMsg, QueryMsg,
+};
+
+use crate::error::ContractError;
+use crate::state::{Config, CLAIMED, CONFIG, DISTRIBUTION};
+
+/// Contract name that is used for migration.
+const CONTRACT_NAME: &str = "astroport-merkle-distributor";
+/// Contract version that is used for migration.
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let config = Config {
+        owner: deps.api.addr_validate(&msg.owner)?,
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(
+        Response::new()
+            .add_attributes([attr("action", "instantiate"), attr("owner", config.owner)]),
+    )
+}
+
+/// Exposes execute functions available in the contract.
+///
+/// ## Variants
+/// * **ExecuteMsg::SetMerkleRoot {
+///             root,
+///             total_amount,
+///             asset,
+///             expiry,
+///         }** Sets the Merkle root of the distribution.
+///
+/// * **ExecuteMsg::Claim { amount, proof }** Sends the allocation proved by `proof` to the caller.
+///
+/// * **ExecuteMsg::Clawback {}** Sends the unclaimed assets to the owner after expiry.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::SetMerkleRoot {
+            root,
+            total_amount,
+            asset,
+            expiry,
+        } => set_merkle_root(deps, env, info, root, total_amount, asset, expiry),
+        ExecuteMsg::Claim { amount, proof } => claim(deps, env, info, amount, proof),
+        ExecuteMsg::Clawback {} => clawback(deps, env),
+    }
+}
+
+/// Sets the Merkle root of the distribution. Each contract instance runs a single distribution.
+///
+/// * **root** Merkle root built from `sha256(address || amount)` leaves.
+///
+/// * **total_amount** sum of all the amounts in the tree.
+///
+/// * **asset** distributed asset.
+///
+/// * **expiry** timestamp (in seconds) after which claims are no longer accepted.
+///
+/// ## Executor
+/// Only the owner can execute this.
+pub fn set_merkle_root(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    root: [u8; 32],
+    total_amount: Uint128,
+    asset: AssetInfo,
+    expiry: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if DISTRIBUTION.exists(deps.storage) {
+        return Err(ContractError::MerkleRootAlreadySet {});
+    }
+
+    if expiry <= env.block.time.seconds() {
+        return Err(ContractError::InvalidExpiry {});
+    }
+
+    asset.check(deps.api)?;
+
+    let distribution = Distribution {
+        root,
+        total_amount,
+        claimed_amount: Uint128::zero(),
+        asset,
+        expiry,
+        clawed_back: false,
+    };
+    DISTRIBUTION.save(deps.storage, &distribution)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "set_merkle_root"),
+        attr("root", Binary::from(root.as_slice()).to_base64()),
+        attr("total_amount", total_amount),
+        attr("asset", distribution.asset.to_string()),
+        attr("expiry", expiry.to_string()),
+    ]))
+}
+
+/// Sends `amount` of the distributed asset to the caller if `proof` links the
+/// `sha256(address || amount)` leaf to the stored Merkle root.
+///
+/// * **amount** amount allocated to the caller in the Merkle tree.
+///
+/// * **proof** sibling hashes from the leaf up to the root.
+pub fn claim(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    proof: Vec<[u8; 32]>,
+) -> Result<Response, ContractError> {
+    let mut distribution = DISTRIBUTION
+        .may_load(deps.storage)?
+        .ok_or(ContractError::MerkleRootNotSet {})?;
+
+    if env.block.time.seconds() >= distribution.expiry {
+        return Err(ContractError::Expired(distribution.expiry));
+    }
+
+    if CLAIMED.has(deps.storage, &info.sender) {
+        return Err(ContractError::AlreadyClaimed {});
+    }
+
+    if compute_root(&info.sender, amount, &proof) != distribution.root {
+        return Err(ContractError::InvalidProof {});
+    }
+
+    distribution.claimed_amount = distribution.claimed_amount.checked_add(amount)?;
+    if distribution.claimed_amount > distribution.total_amount {
+        return Err(ContractError::ExceedsTotalAmount {});
+    }
+    DISTRIBUTION.save(deps.storage, &distribution)?;
+    CLAIMED.save(deps.storage, &info.sender, &true)?;
+
+    let transfer_msg = distribution
+        .asset
+        .with_balance(amount)
+        .into_msg(&info.sender)?;
+
+    Ok(Response::new().add_message(transfer_msg).add_attributes([
+        attr("action", "claim"),
+        attr("address", info.sender),
+        attr("amount", amount),
+    ]))
+}
+
+/// Sends the unclaimed assets to the owner once the distribution is expired.
+///
+/// ## Executor
+/// Anyone can execute this after expiry.
+pub fn clawback(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut distribution = DISTRIBUTION
+        .may_load(deps.storage)?
+        .ok_or(ContractError::MerkleRootNotSet {})?;
+
+    if env.block.time.seconds() < distribution.expiry {
+        return Err(ContractError::NotExpired(distribution.expiry));
+    }
+
+    if distribution.clawed_back {
+        return Err(ContractError::AlreadyClawedBack {});
+    }
+
+    distribution.clawed_back = true;
+    DISTRIBUTION.save(deps.storage, &distribution)?;
+
+    let unclaimed = distribution
+        .total_amount
+        .checked_sub(distribution.claimed_amount)?;
+
+    let mut response = Response::new().add_attributes([
+        attr("action", "clawback"),
+        attr("recipient", &config.owner),
+        attr("amount", unclaimed),
+    ]);
+    if !unclaimed.is_zero() {
+        response = response.add_message(
+            distribution
+                .asset
+                .with_balance(unclaimed)
+                .into_msg(&config.owner)?,
+        );
+    }
+
+    Ok(response)
+}
+
+/// Folds the proof into the root starting from the `sha256(address || amount)` leaf.
+/// Each pair of hashes is sorted before hashing, so the proof doesn't need to specify
+/// the side of its siblings.
+fn compute_root(address: &Addr, amount: Uint128, proof: &[[u8; 32]]) -> [u8; 32] {
+    let leaf: [u8; 32] = Sha256::digest(format!("{address}{amount}").as_bytes()).into();
+
+    proof.iter().fold(leaf, |hash, sibling| {
+        let (first, second) = if hash <= *sibling {
+            (hash, *sibling)
+        } else {
+            (*sibling, hash)
+        };
+
+        Sha256::new()
+            .chain_update(first)
+            .chain_update(second)
+            .finalize()
+            .into()
+    })
+}
+
+/// Exposes all the queries available in the contract.
+///
+/// ## Queries
+/// * **QueryMsg::Config {}** Returns the contract configuration using a [`ConfigResponse`] object.
+///
+/// * **QueryMsg::IsClaimed { address }** Returns whether the address has already claimed its
+/// allocation.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&ConfigResponse {
+            owner: CONFIG.load(deps.storage)?.owner,
+            distribution: DISTRIBUTION.may_load(deps.storage)?,
+        }),
+        QueryMsg::IsClaimed { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_json_binary(&CLAIMED.has(deps.storage, &address))
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use cosmwasm_std::{OverflowError, StdError};
+use thiserror::Error;
+
+/// This enum describes Merkle distributor contract errors
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Merkle root is already set")]
+    MerkleRootAlreadySet {},
+
+    #[error("Merkle root is not set")]
+    MerkleRootNotSet {},
+
+    #[error("Expiry must be in the future")]
+    InvalidExpiry {},
+
+    #[error("Distribution expired at {0}")]
+    Expired(u64),
+
+    #[error("Distribution is active until {0}")]
+    NotExpired(u64),
+
+    #[error("Allocation is already claimed")]
+    AlreadyClaimed {},
+
+    #[error("Invalid Merkle proof")]
+    InvalidProof {},
+
+    #[error("Unclaimed assets are already clawed back")]
+    AlreadyClawedBack {},
+
+    #[error("Claimed amount exceeds the distribution total amount")]
+    ExceedsTotalAmount {},
+}
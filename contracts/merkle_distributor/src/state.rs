@@ -0,0 +1,18 @@
+use astroport::merkle_distributor::Distribution;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Addr;
+use cw_storage_plus::{Item, Map};
+
+/// This structure stores the main parameters for the Merkle distributor contract.
+#[cw_serde]
+pub struct Config {
+    /// The address allowed to set the Merkle root
+    pub owner: Addr,
+}
+
+/// Stores the contract config at the given key
+pub const CONFIG: Item<Config> = Item::new("config");
+/// Stores the distribution set by the owner
+pub const DISTRIBUTION: Item<Distribution> = Item::new("distribution");
+/// Stores the addresses which already claimed their allocation
+pub const CLAIMED: Map<&Addr, bool> = Map::new("claimed");
@@ -0,0 +1,251 @@
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier};
+use cosmwasm_std::{
+    from_json, Addr, BankMsg, Coin, CosmosMsg, Env, MemoryStorage, OwnedDeps, Timestamp, Uint128,
+};
+use sha2::{Digest, Sha256};
+
+use astroport::asset::AssetInfo;
+use astroport::merkle_distributor::{ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+
+const EXPIRY: u64 = 2_000_000_000;
+
+fn leaf(address: &str, amount: u128) -> [u8; 32] {
+    Sha256::digest(format!("{address}{amount}").as_bytes()).into()
+}
+
+fn hash_pair(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    Sha256::new()
+        .chain_update(first)
+        .chain_update(second)
+        .finalize()
+        .into()
+}
+
+/// Builds a tree with three allocations. The last leaf is paired with itself.
+fn merkle_tree() -> ([u8; 32], Vec<Vec<[u8; 32]>>) {
+    let leaves = [leaf("alice", 1000), leaf("bob", 2000), leaf("carol", 3000)];
+    let left = hash_pair(leaves[0], leaves[1]);
+    let right = hash_pair(leaves[2], leaves[2]);
+
+    let root = hash_pair(left, right);
+    let proofs = vec![
+        vec![leaves[1], right],
+        vec![leaves[0], right],
+        vec![leaves[2], left],
+    ];
+
+    (root, proofs)
+}
+
+fn env_at(time: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(time);
+    env
+}
+
+fn setup() -> (
+    OwnedDeps<MemoryStorage, MockApi, MockQuerier>,
+    Vec<Vec<[u8; 32]>>,
+) {
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        InstantiateMsg {
+            owner: "owner".to_string(),
+        },
+    )
+    .unwrap();
+
+    let (root, proofs) = merkle_tree();
+    let set_root_msg = ExecuteMsg::SetMerkleRoot {
+        root,
+        total_amount: Uint128::new(6000),
+        asset: AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        expiry: EXPIRY,
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        set_root_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        set_root_msg.clone(),
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        set_root_msg,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::MerkleRootAlreadySet {});
+
+    (deps, proofs)
+}
+
+#[test]
+fn claim() {
+    let (mut deps, proofs) = setup();
+
+    // The proof only matches the allocated amount
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::Claim {
+            amount: Uint128::new(2000),
+            proof: proofs[0].clone(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidProof {});
+
+    // Another address can not use the proof
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("bob", &[]),
+        ExecuteMsg::Claim {
+            amount: Uint128::new(1000),
+            proof: proofs[0].clone(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidProof {});
+
+    for (address, amount, proof) in [("alice", 1000, &proofs[0]), ("carol", 3000, &proofs[2])] {
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(address, &[]),
+            ExecuteMsg::Claim {
+                amount: Uint128::new(amount),
+                proof: proof.clone(),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: address.to_string(),
+                amount: vec![Coin::new(amount, "uusd")],
+            })
+        );
+    }
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::Claim {
+            amount: Uint128::new(1000),
+            proof: proofs[0].clone(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AlreadyClaimed {});
+
+    let is_claimed = |address: &str| -> bool {
+        from_json(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::IsClaimed {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    assert!(is_claimed("alice"));
+    assert!(!is_claimed("bob"));
+    assert!(is_claimed("carol"));
+
+    let config: ConfigResponse =
+        from_json(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(config.owner, Addr::unchecked("owner"));
+    assert_eq!(
+        config.distribution.unwrap().claimed_amount,
+        Uint128::new(4000)
+    );
+}
+
+#[test]
+fn clawback_after_expiry() {
+    let (mut deps, proofs) = setup();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::Claim {
+            amount: Uint128::new(1000),
+            proof: proofs[0].clone(),
+        },
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        ExecuteMsg::Clawback {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NotExpired(EXPIRY));
+
+    let err = execute(
+        deps.as_mut(),
+        env_at(EXPIRY),
+        mock_info("bob", &[]),
+        ExecuteMsg::Claim {
+            amount: Uint128::new(2000),
+            proof: proofs[1].clone(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Expired(EXPIRY));
+
+    let res = execute(
+        deps.as_mut(),
+        env_at(EXPIRY),
+        mock_info("anyone", &[]),
+        ExecuteMsg::Clawback {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "owner".to_string(),
+            amount: vec![Coin::new(5000, "uusd")],
+        })
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        env_at(EXPIRY),
+        mock_info("anyone", &[]),
+        ExecuteMsg::Clawback {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AlreadyClawedBack {});
+}
@@ -5,33 +5,59 @@ use std::vec;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, from_json, to_json_binary, Addr, Binary, CosmosMsg, Decimal, Decimal256, Deps, DepsMut,
-    Env, Fraction, MessageInfo, QuerierWrapper, Reply, ReplyOn, Response, StdError, StdResult,
-    SubMsg, SubMsgResponse, SubMsgResult, Uint128, Uint256, Uint64, WasmMsg,
+    attr, from_json, to_json_binary, wasm_execute, Addr, Binary, Coin, CosmosMsg, Decimal,
+    Decimal256, DecimalRangeExceeded, Deps, DepsMut, Env, Event, Fraction, MessageInfo, Order,
+    QuerierWrapper, Reply, ReplyOn, Response, StdError, StdResult, Storage, SubMsg, SubMsgResponse,
+    SubMsgResult, Uint128, Uint256, Uint64, WasmMsg,
 };
 use cw2::{get_contract_version, set_contract_version};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse};
+use cw_storage_plus::Bound;
 
 use astroport::asset::{
-    addr_opt_validate, check_swap_parameters, format_lp_token_name, Asset, AssetInfo, CoinsExt,
-    PairInfo, MINIMUM_LIQUIDITY_AMOUNT,
+    addr_opt_validate, check_swap_parameters, format_lp_token_name, Asset, AssetInfo, AssetInfoExt,
+    CoinsExt, PairInfo, MINIMUM_LIQUIDITY_AMOUNT,
 };
-use astroport::factory::PairType;
+use astroport::factory::{PairType, Role};
 use astroport::generator::Cw20HookMsg as GeneratorHookMsg;
+use astroport::oracle::QueryMsg as OracleQueryMsg;
 use astroport::pair::{
-    ConfigResponse, FeeShareConfig, XYKPoolConfig, XYKPoolParams, XYKPoolUpdateParams,
-    DEFAULT_SLIPPAGE, MAX_ALLOWED_SLIPPAGE, MAX_FEE_SHARE_BPS,
+    ArbitrageOpportunityResponse, BreakevenVolumeResponse, CumulativePricesResponse,
+    CustomHookConfig, CustomHookMsg, Cw20HookMsg, FeeAdjustedTwapResponse, FeePerShareResponse,
+    ImpermanentLossResponse, InstantiateMsg, InvariantResponse, MigrateMsg,
+    NormalizedReservesResponse, OraclePriceResponse, PairFees24hResponse, PairHealthResponse,
+    PoolResponse, PriceAlertConfig, PriceAlertMsg, PriceSnapshot, PythPriceResponse, QueryMsg,
+    RateOracleQueryMsg, ReverseSimulationResponse, SimulateProvideResponse, SimulationResponse,
+    Snapshot, SpotPriceResponse, TradeDirection, TwapSinceResponse, TwapSnapshot,
+    UserLpStatsResponse, PRICE_FEED_EXPO, TWAP_PRECISION,
 };
 use astroport::pair::{
-    CumulativePricesResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, PoolResponse,
-    QueryMsg, ReverseSimulationResponse, SimulationResponse, TWAP_PRECISION,
+    ConfigResponse, FeeShareConfig, RebasingAssetConfig, XYKPoolConfig, XYKPoolParams,
+    XYKPoolUpdateParams, DEFAULT_SLIPPAGE, EMERGENCY_WITHDRAW_TIMELOCK, FEE_WINDOW_SECS,
+    MAX_ALLOWED_SLIPPAGE, MAX_ALLOWED_SPREAD, MAX_FEE_ADJUSTED_OBSERVATIONS, MAX_FEE_SHARE_BPS,
+    MAX_FEE_WINDOW_SWAPS, MAX_POOL_SNAPSHOTS, MAX_TOP_LP_HOLDERS, PRICE_RING_SIZE,
+    RESERVES_HISTORY_DEPTH, SNAPSHOT_INTERVAL_SECS,
 };
-use astroport::querier::{query_factory_config, query_fee_info, query_supply};
-use astroport::{token::InstantiateMsg as TokenInstantiateMsg, U256};
+use astroport::pair_xyk::{ExecuteMsg, LpTransfer};
+use astroport::querier::{
+    query_factory_config, query_has_role, query_pair_admin, query_pair_fee_info, query_pair_paused,
+    query_supply, query_token_balance,
+};
+use astroport::token::{FeesExecuteMsg, InstantiateMsg as TokenInstantiateMsg};
+use astroport::U256;
 use cw_utils::parse_instantiate_response_data;
 
 use crate::error::ContractError;
-use crate::state::{Config, BALANCES, CONFIG};
+use crate::state::{
+    save_config_with_hash, BlockReason, Config, FeeAdjustedObservation, LpCooldown,
+    ACCEPTED_DENOMS, BALANCES, BLOCKED_SENDERS, CONFIG, CONFIG_HASH, DAILY_SPREADS,
+    DAILY_SWAP_VOLUME_USD, EMERGENCY_WITHDRAW_PROPOSAL, ENTRY_PRICE, FACTORY_PAUSED,
+    FEE_ADJUSTED_TWAP, FEE_PER_SHARE_CUMULATIVE, FEE_RING_24H, FEE_RING_24H_BOUNDS,
+    FEE_RING_24H_TOTALS, LP_COOLDOWNS, NEXT_SNAPSHOT_ID, NEXT_TWAP_SNAPSHOT_ID,
+    OLDEST_HISTORY_BLOCK, ORACLE, PAUSED, PRE_SWAP_INVARIANT, PRICE_ALERT_CONFIG, PRICE_RING,
+    RESERVES_HISTORY, RING_HEAD, SNAPSHOTS, SNAPSHOT_VOLUME, TOP_LP_HOLDER_HEAP, TWAP_SNAPSHOTS,
+    USER_LP_STATS,
+};
 
 /// Contract name that is used for migration.
 const CONTRACT_NAME: &str = "astroport-pair";
@@ -39,6 +65,14 @@ const CONTRACT_NAME: &str = "astroport-pair";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 /// A `reply` call code ID used for sub-messages.
 const INSTANTIATE_TOKEN_REPLY_ID: u64 = 1;
+/// A `reply` call code ID used to verify the pool invariant after a swap.
+const SWAP_INVARIANT_REPLY_ID: u64 = 2;
+/// A `reply` call code ID used to ignore a failed custom hook.
+const CUSTOM_HOOK_REPLY_ID: u64 = 3;
+/// A `reply` call code ID used to ignore a failed price alert notification.
+const PRICE_ALERT_REPLY_ID: u64 = 4;
+/// The number of days the average spread in [`QueryMsg::PairHealth`] is computed over.
+const SPREAD_AVG_PERIOD_DAYS: u64 = 7;
 
 /// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -60,10 +94,17 @@ pub fn instantiate(
     }
 
     let mut track_asset_balances = false;
+    let mut max_spread = None;
+    let mut lp_cooldown_secs = None;
+    let mut track_swap_history = true;
 
     if let Some(init_params) = msg.init_params {
         let params: XYKPoolParams = from_json(init_params)?;
         track_asset_balances = params.track_asset_balances.unwrap_or_default();
+        validate_max_spread(params.max_spread)?;
+        max_spread = params.max_spread;
+        lp_cooldown_secs = params.lp_cooldown_secs;
+        track_swap_history = params.track_swap_history.unwrap_or(true);
     }
 
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
@@ -74,6 +115,9 @@ pub fn instantiate(
             liquidity_token: Addr::unchecked(""),
             asset_infos: msg.asset_infos.clone(),
             pair_type: PairType::Xyk {},
+            creator: None,
+            creation_height: 0,
+            creation_time: 0,
         },
         factory_addr: deps.api.addr_validate(msg.factory_addr.as_str())?,
         block_time_last: 0,
@@ -81,6 +125,21 @@ pub fn instantiate(
         price1_cumulative_last: Uint128::zero(),
         track_asset_balances,
         fee_share: None,
+        max_spread,
+        lp_cooldown_secs,
+        custom_hook: None,
+        min_provide_amount: Default::default(),
+        swap_fee_rebate_bps: 0,
+        fee_rebate_expires_at: None,
+        max_tvl_asset0: None,
+        max_tvl_asset1: None,
+        sender_whitelist: None,
+        max_single_swap_usd: None,
+        max_daily_swap_usd: None,
+        usd_oracle: None,
+        compliance_mode: false,
+        rebasing_asset: None,
+        track_swap_history,
     };
 
     if track_asset_balances {
@@ -89,7 +148,11 @@ pub fn instantiate(
         }
     }
 
-    CONFIG.save(deps.storage, &config)?;
+    save_config_with_hash(deps.storage, &config)?;
+    ACCEPTED_DENOMS.save(
+        deps.storage,
+        &accepted_denoms(&config.pair_info.asset_infos),
+    )?;
 
     let token_name = format_lp_token_name(&msg.asset_infos, &deps.querier)?;
 
@@ -152,11 +215,42 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
             config.pair_info.liquidity_token =
                 deps.api.addr_validate(&init_response.contract_address)?;
 
-            CONFIG.save(deps.storage, &config)?;
+            save_config_with_hash(deps.storage, &config)?;
 
+            // The LP token tracks the LP fees with the fees per LP token reported by the pair
             Ok(Response::new()
+                .add_message(wasm_execute(
+                    &config.pair_info.liquidity_token,
+                    &FeesExecuteMsg::EnableFeeTracking {},
+                    vec![],
+                )?)
                 .add_attribute("liquidity_token_addr", config.pair_info.liquidity_token))
         }
+        Reply {
+            id: SWAP_INVARIANT_REPLY_ID,
+            result,
+        } => {
+            let pre_swap_invariant = PRE_SWAP_INVARIANT.load(deps.storage)?;
+            PRE_SWAP_INVARIANT.remove(deps.storage);
+
+            if let SubMsgResult::Err(err) = result {
+                return Err(StdError::generic_err(err).into());
+            }
+
+            verify_invariant(deps.as_ref(), pre_swap_invariant)?;
+
+            Ok(Response::new())
+        }
+        Reply {
+            id: CUSTOM_HOOK_REPLY_ID,
+            result: SubMsgResult::Err(err),
+        } => Ok(Response::new()
+            .add_attributes([attr("action", "custom_hook_failed"), attr("error", err)])),
+        Reply {
+            id: PRICE_ALERT_REPLY_ID,
+            result: SubMsgResult::Err(err),
+        } => Ok(Response::new()
+            .add_attributes([attr("action", "price_alert_failed"), attr("error", err)])),
         _ => Err(ContractError::FailedToParseReply {}),
     }
 }
@@ -182,6 +276,56 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
 ///             max_spread,
 ///             to,
 ///         }** Performs a swap operation with the specified parameters.
+///
+/// * **ExecuteMsg::ProposeEmergencyWithdraw { recipient }** Starts the timelock for draining the pair.
+///
+/// * **ExecuteMsg::DropEmergencyWithdrawProposal {}** Cancels the pending emergency withdrawal.
+///
+/// * **ExecuteMsg::EmergencyWithdraw { recipient }** Transfers all the pair balances to the recipient
+/// and pauses the pair permanently.
+///
+/// * **ExecuteMsg::SaveTwapSnapshot {}** Stores the current price accumulators for [`QueryMsg::TwapSince`].
+///
+/// * **ExecuteMsg::UpdateLpHolders { addresses, transfer }** Refreshes the top LP holders with the
+/// LP balances of the specified addresses and carries the LP cooldown over with LP transfers.
+///
+/// * **ExecuteMsg::SetCustomHook { config }** Sets the contract notified after pair actions.
+///
+/// * **ExecuteMsg::SetPaused { paused }** Stores whether the pair is paused in the factory.
+///
+/// * **ExecuteMsg::UpdateMinLiquidityThreshold { min_amounts }** Sets the minimum amounts of the
+/// pool assets accepted in a single liquidity provision.
+///
+/// * **ExecuteMsg::TakeSnapshot {}** Stores the current pool state for [`QueryMsg::Snapshot`].
+///
+/// * **ExecuteMsg::SetPriceAlertThreshold { config }** Sets the price movement after which swaps
+/// emit a price alert.
+///
+/// * **ExecuteMsg::SetFeeRebate { rebate_bps, expires_at }** Sets the share of the swap commission
+/// returned to swappers.
+///
+/// * **ExecuteMsg::SetTvlCap { caps }** Sets the max reserves of the pool assets after a liquidity
+/// provision.
+///
+/// * **ExecuteMsg::AddToWhitelist { addresses }** Allows the addresses to use the pair.
+///
+/// * **ExecuteMsg::RemoveFromWhitelist { addresses }** Revokes the pair access of the addresses.
+///
+/// * **ExecuteMsg::RemoveLiquidityImbalanced {
+///             lp_amount,
+///             min_asset0,
+///             min_asset1,
+///             preferred_asset,
+///             target_ratio,
+///         }** Withdraws liquidity and swaps part of the non-preferred asset into the preferred one.
+///
+/// * **ExecuteMsg::UpdateSwapLimits { max_single, max_daily }** Sets the max USD value of a single
+/// swap and of all the swaps of a day.
+///
+/// * **ExecuteMsg::BlockSender { address, reason }** Prevents the address from swapping, providing
+/// and withdrawing liquidity.
+///
+/// * **ExecuteMsg::UnblockSender { address }** Lifts the block of the address.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -189,6 +333,8 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    assert_compliance_mode_allows(deps.as_ref(), &msg)?;
+
     match msg {
         ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
         ExecuteMsg::ProvideLiquidity {
@@ -204,6 +350,7 @@ pub fn execute(
             slippage_tolerance,
             auto_stake,
             receiver,
+            None,
         ),
         ExecuteMsg::Swap {
             offer_asset,
@@ -231,6 +378,63 @@ pub fn execute(
             )
         }
         ExecuteMsg::UpdateConfig { params } => update_config(deps, env, info, params),
+        ExecuteMsg::ProposeEmergencyWithdraw { recipient } => {
+            propose_emergency_withdraw(deps, env, info, recipient)
+        }
+        ExecuteMsg::DropEmergencyWithdrawProposal {} => {
+            drop_emergency_withdraw_proposal(deps, info)
+        }
+        ExecuteMsg::EmergencyWithdraw { recipient } => {
+            emergency_withdraw(deps, env, info, recipient)
+        }
+        ExecuteMsg::SaveTwapSnapshot {} => save_twap_snapshot(deps, env),
+        ExecuteMsg::UpdateLpHolders {
+            addresses,
+            transfer,
+        } => update_lp_holders(deps, env, info, addresses, transfer),
+        ExecuteMsg::SetCustomHook { config } => set_custom_hook(deps, info, config),
+        ExecuteMsg::SetPaused { paused } => set_paused(deps, info, paused),
+        ExecuteMsg::UpdateMinLiquidityThreshold { min_amounts } => {
+            update_min_liquidity_threshold(deps, info, min_amounts)
+        }
+        ExecuteMsg::TakeSnapshot {} => take_snapshot(deps, env),
+        ExecuteMsg::SetPriceAlertThreshold { config } => {
+            set_price_alert_threshold(deps, info, config)
+        }
+        ExecuteMsg::SetFeeRebate {
+            rebate_bps,
+            expires_at,
+        } => set_fee_rebate(deps, env, info, rebate_bps, expires_at),
+        ExecuteMsg::SetTvlCap { caps } => set_tvl_cap(deps, info, caps),
+        ExecuteMsg::AddToWhitelist { addresses } => {
+            update_sender_whitelist(deps, info, addresses, true)
+        }
+        ExecuteMsg::RemoveFromWhitelist { addresses } => {
+            update_sender_whitelist(deps, info, addresses, false)
+        }
+        ExecuteMsg::RemoveLiquidityImbalanced {
+            lp_amount,
+            min_asset0,
+            min_asset1,
+            preferred_asset,
+            target_ratio,
+        } => remove_liquidity_imbalanced(
+            deps,
+            env,
+            info,
+            lp_amount,
+            [min_asset0, min_asset1],
+            preferred_asset,
+            target_ratio,
+        ),
+        ExecuteMsg::UpdateSwapLimits {
+            max_single,
+            max_daily,
+        } => update_swap_limits(deps, info, max_single, max_daily),
+        ExecuteMsg::BlockSender { address, reason } => {
+            block_sender(deps, env, info, address, reason)
+        }
+        ExecuteMsg::UnblockSender { address } => unblock_sender(deps, info, address),
         _ => Err(ContractError::NonSupported {}),
     }
 }
@@ -292,6 +496,42 @@ pub fn receive_cw20(
             cw20_msg.amount,
             assets,
         ),
+        Cw20HookMsg::ProvideLiquidity {
+            pair_asset,
+            slippage_tolerance,
+            auto_stake,
+            receiver,
+        } => {
+            let config = CONFIG.load(deps.storage)?;
+            let received_asset = Asset {
+                info: AssetInfo::Token {
+                    contract_addr: info.sender,
+                },
+                amount: cw20_msg.amount,
+            };
+
+            // Only asset contract can execute this message
+            if !config.pair_info.asset_infos.contains(&received_asset.info) {
+                return Err(ContractError::Unauthorized {});
+            }
+            if pair_asset.info.equal(&received_asset.info) {
+                return Err(ContractError::DoublingAssets {});
+            }
+
+            provide_liquidity(
+                deps,
+                env,
+                MessageInfo {
+                    sender: Addr::unchecked(cw20_msg.sender),
+                    funds: vec![],
+                },
+                vec![received_asset.clone(), pair_asset],
+                slippage_tolerance,
+                Some(auto_stake),
+                receiver,
+                Some(received_asset),
+            )
+        }
     }
 }
 
@@ -308,7 +548,11 @@ pub fn receive_cw20(
 /// * **receiver** is an optional parameter which defines the receiver of the LP tokens.
 /// If no custom receiver is specified, the pair will mint LP tokens for the function caller.
 ///
+/// * **received_asset** is the CW20 asset that was already sent to the pair via
+/// [`Cw20HookMsg::ProvideLiquidity`], so it is not pulled from the caller.
+///
 /// NOTE - the address that wants to provide liquidity should approve the pair contract to pull its relevant tokens.
+#[allow(clippy::too_many_arguments)]
 pub fn provide_liquidity(
     deps: DepsMut,
     env: Env,
@@ -317,16 +561,25 @@ pub fn provide_liquidity(
     slippage_tolerance: Option<Decimal>,
     auto_stake: Option<bool>,
     receiver: Option<String>,
+    received_asset: Option<Asset>,
 ) -> Result<Response, ContractError> {
     if assets.len() != 2 {
         return Err(StdError::generic_err("asset_infos must contain exactly two elements").into());
     }
     assets[0].info.check(deps.api)?;
     assets[1].info.check(deps.api)?;
+    assets
+        .iter()
+        .try_for_each(|asset| asset.validate_non_zero())?;
 
     let auto_stake = auto_stake.unwrap_or(false);
 
     let mut config = CONFIG.load(deps.storage)?;
+    assert_not_paused(deps.as_ref(), &config)?;
+    assert_sender_whitelisted(&config, &info.sender)?;
+    assert_sender_not_blocked(deps.storage, &info.sender)?;
+
+    assert_accepted_funds(deps.as_ref(), &info.funds)?;
     info.funds
         .assert_coins_properly_sent(&assets, &config.pair_info.asset_infos)?;
     let mut pools = config
@@ -349,10 +602,19 @@ pub fn provide_liquidity(
         return Err(ContractError::InvalidZeroAmount {});
     }
 
+    for (provided, required) in deposits.into_iter().zip(config.min_provide_amount) {
+        if provided < required {
+            return Err(ContractError::MinLiquidityThresholdNotMet { required, provided });
+        }
+    }
+
     let mut messages = vec![];
     for (i, pool) in pools.iter_mut().enumerate() {
+        let is_received = received_asset
+            .as_ref()
+            .map_or(false, |asset| asset.info.equal(&pool.info));
         // If the asset is a token contract, then we need to execute a TransferFrom msg to receive assets
-        if let AssetInfo::Token { contract_addr, .. } = &pool.info {
+        if let (AssetInfo::Token { contract_addr, .. }, false) = (&pool.info, is_received) {
             messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: contract_addr.to_string(),
                 msg: to_json_binary(&Cw20ExecuteMsg::TransferFrom {
@@ -363,22 +625,36 @@ pub fn provide_liquidity(
                 funds: vec![],
             }));
         } else {
-            // If the asset is native token, the pool balance is already increased
+            // If the asset is native token or was sent via the CW20 hook, the pool balance is already increased
             // To calculate the total amount of deposits properly, we should subtract the user deposit from the pool
             pool.amount = pool.amount.checked_sub(deposits[i])?;
         }
     }
 
+    // The whole provision fails if a reserve exceeds its cap
+    for (pool, (deposit, cap)) in pools
+        .iter()
+        .zip(deposits.into_iter().zip(config.tvl_caps()))
+    {
+        let current = pool.amount.checked_add(deposit)?;
+        if let Some(cap) = cap {
+            if current > cap {
+                return Err(ContractError::TvlCapExceeded {
+                    asset: pool.info.clone(),
+                    cap,
+                    current,
+                });
+            }
+        }
+    }
+
     let total_share = query_supply(&deps.querier, &config.pair_info.liquidity_token)?;
     let share = if total_share.is_zero() {
         // Initial share = collateral amount
-        let share = Uint128::new(
-            (U256::from(deposits[0].u128()) * U256::from(deposits[1].u128()))
-                .integer_sqrt()
-                .as_u128(),
-        )
-        .checked_sub(MINIMUM_LIQUIDITY_AMOUNT)
-        .map_err(|_| ContractError::MinimumLiquidityAmountError {})?;
+        let share = Uint128::try_from(isqrt(compute_invariant(deposits[0], deposits[1])))
+            .map_err(StdError::from)?
+            .checked_sub(MINIMUM_LIQUIDITY_AMOUNT)
+            .map_err(|_| ContractError::MinimumLiquidityAmountError {})?;
 
         messages.extend(mint_liquidity_token_message(
             deps.querier,
@@ -412,6 +688,40 @@ pub fn provide_liquidity(
 
     // Mint LP tokens for the sender or for the receiver (if set)
     let receiver = addr_opt_validate(deps.api, &receiver)?.unwrap_or_else(|| info.sender.clone());
+    if let Some(cooldown_secs) = config.lp_cooldown_secs {
+        if receiver == info.sender {
+            let mut cooldown = active_lp_cooldown(deps.storage, &env, cooldown_secs, &receiver)?
+                .unwrap_or_default();
+            cooldown.amount = cooldown.amount.checked_add(share)?;
+            cooldown.received_at = env.block.time.seconds();
+            LP_COOLDOWNS.save(deps.storage, &receiver, &cooldown)?;
+        } else {
+            // Providing for another receiver must not restart the cooldown of its LP tokens,
+            // otherwise anyone could keep them locked with tiny provisions
+            add_lp_cooldown(
+                deps.storage,
+                &env,
+                cooldown_secs,
+                &receiver,
+                share,
+                env.block.time.seconds(),
+            )?;
+        }
+    }
+    if !ENTRY_PRICE.has(deps.storage, &receiver) {
+        let entry_price = Decimal::checked_from_ratio(
+            pools[1].amount.checked_add(deposits[1])?,
+            pools[0].amount.checked_add(deposits[0])?,
+        )
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+        ENTRY_PRICE.save(deps.storage, &receiver, &entry_price)?;
+
+        let mut stats = USER_LP_STATS
+            .may_load(deps.storage, &receiver)?
+            .unwrap_or_default();
+        stats.first_provide_time = env.block.time.seconds();
+        USER_LP_STATS.save(deps.storage, &receiver, &stats)?;
+    }
     messages.extend(mint_liquidity_token_message(
         deps.querier,
         &config,
@@ -431,6 +741,16 @@ pub fn provide_liquidity(
             )?;
         }
     }
+    if config.track_swap_history {
+        record_reserves(
+            deps.storage,
+            &env,
+            [
+                pools[0].amount.checked_add(deposits[0])?,
+                pools[1].amount.checked_add(deposits[1])?,
+            ],
+        )?;
+    }
 
     // Accumulate prices for the assets in the pool
     if let Some((price0_cumulative_new, price1_cumulative_new, block_time)) =
@@ -442,13 +762,26 @@ pub fn provide_liquidity(
         CONFIG.save(deps.storage, &config)?;
     }
 
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        attr("action", "provide_liquidity"),
-        attr("sender", info.sender),
-        attr("receiver", receiver),
-        attr("assets", format!("{}, {}", assets[0], assets[1])),
-        attr("share", share),
-    ]))
+    let hook_msg = custom_hook_message(
+        &config,
+        CustomHookMsg::AfterProvide {
+            sender: info.sender.clone(),
+            receiver: receiver.clone(),
+            assets: assets.clone(),
+            share,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_submessages(hook_msg)
+        .add_attributes(vec![
+            attr("action", "provide_liquidity"),
+            attr("sender", info.sender),
+            attr("receiver", receiver),
+            attr("assets", format!("{}, {}", assets[0], assets[1])),
+            attr("share", share),
+        ]))
 }
 
 /// Mint LP tokens for a beneficiary and auto stake the tokens in the Generator contract (if auto staking is specified).
@@ -509,6 +842,133 @@ fn mint_liquidity_token_message(
     }
 }
 
+/// Checks that `sender` keeps its LP tokens which are still in the LP cooldown after burning
+/// `lp_amount` of its current LP balance, so only the LP tokens received at least
+/// [`Config::lp_cooldown_secs`] ago can be withdrawn.
+/// The factory and the fee collector are not subject to the cooldown.
+fn assert_lp_cooldown(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    sender: &Addr,
+    lp_amount: Uint128,
+) -> Result<(), ContractError> {
+    if let Some(cooldown_secs) = config.lp_cooldown_secs {
+        if let Some(cooldown) = active_lp_cooldown(deps.storage, env, cooldown_secs, sender)? {
+            let lp_balance =
+                query_token_balance(&deps.querier, &config.pair_info.liquidity_token, sender)?;
+            if lp_balance.saturating_sub(lp_amount) < cooldown.amount
+                && *sender != config.factory_addr
+                && query_factory_config(&deps.querier, &config.factory_addr)?
+                    .fee_address
+                    .as_ref()
+                    != Some(sender)
+            {
+                return Err(ContractError::LpCooldownActive {
+                    unlock_at: cooldown.unlock_at(cooldown_secs),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the LP tokens of `address` which are still in the LP cooldown.
+fn active_lp_cooldown(
+    storage: &dyn Storage,
+    env: &Env,
+    cooldown_secs: u64,
+    address: &Addr,
+) -> StdResult<Option<LpCooldown>> {
+    Ok(LP_COOLDOWNS.may_load(storage, address)?.filter(|cooldown| {
+        !cooldown.amount.is_zero() && env.block.time.seconds() < cooldown.unlock_at(cooldown_secs)
+    }))
+}
+
+/// Adds `amount` LP tokens received at `received_at` to the LP cooldown of `address`. The receive
+/// time of all the LP tokens in the cooldown is weighted by their amounts, so a few LP tokens
+/// barely move the cooldown of a large amount.
+fn add_lp_cooldown(
+    storage: &mut dyn Storage,
+    env: &Env,
+    cooldown_secs: u64,
+    address: &Addr,
+    amount: Uint128,
+    received_at: u64,
+) -> StdResult<()> {
+    let cooldown = match active_lp_cooldown(storage, env, cooldown_secs, address)? {
+        Some(cooldown) => {
+            let total = cooldown.amount.checked_add(amount)?;
+            let received_at = if received_at >= cooldown.received_at {
+                let shift =
+                    Uint128::from(received_at - cooldown.received_at).multiply_ratio(amount, total);
+                cooldown.received_at + shift.u128() as u64
+            } else {
+                let shift = Uint128::from(cooldown.received_at - received_at)
+                    .multiply_ratio(cooldown.amount, total);
+                received_at + shift.u128() as u64
+            };
+
+            LpCooldown {
+                amount: total,
+                received_at,
+            }
+        }
+        None => LpCooldown {
+            amount,
+            received_at,
+        },
+    };
+
+    LP_COOLDOWNS.save(storage, address, &cooldown)
+}
+
+/// Moves the LP tokens in the cooldown sent with an LP transfer to the recipient, so the LP
+/// cooldown can't be bypassed by transferring the LP tokens to another address. Transfers spend
+/// the LP tokens which aren't in the cooldown first.
+fn carry_lp_cooldown(
+    deps: DepsMut,
+    env: &Env,
+    config: &Config,
+    cooldown_secs: u64,
+    transfer: LpTransfer,
+) -> Result<(), ContractError> {
+    let from = deps.api.addr_validate(&transfer.from)?;
+    let mut cooldown = match active_lp_cooldown(deps.storage, env, cooldown_secs, &from)? {
+        Some(cooldown) => cooldown,
+        None => return Ok(()),
+    };
+
+    let lp_balance = query_token_balance(&deps.querier, &config.pair_info.liquidity_token, &from)?;
+    let moved = cooldown.amount.saturating_sub(lp_balance);
+    if moved.is_zero() {
+        return Ok(());
+    }
+
+    cooldown.amount -= moved;
+    if cooldown.amount.is_zero() {
+        LP_COOLDOWNS.remove(deps.storage, &from);
+    } else {
+        LP_COOLDOWNS.save(deps.storage, &from, &cooldown)?;
+    }
+
+    // The LP tokens sent to the pair are burned
+    let to = deps.api.addr_validate(&transfer.to)?;
+    if to != env.contract.address {
+        add_lp_cooldown(
+            deps.storage,
+            env,
+            cooldown_secs,
+            &to,
+            moved,
+            cooldown.received_at,
+        )?;
+    }
+
+    Ok(())
+}
+
 /// Withdraw liquidity from the pool.
 /// * **sender** is the address that will receive assets back from the pair contract.
 ///
@@ -527,6 +987,11 @@ pub fn withdraw_liquidity(
         return Err(ContractError::Unauthorized {});
     }
 
+    assert_sender_whitelisted(&config, &sender)?;
+    assert_sender_not_blocked(deps.storage, &sender)?;
+    // The LP tokens to burn were already sent to the pair
+    assert_lp_cooldown(deps.as_ref(), &env, &config, &sender, Uint128::zero())?;
+
     let (pools, total_share) = pool_info(deps.querier, &config)?;
 
     let refund_assets = if assets.is_empty() {
@@ -546,6 +1011,16 @@ pub fn withdraw_liquidity(
             )?;
         }
     }
+    if config.track_swap_history {
+        record_reserves(
+            deps.storage,
+            &env,
+            [
+                pools[0].amount - refund_assets[0].amount,
+                pools[1].amount - refund_assets[1].amount,
+            ],
+        )?;
+    }
 
     // Accumulate prices for the pair assets
     if let Some((price0_cumulative_new, price1_cumulative_new, block_time)) =
@@ -568,15 +1043,191 @@ pub fn withdraw_liquidity(
         }),
     ];
 
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        attr("action", "withdraw_liquidity"),
-        attr("sender", sender),
-        attr("withdrawn_share", amount),
-        attr(
-            "refund_assets",
-            format!("{}, {}", refund_assets[0], refund_assets[1]),
-        ),
-    ]))
+    let hook_msg = custom_hook_message(
+        &config,
+        CustomHookMsg::AfterWithdraw {
+            sender: sender.clone(),
+            refund_assets: refund_assets.clone(),
+            withdrawn_share: amount,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_submessages(hook_msg)
+        .add_attributes(vec![
+            attr("action", "withdraw_liquidity"),
+            attr("sender", sender),
+            attr("withdrawn_share", amount),
+            attr(
+                "refund_assets",
+                format!("{}, {}", refund_assets[0], refund_assets[1]),
+            ),
+        ]))
+}
+
+/// Withdraws liquidity and swaps `1 - target_ratio` of the withdrawn non-preferred asset into
+/// the preferred asset against the pool reserves left after the withdrawal. The swap is executed
+/// like [`swap`], so it fails while the pair is paused and pays the same fees and rebate.
+/// The LP tokens are transferred from the sender with an allowance and burned.
+///
+/// * **lp_amount** amount of LP tokens to burn.
+///
+/// * **min_assets** min amounts of the pool assets to receive.
+///
+/// * **preferred_asset** pool asset the non-preferred asset is swapped into.
+///
+/// * **target_ratio** share of the withdrawn non-preferred asset to keep.
+pub fn remove_liquidity_imbalanced(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lp_amount: Uint128,
+    min_assets: [Uint128; 2],
+    preferred_asset: AssetInfo,
+    target_ratio: Decimal,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    assert_sender_whitelisted(&config, &info.sender)?;
+    assert_sender_not_blocked(deps.storage, &info.sender)?;
+    assert_lp_cooldown(deps.as_ref(), &env, &config, &info.sender, lp_amount)?;
+
+    if target_ratio > Decimal::one() {
+        return Err(ContractError::InvalidTargetRatio {});
+    }
+    let preferred = config
+        .pair_info
+        .asset_infos
+        .iter()
+        .position(|asset_info| asset_info.equal(&preferred_asset))
+        .ok_or(ContractError::AssetMismatch {})?;
+    let other = 1 - preferred;
+
+    let (pools, total_share) = pool_info(deps.querier, &config)?;
+    let mut refund_assets = get_share_in_assets(&pools, lp_amount, total_share);
+    let mut new_pools = [
+        pools[0].amount.checked_sub(refund_assets[0].amount)?,
+        pools[1].amount.checked_sub(refund_assets[1].amount)?,
+    ];
+
+    let mut fee_messages = vec![];
+    let mut events = vec![];
+    let mut alert_messages = vec![];
+    let mut pre_swap_invariant = None;
+    let swap_amount = refund_assets[other].amount * (Decimal::one() - target_ratio);
+    let mut commission_amount = Uint128::zero();
+    if !swap_amount.is_zero() {
+        // The withdrawn asset is swapped like any other swap against the remaining reserves
+        let leg_pools = [
+            pools[0].info.with_balance(new_pools[0]),
+            pools[1].info.with_balance(new_pools[1]),
+        ];
+        let leg = execute_swap_leg(
+            deps.branch(),
+            &env,
+            &config,
+            &leg_pools,
+            &pools[other].info.with_balance(swap_amount),
+        )?;
+        pre_swap_invariant = Some(compute_invariant(new_pools[0], new_pools[1]));
+        commission_amount = leg.commission_amount;
+        fee_messages = leg.fee_messages;
+        if let Some((event, notification)) = leg.alert {
+            events.push(event);
+            alert_messages.extend(notification);
+        }
+
+        refund_assets[other].amount -= swap_amount;
+        refund_assets[preferred].amount += leg.return_amount + leg.fee_rebate_amount;
+        new_pools = leg.new_pools;
+    }
+
+    for (refund_asset, min_amount) in refund_assets.iter().zip(min_assets) {
+        if refund_asset.amount < min_amount {
+            return Err(ContractError::WithdrawMinimumNotMet {
+                asset: refund_asset.info.clone(),
+                received: refund_asset.amount,
+                minimum: min_amount,
+            });
+        }
+    }
+
+    if config.track_asset_balances {
+        for (pool, new_pool) in pools.iter().zip(new_pools) {
+            BALANCES.save(deps.storage, &pool.info, &new_pool, env.block.height)?;
+        }
+    }
+    if swap_amount.is_zero() && config.track_swap_history {
+        record_reserves(deps.storage, &env, new_pools)?;
+    }
+
+    // Accumulate prices for the pair assets
+    if let Some((price0_cumulative_new, price1_cumulative_new, block_time)) =
+        accumulate_prices(env, &config, pools[0].amount, pools[1].amount)?
+    {
+        config.price0_cumulative_last = price0_cumulative_new;
+        config.price1_cumulative_last = price1_cumulative_new;
+        config.block_time_last = block_time;
+        CONFIG.save(deps.storage, &config)?;
+    }
+
+    let lp_token = config.pair_info.liquidity_token.to_string();
+    let mut messages: Vec<CosmosMsg> = vec![
+        wasm_execute(
+            &lp_token,
+            &Cw20ExecuteMsg::TransferFrom {
+                owner: info.sender.to_string(),
+                recipient: config.pair_info.contract_addr.to_string(),
+                amount: lp_amount,
+            },
+            vec![],
+        )?
+        .into(),
+        wasm_execute(
+            &lp_token,
+            &Cw20ExecuteMsg::Burn { amount: lp_amount },
+            vec![],
+        )?
+        .into(),
+    ];
+    for refund_asset in refund_assets.iter().filter(|asset| !asset.amount.is_zero()) {
+        messages.push(refund_asset.clone().into_msg(info.sender.clone())?);
+    }
+    messages.extend(fee_messages);
+
+    // The pool invariant after the withdrawal is verified once the last transfer is executed
+    let mut messages: Vec<_> = messages.into_iter().map(SubMsg::new).collect();
+    if let (Some(pre_swap_invariant), Some(last_msg)) = (pre_swap_invariant, messages.last_mut()) {
+        PRE_SWAP_INVARIANT.save(deps.storage, &pre_swap_invariant)?;
+        last_msg.id = SWAP_INVARIANT_REPLY_ID;
+        last_msg.reply_on = ReplyOn::Always;
+    }
+
+    let hook_msg = custom_hook_message(
+        &config,
+        CustomHookMsg::AfterWithdraw {
+            sender: info.sender.clone(),
+            refund_assets: refund_assets.clone(),
+            withdrawn_share: lp_amount,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_events(events)
+        .add_submessages(messages)
+        .add_submessages(hook_msg)
+        .add_submessages(alert_messages)
+        .add_attributes(vec![
+            attr("action", "remove_liquidity_imbalanced"),
+            attr("sender", info.sender),
+            attr("withdrawn_share", lp_amount),
+            attr(
+                "refund_assets",
+                format!("{}, {}", refund_assets[0], refund_assets[1]),
+            ),
+            attr("swap_amount", swap_amount),
+            attr("commission_amount", commission_amount),
+        ]))
 }
 
 /// Returns the amount of pool assets that correspond to an amount of LP tokens.
@@ -617,7 +1268,7 @@ pub fn get_share_in_assets(pools: &[Asset], amount: Uint128, total_share: Uint12
 /// NOTE - the address that wants to swap should approve the pair contract to pull the offer token.
 #[allow(clippy::too_many_arguments)]
 pub fn swap(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     sender: Addr,
@@ -626,9 +1277,13 @@ pub fn swap(
     max_spread: Option<Decimal>,
     to: Option<Addr>,
 ) -> Result<Response, ContractError> {
+    assert_accepted_funds(deps.as_ref(), &info.funds)?;
+    offer_asset.validate_non_zero()?;
     offer_asset.assert_sent_native_token_balance(&info)?;
 
     let mut config = CONFIG.load(deps.storage)?;
+    assert_sender_whitelisted(&config, &sender)?;
+    assert_sender_not_blocked(deps.storage, &sender)?;
 
     // If the asset balance is already increased, we should subtract the user deposit from the pool amount
     let pools = config
@@ -643,137 +1298,1277 @@ pub fn swap(
         })
         .collect::<StdResult<Vec<_>>>()?;
 
-    let offer_pool: Asset;
-    let ask_pool: Asset;
-
-    if offer_asset.info.equal(&pools[0].info) {
-        offer_pool = pools[0].clone();
-        ask_pool = pools[1].clone();
-    } else if offer_asset.info.equal(&pools[1].info) {
-        offer_pool = pools[1].clone();
-        ask_pool = pools[0].clone();
-    } else {
-        return Err(ContractError::AssetMismatch {});
-    }
-
-    // Get fee info from the factory
-    let fee_info = query_fee_info(
-        &deps.querier,
-        &config.factory_addr,
-        config.pair_info.pair_type.clone(),
-    )?;
-
     let offer_amount = offer_asset.amount;
-
-    let (return_amount, spread_amount, commission_amount) = compute_swap(
-        offer_pool.amount,
-        ask_pool.amount,
-        offer_amount,
-        fee_info.total_fee_rate,
-    )?;
-
-    // Check the max spread limit (if it was specified)
+    let SwapLeg {
+        ask_info,
+        return_amount,
+        spread_amount,
+        commission_amount,
+        maker_fee_amount,
+        fee_share_amount,
+        fee_rebate_amount,
+        new_pools,
+        fee_messages,
+        alert,
+    } = execute_swap_leg(deps.branch(), &env, &config, &pools, &offer_asset)?;
+
+    // Check the max spread limit (if it was specified). Fall back to the pair's max spread
     assert_max_spread(
         belief_price,
-        max_spread,
+        max_spread.or(config.max_spread),
         offer_amount,
         return_amount + commission_amount,
         spread_amount,
     )?;
 
-    let return_asset = Asset {
-        info: ask_pool.info.clone(),
-        amount: return_amount,
-    };
-
     let receiver = to.unwrap_or_else(|| sender.clone());
-    let mut messages = vec![];
-    if !return_amount.is_zero() {
-        messages.push(return_asset.into_msg(receiver.clone())?)
+    let mut messages = fee_messages;
+
+    let receive_amount = return_amount + fee_rebate_amount;
+    if !receive_amount.is_zero() {
+        let return_asset = Asset {
+            info: ask_info.clone(),
+            amount: receive_amount,
+        };
+        messages.insert(0, return_asset.into_msg(receiver.clone())?)
     }
 
-    // If this pool is configured to share fees, calculate the amount to send
-    // to the receiver and add the transfer message
-    // The calculation works as follows: We take the share percentage first,
-    // and the remainder is then split between LPs and maker
-    let mut fees_commission_amount = commission_amount;
-    let mut fee_share_amount = Uint128::zero();
-    if let Some(fee_share) = config.fee_share.clone() {
-        // Calculate the fee share amount from the full commission amount
-        let share_fee_rate = Decimal::from_ratio(fee_share.bps, 10000u16);
-        fee_share_amount = fees_commission_amount * share_fee_rate;
+    if config.track_asset_balances {
+        for (pool, new_pool) in pools.iter().zip(new_pools) {
+            BALANCES.save(deps.storage, &pool.info, &new_pool, env.block.height)?;
+        }
+    }
+
+    // Accumulate prices for the assets in the pool
+    if let Some((price0_cumulative_new, price1_cumulative_new, block_time)) =
+        accumulate_prices(env, &config, pools[0].amount, pools[1].amount)?
+    {
+        config.price0_cumulative_last = price0_cumulative_new;
+        config.price1_cumulative_last = price1_cumulative_new;
+        config.block_time_last = block_time;
+        CONFIG.save(deps.storage, &config)?;
+    }
+
+    // The pool invariant is verified once the last transfer is executed
+    let mut messages: Vec<_> = messages.into_iter().map(SubMsg::new).collect();
+    if let Some(last_msg) = messages.last_mut() {
+        PRE_SWAP_INVARIANT.save(
+            deps.storage,
+            &compute_invariant(pools[0].amount, pools[1].amount),
+        )?;
+        last_msg.id = SWAP_INVARIANT_REPLY_ID;
+        last_msg.reply_on = ReplyOn::Always;
+    }
+
+    // The hook is executed after the invariant is verified
+    messages.extend(custom_hook_message(
+        &config,
+        CustomHookMsg::AfterSwap {
+            sender: sender.clone(),
+            receiver: receiver.clone(),
+            offer_asset: offer_asset.clone(),
+            return_asset: ask_info.with_balance(return_amount),
+        },
+    )?);
+
+    let mut events = vec![];
+    if let Some((event, notification)) = alert {
+        events.push(event);
+        messages.extend(notification);
+    }
+
+    Ok(Response::new()
+        .add_events(events)
+        .add_submessages(
+            // 1. send collateral tokens from the contract to a user
+            // 2. send inactive commission fees to the Maker contract
+            messages,
+        )
+        .add_attributes(vec![
+            attr("action", "swap"),
+            attr("sender", sender),
+            attr("receiver", receiver),
+            attr("offer_asset", offer_asset.info.to_string()),
+            attr("ask_asset", ask_info.to_string()),
+            attr("offer_amount", offer_amount),
+            attr("return_amount", return_amount),
+            attr("spread_amount", spread_amount),
+            attr("commission_amount", commission_amount),
+            attr("maker_fee_amount", maker_fee_amount),
+            attr("fee_share_amount", fee_share_amount),
+            attr("fee_rebate", fee_rebate_amount),
+        ]))
+}
+
+/// The result of a swap computed by [`execute_swap_leg`].
+struct SwapLeg {
+    ask_info: AssetInfo,
+    return_amount: Uint128,
+    spread_amount: Uint128,
+    commission_amount: Uint128,
+    maker_fee_amount: Uint128,
+    fee_share_amount: Uint128,
+    /// The part of the LP commission paid to the swapper on top of the return amount
+    fee_rebate_amount: Uint128,
+    /// The reserves after the swap in the order of the pair assets
+    new_pools: [Uint128; 2],
+    /// The fee share and Maker fee transfers
+    fee_messages: Vec<CosmosMsg>,
+    alert: Option<(Event, Option<SubMsg>)>,
+}
+
+/// Swaps `offer_asset` against the `pools` reserves. Checks that swaps are enabled and within
+/// the swap limits, computes the fee share, the Maker fee and the fee rebate, accrues the LP
+/// fees and records the swap in the pool history unless [`Config::track_swap_history`] is
+/// disabled. The caller sends the return asset.
+///
+/// * **pools** the reserves before the swap in the order of the pair assets.
+///
+/// * **offer_asset** the asset to swap.
+fn execute_swap_leg(
+    deps: DepsMut,
+    env: &Env,
+    config: &Config,
+    pools: &[Asset],
+    offer_asset: &Asset,
+) -> Result<SwapLeg, ContractError> {
+    assert_not_paused(deps.as_ref(), config)?;
+    check_swap_limits(deps.storage, deps.querier, env, config, offer_asset)?;
+
+    let (offer_pool, ask_pool) = if offer_asset.info.equal(&pools[0].info) {
+        (&pools[0], &pools[1])
+    } else if offer_asset.info.equal(&pools[1].info) {
+        (&pools[1], &pools[0])
+    } else {
+        return Err(ContractError::AssetMismatch {});
+    };
+
+    // Get fee info from the factory
+    let fee_info = query_pair_fee_info(
+        &deps.querier,
+        &config.factory_addr,
+        &config.pair_info.contract_addr,
+        config.pair_info.pair_type.clone(),
+    )?;
+
+    let offer_amount = offer_asset.amount;
+
+    let (return_amount, spread_amount, commission_amount) = compute_swap(
+        offer_pool.amount,
+        ask_pool.amount,
+        offer_amount,
+        fee_info.total_fee_rate,
+    )?;
+
+    let mut fee_messages = vec![];
+
+    // If this pool is configured to share fees, calculate the amount to send
+    // to the receiver and add the transfer message
+    // The calculation works as follows: We take the share percentage first,
+    // and the remainder is then split between LPs and maker
+    let mut fees_commission_amount = commission_amount;
+    let mut fee_share_amount = Uint128::zero();
+    if let Some(fee_share) = config.fee_share.clone() {
+        // Calculate the fee share amount from the full commission amount
+        let share_fee_rate = Decimal::from_ratio(fee_share.bps, 10000u16);
+        fee_share_amount = fees_commission_amount * share_fee_rate;
 
         if !fee_share_amount.is_zero() {
             // Subtract the fee share amount from the commission
             fees_commission_amount = fees_commission_amount.saturating_sub(fee_share_amount);
 
-            // Build send message for the shared amount
-            let fee_share_msg = Asset {
-                info: ask_pool.info.clone(),
-                amount: fee_share_amount,
+            // Build send message for the shared amount
+            let fee_share_msg = Asset {
+                info: ask_pool.info.clone(),
+                amount: fee_share_amount,
+            }
+            .into_msg(fee_share.recipient)?;
+            fee_messages.push(fee_share_msg);
+        }
+    }
+
+    // Compute the Maker fee
+    let mut maker_fee_amount = Uint128::zero();
+    if let Some(fee_address) = fee_info.fee_address {
+        if let Some(f) = calculate_maker_fee(
+            &ask_pool.info,
+            fees_commission_amount,
+            fee_info.maker_fee_rate,
+        ) {
+            maker_fee_amount = f.amount;
+            fee_messages.push(f.into_msg(fee_address)?);
+        }
+    }
+
+    // The fee rebate is paid out of the LP share of the commission
+    let lp_commission_amount = fees_commission_amount.saturating_sub(maker_fee_amount);
+    let fee_rebate_amount = commission_amount
+        .multiply_ratio(config.fee_rebate_bps(env.block.time.seconds()), 10000u16)
+        .min(lp_commission_amount);
+
+    if config.track_swap_history {
+        record_spread(
+            deps.storage,
+            env,
+            Decimal256::checked_from_ratio(spread_amount, return_amount + spread_amount)
+                .unwrap_or_default(),
+        )?;
+    }
+
+    let new_offer_pool = offer_pool.amount + offer_amount;
+    let new_ask_pool =
+        ask_pool.amount - return_amount - fee_rebate_amount - maker_fee_amount - fee_share_amount;
+    let (new_pools, volume) = if offer_pool.info.equal(&pools[0].info) {
+        ([new_offer_pool, new_ask_pool], return_amount)
+    } else {
+        ([new_ask_pool, new_offer_pool], offer_amount)
+    };
+    if config.track_swap_history {
+        record_price_snapshot(deps.storage, env, new_pools, volume)?;
+        record_reserves(deps.storage, env, new_pools)?;
+    }
+    let alert = price_alert(
+        deps.storage,
+        env,
+        Decimal::checked_from_ratio(new_pools[1], new_pools[0]).unwrap_or_default(),
+    )?;
+
+    // The rest of the commission stays in the pool and is accrued to the LP token holders
+    let lp_fee_amount = lp_commission_amount - fee_rebate_amount;
+    accrue_lp_fees(
+        deps.storage,
+        deps.querier,
+        config,
+        &ask_pool.info,
+        lp_fee_amount,
+    )?;
+
+    if config.track_swap_history {
+        let lp_fees = if ask_pool.info.equal(&pools[0].info) {
+            [lp_fee_amount, Uint128::zero()]
+        } else {
+            [Uint128::zero(), lp_fee_amount]
+        };
+        record_fees(deps.storage, env, lp_fees)?;
+
+        let gross_return_amount = return_amount + commission_amount;
+        let swapped_amounts = if offer_pool.info.equal(&pools[0].info) {
+            [offer_amount, gross_return_amount]
+        } else {
+            [gross_return_amount, offer_amount]
+        };
+        record_fee_adjusted_observation(
+            deps.storage,
+            env,
+            swapped_amounts,
+            Decimal::checked_from_ratio(commission_amount, gross_return_amount).unwrap_or_default(),
+        )?;
+    }
+
+    Ok(SwapLeg {
+        ask_info: ask_pool.info.clone(),
+        return_amount,
+        spread_amount,
+        commission_amount,
+        maker_fee_amount,
+        fee_share_amount,
+        fee_rebate_amount,
+        new_pools,
+        fee_messages,
+        alert,
+    })
+}
+
+/// Adds the LP fee per LP token to [`FEE_PER_SHARE_CUMULATIVE`] for the asset the fee is charged in.
+///
+/// * **fee_asset** the asset the fee is charged in.
+///
+/// * **lp_fee_amount** the part of the commission which stays in the pool.
+fn accrue_lp_fees(
+    storage: &mut dyn Storage,
+    querier: QuerierWrapper,
+    config: &Config,
+    fee_asset: &AssetInfo,
+    lp_fee_amount: Uint128,
+) -> StdResult<()> {
+    let total_share = query_supply(&querier, &config.pair_info.liquidity_token)?;
+    if lp_fee_amount.is_zero() || total_share.is_zero() {
+        return Ok(());
+    }
+
+    let mut fee_per_share = FEE_PER_SHARE_CUMULATIVE
+        .may_load(storage)?
+        .unwrap_or_default();
+    let idx = if fee_asset.equal(&config.pair_info.asset_infos[0]) {
+        0
+    } else {
+        1
+    };
+    fee_per_share[idx] =
+        fee_per_share[idx].checked_add(Decimal256::from_ratio(lp_fee_amount, total_share))?;
+    FEE_PER_SHARE_CUMULATIVE.save(storage, &fee_per_share)
+}
+
+/// Adds the swap spread to today's entry in [`DAILY_SPREADS`]. Entries older than
+/// [`SPREAD_AVG_PERIOD_DAYS`] are removed.
+///
+/// * **spread** spread of the swap relative to the amount the swap would return at the pool price.
+fn record_spread(storage: &mut dyn Storage, env: &Env, spread: Decimal256) -> StdResult<()> {
+    let day = env.block.time.seconds() / 86400;
+
+    let (spreads_sum, swaps) = DAILY_SPREADS.may_load(storage, day)?.unwrap_or_default();
+    DAILY_SPREADS.save(storage, day, &(spreads_sum + spread, swaps + 1))?;
+
+    if let Some(expired_day) = day.checked_sub(SPREAD_AVG_PERIOD_DAYS) {
+        DAILY_SPREADS.remove(storage, expired_day);
+    }
+
+    Ok(())
+}
+
+/// Pushes the LP fees of a swap to [`FEE_RING_24H`]. Entries older than [`FEE_WINDOW_SECS`]
+/// are removed as well as the oldest entries once [`MAX_FEE_WINDOW_SWAPS`] swaps are kept.
+///
+/// * **fees** the LP fees charged in every pool asset.
+fn record_fees(storage: &mut dyn Storage, env: &Env, fees: [Uint128; 2]) -> StdResult<()> {
+    let now = env.block.time.seconds();
+    let window_start = now.saturating_sub(FEE_WINDOW_SECS);
+    let (mut front, back) = FEE_RING_24H_BOUNDS.may_load(storage)?.unwrap_or_default();
+    let mut totals = FEE_RING_24H_TOTALS.may_load(storage)?.unwrap_or_default();
+
+    while front < back {
+        let (timestamp, fee0, fee1) = FEE_RING_24H.load(storage, front)?;
+        if timestamp >= window_start && back - front < MAX_FEE_WINDOW_SWAPS {
+            break;
+        }
+        FEE_RING_24H.remove(storage, front);
+        totals = [totals[0].checked_sub(fee0)?, totals[1].checked_sub(fee1)?];
+        front += 1;
+    }
+
+    FEE_RING_24H.save(storage, back, &(now, fees[0], fees[1]))?;
+    FEE_RING_24H_BOUNDS.save(storage, &(front, back + 1))?;
+    FEE_RING_24H_TOTALS.save(
+        storage,
+        &[
+            totals[0].checked_add(fees[0])?,
+            totals[1].checked_add(fees[1])?,
+        ],
+    )
+}
+
+/// Pushes a swap observation to [`FEE_ADJUSTED_TWAP`]. The oldest observations are removed once
+/// [`MAX_FEE_ADJUSTED_OBSERVATIONS`] swaps are kept. Swaps which return nothing aren't observed.
+///
+/// * **amounts** the amounts of the first and the second pool asset swapped before the commission
+/// is deducted.
+///
+/// * **fee_rate** the commission relative to the amount returned before the commission.
+fn record_fee_adjusted_observation(
+    storage: &mut dyn Storage,
+    env: &Env,
+    amounts: [Uint128; 2],
+    fee_rate: Decimal,
+) -> StdResult<()> {
+    if amounts.iter().any(Uint128::is_zero) {
+        return Ok(());
+    }
+
+    let mut state = FEE_ADJUSTED_TWAP.may_load(storage)?.unwrap_or_default();
+    while state.observations.len() >= MAX_FEE_ADJUSTED_OBSERVATIONS {
+        state.observations.pop_front();
+    }
+    state.observations.push_back(FeeAdjustedObservation {
+        timestamp: env.block.time.seconds(),
+        effective_price: Decimal::checked_from_ratio(amounts[1], amounts[0]).unwrap_or_default(),
+        fee_rate,
+        volume: amounts[1],
+    });
+
+    FEE_ADJUSTED_TWAP.save(storage, &state)
+}
+
+/// Adds `volume` to the volume since the newest price snapshot and pushes a new snapshot
+/// to [`PRICE_RING`] if at least [`SNAPSHOT_INTERVAL_SECS`] passed since the newest one.
+/// The oldest snapshot is overwritten once the ring buffer is full.
+///
+/// * **pools** pool reserves after the swap.
+///
+/// * **volume** amount of the second asset swapped.
+fn record_price_snapshot(
+    storage: &mut dyn Storage,
+    env: &Env,
+    pools: [Uint128; 2],
+    volume: Uint128,
+) -> StdResult<()> {
+    let volume = SNAPSHOT_VOLUME.may_load(storage)?.unwrap_or_default() + volume;
+    let head = RING_HEAD.may_load(storage)?;
+
+    if let Some(head) = head {
+        let last_snapshot = PRICE_RING.load(storage, head)?;
+        if env.block.time.seconds() - last_snapshot.timestamp < SNAPSHOT_INTERVAL_SECS {
+            return SNAPSHOT_VOLUME.save(storage, &volume);
+        }
+    }
+
+    let head = head.map_or(0, |head| (head + 1) % PRICE_RING_SIZE);
+    PRICE_RING.save(
+        storage,
+        head,
+        &PriceSnapshot {
+            price: Decimal::checked_from_ratio(pools[1], pools[0]).unwrap_or_default(),
+            timestamp: env.block.time.seconds(),
+            volume,
+        },
+    )?;
+    RING_HEAD.save(storage, &head)?;
+    SNAPSHOT_VOLUME.remove(storage);
+
+    Ok(())
+}
+
+/// Saves the pool reserves for the current block to [`RESERVES_HISTORY`] and removes the entries
+/// older than [`RESERVES_HISTORY_DEPTH`] blocks.
+///
+/// * **reserves** pool reserves after the operation.
+fn record_reserves(storage: &mut dyn Storage, env: &Env, reserves: [Uint128; 2]) -> StdResult<()> {
+    let height = env.block.height;
+    RESERVES_HISTORY.save(storage, height, &reserves)?;
+
+    let cutoff = height.saturating_sub(RESERVES_HISTORY_DEPTH);
+    let oldest = OLDEST_HISTORY_BLOCK.may_load(storage)?.unwrap_or(height);
+    if oldest >= cutoff {
+        return OLDEST_HISTORY_BLOCK.save(storage, &oldest);
+    }
+
+    let expired = RESERVES_HISTORY
+        .keys(
+            storage,
+            Some(Bound::inclusive(oldest)),
+            Some(Bound::exclusive(cutoff)),
+            Order::Ascending,
+        )
+        .collect::<StdResult<Vec<_>>>()?;
+    for block in expired {
+        RESERVES_HISTORY.remove(storage, block);
+    }
+
+    // The current block is always kept, so there is an entry at or after the cutoff
+    let oldest = RESERVES_HISTORY
+        .keys(
+            storage,
+            Some(Bound::inclusive(cutoff)),
+            None,
+            Order::Ascending,
+        )
+        .next()
+        .transpose()?
+        .unwrap_or(height);
+    OLDEST_HISTORY_BLOCK.save(storage, &oldest)
+}
+
+/// Returns the constant product `k = x * y` of the pool reserves. The product of two [`Uint128`]
+/// reserves always fits into [`Uint256`], so it can't overflow.
+fn compute_invariant(x: Uint128, y: Uint128) -> Uint256 {
+    Uint256::from(x) * Uint256::from(y)
+}
+
+/// Returns the square root of `value` rounded down.
+fn isqrt(value: Uint256) -> Uint256 {
+    let mut sqrt = [0u8; 32];
+    U256::from_big_endian(&value.to_be_bytes())
+        .integer_sqrt()
+        .to_big_endian(&mut sqrt);
+    Uint256::from_be_bytes(sqrt)
+}
+
+/// Checks that the pool invariant did not decrease during a swap. The commission stays in the pool
+/// and the fee transfers never exceed it, so the constant product can only grow. A decrease of
+/// one unit is tolerated for rounding.
+///
+/// * **pre_swap_invariant** is the constant product of the reserves before the swap.
+fn verify_invariant(deps: Deps, pre_swap_invariant: Uint256) -> Result<(), ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let pools = config
+        .pair_info
+        .query_pools(&deps.querier, &config.pair_info.contract_addr)?;
+    let post_swap_invariant = compute_invariant(pools[0].amount, pools[1].amount);
+
+    if post_swap_invariant < pre_swap_invariant.saturating_sub(Uint256::one()) {
+        return Err(ContractError::InvariantViolated {
+            pre_swap: pre_swap_invariant,
+            post_swap: post_swap_invariant,
+        });
+    }
+
+    Ok(())
+}
+
+/// Starts the [`EMERGENCY_WITHDRAW_TIMELOCK`] for draining the pair to `recipient`.
+/// A new proposal replaces the pending one.
+///
+/// * **recipient** address that receives the pair balances.
+///
+/// ## Executor
+/// Only the factory owner or a [`Role::EmergencyAdmin`] can execute this.
+pub fn propose_emergency_withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_emergency_admin(deps.querier, &config, &info.sender)?;
+
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let unlock_at = env.block.time.seconds() + EMERGENCY_WITHDRAW_TIMELOCK;
+    EMERGENCY_WITHDRAW_PROPOSAL.save(deps.storage, &(recipient.clone(), unlock_at))?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "propose_emergency_withdraw"),
+        attr("recipient", recipient),
+        attr("unlock_at", unlock_at.to_string()),
+    ]))
+}
+
+/// Cancels the pending emergency withdrawal.
+///
+/// ## Executor
+/// Only the factory owner or a [`Role::EmergencyAdmin`] can execute this.
+pub fn drop_emergency_withdraw_proposal(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_emergency_admin(deps.querier, &config, &info.sender)?;
+
+    EMERGENCY_WITHDRAW_PROPOSAL.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "drop_emergency_withdraw_proposal"))
+}
+
+/// Transfers all native balances and pool CW20 balances of the pair to `recipient`. The pair is
+/// paused permanently afterwards.
+///
+/// * **recipient** address that receives the pair balances. It must match the proposal.
+///
+/// ## Executor
+/// Only the factory owner or a [`Role::EmergencyAdmin`] can execute this once the
+/// [`EMERGENCY_WITHDRAW_TIMELOCK`] has passed.
+pub fn emergency_withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_emergency_admin(deps.querier, &config, &info.sender)?;
+
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let (proposed_recipient, unlock_at) = EMERGENCY_WITHDRAW_PROPOSAL
+        .may_load(deps.storage)?
+        .ok_or(ContractError::EmergencyWithdrawProposalNotFound {})?;
+    if proposed_recipient != recipient {
+        return Err(ContractError::EmergencyWithdrawRecipientMismatch(
+            proposed_recipient.to_string(),
+        ));
+    }
+    if env.block.time.seconds() < unlock_at {
+        return Err(ContractError::EmergencyWithdrawTimelocked(unlock_at));
+    }
+
+    EMERGENCY_WITHDRAW_PROPOSAL.remove(deps.storage);
+    PAUSED.save(deps.storage, &true)?;
+
+    let mut assets = deps
+        .querier
+        .query_all_balances(&env.contract.address)?
+        .into_iter()
+        .map(|coin| Asset {
+            info: AssetInfo::NativeToken { denom: coin.denom },
+            amount: coin.amount,
+        })
+        .collect::<Vec<_>>();
+    for pool in config
+        .pair_info
+        .query_pools(&deps.querier, &env.contract.address)?
+    {
+        if !pool.is_native_token() && !pool.amount.is_zero() {
+            assets.push(pool);
+        }
+    }
+
+    if config.track_asset_balances {
+        for asset_info in &config.pair_info.asset_infos {
+            BALANCES.save(deps.storage, asset_info, &Uint128::zero(), env.block.height)?;
+        }
+    }
+
+    let messages = assets
+        .iter()
+        .map(|asset| asset.clone().into_msg(&recipient))
+        .collect::<StdResult<Vec<_>>>()?;
+    let withdrawn_assets = assets
+        .iter()
+        .map(|asset| asset.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "emergency_withdraw"),
+        attr("recipient", recipient),
+        attr("assets", withdrawn_assets),
+    ]))
+}
+
+/// Stores the current price accumulators under an auto-incremented snapshot identifier.
+///
+/// ## Executor
+/// Anyone can execute this.
+pub fn save_twap_snapshot(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let snapshot = current_twap_snapshot(deps.as_ref(), env, &config)?;
+
+    let snapshot_id = NEXT_TWAP_SNAPSHOT_ID
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    NEXT_TWAP_SNAPSHOT_ID.save(deps.storage, &(snapshot_id + 1))?;
+    TWAP_SNAPSHOTS.save(deps.storage, snapshot_id, &snapshot)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "save_twap_snapshot"),
+        attr("snapshot_id", snapshot_id.to_string()),
+        attr("timestamp", snapshot.timestamp.to_string()),
+    ]))
+}
+
+/// Stores the current pool state under an auto-incremented snapshot identifier. The oldest
+/// snapshot is removed once more than [`MAX_POOL_SNAPSHOTS`] snapshots are stored.
+///
+/// ## Executor
+/// Anyone can execute this.
+pub fn take_snapshot(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let (reserves, total_lp) = pool_info(deps.querier, &config)?;
+
+    let snapshot_id = NEXT_SNAPSHOT_ID.may_load(deps.storage)?.unwrap_or_default();
+    NEXT_SNAPSHOT_ID.save(deps.storage, &(snapshot_id + 1))?;
+    if snapshot_id >= MAX_POOL_SNAPSHOTS {
+        SNAPSHOTS.remove(deps.storage, snapshot_id - MAX_POOL_SNAPSHOTS);
+    }
+    SNAPSHOTS.save(
+        deps.storage,
+        snapshot_id,
+        &Snapshot {
+            invariant: compute_invariant(reserves[0].amount, reserves[1].amount),
+            price: Decimal::checked_from_ratio(reserves[1].amount, reserves[0].amount)
+                .unwrap_or_default(),
+            reserves,
+            total_lp,
+            timestamp: env.block.time.seconds(),
+            block: env.block.height,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "take_snapshot"),
+        attr("snapshot_id", snapshot_id.to_string()),
+    ]))
+}
+
+/// Refreshes [`TOP_LP_HOLDER_HEAP`] with the current LP balances of `addresses`. Addresses
+/// which no longer hold enough LP tokens are dropped from the heap. The LP tokens in the cooldown
+/// sent with an LP transfer are moved to the recipient.
+///
+/// * **addresses** addresses whose LP balances changed.
+///
+/// * **transfer** the LP transfer which changed the balances, if any.
+///
+/// ## Executor
+/// Only the LP token can execute this.
+pub fn update_lp_holders(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    addresses: Vec<String>,
+    transfer: Option<LpTransfer>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.pair_info.liquidity_token {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let (Some(cooldown_secs), Some(transfer)) = (config.lp_cooldown_secs, transfer) {
+        carry_lp_cooldown(deps.branch(), &env, &config, cooldown_secs, transfer)?;
+    }
+
+    let mut holders = vec![];
+    for address in addresses {
+        let address = deps.api.addr_validate(&address)?;
+        // The pair only holds LP tokens while liquidity is withdrawn or locked forever
+        if address == env.contract.address {
+            continue;
+        }
+
+        let balance =
+            query_token_balance(&deps.querier, &config.pair_info.liquidity_token, &address)?;
+        checkpoint_user_stats(deps.storage, &env, &address, balance)?;
+        holders.push((balance, address));
+    }
+
+    let mut heap: Vec<_> = TOP_LP_HOLDER_HEAP
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .into_vec()
+        .into_iter()
+        .filter(|(_, address)| !holders.iter().any(|(_, holder)| holder == address))
+        .collect();
+    heap.extend(
+        holders
+            .into_iter()
+            .filter(|(balance, _)| !balance.is_zero()),
+    );
+
+    // Keep the largest balances only
+    heap.sort_unstable_by(|a, b| b.cmp(a));
+    heap.truncate(MAX_TOP_LP_HOLDERS);
+    TOP_LP_HOLDER_HEAP.save(deps.storage, &heap.into())?;
+
+    Ok(Response::new().add_attribute("action", "update_lp_holders"))
+}
+
+/// Moves the LP fees earned by the previous LP balance of `address` to its stats and stores the
+/// new balance.
+fn checkpoint_user_stats(
+    storage: &mut dyn Storage,
+    env: &Env,
+    address: &Addr,
+    lp_balance: Uint128,
+) -> StdResult<()> {
+    let fee_per_share = FEE_PER_SHARE_CUMULATIVE
+        .may_load(storage)?
+        .unwrap_or_default();
+    let mut stats = USER_LP_STATS
+        .may_load(storage, address)?
+        .unwrap_or_default();
+    stats.fees_accrued = stats.fees_earned(&fee_per_share)?;
+    stats.fee_per_share_checkpoint = fee_per_share;
+    stats.lp_balance = lp_balance;
+    stats.last_activity_time = env.block.time.seconds();
+    USER_LP_STATS.save(storage, address, &stats)
+}
+
+/// Sets the minimum amount of each pool asset which must be provided in a single liquidity provision.
+///
+/// * **min_amounts** the minimum amounts in the order of the pair assets. Zero disables the check.
+///
+/// ## Executor
+/// Only the factory owner or the pair admin can execute this.
+pub fn update_min_liquidity_threshold(
+    deps: DepsMut,
+    info: MessageInfo,
+    min_amounts: [Uint128; 2],
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    assert_owner_or_pair_admin(deps.querier, &config, &info.sender)?;
+
+    config.min_provide_amount = min_amounts;
+    save_config_with_hash(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "update_min_liquidity_threshold"),
+        attr(
+            "min_amounts",
+            format!("{}, {}", min_amounts[0], min_amounts[1]),
+        ),
+    ]))
+}
+
+/// Sets the price movement after which swaps emit a `price_alert` event and notify the alert recipient.
+///
+/// * **alert_config** the price alert settings.
+///
+/// ## Executor
+/// Only the factory owner or the pair admin can execute this.
+pub fn set_price_alert_threshold(
+    deps: DepsMut,
+    info: MessageInfo,
+    alert_config: PriceAlertConfig,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_owner_or_pair_admin(deps.querier, &config, &info.sender)?;
+
+    if alert_config.base_price.is_zero() {
+        return Err(ContractError::ZeroBasePrice {});
+    }
+    if let Some(recipient) = &alert_config.alert_recipient {
+        deps.api.addr_validate(recipient.as_str())?;
+    }
+
+    PRICE_ALERT_CONFIG.save(deps.storage, &alert_config)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "set_price_alert_threshold"),
+        attr("base_price", alert_config.base_price.to_string()),
+        attr(
+            "alert_threshold_bps",
+            alert_config.alert_threshold_bps.to_string(),
+        ),
+    ]))
+}
+
+/// Sets the share of the swap commission returned to the swap receiver. The rebate is paid out of
+/// the LP share of the commission, so it can't exceed the commission share left after the Maker fee.
+///
+/// * **rebate_bps** the rebate in bps of the swap commission.
+///
+/// * **expires_at** the timestamp after which the rebate is no longer returned. `None` never expires.
+///
+/// ## Executor
+/// Only the factory owner or the pair admin can execute this.
+pub fn set_fee_rebate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    rebate_bps: u16,
+    expires_at: Option<u64>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    assert_owner_or_pair_admin(deps.querier, &config, &info.sender)?;
+
+    let fee_info = query_pair_fee_info(
+        &deps.querier,
+        &config.factory_addr,
+        &config.pair_info.contract_addr,
+        config.pair_info.pair_type.clone(),
+    )?;
+    let max_bps = (Uint128::new(10000) * (Decimal::one() - fee_info.maker_fee_rate)).u128() as u16;
+    if rebate_bps > max_bps {
+        return Err(ContractError::FeeRebateTooHigh { max_bps });
+    }
+    if let Some(expires_at) = expires_at {
+        if expires_at <= env.block.time.seconds() {
+            return Err(ContractError::FeeRebateExpired { expires_at });
+        }
+    }
+
+    config.swap_fee_rebate_bps = rebate_bps;
+    config.fee_rebate_expires_at = expires_at;
+    save_config_with_hash(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "set_fee_rebate"),
+        attr("rebate_bps", rebate_bps.to_string()),
+        attr(
+            "expires_at",
+            expires_at.map_or("never".to_string(), |expires_at| expires_at.to_string()),
+        ),
+    ]))
+}
+
+/// Sets the max reserves of the pool assets after a liquidity provision. Swaps aren't limited.
+///
+/// * **caps** the caps of the pool assets in the pool asset order. `None` removes the cap.
+///
+/// ## Executor
+/// Only the factory owner can execute this.
+pub fn set_tvl_cap(
+    deps: DepsMut,
+    info: MessageInfo,
+    caps: [Option<Uint128>; 2],
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != query_factory_config(&deps.querier, &config.factory_addr)?.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    [config.max_tvl_asset0, config.max_tvl_asset1] = caps;
+    save_config_with_hash(deps.storage, &config)?;
+
+    let cap_attr = |cap: Option<Uint128>| cap.map_or("none".to_string(), |cap| cap.to_string());
+    Ok(Response::new().add_attributes([
+        attr("action", "set_tvl_cap"),
+        attr("max_tvl_asset0", cap_attr(caps[0])),
+        attr("max_tvl_asset1", cap_attr(caps[1])),
+    ]))
+}
+
+/// Adds the addresses to the sender whitelist or removes them from it. Adding to an open pair
+/// makes it permissioned. Removing all the addresses doesn't open the pair again and removing
+/// from an open pair does nothing.
+///
+/// * **addresses** the addresses to add or remove.
+///
+/// * **add** whether the addresses are added or removed.
+///
+/// ## Executor
+/// Only the factory owner can execute this.
+pub fn update_sender_whitelist(
+    deps: DepsMut,
+    info: MessageInfo,
+    addresses: Vec<String>,
+    add: bool,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != query_factory_config(&deps.querier, &config.factory_addr)?.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let addresses = addresses
+        .iter()
+        .map(|addr| deps.api.addr_validate(addr))
+        .collect::<StdResult<Vec<_>>>()?;
+    if add {
+        let whitelist = config.sender_whitelist.get_or_insert_with(Vec::new);
+        for addr in addresses {
+            if !whitelist.contains(&addr) {
+                whitelist.push(addr);
             }
-            .into_msg(fee_share.recipient)?;
-            messages.push(fee_share_msg);
         }
+    } else if let Some(whitelist) = &mut config.sender_whitelist {
+        whitelist.retain(|addr| !addresses.contains(addr));
     }
+    save_config_with_hash(deps.storage, &config)?;
 
-    // Compute the Maker fee
-    let mut maker_fee_amount = Uint128::zero();
-    if let Some(fee_address) = fee_info.fee_address {
-        if let Some(f) = calculate_maker_fee(
-            &ask_pool.info,
-            fees_commission_amount,
-            fee_info.maker_fee_rate,
-        ) {
-            maker_fee_amount = f.amount;
-            messages.push(f.into_msg(fee_address)?);
+    Ok(Response::new().add_attribute(
+        "action",
+        if add {
+            "add_to_whitelist"
+        } else {
+            "remove_from_whitelist"
+        },
+    ))
+}
+
+/// Sets the max USD value of a single swap and of all the swaps of a day. The limits can only be
+/// set if the USD oracle is set with [`XYKPoolUpdateParams::UpdateUsdOracle`].
+///
+/// * **max_single** the max USD value of a single swap. `None` removes the limit.
+///
+/// * **max_daily** the max USD value of all the swaps of a day. `None` removes the limit.
+///
+/// ## Executor
+/// Only the factory owner can execute this.
+pub fn update_swap_limits(
+    deps: DepsMut,
+    info: MessageInfo,
+    max_single: Option<Uint128>,
+    max_daily: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != query_factory_config(&deps.querier, &config.factory_addr)?.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if (max_single.is_some() || max_daily.is_some()) && config.usd_oracle.is_none() {
+        return Err(ContractError::UsdOracleNotSet {});
+    }
+
+    config.max_single_swap_usd = max_single;
+    config.max_daily_swap_usd = max_daily;
+    save_config_with_hash(deps.storage, &config)?;
+
+    let limit_attr =
+        |limit: Option<Uint128>| limit.map_or("none".to_string(), |limit| limit.to_string());
+    Ok(Response::new().add_attributes([
+        attr("action", "update_swap_limits"),
+        attr("max_single_swap_usd", limit_attr(max_single)),
+        attr("max_daily_swap_usd", limit_attr(max_daily)),
+    ]))
+}
+
+/// Adds the address to [`BLOCKED_SENDERS`].
+///
+/// * **address** the address which can't swap, provide or withdraw liquidity anymore.
+///
+/// * **reason** why the address is blocked.
+///
+/// ## Executor
+/// Only the factory owner can execute this.
+pub fn block_sender(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+    reason: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != query_factory_config(&deps.querier, &config.factory_addr)?.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let address = deps.api.addr_validate(&address)?;
+    BLOCKED_SENDERS.save(
+        deps.storage,
+        &address,
+        &BlockReason {
+            reason: reason.clone(),
+            blocked_at: env.block.time.seconds(),
+        },
+    )?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "block_sender"),
+        attr("address", address),
+        attr("reason", reason),
+    ]))
+}
+
+/// Removes the address from [`BLOCKED_SENDERS`].
+///
+/// ## Executor
+/// Only the factory owner can execute this.
+pub fn unblock_sender(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != query_factory_config(&deps.querier, &config.factory_addr)?.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let address = deps.api.addr_validate(&address)?;
+    BLOCKED_SENDERS.remove(deps.storage, &address);
+
+    Ok(
+        Response::new()
+            .add_attributes([attr("action", "unblock_sender"), attr("address", address)]),
+    )
+}
+
+/// Values the offer asset in USD with [`Config::usd_oracle`] and checks it against the swap
+/// limits. The USD value is added to the volume of the current day if the daily limit is set.
+/// Swaps are rejected if a limit is set but the oracle isn't.
+fn check_swap_limits(
+    storage: &mut dyn Storage,
+    querier: QuerierWrapper,
+    env: &Env,
+    config: &Config,
+    offer_asset: &Asset,
+) -> Result<(), ContractError> {
+    if config.max_single_swap_usd.is_none() && config.max_daily_swap_usd.is_none() {
+        return Ok(());
+    }
+    let oracle = config
+        .usd_oracle
+        .as_ref()
+        .ok_or(ContractError::UsdOracleNotSet {})?;
+
+    let prices: Vec<(AssetInfo, Uint256)> = querier.query_wasm_smart(
+        oracle,
+        &OracleQueryMsg::Consult {
+            token: offer_asset.info.clone(),
+            amount: offer_asset.amount,
+        },
+    )?;
+    let offer_usd: Uint128 = prices
+        .first()
+        .map(|(_, amount)| *amount)
+        .ok_or_else(|| {
+            StdError::generic_err(format!(
+                "The USD oracle has no price for {}",
+                offer_asset.info
+            ))
+        })?
+        .try_into()
+        .map_err(StdError::from)?;
+
+    if let Some(limit_usd) = config.max_single_swap_usd {
+        if offer_usd > limit_usd {
+            return Err(ContractError::SwapSizeExceeded {
+                offer_usd,
+                limit_usd,
+            });
         }
     }
 
-    if config.track_asset_balances {
-        BALANCES.save(
-            deps.storage,
-            &offer_pool.info,
-            &(offer_pool.amount + offer_amount),
-            env.block.height,
-        )?;
-        BALANCES.save(
-            deps.storage,
-            &ask_pool.info,
-            &(ask_pool.amount - return_amount - maker_fee_amount - fee_share_amount),
-            env.block.height,
+    if let Some(limit_usd) = config.max_daily_swap_usd {
+        let day = env.block.time.seconds() / 86400;
+        let volume_usd = match DAILY_SWAP_VOLUME_USD.may_load(storage)? {
+            Some((volume_day, volume)) if volume_day == day => volume,
+            _ => Uint128::zero(),
+        }
+        .checked_add(offer_usd)?;
+        if volume_usd > limit_usd {
+            return Err(ContractError::DailySwapLimitExceeded {
+                volume_usd,
+                limit_usd,
+            });
+        }
+        DAILY_SWAP_VOLUME_USD.save(storage, &(day, volume_usd))?;
+    }
+
+    Ok(())
+}
+
+/// Builds the `price_alert` event and the alert recipient notification if `new_price` deviates from
+/// [`PriceAlertConfig::base_price`] more than the alert threshold. The base price is set to the
+/// new price after every alert. The notification failure is ignored in [`reply`].
+///
+/// * **new_price** the price of the second asset in the first one after the swap.
+fn price_alert(
+    storage: &mut dyn Storage,
+    env: &Env,
+    new_price: Decimal,
+) -> StdResult<Option<(Event, Option<SubMsg>)>> {
+    let mut alert_config = if let Some(alert_config) = PRICE_ALERT_CONFIG.may_load(storage)? {
+        alert_config
+    } else {
+        return Ok(None);
+    };
+
+    let old_price = alert_config.base_price;
+    let deviation = new_price
+        .abs_diff(old_price)
+        .checked_div(old_price)
+        .unwrap_or(Decimal::MAX);
+    if deviation <= Decimal::from_ratio(alert_config.alert_threshold_bps, 10000u16) {
+        return Ok(None);
+    }
+
+    let deviation_bps = deviation.checked_mul_uint128(10000u128.into())?.u128() as u64;
+    let event = Event::new("price_alert").add_attributes([
+        attr("deviation_bps", deviation_bps.to_string()),
+        attr("old_price", old_price.to_string()),
+        attr("new_price", new_price.to_string()),
+    ]);
+    let notification = alert_config
+        .alert_recipient
+        .as_ref()
+        .map(|recipient| -> StdResult<_> {
+            Ok(SubMsg::reply_on_error(
+                wasm_execute(
+                    recipient,
+                    &PriceAlertMsg::Alert {
+                        pair: env.contract.address.clone(),
+                        deviation_bps,
+                        old_price,
+                        new_price,
+                    },
+                    vec![],
+                )?,
+                PRICE_ALERT_REPLY_ID,
+            ))
+        })
+        .transpose()?;
+
+    alert_config.base_price = new_price;
+    PRICE_ALERT_CONFIG.save(storage, &alert_config)?;
+
+    Ok(Some((event, notification)))
+}
+
+/// Sets the contract notified with a [`CustomHookMsg`] after pair actions.
+///
+/// * **custom_hook** the hook configuration. [`None`] removes the hook.
+///
+/// ## Executor
+/// Only the factory can execute this.
+pub fn set_custom_hook(
+    deps: DepsMut,
+    info: MessageInfo,
+    custom_hook: Option<CustomHookConfig>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.factory_addr {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let hook_contract = if let Some(custom_hook) = &custom_hook {
+        deps.api.addr_validate(custom_hook.contract.as_str())?;
+        custom_hook.contract.to_string()
+    } else {
+        "none".to_string()
+    };
+
+    config.custom_hook = custom_hook;
+    save_config_with_hash(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "set_custom_hook"),
+        attr("custom_hook", hook_contract),
+    ]))
+}
+
+/// Stores whether the pair is paused in the factory, so swaps and liquidity provision don't
+/// have to query the factory.
+///
+/// * **paused** whether the pair is paused either individually or globally.
+///
+/// ## Executor
+/// Only the factory can execute this.
+pub fn set_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.factory_addr {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    FACTORY_PAUSED.save(deps.storage, &paused)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "set_paused"),
+        attr("paused", paused.to_string()),
+    ]))
+}
+
+/// Builds the sub-message notifying the custom hook about a pair action. Nothing is returned if
+/// there is no hook or it isn't configured for the action. The hook runs with its own gas limit
+/// and its failure is ignored in [`reply`].
+///
+/// * **hook_msg** the message describing the pair action.
+fn custom_hook_message(config: &Config, hook_msg: CustomHookMsg) -> StdResult<Option<SubMsg>> {
+    match &config.custom_hook {
+        Some(custom_hook) if custom_hook.hook_type.matches(&hook_msg.hook_type()) => Ok(Some(
+            SubMsg::reply_on_error(
+                wasm_execute(&custom_hook.contract, &hook_msg, vec![])?,
+                CUSTOM_HOOK_REPLY_ID,
+            )
+            .with_gas_limit(custom_hook.gas_limit),
+        )),
+        _ => Ok(None),
+    }
+}
+
+/// Checks that the sender is the factory owner or the pair admin set in the factory.
+fn assert_owner_or_pair_admin(
+    querier: QuerierWrapper,
+    config: &Config,
+    sender: &Addr,
+) -> Result<(), ContractError> {
+    let factory_config = query_factory_config(&querier, &config.factory_addr)?;
+
+    if *sender != factory_config.owner {
+        let pair_admin = query_pair_admin(
+            &querier,
+            &config.factory_addr,
+            &config.pair_info.contract_addr,
         )?;
+        if pair_admin.as_ref() != Some(sender) {
+            return Err(ContractError::Unauthorized {});
+        }
     }
 
-    // Accumulate prices for the assets in the pool
-    if let Some((price0_cumulative_new, price1_cumulative_new, block_time)) =
-        accumulate_prices(env, &config, pools[0].amount, pools[1].amount)?
+    Ok(())
+}
+
+/// Checks that the sender is the factory owner or holds the [`Role::EmergencyAdmin`] role.
+fn assert_emergency_admin(
+    querier: QuerierWrapper,
+    config: &Config,
+    sender: &Addr,
+) -> Result<(), ContractError> {
+    let factory_config = query_factory_config(&querier, &config.factory_addr)?;
+    if *sender != factory_config.owner
+        && !query_has_role(&querier, &config.factory_addr, sender, Role::EmergencyAdmin)?
     {
-        config.price0_cumulative_last = price0_cumulative_new;
-        config.price1_cumulative_last = price1_cumulative_new;
-        config.block_time_last = block_time;
-        CONFIG.save(deps.storage, &config)?;
+        return Err(ContractError::Unauthorized {});
     }
 
-    Ok(Response::new()
-        .add_messages(
-            // 1. send collateral tokens from the contract to a user
-            // 2. send inactive commission fees to the Maker contract
-            messages,
-        )
-        .add_attributes(vec![
-            attr("action", "swap"),
-            attr("sender", sender),
-            attr("receiver", receiver),
-            attr("offer_asset", offer_asset.info.to_string()),
-            attr("ask_asset", ask_pool.info.to_string()),
-            attr("offer_amount", offer_amount),
-            attr("return_amount", return_amount),
-            attr("spread_amount", spread_amount),
-            attr("commission_amount", commission_amount),
-            attr("maker_fee_amount", maker_fee_amount),
-            attr("fee_share_amount", fee_share_amount),
-        ]))
+    Ok(())
 }
 
 /// Updates the pool configuration with the specified parameters in the `params` variable.
@@ -788,13 +2583,25 @@ pub fn update_config(
     let mut config = CONFIG.load(deps.storage)?;
     let factory_config = query_factory_config(&deps.querier, &config.factory_addr)?;
 
+    let params = from_json::<XYKPoolUpdateParams>(&params)?;
+
+    // The factory forwards config updates on behalf of the pair admin, who can only tune the pool
     if info.sender != factory_config.owner {
-        return Err(ContractError::Unauthorized {});
+        if info.sender != config.factory_addr {
+            return Err(ContractError::Unauthorized {});
+        }
+        if !matches!(
+            params,
+            XYKPoolUpdateParams::UpdateMaxSpread { .. }
+                | XYKPoolUpdateParams::UpdateLpCooldown { .. }
+        ) {
+            return Err(ContractError::ParamsNotForwardable {});
+        }
     }
 
     let mut response = Response::default();
 
-    match from_json::<XYKPoolUpdateParams>(&params)? {
+    match params {
         XYKPoolUpdateParams::EnableAssetBalancesTracking => {
             if config.track_asset_balances {
                 return Err(ContractError::AssetBalancesTrackingIsAlreadyEnabled {});
@@ -809,50 +2616,156 @@ pub fn update_config(
                 BALANCES.save(deps.storage, &pool.info, &pool.amount, env.block.height)?;
             }
 
-            CONFIG.save(deps.storage, &config)?;
+            save_config_with_hash(deps.storage, &config)?;
 
             response.attributes.push(attr(
                 "asset_balances_tracking".to_owned(),
                 "enabled".to_owned(),
             ));
         }
-        XYKPoolUpdateParams::EnableFeeShare {
-            fee_share_bps,
-            fee_share_address,
+        XYKPoolUpdateParams::EnableFeeShare {
+            fee_share_bps,
+            fee_share_address,
+        } => {
+            // Enable fee sharing for this contract
+            // If fee sharing is already enabled, we should be able to overwrite
+            // the values currently set
+
+            // Ensure the fee share isn't 0 and doesn't exceed the maximum allowed value
+            if fee_share_bps == 0 || fee_share_bps > MAX_FEE_SHARE_BPS {
+                return Err(ContractError::FeeShareOutOfBounds {});
+            }
+
+            // Set sharing config
+            config.fee_share = Some(FeeShareConfig {
+                bps: fee_share_bps,
+                recipient: deps.api.addr_validate(&fee_share_address)?,
+            });
+
+            save_config_with_hash(deps.storage, &config)?;
+
+            response.attributes.push(attr("action", "enable_fee_share"));
+            response
+                .attributes
+                .push(attr("fee_share_bps", fee_share_bps.to_string()));
+            response
+                .attributes
+                .push(attr("fee_share_address", fee_share_address));
+        }
+        XYKPoolUpdateParams::DisableFeeShare => {
+            // Disable fee sharing for this contract by setting bps and
+            // address back to None
+            config.fee_share = None;
+            save_config_with_hash(deps.storage, &config)?;
+            response
+                .attributes
+                .push(attr("action", "disable_fee_share"));
+        }
+        XYKPoolUpdateParams::UpdateMaxSpread { max_spread } => {
+            validate_max_spread(max_spread)?;
+            config.max_spread = max_spread;
+            save_config_with_hash(deps.storage, &config)?;
+
+            response
+                .attributes
+                .push(attr("action", "update_max_spread"));
+            response.attributes.push(attr(
+                "max_spread",
+                max_spread.map_or_else(|| "none".to_string(), |v| v.to_string()),
+            ));
+        }
+        XYKPoolUpdateParams::UpdateLpCooldown { lp_cooldown_secs } => {
+            config.lp_cooldown_secs = lp_cooldown_secs;
+            save_config_with_hash(deps.storage, &config)?;
+
+            response
+                .attributes
+                .push(attr("action", "update_lp_cooldown"));
+            response.attributes.push(attr(
+                "lp_cooldown_secs",
+                lp_cooldown_secs.map_or_else(|| "none".to_string(), |v| v.to_string()),
+            ));
+        }
+        XYKPoolUpdateParams::UpdateOracle { oracle } => {
+            if let Some(oracle) = &oracle {
+                ORACLE.save(deps.storage, &deps.api.addr_validate(oracle)?)?;
+            } else {
+                ORACLE.remove(deps.storage);
+            }
+
+            response.attributes.push(attr("action", "update_oracle"));
+            response
+                .attributes
+                .push(attr("oracle", oracle.unwrap_or_else(|| "none".to_string())));
+        }
+        XYKPoolUpdateParams::UpdateUsdOracle { oracle } => {
+            config.usd_oracle = addr_opt_validate(deps.api, &oracle)?;
+            save_config_with_hash(deps.storage, &config)?;
+
+            response
+                .attributes
+                .push(attr("action", "update_usd_oracle"));
+            response
+                .attributes
+                .push(attr("oracle", oracle.unwrap_or_else(|| "none".to_string())));
+        }
+        XYKPoolUpdateParams::UpdateRebasingAsset {
+            config: rebasing_asset,
         } => {
-            // Enable fee sharing for this contract
-            // If fee sharing is already enabled, we should be able to overwrite
-            // the values currently set
-
-            // Ensure the fee share isn't 0 and doesn't exceed the maximum allowed value
-            if fee_share_bps == 0 || fee_share_bps > MAX_FEE_SHARE_BPS {
-                return Err(ContractError::FeeShareOutOfBounds {});
-            }
+            let rebasing_asset = rebasing_asset
+                .map(|rebasing_asset| -> Result<_, ContractError> {
+                    let token = deps.api.addr_validate(rebasing_asset.token.as_str())?;
+                    if !config.pair_info.asset_infos.contains(&AssetInfo::Token {
+                        contract_addr: token.clone(),
+                    }) {
+                        return Err(ContractError::InvalidRebasingAsset(token.into_string()));
+                    }
 
-            // Set sharing config
-            config.fee_share = Some(FeeShareConfig {
-                bps: fee_share_bps,
-                recipient: deps.api.addr_validate(&fee_share_address)?,
-            });
+                    Ok(RebasingAssetConfig {
+                        token,
+                        rate_oracle: deps
+                            .api
+                            .addr_validate(rebasing_asset.rate_oracle.as_str())?,
+                        base_denom: rebasing_asset.base_denom,
+                    })
+                })
+                .transpose()?;
 
-            CONFIG.save(deps.storage, &config)?;
+            response
+                .attributes
+                .push(attr("action", "update_rebasing_asset"));
+            response.attributes.push(attr(
+                "rebasing_asset",
+                rebasing_asset
+                    .as_ref()
+                    .map_or("none".to_string(), |rebasing_asset| {
+                        rebasing_asset.token.to_string()
+                    }),
+            ));
+            config.rebasing_asset = rebasing_asset;
+            save_config_with_hash(deps.storage, &config)?;
+        }
+        XYKPoolUpdateParams::UpdateComplianceMode { compliance_mode } => {
+            config.compliance_mode = compliance_mode;
+            save_config_with_hash(deps.storage, &config)?;
 
-            response.attributes.push(attr("action", "enable_fee_share"));
             response
                 .attributes
-                .push(attr("fee_share_bps", fee_share_bps.to_string()));
+                .push(attr("action", "update_compliance_mode"));
             response
                 .attributes
-                .push(attr("fee_share_address", fee_share_address));
+                .push(attr("compliance_mode", compliance_mode.to_string()));
         }
-        XYKPoolUpdateParams::DisableFeeShare => {
-            // Disable fee sharing for this contract by setting bps and
-            // address back to None
-            config.fee_share = None;
-            CONFIG.save(deps.storage, &config)?;
+        XYKPoolUpdateParams::UpdateSwapHistoryTracking { track_swap_history } => {
+            config.track_swap_history = track_swap_history;
+            save_config_with_hash(deps.storage, &config)?;
+
             response
                 .attributes
-                .push(attr("action", "disable_fee_share"));
+                .push(attr("action", "update_swap_history_tracking"));
+            response
+                .attributes
+                .push(attr("track_swap_history", track_swap_history.to_string()));
         }
     }
 
@@ -918,55 +2831,712 @@ pub fn calculate_maker_fee(
         return None;
     }
 
-    Some(Asset {
-        info: pool_info.clone(),
-        amount: maker_fee,
+    Some(Asset {
+        info: pool_info.clone(),
+        amount: maker_fee,
+    })
+}
+
+/// Exposes all the queries available in the contract.
+///
+/// ## Queries
+/// * **QueryMsg::Pair {}** Returns information about the pair in an object of type [`PairInfo`].
+///
+/// * **QueryMsg::Pool {}** Returns information about the amount of assets in the pair contract as
+/// well as the amount of LP tokens issued using an object of type [`PoolResponse`].
+///
+/// * **QueryMsg::Share { amount }** Returns the amount of assets that could be withdrawn from the pool
+/// using a specific amount of LP tokens. The result is returned in a vector that contains objects of type [`Asset`].
+///
+/// * **QueryMsg::Simulation { offer_asset }** Returns the result of a swap simulation using a [`SimulationResponse`] object.
+///
+/// * **QueryMsg::ReverseSimulation { ask_asset }** Returns the result of a reverse swap simulation  using
+/// a [`ReverseSimulationResponse`] object.
+///
+/// * **QueryMsg::CumulativePrices {}** Returns information about cumulative prices for the assets in the
+/// pool using a [`CumulativePricesResponse`] object.
+///
+/// * **QueryMsg::Config {}** Returns the configuration for the pair contract using a [`ConfigResponse`] object.
+///
+/// * **QueryMsg::AssetBalanceAt { asset_info, block_height }** Returns the balance of the specified asset that was in the pool
+/// just preceeding the moment of the specified block height creation.
+///
+/// * **QueryMsg::ImpermanentLoss { initial_assets, current_lp_amount }** Returns the impermanent loss
+/// of an LP position compared to holding the initial assets using an [`ImpermanentLossResponse`] object.
+///
+/// * **QueryMsg::BreakevenVolume { initial_price, current_price }** Returns the trading volume
+/// at which the LP fees offset the impermanent loss using a [`BreakevenVolumeResponse`] object.
+///
+/// * **QueryMsg::SimulateProvideLiquidity { assets }** Returns the amount of LP tokens minted for the
+/// specified deposit using a [`SimulateProvideResponse`] object.
+///
+/// * **QueryMsg::TwapSince { snapshot_id }** Returns the average prices since the specified snapshot
+/// using a [`TwapSinceResponse`] object.
+///
+/// * **QueryMsg::TopLpProviders { limit }** Returns the largest LP holders ordered by LP balance.
+///
+/// * **QueryMsg::PairHealth {}** Returns the health indicators of the pair using a
+/// [`PairHealthResponse`] object.
+///
+/// * **QueryMsg::PriceHistory { limit }** Returns the latest price snapshots from newest to oldest.
+///
+/// * **QueryMsg::PriceAt { timestamp }** Returns the price snapshot closest to the timestamp.
+///
+/// * **QueryMsg::ArbitrageOpportunity { reference_price }** Returns the trade which moves the pool
+/// price to the reference price using an [`ArbitrageOpportunityResponse`] object.
+///
+/// * **QueryMsg::FeePerShare {}** Returns the LP fees accrued per LP token using a
+/// [`FeePerShareResponse`] object.
+///
+/// * **QueryMsg::PairFees24h {}** Returns the LP fees of the swaps in the last 24 hours using a
+/// [`PairFees24hResponse`] object.
+///
+/// * **QueryMsg::Price { base, quote, amount }** Returns the oracle price of the base asset
+/// denominated in the quote asset using an [`OraclePriceResponse`] object.
+///
+/// * **QueryMsg::ConfigHash {}** Returns the config hash stored at the last config update.
+///
+/// * **QueryMsg::VerifyConfig { expected_hash }** Returns whether the current config hash matches the expected one.
+///
+/// * **QueryMsg::Invariant {}** Returns the current pool invariant using an [`InvariantResponse`] object.
+///
+/// * **QueryMsg::Snapshot { id }** Returns the pool state snapshot with the specified identifier.
+///
+/// * **QueryMsg::LatestSnapshot {}** Returns the latest pool state snapshot with its identifier.
+///
+/// * **QueryMsg::ReservesHistory { from_block, to_block }** Returns the pool reserves recorded
+/// in the specified block range.
+///
+/// * **QueryMsg::UserLpStats { address }** Returns the LP position stats of the address using a
+/// [`UserLpStatsResponse`] object.
+///
+/// * **QueryMsg::PriceFeed { asset }** Returns the spot price of the asset in the Pyth Network
+/// price feed format using a [`PythPriceResponse`] object.
+///
+/// * **QueryMsg::SpotPriceInAsset { ask_asset }** Returns the spot price of the other pool asset
+/// in `ask_asset` using a [`SpotPriceResponse`] object.
+///
+/// * **QueryMsg::FeeAdjustedTwap { window_secs }** Returns the volume weighted average price of the
+/// recent swaps before the commission using a [`FeeAdjustedTwapResponse`] object.
+///
+/// * **QueryMsg::NormalizedReserves {}** Returns the pool reserves with the rebasing asset valued
+/// in its base denomination using a [`NormalizedReservesResponse`] object.
+///
+/// * **QueryMsg::ContractInfo {}** Returns the cw2 contract name and version.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Pair {} => to_json_binary(&CONFIG.load(deps.storage)?.pair_info),
+        QueryMsg::Pool {} => to_json_binary(&query_pool(deps)?),
+        QueryMsg::Share { amount } => to_json_binary(&query_share(deps, amount)?),
+        QueryMsg::Simulation { offer_asset, .. } => {
+            to_json_binary(&query_simulation(deps, offer_asset)?)
+        }
+        QueryMsg::ReverseSimulation { ask_asset, .. } => {
+            to_json_binary(&query_reverse_simulation(deps, ask_asset)?)
+        }
+        QueryMsg::CumulativePrices {} => to_json_binary(&query_cumulative_prices(deps, env)?),
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::AssetBalanceAt {
+            asset_info,
+            block_height,
+        } => to_json_binary(&query_asset_balances_at(deps, asset_info, block_height)?),
+        QueryMsg::ImpermanentLoss {
+            initial_assets,
+            current_lp_amount,
+        } => to_json_binary(&query_impermanent_loss(
+            deps,
+            initial_assets,
+            current_lp_amount,
+        )?),
+        QueryMsg::BreakevenVolume {
+            initial_price,
+            current_price,
+        } => to_json_binary(&query_breakeven_volume(deps, initial_price, current_price)?),
+        QueryMsg::SimulateProvideLiquidity { assets } => {
+            to_json_binary(&query_simulate_provide(deps, assets)?)
+        }
+        QueryMsg::TwapSince { snapshot_id } => {
+            to_json_binary(&query_twap_since(deps, env, snapshot_id)?)
+        }
+        QueryMsg::FeePerShare {} => to_json_binary(&FeePerShareResponse {
+            asset_infos: CONFIG.load(deps.storage)?.pair_info.asset_infos,
+            fee_per_share_cumulative: FEE_PER_SHARE_CUMULATIVE
+                .may_load(deps.storage)?
+                .unwrap_or_default(),
+        }),
+        QueryMsg::TopLpProviders { limit } => to_json_binary(&query_top_lp_providers(deps, limit)?),
+        QueryMsg::PairHealth {} => to_json_binary(&query_pair_health(deps, env)?),
+        QueryMsg::PriceHistory { limit } => to_json_binary(&query_price_history(deps, limit)?),
+        QueryMsg::PriceAt { timestamp } => to_json_binary(&query_price_at(deps, timestamp)?),
+        QueryMsg::ArbitrageOpportunity { reference_price } => {
+            to_json_binary(&query_arbitrage_opportunity(deps, reference_price)?)
+        }
+        QueryMsg::PairFees24h {} => to_json_binary(&query_pair_fees_24h(deps, env)?),
+        QueryMsg::Price {
+            base,
+            quote,
+            amount,
+        } => to_json_binary(&query_oracle_price(deps, env, base, quote, amount)?),
+        QueryMsg::ConfigHash {} => to_json_binary(&CONFIG_HASH.load(deps.storage)?),
+        QueryMsg::VerifyConfig { expected_hash } => {
+            to_json_binary(&(CONFIG.load(deps.storage)?.hash()? == expected_hash))
+        }
+        QueryMsg::Invariant {} => to_json_binary(&query_invariant(deps)?),
+        QueryMsg::Snapshot { id } => to_json_binary(&SNAPSHOTS.load(deps.storage, id)?),
+        QueryMsg::LatestSnapshot {} => to_json_binary(&query_latest_snapshot(deps)?),
+        QueryMsg::ReservesHistory {
+            from_block,
+            to_block,
+        } => to_json_binary(&query_reserves_history(deps, from_block, to_block)?),
+        QueryMsg::UserLpStats { address } => to_json_binary(&query_user_lp_stats(deps, address)?),
+        QueryMsg::PriceFeed { asset } => to_json_binary(&query_price_feed(deps, env, asset)?),
+        QueryMsg::SpotPriceInAsset { ask_asset } => {
+            to_json_binary(&query_spot_price_in_asset(deps, ask_asset)?)
+        }
+        QueryMsg::FeeAdjustedTwap { window_secs } => {
+            to_json_binary(&query_fee_adjusted_twap(deps, env, window_secs)?)
+        }
+        QueryMsg::NormalizedReserves {} => to_json_binary(&query_normalized_reserves(deps)?),
+        QueryMsg::ContractInfo {} => to_json_binary(&get_contract_version(deps.storage)?),
+        _ => Err(StdError::generic_err("Query is not supported")),
+    }
+}
+
+/// Returns the price of `amount` of `base` denominated in `quote`. The spot price is computed
+/// from the pool reserves and the TWAP since the latest snapshot stored with
+/// [`ExecuteMsg::SaveTwapSnapshot`] is used if there is one.
+///
+/// * **amount** amount of the base asset to price. A zero amount returns the marginal price.
+pub fn query_oracle_price(
+    deps: Deps,
+    env: Env,
+    base: AssetInfo,
+    quote: AssetInfo,
+    amount: Uint128,
+) -> StdResult<OraclePriceResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let asset_infos = &config.pair_info.asset_infos;
+    if base.equal(&quote) || !asset_infos.contains(&base) || !asset_infos.contains(&quote) {
+        return Err(StdError::generic_err(
+            "Base and quote assets must be different pool assets",
+        ));
+    }
+
+    let pools = config
+        .pair_info
+        .query_pools(&deps.querier, &config.pair_info.contract_addr)?;
+    let (base_pool, quote_pool) = if pools[0].info.equal(&base) {
+        (pools[0].amount, pools[1].amount)
+    } else {
+        (pools[1].amount, pools[0].amount)
+    };
+    let marginal_price = Decimal::checked_from_ratio(quote_pool, base_pool).unwrap_or_default();
+
+    let execution_price = if amount.is_zero() || marginal_price.is_zero() {
+        marginal_price
+    } else {
+        let simulation = query_simulation(deps, base.with_balance(amount))?;
+        Decimal::from_ratio(
+            simulation.return_amount + simulation.commission_amount,
+            amount,
+        )
+    };
+
+    let mut twap = None;
+    if let Some(snapshot_id) = NEXT_TWAP_SNAPSHOT_ID.may_load(deps.storage)? {
+        let snapshot = TWAP_SNAPSHOTS.load(deps.storage, snapshot_id - 1)?;
+        if env.block.time.seconds() > snapshot.timestamp {
+            let period = env.block.time.seconds() - snapshot.timestamp;
+            let average_price = query_twap_since(deps, env, snapshot_id - 1)?
+                .average_prices
+                .into_iter()
+                .find(|(offer, _, _)| offer.equal(&base))
+                .map(|(_, _, price)| price)
+                .unwrap_or_default();
+            twap = Some((average_price, period));
+        }
+    }
+
+    Ok(OraclePriceResponse::new(
+        marginal_price,
+        execution_price,
+        twap,
+        config.block_time_last,
+    ))
+}
+
+/// Returns the LP fees of the swaps in the last [`FEE_WINDOW_SECS`]. The fees of the swaps which
+/// expired since the last swap are excluded as they are only removed by the next swap.
+pub fn query_pair_fees_24h(deps: Deps, env: Env) -> StdResult<PairFees24hResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let period_start = env.block.time.seconds().saturating_sub(FEE_WINDOW_SECS);
+    let (mut front, back) = FEE_RING_24H_BOUNDS
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    let mut totals = FEE_RING_24H_TOTALS
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+
+    for item in FEE_RING_24H.range(
+        deps.storage,
+        Some(Bound::inclusive(front)),
+        None,
+        Order::Ascending,
+    ) {
+        let (_, (timestamp, fee0, fee1)) = item?;
+        if timestamp >= period_start {
+            break;
+        }
+        totals = [totals[0].checked_sub(fee0)?, totals[1].checked_sub(fee1)?];
+        front += 1;
+    }
+
+    Ok(PairFees24hResponse {
+        fees: config
+            .pair_info
+            .asset_infos
+            .iter()
+            .zip(totals)
+            .map(|(asset_info, fee)| asset_info.with_balance(fee))
+            .collect(),
+        period_start,
+        swap_count: back - front,
+    })
+}
+
+/// Returns up to `limit` of the largest LP holders and their LP balances in descending order.
+pub fn query_top_lp_providers(deps: Deps, limit: u32) -> StdResult<Vec<(String, Uint128)>> {
+    let holders = TOP_LP_HOLDER_HEAP
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .into_sorted_vec()
+        .into_iter()
+        .rev()
+        .take(limit as usize)
+        .map(|(balance, address)| (address.to_string(), balance))
+        .collect();
+
+    Ok(holders)
+}
+
+/// Returns the swap which moves the pool price `reserve0 / reserve1` to `reference_price`
+/// in an [`ArbitrageOpportunityResponse`] object.
+///
+/// The optimal offer keeps the constant product `k` unchanged, so the first reserve becomes
+/// `sqrt(k * reference_price)` and the second one becomes `sqrt(k / reference_price)`.
+///
+/// * **reference_price** external price of the second asset denominated in the first one.
+pub fn query_arbitrage_opportunity(
+    deps: Deps,
+    reference_price: Decimal,
+) -> StdResult<ArbitrageOpportunityResponse> {
+    if reference_price.is_zero() {
+        return Err(StdError::generic_err("Reference price must be positive"));
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let pools = config
+        .pair_info
+        .query_pools(&deps.querier, &config.pair_info.contract_addr)?;
+    if pools.iter().any(|pool| pool.amount.is_zero()) {
+        return Err(StdError::generic_err("One of the pools is empty"));
+    }
+
+    let reserve0 = Uint256::from(pools[0].amount);
+    let reserve1 = Uint256::from(pools[1].amount);
+    let reference_price = Decimal256::from(reference_price);
+    let implied_price = Decimal256::from_ratio(reserve0, reserve1);
+
+    let price_diff = if implied_price > reference_price {
+        implied_price - reference_price
+    } else {
+        reference_price - implied_price
+    };
+    let deviation_bps = Uint128::try_from(Uint256::from(10000u16) * (price_diff / reference_price))
+        .ok()
+        .and_then(|deviation| i64::try_from(deviation.u128()).ok())
+        .unwrap_or(i64::MAX);
+    let deviation_bps = if implied_price < reference_price {
+        -deviation_bps
+    } else {
+        deviation_bps
+    };
+
+    // The constant product is kept in integers, as reserves of 18 decimal tokens easily exceed
+    // the range of Decimal256
+    let k = compute_invariant(pools[0].amount, pools[1].amount);
+    let target0 = isqrt(
+        k.checked_multiply_ratio(reference_price.numerator(), reference_price.denominator())
+            .map_err(|e| StdError::generic_err(e.to_string()))?,
+    );
+
+    let (offer_pool, ask_pool, offer_amount, direction) = if target0 > reserve0 {
+        (
+            &pools[0],
+            &pools[1],
+            target0 - reserve0,
+            TradeDirection::ZeroForOne,
+        )
+    } else {
+        let target1 = isqrt(
+            k.checked_multiply_ratio(reference_price.denominator(), reference_price.numerator())
+                .map_err(|e| StdError::generic_err(e.to_string()))?,
+        );
+        (
+            &pools[1],
+            &pools[0],
+            target1.saturating_sub(reserve1),
+            TradeDirection::OneForZero,
+        )
+    };
+    let offer_amount: Uint128 = offer_amount.try_into()?;
+
+    let mut expected_profit = Uint128::zero();
+    if !offer_amount.is_zero() {
+        let fee_info = query_pair_fee_info(
+            &deps.querier,
+            &config.factory_addr,
+            &config.pair_info.contract_addr,
+            config.pair_info.pair_type.clone(),
+        )?;
+        let (return_amount, _, _) = compute_swap(
+            offer_pool.amount,
+            ask_pool.amount,
+            offer_amount,
+            fee_info.total_fee_rate,
+        )?;
+
+        // Value the return in the offer asset using the reference price
+        let return_value = match direction {
+            TradeDirection::ZeroForOne => Uint256::from(return_amount) * reference_price,
+            TradeDirection::OneForZero => Uint256::from(return_amount)
+                .multiply_ratio(reference_price.denominator(), reference_price.numerator()),
+        };
+        expected_profit = return_value
+            .saturating_sub(offer_amount.into())
+            .try_into()?;
+    }
+
+    Ok(ArbitrageOpportunityResponse {
+        deviation_bps,
+        optimal_offer: offer_pool.info.with_balance(offer_amount),
+        expected_profit: offer_pool.info.with_balance(expected_profit),
+        direction,
+    })
+}
+
+/// Returns the position of the newest snapshot in [`PRICE_RING`] and the number of
+/// stored snapshots.
+fn price_ring_bounds(storage: &dyn Storage) -> StdResult<Option<(u32, u32)>> {
+    Ok(RING_HEAD.may_load(storage)?.map(|head| {
+        // The slot after the head is only taken once the ring buffer wrapped around
+        let len = if PRICE_RING.has(storage, (head + 1) % PRICE_RING_SIZE) {
+            PRICE_RING_SIZE
+        } else {
+            head + 1
+        };
+        (head, len)
+    }))
+}
+
+/// Returns up to `limit` of the latest price snapshots ordered from newest to oldest.
+pub fn query_price_history(deps: Deps, limit: u32) -> StdResult<Vec<PriceSnapshot>> {
+    if let Some((head, len)) = price_ring_bounds(deps.storage)? {
+        (0..limit.min(len))
+            .map(|i| PRICE_RING.load(deps.storage, (head + PRICE_RING_SIZE - i) % PRICE_RING_SIZE))
+            .collect()
+    } else {
+        Ok(vec![])
+    }
+}
+
+/// Returns the price snapshot with the timestamp closest to `timestamp`. If two snapshots
+/// are equally close, the older one is returned.
+pub fn query_price_at(deps: Deps, timestamp: u64) -> StdResult<Option<PriceSnapshot>> {
+    let (head, len) = if let Some(bounds) = price_ring_bounds(deps.storage)? {
+        bounds
+    } else {
+        return Ok(None);
+    };
+
+    // Snapshots are stored in chronological order starting from the slot after the head
+    let oldest = (head + PRICE_RING_SIZE + 1 - len) % PRICE_RING_SIZE;
+    let load = |i: u32| PRICE_RING.load(deps.storage, (oldest + i) % PRICE_RING_SIZE);
+
+    // Find the first snapshot taken at or after the timestamp
+    let (mut low, mut high) = (0, len);
+    while low < high {
+        let mid = (low + high) / 2;
+        if load(mid)?.timestamp < timestamp {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+
+    let closest = if low == len {
+        load(len - 1)?
+    } else if low == 0 {
+        load(0)?
+    } else {
+        let (before, after) = (load(low - 1)?, load(low)?);
+        if timestamp - before.timestamp <= after.timestamp - timestamp {
+            before
+        } else {
+            after
+        }
+    };
+
+    Ok(Some(closest))
+}
+
+/// Returns the average spread of the swaps in the last [`SPREAD_AVG_PERIOD_DAYS`] or `None` if
+/// there were no swaps in the period.
+fn average_spread(storage: &dyn Storage, env: &Env) -> StdResult<Option<Decimal256>> {
+    let today = env.block.time.seconds() / 86400;
+    let (spreads_sum, swaps) = DAILY_SPREADS
+        .range(
+            storage,
+            Some(Bound::inclusive(
+                today.saturating_sub(SPREAD_AVG_PERIOD_DAYS - 1),
+            )),
+            None,
+            Order::Ascending,
+        )
+        .try_fold((Decimal256::zero(), 0u64), |(sum, count), item| {
+            let (_, (spreads_sum, swaps)) = item?;
+            StdResult::Ok((sum + spreads_sum, count + swaps))
+        })?;
+
+    Ok((swaps > 0).then(|| spreads_sum / Decimal256::from_ratio(swaps, 1u8)))
+}
+
+/// Returns the spot price of `asset` denominated in the other pool asset in the Pyth Network
+/// price feed format. The confidence interval is the price multiplied by the average spread of
+/// the swaps in the last [`SPREAD_AVG_PERIOD_DAYS`] and the publish time is the time of the last
+/// pool update.
+pub fn query_price_feed(deps: Deps, env: Env, asset: AssetInfo) -> StdResult<PythPriceResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let pools = config
+        .pair_info
+        .query_pools(&deps.querier, &config.pair_info.contract_addr)?;
+    let (base_pool, quote_pool) = match pools.iter().position(|pool| pool.info.equal(&asset)) {
+        Some(0) => (pools[0].amount, pools[1].amount),
+        Some(_) => (pools[1].amount, pools[0].amount),
+        None => return Err(StdError::generic_err(format!("{asset} is not in the pool"))),
+    };
+    if base_pool.is_zero() {
+        return Err(StdError::generic_err("The pool is empty"));
+    }
+
+    let precision = 10u128.pow(PRICE_FEED_EXPO.unsigned_abs());
+    let price = quote_pool.full_mul(precision) / Uint256::from(base_pool);
+    let conf = average_spread(deps.storage, &env)?
+        .map(|spread| (Decimal256::from_ratio(price, 1u8) * spread).to_uint_floor())
+        .unwrap_or_default();
+    let to_u64 = |value: Uint256| {
+        Uint128::try_from(value)
+            .ok()
+            .and_then(|value| u64::try_from(value.u128()).ok())
+            .ok_or_else(|| StdError::generic_err("The price doesn't fit the price feed format"))
+    };
+    let price = to_u64(price)?;
+
+    Ok(PythPriceResponse {
+        price: i64::try_from(price)
+            .map_err(|_| StdError::generic_err("The price doesn't fit the price feed format"))?,
+        conf: to_u64(conf)?,
+        expo: PRICE_FEED_EXPO,
+        publish_time: config.block_time_last,
+    })
+}
+
+/// Returns the spot price of the other pool asset denominated in `ask_asset` and its inverse
+/// using a [`SpotPriceResponse`] object. The prices are computed from the raw reserves.
+///
+/// * **ask_asset** the pool asset the price is denominated in.
+pub fn query_spot_price_in_asset(deps: Deps, ask_asset: AssetInfo) -> StdResult<SpotPriceResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let pools = config
+        .pair_info
+        .query_pools(&deps.querier, &config.pair_info.contract_addr)?;
+    let (ask_pool, offer_pool) = match pools.iter().position(|pool| pool.info.equal(&ask_asset)) {
+        Some(0) => (pools[0].amount, pools[1].amount),
+        Some(_) => (pools[1].amount, pools[0].amount),
+        None => {
+            return Err(StdError::generic_err(format!(
+                "{ask_asset} is not in the pool"
+            )))
+        }
+    };
+    if ask_pool.is_zero() || offer_pool.is_zero() {
+        return Err(StdError::generic_err("The pool is empty"));
+    }
+
+    Ok(SpotPriceResponse {
+        price: Decimal::checked_from_ratio(ask_pool, offer_pool)
+            .map_err(|e| StdError::generic_err(e.to_string()))?,
+        price_inverted: Decimal::checked_from_ratio(offer_pool, ask_pool)
+            .map_err(|e| StdError::generic_err(e.to_string()))?,
+    })
+}
+
+/// Returns the average price of the swaps observed in the last `window_secs` seconds using a
+/// [`FeeAdjustedTwapResponse`] object. Every swap is priced at the amount it returned before the
+/// commission was deducted and weighted by the amount of the second asset swapped. The price and
+/// the fee rate are zero if there were no swaps within the window.
+///
+/// * **window_secs** the length of the window ending at the current block.
+pub fn query_fee_adjusted_twap(
+    deps: Deps,
+    env: Env,
+    window_secs: u64,
+) -> StdResult<FeeAdjustedTwapResponse> {
+    let window_start = env.block.time.seconds().saturating_sub(window_secs);
+    let state = FEE_ADJUSTED_TWAP
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+
+    let mut weighted_price = Decimal256::zero();
+    let mut weighted_fee_rate = Decimal256::zero();
+    let mut total_volume = Uint256::zero();
+    let mut observations = 0u32;
+    for observation in state
+        .observations
+        .iter()
+        .filter(|observation| observation.timestamp >= window_start)
+    {
+        let volume = Decimal256::from_ratio(observation.volume, 1u8);
+        weighted_price = weighted_price
+            .checked_add(Decimal256::from(observation.effective_price).checked_mul(volume)?)?;
+        weighted_fee_rate = weighted_fee_rate
+            .checked_add(Decimal256::from(observation.fee_rate).checked_mul(volume)?)?;
+        total_volume = total_volume.checked_add(observation.volume.into())?;
+        observations += 1;
+    }
+
+    let average = |weighted_sum: Decimal256| -> StdResult<Decimal> {
+        if total_volume.is_zero() {
+            return Ok(Decimal::zero());
+        }
+        (weighted_sum / Decimal256::from_ratio(total_volume, 1u8))
+            .try_into()
+            .map_err(|e: DecimalRangeExceeded| StdError::generic_err(e.to_string()))
+    };
+
+    Ok(FeeAdjustedTwapResponse {
+        price: average(weighted_price)?,
+        fee_rate_avg: average(weighted_fee_rate)?,
+        observations,
+    })
+}
+
+/// Returns the health indicators of the pair in a [`PairHealthResponse`] object.
+pub fn query_pair_health(deps: Deps, env: Env) -> StdResult<PairHealthResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let pools = config
+        .pair_info
+        .query_pools(&deps.querier, &config.pair_info.contract_addr)?;
+
+    let reserve_ratio =
+        Decimal::checked_from_ratio(pools[0].amount, pools[1].amount).unwrap_or_default();
+
+    // The oracle values the first reserve in the second asset, so the result is compared
+    // with the second reserve
+    let oracle_amount = match ORACLE.may_load(deps.storage)? {
+        Some(oracle) if !pools[0].amount.is_zero() => deps
+            .querier
+            .query_wasm_smart::<Vec<(AssetInfo, Uint256)>>(
+                oracle,
+                &OracleQueryMsg::Consult {
+                    token: pools[0].info.clone(),
+                    amount: pools[0].amount,
+                },
+            )
+            .ok()
+            .and_then(|prices| prices.first().map(|(_, amount)| *amount))
+            .filter(|amount| !amount.is_zero()),
+        _ => None,
+    };
+    let price_deviation_from_oracle = oracle_amount
+        .map(|oracle_amount| {
+            let pool_amount = Uint256::from(pools[1].amount);
+            let diff = if pool_amount > oracle_amount {
+                pool_amount - oracle_amount
+            } else {
+                oracle_amount - pool_amount
+            };
+
+            Decimal256::from_ratio(diff, oracle_amount)
+                .try_into()
+                .map_err(|e: DecimalRangeExceeded| StdError::generic_err(e.to_string()))
+        })
+        .transpose()?;
+
+    let spread_7d_avg = average_spread(deps.storage, &env)?
+        .map(|spread| {
+            spread
+                .try_into()
+                .map_err(|e: DecimalRangeExceeded| StdError::generic_err(e.to_string()))
+        })
+        .transpose()?;
+
+    let circuit_breaker_active = is_factory_paused(deps, &config)?;
+    let paused = PAUSED.may_load(deps.storage)?.unwrap_or_default();
+
+    let health_score = compute_health_score(
+        pools[0].amount.is_zero() || pools[1].amount.is_zero(),
+        price_deviation_from_oracle,
+        spread_7d_avg,
+        circuit_breaker_active,
+        paused,
+    );
+
+    Ok(PairHealthResponse {
+        reserve_ratio,
+        price_deviation_from_oracle,
+        spread_7d_avg,
+        circuit_breaker_active,
+        paused,
+        health_score,
     })
 }
 
-/// Exposes all the queries available in the contract.
-///
-/// ## Queries
-/// * **QueryMsg::Pair {}** Returns information about the pair in an object of type [`PairInfo`].
-///
-/// * **QueryMsg::Pool {}** Returns information about the amount of assets in the pair contract as
-/// well as the amount of LP tokens issued using an object of type [`PoolResponse`].
-///
-/// * **QueryMsg::Share { amount }** Returns the amount of assets that could be withdrawn from the pool
-/// using a specific amount of LP tokens. The result is returned in a vector that contains objects of type [`Asset`].
-///
-/// * **QueryMsg::Simulation { offer_asset }** Returns the result of a swap simulation using a [`SimulationResponse`] object.
-///
-/// * **QueryMsg::ReverseSimulation { ask_asset }** Returns the result of a reverse swap simulation  using
-/// a [`ReverseSimulationResponse`] object.
-///
-/// * **QueryMsg::CumulativePrices {}** Returns information about cumulative prices for the assets in the
-/// pool using a [`CumulativePricesResponse`] object.
-///
-/// * **QueryMsg::Config {}** Returns the configuration for the pair contract using a [`ConfigResponse`] object.
-///
-/// * **QueryMsg::AssetBalanceAt { asset_info, block_height }** Returns the balance of the specified asset that was in the pool
-/// just preceeding the moment of the specified block height creation.
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::Pair {} => to_json_binary(&CONFIG.load(deps.storage)?.pair_info),
-        QueryMsg::Pool {} => to_json_binary(&query_pool(deps)?),
-        QueryMsg::Share { amount } => to_json_binary(&query_share(deps, amount)?),
-        QueryMsg::Simulation { offer_asset, .. } => {
-            to_json_binary(&query_simulation(deps, offer_asset)?)
-        }
-        QueryMsg::ReverseSimulation { ask_asset, .. } => {
-            to_json_binary(&query_reverse_simulation(deps, ask_asset)?)
-        }
-        QueryMsg::CumulativePrices {} => to_json_binary(&query_cumulative_prices(deps, env)?),
-        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
-        QueryMsg::AssetBalanceAt {
-            asset_info,
-            block_height,
-        } => to_json_binary(&query_asset_balances_at(deps, asset_info, block_height)?),
-        _ => Err(StdError::generic_err("Query is not supported")),
+/// Computes a health score from 0 to 100. A paused pair scores 0, otherwise points are deducted:
+/// * 50 if the pair is paused in the factory;
+/// * 20 if one of the reserves is empty;
+/// * 4 per percent of price deviation from the oracle, up to 40;
+/// * 10 per percent of average spread, up to 30.
+fn compute_health_score(
+    empty_reserves: bool,
+    price_deviation_from_oracle: Option<Decimal>,
+    spread_7d_avg: Option<Decimal>,
+    circuit_breaker_active: bool,
+    paused: bool,
+) -> u8 {
+    if paused {
+        return 0;
+    }
+
+    let penalty = |value: Option<Decimal>, points_per_percent: u128, max: u128| {
+        value.map_or(0, |value| {
+            (Uint128::new(100 * points_per_percent) * value)
+                .u128()
+                .min(max)
+        })
+    };
+
+    let mut deductions =
+        penalty(price_deviation_from_oracle, 4, 40) + penalty(spread_7d_avg, 10, 30);
+    if circuit_breaker_active {
+        deductions += 50;
     }
+    if empty_reserves {
+        deductions += 20;
+    }
+
+    100u128.saturating_sub(deductions) as u8
 }
 
 /// Returns the amounts of assets in the pair contract as well as the amount of LP
@@ -983,6 +3553,74 @@ pub fn query_pool(deps: Deps) -> StdResult<PoolResponse> {
     Ok(resp)
 }
 
+/// Returns the pool reserves with the amount of the rebasing asset converted to base units.
+pub fn query_normalized_reserves(deps: Deps) -> StdResult<NormalizedReservesResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let (mut reserves, _) = pool_info(deps.querier, &config)?;
+
+    let mut rebasing_rate = Decimal::one();
+    for reserve in &mut reserves {
+        let rate = rebase_rate(deps.querier, &config, &reserve.info)?;
+        if rate != Decimal::one() {
+            rebasing_rate = rate;
+        }
+        reserve.amount = reserve.amount * rate;
+    }
+
+    Ok(NormalizedReservesResponse {
+        reserves,
+        rebase_rate: rebasing_rate,
+        base_denom: config
+            .rebasing_asset
+            .map(|rebasing_asset| rebasing_asset.base_denom),
+    })
+}
+
+/// Returns the latest pool state snapshot with its identifier or [`None`] if no snapshot was
+/// taken yet.
+pub fn query_latest_snapshot(deps: Deps) -> StdResult<Option<(u64, Snapshot)>> {
+    SNAPSHOTS
+        .range(deps.storage, None, None, Order::Descending)
+        .next()
+        .transpose()
+}
+
+/// Returns the pool reserves recorded in the blocks between `from_block` and `to_block`
+/// (both inclusive) in ascending block order.
+pub fn query_reserves_history(
+    deps: Deps,
+    from_block: u64,
+    to_block: u64,
+) -> StdResult<Vec<(u64, [Uint128; 2])>> {
+    if from_block > to_block {
+        return Err(StdError::generic_err(
+            "from_block must not be greater than to_block",
+        ));
+    }
+
+    RESERVES_HISTORY
+        .range(
+            deps.storage,
+            Some(Bound::inclusive(from_block)),
+            Some(Bound::inclusive(to_block)),
+            Order::Ascending,
+        )
+        .collect()
+}
+
+/// Returns the constant product of the pool reserves using an [`InvariantResponse`] object.
+pub fn query_invariant(deps: Deps) -> StdResult<InvariantResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let pools = config
+        .pair_info
+        .query_pools(&deps.querier, &config.pair_info.contract_addr)?;
+
+    Ok(InvariantResponse {
+        value: compute_invariant(pools[0].amount, pools[1].amount),
+        formula: "xy=k".to_string(),
+    })
+}
+
 /// Returns the amount of assets that could be withdrawn from the pool using a specific amount of LP tokens.
 /// The result is returned in a vector that contains objects of type [`Asset`].
 ///
@@ -995,6 +3633,266 @@ pub fn query_share(deps: Deps, amount: Uint128) -> StdResult<Vec<Asset>> {
     Ok(refund_assets)
 }
 
+/// Returns the impermanent loss of an LP position in an [`ImpermanentLossResponse`] object.
+/// Both values are calculated at the current pool price and denominated in the second pool asset.
+///
+/// * **initial_assets** are the assets that were originally deposited into the pool.
+///
+/// * **current_lp_amount** is the amount of LP tokens currently held.
+pub fn query_impermanent_loss(
+    deps: Deps,
+    initial_assets: Vec<Asset>,
+    current_lp_amount: Uint128,
+) -> StdResult<ImpermanentLossResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let (pools, total_share) = pool_info(deps.querier, &config)?;
+
+    if initial_assets.len() != 2 {
+        return Err(StdError::generic_err("Two initial assets are required"));
+    }
+
+    // Order the initial amounts the same way as the pool assets
+    let initial_amounts = pools
+        .iter()
+        .map(|pool| {
+            initial_assets
+                .iter()
+                .find(|asset| asset.info.equal(&pool.info))
+                .map(|asset| asset.amount)
+                .ok_or_else(|| {
+                    StdError::generic_err(format!("Initial assets must include {}", pool.info))
+                })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    if initial_amounts.iter().any(|amount| amount.is_zero()) {
+        return Err(StdError::generic_err(
+            "Initial asset amounts must be non-zero",
+        ));
+    }
+    if total_share.is_zero() || pools.iter().any(|pool| pool.amount.is_zero()) {
+        return Err(StdError::generic_err("The pool is empty"));
+    }
+
+    // Price of the first pool asset denominated in the second one
+    let current_price = Decimal256::from_ratio(pools[1].amount, pools[0].amount);
+    let initial_price = Decimal256::from_ratio(initial_amounts[1], initial_amounts[0]);
+
+    let holding_value =
+        Uint256::from(initial_amounts[0]) * current_price + Uint256::from(initial_amounts[1]);
+
+    let lp_assets = get_share_in_assets(&pools, current_lp_amount, total_share);
+    let lp_value =
+        Uint256::from(lp_assets[0].amount) * current_price + Uint256::from(lp_assets[1].amount);
+
+    let il_percent = Decimal256::from_ratio(holding_value.saturating_sub(lp_value), holding_value);
+    let price_ratio = current_price
+        .checked_div(initial_price)
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+
+    Ok(ImpermanentLossResponse {
+        holding_value: holding_value.try_into()?,
+        lp_value: lp_value.try_into()?,
+        il_percent: il_percent
+            .try_into()
+            .map_err(|e: DecimalRangeExceeded| StdError::generic_err(e.to_string()))?,
+        price_ratio: price_ratio
+            .try_into()
+            .map_err(|e: DecimalRangeExceeded| StdError::generic_err(e.to_string()))?,
+    })
+}
+
+/// Returns the LP position stats of `address` in a [`UserLpStatsResponse`] object.
+/// The fees are tracked from the LP balance changes reported by the LP token. The impermanent loss
+/// compares the current pool price with the price after the first provision of the address:
+///
+/// il = 1 - 2 * sqrt(p) / (1 + p), where p = current_price / entry_price
+pub fn query_user_lp_stats(deps: Deps, address: String) -> StdResult<UserLpStatsResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let config = CONFIG.load(deps.storage)?;
+    let (pools, total_share) = pool_info(deps.querier, &config)?;
+
+    let lp_balance =
+        query_token_balance(&deps.querier, &config.pair_info.liquidity_token, &address)?;
+    let underlying_assets = get_share_in_assets(&pools, lp_balance, total_share);
+    let position_value_in_asset0 = if pools[1].amount.is_zero() {
+        underlying_assets[0].amount
+    } else {
+        underlying_assets[0].amount.checked_add(
+            underlying_assets[1]
+                .amount
+                .multiply_ratio(pools[0].amount, pools[1].amount),
+        )?
+    };
+
+    let stats = USER_LP_STATS
+        .may_load(deps.storage, &address)?
+        .unwrap_or_default();
+    let fee_per_share = FEE_PER_SHARE_CUMULATIVE
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    let fees_earned = config
+        .pair_info
+        .asset_infos
+        .iter()
+        .zip(stats.fees_earned(&fee_per_share)?)
+        .map(|(asset_info, fee)| {
+            Ok(asset_info.with_balance(Uint128::try_from(fee.to_uint_floor())?))
+        })
+        .collect::<StdResult<_>>()?;
+
+    let impermanent_loss_bps = match ENTRY_PRICE.may_load(deps.storage, &address)? {
+        Some(entry_price)
+            if !entry_price.is_zero() && pools.iter().all(|pool| !pool.amount.is_zero()) =>
+        {
+            let current_price = Decimal256::from_ratio(pools[1].amount, pools[0].amount);
+            let price_ratio = current_price / Decimal256::from(entry_price);
+            let il = Decimal256::one().saturating_sub(
+                Decimal256::from_ratio(2u8, 1u8) * price_ratio.sqrt()
+                    / (Decimal256::one() + price_ratio),
+            );
+            // The loss never exceeds 100%
+            Uint128::try_from(Uint256::from(10000u16) * il)?.u128() as i64
+        }
+        _ => 0,
+    };
+
+    Ok(UserLpStatsResponse {
+        lp_balance,
+        underlying_assets,
+        position_value_in_asset0,
+        fees_earned,
+        impermanent_loss_bps,
+        first_provide_time: stats.first_provide_time,
+        last_activity_time: stats.last_activity_time,
+    })
+}
+
+/// Returns the trading volume at which the LP fees offset the impermanent loss of the price
+/// moving from `initial_price` to `current_price` in a [`BreakevenVolumeResponse`] object.
+///
+/// * **Equations**
+///
+/// il = 1 - 2 * sqrt(p) / (1 + p), where p = current_price / initial_price
+///
+/// required_volume = il * tvl / lp_fee_rate
+///
+/// The TVL is denominated in the second asset and computed from the current pool reserves.
+pub fn query_breakeven_volume(
+    deps: Deps,
+    initial_price: Decimal,
+    current_price: Decimal,
+) -> StdResult<BreakevenVolumeResponse> {
+    if initial_price.is_zero() || current_price.is_zero() {
+        return Err(StdError::generic_err("Prices must be non-zero"));
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let fee_info = query_pair_fee_info(
+        &deps.querier,
+        &config.factory_addr,
+        &config.pair_info.contract_addr,
+        config.pair_info.pair_type.clone(),
+    )?;
+    let current_fee_rate = fee_info.total_fee_rate * (Decimal::one() - fee_info.maker_fee_rate);
+    if current_fee_rate.is_zero() {
+        return Err(StdError::generic_err("The pair doesn't charge LP fees"));
+    }
+
+    let price_ratio = Decimal256::from(current_price) / Decimal256::from(initial_price);
+    let il_percentage = Decimal256::one().saturating_sub(
+        Decimal256::from_ratio(2u8, 1u8) * price_ratio.sqrt() / (Decimal256::one() + price_ratio),
+    );
+
+    // Both sides of a constant product pool have the same value
+    let (pools, _) = pool_info(deps.querier, &config)?;
+    let tvl = Uint256::from(pools[1].amount) * Uint256::from(2u8);
+    let required_volume = tvl * (il_percentage / Decimal256::from(current_fee_rate));
+
+    Ok(BreakevenVolumeResponse {
+        required_volume: required_volume.try_into()?,
+        current_fee_rate,
+        il_percentage: il_percentage
+            .try_into()
+            .map_err(|e: DecimalRangeExceeded| StdError::generic_err(e.to_string()))?,
+    })
+}
+
+/// Returns the amount of LP tokens minted for a deposit in a [`SimulateProvideResponse`] object.
+/// LP tokens are minted for the smaller of the two deposit ratios, so the rest of the deposit is
+/// donated to the pool and reported as price impact.
+///
+/// * **assets** are the assets to deposit.
+pub fn query_simulate_provide(
+    deps: Deps,
+    assets: Vec<Asset>,
+) -> StdResult<SimulateProvideResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let (pools, total_share) = pool_info(deps.querier, &config)?;
+
+    if assets.len() != 2 {
+        return Err(StdError::generic_err(
+            "asset_infos must contain exactly two elements",
+        ));
+    }
+
+    // Order the deposits the same way as the pool assets
+    let deposits = pools
+        .iter()
+        .map(|pool| {
+            assets
+                .iter()
+                .find(|asset| asset.info.equal(&pool.info))
+                .map(|asset| asset.amount)
+                .ok_or_else(|| StdError::generic_err(format!("Assets must include {}", pool.info)))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    if deposits.iter().any(|deposit| deposit.is_zero()) {
+        return Err(StdError::generic_err(
+            ContractError::InvalidZeroAmount {}.to_string(),
+        ));
+    }
+
+    if total_share.is_zero() {
+        // The initial deposit sets the pool price, so it has no price impact
+        let lp_amount = Uint128::try_from(isqrt(compute_invariant(deposits[0], deposits[1])))?
+            .checked_sub(MINIMUM_LIQUIDITY_AMOUNT)
+            .map_err(|_| {
+                StdError::generic_err(ContractError::MinimumLiquidityAmountError {}.to_string())
+            })?;
+
+        return Ok(SimulateProvideResponse {
+            lp_amount,
+            fee: None,
+            price_impact_bps: 0,
+        });
+    }
+
+    if pools.iter().any(|pool| pool.amount.is_zero()) {
+        return Err(StdError::generic_err("The pool is empty"));
+    }
+
+    let shares = [
+        deposits[0].multiply_ratio(total_share, pools[0].amount),
+        deposits[1].multiply_ratio(total_share, pools[1].amount),
+    ];
+    let lp_amount = std::cmp::min(shares[0], shares[1]);
+    let max_share = std::cmp::max(shares[0], shares[1]);
+
+    // Both deposits are valued at the pool price, so the donated part of the deposit is
+    // (max_share - min_share) / (max_share + min_share)
+    let price_impact_bps = (max_share - lp_amount)
+        .multiply_ratio(10000u16, max_share.checked_add(lp_amount)?)
+        .u128() as u64;
+
+    Ok(SimulateProvideResponse {
+        lp_amount,
+        fee: None,
+        price_impact_bps,
+    })
+}
+
 /// Returns information about a swap simulation in a [`SimulationResponse`] object.
 ///
 /// * **offer_asset** is the asset to swap as well as an amount of the said asset.
@@ -1020,9 +3918,10 @@ pub fn query_simulation(deps: Deps, offer_asset: Asset) -> StdResult<SimulationR
     }
 
     // Get fee info from the factory contract
-    let fee_info = query_fee_info(
+    let fee_info = query_pair_fee_info(
         &deps.querier,
         config.factory_addr,
+        &config.pair_info.contract_addr,
         config.pair_info.pair_type,
     )?;
 
@@ -1069,9 +3968,10 @@ pub fn query_reverse_simulation(
     }
 
     // Get fee info from factory
-    let fee_info = query_fee_info(
+    let fee_info = query_pair_fee_info(
         &deps.querier,
         config.factory_addr,
+        &config.pair_info.contract_addr,
         config.pair_info.pair_type,
     )?;
 
@@ -1126,6 +4026,73 @@ pub fn query_cumulative_prices(deps: Deps, env: Env) -> StdResult<CumulativePric
     Ok(resp)
 }
 
+/// Returns the average prices between the specified snapshot and the current block in a
+/// [`TwapSinceResponse`] object. The accumulators are allowed to overflow, so the difference is
+/// computed with wrapping arithmetic.
+///
+/// * **snapshot_id** identifier of a snapshot stored with [`ExecuteMsg::SaveTwapSnapshot`].
+pub fn query_twap_since(deps: Deps, env: Env, snapshot_id: u64) -> StdResult<TwapSinceResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let snapshot = TWAP_SNAPSHOTS
+        .may_load(deps.storage, snapshot_id)?
+        .ok_or_else(|| StdError::generic_err(format!("TWAP snapshot {snapshot_id} not found")))?;
+    let current = current_twap_snapshot(deps, env, &config)?;
+
+    if current.timestamp <= snapshot.timestamp {
+        return Err(StdError::generic_err(
+            "TWAP can not be computed in the same block the snapshot was saved",
+        ));
+    }
+
+    let time_elapsed = Uint128::from(current.timestamp - snapshot.timestamp)
+        .checked_mul(Uint128::from(10u128.pow(TWAP_PRECISION.into())))?;
+    let average_price = |current: Uint128, snapshot: Uint128| {
+        Decimal::checked_from_ratio(current.wrapping_sub(snapshot), time_elapsed)
+            .map_err(|err| StdError::generic_err(err.to_string()))
+    };
+
+    let asset_infos = &config.pair_info.asset_infos;
+    let average_prices = vec![
+        (
+            asset_infos[0].clone(),
+            asset_infos[1].clone(),
+            average_price(current.price0_cumulative, snapshot.price0_cumulative)?,
+        ),
+        (
+            asset_infos[1].clone(),
+            asset_infos[0].clone(),
+            average_price(current.price1_cumulative, snapshot.price1_cumulative)?,
+        ),
+    ];
+
+    Ok(TwapSinceResponse {
+        snapshot,
+        average_prices,
+    })
+}
+
+/// Returns the price accumulators as of the current block.
+fn current_twap_snapshot(deps: Deps, env: Env, config: &Config) -> StdResult<TwapSnapshot> {
+    let pools = config
+        .pair_info
+        .query_pools(&deps.querier, &config.pair_info.contract_addr)?;
+    let timestamp = env.block.time.seconds();
+
+    let (price0_cumulative, price1_cumulative) =
+        match accumulate_prices(env, config, pools[0].amount, pools[1].amount)? {
+            Some((price0_cumulative, price1_cumulative, _)) => {
+                (price0_cumulative, price1_cumulative)
+            }
+            None => (config.price0_cumulative_last, config.price1_cumulative_last),
+        };
+
+    Ok(TwapSnapshot {
+        price0_cumulative,
+        price1_cumulative,
+        timestamp,
+    })
+}
+
 /// Returns the pair contract configuration in a [`ConfigResponse`] object.
 pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let config: Config = CONFIG.load(deps.storage)?;
@@ -1137,6 +4104,9 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         params: Some(to_json_binary(&XYKPoolConfig {
             track_asset_balances: config.track_asset_balances,
             fee_share: config.fee_share,
+            max_spread: config.max_spread,
+            lp_cooldown_secs: config.lp_cooldown_secs,
+            track_swap_history: config.track_swap_history,
         })?),
         owner: factory_config.owner,
         factory_addr: config.factory_addr,
@@ -1239,6 +4209,35 @@ pub fn compute_offer_amount(
     Ok((offer_amount, spread_amount, commission_amount.try_into()?))
 }
 
+/// Returns the base units one unit of the asset is worth. Only the rebasing asset of the pair
+/// is worth other than one base unit.
+fn rebase_rate(
+    querier: QuerierWrapper,
+    config: &Config,
+    asset_info: &AssetInfo,
+) -> StdResult<Decimal> {
+    match (&config.rebasing_asset, asset_info) {
+        (Some(rebasing_asset), AssetInfo::Token { contract_addr })
+            if *contract_addr == rebasing_asset.token =>
+        {
+            let rate: Decimal = querier.query_wasm_smart(
+                &rebasing_asset.rate_oracle,
+                &RateOracleQueryMsg::ExchangeRate {
+                    base_denom: rebasing_asset.base_denom.clone(),
+                },
+            )?;
+            if rate.is_zero() {
+                return Err(StdError::generic_err(format!(
+                    "The rate oracle returned a zero exchange rate for {contract_addr}"
+                )));
+            }
+
+            Ok(rate)
+        }
+        _ => Ok(Decimal::one()),
+    }
+}
+
 /// If `belief_price` and `max_spread` are both specified, we compute a new spread,
 /// otherwise we just use the swap spread to check `max_spread`.
 ///
@@ -1285,6 +4284,17 @@ pub fn assert_max_spread(
     Ok(())
 }
 
+/// Validates the max spread stored in the pair's config.
+/// It must not exceed [`MAX_ALLOWED_SPREAD`].
+fn validate_max_spread(max_spread: Option<Decimal>) -> Result<(), ContractError> {
+    match max_spread {
+        Some(max_spread) if max_spread > MAX_ALLOWED_SPREAD => {
+            Err(ContractError::InvalidSpreadConfig {})
+        }
+        _ => Ok(()),
+    }
+}
+
 /// This is an internal function that enforces slippage tolerance for swaps.
 ///
 /// * **slippage_tolerance** slippage tolerance to enforce.
@@ -1305,19 +4315,8 @@ pub fn assert_slippage_tolerance(
         return Err(ContractError::AllowedSpreadAssertion {});
     }
 
-    let slippage_tolerance: Decimal256 = Decimal256::from(slippage_tolerance);
-    let one_minus_slippage_tolerance = Decimal256::one() - slippage_tolerance;
-    let deposits: [Uint256; 2] = [deposits[0].into(), deposits[1].into()];
-    let pools: [Uint256; 2] = [pools[0].amount.into(), pools[1].amount.into()];
-
-    // Ensure each price does not change more than what the slippage tolerance allows
-    if Decimal256::from_ratio(deposits[0], deposits[1]) * one_minus_slippage_tolerance
-        > Decimal256::from_ratio(pools[0], pools[1])
-        || Decimal256::from_ratio(deposits[1], deposits[0]) * one_minus_slippage_tolerance
-            > Decimal256::from_ratio(pools[1], pools[0])
-    {
-        return Err(ContractError::MaxSlippageAssertion {});
-    }
+    let reserves = [pools[0].amount, pools[1].amount];
+    astroport::math::assert_slippage_tolerance(pools, deposits, &reserves, slippage_tolerance)?;
 
     Ok(())
 }
@@ -1339,6 +4338,15 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, C
         _ => return Err(ContractError::MigrationError {}),
     }
 
+    let config = CONFIG.load(deps.storage)?;
+    if !ACCEPTED_DENOMS.exists(deps.storage) {
+        ACCEPTED_DENOMS.save(
+            deps.storage,
+            &accepted_denoms(&config.pair_info.asset_infos),
+        )?;
+    }
+    CONFIG_HASH.save(deps.storage, &config.hash()?)?;
+
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     Ok(Response::default().add_attributes([
@@ -1352,6 +4360,111 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, C
     ]))
 }
 
+/// Returns the denoms of the native pool assets.
+fn accepted_denoms(asset_infos: &[AssetInfo]) -> Vec<String> {
+    asset_infos
+        .iter()
+        .filter_map(|asset_info| match asset_info {
+            AssetInfo::NativeToken { denom } => Some(denom.clone()),
+            AssetInfo::Token { .. } => None,
+        })
+        .collect()
+}
+
+/// Returns an error if any of the sent funds is not a native pool asset.
+/// Such funds would otherwise be ignored and stay locked in the pair.
+fn assert_accepted_funds(deps: Deps, funds: &[Coin]) -> Result<(), ContractError> {
+    let accepted_denoms = ACCEPTED_DENOMS.load(deps.storage)?;
+    if let Some(coin) = funds
+        .iter()
+        .find(|coin| !accepted_denoms.contains(&coin.denom))
+    {
+        return Err(ContractError::UnexpectedFunds {
+            denom: coin.denom.clone(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Returns an error if the pair was paused in the factory.
+/// Withdrawals are always allowed so that LPs can exit a paused pair.
+fn assert_not_paused(deps: Deps, config: &Config) -> Result<(), ContractError> {
+    // The pair can not be unpaused after an emergency withdrawal
+    if PAUSED.may_load(deps.storage)?.unwrap_or_default() {
+        return Err(ContractError::PairPaused {});
+    }
+
+    if is_factory_paused(deps, config)? {
+        return Err(ContractError::PairPaused {});
+    }
+
+    Ok(())
+}
+
+/// Returns whether the pair is paused in the factory. The flag pushed by the factory is used
+/// if set, otherwise the factory is queried.
+fn is_factory_paused(deps: Deps, config: &Config) -> StdResult<bool> {
+    match FACTORY_PAUSED.may_load(deps.storage)? {
+        Some(paused) => Ok(paused),
+        None => query_pair_paused(
+            &deps.querier,
+            &config.factory_addr,
+            &config.pair_info.contract_addr,
+        ),
+    }
+}
+
+/// Returns an error if the pair is permissioned and `sender` isn't whitelisted.
+fn assert_sender_whitelisted(config: &Config, sender: &Addr) -> Result<(), ContractError> {
+    match &config.sender_whitelist {
+        Some(whitelist) if !whitelist.contains(sender) => {
+            Err(ContractError::SenderNotWhitelisted {
+                sender: sender.clone(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Returns an error if `sender` is in [`BLOCKED_SENDERS`].
+fn assert_sender_not_blocked(storage: &dyn Storage, sender: &Addr) -> Result<(), ContractError> {
+    match BLOCKED_SENDERS.may_load(storage, sender)? {
+        Some(BlockReason { reason, .. }) => Err(ContractError::SenderBlocked { reason }),
+        None => Ok(()),
+    }
+}
+
+/// Returns an error if [`Config::compliance_mode`] is enabled, any sender is blocked and `msg`
+/// doesn't check the sender against [`BLOCKED_SENDERS`]. The block list and the configuration
+/// can always be updated, and the LP token can always update the top LP holders.
+fn assert_compliance_mode_allows(deps: Deps, msg: &ExecuteMsg) -> Result<(), ContractError> {
+    match msg {
+        ExecuteMsg::Receive(_)
+        | ExecuteMsg::ProvideLiquidity { .. }
+        | ExecuteMsg::Swap { .. }
+        | ExecuteMsg::RemoveLiquidityImbalanced { .. }
+        // The LP token notifies the pair after every balance change
+        | ExecuteMsg::UpdateLpHolders { .. }
+        | ExecuteMsg::UpdateConfig { .. }
+        | ExecuteMsg::BlockSender { .. }
+        | ExecuteMsg::UnblockSender { .. } => return Ok(()),
+        _ => {}
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    if config.compliance_mode
+        && BLOCKED_SENDERS
+            .keys(deps.storage, None, None, Order::Ascending)
+            .next()
+            .is_some()
+    {
+        return Err(ContractError::ComplianceModeActive {});
+    }
+
+    Ok(())
+}
+
 /// Returns the total amount of assets in the pool as well as the total amount of LP tokens currently minted.
 pub fn pool_info(querier: QuerierWrapper, config: &Config) -> StdResult<(Vec<Asset>, Uint128)> {
     let pools = config
@@ -1,5 +1,9 @@
-use astroport::{asset::MINIMUM_LIQUIDITY_AMOUNT, pair::MAX_FEE_SHARE_BPS};
-use cosmwasm_std::{OverflowError, StdError};
+use astroport::{
+    asset::{AmountTooLargeError, AssetInfo, ZeroAmountError, MINIMUM_LIQUIDITY_AMOUNT},
+    math::SlippageToleranceError,
+    pair::{MAX_ALLOWED_SPREAD, MAX_FEE_SHARE_BPS},
+};
+use cosmwasm_std::{Addr, Decimal, Decimal256, OverflowError, StdError, Uint128, Uint256};
 use thiserror::Error;
 
 /// This enum describes pair contract errors
@@ -20,14 +24,23 @@ pub enum ContractError {
     #[error("Event of zero transfer")]
     InvalidZeroAmount {},
 
+    #[error("Amount {amount} exceeds the maximum allowed {max}")]
+    AmountTooLarge { amount: Uint128, max: Uint128 },
+
     #[error("Operation exceeds max spread limit")]
     MaxSpreadAssertion {},
 
     #[error("Provided spread amount exceeds allowed limit")]
     AllowedSpreadAssertion {},
 
-    #[error("Operation exceeds max splippage tolerance")]
-    MaxSlippageAssertion {},
+    #[error(
+        "Deposit of {asset} deviates from the pool ratio by {deviation}, the slippage tolerance is {allowed}"
+    )]
+    SlippageTolerance {
+        asset: String,
+        deviation: Decimal256,
+        allowed: Decimal,
+    },
 
     #[error("Doubling assets in asset infos")]
     DoublingAssets {},
@@ -58,6 +71,120 @@ pub enum ContractError {
         MAX_FEE_SHARE_BPS
     )]
     FeeShareOutOfBounds {},
+
+    #[error("Pair is paused")]
+    PairPaused {},
+
+    #[error("Max spread must not exceed {}", MAX_ALLOWED_SPREAD)]
+    InvalidSpreadConfig {},
+
+    #[error("Liquidity can not be withdrawn until {unlock_at}")]
+    LpCooldownActive { unlock_at: u64 },
+
+    #[error("Emergency withdraw proposal not found")]
+    EmergencyWithdrawProposalNotFound {},
+
+    #[error("Emergency withdraw was proposed for {0}")]
+    EmergencyWithdrawRecipientMismatch(String),
+
+    #[error("Emergency withdraw can not be executed until {0}")]
+    EmergencyWithdrawTimelocked(u64),
+
+    #[error("Swap decreased the pool invariant from {pre_swap} to {post_swap}")]
+    InvariantViolated {
+        pre_swap: Uint256,
+        post_swap: Uint256,
+    },
+
+    #[error("Native token {denom} is not accepted by the pair")]
+    UnexpectedFunds { denom: String },
+
+    #[error("Price alert base price must not be zero")]
+    ZeroBasePrice {},
+
+    #[error("Fee rebate must not exceed the LP share of the commission: {max_bps} bps")]
+    FeeRebateTooHigh { max_bps: u16 },
+
+    #[error("Fee rebate expiry {expires_at} is in the past")]
+    FeeRebateExpired { expires_at: u64 },
+
+    #[error("{sender} is not whitelisted to use the pair")]
+    SenderNotWhitelisted { sender: Addr },
+
+    #[error("Liquidity provision raises the {asset} reserve to {current}, above the cap {cap}")]
+    TvlCapExceeded {
+        asset: AssetInfo,
+        cap: Uint128,
+        current: Uint128,
+    },
+
+    #[error("Swap worth {offer_usd} USD exceeds the single swap limit of {limit_usd} USD")]
+    SwapSizeExceeded {
+        offer_usd: Uint128,
+        limit_usd: Uint128,
+    },
+
+    #[error("Swaps worth {volume_usd} USD today exceed the daily limit of {limit_usd} USD")]
+    DailySwapLimitExceeded {
+        volume_usd: Uint128,
+        limit_usd: Uint128,
+    },
+
+    #[error("The USD oracle must be set to limit swaps")]
+    UsdOracleNotSet {},
+
+    #[error("Sender is blocked: {reason}")]
+    SenderBlocked { reason: String },
+
+    #[error("Operation is disabled in compliance mode while senders are blocked")]
+    ComplianceModeActive {},
+
+    #[error("Token {0} isn't an asset of the pair")]
+    InvalidRebasingAsset(String),
+
+    #[error("Target ratio must not exceed 1")]
+    InvalidTargetRatio {},
+
+    #[error("Withdrawal returns {received} {asset}, less than the minimum {minimum}")]
+    WithdrawMinimumNotMet {
+        asset: AssetInfo,
+        received: Uint128,
+        minimum: Uint128,
+    },
+
+    #[error("Provided {provided} is less than the minimum liquidity threshold {required}")]
+    MinLiquidityThresholdNotMet {
+        required: Uint128,
+        provided: Uint128,
+    },
+
+    #[error("The factory can only forward max spread and LP cooldown updates")]
+    ParamsNotForwardable {},
+}
+
+impl From<ZeroAmountError> for ContractError {
+    fn from(_: ZeroAmountError) -> Self {
+        ContractError::InvalidZeroAmount {}
+    }
+}
+
+impl From<AmountTooLargeError> for ContractError {
+    fn from(err: AmountTooLargeError) -> Self {
+        ContractError::AmountTooLarge {
+            amount: err.asset.amount,
+            max: err.max,
+        }
+    }
+}
+
+impl From<SlippageToleranceError> for ContractError {
+    fn from(err: SlippageToleranceError) -> Self {
+        ContractError::SlippageTolerance {
+            asset: err.asset.to_string(),
+            deviation: err.deviation,
+            allowed: err.allowed,
+        }
+    }
 }
 
 impl From<OverflowError> for ContractError {
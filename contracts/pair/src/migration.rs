@@ -39,6 +39,21 @@ pub(crate) fn add_asset_balances_tracking_flag(
         price1_cumulative_last: old_config.price1_cumulative_last,
         track_asset_balances: false,
         fee_share: None,
+        max_spread: None,
+        lp_cooldown_secs: None,
+        custom_hook: None,
+        min_provide_amount: Default::default(),
+        swap_fee_rebate_bps: 0,
+        fee_rebate_expires_at: None,
+        max_tvl_asset0: None,
+        max_tvl_asset1: None,
+        sender_whitelist: None,
+        max_single_swap_usd: None,
+        max_daily_swap_usd: None,
+        usd_oracle: None,
+        compliance_mode: false,
+        rebasing_asset: None,
+        track_swap_history: true,
     };
 
     CONFIG.save(storage, &new_config)?;
@@ -1,12 +1,14 @@
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_json, to_json_binary, Addr, Coin, Empty, OwnedDeps, Querier, QuerierResult, QueryRequest,
-    SystemError, SystemResult, Uint128, WasmQuery,
+    from_json, to_json_binary, Addr, Coin, Decimal, Empty, OwnedDeps, Querier, QuerierResult,
+    QueryRequest, SystemError, SystemResult, Uint128, Uint256, WasmQuery,
 };
 use std::collections::HashMap;
 
-use astroport::factory::FeeInfoResponse;
-use astroport::factory::QueryMsg::FeeInfo;
+use astroport::factory::QueryMsg::{FeeInfo, HasRole, IsPairPaused, PairAdmin, PairFeeInfo};
+use astroport::factory::{Config as FactoryConfig, FeeInfoResponse, Role};
+use astroport::oracle::QueryMsg as OracleQueryMsg;
+use astroport::pair::RateOracleQueryMsg;
 use cw20::{BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
 
 /// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies.
@@ -81,7 +83,7 @@ impl WasmMockQuerier {
             QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
                 if contract_addr == "factory" {
                     match from_json(&msg).unwrap() {
-                        FeeInfo { .. } => SystemResult::Ok(
+                        FeeInfo { .. } | PairFeeInfo { .. } => SystemResult::Ok(
                             to_json_binary(&FeeInfoResponse {
                                 fee_address: Some(Addr::unchecked("fee_address")),
                                 total_fee_bps: 30,
@@ -89,8 +91,36 @@ impl WasmMockQuerier {
                             })
                             .into(),
                         ),
+                        IsPairPaused { .. } => SystemResult::Ok(to_json_binary(&false).into()),
+                        PairAdmin { .. } => SystemResult::Ok(
+                            to_json_binary(&Some(Addr::unchecked("pair_admin"))).into(),
+                        ),
+                        HasRole { address, role } => SystemResult::Ok(
+                            to_json_binary(
+                                &(address == "emergency_admin" && role == Role::EmergencyAdmin),
+                            )
+                            .into(),
+                        ),
                         _ => panic!("DO NOT ENTER HERE"),
                     }
+                } else if contract_addr == "oracle" {
+                    match from_json(&msg).unwrap() {
+                        // The oracle values one unit of the offered token at 0.625
+                        OracleQueryMsg::Consult { token, amount } => SystemResult::Ok(
+                            to_json_binary(&vec![(
+                                token,
+                                Uint256::from(amount.multiply_ratio(5u8, 8u8)),
+                            )])
+                            .into(),
+                        ),
+                    }
+                } else if contract_addr == "rate_oracle" {
+                    match from_json(&msg).unwrap() {
+                        // One unit of the rebasing token is worth 1.25 base units
+                        RateOracleQueryMsg::ExchangeRate { .. } => {
+                            SystemResult::Ok(to_json_binary(&Decimal::percent(125)).into())
+                        }
+                    }
                 } else {
                     match from_json(&msg).unwrap() {
                         Cw20QueryMsg::TokenInfo {} => {
@@ -142,8 +172,21 @@ impl WasmMockQuerier {
                     }
                 }
             }
-            QueryRequest::Wasm(WasmQuery::Raw { contract_addr, .. }) => {
-                if contract_addr == "factory" {
+            QueryRequest::Wasm(WasmQuery::Raw { contract_addr, key }) => {
+                if contract_addr == "factory" && key.as_slice() == b"config" {
+                    SystemResult::Ok(
+                        to_json_binary(&FactoryConfig {
+                            owner: Addr::unchecked("owner"),
+                            token_code_id: 0,
+                            generator_address: None,
+                            fee_address: None,
+                            whitelist_code_id: 0,
+                            coin_registry_address: Addr::unchecked("coin_registry"),
+                            multisig_module: None,
+                        })
+                        .into(),
+                    )
+                } else if contract_addr == "factory" {
                     SystemResult::Ok(to_json_binary(&Vec::<Addr>::new()).into())
                 } else {
                     panic!("DO NOT ENTER HERE");
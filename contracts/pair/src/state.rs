@@ -1,10 +1,19 @@
+use std::collections::{BinaryHeap, VecDeque};
+
 use astroport::{
     asset::{AssetInfo, PairInfo},
-    pair::FeeShareConfig,
+    pair::{
+        config_hash, CustomHookConfig, FeeShareConfig, PriceAlertConfig, PriceSnapshot,
+        RebasingAssetConfig, Snapshot, TwapSnapshot,
+    },
 };
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Uint128};
-use cw_storage_plus::{Item, SnapshotMap};
+use cosmwasm_std::{Addr, Decimal, Decimal256, StdResult, Storage, Uint128, Uint256};
+use cw_storage_plus::{Item, Map, SnapshotMap};
+
+fn default_track_swap_history() -> bool {
+    true
+}
 
 /// This structure stores the main config parameters for a constant product pair contract.
 #[cw_serde]
@@ -23,11 +32,260 @@ pub struct Config {
     pub track_asset_balances: bool,
     // The config for swap fee sharing
     pub fee_share: Option<FeeShareConfig>,
+    /// The max spread applied to swaps which don't specify one
+    pub max_spread: Option<Decimal>,
+    /// The minimum number of seconds between receiving LP tokens and withdrawing them
+    pub lp_cooldown_secs: Option<u64>,
+    /// The contract notified after pair actions
+    pub custom_hook: Option<CustomHookConfig>,
+    /// The minimum amount of each pool asset which must be provided in a single liquidity provision
+    #[serde(default)]
+    pub min_provide_amount: [Uint128; 2],
+    /// The share of the swap commission returned to the swap receiver, in bps
+    #[serde(default)]
+    pub swap_fee_rebate_bps: u16,
+    /// The timestamp after which the fee rebate is no longer returned
+    #[serde(default)]
+    pub fee_rebate_expires_at: Option<u64>,
+    /// The max reserve of the first pool asset after a liquidity provision
+    #[serde(default)]
+    pub max_tvl_asset0: Option<Uint128>,
+    /// The max reserve of the second pool asset after a liquidity provision
+    #[serde(default)]
+    pub max_tvl_asset1: Option<Uint128>,
+    /// The only addresses allowed to swap, provide and withdraw liquidity. `None` means open access
+    #[serde(default)]
+    pub sender_whitelist: Option<Vec<Addr>>,
+    /// The max USD value of a single swap
+    #[serde(default)]
+    pub max_single_swap_usd: Option<Uint128>,
+    /// The max USD value of all the swaps of a day
+    #[serde(default)]
+    pub max_daily_swap_usd: Option<Uint128>,
+    /// The oracle valuing the offer assets in USD for the swap limits
+    #[serde(default)]
+    pub usd_oracle: Option<Addr>,
+    /// Whether the operations which don't check the sender against [`BLOCKED_SENDERS`] are
+    /// disabled while any sender is blocked
+    #[serde(default)]
+    pub compliance_mode: bool,
+    /// The interest-bearing asset whose reserve is reported in base units by the
+    /// `NormalizedReserves` query
+    #[serde(default)]
+    pub rebasing_asset: Option<RebasingAssetConfig>,
+    /// Whether swaps and liquidity changes record the price, reserves, spread and fee histories
+    #[serde(default = "default_track_swap_history")]
+    pub track_swap_history: bool,
+}
+
+impl Config {
+    /// Returns the hash of the config fields which aren't updated by swaps and liquidity changes.
+    pub fn hash(&self) -> StdResult<[u8; 32]> {
+        config_hash(&Config {
+            block_time_last: 0,
+            price0_cumulative_last: Uint128::zero(),
+            price1_cumulative_last: Uint128::zero(),
+            ..self.clone()
+        })
+    }
+
+    /// Returns the reserve caps of the pool assets in the pool asset order.
+    pub fn tvl_caps(&self) -> [Option<Uint128>; 2] {
+        [self.max_tvl_asset0, self.max_tvl_asset1]
+    }
+
+    /// Returns the fee rebate in bps at the `now` timestamp. Expired rebates are zero.
+    pub fn fee_rebate_bps(&self, now: u64) -> u16 {
+        match self.fee_rebate_expires_at {
+            Some(expires_at) if now > expires_at => 0,
+            _ => self.swap_fee_rebate_bps,
+        }
+    }
 }
 
 /// Stores the config struct at the given key
 pub const CONFIG: Item<Config> = Item::new("config");
 
+/// Stores the hash of the config written at instantiation, config updates and migrations
+pub const CONFIG_HASH: Item<[u8; 32]> = Item::new("config_hash");
+
+/// Saves the config along with its hash. Used for every config update except for the price
+/// accumulator updates.
+pub fn save_config_with_hash(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
+    CONFIG.save(storage, config)?;
+    CONFIG_HASH.save(storage, &config.hash()?)
+}
+
+/// Stores the native denoms which can be sent to the pair, i.e. the denoms of the native pool assets
+pub const ACCEPTED_DENOMS: Item<Vec<String>> = Item::new("accepted_denoms");
+
+/// Describes the LP tokens of an address which are still in the LP cooldown
+#[cw_serde]
+#[derive(Default)]
+pub struct LpCooldown {
+    /// The amount of LP tokens in the cooldown
+    pub amount: Uint128,
+    /// The time the LP tokens were received. It is weighted by the amounts if the LP tokens
+    /// were received at different times
+    pub received_at: u64,
+}
+
+impl LpCooldown {
+    /// Returns the timestamp at which the LP tokens leave the cooldown.
+    pub fn unlock_at(&self, cooldown_secs: u64) -> u64 {
+        self.received_at.saturating_add(cooldown_secs)
+    }
+}
+
+/// Stores the LP tokens of each address which are still in the LP cooldown
+pub const LP_COOLDOWNS: Map<&Addr, LpCooldown> = Map::new("lp_cooldowns");
+
+/// Describes the LP position history of an address
+#[cw_serde]
+#[derive(Default)]
+pub struct UserStats {
+    /// The timestamp of the first liquidity provision. Zero if the address never provided liquidity
+    pub first_provide_time: u64,
+    /// The timestamp of the last LP balance change
+    pub last_activity_time: u64,
+    /// The LP balance after the last balance change
+    pub lp_balance: Uint128,
+    /// The LP fees per LP token at the last balance change
+    pub fee_per_share_checkpoint: [Decimal256; 2],
+    /// The LP fees earned before the last balance change
+    pub fees_accrued: [Decimal256; 2],
+}
+
+impl UserStats {
+    /// Returns the accrued LP fees plus the fees earned by the LP balance since the last
+    /// balance change: `lp_balance * (fee_per_share - fee_per_share_checkpoint)`.
+    pub fn fees_earned(&self, fee_per_share: &[Decimal256; 2]) -> StdResult<[Decimal256; 2]> {
+        let balance = Decimal256::from_ratio(self.lp_balance, 1u8);
+        let mut fees = self.fees_accrued;
+        for (i, fee) in fees.iter_mut().enumerate() {
+            let earned = balance
+                .checked_mul(fee_per_share[i].checked_sub(self.fee_per_share_checkpoint[i])?)?;
+            *fee = fee.checked_add(earned)?;
+        }
+
+        Ok(fees)
+    }
+}
+
+/// Stores the LP position history of every address which held LP tokens
+pub const USER_LP_STATS: Map<&Addr, UserStats> = Map::new("user_lp_stats");
+
+/// Stores the price of the first pool asset denominated in the second one after the first liquidity
+/// provision of every address
+pub const ENTRY_PRICE: Map<&Addr, Decimal> = Map::new("entry_price");
+
+/// Stores the recipient and the unlock time of the pending emergency withdrawal
+pub const EMERGENCY_WITHDRAW_PROPOSAL: Item<(Addr, u64)> = Item::new("emergency_withdraw_proposal");
+
+/// Set once the pair is drained by an emergency withdrawal. There is no way to unpause the pair
+pub const PAUSED: Item<bool> = Item::new("paused");
+
+/// Stores the pause flag pushed by the factory. The factory is queried if it was never pushed
+pub const FACTORY_PAUSED: Item<bool> = Item::new("factory_paused");
+
+/// Stores the price accumulator snapshots used by the TWAP since query
+pub const TWAP_SNAPSHOTS: Map<u64, TwapSnapshot> = Map::new("twap_snapshots");
+
+/// Stores the identifier assigned to the next TWAP snapshot
+pub const NEXT_TWAP_SNAPSHOT_ID: Item<u64> = Item::new("next_twap_snapshot_id");
+
+/// Stores the latest [`astroport::pair::MAX_POOL_SNAPSHOTS`] pool state snapshots
+pub const SNAPSHOTS: Map<u64, Snapshot> = Map::new("snapshots");
+
+/// Stores the identifier assigned to the next pool state snapshot
+pub const NEXT_SNAPSHOT_ID: Item<u64> = Item::new("next_snapshot_id");
+
+/// Stores the pool reserves after the last swap or liquidity change in a block. Entries older than
+/// [`astroport::pair::RESERVES_HISTORY_DEPTH`] blocks are removed. Key: block height
+pub const RESERVES_HISTORY: Map<u64, [Uint128; 2]> = Map::new("reserves_history");
+
+/// Stores the block height of the oldest entry in [`RESERVES_HISTORY`]
+pub const OLDEST_HISTORY_BLOCK: Item<u64> = Item::new("oldest_history_block");
+
+/// Stores the price alert settings. Swaps don't emit alerts if it isn't set
+pub const PRICE_ALERT_CONFIG: Item<PriceAlertConfig> = Item::new("price_alert_config");
+
+/// Stores the LP fees accrued per LP token for each pool asset since the pair creation
+pub const FEE_PER_SHARE_CUMULATIVE: Item<[Decimal256; 2]> = Item::new("fee_per_share_cumulative");
+
+/// Stores the largest LP holders as (LP balance, address), capped at [`astroport::pair::MAX_TOP_LP_HOLDERS`]
+pub const TOP_LP_HOLDER_HEAP: Item<BinaryHeap<(Uint128, Addr)>> = Item::new("top_lp_holder_heap");
+
+/// Stores the TWAP oracle used to compute the price deviation of the pool
+pub const ORACLE: Item<Addr> = Item::new("oracle");
+
+/// Stores the day since epoch and the USD value of the swaps made on that day. Only tracked
+/// while [`Config::max_daily_swap_usd`] is set
+pub const DAILY_SWAP_VOLUME_USD: Item<(u64, Uint128)> = Item::new("daily_swap_volume_usd");
+
+/// Stores the sum of swap spreads and the number of swaps for each day. Key: day since epoch
+pub const DAILY_SPREADS: Map<u64, (Decimal256, u64)> = Map::new("daily_spreads");
+
+/// Stores the latest [`astroport::pair::PRICE_RING_SIZE`] price snapshots as a ring buffer.
+/// Key: position in the ring buffer
+pub const PRICE_RING: Map<u32, PriceSnapshot> = Map::new("price_ring");
+
+/// Stores the position of the newest price snapshot in [`PRICE_RING`]
+pub const RING_HEAD: Item<u32> = Item::new("ring_head");
+
+/// Stores the amount of the second asset swapped since the newest price snapshot
+pub const SNAPSHOT_VOLUME: Item<Uint128> = Item::new("snapshot_volume");
+
+/// Stores the LP fees of the recent swaps as (timestamp, fee0, fee1) in a queue. Entries older
+/// than [`astroport::pair::FEE_WINDOW_SECS`] are removed on every swap. Key: position in the queue
+pub const FEE_RING_24H: Map<u64, (u64, Uint128, Uint128)> = Map::new("fee_ring_24h");
+
+/// Stores the positions of the oldest entry and of the next entry in [`FEE_RING_24H`]
+pub const FEE_RING_24H_BOUNDS: Item<(u64, u64)> = Item::new("fee_ring_24h_bounds");
+
+/// Stores the sum of the fees in [`FEE_RING_24H`] for each pool asset
+pub const FEE_RING_24H_TOTALS: Item<[Uint128; 2]> = Item::new("fee_ring_24h_totals");
+
+/// This structure stores why and when a sender was blocked.
+#[cw_serde]
+pub struct BlockReason {
+    /// The reason passed to [`astroport::pair_xyk::ExecuteMsg::BlockSender`]
+    pub reason: String,
+    /// The block time at which the sender was blocked
+    pub blocked_at: u64,
+}
+
+/// Stores the senders which can't swap, provide or withdraw liquidity
+pub const BLOCKED_SENDERS: Map<&Addr, BlockReason> = Map::new("blocked_senders");
+
+/// This structure stores a swap observed by [`FEE_ADJUSTED_TWAP`].
+#[cw_serde]
+pub struct FeeAdjustedObservation {
+    /// The block time of the swap
+    pub timestamp: u64,
+    /// The price of the first asset denominated in the second one at which the swap was executed
+    /// before the commission was deducted
+    pub effective_price: Decimal,
+    /// The commission relative to the amount returned before the commission
+    pub fee_rate: Decimal,
+    /// The amount of the second asset swapped before the commission. Used as the observation weight
+    pub volume: Uint128,
+}
+
+/// This structure stores the recent swaps aggregated by the fee adjusted TWAP.
+#[cw_serde]
+#[derive(Default)]
+pub struct FeeAdjustedTwapState {
+    /// The latest [`astroport::pair::MAX_FEE_ADJUSTED_OBSERVATIONS`] swaps, oldest first
+    pub observations: VecDeque<FeeAdjustedObservation>,
+}
+
+/// Stores the recent swaps used by the fee adjusted TWAP query
+pub const FEE_ADJUSTED_TWAP: Item<FeeAdjustedTwapState> = Item::new("fee_adjusted_twap");
+
+/// Stores the pool invariant before a swap until it is verified in the swap reply
+pub const PRE_SWAP_INVARIANT: Item<Uint256> = Item::new("pre_swap_invariant");
+
 /// Stores asset balances to query them later at any block height
 pub const BALANCES: SnapshotMap<&AssetInfo, Uint128> = SnapshotMap::new(
     "balances",
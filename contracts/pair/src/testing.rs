@@ -1,28 +1,46 @@
-use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    attr, to_json_binary, Addr, BankMsg, BlockInfo, Coin, CosmosMsg, Decimal, DepsMut, Env, Reply,
-    ReplyOn, Response, StdError, SubMsg, SubMsgResponse, SubMsgResult, Timestamp, Uint128, WasmMsg,
+    attr, coin, from_json, to_json_binary, Addr, BankMsg, BlockInfo, Coin, CosmosMsg, Decimal,
+    Decimal256, Deps, DepsMut, Env, Event, OwnedDeps, Reply, ReplyOn, Response, StdError,
+    StdResult, SubMsg, SubMsgResponse, SubMsgResult, Timestamp, Uint128, Uint256, WasmMsg,
 };
+use cw2::{set_contract_version, ContractVersion};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse};
 use proptest::prelude::*;
 
-use astroport::asset::{Asset, AssetInfo, PairInfo};
+use astroport::asset::{
+    native_asset_info, token_asset_info, Asset, AssetInfo, AssetInfoExt, PairInfo,
+};
 use astroport::factory::PairType;
 use astroport::pair::{
-    Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolResponse, ReverseSimulationResponse,
-    SimulationResponse, TWAP_PRECISION,
+    ArbitrageOpportunityResponse, BreakevenVolumeResponse, ConfigResponse, CustomHookConfig,
+    CustomHookMsg, Cw20HookMsg, FeeAdjustedTwapResponse, HookType, ImpermanentLossResponse,
+    InstantiateMsg, InvariantResponse, MigrateMsg, NormalizedReservesResponse, OraclePriceResponse,
+    PairFees24hResponse, PairHealthResponse, PoolResponse, PriceAlertConfig, PriceAlertMsg,
+    PriceSnapshot, PythPriceResponse, QueryMsg, RebasingAssetConfig, ReverseSimulationResponse,
+    SimulateProvideResponse, SimulationResponse, Snapshot, SpotPriceResponse, TradeDirection,
+    TwapSinceResponse, UserLpStatsResponse, XYKPoolConfig, XYKPoolParams, XYKPoolUpdateParams,
+    EMERGENCY_WITHDRAW_TIMELOCK, FEE_WINDOW_SECS, MAX_FEE_ADJUSTED_OBSERVATIONS,
+    MAX_POOL_SNAPSHOTS, MAX_TOP_LP_HOLDERS, ORACLE_TWAP_PERIOD, PRICE_FEED_EXPO, PRICE_RING_SIZE,
+    RESERVES_HISTORY_DEPTH, SNAPSHOT_INTERVAL_SECS, TWAP_PRECISION,
 };
+use astroport::pair_xyk::ExecuteMsg;
 use astroport::token::InstantiateMsg as TokenInstantiateMsg;
 
 use crate::contract::compute_offer_amount;
 use crate::contract::reply;
 use crate::contract::{
-    accumulate_prices, assert_max_spread, compute_swap, execute, instantiate, query_pool,
-    query_reverse_simulation, query_share, query_simulation,
+    accumulate_prices, assert_max_spread, compute_swap, execute, instantiate, migrate, query,
+    query_breakeven_volume, query_impermanent_loss, query_pool, query_reverse_simulation,
+    query_share, query_simulate_provide, query_simulation,
 };
 use crate::error::ContractError;
-use crate::mock_querier::mock_dependencies;
-use crate::state::{Config, CONFIG};
+use crate::mock_querier::{mock_dependencies, WasmMockQuerier};
+use crate::state::{
+    Config, CONFIG, DAILY_SPREADS, DAILY_SWAP_VOLUME_USD, FEE_PER_SHARE_CUMULATIVE, FEE_RING_24H,
+    NEXT_SNAPSHOT_ID, OLDEST_HISTORY_BLOCK, PAUSED, PRE_SWAP_INVARIANT, PRICE_ALERT_CONFIG,
+    PRICE_RING, RING_HEAD,
+};
 
 use prost::Message;
 
@@ -451,7 +469,14 @@ fn provide_liquidity() {
         }],
     );
     let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
-    assert_eq!(res, ContractError::MaxSlippageAssertion {});
+    assert_eq!(
+        res,
+        ContractError::SlippageTolerance {
+            asset: "asset0000".to_string(),
+            deviation: Decimal256::percent(2),
+            allowed: Decimal::percent(1),
+        }
+    );
 
     // Initialize token balance to 1:1
     deps.querier.with_balance(&[(
@@ -492,7 +517,14 @@ fn provide_liquidity() {
         }],
     );
     let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
-    assert_eq!(res, ContractError::MaxSlippageAssertion {});
+    assert_eq!(
+        res,
+        ContractError::SlippageTolerance {
+            asset: "asset0000".to_string(),
+            deviation: Decimal256::percent(2),
+            allowed: Decimal::percent(1),
+        }
+    );
 
     // Initialize token amount with a 1:1 ratio
     deps.querier.with_balance(&[(
@@ -631,6 +663,149 @@ fn provide_liquidity() {
     );
     let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
     assert_eq!(err, ContractError::AllowedSpreadAssertion {});
+
+    // Deposits with an extreme asset ratio are rejected
+    let msg = ExecuteMsg::ProvideLiquidity {
+        assets: vec![
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: Addr::unchecked("asset0000"),
+                },
+                amount: Uint128::from(1_000000000000000000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                amount: Uint128::from(99_000000000000000000u128),
+            },
+        ],
+        slippage_tolerance: Some(Decimal::percent(50)),
+        auto_stake: None,
+        receiver: None,
+    };
+    let info = mock_info(
+        "addr0001",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(99_000000000000000000u128),
+        }],
+    );
+    let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::SlippageTolerance {
+            asset: "asset0000".to_string(),
+            deviation: Decimal256::from_ratio(98u8, 99u8),
+            allowed: Decimal::percent(50),
+        }
+    );
+}
+
+#[test]
+fn provide_liquidity_via_cw20_hook() {
+    let mut deps = mock_dependencies(&[]);
+
+    // The hook tokens are already transferred to the pair when the hook is executed
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("asset0000"),
+            &[(
+                &String::from(MOCK_CONTRACT_ADDR),
+                &Uint128::new(100_000000000000000000),
+            )],
+        ),
+        (
+            &String::from("asset0001"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(0))],
+        ),
+        (
+            &String::from("liquidity0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(0))],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked("asset0000"),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked("asset0001"),
+            },
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+    // Store liquidity token
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let hook_msg = |pair_asset: &str| {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: String::from("addr0000"),
+            amount: Uint128::from(100_000000000000000000u128),
+            msg: to_json_binary(&Cw20HookMsg::ProvideLiquidity {
+                pair_asset: Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: Addr::unchecked(pair_asset),
+                    },
+                    amount: Uint128::from(100_000000000000000000u128),
+                },
+                slippage_tolerance: None,
+                auto_stake: false,
+                receiver: None,
+            })
+            .unwrap(),
+        })
+    };
+
+    // Only pool tokens can trigger the hook
+    let info = mock_info("asset0002", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, hook_msg("asset0001")).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let info = mock_info("asset0000", &[]);
+    let err = execute(deps.as_mut(), mock_env(), info, hook_msg("asset0000")).unwrap_err();
+    assert_eq!(err, ContractError::DoublingAssets {});
+
+    let info = mock_info("asset0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, hook_msg("asset0001")).unwrap();
+
+    // Only the counterpart is pulled from the provider
+    assert_eq!(res.messages.len(), 3);
+    assert_eq!(
+        res.messages[0].msg,
+        WasmMsg::Execute {
+            contract_addr: String::from("asset0001"),
+            msg: to_json_binary(&Cw20ExecuteMsg::TransferFrom {
+                owner: String::from("addr0000"),
+                recipient: String::from(MOCK_CONTRACT_ADDR),
+                amount: Uint128::from(100_000000000000000000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }
+        .into()
+    );
+    assert_eq!(
+        res.messages[2].msg,
+        WasmMsg::Execute {
+            contract_addr: String::from("liquidity0000"),
+            msg: to_json_binary(&Cw20ExecuteMsg::Mint {
+                recipient: String::from("addr0000"),
+                amount: Uint128::from(99_999999999999999000u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }
+        .into()
+    );
 }
 
 #[test]
@@ -834,6 +1009,20 @@ fn try_native_to_token() {
         .checked_sub(expected_commission_amount)
         .unwrap();
 
+    // The commission left after the Maker fee is accrued to the LP token holders
+    assert_eq!(
+        FEE_PER_SHARE_CUMULATIVE
+            .load(deps.as_ref().storage)
+            .unwrap(),
+        [
+            Decimal256::zero(),
+            Decimal256::from_ratio(
+                expected_commission_amount - expected_maker_fee_amount,
+                total_share
+            )
+        ]
+    );
+
     // Check simulation result
     deps.querier.with_balance(&[(
         &String::from(MOCK_CONTRACT_ADDR),
@@ -932,6 +1121,7 @@ fn try_native_to_token() {
             attr("commission_amount", expected_commission_amount.to_string()),
             attr("maker_fee_amount", expected_maker_fee_amount.to_string()),
             attr("fee_share_amount", "0"),
+            attr("fee_rebate", "0"),
         ]
     );
 
@@ -1123,6 +1313,7 @@ fn try_token_to_native() {
             attr("commission_amount", expected_commission_amount.to_string()),
             attr("maker_fee_amount", expected_maker_fee_amount.to_string()),
             attr("fee_share_amount", "0"),
+            attr("fee_rebate", "0"),
         ]
     );
 
@@ -1162,71 +1353,25 @@ fn try_token_to_native() {
 }
 
 #[test]
-fn test_max_spread() {
-    assert_max_spread(
-        Some(Decimal::from_ratio(1200u128, 1u128)),
-        Some(Decimal::percent(1)),
-        Uint128::from(1200000000u128),
-        Uint128::from(989999u128),
-        Uint128::zero(),
-    )
-    .unwrap_err();
-
-    assert_max_spread(
-        Some(Decimal::from_ratio(1200u128, 1u128)),
-        Some(Decimal::percent(1)),
-        Uint128::from(1200000000u128),
-        Uint128::from(990000u128),
-        Uint128::zero(),
-    )
-    .unwrap();
-
-    assert_max_spread(
-        None,
-        Some(Decimal::percent(1)),
-        Uint128::zero(),
-        Uint128::from(989999u128),
-        Uint128::from(10001u128),
-    )
-    .unwrap_err();
-
-    assert_max_spread(
-        None,
-        Some(Decimal::percent(1)),
-        Uint128::zero(),
-        Uint128::from(990000u128),
-        Uint128::from(10000u128),
-    )
-    .unwrap();
-
-    assert_max_spread(
-        Some(Decimal::from_ratio(1200u128, 1u128)),
-        Some(Decimal::percent(51)),
-        Uint128::from(1200000000u128),
-        Uint128::from(989999u128),
-        Uint128::zero(),
-    )
-    .unwrap_err();
-}
+fn swap_invariant_is_verified() {
+    let total_share = Uint128::new(30000000000u128);
+    let asset_pool_amount = Uint128::new(20000000000u128);
+    let collateral_pool_amount = Uint128::new(30000000000u128);
+    let offer_amount = Uint128::new(1500000000u128);
 
-#[test]
-fn test_query_pool() {
-    let total_share_amount = Uint128::from(111u128);
-    let asset_0_amount = Uint128::from(222u128);
-    let asset_1_amount = Uint128::from(333u128);
     let mut deps = mock_dependencies(&[Coin {
         denom: "uusd".to_string(),
-        amount: asset_0_amount,
+        amount: collateral_pool_amount + offer_amount,
     }]);
 
     deps.querier.with_token_balances(&[
         (
-            &String::from("asset0000"),
-            &[(&String::from(MOCK_CONTRACT_ADDR), &asset_1_amount)],
+            &String::from("liquidity0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &total_share)],
         ),
         (
-            &String::from("liquidity0000"),
-            &[(&String::from(MOCK_CONTRACT_ADDR), &total_share_amount)],
+            &String::from("asset0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &asset_pool_amount)],
         ),
     ]);
 
@@ -1246,318 +1391,4106 @@ fn test_query_pool() {
 
     let env = mock_env();
     let info = mock_info("addr0000", &[]);
-    // We can just call .unwrap() to assert this was a success
-    let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
-
-    // Store liquidity token
+    instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
     store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
 
-    let res: PoolResponse = query_pool(deps.as_ref()).unwrap();
+    let msg = ExecuteMsg::Swap {
+        offer_asset: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: offer_amount,
+        },
+        ask_asset_info: None,
+        belief_price: None,
+        max_spread: Some(Decimal::percent(50)),
+        to: None,
+    };
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: offer_amount,
+        }],
+    );
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
+    // Only the last transfer triggers the invariant check
+    assert_eq!(res.messages[0].reply_on, ReplyOn::Never);
+    let last_msg = res.messages.last().unwrap();
+    assert_eq!(last_msg.id, 2);
+    assert_eq!(last_msg.reply_on, ReplyOn::Always);
     assert_eq!(
-        res.assets,
-        [
-            Asset {
-                info: AssetInfo::NativeToken {
-                    denom: "uusd".to_string(),
-                },
-                amount: asset_0_amount
-            },
-            Asset {
-                info: AssetInfo::Token {
-                    contract_addr: Addr::unchecked("asset0000"),
-                },
-                amount: asset_1_amount
-            }
-        ]
+        PRE_SWAP_INVARIANT.load(deps.as_ref().storage).unwrap(),
+        Uint256::from(collateral_pool_amount) * Uint256::from(asset_pool_amount)
+    );
+
+    let reply_msg = |result| Reply { id: 2, result };
+
+    // The constant product grows as the offer asset is still in the pool
+    reply(
+        deps.as_mut(),
+        env.clone(),
+        reply_msg(SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: None,
+        })),
+    )
+    .unwrap();
+    assert!(PRE_SWAP_INVARIANT
+        .may_load(deps.as_ref().storage)
+        .unwrap()
+        .is_none());
+
+    // A failed transfer is propagated
+    PRE_SWAP_INVARIANT
+        .save(deps.as_mut().storage, &Uint256::zero())
+        .unwrap();
+    let err = reply(
+        deps.as_mut(),
+        env.clone(),
+        reply_msg(SubMsgResult::Err("transfer failed".to_string())),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("transfer failed"))
+    );
+
+    // Too many tokens left the pool
+    let pre_swap = Uint256::from(collateral_pool_amount) * Uint256::from(asset_pool_amount);
+    PRE_SWAP_INVARIANT
+        .save(deps.as_mut().storage, &pre_swap)
+        .unwrap();
+    let drained_amount = Uint128::new(10000000000u128);
+    deps.querier.with_token_balances(&[(
+        &String::from("asset0000"),
+        &[(&String::from(MOCK_CONTRACT_ADDR), &drained_amount)],
+    )]);
+    let err = reply(
+        deps.as_mut(),
+        env,
+        reply_msg(SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: None,
+        })),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvariantViolated {
+            pre_swap,
+            post_swap: Uint256::from(collateral_pool_amount + offer_amount)
+                * Uint256::from(drained_amount),
+        }
     );
-    assert_eq!(res.total_share, total_share_amount);
 }
 
 #[test]
-fn test_query_share() {
-    let total_share_amount = Uint128::from(500u128);
-    let asset_0_amount = Uint128::from(250u128);
-    let asset_1_amount = Uint128::from(1000u128);
-    let mut deps = mock_dependencies(&[Coin {
-        denom: "uusd".to_string(),
-        amount: asset_0_amount,
-    }]);
-
-    deps.querier.with_token_balances(&[
-        (
-            &String::from("asset0000"),
-            &[(&String::from(MOCK_CONTRACT_ADDR), &asset_1_amount)],
-        ),
-        (
-            &String::from("liquidity0000"),
-            &[(&String::from(MOCK_CONTRACT_ADDR), &total_share_amount)],
-        ),
-    ]);
+fn near_overflow_reserves() {
+    // Reserves of 18 decimal tokens whose constant product exceeds Uint128::MAX
+    let reserve = Uint128::MAX.multiply_ratio(1u8, 4u8);
+    let mut deps = mock_dependencies(&[coin(reserve.u128(), "uusd")]);
+    deps.querier.with_token_balances(&[(
+        &String::from("asset0000"),
+        &[(&String::from(MOCK_CONTRACT_ADDR), &reserve)],
+    )]);
 
     let msg = InstantiateMsg {
         asset_infos: vec![
-            AssetInfo::NativeToken {
-                denom: "uusd".to_string(),
-            },
-            AssetInfo::Token {
-                contract_addr: Addr::unchecked("asset0000"),
-            },
+            native_asset_info("uusd".to_string()),
+            token_asset_info(Addr::unchecked("asset0000")),
         ],
         token_code_id: 10u64,
         factory_addr: String::from("factory"),
         init_params: None,
     };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
 
-    let env = mock_env();
-    let info = mock_info("addr0000", &[]);
-    // We can just call .unwrap() to assert this was a success
-    let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+    let offer_amount = Uint128::new(1_000_000_000000000000000000);
+    let (return_amount, spread_amount, commission_amount) =
+        compute_swap(reserve, reserve, offer_amount, Decimal::permille(3)).unwrap();
+    assert!(return_amount + spread_amount + commission_amount <= offer_amount);
+    assert!(return_amount > offer_amount.multiply_ratio(99u8, 100u8));
 
-    // Store liquidity token
-    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+    // sqrt(reserve * reserve * 4) = 2 * reserve
+    let res: ArbitrageOpportunityResponse = from_json(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ArbitrageOpportunity {
+                reference_price: Decimal::from_ratio(4u8, 1u8),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.optimal_offer,
+        native_asset_info("uusd".to_string()).with_balance(reserve)
+    );
 
-    let res = query_share(deps.as_ref(), Uint128::new(250)).unwrap();
+    // The invariant check tolerates a decrease of one unit for rounding
+    let invariant = Uint256::from(reserve) * Uint256::from(reserve);
+    assert!(invariant > Uint256::from(Uint128::MAX));
+    let reply_msg = Reply {
+        id: 2,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: None,
+        }),
+    };
+    PRE_SWAP_INVARIANT
+        .save(deps.as_mut().storage, &(invariant + Uint256::one()))
+        .unwrap();
+    reply(deps.as_mut(), mock_env(), reply_msg.clone()).unwrap();
 
-    assert_eq!(res[0].amount, Uint128::new(125));
-    assert_eq!(res[1].amount, Uint128::new(500));
+    let pre_swap = invariant + Uint256::from(2u8);
+    PRE_SWAP_INVARIANT
+        .save(deps.as_mut().storage, &pre_swap)
+        .unwrap();
+    let err = reply(deps.as_mut(), mock_env(), reply_msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvariantViolated {
+            pre_swap,
+            post_swap: invariant,
+        }
+    );
 }
 
 #[test]
-fn test_accumulate_prices() {
-    struct Case {
-        block_time: u64,
-        block_time_last: u64,
-        last0: u128,
-        last1: u128,
-        x_amount: u128,
-        y_amount: u128,
-    }
-
-    struct Result {
-        block_time_last: u64,
-        price_x: u128,
-        price_y: u128,
-        is_some: bool,
-    }
+fn emergency_withdraw() {
+    let total_share = Uint128::new(30000000000u128);
+    let asset_pool_amount = Uint128::new(20000000000u128);
+    let collateral_pool_amount = Uint128::new(30000000000u128);
 
-    let price_precision = 10u128.pow(TWAP_PRECISION.into());
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: collateral_pool_amount,
+    }]);
 
-    let test_cases: Vec<(Case, Result)> = vec![
+    deps.querier.with_token_balances(&[
         (
-            Case {
-                block_time: 1000,
-                block_time_last: 0,
-                last0: 0,
-                last1: 0,
-                x_amount: 250,
-                y_amount: 500,
-            },
-            Result {
-                block_time_last: 1000,
-                price_x: 2000, // 500/250*1000
-                price_y: 500,  // 250/500*1000
-                is_some: true,
-            },
+            &String::from("liquidity0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &total_share)],
         ),
-        // Same block height, no changes
         (
-            Case {
-                block_time: 1000,
-                block_time_last: 1000,
-                last0: 1 * price_precision,
-                last1: 2 * price_precision,
-                x_amount: 250,
-                y_amount: 500,
-            },
-            Result {
-                block_time_last: 1000,
-                price_x: 1,
-                price_y: 2,
-                is_some: false,
-            },
+            &String::from("asset0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &asset_pool_amount)],
         ),
-        (
-            Case {
-                block_time: 1500,
-                block_time_last: 1000,
-                last0: 500 * price_precision,
-                last1: 2000 * price_precision,
-                x_amount: 250,
-                y_amount: 500,
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
             },
-            Result {
-                block_time_last: 1500,
-                price_x: 1500, // 500 + (500/250*500)
-                price_y: 2250, // 2000 + (250/500*500)
-                is_some: true,
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked("asset0000"),
             },
-        ),
-    ];
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
 
-    for test_case in test_cases {
-        let (case, result) = test_case;
+    let mut env = mock_env();
+    instantiate(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
 
-        let env = mock_env_with_block_time(case.block_time);
-        let config = accumulate_prices(
-            env,
-            &Config {
-                pair_info: PairInfo {
-                    asset_infos: vec![
-                        AssetInfo::NativeToken {
-                            denom: "uusd".to_string(),
-                        },
-                        AssetInfo::Token {
-                            contract_addr: Addr::unchecked("asset0000"),
-                        },
-                    ],
-                    contract_addr: Addr::unchecked("pair"),
-                    liquidity_token: Addr::unchecked("lp_token"),
-                    pair_type: PairType::Xyk {}, // Implemented in mock querier
-                },
-                factory_addr: Addr::unchecked("factory"),
-                block_time_last: case.block_time_last,
-                price0_cumulative_last: Uint128::new(case.last0),
-                price1_cumulative_last: Uint128::new(case.last1),
-                track_asset_balances: false,
-                fee_share: None,
-            },
-            Uint128::new(case.x_amount),
-            Uint128::new(case.y_amount),
-        )
-        .unwrap();
+    let propose_msg = ExecuteMsg::ProposeEmergencyWithdraw {
+        recipient: "recovery".to_string(),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        propose_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
 
-        assert_eq!(result.is_some, config.is_some());
+    let withdraw_msg = ExecuteMsg::EmergencyWithdraw {
+        recipient: "recovery".to_string(),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        withdraw_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::EmergencyWithdrawProposalNotFound {});
 
-        if let Some(config) = config {
-            assert_eq!(config.2, result.block_time_last);
-            assert_eq!(
-                config.0 / Uint128::from(price_precision),
-                Uint128::new(result.price_x)
-            );
-            assert_eq!(
-                config.1 / Uint128::from(price_precision),
-                Uint128::new(result.price_y)
-            );
-        }
-    }
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("emergency_admin", &[]),
+        propose_msg,
+    )
+    .unwrap();
+    let unlock_at = env.block.time.seconds() + EMERGENCY_WITHDRAW_TIMELOCK;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "propose_emergency_withdraw"),
+            attr("recipient", "recovery"),
+            attr("unlock_at", unlock_at.to_string()),
+        ]
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        withdraw_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::EmergencyWithdrawTimelocked(unlock_at));
+
+    env.block.time = env.block.time.plus_seconds(EMERGENCY_WITHDRAW_TIMELOCK);
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecuteMsg::EmergencyWithdraw {
+            recipient: "attacker".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::EmergencyWithdrawRecipientMismatch("recovery".to_string())
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        withdraw_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "recovery".to_string(),
+                amount: vec![Coin {
+                    denom: "uusd".to_string(),
+                    amount: collateral_pool_amount,
+                }],
+            })),
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "asset0000".to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "recovery".to_string(),
+                    amount: asset_pool_amount,
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+        ]
+    );
+
+    // The proposal is consumed
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        withdraw_msg,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::EmergencyWithdrawProposalNotFound {});
+
+    // The pair stays paused
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(
+            "addr0000",
+            &[Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(1000),
+            }],
+        ),
+        ExecuteMsg::Swap {
+            offer_asset: native_asset_info("uusd".to_string()).with_balance(1000u128),
+            ask_asset_info: None,
+            belief_price: None,
+            max_spread: None,
+            to: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::PairPaused {});
+
+    let err = execute(
+        deps.as_mut(),
+        env,
+        mock_info(
+            "addr0000",
+            &[Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(1000),
+            }],
+        ),
+        ExecuteMsg::ProvideLiquidity {
+            assets: vec![
+                native_asset_info("uusd".to_string()).with_balance(1000u128),
+                token_asset_info(Addr::unchecked("asset0000")).with_balance(1000u128),
+            ],
+            slippage_tolerance: None,
+            auto_stake: None,
+            receiver: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::PairPaused {});
 }
 
-fn mock_env_with_block_time(time: u64) -> Env {
-    let mut env = mock_env();
-    env.block = BlockInfo {
-        height: 1,
-        time: Timestamp::from_seconds(time),
-        chain_id: "columbus".to_string(),
+#[test]
+fn instantiate_with_max_spread() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = |max_spread| InstantiateMsg {
+        factory_addr: String::from("factory"),
+        asset_infos: vec![
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked("asset0000"),
+            },
+        ],
+        token_code_id: 10u64,
+        init_params: Some(
+            to_json_binary(&XYKPoolParams {
+                track_asset_balances: None,
+                max_spread: Some(max_spread),
+                lp_cooldown_secs: None,
+                track_swap_history: None,
+            })
+            .unwrap(),
+        ),
     };
-    env
+
+    let err = instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg(Decimal::percent(51)),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidSpreadConfig {});
+
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg(Decimal::percent(50)),
+    )
+    .unwrap();
+    assert_eq!(
+        CONFIG.load(deps.as_ref().storage).unwrap().max_spread,
+        Some(Decimal::percent(50))
+    );
 }
 
 #[test]
-fn compute_swap_rounding() {
-    let offer_pool = Uint128::from(5_000_000_000_000_u128);
-    let ask_pool = Uint128::from(1_000_000_000_u128);
+fn test_max_spread() {
+    assert_max_spread(
+        Some(Decimal::from_ratio(1200u128, 1u128)),
+        Some(Decimal::percent(1)),
+        Uint128::from(1200000000u128),
+        Uint128::from(989999u128),
+        Uint128::zero(),
+    )
+    .unwrap_err();
+
+    assert_max_spread(
+        Some(Decimal::from_ratio(1200u128, 1u128)),
+        Some(Decimal::percent(1)),
+        Uint128::from(1200000000u128),
+        Uint128::from(990000u128),
+        Uint128::zero(),
+    )
+    .unwrap();
+
+    assert_max_spread(
+        None,
+        Some(Decimal::percent(1)),
+        Uint128::zero(),
+        Uint128::from(989999u128),
+        Uint128::from(10001u128),
+    )
+    .unwrap_err();
+
+    assert_max_spread(
+        None,
+        Some(Decimal::percent(1)),
+        Uint128::zero(),
+        Uint128::from(990000u128),
+        Uint128::from(10000u128),
+    )
+    .unwrap();
+
+    assert_max_spread(
+        Some(Decimal::from_ratio(1200u128, 1u128)),
+        Some(Decimal::percent(51)),
+        Uint128::from(1200000000u128),
+        Uint128::from(989999u128),
+        Uint128::zero(),
+    )
+    .unwrap_err();
+}
+
+#[test]
+fn test_query_pool() {
+    let total_share_amount = Uint128::from(111u128);
+    let asset_0_amount = Uint128::from(222u128);
+    let asset_1_amount = Uint128::from(333u128);
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: asset_0_amount,
+    }]);
+
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("asset0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &asset_1_amount)],
+        ),
+        (
+            &String::from("liquidity0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &total_share_amount)],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked("asset0000"),
+            },
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    // We can just call .unwrap() to assert this was a success
+    let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+    // Store liquidity token
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let res: PoolResponse = query_pool(deps.as_ref()).unwrap();
+
+    assert_eq!(
+        res.assets,
+        [
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                amount: asset_0_amount
+            },
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: Addr::unchecked("asset0000"),
+                },
+                amount: asset_1_amount
+            }
+        ]
+    );
+    assert_eq!(res.total_share, total_share_amount);
+
+    let res: InvariantResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Invariant {}).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        InvariantResponse {
+            value: Uint256::from(222u128 * 333u128),
+            formula: "xy=k".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_query_share() {
+    let total_share_amount = Uint128::from(500u128);
+    let asset_0_amount = Uint128::from(250u128);
+    let asset_1_amount = Uint128::from(1000u128);
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: asset_0_amount,
+    }]);
+
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("asset0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &asset_1_amount)],
+        ),
+        (
+            &String::from("liquidity0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &total_share_amount)],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked("asset0000"),
+            },
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    // We can just call .unwrap() to assert this was a success
+    let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+    // Store liquidity token
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let res = query_share(deps.as_ref(), Uint128::new(250)).unwrap();
+
+    assert_eq!(res[0].amount, Uint128::new(125));
+    assert_eq!(res[1].amount, Uint128::new(500));
+}
+
+#[test]
+fn test_query_simulate_provide() {
+    let total_share_amount = Uint128::from(500u128);
+    let asset_0_amount = Uint128::from(250u128);
+    let asset_1_amount = Uint128::from(1000u128);
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: asset_0_amount,
+    }]);
+
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("asset0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &asset_1_amount)],
+        ),
+        (
+            &String::from("liquidity0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &total_share_amount)],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked("asset0000"),
+            },
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), env, info, msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let deposit = |uusd_amount: u128, token_amount: u128| {
+        vec![
+            native_asset_info("uusd".to_string()).with_balance(uusd_amount),
+            token_asset_info(Addr::unchecked("asset0000")).with_balance(token_amount),
+        ]
+    };
+
+    // A deposit at the pool ratio has no price impact
+    let res = query_simulate_provide(deps.as_ref(), deposit(50, 200)).unwrap();
+    assert_eq!(
+        res,
+        SimulateProvideResponse {
+            lp_amount: Uint128::new(100),
+            fee: None,
+            price_impact_bps: 0,
+        }
+    );
+
+    // Half of the token deposit is donated to the pool
+    let res = query_simulate_provide(deps.as_ref(), deposit(50, 400)).unwrap();
+    assert_eq!(
+        res,
+        SimulateProvideResponse {
+            lp_amount: Uint128::new(100),
+            fee: None,
+            price_impact_bps: 3333,
+        }
+    );
+
+    let err = query_simulate_provide(deps.as_ref(), deposit(0, 400)).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err(ContractError::InvalidZeroAmount {}.to_string())
+    );
+
+    let err = query_simulate_provide(
+        deps.as_ref(),
+        vec![
+            native_asset_info("uusd".to_string()).with_balance(50u128),
+            native_asset_info("uluna".to_string()).with_balance(200u128),
+        ],
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("Assets must include asset0000"));
+}
+
+#[test]
+fn test_query_impermanent_loss() {
+    let total_share_amount = Uint128::from(500u128);
+    let asset_0_amount = Uint128::from(250u128);
+    let asset_1_amount = Uint128::from(1000u128);
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: asset_0_amount,
+    }]);
+
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("asset0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &asset_1_amount)],
+        ),
+        (
+            &String::from("liquidity0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &total_share_amount)],
+        ),
+    ]);
+
+    let asset_infos = vec![
+        AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        AssetInfo::Token {
+            contract_addr: Addr::unchecked("asset0000"),
+        },
+    ];
+    let msg = InstantiateMsg {
+        asset_infos: asset_infos.clone(),
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+    // Store liquidity token
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    // Half of the pool was provided at a 1:1 price, the price has moved to 4:1 since then
+    let initial_assets = vec![
+        asset_infos[1].with_balance(250u128),
+        asset_infos[0].with_balance(250u128),
+    ];
+    let res =
+        query_impermanent_loss(deps.as_ref(), initial_assets.clone(), Uint128::new(250)).unwrap();
+    assert_eq!(
+        res,
+        ImpermanentLossResponse {
+            holding_value: Uint128::new(1250),
+            lp_value: Uint128::new(1000),
+            il_percent: Decimal::percent(20),
+            price_ratio: Decimal::from_ratio(4u8, 1u8),
+        }
+    );
+
+    let err = query_impermanent_loss(
+        deps.as_ref(),
+        initial_assets[..1].to_vec(),
+        Uint128::new(250),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("Two initial assets are required")
+    );
+
+    let err = query_impermanent_loss(
+        deps.as_ref(),
+        vec![
+            asset_infos[0].with_balance(250u128),
+            asset_infos[1].with_balance(0u128),
+        ],
+        Uint128::new(250),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("Initial asset amounts must be non-zero")
+    );
+}
+
+#[test]
+fn test_query_breakeven_volume() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(250),
+    }]);
+
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("asset0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(1000))],
+        ),
+        (
+            &String::from("liquidity0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(500))],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            native_asset_info("uusd".to_string()),
+            token_asset_info(Addr::unchecked("asset0000")),
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    // A 4x price move causes a 20% impermanent loss of the 2000 TVL. 0.3% fee minus 16.6%
+    // going to the Maker leaves 0.2502% for the LPs
+    let res = query_breakeven_volume(deps.as_ref(), Decimal::one(), Decimal::from_ratio(4u8, 1u8))
+        .unwrap();
+    assert_eq!(
+        res,
+        BreakevenVolumeResponse {
+            required_volume: Uint128::new(159872),
+            current_fee_rate: Decimal::from_ratio(2502u16, 1_000_000u32),
+            il_percentage: Decimal::percent(20),
+        }
+    );
+
+    // The loss is the same whichever way the price moves
+    let res = query_breakeven_volume(deps.as_ref(), Decimal::from_ratio(4u8, 1u8), Decimal::one())
+        .unwrap();
+    assert_eq!(res.il_percentage, Decimal::percent(20));
+
+    let res = query_breakeven_volume(deps.as_ref(), Decimal::one(), Decimal::one()).unwrap();
+    assert_eq!(res.required_volume, Uint128::zero());
+
+    let err = query_breakeven_volume(deps.as_ref(), Decimal::zero(), Decimal::one()).unwrap_err();
+    assert_eq!(err, StdError::generic_err("Prices must be non-zero"));
+}
+
+#[test]
+fn test_accumulate_prices() {
+    struct Case {
+        block_time: u64,
+        block_time_last: u64,
+        last0: u128,
+        last1: u128,
+        x_amount: u128,
+        y_amount: u128,
+    }
+
+    struct Result {
+        block_time_last: u64,
+        price_x: u128,
+        price_y: u128,
+        is_some: bool,
+    }
+
+    let price_precision = 10u128.pow(TWAP_PRECISION.into());
+
+    let test_cases: Vec<(Case, Result)> = vec![
+        (
+            Case {
+                block_time: 1000,
+                block_time_last: 0,
+                last0: 0,
+                last1: 0,
+                x_amount: 250,
+                y_amount: 500,
+            },
+            Result {
+                block_time_last: 1000,
+                price_x: 2000, // 500/250*1000
+                price_y: 500,  // 250/500*1000
+                is_some: true,
+            },
+        ),
+        // Same block height, no changes
+        (
+            Case {
+                block_time: 1000,
+                block_time_last: 1000,
+                last0: 1 * price_precision,
+                last1: 2 * price_precision,
+                x_amount: 250,
+                y_amount: 500,
+            },
+            Result {
+                block_time_last: 1000,
+                price_x: 1,
+                price_y: 2,
+                is_some: false,
+            },
+        ),
+        (
+            Case {
+                block_time: 1500,
+                block_time_last: 1000,
+                last0: 500 * price_precision,
+                last1: 2000 * price_precision,
+                x_amount: 250,
+                y_amount: 500,
+            },
+            Result {
+                block_time_last: 1500,
+                price_x: 1500, // 500 + (500/250*500)
+                price_y: 2250, // 2000 + (250/500*500)
+                is_some: true,
+            },
+        ),
+    ];
+
+    for test_case in test_cases {
+        let (case, result) = test_case;
+
+        let env = mock_env_with_block_time(case.block_time);
+        let config = accumulate_prices(
+            env,
+            &Config {
+                pair_info: PairInfo {
+                    asset_infos: vec![
+                        AssetInfo::NativeToken {
+                            denom: "uusd".to_string(),
+                        },
+                        AssetInfo::Token {
+                            contract_addr: Addr::unchecked("asset0000"),
+                        },
+                    ],
+                    contract_addr: Addr::unchecked("pair"),
+                    liquidity_token: Addr::unchecked("lp_token"),
+                    pair_type: PairType::Xyk {}, // Implemented in mock querier
+                    creator: None,
+                    creation_height: 0,
+                    creation_time: 0,
+                },
+                factory_addr: Addr::unchecked("factory"),
+                block_time_last: case.block_time_last,
+                price0_cumulative_last: Uint128::new(case.last0),
+                price1_cumulative_last: Uint128::new(case.last1),
+                track_asset_balances: false,
+                fee_share: None,
+                max_spread: None,
+                lp_cooldown_secs: None,
+                custom_hook: None,
+                min_provide_amount: Default::default(),
+                swap_fee_rebate_bps: 0,
+                fee_rebate_expires_at: None,
+                max_tvl_asset0: None,
+                max_tvl_asset1: None,
+                sender_whitelist: None,
+                max_single_swap_usd: None,
+                max_daily_swap_usd: None,
+                usd_oracle: None,
+                compliance_mode: false,
+                rebasing_asset: None,
+                track_swap_history: true,
+            },
+            Uint128::new(case.x_amount),
+            Uint128::new(case.y_amount),
+        )
+        .unwrap();
+
+        assert_eq!(result.is_some, config.is_some());
+
+        if let Some(config) = config {
+            assert_eq!(config.2, result.block_time_last);
+            assert_eq!(
+                config.0 / Uint128::from(price_precision),
+                Uint128::new(result.price_x)
+            );
+            assert_eq!(
+                config.1 / Uint128::from(price_precision),
+                Uint128::new(result.price_y)
+            );
+        }
+    }
+}
+
+#[test]
+fn pool_state_snapshots() {
+    let total_share = Uint128::new(30000000000u128);
+    let asset_pool_amount = Uint128::new(20000000000u128);
+    let collateral_pool_amount = Uint128::new(30000000000u128);
+
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: collateral_pool_amount,
+    }]);
+
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("liquidity0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &total_share)],
+        ),
+        (
+            &String::from("asset0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &asset_pool_amount)],
+        ),
+    ]);
+
+    let asset_infos = vec![
+        AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        AssetInfo::Token {
+            contract_addr: Addr::unchecked("asset0000"),
+        },
+    ];
+    let msg = InstantiateMsg {
+        asset_infos: asset_infos.clone(),
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+
+    let env = mock_env_with_block_time(1000);
+    instantiate(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let latest_snapshot: Option<(u64, Snapshot)> =
+        from_json(query(deps.as_ref(), env.clone(), QueryMsg::LatestSnapshot {}).unwrap()).unwrap();
+    assert_eq!(latest_snapshot, None);
+
+    let env = mock_env_with_block_time(2000);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &[]),
+        ExecuteMsg::TakeSnapshot {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "take_snapshot"), attr("snapshot_id", "0")]
+    );
+
+    let expected_snapshot = Snapshot {
+        reserves: vec![
+            asset_infos[0].with_balance(collateral_pool_amount),
+            asset_infos[1].with_balance(asset_pool_amount),
+        ],
+        total_lp: total_share,
+        invariant: collateral_pool_amount.full_mul(asset_pool_amount),
+        price: Decimal::from_ratio(2u8, 3u8),
+        timestamp: 2000,
+        block: env.block.height,
+    };
+    let snapshot: Snapshot =
+        from_json(query(deps.as_ref(), env.clone(), QueryMsg::Snapshot { id: 0 }).unwrap())
+            .unwrap();
+    assert_eq!(snapshot, expected_snapshot);
+
+    // The oldest snapshot is evicted once the limit is reached
+    NEXT_SNAPSHOT_ID
+        .save(deps.as_mut().storage, &MAX_POOL_SNAPSHOTS)
+        .unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &[]),
+        ExecuteMsg::TakeSnapshot {},
+    )
+    .unwrap();
+    query(deps.as_ref(), env.clone(), QueryMsg::Snapshot { id: 0 }).unwrap_err();
+    let latest_snapshot: Option<(u64, Snapshot)> =
+        from_json(query(deps.as_ref(), env, QueryMsg::LatestSnapshot {}).unwrap()).unwrap();
+    assert_eq!(
+        latest_snapshot,
+        Some((MAX_POOL_SNAPSHOTS, expected_snapshot))
+    );
+}
+
+/// Provides `amount` uusd and twice as much of the token to the mocked pool at `height`.
+/// The mocked pool is empty before every provision, so the reserves equal the deposits.
+fn provide_at_height(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    height: u64,
+    amount: u128,
+) {
+    deps.querier
+        .with_balance(&[(&String::from(MOCK_CONTRACT_ADDR), &[coin(amount, "uusd")])]);
+    let mut env = mock_env();
+    env.block.height = height;
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("addr0000", &[coin(amount, "uusd")]),
+        ExecuteMsg::ProvideLiquidity {
+            assets: vec![
+                native_asset_info("uusd".to_string()).with_balance(amount),
+                token_asset_info(Addr::unchecked("asset0000")).with_balance(2 * amount),
+            ],
+            slippage_tolerance: None,
+            auto_stake: None,
+            receiver: None,
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn reserves_history() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("asset0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::zero())],
+        ),
+        (
+            &String::from("liquidity0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::zero())],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            native_asset_info("uusd".to_string()),
+            token_asset_info(Addr::unchecked("asset0000")),
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let history = |deps: Deps, from_block: u64, to_block: u64| -> Vec<(u64, [Uint128; 2])> {
+        from_json(
+            query(
+                deps,
+                mock_env(),
+                QueryMsg::ReservesHistory {
+                    from_block,
+                    to_block,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    let reserves = |amount: u128| [Uint128::new(amount), Uint128::new(2 * amount)];
+
+    provide_at_height(&mut deps, 100, 1_000000);
+    provide_at_height(&mut deps, 105, 2_000000);
+    // Only the last reserves of a block are kept
+    provide_at_height(&mut deps, 105, 3_000000);
+    assert_eq!(
+        history(deps.as_ref(), 0, u64::MAX),
+        vec![(100, reserves(1_000000)), (105, reserves(3_000000))]
+    );
+    assert_eq!(
+        history(deps.as_ref(), 101, 105),
+        vec![(105, reserves(3_000000))]
+    );
+    assert_eq!(history(deps.as_ref(), 101, 104), vec![]);
+
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::ReservesHistory {
+            from_block: 105,
+            to_block: 100,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("from_block must not be greater than to_block")
+    );
+
+    // The entries older than the history depth are removed
+    provide_at_height(&mut deps, 101 + RESERVES_HISTORY_DEPTH, 4_000000);
+    assert_eq!(
+        history(deps.as_ref(), 0, u64::MAX),
+        vec![
+            (105, reserves(3_000000)),
+            (101 + RESERVES_HISTORY_DEPTH, reserves(4_000000))
+        ]
+    );
+    assert_eq!(
+        OLDEST_HISTORY_BLOCK.load(deps.as_ref().storage).unwrap(),
+        105
+    );
+}
+
+#[test]
+fn swap_history_tracking() {
+    let offer_amount = Uint128::new(1_000000);
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(30000_000000) + offer_amount,
+    }]);
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("liquidity0000"),
+            &[(
+                &String::from(MOCK_CONTRACT_ADDR),
+                &Uint128::new(20000_000000),
+            )],
+        ),
+        (
+            &String::from("asset0000"),
+            &[(
+                &String::from(MOCK_CONTRACT_ADDR),
+                &Uint128::new(20000_000000),
+            )],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            native_asset_info("uusd".to_string()),
+            token_asset_info(Addr::unchecked("asset0000")),
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let swap = |deps: DepsMut| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("addr0000", &[coin(offer_amount.u128(), "uusd")]),
+            ExecuteMsg::Swap {
+                offer_asset: native_asset_info("uusd".to_string()).with_balance(offer_amount),
+                ask_asset_info: None,
+                belief_price: None,
+                max_spread: Some(Decimal::percent(50)),
+                to: None,
+            },
+        )
+        .unwrap();
+    };
+    let track_swap_history = |deps: Deps| -> bool {
+        let config: ConfigResponse =
+            from_json(&query(deps, mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+        let params: XYKPoolConfig = from_json(config.params.unwrap()).unwrap();
+        params.track_swap_history
+    };
+
+    // Swaps record the history by default
+    assert!(track_swap_history(deps.as_ref()));
+    swap(deps.as_mut());
+    assert!(RING_HEAD.may_load(deps.as_ref().storage).unwrap().is_some());
+    assert!(FEE_RING_24H.has(deps.as_ref().storage, 0));
+
+    let update_tracking = |track_swap_history: bool| ExecuteMsg::UpdateConfig {
+        params: to_json_binary(&XYKPoolUpdateParams::UpdateSwapHistoryTracking {
+            track_swap_history,
+        })
+        .unwrap(),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        update_tracking(false),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        update_tracking(false),
+    )
+    .unwrap();
+    assert!(!track_swap_history(deps.as_ref()));
+
+    // The recorded history is kept, but swaps no longer add to it
+    swap(deps.as_mut());
+    assert!(FEE_RING_24H.has(deps.as_ref().storage, 0));
+    assert!(!FEE_RING_24H.has(deps.as_ref().storage, 1));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        update_tracking(true),
+    )
+    .unwrap();
+    swap(deps.as_mut());
+    assert!(FEE_RING_24H.has(deps.as_ref().storage, 1));
+}
+
+#[test]
+fn twap_since_snapshot() {
+    let total_share = Uint128::new(30000000000u128);
+    let asset_pool_amount = Uint128::new(20000000000u128);
+    let collateral_pool_amount = Uint128::new(30000000000u128);
+
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: collateral_pool_amount,
+    }]);
+
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("liquidity0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &total_share)],
+        ),
+        (
+            &String::from("asset0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &asset_pool_amount)],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked("asset0000"),
+            },
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+
+    let env = mock_env_with_block_time(1000);
+    instantiate(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let env = mock_env_with_block_time(2000);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &[]),
+        ExecuteMsg::SaveTwapSnapshot {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "save_twap_snapshot"),
+            attr("snapshot_id", "0"),
+            attr("timestamp", "2000"),
+        ]
+    );
+
+    let err = query(deps.as_ref(), env, QueryMsg::TwapSince { snapshot_id: 0 }).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("TWAP can not be computed in the same block the snapshot was saved")
+    );
+
+    let env = mock_env_with_block_time(2600);
+    let err = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::TwapSince { snapshot_id: 1 },
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("TWAP snapshot 1 not found"));
+
+    let res: TwapSinceResponse =
+        from_json(&query(deps.as_ref(), env, QueryMsg::TwapSince { snapshot_id: 0 }).unwrap())
+            .unwrap();
+    assert_eq!(res.snapshot.timestamp, 2000);
+    assert_eq!(
+        res.average_prices,
+        vec![
+            (
+                native_asset_info("uusd".to_string()),
+                token_asset_info(Addr::unchecked("asset0000")),
+                Decimal::from_ratio(asset_pool_amount, collateral_pool_amount),
+            ),
+            (
+                token_asset_info(Addr::unchecked("asset0000")),
+                native_asset_info("uusd".to_string()),
+                Decimal::from_ratio(collateral_pool_amount, asset_pool_amount),
+            ),
+        ]
+    );
+}
+
+#[test]
+fn oracle_price() {
+    let total_share = Uint128::new(30000000000u128);
+    let asset_pool_amount = Uint128::new(20000000000u128);
+    let collateral_pool_amount = Uint128::new(30000000000u128);
+
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: collateral_pool_amount,
+    }]);
+
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("liquidity0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &total_share)],
+        ),
+        (
+            &String::from("asset0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &asset_pool_amount)],
+        ),
+    ]);
+
+    let uusd = native_asset_info("uusd".to_string());
+    let token = token_asset_info(Addr::unchecked("asset0000"));
+    let msg = InstantiateMsg {
+        asset_infos: vec![uusd.clone(), token.clone()],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+
+    let env = mock_env_with_block_time(1000);
+    instantiate(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let price_query = |base: &AssetInfo, quote: &AssetInfo, amount: u128| QueryMsg::Price {
+        base: base.clone(),
+        quote: quote.clone(),
+        amount: Uint128::new(amount),
+    };
+
+    let err = query(deps.as_ref(), env.clone(), price_query(&uusd, &uusd, 0)).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("Base and quote assets must be different pool assets")
+    );
+
+    // The spot price is returned with zero confidence until a TWAP snapshot is stored
+    let spot_price = Decimal::from_ratio(asset_pool_amount, collateral_pool_amount);
+    let res: OraclePriceResponse =
+        from_json(&query(deps.as_ref(), env.clone(), price_query(&uusd, &token, 0)).unwrap())
+            .unwrap();
+    assert_eq!(res.price, spot_price);
+    assert_eq!(res.confidence, Decimal::zero());
+
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("anyone", &[]),
+        ExecuteMsg::SaveTwapSnapshot {},
+    )
+    .unwrap();
+
+    // The reserves didn't change, so the confidence only reflects the TWAP period
+    let env = mock_env_with_block_time(1000 + ORACLE_TWAP_PERIOD / 2);
+    let res: OraclePriceResponse =
+        from_json(&query(deps.as_ref(), env.clone(), price_query(&token, &uusd, 0)).unwrap())
+            .unwrap();
+    assert_eq!(
+        res.price,
+        Decimal::from_ratio(collateral_pool_amount, asset_pool_amount)
+    );
+    assert_eq!(res.confidence, Decimal::percent(50));
+
+    // Pricing a trade includes its price impact
+    let res: OraclePriceResponse = from_json(
+        &query(
+            deps.as_ref(),
+            env,
+            price_query(&uusd, &token, 1_000_000_000),
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert!(res.price < spot_price);
+    assert_eq!(res.confidence, Decimal::percent(50));
+}
+
+fn set_lp_balances(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    balances: &[(&str, u128)],
+) {
+    let balances: Vec<_> = balances
+        .iter()
+        .map(|(address, balance)| (address.to_string(), Uint128::new(*balance)))
+        .collect();
+    let balances: Vec<_> = balances
+        .iter()
+        .map(|(address, balance)| (address, balance))
+        .collect();
+    deps.querier
+        .with_token_balances(&[(&String::from("liquidity0000"), &balances)]);
+}
+
+#[test]
+fn top_lp_providers() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked("asset0000"),
+            },
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+    let env = mock_env();
+    instantiate(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let update_holders = |deps: DepsMut, balances: &[(&str, u128)]| {
+        let addresses = balances
+            .iter()
+            .map(|(address, _)| address.to_string())
+            .collect();
+        execute(
+            deps,
+            mock_env(),
+            mock_info("liquidity0000", &[]),
+            ExecuteMsg::UpdateLpHolders {
+                addresses,
+                transfer: None,
+            },
+        )
+        .unwrap();
+    };
+    let top_providers = |deps: Deps, limit: u32| -> Vec<(String, Uint128)> {
+        from_json(&query(deps, mock_env(), QueryMsg::TopLpProviders { limit }).unwrap()).unwrap()
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UpdateLpHolders {
+            addresses: vec!["alice".to_string()],
+            transfer: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // The pair itself is never tracked
+    let balances = [
+        ("alice", 300),
+        ("bob", 200),
+        ("carol", 100),
+        (MOCK_CONTRACT_ADDR, 1000),
+    ];
+    set_lp_balances(&mut deps, &balances);
+    update_holders(deps.as_mut(), &balances);
+    assert_eq!(
+        top_providers(deps.as_ref(), 2),
+        vec![
+            ("alice".to_string(), Uint128::new(300)),
+            ("bob".to_string(), Uint128::new(200)),
+        ]
+    );
+
+    // Alice transfers her whole balance to a new holder
+    let balances = [("alice", 0), ("bob", 200), ("carol", 100), ("dave", 300)];
+    set_lp_balances(&mut deps, &balances);
+    update_holders(deps.as_mut(), &[("alice", 0), ("dave", 300)]);
+    assert_eq!(
+        top_providers(deps.as_ref(), 10),
+        vec![
+            ("dave".to_string(), Uint128::new(300)),
+            ("bob".to_string(), Uint128::new(200)),
+            ("carol".to_string(), Uint128::new(100)),
+        ]
+    );
+
+    // The smallest holders are dropped once the heap is full
+    let addresses: Vec<_> = (0..MAX_TOP_LP_HOLDERS)
+        .map(|i| format!("holder{i:03}"))
+        .collect();
+    let mut balances: Vec<_> = addresses
+        .iter()
+        .map(|address| (address.as_str(), 150))
+        .collect();
+    balances.extend([("bob", 200), ("carol", 100), ("dave", 300)]);
+    set_lp_balances(&mut deps, &balances);
+    update_holders(deps.as_mut(), &balances[..MAX_TOP_LP_HOLDERS]);
+
+    let holders = top_providers(deps.as_ref(), 1000);
+    assert_eq!(holders.len(), MAX_TOP_LP_HOLDERS);
+    assert_eq!(holders[0], ("dave".to_string(), Uint128::new(300)));
+    assert_eq!(holders[1], ("bob".to_string(), Uint128::new(200)));
+    assert!(!holders.iter().any(|(address, _)| address == "carol"));
+}
+
+#[test]
+fn pair_health() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(30000),
+    }]);
+    deps.querier.with_token_balances(&[(
+        &String::from("asset0000"),
+        &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(20000))],
+    )]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked("asset0000"),
+            },
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+    let env = mock_env();
+    instantiate(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let pair_health = |deps: Deps| -> PairHealthResponse {
+        from_json(&query(deps, mock_env(), QueryMsg::PairHealth {}).unwrap()).unwrap()
+    };
+
+    assert_eq!(
+        pair_health(deps.as_ref()),
+        PairHealthResponse {
+            reserve_ratio: Decimal::from_ratio(3u8, 2u8),
+            price_deviation_from_oracle: None,
+            spread_7d_avg: None,
+            circuit_breaker_active: false,
+            paused: false,
+            health_score: 100,
+        }
+    );
+
+    // Only the spreads of the last 7 days are averaged
+    let today = env.block.time.seconds() / 86400;
+    DAILY_SPREADS
+        .save(deps.as_mut().storage, today, &(Decimal256::percent(3), 2))
+        .unwrap();
+    DAILY_SPREADS
+        .save(deps.as_mut().storage, today - 6, &(Decimal256::zero(), 1))
+        .unwrap();
+    DAILY_SPREADS
+        .save(
+            deps.as_mut().storage,
+            today - 7,
+            &(Decimal256::percent(50), 1),
+        )
+        .unwrap();
+
+    let update_oracle = |oracle: Option<String>| ExecuteMsg::UpdateConfig {
+        params: to_json_binary(&XYKPoolUpdateParams::UpdateOracle { oracle }).unwrap(),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        update_oracle(Some("oracle".to_string())),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        update_oracle(Some("oracle".to_string())),
+    )
+    .unwrap();
+
+    // The oracle values the uusd reserve at 18750 while the pool holds 20000
+    assert_eq!(
+        pair_health(deps.as_ref()),
+        PairHealthResponse {
+            reserve_ratio: Decimal::from_ratio(3u8, 2u8),
+            price_deviation_from_oracle: Some(Decimal::from_ratio(1250u128, 18750u128)),
+            spread_7d_avg: Some(Decimal::percent(1)),
+            circuit_breaker_active: false,
+            paused: false,
+            health_score: 64,
+        }
+    );
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        update_oracle(None),
+    )
+    .unwrap();
+
+    // The pause flag pushed by the factory is used instead of querying the factory
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::SetPaused { paused: true },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("factory", &[]),
+        ExecuteMsg::SetPaused { paused: true },
+    )
+    .unwrap();
+    let health = pair_health(deps.as_ref());
+    assert!(health.circuit_breaker_active);
+    assert_eq!(health.health_score, 40);
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("factory", &[]),
+        ExecuteMsg::SetPaused { paused: false },
+    )
+    .unwrap();
+    assert!(!pair_health(deps.as_ref()).circuit_breaker_active);
+
+    PAUSED.save(deps.as_mut().storage, &true).unwrap();
+    let health = pair_health(deps.as_ref());
+    assert_eq!(health.price_deviation_from_oracle, None);
+    assert!(health.paused);
+    assert_eq!(health.health_score, 0);
+}
+
+#[test]
+fn price_history() {
+    let offer_amount = Uint128::new(1_000000);
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(30000_000000) + offer_amount,
+    }]);
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("liquidity0000"),
+            &[(
+                &String::from(MOCK_CONTRACT_ADDR),
+                &Uint128::new(20000_000000),
+            )],
+        ),
+        (
+            &String::from("asset0000"),
+            &[(
+                &String::from(MOCK_CONTRACT_ADDR),
+                &Uint128::new(20000_000000),
+            )],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked("asset0000"),
+            },
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let price_history = |deps: Deps, limit: u32| -> Vec<PriceSnapshot> {
+        from_json(&query(deps, mock_env(), QueryMsg::PriceHistory { limit }).unwrap()).unwrap()
+    };
+    let price_at = |deps: Deps, timestamp: u64| -> Option<PriceSnapshot> {
+        from_json(&query(deps, mock_env(), QueryMsg::PriceAt { timestamp }).unwrap()).unwrap()
+    };
+    assert_eq!(price_history(deps.as_ref(), 10), vec![]);
+    assert_eq!(price_at(deps.as_ref(), 1000), None);
+
+    let swap = |deps: DepsMut, time: u64| -> Uint128 {
+        let res = execute(
+            deps,
+            mock_env_with_block_time(time),
+            mock_info(
+                "addr0000",
+                &[Coin {
+                    denom: "uusd".to_string(),
+                    amount: offer_amount,
+                }],
+            ),
+            ExecuteMsg::Swap {
+                offer_asset: native_asset_info("uusd".to_string()).with_balance(offer_amount),
+                ask_asset_info: None,
+                belief_price: None,
+                max_spread: Some(Decimal::percent(50)),
+                to: None,
+            },
+        )
+        .unwrap();
+        let return_amount = res
+            .attributes
+            .iter()
+            .find(|attr| attr.key == "return_amount")
+            .unwrap();
+        return_amount.value.parse().unwrap()
+    };
+
+    let first_return = swap(deps.as_mut(), 1000);
+    let history = price_history(deps.as_ref(), 10);
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].timestamp, 1000);
+    assert_eq!(history[0].volume, first_return);
+
+    // The next snapshot is only taken once the snapshot interval passed
+    let second_return = swap(deps.as_mut(), 1000 + SNAPSHOT_INTERVAL_SECS - 1);
+    assert_eq!(price_history(deps.as_ref(), 10).len(), 1);
+    let third_return = swap(deps.as_mut(), 1000 + SNAPSHOT_INTERVAL_SECS);
+    let history = price_history(deps.as_ref(), 10);
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].timestamp, 1000 + SNAPSHOT_INTERVAL_SECS);
+    assert_eq!(history[0].volume, second_return + third_return);
+    assert_eq!(history[1].timestamp, 1000);
+    assert_eq!(price_history(deps.as_ref(), 1), history[..1]);
+
+    // Once the ring buffer is full the oldest snapshots are overwritten
+    for i in 0..300u32 {
+        let slot = i % PRICE_RING_SIZE;
+        PRICE_RING
+            .save(
+                deps.as_mut().storage,
+                slot,
+                &PriceSnapshot {
+                    price: Decimal::from_ratio(i + 1, 1u8),
+                    timestamp: i as u64 * 3600,
+                    volume: Uint128::zero(),
+                },
+            )
+            .unwrap();
+        RING_HEAD.save(deps.as_mut().storage, &slot).unwrap();
+    }
+
+    let history = price_history(deps.as_ref(), 1000);
+    assert_eq!(history.len(), PRICE_RING_SIZE as usize);
+    assert_eq!(history[0].timestamp, 299 * 3600);
+    assert_eq!(history[PRICE_RING_SIZE as usize - 1].timestamp, 44 * 3600);
+    assert!(history
+        .windows(2)
+        .all(|pair| pair[0].timestamp > pair[1].timestamp));
+
+    let price_at_time = |timestamp: u64| price_at(deps.as_ref(), timestamp).unwrap().timestamp;
+    assert_eq!(price_at_time(0), 44 * 3600);
+    assert_eq!(price_at_time(100 * 3600), 100 * 3600);
+    assert_eq!(price_at_time(100 * 3600 + 1000), 100 * 3600);
+    // Equally close snapshots resolve to the older one
+    assert_eq!(price_at_time(100 * 3600 + 1800), 100 * 3600);
+    assert_eq!(price_at_time(100 * 3600 + 1801), 101 * 3600);
+    assert_eq!(price_at_time(u64::MAX), 299 * 3600);
+}
+
+#[test]
+fn pair_fees_24h() {
+    let offer_amount = Uint128::new(1_000000);
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(30000_000000) + offer_amount,
+    }]);
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("liquidity0000"),
+            &[(
+                &String::from(MOCK_CONTRACT_ADDR),
+                &Uint128::new(20000_000000),
+            )],
+        ),
+        (
+            &String::from("asset0000"),
+            &[(
+                &String::from(MOCK_CONTRACT_ADDR),
+                &Uint128::new(20000_000000),
+            )],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            native_asset_info("uusd".to_string()),
+            token_asset_info(Addr::unchecked("asset0000")),
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let pair_fees = |deps: Deps, time: u64| -> PairFees24hResponse {
+        from_json(
+            &query(
+                deps,
+                mock_env_with_block_time(time),
+                QueryMsg::PairFees24h {},
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    // Returns the LP fee of the swap charged in asset0000
+    let swap = |deps: DepsMut, time: u64| -> Uint128 {
+        let res = execute(
+            deps,
+            mock_env_with_block_time(time),
+            mock_info("addr0000", &[coin(offer_amount.u128(), "uusd")]),
+            ExecuteMsg::Swap {
+                offer_asset: native_asset_info("uusd".to_string()).with_balance(offer_amount),
+                ask_asset_info: None,
+                belief_price: None,
+                max_spread: Some(Decimal::percent(50)),
+                to: None,
+            },
+        )
+        .unwrap();
+        let attr_amount = |key: &str| -> Uint128 {
+            res.attributes
+                .iter()
+                .find(|attribute| attribute.key == key)
+                .unwrap()
+                .value
+                .parse()
+                .unwrap()
+        };
+        attr_amount("commission_amount") - attr_amount("maker_fee_amount")
+    };
+    let fees = |fee: Uint128| {
+        vec![
+            native_asset_info("uusd".to_string()).with_balance(0u8),
+            token_asset_info(Addr::unchecked("asset0000")).with_balance(fee),
+        ]
+    };
+
+    assert_eq!(
+        pair_fees(deps.as_ref(), 1000),
+        PairFees24hResponse {
+            fees: fees(Uint128::zero()),
+            period_start: 0,
+            swap_count: 0,
+        }
+    );
+
+    let first_fee = swap(deps.as_mut(), 1000);
+    let second_fee = swap(deps.as_mut(), 50000);
+    assert!(!first_fee.is_zero());
+    assert_eq!(
+        pair_fees(deps.as_ref(), 50000),
+        PairFees24hResponse {
+            fees: fees(first_fee + second_fee),
+            period_start: 0,
+            swap_count: 2,
+        }
+    );
+
+    // The first swap expires before the next swap removes it
+    assert_eq!(
+        pair_fees(deps.as_ref(), 1000 + FEE_WINDOW_SECS + 1),
+        PairFees24hResponse {
+            fees: fees(second_fee),
+            period_start: 1001,
+            swap_count: 1,
+        }
+    );
+    let third_fee = swap(deps.as_mut(), 1000 + FEE_WINDOW_SECS + 1);
+    assert!(!FEE_RING_24H.has(deps.as_ref().storage, 0));
+    assert_eq!(
+        pair_fees(deps.as_ref(), 1000 + FEE_WINDOW_SECS + 1),
+        PairFees24hResponse {
+            fees: fees(second_fee + third_fee),
+            period_start: 1001,
+            swap_count: 2,
+        }
+    );
+}
+
+#[test]
+fn arbitrage_opportunity() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(30000_000000),
+    }]);
+    deps.querier.with_token_balances(&[(
+        &String::from("asset0000"),
+        &[(
+            &String::from(MOCK_CONTRACT_ADDR),
+            &Uint128::new(20000_000000),
+        )],
+    )]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked("asset0000"),
+            },
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let arbitrage_opportunity = |deps: Deps, reference_price: Decimal| {
+        query(
+            deps,
+            mock_env(),
+            QueryMsg::ArbitrageOpportunity { reference_price },
+        )
+        .map(|res| from_json::<ArbitrageOpportunityResponse>(&res).unwrap())
+    };
+    let uusd = native_asset_info("uusd".to_string());
+    let token = token_asset_info(Addr::unchecked("asset0000"));
+    let fee_rate = Decimal::permille(3);
+
+    let err = arbitrage_opportunity(deps.as_ref(), Decimal::zero()).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("Reference price must be positive")
+    );
+
+    // The pool price of 1.5 is lower than the reference price, so the token is bought.
+    // sqrt(30000_000000 * 20000_000000 * 2) = 34641_016151
+    let res = arbitrage_opportunity(deps.as_ref(), Decimal::from_ratio(2u8, 1u8)).unwrap();
+    let offer_amount = Uint128::new(4641_016151);
+    let (return_amount, _, _) = compute_swap(
+        Uint128::new(30000_000000),
+        Uint128::new(20000_000000),
+        offer_amount,
+        fee_rate,
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        ArbitrageOpportunityResponse {
+            deviation_bps: -2500,
+            optimal_offer: uusd.with_balance(offer_amount),
+            expected_profit: uusd.with_balance(return_amount * Uint128::new(2) - offer_amount),
+            direction: TradeDirection::ZeroForOne,
+        }
+    );
+
+    // The pool price is higher than the reference price, so the token is sold.
+    // sqrt(30000_000000 * 20000_000000 / 1) = 24494_897427
+    let res = arbitrage_opportunity(deps.as_ref(), Decimal::one()).unwrap();
+    let offer_amount = Uint128::new(4494_897427);
+    let (return_amount, _, _) = compute_swap(
+        Uint128::new(20000_000000),
+        Uint128::new(30000_000000),
+        offer_amount,
+        fee_rate,
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        ArbitrageOpportunityResponse {
+            deviation_bps: 5000,
+            optimal_offer: token.with_balance(offer_amount),
+            expected_profit: token.with_balance(return_amount - offer_amount),
+            direction: TradeDirection::OneForZero,
+        }
+    );
+
+    // The fees exceed the gain of a tiny price difference
+    let res =
+        arbitrage_opportunity(deps.as_ref(), Decimal::from_ratio(15001u16, 10000u16)).unwrap();
+    assert_eq!(res.deviation_bps, 0);
+    assert_eq!(res.direction, TradeDirection::ZeroForOne);
+    assert_eq!(res.expected_profit, uusd.with_balance(0u8));
+}
+
+#[test]
+fn custom_hook() {
+    let offer_amount = Uint128::new(1_000000);
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(30000_000000) + offer_amount,
+    }]);
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("liquidity0000"),
+            &[(
+                &String::from(MOCK_CONTRACT_ADDR),
+                &Uint128::new(20000_000000),
+            )],
+        ),
+        (
+            &String::from("asset0000"),
+            &[(
+                &String::from(MOCK_CONTRACT_ADDR),
+                &Uint128::new(20000_000000),
+            )],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked("asset0000"),
+            },
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let set_hook_msg = ExecuteMsg::SetCustomHook {
+        config: Some(CustomHookConfig {
+            contract: Addr::unchecked("hook0000"),
+            gas_limit: 200_000,
+            hook_type: HookType::AfterSwap,
+        }),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        set_hook_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("factory", &[]),
+        set_hook_msg,
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(
+            "addr0000",
+            &[Coin {
+                denom: "uusd".to_string(),
+                amount: offer_amount,
+            }],
+        ),
+        ExecuteMsg::Swap {
+            offer_asset: native_asset_info("uusd".to_string()).with_balance(offer_amount),
+            ask_asset_info: None,
+            belief_price: None,
+            max_spread: Some(Decimal::percent(50)),
+            to: None,
+        },
+    )
+    .unwrap();
+    let return_amount: Uint128 = res
+        .attributes
+        .iter()
+        .find(|attr| attr.key == "return_amount")
+        .unwrap()
+        .value
+        .parse()
+        .unwrap();
+
+    // The hook is executed after the swap invariant is verified and its failures are ignored
+    let hook_msg = res.messages.last().unwrap();
+    assert_eq!(res.messages[res.messages.len() - 2].id, 2);
+    assert_eq!(
+        hook_msg,
+        &SubMsg {
+            msg: WasmMsg::Execute {
+                contract_addr: "hook0000".to_string(),
+                msg: to_json_binary(&CustomHookMsg::AfterSwap {
+                    sender: Addr::unchecked("addr0000"),
+                    receiver: Addr::unchecked("addr0000"),
+                    offer_asset: native_asset_info("uusd".to_string()).with_balance(offer_amount),
+                    return_asset: token_asset_info(Addr::unchecked("asset0000"))
+                        .with_balance(return_amount),
+                })
+                .unwrap(),
+                funds: vec![],
+            }
+            .into(),
+            id: 3,
+            gas_limit: Some(200_000),
+            reply_on: ReplyOn::Error,
+        }
+    );
+
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: 3,
+            result: SubMsgResult::Err("out of gas".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "custom_hook_failed"),
+            attr("error", "out of gas")
+        ]
+    );
+
+    // The hook isn't notified about withdrawals
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("liquidity0000", &[]),
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: String::from("addr0000"),
+            msg: to_json_binary(&Cw20HookMsg::WithdrawLiquidity { assets: vec![] }).unwrap(),
+            amount: Uint128::new(100),
+        }),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 3);
+
+    // Removing the hook stops the notifications
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("factory", &[]),
+        ExecuteMsg::SetCustomHook { config: None },
+    )
+    .unwrap();
+    assert_eq!(
+        CONFIG.load(deps.as_ref().storage).unwrap().custom_hook,
+        None
+    );
+}
+
+#[test]
+fn config_hash() {
+    let offer_amount = Uint128::new(1_000000);
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(30000_000000) + offer_amount,
+    }]);
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("liquidity0000"),
+            &[(
+                &String::from(MOCK_CONTRACT_ADDR),
+                &Uint128::new(20000_000000),
+            )],
+        ),
+        (
+            &String::from("asset0000"),
+            &[(
+                &String::from(MOCK_CONTRACT_ADDR),
+                &Uint128::new(20000_000000),
+            )],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            native_asset_info("uusd".to_string()),
+            token_asset_info(Addr::unchecked("asset0000")),
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let config_hash = |deps: Deps| -> [u8; 32] {
+        from_json(query(deps, mock_env(), QueryMsg::ConfigHash {}).unwrap()).unwrap()
+    };
+    let verify_config = |deps: Deps, expected_hash: [u8; 32]| -> bool {
+        from_json(query(deps, mock_env(), QueryMsg::VerifyConfig { expected_hash }).unwrap())
+            .unwrap()
+    };
+
+    // The hash covers the LP token set after instantiation
+    let initial_hash = config_hash(deps.as_ref());
+    assert_eq!(
+        initial_hash,
+        CONFIG.load(deps.as_ref().storage).unwrap().hash().unwrap()
+    );
+    assert!(verify_config(deps.as_ref(), initial_hash));
+
+    // Swaps update the price accumulators only
+    let env = mock_env_with_block_time(mock_env().block.time.seconds() + 1000);
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info(
+            "addr0000",
+            &[Coin {
+                denom: "uusd".to_string(),
+                amount: offer_amount,
+            }],
+        ),
+        ExecuteMsg::Swap {
+            offer_asset: native_asset_info("uusd".to_string()).with_balance(offer_amount),
+            ask_asset_info: None,
+            belief_price: None,
+            max_spread: Some(Decimal::percent(50)),
+            to: None,
+        },
+    )
+    .unwrap();
+    assert_ne!(
+        CONFIG
+            .load(deps.as_ref().storage)
+            .unwrap()
+            .price0_cumulative_last,
+        Uint128::zero()
+    );
+    assert_eq!(config_hash(deps.as_ref()), initial_hash);
+    assert!(verify_config(deps.as_ref(), initial_hash));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("factory", &[]),
+        ExecuteMsg::UpdateConfig {
+            params: to_json_binary(&XYKPoolUpdateParams::UpdateMaxSpread {
+                max_spread: Some(Decimal::percent(10)),
+            })
+            .unwrap(),
+        },
+    )
+    .unwrap();
+    let updated_hash = config_hash(deps.as_ref());
+    assert_ne!(updated_hash, initial_hash);
+    assert!(verify_config(deps.as_ref(), updated_hash));
+    assert!(!verify_config(deps.as_ref(), initial_hash));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("factory", &[]),
+        ExecuteMsg::UpdateConfig {
+            params: to_json_binary(&XYKPoolUpdateParams::UpdateLpCooldown {
+                lp_cooldown_secs: Some(50),
+            })
+            .unwrap(),
+        },
+    )
+    .unwrap();
+    let cooldown_hash = config_hash(deps.as_ref());
+    assert_ne!(cooldown_hash, updated_hash);
+    assert!(verify_config(deps.as_ref(), cooldown_hash));
+
+    // The factory can't forward params other than the pool tuning ones
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("factory", &[]),
+        ExecuteMsg::UpdateConfig {
+            params: to_json_binary(&XYKPoolUpdateParams::UpdateComplianceMode {
+                compliance_mode: true,
+            })
+            .unwrap(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::ParamsNotForwardable {});
+    assert_eq!(config_hash(deps.as_ref()), cooldown_hash);
+
+    // Config changes bypassing the config updates aren't reflected in the stored hash
+    let mut config = CONFIG.load(deps.as_ref().storage).unwrap();
+    config.lp_cooldown_secs = Some(100);
+    CONFIG.save(deps.as_mut().storage, &config).unwrap();
+    assert_eq!(config_hash(deps.as_ref()), cooldown_hash);
+    assert!(!verify_config(deps.as_ref(), cooldown_hash));
+}
+
+#[test]
+fn unexpected_funds() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(2_000000),
+    }]);
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("liquidity0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(1_000000))],
+        ),
+        (
+            &String::from("asset0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(1_000000))],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            native_asset_info("uusd".to_string()),
+            token_asset_info(Addr::unchecked("asset0000")),
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let funds = [coin(1_000000, "uusd"), coin(1_000000, "uluna")];
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &funds),
+        ExecuteMsg::Swap {
+            offer_asset: native_asset_info("uusd".to_string()).with_balance(1_000000u128),
+            ask_asset_info: None,
+            belief_price: None,
+            max_spread: None,
+            to: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::UnexpectedFunds {
+            denom: "uluna".to_string()
+        }
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &funds),
+        ExecuteMsg::ProvideLiquidity {
+            assets: vec![
+                native_asset_info("uusd".to_string()).with_balance(1_000000u128),
+                token_asset_info(Addr::unchecked("asset0000")).with_balance(1_000000u128),
+            ],
+            slippage_tolerance: None,
+            auto_stake: None,
+            receiver: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::UnexpectedFunds {
+            denom: "uluna".to_string()
+        }
+    );
+}
+
+#[test]
+fn min_liquidity_threshold() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(2_000000),
+    }]);
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("liquidity0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(1_000000))],
+        ),
+        (
+            &String::from("asset0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(1_000000))],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            native_asset_info("uusd".to_string()),
+            token_asset_info(Addr::unchecked("asset0000")),
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+    assert_eq!(
+        CONFIG
+            .load(deps.as_ref().storage)
+            .unwrap()
+            .min_provide_amount,
+        [Uint128::zero(); 2]
+    );
+
+    let update_threshold = ExecuteMsg::UpdateMinLiquidityThreshold {
+        min_amounts: [Uint128::new(1_000000), Uint128::new(500000)],
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("random", &[]),
+        update_threshold.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("pair_admin", &[]),
+        update_threshold,
+    )
+    .unwrap();
+
+    let provide_liquidity = |token_amount: u128| ExecuteMsg::ProvideLiquidity {
+        assets: vec![
+            native_asset_info("uusd".to_string()).with_balance(1_000000u128),
+            token_asset_info(Addr::unchecked("asset0000")).with_balance(token_amount),
+        ],
+        slippage_tolerance: None,
+        auto_stake: None,
+        receiver: None,
+    };
+    let funds = [coin(1_000000, "uusd")];
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &funds),
+        provide_liquidity(100000),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::MinLiquidityThresholdNotMet {
+            required: Uint128::new(500000),
+            provided: Uint128::new(100000),
+        }
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &funds),
+        provide_liquidity(1_000000),
+    )
+    .unwrap();
+}
+
+#[test]
+fn price_alerts() {
+    let asset_pool_amount = Uint128::new(20000000000u128);
+    let collateral_pool_amount = Uint128::new(30000000000u128);
+    let offer_amount = Uint128::new(1500000000u128);
+
+    let mut deps = mock_dependencies(&[coin(
+        collateral_pool_amount.u128() + offer_amount.u128(),
+        "uusd",
+    )]);
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("liquidity0000"),
+            &[(
+                &String::from(MOCK_CONTRACT_ADDR),
+                &Uint128::new(30000000000),
+            )],
+        ),
+        (
+            &String::from("asset0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &asset_pool_amount)],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            native_asset_info("uusd".to_string()),
+            token_asset_info(Addr::unchecked("asset0000")),
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let set_alert_msg = |alert_threshold_bps: u16| ExecuteMsg::SetPriceAlertThreshold {
+        config: PriceAlertConfig {
+            base_price: Decimal::from_ratio(2u8, 3u8),
+            alert_threshold_bps,
+            alert_recipient: Some(Addr::unchecked("market_maker")),
+        },
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("random", &[]),
+        set_alert_msg(1000),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("pair_admin", &[]),
+        ExecuteMsg::SetPriceAlertThreshold {
+            config: PriceAlertConfig {
+                base_price: Decimal::zero(),
+                alert_threshold_bps: 1000,
+                alert_recipient: None,
+            },
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::ZeroBasePrice {});
+
+    let swap = |deps: DepsMut| {
+        execute(
+            deps,
+            mock_env_with_block_time(1000),
+            mock_info("addr0000", &[coin(offer_amount.u128(), "uusd")]),
+            ExecuteMsg::Swap {
+                offer_asset: native_asset_info("uusd".to_string()).with_balance(offer_amount),
+                ask_asset_info: None,
+                belief_price: None,
+                max_spread: Some(Decimal::percent(50)),
+                to: None,
+            },
+        )
+        .unwrap()
+    };
+
+    // The swap moves the price by more than 9%, which is below the threshold
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("pair_admin", &[]),
+        set_alert_msg(1000),
+    )
+    .unwrap();
+    let res = swap(deps.as_mut());
+    assert!(res.events.is_empty());
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("pair_admin", &[]),
+        set_alert_msg(500),
+    )
+    .unwrap();
+    let res = swap(deps.as_mut());
+    let new_price = PRICE_ALERT_CONFIG
+        .load(deps.as_ref().storage)
+        .unwrap()
+        .base_price;
+    assert!(new_price < Decimal::from_ratio(2u8, 3u8));
+    let deviation_bps = Uint128::new(10000)
+        * (Decimal::from_ratio(2u8, 3u8) - new_price)
+            .checked_div(Decimal::from_ratio(2u8, 3u8))
+            .unwrap();
+    assert!(deviation_bps > Uint128::new(500));
+    assert_eq!(
+        res.events,
+        vec![Event::new("price_alert").add_attributes([
+            attr("deviation_bps", deviation_bps),
+            attr("old_price", Decimal::from_ratio(2u8, 3u8).to_string()),
+            attr("new_price", new_price.to_string()),
+        ])]
+    );
+    assert_eq!(
+        res.messages.last().unwrap(),
+        &SubMsg::reply_on_error(
+            WasmMsg::Execute {
+                contract_addr: "market_maker".to_string(),
+                msg: to_json_binary(&PriceAlertMsg::Alert {
+                    pair: Addr::unchecked(MOCK_CONTRACT_ADDR),
+                    deviation_bps: deviation_bps.u128() as u64,
+                    old_price: Decimal::from_ratio(2u8, 3u8),
+                    new_price,
+                })
+                .unwrap(),
+                funds: vec![],
+            },
+            4
+        )
+    );
+
+    // The mocked reserves don't change, so the next swap results in the new base price
+    let res = swap(deps.as_mut());
+    assert!(res.events.is_empty());
+}
+
+#[test]
+fn fee_rebates() {
+    let asset_pool_amount = Uint128::new(20000000000u128);
+    let collateral_pool_amount = Uint128::new(30000000000u128);
+    let offer_amount = Uint128::new(1500000000u128);
+
+    let mut deps = mock_dependencies(&[coin(
+        collateral_pool_amount.u128() + offer_amount.u128(),
+        "uusd",
+    )]);
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("liquidity0000"),
+            &[(
+                &String::from(MOCK_CONTRACT_ADDR),
+                &Uint128::new(30000000000),
+            )],
+        ),
+        (
+            &String::from("asset0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &asset_pool_amount)],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            native_asset_info("uusd".to_string()),
+            token_asset_info(Addr::unchecked("asset0000")),
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let set_fee_rebate = |deps: DepsMut, sender: &str, rebate_bps: u16, expires_at: u64| {
+        execute(
+            deps,
+            mock_env_with_block_time(1000),
+            mock_info(sender, &[]),
+            ExecuteMsg::SetFeeRebate {
+                rebate_bps,
+                expires_at: Some(expires_at),
+            },
+        )
+    };
+    let err = set_fee_rebate(deps.as_mut(), "random", 5000, 2000).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    // The Maker receives 16.6% of the commission
+    let err = set_fee_rebate(deps.as_mut(), "pair_admin", 8341, 2000).unwrap_err();
+    assert_eq!(err, ContractError::FeeRebateTooHigh { max_bps: 8340 });
+    let err = set_fee_rebate(deps.as_mut(), "pair_admin", 5000, 1000).unwrap_err();
+    assert_eq!(err, ContractError::FeeRebateExpired { expires_at: 1000 });
+    set_fee_rebate(deps.as_mut(), "pair_admin", 5000, 2000).unwrap();
+
+    let swap = |deps: DepsMut, time: u64| {
+        execute(
+            deps,
+            mock_env_with_block_time(time),
+            mock_info("addr0000", &[coin(offer_amount.u128(), "uusd")]),
+            ExecuteMsg::Swap {
+                offer_asset: native_asset_info("uusd".to_string()).with_balance(offer_amount),
+                ask_asset_info: None,
+                belief_price: None,
+                max_spread: Some(Decimal::percent(50)),
+                to: None,
+            },
+        )
+        .unwrap()
+    };
+    let attribute = |res: &Response, key: &str| -> Uint128 {
+        res.attributes
+            .iter()
+            .find(|attribute| attribute.key == key)
+            .unwrap()
+            .value
+            .parse()
+            .unwrap()
+    };
+
+    let res = swap(deps.as_mut(), 2000);
+    let commission_amount = attribute(&res, "commission_amount");
+    let fee_rebate = attribute(&res, "fee_rebate");
+    assert_eq!(fee_rebate, commission_amount.multiply_ratio(1u8, 2u8));
+    // The rebate is sent to the receiver along with the return amount
+    assert_eq!(
+        res.messages[0].msg,
+        WasmMsg::Execute {
+            contract_addr: String::from("asset0000"),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: String::from("addr0000"),
+                amount: attribute(&res, "return_amount") + fee_rebate,
+            })
+            .unwrap(),
+            funds: vec![],
+        }
+        .into()
+    );
+
+    // The expired rebate is no longer returned
+    let res = swap(deps.as_mut(), 2001);
+    assert_eq!(attribute(&res, "fee_rebate"), Uint128::zero());
+}
+
+fn mock_env_with_block_time(time: u64) -> Env {
+    let mut env = mock_env();
+    env.block = BlockInfo {
+        height: 1,
+        time: Timestamp::from_seconds(time),
+        chain_id: "columbus".to_string(),
+    };
+    env
+}
+
+#[test]
+fn compute_swap_rounding() {
+    let offer_pool = Uint128::from(5_000_000_000_000_u128);
+    let ask_pool = Uint128::from(1_000_000_000_u128);
     let return_amount = Uint128::from(0_u128);
     let spread_amount = Uint128::from(0_u128);
     let commission_amount = Uint128::from(0_u128);
     let offer_amount = Uint128::from(1_u128);
 
     assert_eq!(
-        compute_swap(offer_pool, ask_pool, offer_amount, Decimal::zero()),
-        Ok((return_amount, spread_amount, commission_amount))
+        compute_swap(offer_pool, ask_pool, offer_amount, Decimal::zero()),
+        Ok((return_amount, spread_amount, commission_amount))
+    );
+}
+
+proptest! {
+    #[test]
+    fn compute_swap_overflow_test(
+        offer_pool in 1_000_000..9_000_000_000_000_000_000u128,
+        ask_pool in 1_000_000..9_000_000_000_000_000_000u128,
+        offer_amount in 1..100_000_000000u128,
+    ) {
+
+        let offer_pool = Uint128::from(offer_pool);
+        let ask_pool = Uint128::from(ask_pool);
+        let offer_amount = Uint128::from(offer_amount);
+        let commission_amount = Decimal::zero();
+
+        // Make sure there are no overflows
+        compute_swap(
+            offer_pool,
+            ask_pool,
+            offer_amount,
+            commission_amount,
+        ).unwrap();
+    }
+}
+
+#[test]
+fn ensure_useful_error_messages_are_given_on_swaps() {
+    const OFFER: Uint128 = Uint128::new(1_000_000_000000);
+    const ASK: Uint128 = Uint128::new(1_000_000_000000);
+    const AMOUNT: Uint128 = Uint128::new(1_000000);
+    const ZERO: Uint128 = Uint128::zero();
+    const DZERO: Decimal = Decimal::zero();
+
+    // Computing ask
+    assert_eq!(
+        compute_swap(ZERO, ZERO, ZERO, DZERO).unwrap_err(),
+        StdError::generic_err("One of the pools is empty")
+    );
+    assert_eq!(
+        compute_swap(ZERO, ZERO, AMOUNT, DZERO).unwrap_err(),
+        StdError::generic_err("One of the pools is empty")
+    );
+    assert_eq!(
+        compute_swap(ZERO, ASK, ZERO, DZERO).unwrap_err(),
+        StdError::generic_err("One of the pools is empty")
+    );
+    assert_eq!(
+        compute_swap(ZERO, ASK, AMOUNT, DZERO).unwrap_err(),
+        StdError::generic_err("One of the pools is empty")
+    );
+    assert_eq!(
+        compute_swap(OFFER, ZERO, ZERO, DZERO).unwrap_err(),
+        StdError::generic_err("One of the pools is empty")
+    );
+    assert_eq!(
+        compute_swap(OFFER, ZERO, AMOUNT, DZERO).unwrap_err(),
+        StdError::generic_err("One of the pools is empty")
+    );
+    assert_eq!(
+        compute_swap(OFFER, ASK, ZERO, DZERO).unwrap_err(),
+        StdError::generic_err("Swap amount must not be zero")
+    );
+    compute_swap(OFFER, ASK, AMOUNT, DZERO).unwrap();
+
+    // Computing offer
+    assert_eq!(
+        compute_offer_amount(ZERO, ZERO, ZERO, DZERO).unwrap_err(),
+        StdError::generic_err("One of the pools is empty")
+    );
+    assert_eq!(
+        compute_offer_amount(ZERO, ZERO, AMOUNT, DZERO).unwrap_err(),
+        StdError::generic_err("One of the pools is empty")
+    );
+    assert_eq!(
+        compute_offer_amount(ZERO, ASK, ZERO, DZERO).unwrap_err(),
+        StdError::generic_err("One of the pools is empty")
+    );
+    assert_eq!(
+        compute_offer_amount(ZERO, ASK, AMOUNT, DZERO).unwrap_err(),
+        StdError::generic_err("One of the pools is empty")
+    );
+    assert_eq!(
+        compute_offer_amount(OFFER, ZERO, ZERO, DZERO).unwrap_err(),
+        StdError::generic_err("One of the pools is empty")
+    );
+    assert_eq!(
+        compute_offer_amount(OFFER, ZERO, AMOUNT, DZERO).unwrap_err(),
+        StdError::generic_err("One of the pools is empty")
+    );
+    assert_eq!(
+        compute_offer_amount(OFFER, ASK, ZERO, DZERO).unwrap_err(),
+        StdError::generic_err("Swap amount must not be zero")
+    );
+    compute_offer_amount(OFFER, ASK, AMOUNT, DZERO).unwrap();
+}
+
+#[test]
+fn tvl_caps() {
+    let mut deps = mock_dependencies(&[coin(2000_000000, "uusd")]);
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("asset0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::zero())],
+        ),
+        (
+            &String::from("liquidity0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::zero())],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            native_asset_info("uusd".to_string()),
+            token_asset_info(Addr::unchecked("asset0000")),
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let set_tvl_cap = |deps: DepsMut, sender: &str, caps: [Option<Uint128>; 2]| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info(sender, &[]),
+            ExecuteMsg::SetTvlCap { caps },
+        )
+    };
+    let err = set_tvl_cap(deps.as_mut(), "pair_admin", [None, None]).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let res = set_tvl_cap(deps.as_mut(), "owner", [None, Some(1500_000000u128.into())]).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "set_tvl_cap"),
+            attr("max_tvl_asset0", "none"),
+            attr("max_tvl_asset1", "1500000000"),
+        ]
+    );
+
+    let provide = |deps: DepsMut, amount: u128| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("addr0000", &[coin(amount, "uusd")]),
+            ExecuteMsg::ProvideLiquidity {
+                assets: vec![
+                    native_asset_info("uusd".to_string()).with_balance(amount),
+                    token_asset_info(Addr::unchecked("asset0000")).with_balance(amount),
+                ],
+                slippage_tolerance: None,
+                auto_stake: None,
+                receiver: None,
+            },
+        )
+    };
+    // Only the second asset is capped
+    let err = provide(deps.as_mut(), 2000_000000).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::TvlCapExceeded {
+            asset: token_asset_info(Addr::unchecked("asset0000")),
+            cap: Uint128::new(1500_000000),
+            current: Uint128::new(2000_000000),
+        }
+    );
+
+    deps.querier.with_balance(&[(
+        &String::from(MOCK_CONTRACT_ADDR),
+        &[coin(1500_000000, "uusd")],
+    )]);
+    provide(deps.as_mut(), 1500_000000).unwrap();
+
+    // Removed caps don't limit provisions
+    set_tvl_cap(deps.as_mut(), "owner", [None, None]).unwrap();
+    deps.querier.with_balance(&[(
+        &String::from(MOCK_CONTRACT_ADDR),
+        &[coin(2000_000000, "uusd")],
+    )]);
+    provide(deps.as_mut(), 2000_000000).unwrap();
+}
+
+#[test]
+fn sender_whitelist() {
+    let mut deps = mock_dependencies(&[coin(1000_000000, "uusd")]);
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("asset0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::zero())],
+        ),
+        (
+            &String::from("liquidity0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::zero())],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            native_asset_info("uusd".to_string()),
+            token_asset_info(Addr::unchecked("asset0000")),
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let provide = |deps: DepsMut| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("addr0000", &[coin(1000_000000, "uusd")]),
+            ExecuteMsg::ProvideLiquidity {
+                assets: vec![
+                    native_asset_info("uusd".to_string()).with_balance(1000_000000u128),
+                    token_asset_info(Addr::unchecked("asset0000")).with_balance(1000_000000u128),
+                ],
+                slippage_tolerance: None,
+                auto_stake: None,
+                receiver: None,
+            },
+        )
+    };
+    let update_whitelist = |deps: DepsMut, sender: &str, address: &str, add: bool| {
+        let addresses = vec![address.to_string()];
+        let msg = if add {
+            ExecuteMsg::AddToWhitelist { addresses }
+        } else {
+            ExecuteMsg::RemoveFromWhitelist { addresses }
+        };
+        execute(deps, mock_env(), mock_info(sender, &[]), msg)
+    };
+
+    // Removing from an open pair keeps it open
+    update_whitelist(deps.as_mut(), "owner", "addr0000", false).unwrap();
+    provide(deps.as_mut()).unwrap();
+
+    let err = update_whitelist(deps.as_mut(), "pair_admin", "addr0001", true).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    update_whitelist(deps.as_mut(), "owner", "addr0001", true).unwrap();
+
+    let not_whitelisted = ContractError::SenderNotWhitelisted {
+        sender: Addr::unchecked("addr0000"),
+    };
+    assert_eq!(provide(deps.as_mut()).unwrap_err(), not_whitelisted);
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[coin(100, "uusd")]),
+        ExecuteMsg::Swap {
+            offer_asset: native_asset_info("uusd".to_string()).with_balance(100u128),
+            ask_asset_info: None,
+            belief_price: None,
+            max_spread: None,
+            to: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, not_whitelisted);
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("liquidity0000", &[]),
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "addr0000".to_string(),
+            amount: Uint128::new(100),
+            msg: to_json_binary(&Cw20HookMsg::WithdrawLiquidity { assets: vec![] }).unwrap(),
+        }),
+    )
+    .unwrap_err();
+    assert_eq!(err, not_whitelisted);
+
+    update_whitelist(deps.as_mut(), "owner", "addr0000", true).unwrap();
+    provide(deps.as_mut()).unwrap();
+
+    // Removing every address doesn't open the pair again
+    update_whitelist(deps.as_mut(), "owner", "addr0000", false).unwrap();
+    update_whitelist(deps.as_mut(), "owner", "addr0001", false).unwrap();
+    assert_eq!(provide(deps.as_mut()).unwrap_err(), not_whitelisted);
+}
+
+#[test]
+fn user_lp_stats() {
+    let mut deps = mock_dependencies(&[coin(10000, "uusd")]);
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("asset0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::zero())],
+        ),
+        (
+            &String::from("liquidity0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::zero())],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            native_asset_info("uusd".to_string()),
+            token_asset_info(Addr::unchecked("asset0000")),
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+    let env = mock_env();
+    instantiate(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[coin(10000, "uusd")]),
+        ExecuteMsg::ProvideLiquidity {
+            assets: vec![
+                native_asset_info("uusd".to_string()).with_balance(10000u16),
+                token_asset_info(Addr::unchecked("asset0000")).with_balance(10000u16),
+            ],
+            slippage_tolerance: None,
+            auto_stake: None,
+            receiver: None,
+        },
+    )
+    .unwrap();
+
+    let set_balances = |deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+                        uusd: u128,
+                        token: u128,
+                        lp_balances: &[(&str, u128)]| {
+        deps.querier
+            .with_balance(&[(&String::from(MOCK_CONTRACT_ADDR), &[coin(uusd, "uusd")])]);
+        let lp_balances: Vec<_> = lp_balances
+            .iter()
+            .map(|(address, balance)| (address.to_string(), Uint128::new(*balance)))
+            .collect();
+        let lp_balances: Vec<_> = lp_balances.iter().map(|(a, b)| (a, b)).collect();
+        deps.querier.with_token_balances(&[
+            (
+                &String::from("asset0000"),
+                &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(token))],
+            ),
+            (&String::from("liquidity0000"), &lp_balances),
+        ]);
+    };
+    let update_holders = |deps: DepsMut, env: Env, addresses: &[&str]| {
+        execute(
+            deps,
+            env,
+            mock_info("liquidity0000", &[]),
+            ExecuteMsg::UpdateLpHolders {
+                addresses: addresses.iter().map(|addr| addr.to_string()).collect(),
+                transfer: None,
+            },
+        )
+        .unwrap();
+    };
+    let user_lp_stats = |deps: Deps, address: &str| -> UserLpStatsResponse {
+        from_json(
+            &query(
+                deps,
+                mock_env(),
+                QueryMsg::UserLpStats {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+
+    let mut later_env = env.clone();
+    later_env.block.time = env.block.time.plus_seconds(100);
+    // The LP token reports the minted balance
+    set_balances(
+        &mut deps,
+        10000,
+        10000,
+        &[("alice", 9000), (MOCK_CONTRACT_ADDR, 1000)],
+    );
+    update_holders(deps.as_mut(), later_env.clone(), &["alice"]);
+
+    FEE_PER_SHARE_CUMULATIVE
+        .save(
+            deps.as_mut().storage,
+            &[Decimal256::percent(10), Decimal256::percent(20)],
+        )
+        .unwrap();
+    // The price of the first asset drops 4 times
+    set_balances(
+        &mut deps,
+        20000,
+        5000,
+        &[("alice", 9000), (MOCK_CONTRACT_ADDR, 1000)],
+    );
+    let stats = user_lp_stats(deps.as_ref(), "alice");
+    assert_eq!(
+        stats,
+        UserLpStatsResponse {
+            lp_balance: Uint128::new(9000),
+            underlying_assets: vec![
+                native_asset_info("uusd".to_string()).with_balance(18000u16),
+                token_asset_info(Addr::unchecked("asset0000")).with_balance(4500u16),
+            ],
+            position_value_in_asset0: Uint128::new(36000),
+            fees_earned: vec![
+                native_asset_info("uusd".to_string()).with_balance(900u16),
+                token_asset_info(Addr::unchecked("asset0000")).with_balance(1800u16),
+            ],
+            // il = 1 - 2 * sqrt(0.25) / 1.25
+            impermanent_loss_bps: 2000,
+            first_provide_time: env.block.time.seconds(),
+            last_activity_time: later_env.block.time.seconds(),
+        }
+    );
+
+    // Alice transfers her LP tokens to Bob, who never provided liquidity
+    set_balances(
+        &mut deps,
+        20000,
+        5000,
+        &[("alice", 0), ("bob", 9000), (MOCK_CONTRACT_ADDR, 1000)],
+    );
+    update_holders(deps.as_mut(), later_env, &["alice", "bob"]);
+    FEE_PER_SHARE_CUMULATIVE
+        .save(
+            deps.as_mut().storage,
+            &[Decimal256::percent(20), Decimal256::percent(20)],
+        )
+        .unwrap();
+
+    let stats = user_lp_stats(deps.as_ref(), "alice");
+    assert_eq!(stats.lp_balance, Uint128::zero());
+    assert_eq!(
+        stats.fees_earned,
+        vec![
+            native_asset_info("uusd".to_string()).with_balance(900u16),
+            token_asset_info(Addr::unchecked("asset0000")).with_balance(1800u16),
+        ]
+    );
+    let stats = user_lp_stats(deps.as_ref(), "bob");
+    assert_eq!(
+        stats.fees_earned,
+        vec![
+            native_asset_info("uusd".to_string()).with_balance(900u16),
+            token_asset_info(Addr::unchecked("asset0000")).with_balance(0u16),
+        ]
+    );
+    assert_eq!(stats.impermanent_loss_bps, 0);
+    assert_eq!(stats.first_provide_time, 0);
+}
+
+#[test]
+fn price_feed() {
+    let mut deps = mock_dependencies(&[coin(30000, "uusd")]);
+    deps.querier.with_token_balances(&[(
+        &String::from("asset0000"),
+        &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(20000))],
+    )]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            native_asset_info("uusd".to_string()),
+            token_asset_info(Addr::unchecked("asset0000")),
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+    let env = mock_env();
+    instantiate(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let price_feed = |deps: Deps, asset: AssetInfo| -> StdResult<PythPriceResponse> {
+        query(deps, mock_env(), QueryMsg::PriceFeed { asset }).map(|res| from_json(&res).unwrap())
+    };
+
+    // No swaps were made, so the confidence interval is zero
+    assert_eq!(
+        price_feed(deps.as_ref(), native_asset_info("uusd".to_string())).unwrap(),
+        PythPriceResponse {
+            price: 666666,
+            conf: 0,
+            expo: PRICE_FEED_EXPO,
+            publish_time: 0,
+        }
+    );
+
+    let today = env.block.time.seconds() / 86400;
+    DAILY_SPREADS
+        .save(deps.as_mut().storage, today, &(Decimal256::percent(3), 2))
+        .unwrap();
+    DAILY_SPREADS
+        .save(
+            deps.as_mut().storage,
+            today - 7,
+            &(Decimal256::percent(50), 1),
+        )
+        .unwrap();
+    let mut config = CONFIG.load(deps.as_ref().storage).unwrap();
+    config.block_time_last = env.block.time.seconds();
+    CONFIG.save(deps.as_mut().storage, &config).unwrap();
+
+    assert_eq!(
+        price_feed(
+            deps.as_ref(),
+            token_asset_info(Addr::unchecked("asset0000"))
+        )
+        .unwrap(),
+        PythPriceResponse {
+            price: 1500000,
+            conf: 22500,
+            expo: PRICE_FEED_EXPO,
+            publish_time: env.block.time.seconds(),
+        }
+    );
+
+    let err = price_feed(deps.as_ref(), native_asset_info("uluna".to_string())).unwrap_err();
+    assert_eq!(err, StdError::generic_err("uluna is not in the pool"));
+
+    deps.querier.with_token_balances(&[(
+        &String::from("asset0000"),
+        &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::zero())],
+    )]);
+    let err = price_feed(
+        deps.as_ref(),
+        token_asset_info(Addr::unchecked("asset0000")),
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("The pool is empty"));
+}
+
+#[test]
+fn remove_liquidity_imbalanced() {
+    let mut deps = mock_dependencies(&[coin(100000, "uusd")]);
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("liquidity0000"),
+            &[
+                (&String::from("addr0000"), &Uint128::new(10000)),
+                (&String::from("addr0001"), &Uint128::new(90000)),
+            ],
+        ),
+        (
+            &String::from("asset0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(100000))],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            native_asset_info("uusd".to_string()),
+            token_asset_info(Addr::unchecked("asset0000")),
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let remove = |min_asset0: u128, preferred_asset: AssetInfo, target_ratio: Decimal| {
+        ExecuteMsg::RemoveLiquidityImbalanced {
+            lp_amount: Uint128::new(10000),
+            min_asset0: Uint128::new(min_asset0),
+            min_asset1: Uint128::zero(),
+            preferred_asset,
+            target_ratio,
+        }
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        remove(
+            0,
+            native_asset_info("uusd".to_string()),
+            Decimal::percent(101),
+        ),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidTargetRatio {});
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        remove(0, native_asset_info("uluna".to_string()), Decimal::zero()),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AssetMismatch {});
+
+    // The withdrawn 10000 tokens are swapped against the 90000/90000 reserves left in the pool
+    // for 9000 uusd minus the 27 uusd commission
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        remove(
+            19000,
+            native_asset_info("uusd".to_string()),
+            Decimal::zero(),
+        ),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::WithdrawMinimumNotMet {
+            asset: native_asset_info("uusd".to_string()),
+            received: Uint128::new(18973),
+            minimum: Uint128::new(19000),
+        }
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        remove(
+            18973,
+            native_asset_info("uusd".to_string()),
+            Decimal::zero(),
+        ),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "liquidity0000".to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: "addr0000".to_string(),
+                    recipient: MOCK_CONTRACT_ADDR.to_string(),
+                    amount: Uint128::new(10000),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "liquidity0000".to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Burn {
+                    amount: Uint128::new(10000),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            SubMsg::new(BankMsg::Send {
+                to_address: "addr0000".to_string(),
+                amount: vec![coin(18973, "uusd")],
+            }),
+            SubMsg::reply_always(
+                BankMsg::Send {
+                    to_address: "fee_address".to_string(),
+                    amount: vec![coin(4, "uusd")],
+                },
+                2,
+            ),
+        ]
+    );
+    assert_eq!(
+        res.attributes[3],
+        attr("refund_assets", "18973uusd, 0asset0000")
+    );
+    // The swap is checked against the reserves left after the withdrawal
+    assert_eq!(
+        PRE_SWAP_INVARIANT.load(deps.as_ref().storage).unwrap(),
+        Uint256::from(90000u128) * Uint256::from(90000u128)
+    );
+
+    // Keeping half of the withdrawn tokens swaps the other 5000
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        remove(
+            0,
+            native_asset_info("uusd".to_string()),
+            Decimal::percent(50),
+        ),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes[3],
+        attr("refund_assets", "14722uusd, 5000asset0000")
+    );
+    assert_eq!(res.attributes[4], attr("swap_amount", "5000"));
+    assert_eq!(res.attributes[5], attr("commission_amount", "14"));
+
+    // A target ratio of one is a balanced withdrawal
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        remove(
+            0,
+            token_asset_info(Addr::unchecked("asset0000")),
+            Decimal::one(),
+        ),
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes[3],
+        attr("refund_assets", "10000uusd, 10000asset0000")
+    );
+    assert_eq!(res.messages.len(), 4);
+
+    // Only the balanced withdrawal works while swaps are paused
+    PAUSED.save(deps.as_mut().storage, &true).unwrap();
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        remove(
+            0,
+            native_asset_info("uusd".to_string()),
+            Decimal::percent(50),
+        ),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::PairPaused {});
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        remove(0, native_asset_info("uusd".to_string()), Decimal::one()),
+    )
+    .unwrap();
+}
+
+#[test]
+fn contract_info() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_token_balances(&[(
+        &String::from("asset0000"),
+        &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::zero())],
+    )]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            native_asset_info("uusd".to_string()),
+            token_asset_info(Addr::unchecked("asset0000")),
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let contract_info = |deps: Deps| -> ContractVersion {
+        from_json(&query(deps, mock_env(), QueryMsg::ContractInfo {}).unwrap()).unwrap()
+    };
+    let current_version = ContractVersion {
+        contract: "astroport-pair".to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    };
+    assert_eq!(contract_info(deps.as_ref()), current_version);
+
+    // A v1.3.1 pair reports the new version once it is migrated
+    set_contract_version(deps.as_mut().storage, "astroport-pair", "1.3.1").unwrap();
+    assert_eq!(contract_info(deps.as_ref()).version, "1.3.1");
+    migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    assert_eq!(contract_info(deps.as_ref()), current_version);
+}
+
+#[test]
+fn swap_limits() {
+    let offer_amount = Uint128::new(1000);
+    let mut deps = mock_dependencies(&[coin(1_000_000 + offer_amount.u128(), "uusd")]);
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("liquidity0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(1_000_000))],
+        ),
+        (
+            &String::from("asset0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(1_000_000))],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            native_asset_info("uusd".to_string()),
+            token_asset_info(Addr::unchecked("asset0000")),
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let update_limits = |deps: DepsMut, sender: &str, max_single: u128, max_daily: u128| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info(sender, &[]),
+            ExecuteMsg::UpdateSwapLimits {
+                max_single: Some(Uint128::new(max_single)),
+                max_daily: Some(Uint128::new(max_daily)),
+            },
+        )
+    };
+    let swap = |deps: DepsMut, block_time: u64| {
+        execute(
+            deps,
+            mock_env_with_block_time(block_time),
+            mock_info("addr0000", &[coin(offer_amount.u128(), "uusd")]),
+            ExecuteMsg::Swap {
+                offer_asset: native_asset_info("uusd".to_string()).with_balance(offer_amount),
+                ask_asset_info: None,
+                belief_price: None,
+                max_spread: Some(Decimal::percent(50)),
+                to: None,
+            },
+        )
+    };
+
+    let err = update_limits(deps.as_mut(), "addr0000", 700, 1500).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let err = update_limits(deps.as_mut(), "owner", 700, 1500).unwrap_err();
+    assert_eq!(err, ContractError::UsdOracleNotSet {});
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::UpdateConfig {
+            params: to_json_binary(&XYKPoolUpdateParams::UpdateUsdOracle {
+                oracle: Some("oracle".to_string()),
+            })
+            .unwrap(),
+        },
+    )
+    .unwrap();
+
+    // The oracle values the offered 1000 uusd at 625 USD
+    let res = update_limits(deps.as_mut(), "owner", 600, 1500).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "update_swap_limits"),
+            attr("max_single_swap_usd", "600"),
+            attr("max_daily_swap_usd", "1500"),
+        ]
+    );
+    assert_eq!(
+        swap(deps.as_mut(), 1000).unwrap_err(),
+        ContractError::SwapSizeExceeded {
+            offer_usd: Uint128::new(625),
+            limit_usd: Uint128::new(600),
+        }
     );
-}
 
-proptest! {
-    #[test]
-    fn compute_swap_overflow_test(
-        offer_pool in 1_000_000..9_000_000_000_000_000_000u128,
-        ask_pool in 1_000_000..9_000_000_000_000_000_000u128,
-        offer_amount in 1..100_000_000000u128,
-    ) {
+    update_limits(deps.as_mut(), "owner", 700, 1500).unwrap();
+    swap(deps.as_mut(), 1000).unwrap();
+    swap(deps.as_mut(), 2000).unwrap();
+    assert_eq!(
+        swap(deps.as_mut(), 3000).unwrap_err(),
+        ContractError::DailySwapLimitExceeded {
+            volume_usd: Uint128::new(1875),
+            limit_usd: Uint128::new(1500),
+        }
+    );
 
-        let offer_pool = Uint128::from(offer_pool);
-        let ask_pool = Uint128::from(ask_pool);
-        let offer_amount = Uint128::from(offer_amount);
-        let commission_amount = Decimal::zero();
+    // The volume is reset on the next day
+    swap(deps.as_mut(), 86400).unwrap();
+    assert_eq!(
+        DAILY_SWAP_VOLUME_USD.load(deps.as_ref().storage).unwrap(),
+        (1, Uint128::new(625))
+    );
 
-        // Make sure there are no overflows
-        compute_swap(
-            offer_pool,
-            ask_pool,
-            offer_amount,
-            commission_amount,
-        ).unwrap();
-    }
+    // Removing the limits stops valuing the swaps
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::UpdateSwapLimits {
+            max_single: None,
+            max_daily: None,
+        },
+    )
+    .unwrap();
+    swap(deps.as_mut(), 86500).unwrap();
+    assert_eq!(
+        DAILY_SWAP_VOLUME_USD.load(deps.as_ref().storage).unwrap(),
+        (1, Uint128::new(625))
+    );
 }
 
 #[test]
-fn ensure_useful_error_messages_are_given_on_swaps() {
-    const OFFER: Uint128 = Uint128::new(1_000_000_000000);
-    const ASK: Uint128 = Uint128::new(1_000_000_000000);
-    const AMOUNT: Uint128 = Uint128::new(1_000000);
-    const ZERO: Uint128 = Uint128::zero();
-    const DZERO: Decimal = Decimal::zero();
+fn blocked_senders() {
+    let offer_amount = Uint128::new(1000);
+    let mut deps = mock_dependencies(&[coin(1_000_000 + offer_amount.u128(), "uusd")]);
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("liquidity0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(1_000_000))],
+        ),
+        (
+            &String::from("asset0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(1_000_000))],
+        ),
+    ]);
 
-    // Computing ask
-    assert_eq!(
-        compute_swap(ZERO, ZERO, ZERO, DZERO).unwrap_err(),
-        StdError::generic_err("One of the pools is empty")
-    );
-    assert_eq!(
-        compute_swap(ZERO, ZERO, AMOUNT, DZERO).unwrap_err(),
-        StdError::generic_err("One of the pools is empty")
-    );
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            native_asset_info("uusd".to_string()),
+            token_asset_info(Addr::unchecked("asset0000")),
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let swap = |deps: DepsMut, sender: &str| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info(sender, &[coin(offer_amount.u128(), "uusd")]),
+            ExecuteMsg::Swap {
+                offer_asset: native_asset_info("uusd".to_string()).with_balance(offer_amount),
+                ask_asset_info: None,
+                belief_price: None,
+                max_spread: Some(Decimal::percent(50)),
+                to: None,
+            },
+        )
+    };
+    let block_sender = ExecuteMsg::BlockSender {
+        address: "addr0000".to_string(),
+        reason: "sanctioned".to_string(),
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        block_sender.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        block_sender,
+    )
+    .unwrap();
     assert_eq!(
-        compute_swap(ZERO, ASK, ZERO, DZERO).unwrap_err(),
-        StdError::generic_err("One of the pools is empty")
+        res.attributes,
+        vec![
+            attr("action", "block_sender"),
+            attr("address", "addr0000"),
+            attr("reason", "sanctioned"),
+        ]
     );
+
     assert_eq!(
-        compute_swap(ZERO, ASK, AMOUNT, DZERO).unwrap_err(),
-        StdError::generic_err("One of the pools is empty")
+        swap(deps.as_mut(), "addr0000").unwrap_err(),
+        ContractError::SenderBlocked {
+            reason: "sanctioned".to_string()
+        }
     );
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("liquidity0000", &[]),
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "addr0000".to_string(),
+            amount: Uint128::new(100),
+            msg: to_json_binary(&Cw20HookMsg::WithdrawLiquidity { assets: vec![] }).unwrap(),
+        }),
+    )
+    .unwrap_err();
     assert_eq!(
-        compute_swap(OFFER, ZERO, ZERO, DZERO).unwrap_err(),
-        StdError::generic_err("One of the pools is empty")
+        err,
+        ContractError::SenderBlocked {
+            reason: "sanctioned".to_string()
+        }
     );
+    swap(deps.as_mut(), "addr0001").unwrap();
+
+    // Operations which don't check the sender are disabled in compliance mode
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::UpdateConfig {
+            params: to_json_binary(&XYKPoolUpdateParams::UpdateComplianceMode {
+                compliance_mode: true,
+            })
+            .unwrap(),
+        },
+    )
+    .unwrap();
+    assert!(CONFIG.load(deps.as_ref().storage).unwrap().compliance_mode);
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::SaveTwapSnapshot {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::ComplianceModeActive {});
+    swap(deps.as_mut(), "addr0001").unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::UnblockSender {
+            address: "addr0000".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::UnblockSender {
+            address: "addr0000".to_string(),
+        },
+    )
+    .unwrap();
+
+    swap(deps.as_mut(), "addr0000").unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0001", &[]),
+        ExecuteMsg::SaveTwapSnapshot {},
+    )
+    .unwrap();
+}
+
+#[test]
+fn rebasing_asset() {
+    let mut deps = mock_dependencies(&[coin(1_000_000, "uusd")]);
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("liquidity0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(1_000_000))],
+        ),
+        (
+            &String::from("asset0000"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(1_000_000))],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            native_asset_info("uusd".to_string()),
+            token_asset_info(Addr::unchecked("asset0000")),
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let update_rebasing_asset = |deps: DepsMut, token: &str| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("owner", &[]),
+            ExecuteMsg::UpdateConfig {
+                params: to_json_binary(&XYKPoolUpdateParams::UpdateRebasingAsset {
+                    config: Some(RebasingAssetConfig {
+                        token: Addr::unchecked(token),
+                        rate_oracle: Addr::unchecked("rate_oracle"),
+                        base_denom: "uatom".to_string(),
+                    }),
+                })
+                .unwrap(),
+            },
+        )
+    };
+
+    let err = update_rebasing_asset(deps.as_mut(), "asset0001").unwrap_err();
     assert_eq!(
-        compute_swap(OFFER, ZERO, AMOUNT, DZERO).unwrap_err(),
-        StdError::generic_err("One of the pools is empty")
+        err,
+        ContractError::InvalidRebasingAsset("asset0001".to_string())
     );
+    let res = update_rebasing_asset(deps.as_mut(), "asset0000").unwrap();
     assert_eq!(
-        compute_swap(OFFER, ASK, ZERO, DZERO).unwrap_err(),
-        StdError::generic_err("Swap amount must not be zero")
+        res.attributes,
+        vec![
+            attr("action", "update_rebasing_asset"),
+            attr("rebasing_asset", "asset0000"),
+        ]
     );
-    compute_swap(OFFER, ASK, AMOUNT, DZERO).unwrap();
 
-    // Computing offer
+    // The rate oracle values one token at 1.25 base units
+    let normalized: NormalizedReservesResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::NormalizedReserves {}).unwrap())
+            .unwrap();
     assert_eq!(
-        compute_offer_amount(ZERO, ZERO, ZERO, DZERO).unwrap_err(),
-        StdError::generic_err("One of the pools is empty")
+        normalized,
+        NormalizedReservesResponse {
+            reserves: vec![
+                native_asset_info("uusd".to_string()).with_balance(1_000_000u128),
+                token_asset_info(Addr::unchecked("asset0000")).with_balance(1_250_000u128),
+            ],
+            rebase_rate: Decimal::percent(125),
+            base_denom: Some("uatom".to_string()),
+        }
     );
+
+    // Swaps use the token units of the reserves
+    let offer_amount = Uint128::new(1000);
+    let (return_amount, spread_amount, commission_amount) = compute_swap(
+        Uint128::new(1_000_000),
+        Uint128::new(1_000_000),
+        offer_amount,
+        Decimal::permille(3),
+    )
+    .unwrap();
+    let simulation = query_simulation(
+        deps.as_ref(),
+        native_asset_info("uusd".to_string()).with_balance(offer_amount),
+    )
+    .unwrap();
     assert_eq!(
-        compute_offer_amount(ZERO, ZERO, AMOUNT, DZERO).unwrap_err(),
-        StdError::generic_err("One of the pools is empty")
+        simulation,
+        SimulationResponse {
+            return_amount,
+            spread_amount,
+            commission_amount,
+        }
     );
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::UpdateConfig {
+            params: to_json_binary(&XYKPoolUpdateParams::UpdateRebasingAsset { config: None })
+                .unwrap(),
+        },
+    )
+    .unwrap();
+    let normalized: NormalizedReservesResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::NormalizedReserves {}).unwrap())
+            .unwrap();
+    assert_eq!(normalized.rebase_rate, Decimal::one());
+    assert_eq!(normalized.base_denom, None);
+}
+
+#[test]
+fn spot_price_in_asset() {
+    let mut deps = mock_dependencies(&[coin(30000, "uusd")]);
+    deps.querier.with_token_balances(&[(
+        &String::from("asset0000"),
+        &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(20000))],
+    )]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            native_asset_info("uusd".to_string()),
+            token_asset_info(Addr::unchecked("asset0000")),
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let spot_price = |deps: Deps, ask_asset: AssetInfo| -> StdResult<SpotPriceResponse> {
+        query(deps, mock_env(), QueryMsg::SpotPriceInAsset { ask_asset })
+            .map(|res| from_json(&res).unwrap())
+    };
+
     assert_eq!(
-        compute_offer_amount(ZERO, ASK, ZERO, DZERO).unwrap_err(),
-        StdError::generic_err("One of the pools is empty")
+        spot_price(deps.as_ref(), native_asset_info("uusd".to_string())).unwrap(),
+        SpotPriceResponse {
+            price: Decimal::from_ratio(3u8, 2u8),
+            price_inverted: Decimal::from_ratio(2u8, 3u8),
+        }
     );
     assert_eq!(
-        compute_offer_amount(ZERO, ASK, AMOUNT, DZERO).unwrap_err(),
-        StdError::generic_err("One of the pools is empty")
+        spot_price(
+            deps.as_ref(),
+            token_asset_info(Addr::unchecked("asset0000"))
+        )
+        .unwrap(),
+        SpotPriceResponse {
+            price: Decimal::from_ratio(2u8, 3u8),
+            price_inverted: Decimal::from_ratio(3u8, 2u8),
+        }
     );
+
+    let err = spot_price(deps.as_ref(), native_asset_info("uluna".to_string())).unwrap_err();
+    assert_eq!(err, StdError::generic_err("uluna is not in the pool"));
+
+    deps.querier.with_token_balances(&[(
+        &String::from("asset0000"),
+        &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::zero())],
+    )]);
+    let err = spot_price(deps.as_ref(), native_asset_info("uusd".to_string())).unwrap_err();
+    assert_eq!(err, StdError::generic_err("The pool is empty"));
+}
+
+#[test]
+fn fee_adjusted_twap() {
+    let mut deps = mock_dependencies(&[coin(30000_000000, "uusd")]);
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("liquidity0000"),
+            &[(
+                &String::from(MOCK_CONTRACT_ADDR),
+                &Uint128::new(20000_000000),
+            )],
+        ),
+        (
+            &String::from("asset0000"),
+            &[(
+                &String::from(MOCK_CONTRACT_ADDR),
+                &Uint128::new(20000_000000),
+            )],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            native_asset_info("uusd".to_string()),
+            token_asset_info(Addr::unchecked("asset0000")),
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let fee_adjusted_twap = |deps: Deps, time: u64, window_secs: u64| -> FeeAdjustedTwapResponse {
+        from_json(
+            &query(
+                deps,
+                mock_env_with_block_time(time),
+                QueryMsg::FeeAdjustedTwap { window_secs },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    // Returns the price and the fee rate of the swap before the commission
+    let swap = |deps: DepsMut, time: u64, offer_amount: u128| -> (Decimal, Decimal) {
+        let res = execute(
+            deps,
+            mock_env_with_block_time(time),
+            mock_info("addr0000", &[coin(offer_amount, "uusd")]),
+            ExecuteMsg::Swap {
+                offer_asset: native_asset_info("uusd".to_string()).with_balance(offer_amount),
+                ask_asset_info: None,
+                belief_price: None,
+                max_spread: Some(Decimal::percent(50)),
+                to: None,
+            },
+        )
+        .unwrap();
+        let attr_amount = |key: &str| -> Uint128 {
+            res.attributes
+                .iter()
+                .find(|attribute| attribute.key == key)
+                .unwrap()
+                .value
+                .parse()
+                .unwrap()
+        };
+        let gross_return = attr_amount("return_amount") + attr_amount("commission_amount");
+        (
+            Decimal::from_ratio(gross_return, offer_amount),
+            Decimal::from_ratio(attr_amount("commission_amount"), gross_return),
+        )
+    };
+
     assert_eq!(
-        compute_offer_amount(OFFER, ZERO, ZERO, DZERO).unwrap_err(),
-        StdError::generic_err("One of the pools is empty")
+        fee_adjusted_twap(deps.as_ref(), 1000, 3600),
+        FeeAdjustedTwapResponse {
+            price: Decimal::zero(),
+            fee_rate_avg: Decimal::zero(),
+            observations: 0,
+        }
     );
+
+    let (first_price, fee_rate) = swap(deps.as_mut(), 1000, 1_000000);
     assert_eq!(
-        compute_offer_amount(OFFER, ZERO, AMOUNT, DZERO).unwrap_err(),
-        StdError::generic_err("One of the pools is empty")
+        fee_adjusted_twap(deps.as_ref(), 1000, 3600),
+        FeeAdjustedTwapResponse {
+            price: first_price,
+            fee_rate_avg: fee_rate,
+            observations: 1,
+        }
     );
+
+    // A larger swap has a larger spread, so it is executed at a lower price
+    let (second_price, second_fee_rate) = swap(deps.as_mut(), 2000, 1000_000000);
+    assert!(second_price < first_price);
     assert_eq!(
-        compute_offer_amount(OFFER, ASK, ZERO, DZERO).unwrap_err(),
-        StdError::generic_err("Swap amount must not be zero")
+        fee_adjusted_twap(deps.as_ref(), 2000, 500),
+        FeeAdjustedTwapResponse {
+            price: second_price,
+            fee_rate_avg: second_fee_rate,
+            observations: 1,
+        }
     );
-    compute_offer_amount(OFFER, ASK, AMOUNT, DZERO).unwrap();
+
+    // The average is weighted by the volume of the swaps
+    let twap = fee_adjusted_twap(deps.as_ref(), 2000, 3600);
+    assert_eq!(twap.observations, 2);
+    assert!(twap.price > second_price);
+    assert!(twap.price - second_price < (first_price - second_price) * Decimal::percent(1));
+    assert!(twap.fee_rate_avg.abs_diff(second_fee_rate) <= fee_rate.abs_diff(second_fee_rate));
+
+    for i in 0..MAX_FEE_ADJUSTED_OBSERVATIONS as u64 {
+        swap(deps.as_mut(), 3000 + i, 1_000000);
+    }
+    let twap = fee_adjusted_twap(deps.as_ref(), 4000, 3600);
+    assert_eq!(twap.observations, MAX_FEE_ADJUSTED_OBSERVATIONS as u32);
+    assert_eq!(twap.price, first_price);
 }
@@ -9,15 +9,16 @@ use astroport::factory::{
     QueryMsg as FactoryQueryMsg,
 };
 use astroport::pair::{
-    ConfigResponse, CumulativePricesResponse, Cw20HookMsg, ExecuteMsg, FeeShareConfig,
-    InstantiateMsg, PoolResponse, QueryMsg, XYKPoolConfig, XYKPoolParams, XYKPoolUpdateParams,
-    MAX_FEE_SHARE_BPS, TWAP_PRECISION,
+    ConfigResponse, CumulativePricesResponse, Cw20HookMsg, FeeShareConfig, InstantiateMsg,
+    PoolResponse, QueryMsg, XYKPoolConfig, XYKPoolParams, XYKPoolUpdateParams, MAX_FEE_SHARE_BPS,
+    TWAP_PRECISION,
 };
+use astroport::pair_xyk::ExecuteMsg;
 use astroport::token::InstantiateMsg as TokenInstantiateMsg;
 use astroport_mocks::cw_multi_test::{App, BasicApp, ContractWrapper, Executor};
 use astroport_mocks::{astroport_address, MockGeneratorBuilder, MockXykPairBuilder};
 use astroport_pair::error::ContractError;
-use cosmwasm_std::{attr, to_json_binary, Addr, Coin, Decimal, Uint128, Uint64};
+use cosmwasm_std::{attr, coin, to_json_binary, Addr, Coin, Decimal, Uint128, Uint64};
 use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg, MinterResponse};
 
 const OWNER: &str = "owner";
@@ -30,11 +31,14 @@ fn mock_app(owner: Addr, coins: Vec<Coin>) -> App {
 }
 
 fn store_token_code(app: &mut App) -> u64 {
-    let astro_token_contract = Box::new(ContractWrapper::new_with_empty(
-        astroport_token::contract::execute,
-        astroport_token::contract::instantiate,
-        astroport_token::contract::query,
-    ));
+    let astro_token_contract = Box::new(
+        ContractWrapper::new_with_empty(
+            astroport_token::contract::execute,
+            astroport_token::contract::instantiate,
+            astroport_token::contract::query,
+        )
+        .with_reply_empty(astroport_token::contract::reply),
+    );
 
     app.store_code(astro_token_contract)
 }
@@ -343,6 +347,9 @@ fn test_provide_and_withdraw_liquidity() {
                 to_json_binary(&XYKPoolConfig {
                     track_asset_balances: false,
                     fee_share: None,
+                    max_spread: None,
+                    lp_cooldown_secs: None,
+                    track_swap_history: true,
                 })
                 .unwrap()
             ),
@@ -516,6 +523,7 @@ fn test_compatibility_of_tokens_with_different_precision() {
         ],
         pair_type: PairType::Xyk {},
         init_params: None,
+        proposal_id: None,
     };
 
     app.execute_contract(owner.clone(), factory_instance.clone(), &msg, &[])
@@ -908,6 +916,7 @@ fn asset_balances_tracking_works_correctly() {
         ],
         pair_type: PairType::Xyk {},
         init_params: None,
+        proposal_id: None,
     };
 
     app.execute_contract(owner.clone(), factory_instance.clone(), &msg, &[])
@@ -1108,9 +1117,13 @@ fn asset_balances_tracking_works_correctly() {
         init_params: Some(
             to_json_binary(&XYKPoolParams {
                 track_asset_balances: Some(true),
+                max_spread: None,
+                lp_cooldown_secs: None,
+                track_swap_history: None,
             })
             .unwrap(),
         ),
+        proposal_id: None,
     };
 
     app.execute_contract(owner.clone(), factory_instance.clone(), &msg, &[])
@@ -1379,6 +1392,244 @@ fn asset_balances_tracking_works_correctly() {
     assert_eq!(res.unwrap(), Uint128::new(499_749812));
 }
 
+#[test]
+fn lp_cooldown_blocks_early_withdrawal() {
+    let owner = Addr::unchecked("owner");
+    let mut app = mock_app(
+        owner.clone(),
+        vec![
+            Coin {
+                denom: "uluna".to_owned(),
+                amount: Uint128::new(1000_000000),
+            },
+            Coin {
+                denom: "uusd".to_owned(),
+                amount: Uint128::new(1000_000000),
+            },
+        ],
+    );
+    let token_code_id = store_token_code(&mut app);
+    let pair_code_id = store_pair_code(&mut app);
+    let factory_code_id = store_factory_code(&mut app);
+
+    let init_msg = FactoryInstantiateMsg {
+        fee_address: Some("fee_collector".to_string()),
+        pair_configs: vec![PairConfig {
+            code_id: pair_code_id,
+            maker_fee_bps: 0,
+            pair_type: PairType::Xyk {},
+            total_fee_bps: 0,
+            is_disabled: false,
+            is_generator_disabled: false,
+            permissioned: false,
+        }],
+        token_code_id,
+        generator_address: Some(String::from("generator")),
+        owner: owner.to_string(),
+        whitelist_code_id: 234u64,
+        coin_registry_address: "coin_registry".to_string(),
+    };
+
+    let factory_instance = app
+        .instantiate_contract(
+            factory_code_id,
+            owner.clone(),
+            &init_msg,
+            &[],
+            "FACTORY",
+            None,
+        )
+        .unwrap();
+
+    let asset_infos = vec![
+        native_asset_info("uusd".to_string()),
+        native_asset_info("uluna".to_string()),
+    ];
+    let msg = FactoryExecuteMsg::CreatePair {
+        asset_infos: asset_infos.clone(),
+        pair_type: PairType::Xyk {},
+        init_params: Some(
+            to_json_binary(&XYKPoolParams {
+                track_asset_balances: None,
+                max_spread: None,
+                lp_cooldown_secs: Some(100),
+                track_swap_history: None,
+            })
+            .unwrap(),
+        ),
+        proposal_id: None,
+    };
+    app.execute_contract(owner.clone(), factory_instance.clone(), &msg, &[])
+        .unwrap();
+
+    let res: PairInfo = app
+        .wrap()
+        .query_wasm_smart(&factory_instance, &FactoryQueryMsg::Pair { asset_infos })
+        .unwrap();
+    let pair_instance = res.contract_addr;
+    let lp_token = res.liquidity_token;
+
+    let (msg, coins) = provide_liquidity_msg(
+        Uint128::new(100_000000),
+        Uint128::new(100_000000),
+        None,
+        None,
+    );
+    app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &coins)
+        .unwrap();
+
+    let withdraw_msg = Cw20ExecuteMsg::Send {
+        contract: pair_instance.to_string(),
+        amount: Uint128::new(1_000000),
+        msg: to_json_binary(&Cw20HookMsg::WithdrawLiquidity { assets: vec![] }).unwrap(),
+    };
+
+    // Withdrawing right after providing is not allowed
+    let unlock_at = app.block_info().time.seconds() + 100;
+    let err = app
+        .execute_contract(owner.clone(), lp_token.clone(), &withdraw_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::LpCooldownActive { unlock_at }
+    );
+
+    // Transferring the LP tokens carries the cooldown over to the recipient
+    app.execute_contract(
+        owner.clone(),
+        lp_token.clone(),
+        &Cw20ExecuteMsg::Transfer {
+            recipient: "alice".to_string(),
+            amount: Uint128::new(10_000000),
+        },
+        &[],
+    )
+    .unwrap();
+    let err = app
+        .execute_contract(
+            Addr::unchecked("alice"),
+            lp_token.clone(),
+            &withdraw_msg,
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::LpCooldownActive { unlock_at }
+    );
+
+    // The fee collector is not subject to the cooldown
+    let (msg, coins) = provide_liquidity_msg(
+        Uint128::new(100_000000),
+        Uint128::new(100_000000),
+        Some("fee_collector".to_string()),
+        None,
+    );
+    app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &coins)
+        .unwrap();
+    app.execute_contract(
+        Addr::unchecked("fee_collector"),
+        lp_token.clone(),
+        &withdraw_msg,
+        &[],
+    )
+    .unwrap();
+
+    // Withdrawing is allowed once the cooldown has passed
+    app.update_block(|b| b.time = b.time.plus_seconds(100));
+    app.execute_contract(owner.clone(), lp_token.clone(), &withdraw_msg, &[])
+        .unwrap();
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        lp_token.clone(),
+        &withdraw_msg,
+        &[],
+    )
+    .unwrap();
+
+    // Providing for another receiver only puts the new LP tokens in the cooldown
+    app.send_tokens(
+        owner.clone(),
+        Addr::unchecked("attacker"),
+        &[coin(1_000000, "uusd"), coin(1_000000, "uluna")],
+    )
+    .unwrap();
+    let (msg, coins) = provide_liquidity_msg(
+        Uint128::new(1_000000),
+        Uint128::new(1_000000),
+        Some("alice".to_string()),
+        None,
+    );
+    app.execute_contract(
+        Addr::unchecked("attacker"),
+        pair_instance.clone(),
+        &msg,
+        &coins,
+    )
+    .unwrap();
+    app.execute_contract(
+        Addr::unchecked("alice"),
+        lp_token.clone(),
+        &withdraw_msg,
+        &[],
+    )
+    .unwrap();
+    let err = app
+        .execute_contract(
+            Addr::unchecked("alice"),
+            lp_token.clone(),
+            &Cw20ExecuteMsg::Send {
+                contract: pair_instance.to_string(),
+                amount: Uint128::new(8_500000),
+                msg: to_json_binary(&Cw20HookMsg::WithdrawLiquidity { assets: vec![] }).unwrap(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::LpCooldownActive {
+            unlock_at: app.block_info().time.seconds() + 100
+        }
+    );
+
+    // The cooldown can be disabled by the factory owner
+    let (msg, coins) = provide_liquidity_msg(
+        Uint128::new(100_000000),
+        Uint128::new(100_000000),
+        None,
+        None,
+    );
+    app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &coins)
+        .unwrap();
+    let res: BalanceResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &lp_token,
+            &Cw20QueryMsg::Balance {
+                address: owner.to_string(),
+            },
+        )
+        .unwrap();
+    let withdraw_all_msg = Cw20ExecuteMsg::Send {
+        contract: pair_instance.to_string(),
+        amount: res.balance,
+        msg: to_json_binary(&Cw20HookMsg::WithdrawLiquidity { assets: vec![] }).unwrap(),
+    };
+    app.execute_contract(owner.clone(), lp_token.clone(), &withdraw_all_msg, &[])
+        .unwrap_err();
+    let msg = ExecuteMsg::UpdateConfig {
+        params: to_json_binary(&XYKPoolUpdateParams::UpdateLpCooldown {
+            lp_cooldown_secs: None,
+        })
+        .unwrap(),
+    };
+    app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &[])
+        .unwrap();
+    app.execute_contract(owner.clone(), lp_token, &withdraw_all_msg, &[])
+        .unwrap();
+}
+
 #[test]
 fn update_pair_config() {
     let owner = Addr::unchecked(OWNER);
@@ -1460,6 +1711,9 @@ fn update_pair_config() {
                 to_json_binary(&XYKPoolConfig {
                     track_asset_balances: false,
                     fee_share: None,
+                    max_spread: None,
+                    lp_cooldown_secs: None,
+                    track_swap_history: true,
                 })
                 .unwrap()
             ),
@@ -1501,6 +1755,9 @@ fn update_pair_config() {
                 to_json_binary(&XYKPoolConfig {
                     track_asset_balances: true,
                     fee_share: None,
+                    max_spread: None,
+                    lp_cooldown_secs: None,
+                    track_swap_history: true,
                 })
                 .unwrap()
             ),
@@ -1591,6 +1848,9 @@ fn enable_disable_fee_sharing() {
                 to_json_binary(&XYKPoolConfig {
                     track_asset_balances: false,
                     fee_share: None,
+                    max_spread: None,
+                    lp_cooldown_secs: None,
+                    track_swap_history: true,
                 })
                 .unwrap()
             ),
@@ -1663,6 +1923,9 @@ fn enable_disable_fee_sharing() {
                         bps: fee_share_bps,
                         recipient: Addr::unchecked(fee_share_contract),
                     }),
+                    max_spread: None,
+                    lp_cooldown_secs: None,
+                    track_swap_history: true,
                 })
                 .unwrap()
             ),
@@ -1692,6 +1955,9 @@ fn enable_disable_fee_sharing() {
                 to_json_binary(&XYKPoolConfig {
                     track_asset_balances: false,
                     fee_share: None,
+                    max_spread: None,
+                    lp_cooldown_secs: None,
+                    track_swap_history: true,
                 })
                 .unwrap()
             ),
@@ -2019,9 +2285,13 @@ fn test_fee_share(
         init_params: Some(
             to_json_binary(&XYKPoolParams {
                 track_asset_balances: Some(true),
+                max_spread: None,
+                lp_cooldown_secs: None,
+                track_swap_history: None,
             })
             .unwrap(),
         ),
+        proposal_id: None,
     };
 
     app.execute_contract(owner.clone(), factory_instance.clone(), &msg, &[])
@@ -2273,3 +2543,97 @@ fn test_provide_liquidity_without_funds() {
         "Generic error: Native token balance mismatch between the argument (100000000uusd) and the transferred (0uusd)"
     );
 }
+
+#[test]
+fn compliance_mode_allows_lp_token_transfers() {
+    let owner = Addr::unchecked(OWNER);
+    let mut app = mock_app(
+        owner.clone(),
+        vec![
+            Coin {
+                denom: "uluna".to_owned(),
+                amount: Uint128::new(1000_000000),
+            },
+            Coin {
+                denom: "uusd".to_owned(),
+                amount: Uint128::new(1000_000000),
+            },
+        ],
+    );
+    let pair_instance = instantiate_pair(&mut app, &owner);
+    let lp_token = Addr::unchecked("contract2");
+
+    let (provide_msg, coins) = provide_liquidity_msg(
+        Uint128::new(100_000000),
+        Uint128::new(100_000000),
+        None,
+        None,
+    );
+    app.execute_contract(owner.clone(), pair_instance.clone(), &provide_msg, &coins)
+        .unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        params: to_json_binary(&XYKPoolUpdateParams::UpdateComplianceMode {
+            compliance_mode: true,
+        })
+        .unwrap(),
+    };
+    app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &[])
+        .unwrap();
+    let msg = ExecuteMsg::BlockSender {
+        address: "blocked".to_string(),
+        reason: "sanctioned".to_string(),
+    };
+    app.execute_contract(owner.clone(), pair_instance.clone(), &msg, &[])
+        .unwrap();
+
+    // Operations outside the compliance mode allowlist are disabled
+    let err = app
+        .execute_contract(
+            owner.clone(),
+            pair_instance.clone(),
+            &ExecuteMsg::SaveTwapSnapshot {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::ComplianceModeActive {}
+    );
+
+    // The LP token updates the top LP holders after every balance change
+    app.execute_contract(owner.clone(), pair_instance.clone(), &provide_msg, &coins)
+        .unwrap();
+    app.execute_contract(
+        owner.clone(),
+        lp_token.clone(),
+        &Cw20ExecuteMsg::Transfer {
+            recipient: "user".to_string(),
+            amount: Uint128::new(1_000000),
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        owner.clone(),
+        lp_token.clone(),
+        &Cw20ExecuteMsg::Send {
+            contract: pair_instance.to_string(),
+            amount: Uint128::new(1_000000),
+            msg: to_json_binary(&Cw20HookMsg::WithdrawLiquidity { assets: vec![] }).unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let res: BalanceResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &lp_token,
+            &Cw20QueryMsg::Balance {
+                address: "user".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.balance, Uint128::new(1_000000));
+}
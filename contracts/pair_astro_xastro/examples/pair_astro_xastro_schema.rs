@@ -1,7 +1,6 @@
 use cosmwasm_schema::write_api;
 
-use astroport::pair::InstantiateMsg;
-use astroport::pair_bonded::{ExecuteMsg, QueryMsg};
+use astroport::pair_bonded::{ExecuteMsg, InstantiateMsg, QueryMsg};
 
 fn main() {
     write_api! {
@@ -4,10 +4,10 @@ pub mod contract;
 pub mod state;
 
 use crate::state::{InitParams, MigrateMsg};
-use astroport::pair::InstantiateMsg;
-use astroport::pair_bonded::{ExecuteMsg, QueryMsg};
+use astroport::pair_bonded::{ExecuteMsg, InstantiateMsg, QueryMsg};
 use astroport_pair_bonded::base::PairBonded;
 use astroport_pair_bonded::error::ContractError;
+use astroport_pair_bonded::migration::migrate_config_to_v200;
 use cosmwasm_std::{
     entry_point, from_json, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
 };
@@ -59,9 +59,9 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, C
 
     match contract_version.contract.as_ref() {
         Contract::CONTRACT_NAME => match contract_version.version.as_ref() {
-            "1.0.0" => {}
-            "1.0.1" => {}
-            "1.0.2" => {}
+            "1.0.0" | "1.0.1" | "1.0.2" => {
+                migrate_config_to_v200(deps.storage)?;
+            }
             _ => return Err(ContractError::MigrationError {}),
         },
         _ => return Err(ContractError::MigrationError {}),
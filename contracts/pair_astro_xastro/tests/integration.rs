@@ -11,10 +11,17 @@ use astroport::staking::{
     QueryMsg as StakingQueryMsg,
 };
 
-use astroport::pair_bonded::{ExecuteMsg, QueryMsg};
+use astroport::pair_bonded::{
+    Config, ExecuteMsg, InstantiateMsg as BondedPairInstantiateMsg, QueryMsg,
+    VirtualReservesResponse, DEFAULT_MAX_ORACLE_AGE_SECS,
+};
 use astroport::token::InstantiateMsg as TokenInstantiateMsg;
-use astroport_pair_astro_xastro::state::Params;
-use cosmwasm_std::{to_json_binary, Addr, Coin, Uint128};
+use astroport_pair_astro_xastro::state::{MigrateMsg, Params};
+use astroport_pair_bonded::migration::{ConfigV100, CONFIG_V100};
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Coin, DepsMut, Env, MessageInfo, Response, StdResult, Uint128,
+};
+use cw2::{query_contract_info, set_contract_version};
 use cw20::{Cw20ExecuteMsg, Cw20QueryMsg, MinterResponse};
 use cw_multi_test::{App, ContractWrapper, Executor};
 
@@ -39,6 +46,42 @@ fn store_pair_code(app: &mut App) -> u64 {
     app.store_code(pair_contract)
 }
 
+/// Mimics the instantiation of a pair deployed before the bonded pair template v2.0.0.
+fn instantiate_v100(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: ConfigV100,
+) -> StdResult<Response> {
+    set_contract_version(deps.storage, "astroport-pair-astro-xastro", "1.0.1")?;
+    CONFIG_V100.save(deps.storage, &msg)?;
+
+    Ok(Response::new())
+}
+
+fn store_pair_code_v100(app: &mut App) -> u64 {
+    let pair_contract = Box::new(ContractWrapper::new_with_empty(
+        astroport_pair_astro_xastro::execute,
+        instantiate_v100,
+        astroport_pair_astro_xastro::query,
+    ));
+
+    app.store_code(pair_contract)
+}
+
+fn store_pair_code_with_migrate(app: &mut App) -> u64 {
+    let pair_contract = Box::new(
+        ContractWrapper::new_with_empty(
+            astroport_pair_astro_xastro::execute,
+            astroport_pair_astro_xastro::instantiate,
+            astroport_pair_astro_xastro::query,
+        )
+        .with_migrate_empty(astroport_pair_astro_xastro::migrate),
+    );
+
+    app.store_code(pair_contract)
+}
+
 fn store_staking_code(app: &mut App) -> u64 {
     let staking_contract = Box::new(
         ContractWrapper::new_with_empty(
@@ -713,7 +756,241 @@ fn test_queries() {
             ],
             contract_addr: contracts.pair_instance.clone(),
             liquidity_token: Addr::unchecked(""),
-            pair_type: PairType::Custom("Bonded".to_string())
+            pair_type: PairType::Custom("Bonded".to_string()),
+            creator: None,
+            creation_height: 0,
+            creation_time: 0,
+        }
+    );
+}
+
+#[test]
+fn test_virtual_reserves() {
+    let owner = Addr::unchecked("owner");
+
+    let mut router = mock_app(owner.clone(), vec![]);
+
+    let pair_code_id = store_pair_code(&mut router);
+
+    let factory_instance = instantiate_factory_contract(&mut router, owner.clone(), pair_code_id);
+    let token_instance = instantiate_token(&mut router, owner.clone());
+
+    let (staking_instance, xastro_instance) =
+        instantiate_staking(&mut router, owner.clone(), &token_instance);
+
+    let msg = |initial_virtual_reserves| BondedPairInstantiateMsg {
+        asset_infos: vec![
+            AssetInfo::Token {
+                contract_addr: token_instance.clone(),
+            },
+            AssetInfo::Token {
+                contract_addr: xastro_instance.clone(),
+            },
+        ],
+        token_code_id: 123,
+        factory_addr: factory_instance.to_string(),
+        init_params: Some(
+            to_json_binary(&Params {
+                astro_addr: token_instance.clone(),
+                xastro_addr: xastro_instance.clone(),
+                staking_addr: staking_instance.clone(),
+            })
+            .unwrap(),
+        ),
+        initial_virtual_reserves,
+    };
+
+    let err = router
+        .instantiate_contract(
+            pair_code_id,
+            owner.clone(),
+            &msg(Some([Uint128::new(1000), Uint128::zero()])),
+            &[],
+            String::from("ASTRO-xASTRO pair"),
+            None,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Initial virtual reserves must not be zero"
+    );
+
+    let pair_instance = router
+        .instantiate_contract(
+            pair_code_id,
+            owner.clone(),
+            &msg(Some([Uint128::new(1000), Uint128::new(2000)])),
+            &[],
+            String::from("ASTRO-xASTRO pair"),
+            None,
+        )
+        .unwrap();
+
+    let res: VirtualReservesResponse = router
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::VirtualReserves {})
+        .unwrap();
+    assert_eq!(res.initial_virtual_reserves, res.virtual_reserves);
+
+    // Virtual reserves decay as real reserves grow
+    mint_tokens(
+        &mut router,
+        owner.clone(),
+        token_instance.clone(),
+        Uint128::new(5000),
+        pair_instance.clone(),
+    );
+    let res: VirtualReservesResponse = router
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::VirtualReserves {})
+        .unwrap();
+    assert_eq!(
+        res.virtual_reserves,
+        [Uint128::new(500), Uint128::new(2000)]
+    );
+
+    // Virtual reserves are fully decayed at 10x real reserves
+    mint_tokens(
+        &mut router,
+        owner,
+        token_instance,
+        Uint128::new(5000),
+        pair_instance.clone(),
+    );
+    let res: VirtualReservesResponse = router
+        .wrap()
+        .query_wasm_smart(&pair_instance, &QueryMsg::VirtualReserves {})
+        .unwrap();
+    assert_eq!(res.virtual_reserves, [Uint128::zero(), Uint128::new(2000)]);
+}
+
+#[test]
+fn test_migrate_config_from_v100() {
+    let owner = Addr::unchecked("owner");
+
+    let mut router = mock_app(owner.clone(), vec![]);
+
+    let contracts = instantiate_astroport(&mut router, &owner);
+
+    let pair_info = PairInfo {
+        asset_infos: vec![
+            AssetInfo::Token {
+                contract_addr: contracts.astro_instance.clone(),
+            },
+            AssetInfo::Token {
+                contract_addr: contracts.xastro_instance.clone(),
+            },
+        ],
+        contract_addr: Addr::unchecked("pair"),
+        liquidity_token: Addr::unchecked(""),
+        pair_type: PairType::Custom("Bonded".to_string()),
+        creator: None,
+        creation_height: 0,
+        creation_time: 0,
+    };
+
+    let v100_code_id = store_pair_code_v100(&mut router);
+    let v100_pair = router
+        .instantiate_contract(
+            v100_code_id,
+            owner.clone(),
+            &ConfigV100 {
+                pair_info: pair_info.clone(),
+                factory_addr: contracts.factory_instance.clone(),
+            },
+            &[],
+            String::from("ASTRO-xASTRO pair v1.0.1"),
+            Some(owner.to_string()),
+        )
+        .unwrap();
+
+    // The v1 config can't be read by the current schema
+    router
+        .wrap()
+        .query_wasm_smart::<ConfigResponse>(&v100_pair, &QueryMsg::Config {})
+        .unwrap_err();
+
+    let new_code_id = store_pair_code_with_migrate(&mut router);
+    router
+        .migrate_contract(
+            owner.clone(),
+            v100_pair.clone(),
+            &MigrateMsg {},
+            new_code_id,
+        )
+        .unwrap();
+
+    let res: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(&v100_pair, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(
+        res,
+        ConfigResponse {
+            block_time_last: 0u64,
+            params: None,
+            owner: owner.clone(),
+            factory_addr: contracts.factory_instance
         }
     );
+
+    let res: PairInfo = router
+        .wrap()
+        .query_wasm_smart(&v100_pair, &QueryMsg::Pair {})
+        .unwrap();
+    assert_eq!(res, pair_info);
+
+    let version = query_contract_info(&router.wrap(), &v100_pair).unwrap();
+    assert_eq!(version.version, "2.0.0");
+
+    // The migration is applied only once
+    let err = router
+        .migrate_contract(owner, v100_pair, &MigrateMsg {}, new_code_id)
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Failed to migrate the contract"
+    );
+}
+
+#[test]
+fn test_update_max_oracle_age() {
+    let owner = Addr::unchecked("owner");
+
+    let mut router = mock_app(owner.clone(), vec![]);
+
+    let contracts = instantiate_astroport(&mut router, &owner);
+
+    let max_oracle_age = |router: &App| -> u64 {
+        let config: Config = from_json(
+            router
+                .wrap()
+                .query_wasm_raw(&contracts.pair_instance, b"config".as_slice())
+                .unwrap()
+                .unwrap(),
+        )
+        .unwrap();
+        config.max_oracle_age_secs
+    };
+    assert_eq!(max_oracle_age(&router), DEFAULT_MAX_ORACLE_AGE_SECS);
+
+    let err = router
+        .execute_contract(
+            Addr::unchecked("random"),
+            contracts.pair_instance.clone(),
+            &ExecuteMsg::UpdateMaxOracleAge { max_age: 60 },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "Unauthorized");
+
+    router
+        .execute_contract(
+            owner,
+            contracts.pair_instance.clone(),
+            &ExecuteMsg::UpdateMaxOracleAge { max_age: 60 },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(max_oracle_age(&router), 60);
 }
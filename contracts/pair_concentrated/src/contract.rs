@@ -114,6 +114,9 @@ pub fn instantiate(
             liquidity_token: Addr::unchecked(""),
             asset_infos: msg.asset_infos.clone(),
             pair_type: PairType::Custom("concentrated".to_string()),
+            creator: None,
+            creation_height: 0,
+            creation_time: 0,
         },
         factory_addr,
         pool_params,
@@ -351,6 +354,7 @@ fn receive_cw20(
             cw20_msg.amount,
             assets,
         ),
+        Cw20HookMsg::ProvideLiquidity { .. } => Err(ContractError::NotSupported {}),
     }
 }
 
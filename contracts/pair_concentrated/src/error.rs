@@ -41,6 +41,9 @@ pub enum ContractError {
     #[error("Failed to parse or process reply message")]
     FailedToParseReply {},
 
+    #[error("Operation is not supported")]
+    NotSupported {},
+
     #[error("Pair is not registered in the factory. Only swap and withdraw are allowed")]
     PairIsNotRegistered {},
 
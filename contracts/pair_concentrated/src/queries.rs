@@ -3,14 +3,17 @@ use cosmwasm_std::entry_point;
 use cosmwasm_std::{
     to_json_binary, Binary, Decimal, Decimal256, Deps, Env, StdError, StdResult, Uint128, Uint64,
 };
+use cw2::get_contract_version;
 use itertools::Itertools;
 
 use astroport::asset::{Asset, AssetInfo};
 use astroport::cosmwasm_ext::{DecimalToInteger, IntegerToDecimal};
-use astroport::observation::query_observation;
+use astroport::observation::{query_observation, query_observations_twap};
 use astroport::pair::{
-    ConfigResponse, PoolResponse, ReverseSimulationResponse, SimulationResponse,
+    ConfigResponse, OraclePriceResponse, PoolResponse, ReverseSimulationResponse,
+    SimulationResponse, ORACLE_TWAP_PERIOD,
 };
+use astroport::to_decimal;
 
 use astroport::pair_concentrated::{ConcentratedPoolConfig, QueryMsg};
 use astroport::querier::{query_factory_config, query_fee_info, query_supply};
@@ -50,6 +53,11 @@ use crate::utils::{pool_info, query_pools};
 ///
 /// * **QueryMsg::AssetBalanceAt { asset_info, block_height }** Returns the balance of the specified
 /// asset that was in the pool just preceding the moment of the specified block height creation.
+///
+/// * **QueryMsg::Price { base, quote, amount }** Returns the oracle price of the base asset
+/// denominated in the quote asset using an [`OraclePriceResponse`] object.
+///
+/// * **QueryMsg::ContractInfo {}** Returns the cw2 contract name and version.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -79,7 +87,81 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             asset_info,
             block_height,
         } => to_json_binary(&query_asset_balances_at(deps, asset_info, block_height)?),
+        QueryMsg::Price {
+            base,
+            quote,
+            amount,
+        } => to_json_binary(
+            &query_oracle_price(deps, env, base, quote, amount)
+                .map_err(|err| StdError::generic_err(format!("{err}")))?,
+        ),
+        QueryMsg::ContractInfo {} => to_json_binary(&get_contract_version(deps.storage)?),
+    }
+}
+
+/// Returns the price of `amount` of `base` denominated in `quote`. The spot price is the price of
+/// the last trade and the TWAP over the last [`ORACLE_TWAP_PERIOD`] is derived from the price
+/// observations.
+///
+/// * **amount** amount of the base asset to price. A zero amount returns the marginal price.
+pub fn query_oracle_price(
+    deps: Deps,
+    env: Env,
+    base: AssetInfo,
+    quote: AssetInfo,
+    amount: Uint128,
+) -> Result<OraclePriceResponse, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let asset_infos = &config.pair_info.asset_infos;
+    if base.equal(&quote) || !asset_infos.contains(&base) || !asset_infos.contains(&quote) {
+        return Err(
+            StdError::generic_err("Base and quote assets must be different pool assets").into(),
+        );
     }
+    let base_first = asset_infos[0].equal(&base);
+
+    // The last price is of the second asset denominated in the first one in whole tokens
+    let precisions = Precisions::new(deps.storage)?;
+    let price_state = &config.pool_state.price_state;
+    let last_price = price_state.last_price
+        * Decimal256::from_ratio(
+            10u128.pow(precisions.get_precision(&asset_infos[0])?.into()),
+            10u128.pow(precisions.get_precision(&asset_infos[1])?.into()),
+        );
+    let marginal_price = if base_first {
+        last_price.inv().unwrap_or_default()
+    } else {
+        last_price
+    };
+    let marginal_price = to_decimal(marginal_price)?;
+
+    let execution_price = if amount.is_zero() || marginal_price.is_zero() {
+        marginal_price
+    } else {
+        let simulation = query_simulation(deps, env.clone(), Asset { info: base, amount })?;
+        Decimal::from_ratio(
+            simulation.return_amount + simulation.commission_amount,
+            amount,
+        )
+    };
+
+    // Observed prices are of the second asset denominated in the first one
+    let twap = query_observations_twap(deps, &env, OBSERVATIONS, ORACLE_TWAP_PERIOD)?.map(
+        |(price, period)| {
+            if base_first {
+                (price.inv().unwrap_or_default(), period)
+            } else {
+                (price, period)
+            }
+        },
+    );
+
+    Ok(OraclePriceResponse::new(
+        marginal_price,
+        execution_price,
+        twap,
+        price_state.last_price_update,
+    ))
 }
 
 /// Returns the amounts of assets in the pair contract as well as the amount of LP
@@ -258,6 +258,7 @@ impl Helper {
             pair_type,
             asset_infos: asset_infos.clone(),
             init_params: Some(to_json_binary(&params).unwrap()),
+            proposal_id: None,
         };
 
         app.execute_contract(owner.clone(), factory.clone(), &init_pair_msg, &[])?;
@@ -5,6 +5,7 @@ use std::rc::Rc;
 use std::str::FromStr;
 
 use cosmwasm_std::{Addr, Coin, Decimal, Decimal256, StdError, Uint128};
+use cw2::ContractVersion;
 use itertools::{max, Itertools};
 
 use astroport::asset::{
@@ -1860,3 +1861,23 @@ fn test_provide_liquidity_without_funds() {
         "Generic error: Native token balance mismatch between the argument (100000000000uluna) and the transferred (0uluna)"
     )
 }
+
+#[test]
+fn check_contract_info() {
+    let owner = Addr::unchecked("owner");
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::cw20("USDC")];
+    let helper = Helper::new(&owner, test_coins, common_pcl_params()).unwrap();
+
+    let contract_info: ContractVersion = helper
+        .app
+        .wrap()
+        .query_wasm_smart(helper.pair_addr.to_string(), &QueryMsg::ContractInfo {})
+        .unwrap();
+    assert_eq!(
+        contract_info,
+        ContractVersion {
+            contract: "astroport-pair-concentrated".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    );
+}
@@ -131,6 +131,9 @@ pub fn instantiate(
             liquidity_token: Addr::unchecked(""),
             asset_infos: msg.asset_infos.clone(),
             pair_type: PairType::Custom("concentrated_inj_orderbook".to_string()),
+            creator: None,
+            creation_height: 0,
+            creation_time: 0,
         },
         factory_addr,
         pool_params,
@@ -5,15 +5,17 @@ use cosmwasm_std::{
 use injective_cosmwasm::InjectiveQueryWrapper;
 use itertools::Itertools;
 
-use astroport::asset::Asset;
+use astroport::asset::{Asset, AssetInfo};
 use astroport::cosmwasm_ext::{DecimalToInteger, IntegerToDecimal};
-use astroport::observation::query_observation;
+use astroport::observation::{query_observation, query_observations_twap};
 use astroport::pair::{
-    ConfigResponse, PoolResponse, ReverseSimulationResponse, SimulationResponse,
+    ConfigResponse, OraclePriceResponse, PoolResponse, ReverseSimulationResponse,
+    SimulationResponse, ORACLE_TWAP_PERIOD,
 };
 use astroport::pair_concentrated::ConcentratedPoolParams;
 use astroport::pair_concentrated_inj::{OrderbookStateResponse, QueryMsg};
 use astroport::querier::{query_factory_config, query_fee_info, query_supply};
+use astroport::to_decimal;
 use astroport_pcl_common::state::Precisions;
 use astroport_pcl_common::utils::{
     before_swap_check, compute_offer_amount, compute_swap, get_share_in_assets,
@@ -46,6 +48,9 @@ use crate::utils::query_pools;
 /// pool using a [`CumulativePricesResponse`] object.
 ///
 /// * **QueryMsg::Config {}** Returns the configuration for the pair contract using a [`ConfigResponse`] object.
+///
+/// * **QueryMsg::Price { base, quote, amount }** Returns the oracle price of the base asset
+/// denominated in the quote asset using an [`OraclePriceResponse`] object.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps<InjectiveQueryWrapper>, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -77,7 +82,80 @@ pub fn query(deps: Deps<InjectiveQueryWrapper>, env: Env, msg: QueryMsg) -> StdR
             let resp: OrderbookStateResponse = OrderbookState::load(deps.storage)?.into();
             to_json_binary(&resp)
         }
+        QueryMsg::Price {
+            base,
+            quote,
+            amount,
+        } => to_json_binary(
+            &query_oracle_price(deps, env, base, quote, amount)
+                .map_err(|err| StdError::generic_err(format!("{err}")))?,
+        ),
+    }
+}
+
+/// Returns the price of `amount` of `base` denominated in `quote`. The spot price is the price of
+/// the last trade and the TWAP over the last [`ORACLE_TWAP_PERIOD`] is derived from the price
+/// observations.
+///
+/// * **amount** amount of the base asset to price. A zero amount returns the marginal price.
+pub fn query_oracle_price(
+    deps: Deps<InjectiveQueryWrapper>,
+    env: Env,
+    base: AssetInfo,
+    quote: AssetInfo,
+    amount: Uint128,
+) -> Result<OraclePriceResponse, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let asset_infos = &config.pair_info.asset_infos;
+    if base.equal(&quote) || !asset_infos.contains(&base) || !asset_infos.contains(&quote) {
+        return Err(
+            StdError::generic_err("Base and quote assets must be different pool assets").into(),
+        );
     }
+    let base_first = asset_infos[0].equal(&base);
+
+    // The last price is of the second asset denominated in the first one in whole tokens
+    let precisions = Precisions::new(deps.storage)?;
+    let price_state = &config.pool_state.price_state;
+    let last_price = price_state.last_price
+        * Decimal256::from_ratio(
+            10u128.pow(precisions.get_precision(&asset_infos[0])?.into()),
+            10u128.pow(precisions.get_precision(&asset_infos[1])?.into()),
+        );
+    let marginal_price = if base_first {
+        last_price.inv().unwrap_or_default()
+    } else {
+        last_price
+    };
+    let marginal_price = to_decimal(marginal_price)?;
+
+    let execution_price = if amount.is_zero() || marginal_price.is_zero() {
+        marginal_price
+    } else {
+        let simulation = query_simulation(deps, env.clone(), Asset { info: base, amount })?;
+        Decimal::from_ratio(
+            simulation.return_amount + simulation.commission_amount,
+            amount,
+        )
+    };
+
+    // Observed prices are of the second asset denominated in the first one
+    let twap = query_observations_twap(deps, &env, OBSERVATIONS, ORACLE_TWAP_PERIOD)?.map(
+        |(price, period)| {
+            if base_first {
+                (price.inv().unwrap_or_default(), period)
+            } else {
+                (price, period)
+            }
+        },
+    );
+
+    Ok(OraclePriceResponse::new(
+        marginal_price,
+        execution_price,
+        twap,
+        price_state.last_price_update,
+    ))
 }
 
 /// Returns the amounts of assets in the pair contract and its subaccount as well as the amount of LP
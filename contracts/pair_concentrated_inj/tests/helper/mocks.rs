@@ -46,7 +46,7 @@ const GAS_PRICE: u128 = 1_000_000_000; // 0.000000001 INJ per gas unit
 
 pub fn factory_reply<T, C>(
     deps: DepsMut<C>,
-    _env: Env,
+    env: Env,
     msg: Reply,
 ) -> Result<Response<T>, ContractError>
 where
@@ -54,7 +54,7 @@ where
     T: CustomMsg,
 {
     let tmp = TMP_PAIR_INFO.load(deps.storage)?;
-    if PAIRS.has(deps.storage, &tmp.pair_key) {
+    if PAIRS.may_load(deps.storage, &tmp.pair_key)?.is_some() {
         return Err(ContractError::PairWasRegistered {});
     }
 
@@ -64,7 +64,12 @@ where
 
     let pair_contract = deps.api.addr_validate(&res.contract_address)?;
 
-    PAIRS.save(deps.storage, &tmp.pair_key, &pair_contract)?;
+    PAIRS.save(
+        deps.storage,
+        &tmp.pair_key,
+        &pair_contract,
+        env.block.height,
+    )?;
 
     Ok(Response::new().add_attributes(vec![
         attr("action", "register"),
@@ -0,0 +1,224 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    attr, to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+};
+use cw2::set_contract_version;
+
+use astroport::asset::{Asset, PairInfo};
+use astroport::pair::QueryMsg as PairQueryMsg;
+use astroport::pair_indexer::{
+    ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg, SwapRecord, SwapVolumeResponse,
+    MAX_SWAP_RECORDS,
+};
+use astroport::querier::query_pair_info;
+
+use crate::error::ContractError;
+use crate::state::{Config, CONFIG, SWAP_RECORDS};
+
+/// Contract name that is used for migration.
+const CONTRACT_NAME: &str = "astroport-pair-indexer";
+/// Contract version that is used for migration.
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// The maximum number of swaps returned by [`QueryMsg::RecentSwaps`].
+const MAX_LIMIT: u32 = 100;
+
+/// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let config = Config {
+        factory_addr: deps.api.addr_validate(&msg.factory_addr)?,
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "instantiate"),
+        attr("factory_addr", config.factory_addr),
+    ]))
+}
+
+/// Exposes execute functions available in the contract.
+///
+/// ## Variants
+/// * **ExecuteMsg::RecordSwap { offer, ask, fee, sender }** Records a swap executed by the calling
+/// pair.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::RecordSwap {
+            offer,
+            ask,
+            fee,
+            sender,
+        } => record_swap(deps, env, info, offer, ask, fee, sender),
+    }
+}
+
+/// Stores a swap executed by the calling pair. The oldest record is dropped once
+/// [`MAX_SWAP_RECORDS`] swaps are stored.
+///
+/// * **offer** asset offered by the trader.
+///
+/// * **ask** asset returned to the trader.
+///
+/// * **fee** commission charged for the swap.
+///
+/// * **sender** address that executed the swap.
+///
+/// ## Executor
+/// Only pairs registered in the factory can execute this.
+pub fn record_swap(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    offer: Asset,
+    ask: Asset,
+    fee: Asset,
+    sender: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let pair_info: PairInfo = deps
+        .querier
+        .query_wasm_smart(&info.sender, &PairQueryMsg::Pair {})
+        .map_err(|_| ContractError::Unauthorized {})?;
+    let registered_pair =
+        query_pair_info(&deps.querier, &config.factory_addr, &pair_info.asset_infos)?;
+    if registered_pair.contract_addr != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if [&offer, &ask, &fee]
+        .iter()
+        .any(|asset| !pair_info.asset_infos.contains(&asset.info))
+    {
+        return Err(ContractError::AssetMismatch {});
+    }
+
+    let record = SwapRecord {
+        pair: info.sender,
+        offer,
+        ask,
+        fee,
+        timestamp: env.block.time.seconds(),
+        sender: deps.api.addr_validate(&sender)?,
+    };
+    SWAP_RECORDS.push_back(deps.storage, &record)?;
+    if SWAP_RECORDS.len(deps.storage)? > MAX_SWAP_RECORDS {
+        SWAP_RECORDS.pop_front(deps.storage)?;
+    }
+
+    Ok(Response::new().add_attributes([
+        attr("action", "record_swap"),
+        attr("pair", record.pair),
+        attr("sender", record.sender),
+    ]))
+}
+
+/// Exposes all the queries available in the contract.
+///
+/// ## Queries
+/// * **QueryMsg::Config {}** Returns the contract configuration using a [`ConfigResponse`] object.
+///
+/// * **QueryMsg::RecentSwaps { pair, limit }** Returns the most recent swaps starting from the
+/// newest one.
+///
+/// * **QueryMsg::SwapVolume { pair, window_secs }** Returns the volume swapped in a pair during the
+/// last `window_secs` seconds using a [`SwapVolumeResponse`] object.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&ConfigResponse {
+            factory_addr: CONFIG.load(deps.storage)?.factory_addr,
+        }),
+        QueryMsg::RecentSwaps { pair, limit } => {
+            to_json_binary(&query_recent_swaps(deps, pair, limit)?)
+        }
+        QueryMsg::SwapVolume { pair, window_secs } => {
+            to_json_binary(&query_swap_volume(deps, env, pair, window_secs)?)
+        }
+    }
+}
+
+/// Returns the most recent swaps starting from the newest one.
+///
+/// * **pair** only returns swaps executed in this pair if set.
+///
+/// * **limit** is the maximum number of swaps to return.
+pub fn query_recent_swaps(
+    deps: Deps,
+    pair: Option<String>,
+    limit: u32,
+) -> StdResult<Vec<SwapRecord>> {
+    let pair = pair.map(|pair| deps.api.addr_validate(&pair)).transpose()?;
+    let limit = limit.min(MAX_LIMIT) as usize;
+
+    SWAP_RECORDS
+        .iter(deps.storage)?
+        .rev()
+        .filter(|record| match (record, &pair) {
+            (Ok(record), Some(pair)) => &record.pair == pair,
+            _ => true,
+        })
+        .take(limit)
+        .collect()
+}
+
+/// Returns the amounts offered and returned in a pair during the last `window_secs` seconds.
+///
+/// * **pair** is the pair contract address.
+///
+/// * **window_secs** is the length of the window in seconds.
+pub fn query_swap_volume(
+    deps: Deps,
+    env: Env,
+    pair: String,
+    window_secs: u64,
+) -> StdResult<SwapVolumeResponse> {
+    let pair = deps.api.addr_validate(&pair)?;
+    let window_start = env.block.time.seconds().saturating_sub(window_secs);
+
+    let mut response = SwapVolumeResponse {
+        offer_volume: vec![],
+        ask_volume: vec![],
+        swaps_count: 0,
+    };
+    for record in SWAP_RECORDS.iter(deps.storage)?.rev() {
+        let record = record?;
+        if record.timestamp < window_start {
+            break;
+        }
+        if record.pair != pair {
+            continue;
+        }
+
+        add_volume(&mut response.offer_volume, record.offer)?;
+        add_volume(&mut response.ask_volume, record.ask)?;
+        response.swaps_count += 1;
+    }
+
+    Ok(response)
+}
+
+/// Adds the asset amount to the matching entry of `volume` or appends the asset.
+fn add_volume(volume: &mut Vec<Asset>, asset: Asset) -> StdResult<()> {
+    if let Some(total) = volume.iter_mut().find(|total| total.info == asset.info) {
+        total.amount = total.amount.checked_add(asset.amount)?;
+    } else {
+        volume.push(asset);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,16 @@
+use astroport::pair_indexer::SwapRecord;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Addr;
+use cw_storage_plus::{Deque, Item};
+
+/// This structure stores the main parameters for the pair indexer contract.
+#[cw_serde]
+pub struct Config {
+    /// The factory contract whose pairs can record swaps
+    pub factory_addr: Addr,
+}
+
+/// Stores the contract config at the given key
+pub const CONFIG: Item<Config> = Item::new("config");
+/// Stores the most recent swaps from the oldest to the newest one
+pub const SWAP_RECORDS: Deque<SwapRecord> = Deque::new("swap_records");
@@ -0,0 +1,266 @@
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+};
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, ContractResult, Env, OwnedDeps, SystemError, SystemResult,
+    Uint128, WasmQuery,
+};
+
+use astroport::asset::{Asset, AssetInfo, AssetInfoExt, PairInfo};
+use astroport::factory::{PairType, QueryMsg as FactoryQueryMsg};
+use astroport::pair::QueryMsg as PairQueryMsg;
+use astroport::pair_indexer::{
+    ExecuteMsg, InstantiateMsg, QueryMsg, SwapRecord, SwapVolumeResponse, MAX_SWAP_RECORDS,
+};
+
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use crate::state::SWAP_RECORDS;
+
+fn asset_infos() -> Vec<AssetInfo> {
+    vec![
+        AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        },
+        AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        },
+    ]
+}
+
+fn pair_info(contract_addr: &str) -> PairInfo {
+    PairInfo {
+        asset_infos: asset_infos(),
+        contract_addr: Addr::unchecked(contract_addr),
+        liquidity_token: Addr::unchecked("liquidity0000"),
+        pair_type: PairType::Xyk {},
+        creator: None,
+        creation_height: 0,
+        creation_time: 0,
+    }
+}
+
+fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies();
+
+    // Both pairs report the same assets but only pair0000 is registered in the factory
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { contract_addr, msg } => match contract_addr.as_str() {
+            "factory" => match from_json(msg).unwrap() {
+                FactoryQueryMsg::Pair { .. } => SystemResult::Ok(ContractResult::Ok(
+                    to_json_binary(&pair_info("pair0000")).unwrap(),
+                )),
+                _ => unimplemented!(),
+            },
+            "pair0000" | "fake_pair" => match from_json(msg).unwrap() {
+                PairQueryMsg::Pair {} => SystemResult::Ok(ContractResult::Ok(
+                    to_json_binary(&pair_info(contract_addr)).unwrap(),
+                )),
+                _ => unimplemented!(),
+            },
+            _ => SystemResult::Err(SystemError::NoSuchContract {
+                addr: contract_addr.to_string(),
+            }),
+        },
+        _ => unimplemented!(),
+    });
+
+    let msg = InstantiateMsg {
+        factory_addr: "factory".to_string(),
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    deps
+}
+
+fn record_swap_msg(offer_amount: u128, ask_amount: u128) -> ExecuteMsg {
+    let asset_infos = asset_infos();
+    ExecuteMsg::RecordSwap {
+        offer: asset_infos[0].with_balance(offer_amount),
+        ask: asset_infos[1].with_balance(ask_amount),
+        fee: asset_infos[1].with_balance(ask_amount / 100),
+        sender: "trader".to_string(),
+    }
+}
+
+fn env_at(time: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(time);
+    env
+}
+
+#[test]
+fn only_registered_pairs_can_record_swaps() {
+    let mut deps = setup();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("trader", &[]),
+        record_swap_msg(100, 200),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("fake_pair", &[]),
+        record_swap_msg(100, 200),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("pair0000", &[]),
+        ExecuteMsg::RecordSwap {
+            offer: Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uatom".to_string(),
+                },
+                amount: Uint128::new(100),
+            },
+            ask: asset_infos()[1].with_balance(200u128),
+            fee: asset_infos()[1].with_balance(2u128),
+            sender: "trader".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AssetMismatch {});
+
+    let env = mock_env();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("pair0000", &[]),
+        record_swap_msg(100, 200),
+    )
+    .unwrap();
+
+    let swaps: Vec<SwapRecord> = from_json(
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RecentSwaps {
+                pair: None,
+                limit: 10,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        swaps,
+        vec![SwapRecord {
+            pair: Addr::unchecked("pair0000"),
+            offer: asset_infos()[0].with_balance(100u128),
+            ask: asset_infos()[1].with_balance(200u128),
+            fee: asset_infos()[1].with_balance(2u128),
+            timestamp: env.block.time.seconds(),
+            sender: Addr::unchecked("trader"),
+        }]
+    );
+}
+
+#[test]
+fn recent_swaps_and_volume() {
+    let mut deps = setup();
+
+    for (time, offer_amount) in [(0, 100), (100, 200), (200, 300)] {
+        execute(
+            deps.as_mut(),
+            env_at(time),
+            mock_info("pair0000", &[]),
+            record_swap_msg(offer_amount, offer_amount * 2),
+        )
+        .unwrap();
+    }
+
+    // The newest swaps are returned first
+    let swaps: Vec<SwapRecord> = from_json(
+        query(
+            deps.as_ref(),
+            env_at(200),
+            QueryMsg::RecentSwaps {
+                pair: Some("pair0000".to_string()),
+                limit: 2,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    let offer_amounts: Vec<_> = swaps.iter().map(|swap| swap.offer.amount.u128()).collect();
+    assert_eq!(offer_amounts, vec![300, 200]);
+
+    let swaps: Vec<SwapRecord> = from_json(
+        query(
+            deps.as_ref(),
+            env_at(200),
+            QueryMsg::RecentSwaps {
+                pair: Some("pair0001".to_string()),
+                limit: 10,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert!(swaps.is_empty());
+
+    // Only the last two swaps are within the window
+    let volume: SwapVolumeResponse = from_json(
+        query(
+            deps.as_ref(),
+            env_at(250),
+            QueryMsg::SwapVolume {
+                pair: "pair0000".to_string(),
+                window_secs: 150,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        volume,
+        SwapVolumeResponse {
+            offer_volume: vec![asset_infos()[0].with_balance(500u128)],
+            ask_volume: vec![asset_infos()[1].with_balance(1000u128)],
+            swaps_count: 2,
+        }
+    );
+}
+
+#[test]
+fn swap_records_are_capped() {
+    let mut deps = setup();
+
+    let old_record = SwapRecord {
+        pair: Addr::unchecked("pair0000"),
+        offer: asset_infos()[0].with_balance(1u128),
+        ask: asset_infos()[1].with_balance(1u128),
+        fee: asset_infos()[1].with_balance(0u128),
+        timestamp: 0,
+        sender: Addr::unchecked("trader"),
+    };
+    for _ in 0..MAX_SWAP_RECORDS {
+        SWAP_RECORDS
+            .push_back(deps.as_mut().storage, &old_record)
+            .unwrap();
+    }
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("pair0000", &[]),
+        record_swap_msg(100, 200),
+    )
+    .unwrap();
+
+    assert_eq!(
+        SWAP_RECORDS.len(deps.as_ref().storage).unwrap(),
+        MAX_SWAP_RECORDS
+    );
+    let newest = SWAP_RECORDS.back(deps.as_ref().storage).unwrap().unwrap();
+    assert_eq!(newest.offer.amount, Uint128::new(100));
+}
@@ -0,0 +1,190 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    attr, to_json_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdResult,
+};
+use cw2::set_contract_version;
+use cw_storage_plus::Bound;
+
+use astroport::asset::PairInfo;
+use astroport::pair::QueryMsg as PairQueryMsg;
+use astroport::pair_registry::{
+    ConfigResponse, ExecuteMsg, InstantiateMsg, PairMetadata, QueryMsg, RegisteredPair, MAX_TAGS,
+};
+use astroport::querier::{query_factory_config, query_pair_admin, query_pair_info};
+
+use crate::error::ContractError;
+use crate::state::{Config, CONFIG, PAIRS_BY_TAG, PAIR_METADATA};
+
+/// Contract name that is used for migration.
+const CONTRACT_NAME: &str = "astroport-pair-registry";
+/// Contract version that is used for migration.
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// The maximum number of pairs returned by [`QueryMsg::PairsByTag`]
+const MAX_LIMIT: u32 = 30;
+
+/// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let config = Config {
+        factory_addr: deps.api.addr_validate(&msg.factory_addr)?,
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "instantiate"),
+        attr("factory_addr", config.factory_addr),
+    ]))
+}
+
+/// Exposes execute functions available in the contract.
+///
+/// ## Variants
+/// * **ExecuteMsg::RegisterPair { pair, metadata }** Registers or replaces the metadata of a pair.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::RegisterPair { pair, metadata } => register_pair(deps, info, pair, metadata),
+    }
+}
+
+/// Registers the metadata of a pair created by the factory. Metadata registered earlier for
+/// the same pair is replaced.
+///
+/// * **pair** pair address.
+///
+/// * **metadata** pair metadata.
+///
+/// ## Executor
+/// Only the factory owner or the pair admin set in the factory can execute this.
+pub fn register_pair(
+    deps: DepsMut,
+    info: MessageInfo,
+    pair: String,
+    mut metadata: PairMetadata,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let pair = deps.api.addr_validate(&pair)?;
+
+    let factory_owner = query_factory_config(&deps.querier, &config.factory_addr)?.owner;
+    if info.sender != factory_owner {
+        let pair_admin = query_pair_admin(&deps.querier, &config.factory_addr, &pair)?;
+        if pair_admin != Some(info.sender) {
+            return Err(ContractError::Unauthorized {});
+        }
+    }
+
+    // Only pairs known by the factory can be registered
+    let pair_info: PairInfo = deps
+        .querier
+        .query_wasm_smart(&pair, &PairQueryMsg::Pair {})?;
+    let factory_pair = query_pair_info(&deps.querier, &config.factory_addr, &pair_info.asset_infos)
+        .map_err(|_| ContractError::UnknownPair(pair.to_string()))?;
+    if factory_pair.contract_addr != pair {
+        return Err(ContractError::UnknownPair(pair.to_string()));
+    }
+
+    metadata.display_name = metadata.display_name.trim().to_string();
+    if metadata.display_name.is_empty() {
+        return Err(ContractError::EmptyDisplayName {});
+    }
+
+    metadata.tags.sort();
+    metadata.tags.dedup();
+    if metadata.tags.iter().any(|tag| tag.is_empty()) {
+        return Err(ContractError::EmptyTag {});
+    }
+    if metadata.tags.len() > MAX_TAGS {
+        return Err(ContractError::TooManyTags(MAX_TAGS));
+    }
+
+    if let Some(old_metadata) = PAIR_METADATA.may_load(deps.storage, &pair)? {
+        for tag in &old_metadata.tags {
+            PAIRS_BY_TAG.remove(deps.storage, (tag.as_str(), &pair));
+        }
+    }
+    for tag in &metadata.tags {
+        PAIRS_BY_TAG.save(deps.storage, (tag.as_str(), &pair), &())?;
+    }
+    PAIR_METADATA.save(deps.storage, &pair, &metadata)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "register_pair"),
+        attr("pair", pair),
+        attr("display_name", metadata.display_name),
+        attr("tags", metadata.tags.join(",")),
+    ]))
+}
+
+/// Exposes all the queries available in the contract.
+///
+/// ## Queries
+/// * **QueryMsg::Config {}** Returns the contract configuration using a [`ConfigResponse`] object.
+///
+/// * **QueryMsg::PairMetadata { pair }** Returns the metadata registered for the pair.
+///
+/// * **QueryMsg::PairsByTag {
+///             tag,
+///             start_after,
+///             limit,
+///         }** Returns the pairs with the tag using a vector of [`RegisteredPair`] objects.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&ConfigResponse {
+            factory_addr: CONFIG.load(deps.storage)?.factory_addr,
+        }),
+        QueryMsg::PairMetadata { pair } => {
+            to_json_binary(&PAIR_METADATA.load(deps.storage, &deps.api.addr_validate(&pair)?)?)
+        }
+        QueryMsg::PairsByTag {
+            tag,
+            start_after,
+            limit,
+        } => to_json_binary(&query_pairs_by_tag(deps, tag, start_after, limit)?),
+    }
+}
+
+/// Returns the pairs with the tag ordered by pair address.
+///
+/// * **start_after** pair address to start reading from.
+///
+/// * **limit** number of pairs to read, at most [`MAX_LIMIT`].
+pub fn query_pairs_by_tag(
+    deps: Deps,
+    tag: String,
+    start_after: Option<String>,
+    limit: u32,
+) -> StdResult<Vec<RegisteredPair>> {
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+
+    PAIRS_BY_TAG
+        .prefix(&tag)
+        .keys(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit.min(MAX_LIMIT) as usize)
+        .map(|pair| {
+            let pair: Addr = pair?;
+            let metadata = PAIR_METADATA.load(deps.storage, &pair)?;
+            Ok(RegisteredPair { pair, metadata })
+        })
+        .collect()
+}
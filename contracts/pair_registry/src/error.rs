@@ -0,0 +1,24 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+/// This enum describes pair registry contract errors
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Pair {0} was not created by the factory")]
+    UnknownPair(String),
+
+    #[error("Display name must not be empty")]
+    EmptyDisplayName {},
+
+    #[error("Tags must not be empty")]
+    EmptyTag {},
+
+    #[error("A pair can have at most {0} tags")]
+    TooManyTags(usize),
+}
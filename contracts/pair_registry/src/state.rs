@@ -0,0 +1,18 @@
+use astroport::pair_registry::PairMetadata;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Addr;
+use cw_storage_plus::{Item, Map};
+
+/// This structure stores the main parameters for the pair registry contract.
+#[cw_serde]
+pub struct Config {
+    /// The Astroport factory whose pairs can be registered
+    pub factory_addr: Addr,
+}
+
+/// Stores the contract config at the given key
+pub const CONFIG: Item<Config> = Item::new("config");
+/// Stores the registered pair metadata. Key: pair address -> metadata
+pub const PAIR_METADATA: Map<&Addr, PairMetadata> = Map::new("pair_metadata");
+/// Indexes the registered pairs by tag. Key: (tag, pair address)
+pub const PAIRS_BY_TAG: Map<(&str, &Addr), ()> = Map::new("pairs_by_tag");
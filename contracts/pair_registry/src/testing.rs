@@ -0,0 +1,218 @@
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+};
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, ContractResult, Deps, OwnedDeps, SystemResult, WasmQuery,
+};
+
+use astroport::asset::{native_asset_info, PairInfo};
+use astroport::factory::{Config as FactoryConfig, PairType, QueryMsg as FactoryQueryMsg};
+use astroport::pair::QueryMsg as PairQueryMsg;
+use astroport::pair_registry::{
+    ExecuteMsg, InstantiateMsg, PairMetadata, QueryMsg, RegisteredPair, RiskLevel,
+};
+
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+
+fn pair_info(pair: &str, quote_denom: &str) -> PairInfo {
+    PairInfo {
+        asset_infos: vec![
+            native_asset_info("uluna".to_string()),
+            native_asset_info(quote_denom.to_string()),
+        ],
+        contract_addr: Addr::unchecked(pair),
+        liquidity_token: Addr::unchecked(format!("{pair}_lp")),
+        pair_type: PairType::Xyk {},
+        creator: None,
+        creation_height: 0,
+        creation_time: 0,
+    }
+}
+
+/// Mocks a factory owned by `owner` which created `pair1` and `pair2`. `fake_pair` has the
+/// same assets as `pair1` but wasn't created by the factory. All the pairs are administered
+/// by `pair_admin`.
+fn mock_factory(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>) {
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Raw { contract_addr, .. } if contract_addr == "factory" => {
+            SystemResult::Ok(ContractResult::Ok(
+                to_json_binary(&FactoryConfig {
+                    owner: Addr::unchecked("owner"),
+                    token_code_id: 0,
+                    generator_address: None,
+                    fee_address: None,
+                    whitelist_code_id: 0,
+                    coin_registry_address: Addr::unchecked("coin_registry"),
+                    multisig_module: None,
+                })
+                .unwrap(),
+            ))
+        }
+        WasmQuery::Smart { contract_addr, msg } if contract_addr == "factory" => {
+            match from_json(msg).unwrap() {
+                FactoryQueryMsg::Pair { asset_infos } => {
+                    let pair = if asset_infos[1] == native_asset_info("uusd".to_string()) {
+                        pair_info("pair1", "uusd")
+                    } else {
+                        pair_info("pair2", "uatom")
+                    };
+                    SystemResult::Ok(ContractResult::Ok(to_json_binary(&pair).unwrap()))
+                }
+                FactoryQueryMsg::PairAdmin { .. } => SystemResult::Ok(ContractResult::Ok(
+                    to_json_binary(&Some(Addr::unchecked("pair_admin"))).unwrap(),
+                )),
+                _ => unimplemented!(),
+            }
+        }
+        WasmQuery::Smart { contract_addr, msg } => match from_json(msg).unwrap() {
+            PairQueryMsg::Pair {} => {
+                let pair = match contract_addr.as_str() {
+                    "pair2" => pair_info("pair2", "uatom"),
+                    _ => pair_info(contract_addr, "uusd"),
+                };
+                SystemResult::Ok(ContractResult::Ok(to_json_binary(&pair).unwrap()))
+            }
+            _ => unimplemented!(),
+        },
+        _ => unimplemented!(),
+    });
+}
+
+fn metadata(display_name: &str, tags: &[&str]) -> PairMetadata {
+    PairMetadata {
+        display_name: display_name.to_string(),
+        icon_uri: None,
+        tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        risk_level: RiskLevel::Low,
+    }
+}
+
+fn pairs_by_tag(deps: Deps, tag: &str, start_after: Option<&str>) -> Vec<Addr> {
+    let pairs: Vec<RegisteredPair> = from_json(
+        &query(
+            deps,
+            mock_env(),
+            QueryMsg::PairsByTag {
+                tag: tag.to_string(),
+                start_after: start_after.map(|pair| pair.to_string()),
+                limit: 10,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    pairs.into_iter().map(|pair| pair.pair).collect()
+}
+
+#[test]
+fn register_pair() {
+    let mut deps = mock_dependencies();
+    mock_factory(&mut deps);
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        InstantiateMsg {
+            factory_addr: "factory".to_string(),
+        },
+    )
+    .unwrap();
+
+    let register = |pair: &str, metadata: PairMetadata| ExecuteMsg::RegisterPair {
+        pair: pair.to_string(),
+        metadata,
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("random", &[]),
+        register("pair1", metadata("LUNA-USD", &["stable"])),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        register("fake_pair", metadata("LUNA-USD", &["stable"])),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::UnknownPair("fake_pair".to_string()));
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        register("pair1", metadata(" ", &["stable"])),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::EmptyDisplayName {});
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        register("pair1", metadata("LUNA-USD", &["stable", ""])),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::EmptyTag {});
+
+    // Both the factory owner and the pair admin can register metadata
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        register("pair1", metadata("LUNA-USD", &["stable", "luna", "luna"])),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("pair_admin", &[]),
+        register("pair2", metadata("LUNA-ATOM", &["luna"])),
+    )
+    .unwrap();
+
+    let pair_metadata: PairMetadata = from_json(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PairMetadata {
+                pair: "pair1".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(pair_metadata, metadata("LUNA-USD", &["luna", "stable"]));
+
+    assert_eq!(
+        pairs_by_tag(deps.as_ref(), "luna", None),
+        vec![Addr::unchecked("pair1"), Addr::unchecked("pair2")]
+    );
+    assert_eq!(
+        pairs_by_tag(deps.as_ref(), "luna", Some("pair1")),
+        vec![Addr::unchecked("pair2")]
+    );
+    assert_eq!(
+        pairs_by_tag(deps.as_ref(), "stable", None),
+        vec![Addr::unchecked("pair1")]
+    );
+
+    // Replaced tags are removed from the index
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("pair_admin", &[]),
+        register("pair1", metadata("LUNA-USD", &["luna"])),
+    )
+    .unwrap();
+    assert_eq!(pairs_by_tag(deps.as_ref(), "stable", None), vec![]);
+    assert_eq!(
+        pairs_by_tag(deps.as_ref(), "luna", None),
+        vec![Addr::unchecked("pair1"), Addr::unchecked("pair2")]
+    );
+}
@@ -1,4 +1,5 @@
-use astroport::pair::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use astroport::pair::{InstantiateMsg, MigrateMsg, QueryMsg};
+use astroport::pair_stable::ExecuteMsg;
 use cosmwasm_schema::write_api;
 
 fn main() {
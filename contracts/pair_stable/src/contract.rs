@@ -7,7 +7,7 @@ use cosmwasm_std::entry_point;
 use cosmwasm_std::{
     attr, from_json, to_json_binary, wasm_execute, wasm_instantiate, Addr, Binary, CosmosMsg,
     Decimal, Decimal256, Deps, DepsMut, Env, Fraction, MessageInfo, QuerierWrapper, Reply,
-    Response, StdError, StdResult, SubMsg, SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
+    Response, StdError, StdResult, SubMsg, SubMsgResponse, SubMsgResult, Uint128, Uint256, WasmMsg,
 };
 use cw2::{get_contract_version, set_contract_version};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse};
@@ -22,28 +22,37 @@ use astroport::asset::{
 use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
 use astroport::cosmwasm_ext::IntegerToDecimal;
 use astroport::factory::PairType;
+use astroport::math::assert_slippage_tolerance;
 use astroport::pair::{
     ConfigResponse, FeeShareConfig, InstantiateMsg, StablePoolParams, StablePoolUpdateParams,
-    DEFAULT_SLIPPAGE, MAX_ALLOWED_SLIPPAGE, MAX_FEE_SHARE_BPS, MIN_TRADE_SIZE,
+    DEFAULT_SLIPPAGE, MAX_ALLOWED_SLIPPAGE, MAX_FEE_SHARE_BPS, MAX_PRICE_BANDS, MIN_TRADE_SIZE,
 };
 
 use crate::migration::{migrate_config_from_v21, migrate_config_to_v210};
-use astroport::observation::{query_observation, PrecommitObservation, OBSERVATIONS_SIZE};
+use astroport::observation::{
+    query_observation, query_observations_twap, PrecommitObservation, OBSERVATIONS_SIZE,
+};
 use astroport::pair::{
-    Cw20HookMsg, ExecuteMsg, MigrateMsg, PoolResponse, QueryMsg, ReverseSimulationResponse,
-    SimulationResponse, StablePoolConfig,
+    BandInfo, Cw20HookMsg, InvariantResponse, LiquidityConcentrationResponse, MigrateMsg,
+    OraclePriceResponse, PoolRatioResponse, PoolResponse, QueryMsg, ReverseSimulationResponse,
+    SimulateProvideResponse, SimulationResponse, StablePoolConfig, BALANCED_PEG_DEVIATION_BPS,
+    ORACLE_TWAP_PERIOD,
 };
+use astroport::pair_stable::ExecuteMsg;
 use astroport::querier::{query_factory_config, query_fee_info, query_supply};
 use astroport::token::InstantiateMsg as TokenInstantiateMsg;
-use astroport::DecimalCheckedOps;
+use astroport::{to_decimal, DecimalCheckedOps};
 use astroport_circular_buffer::BufferManager;
 
 use crate::error::ContractError;
 use crate::math::{
-    calc_y, compute_d, AMP_PRECISION, MAX_AMP, MAX_AMP_CHANGE, MIN_AMP_CHANGING_TIME,
+    base_reserve_at_price, calc_y, compute_d, spot_price, AMP_PRECISION, MAX_AMP, MAX_AMP_CHANGE,
+    MIN_AMP_CHANGING_TIME,
 };
 use crate::state::{
-    get_precision, store_precisions, Config, CONFIG, OBSERVATIONS, OWNERSHIP_PROPOSAL,
+    get_precision, save_config_with_hash, store_precisions, Config, TimelockProposal, CONFIG,
+    CONFIG_HASH, MAX_TIMELOCK_DELAY, OBSERVATIONS, OWNERSHIP_PROPOSAL, TIMELOCK_CONFIG,
+    TIMELOCK_PROPOSALS,
 };
 use crate::utils::{
     accumulate_swap_sizes, adjust_precision, check_asset_infos, check_assets, check_cw20_in_pool,
@@ -96,6 +105,9 @@ pub fn instantiate(
             liquidity_token: Addr::unchecked(""),
             asset_infos: msg.asset_infos.clone(),
             pair_type: PairType::Stable {},
+            creator: None,
+            creation_height: 0,
+            creation_time: 0,
         },
         factory_addr,
         block_time_last: 0,
@@ -107,7 +119,7 @@ pub fn instantiate(
         fee_share: None,
     };
 
-    CONFIG.save(deps.storage, &config)?;
+    save_config_with_hash(deps.storage, &config)?;
     BufferManager::init(deps.storage, OBSERVATIONS, OBSERVATIONS_SIZE)?;
 
     let token_name = format_lp_token_name(&msg.asset_infos, &deps.querier)?;
@@ -157,7 +169,7 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
                 .map_err(|e| StdError::generic_err(format!("{e}")))?;
             config.pair_info.liquidity_token =
                 deps.api.addr_validate(&init_response.contract_address)?;
-            CONFIG.save(deps.storage, &config)?;
+            save_config_with_hash(deps.storage, &config)?;
             Ok(Response::new()
                 .add_attribute("liquidity_token_addr", config.pair_info.liquidity_token))
         }
@@ -187,6 +199,12 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
 ///             max_spread,
 ///             to,
 ///         }** Performs an swap using the specified parameters.
+///
+/// * **ExecuteMsg::ProposeConfigChange { changes }** Schedules a config update after the timelock delay.
+///
+/// * **ExecuteMsg::ExecuteProposal { proposal_id }** Applies a config update once its delay has passed.
+///
+/// * **ExecuteMsg::CancelProposal { proposal_id }** Drops a config update before its delay has passed.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -199,10 +217,18 @@ pub fn execute(
         ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
         ExecuteMsg::ProvideLiquidity {
             assets,
+            slippage_tolerance,
             auto_stake,
             receiver,
-            ..
-        } => provide_liquidity(deps, env, info, assets, auto_stake, receiver),
+        } => provide_liquidity(
+            deps,
+            env,
+            info,
+            assets,
+            slippage_tolerance,
+            auto_stake,
+            receiver,
+        ),
         ExecuteMsg::Swap {
             offer_asset,
             ask_asset_info,
@@ -259,15 +285,19 @@ pub fn execute(
         }
         ExecuteMsg::ClaimOwnership {} => {
             claim_ownership(deps, info, env, OWNERSHIP_PROPOSAL, |deps, new_owner| {
-                CONFIG.update::<_, StdError>(deps.storage, |mut config| {
-                    config.owner = Some(new_owner);
-                    Ok(config)
-                })?;
-
-                Ok(())
+                let mut config = CONFIG.load(deps.storage)?;
+                config.owner = Some(new_owner);
+                save_config_with_hash(deps.storage, &config)
             })
             .map_err(|e| e.into())
         }
+        ExecuteMsg::ProposeConfigChange { changes } => {
+            propose_config_change(deps, env, info, changes)
+        }
+        ExecuteMsg::ExecuteProposal { proposal_id } => {
+            execute_proposal(deps, env, info, proposal_id)
+        }
+        ExecuteMsg::CancelProposal { proposal_id } => cancel_proposal(deps, env, info, proposal_id),
     }
 }
 
@@ -316,6 +346,7 @@ pub fn receive_cw20(
             cw20_msg.amount,
             assets,
         ),
+        Cw20HookMsg::ProvideLiquidity { .. } => Err(ContractError::NotSupported {}),
     }
 }
 
@@ -323,6 +354,8 @@ pub fn receive_cw20(
 ///
 /// * **assets** vector with assets available in the pool.
 ///
+/// * **slippage_tolerance** if set, the deposits must be made in the pool ratio with this tolerance.
+///
 /// * **auto_stake** determines whether the resulting LP tokens are automatically staked in
 /// the Generator contract to receive token incentives.
 ///
@@ -334,6 +367,7 @@ pub fn provide_liquidity(
     env: Env,
     info: MessageInfo,
     assets: Vec<Asset>,
+    slippage_tolerance: Option<Decimal>,
     auto_stake: Option<bool>,
     receiver: Option<String>,
 ) -> Result<Response, ContractError> {
@@ -423,6 +457,19 @@ pub fn provide_liquidity(
         }
     }
 
+    if let Some(slippage_tolerance) = slippage_tolerance {
+        if slippage_tolerance > Decimal::from_str(MAX_ALLOWED_SLIPPAGE)? {
+            return Err(ContractError::AllowedSpreadAssertion {});
+        }
+
+        let (deposits, reserves): (Vec<_>, Vec<_>) = assets_collection
+            .iter()
+            .map(|(deposit, pool)| (deposit.clone(), *pool))
+            .unzip();
+        let amounts: Vec<_> = deposits.iter().map(|deposit| deposit.amount).collect();
+        assert_slippage_tolerance(&deposits, &amounts, &reserves, slippage_tolerance)?;
+    }
+
     let assets_collection = assets_collection
         .iter()
         .cloned()
@@ -777,6 +824,29 @@ pub fn calculate_maker_fee(
 /// pool using a [`CumulativePricesResponse`] object.
 ///
 /// * **QueryMsg::Config {}** Returns the configuration for the pair contract using a [`ConfigResponse`] object.
+///
+/// * **QueryMsg::SimulateProvideLiquidity { assets }** Returns the amount of LP tokens minted for the
+/// specified deposit using a [`SimulateProvideResponse`] object.
+///
+/// * **QueryMsg::LiquidityConcentration { price_bands }** Returns how the reserves are distributed
+/// between the price bands using a [`LiquidityConcentrationResponse`] object.
+///
+/// * **QueryMsg::Price { base, quote, amount }** Returns the oracle price of the base asset
+/// denominated in the quote asset using an [`OraclePriceResponse`] object.
+///
+/// * **QueryMsg::ConfigHash {}** Returns the config hash stored at the last config update.
+///
+/// * **QueryMsg::VerifyConfig { expected_hash }** Returns whether the current config hash matches the expected one.
+///
+/// * **QueryMsg::Invariant {}** Returns the current pool invariant using an [`InvariantResponse`] object.
+///
+/// * **QueryMsg::PoolRatio {}** Returns the share of every asset in the pool reserves using a
+/// [`PoolRatioResponse`] object.
+///
+/// * **QueryMsg::DepegAlerts { threshold_bps }** Returns the pool ratios using a [`PoolRatioResponse`]
+/// object or an error if any of them deviates more than the threshold from the peg.
+///
+/// * **QueryMsg::ContractInfo {}** Returns the cw2 contract name and version.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -804,10 +874,117 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         }
         QueryMsg::Config {} => to_json_binary(&query_config(deps, env)?),
         QueryMsg::QueryComputeD {} => to_json_binary(&query_compute_d(deps, env)?),
+        QueryMsg::SimulateProvideLiquidity { assets } => to_json_binary(
+            &query_simulate_provide(deps, env, assets)
+                .map_err(|err| StdError::generic_err(err.to_string()))?,
+        ),
+        QueryMsg::LiquidityConcentration { price_bands } => {
+            to_json_binary(&query_liquidity_concentration(deps, env, price_bands)?)
+        }
+        QueryMsg::Price {
+            base,
+            quote,
+            amount,
+        } => to_json_binary(&query_oracle_price(deps, env, base, quote, amount)?),
+        QueryMsg::ConfigHash {} => to_json_binary(&CONFIG_HASH.load(deps.storage)?),
+        QueryMsg::VerifyConfig { expected_hash } => {
+            to_json_binary(&(CONFIG.load(deps.storage)?.hash()? == expected_hash))
+        }
+        QueryMsg::Invariant {} => to_json_binary(&query_invariant(deps, env)?),
+        QueryMsg::PoolRatio {} => to_json_binary(&query_pool_ratio(deps, env)?),
+        QueryMsg::DepegAlerts { threshold_bps } => {
+            to_json_binary(&query_depeg_alerts(deps, env, threshold_bps)?)
+        }
+        QueryMsg::ContractInfo {} => to_json_binary(&get_contract_version(deps.storage)?),
         _ => Err(StdError::generic_err("Query is not supported")),
     }
 }
 
+/// Returns the price of `amount` of `base` denominated in `quote`. The spot price is computed
+/// from the stableswap invariant and the TWAP over the last [`ORACLE_TWAP_PERIOD`] is derived
+/// from the price observations.
+///
+/// * **amount** amount of the base asset to price. A zero amount returns the marginal price.
+pub fn query_oracle_price(
+    deps: Deps,
+    env: Env,
+    base: AssetInfo,
+    quote: AssetInfo,
+    amount: Uint128,
+) -> StdResult<OraclePriceResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let asset_infos = &config.pair_info.asset_infos;
+    if base.equal(&quote) || !asset_infos.contains(&base) || !asset_infos.contains(&quote) {
+        return Err(StdError::generic_err(
+            "Base and quote assets must be different pool assets",
+        ));
+    }
+    let base_first = asset_infos[0].equal(&base);
+
+    let amp = compute_current_amp(&config, &env)?;
+    let precisions = asset_infos
+        .iter()
+        .map(|asset_info| get_precision(deps.storage, asset_info))
+        .collect::<StdResult<Vec<_>>>()?;
+    let pools = config
+        .pair_info
+        .query_pools(&deps.querier, &env.contract.address)?
+        .into_iter()
+        .zip(&precisions)
+        .map(|(pool, precision)| Decimal256::with_precision(pool.amount, *precision))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let marginal_price = if pools.iter().any(|pool| pool.is_zero()) {
+        Decimal::zero()
+    } else {
+        // The spot price is denominated in whole tokens while the observations use raw amounts
+        let price = spot_price(amp, &pools)?
+            * Decimal256::from_ratio(
+                10u128.pow(precisions[1].into()),
+                10u128.pow(precisions[0].into()),
+            );
+        let price = if base_first {
+            price
+        } else {
+            price.inv().unwrap_or_default()
+        };
+        to_decimal(price)?
+    };
+
+    let execution_price = if amount.is_zero() || marginal_price.is_zero() {
+        marginal_price
+    } else {
+        let simulation =
+            query_simulation(deps, env.clone(), Asset { info: base, amount }, Some(quote))?;
+        Decimal::from_ratio(
+            simulation.return_amount + simulation.commission_amount,
+            amount,
+        )
+    };
+
+    // Observed prices are of the second asset denominated in the first one
+    let twap = query_observations_twap(deps, &env, OBSERVATIONS, ORACLE_TWAP_PERIOD)?.map(
+        |(price, period)| {
+            if base_first {
+                (price.inv().unwrap_or_default(), period)
+            } else {
+                (price, period)
+            }
+        },
+    );
+    let last_updated = BufferManager::new(deps.storage, OBSERVATIONS)?
+        .read_last(deps.storage)?
+        .map(|observation| observation.ts)
+        .unwrap_or_default();
+
+    Ok(OraclePriceResponse::new(
+        marginal_price,
+        execution_price,
+        twap,
+        last_updated,
+    ))
+}
+
 /// Returns the amounts of assets in the pair contract as well as the amount of LP
 /// tokens currently minted in an object of type [`PoolResponse`].
 pub fn query_pool(deps: Deps) -> StdResult<PoolResponse> {
@@ -1072,6 +1249,9 @@ pub fn migrate(mut deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Respons
         _ => return Err(ContractError::MigrationError {}),
     }
 
+    let config = CONFIG.load(deps.storage)?;
+    CONFIG_HASH.save(deps.storage, &config.hash()?)?;
+
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     Ok(Response::new()
@@ -1099,22 +1279,53 @@ pub fn update_config(
     info: MessageInfo,
     params: Binary,
 ) -> Result<Response, ContractError> {
-    let mut config = CONFIG.load(deps.storage)?;
-    let factory_config = query_factory_config(&deps.querier, &config.factory_addr)?;
-
-    if info.sender
-        != if let Some(ref owner) = config.owner {
-            owner.to_owned()
-        } else {
-            factory_config.owner
-        }
+    let config = CONFIG.load(deps.storage)?;
+    assert_owner(&deps.querier, &config, &info.sender)?;
+
+    let params = from_json::<StablePoolUpdateParams>(&params)?;
+    // Amp changes can be stopped immediately in case they were started by mistake
+    if !matches!(params, StablePoolUpdateParams::StopChangingAmp {})
+        && TIMELOCK_CONFIG
+            .may_load(deps.storage)?
+            .unwrap_or_default()
+            .min_delay
+            > 0
     {
+        return Err(ContractError::TimelockActive {});
+    }
+
+    apply_config_changes(deps, env, config, params)
+}
+
+/// Returns an error if `sender` isn't the pair owner. The factory owner is the pair owner
+/// unless the owner is set in the config.
+fn assert_owner(
+    querier: &QuerierWrapper,
+    config: &Config,
+    sender: &Addr,
+) -> Result<(), ContractError> {
+    let owner = match &config.owner {
+        Some(owner) => owner.clone(),
+        None => query_factory_config(querier, &config.factory_addr)?.owner,
+    };
+    if *sender != owner {
         return Err(ContractError::Unauthorized {});
     }
 
+    Ok(())
+}
+
+/// Applies the config changes which were either sent with [`ExecuteMsg::UpdateConfig`] or
+/// proposed with [`ExecuteMsg::ProposeConfigChange`].
+fn apply_config_changes(
+    deps: DepsMut,
+    env: Env,
+    mut config: Config,
+    params: StablePoolUpdateParams,
+) -> Result<Response, ContractError> {
     let mut response = Response::default();
 
-    match from_json::<StablePoolUpdateParams>(&params)? {
+    match params {
         StablePoolUpdateParams::StartChangingAmp {
             next_amp,
             next_amp_time,
@@ -1139,7 +1350,7 @@ pub fn update_config(
                 recipient: deps.api.addr_validate(&fee_share_address)?,
             });
 
-            CONFIG.save(deps.storage, &config)?;
+            save_config_with_hash(deps.storage, &config)?;
 
             response.attributes.push(attr("action", "enable_fee_share"));
             response
@@ -1153,16 +1364,135 @@ pub fn update_config(
             // Disable fee sharing for this contract by setting bps and
             // address back to None
             config.fee_share = None;
-            CONFIG.save(deps.storage, &config)?;
+            save_config_with_hash(deps.storage, &config)?;
             response
                 .attributes
                 .push(attr("action", "disable_fee_share"));
         }
+        StablePoolUpdateParams::UpdateTimelockDelay { min_delay } => {
+            if min_delay > MAX_TIMELOCK_DELAY {
+                return Err(ContractError::TimelockDelayTooLong {});
+            }
+
+            let mut timelock = TIMELOCK_CONFIG.may_load(deps.storage)?.unwrap_or_default();
+            timelock.min_delay = min_delay;
+            TIMELOCK_CONFIG.save(deps.storage, &timelock)?;
+
+            response
+                .attributes
+                .push(attr("action", "update_timelock_delay"));
+            response
+                .attributes
+                .push(attr("min_delay", min_delay.to_string()));
+        }
     }
 
     Ok(response)
 }
 
+/// Schedules a config update which can be executed once the timelock delay has passed.
+///
+/// * **changes** the serialized [`StablePoolUpdateParams`] to apply.
+///
+/// ## Executor
+/// Only the pair owner can execute this.
+fn propose_config_change(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    changes: Binary,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_owner(&deps.querier, &config, &info.sender)?;
+
+    let changes = from_json::<StablePoolUpdateParams>(&changes)?;
+    let mut timelock = TIMELOCK_CONFIG.may_load(deps.storage)?.unwrap_or_default();
+    let proposal_id = timelock.next_proposal_id;
+    let proposal = TimelockProposal {
+        changes,
+        proposed_at: env.block.time.seconds(),
+        proposer: info.sender,
+    };
+    let executable_at = proposal.executable_at(&timelock);
+    TIMELOCK_PROPOSALS.save(deps.storage, proposal_id, &proposal)?;
+    timelock.next_proposal_id += 1;
+    TIMELOCK_CONFIG.save(deps.storage, &timelock)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "propose_config_change"),
+        attr("proposal_id", proposal_id.to_string()),
+        attr("executable_at", executable_at.to_string()),
+    ]))
+}
+
+/// Applies a config update proposal once the current timelock delay has passed since it was
+/// proposed and before it expires [`crate::state::PROPOSAL_EXPIRY_PERIOD`] seconds later. The changes are
+/// validated when they are applied.
+///
+/// ## Executor
+/// Only the pair owner can execute this.
+fn execute_proposal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_owner(&deps.querier, &config, &info.sender)?;
+
+    let proposal = TIMELOCK_PROPOSALS
+        .may_load(deps.storage, proposal_id)?
+        .ok_or(ContractError::ProposalNotFound(proposal_id))?;
+    let timelock = TIMELOCK_CONFIG.may_load(deps.storage)?.unwrap_or_default();
+    let executable_at = proposal.executable_at(&timelock);
+    if env.block.time.seconds() < executable_at {
+        return Err(ContractError::ProposalNotReady {
+            proposal_id,
+            executable_at,
+        });
+    }
+    if env.block.time.seconds() >= proposal.expires_at(&timelock) {
+        return Err(ContractError::ProposalExpired(proposal_id));
+    }
+    TIMELOCK_PROPOSALS.remove(deps.storage, proposal_id);
+
+    Ok(apply_config_changes(deps, env, config, proposal.changes)?
+        .add_attribute("proposal_id", proposal_id.to_string()))
+}
+
+/// Drops a config update proposal within its delay window or once it has expired.
+///
+/// ## Executor
+/// Within the delay window only the proposer or the pair owner can execute this, so other
+/// addresses can't cancel every proposal and block config changes for good. Anyone can cancel
+/// an expired proposal.
+fn cancel_proposal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let proposal = TIMELOCK_PROPOSALS
+        .may_load(deps.storage, proposal_id)?
+        .ok_or(ContractError::ProposalNotFound(proposal_id))?;
+    let timelock = TIMELOCK_CONFIG.may_load(deps.storage)?.unwrap_or_default();
+    let now = env.block.time.seconds();
+    if now < proposal.executable_at(&timelock) {
+        if info.sender != proposal.proposer {
+            assert_owner(&deps.querier, &config, &info.sender)?;
+        }
+    } else if now < proposal.expires_at(&timelock) {
+        return Err(ContractError::ProposalDelayPassed(proposal_id));
+    }
+    TIMELOCK_PROPOSALS.remove(deps.storage, proposal_id);
+
+    Ok(Response::new().add_attributes([
+        attr("action", "cancel_proposal"),
+        attr("proposal_id", proposal_id.to_string()),
+    ]))
+}
+
 /// Start changing the AMP value.
 ///
 /// * **next_amp** new value for AMP.
@@ -1202,7 +1532,7 @@ fn start_changing_amp(
     config.init_amp_time = block_time;
     config.next_amp_time = next_amp_time;
 
-    CONFIG.save(deps.storage, &config)?;
+    save_config_with_hash(deps.storage, &config)?;
 
     Ok(())
 }
@@ -1218,7 +1548,7 @@ fn stop_changing_amp(mut config: Config, deps: DepsMut, env: Env) -> StdResult<(
     config.next_amp_time = block_time;
 
     // now (block_time < next_amp_time) is always False, so we return the saved AMP
-    CONFIG.save(deps.storage, &config)?;
+    save_config_with_hash(deps.storage, &config)?;
 
     Ok(())
 }
@@ -1238,3 +1568,247 @@ fn query_compute_d(deps: Deps, env: Env) -> StdResult<Uint128> {
         .map_err(|_| StdError::generic_err("Failed to calculate the D"))?
         .to_uint128_with_precision(config.greatest_precision)
 }
+
+/// Returns the StableSwap D of the pool reserves at the current amplification using an
+/// [`InvariantResponse`] object.
+fn query_invariant(deps: Deps, env: Env) -> StdResult<InvariantResponse> {
+    Ok(InvariantResponse {
+        value: query_compute_d(deps, env)?.into(),
+        formula: "StableSwap D".to_string(),
+    })
+}
+
+/// Returns the share of every asset in the sum of the pool reserves in a [`PoolRatioResponse`]
+/// object. Reserves are compared at the same precision, so the peg of every ratio is `1 / n_assets`.
+pub fn query_pool_ratio(deps: Deps, env: Env) -> StdResult<PoolRatioResponse> {
+    Ok(compute_pool_ratio(deps, env)?.0)
+}
+
+/// Returns the pool ratios in a [`PoolRatioResponse`] object. Fails with the details of the most
+/// depegged asset if its ratio deviates more than `threshold_bps` from the peg, so the query can be
+/// used to trigger circuit breakers.
+pub fn query_depeg_alerts(
+    deps: Deps,
+    env: Env,
+    threshold_bps: u16,
+) -> StdResult<PoolRatioResponse> {
+    let (response, asset_info) = compute_pool_ratio(deps, env)?;
+    if response.peg_deviation_bps.unsigned_abs() > threshold_bps as u64 {
+        return Err(StdError::generic_err(format!(
+            "Asset {asset_info} deviates {} bps from the peg, more than the {threshold_bps} bps threshold",
+            response.peg_deviation_bps
+        )));
+    }
+
+    Ok(response)
+}
+
+/// Computes the [`PoolRatioResponse`] of the pool along with the asset whose ratio deviates the
+/// most from the peg.
+fn compute_pool_ratio(deps: Deps, env: Env) -> StdResult<(PoolRatioResponse, AssetInfo)> {
+    let config = CONFIG.load(deps.storage)?;
+    let pools = config
+        .pair_info
+        .query_pools(&deps.querier, &env.contract.address)?
+        .into_iter()
+        .map(|pool| {
+            Decimal256::with_precision(pool.amount, get_precision(deps.storage, &pool.info)?)
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    let total_reserves = pools
+        .iter()
+        .try_fold(Decimal256::zero(), |acc, pool| acc.checked_add(*pool))?;
+    if total_reserves.is_zero() {
+        return Err(StdError::generic_err("The pool is empty"));
+    }
+
+    let peg = Decimal256::from_ratio(1u8, pools.len() as u64);
+    let mut ratios = vec![];
+    let mut peg_deviation_bps: i64 = 0;
+    let mut depegged_asset = 0;
+    for (i, pool) in pools.into_iter().enumerate() {
+        let ratio = pool / total_reserves;
+        // Ratios don't exceed 1, so the deviation fits into 10000 bps
+        let deviation_bps = Uint128::try_from(
+            (ratio.abs_diff(peg) * Decimal256::from_ratio(10000u16, 1u8)).to_uint_floor(),
+        )?
+        .u128() as i64;
+        if deviation_bps > peg_deviation_bps.abs() {
+            peg_deviation_bps = if ratio >= peg {
+                deviation_bps
+            } else {
+                -deviation_bps
+            };
+            depegged_asset = i;
+        }
+        ratios.push(to_decimal(ratio)?);
+    }
+
+    Ok((
+        PoolRatioResponse {
+            ratios,
+            peg_deviation_bps,
+            is_balanced: peg_deviation_bps.unsigned_abs() < BALANCED_PEG_DEVIATION_BPS,
+        },
+        config.pair_info.asset_infos[depegged_asset].clone(),
+    ))
+}
+
+/// Returns the share of the reserves traded within each price band in a
+/// [`LiquidityConcentrationResponse`] object. The base reserve at each band bound is found on the
+/// stableswap curve of the current invariant, the amount traded within the band is the
+/// difference between the two.
+///
+/// * **price_bands** `(lower, upper)` prices of the first asset denominated in the second one.
+pub fn query_liquidity_concentration(
+    deps: Deps,
+    env: Env,
+    price_bands: Vec<(Decimal, Decimal)>,
+) -> StdResult<LiquidityConcentrationResponse> {
+    if price_bands.len() > MAX_PRICE_BANDS {
+        return Err(StdError::generic_err(format!(
+            "At most {MAX_PRICE_BANDS} price bands can be queried"
+        )));
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let amp = compute_current_amp(&config, &env)?;
+    let pools = config
+        .pair_info
+        .query_pools(&deps.querier, &env.contract.address)?
+        .into_iter()
+        .map(|pool| {
+            Decimal256::with_precision(pool.amount, get_precision(deps.storage, &pool.info)?)
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    let base_precision = get_precision(deps.storage, &config.pair_info.asset_infos[0])?;
+
+    let d = if pools.iter().any(|pool| pool.is_zero()) {
+        Decimal256::zero()
+    } else {
+        compute_d(amp, &pools)?
+    };
+    let total_reserves = pools[0] + pools[1];
+
+    let bands = price_bands
+        .into_iter()
+        .map(|(lower, upper)| {
+            if lower.is_zero() || lower >= upper {
+                return Err(StdError::generic_err(format!(
+                    "Invalid price band ({lower}, {upper})"
+                )));
+            }
+
+            if d.is_zero() {
+                return Ok(BandInfo {
+                    price_band: (lower, upper),
+                    liquidity_fraction: Decimal::zero(),
+                    depth_in_base: Uint128::zero(),
+                });
+            }
+
+            // A lower price of the base asset corresponds to a larger base reserve
+            let depth = d
+                * (base_reserve_at_price(amp, lower.into())?
+                    - base_reserve_at_price(amp, upper.into())?);
+            let liquidity_fraction = (depth / total_reserves).min(Decimal256::one());
+
+            Ok(BandInfo {
+                price_band: (lower, upper),
+                liquidity_fraction: to_decimal(liquidity_fraction)?,
+                depth_in_base: depth.to_uint128_with_precision(base_precision)?,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(LiquidityConcentrationResponse { bands })
+}
+
+/// Returns the amount of LP tokens minted for a deposit in a [`SimulateProvideResponse`] object.
+/// LP tokens are minted for the invariant growth. The price impact compares the growth with the
+/// deposit value at the 1:1 peg.
+///
+/// * **assets** are the assets to deposit. Omitted pool assets are deposited with a zero amount.
+pub fn query_simulate_provide(
+    deps: Deps,
+    env: Env,
+    assets: Vec<Asset>,
+) -> Result<SimulateProvideResponse, ContractError> {
+    check_assets(deps.api, &assets)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    if assets.len() > config.pair_info.asset_infos.len() {
+        return Err(ContractError::InvalidNumberOfAssets(
+            config.pair_info.asset_infos.len(),
+        ));
+    }
+    if let Some(asset) = assets
+        .iter()
+        .find(|asset| !config.pair_info.asset_infos.contains(&asset.info))
+    {
+        return Err(ContractError::InvalidAsset(asset.info.to_string()));
+    }
+    if assets.iter().all(|asset| asset.amount.is_zero()) {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let (pools, total_share) = pool_info(deps.querier, &config)?;
+
+    let mut deposit_value = Decimal256::zero();
+    let mut old_balances = vec![];
+    let mut new_balances = vec![];
+    for pool in pools {
+        let deposit = assets
+            .iter()
+            .find(|asset| asset.info.equal(&pool.info))
+            .map(|asset| asset.amount)
+            .unwrap_or_default();
+        // We cannot put a zero amount into an empty pool
+        if deposit.is_zero() && pool.amount.is_zero() {
+            return Err(ContractError::InvalidProvideLPsWithSingleToken {});
+        }
+
+        let precision = get_precision(deps.storage, &pool.info)?;
+        let deposit = Decimal256::with_precision(deposit, precision)?;
+        let pool = Decimal256::with_precision(pool.amount, precision)?;
+        deposit_value += deposit;
+        old_balances.push(pool);
+        new_balances.push(pool + deposit);
+    }
+
+    let amp = compute_current_amp(&config, &env)?;
+    let deposit_d = compute_d(amp, &new_balances)?;
+
+    if total_share.is_zero() {
+        // The initial deposit sets the pool price, so it has no price impact
+        let lp_amount = deposit_d
+            .to_uint128_with_precision(config.greatest_precision)?
+            .checked_sub(MINIMUM_LIQUIDITY_AMOUNT)
+            .map_err(|_| ContractError::MinimumLiquidityAmountError {})?;
+
+        return Ok(SimulateProvideResponse {
+            lp_amount,
+            fee: None,
+            price_impact_bps: 0,
+        });
+    }
+
+    let init_d = compute_d(amp, &old_balances)?;
+    let d_growth = deposit_d.saturating_sub(init_d);
+    let lp_amount = Decimal256::with_precision(total_share, config.greatest_precision)?
+        .checked_multiply_ratio(d_growth, init_d)?
+        .to_uint128_with_precision(config.greatest_precision)?;
+
+    // Depositing the scarce asset grows D by more than the deposit value, so there is no impact
+    let price_impact =
+        Decimal256::one().saturating_sub(d_growth.checked_div(deposit_value).unwrap_or_default());
+    let price_impact_bps = Uint128::try_from(Uint256::from(10000u16) * price_impact)
+        .map_err(StdError::from)?
+        .u128() as u64;
+
+    Ok(SimulateProvideResponse {
+        lp_amount,
+        fee: None,
+        price_impact_bps,
+    })
+}
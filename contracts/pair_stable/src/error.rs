@@ -1,10 +1,16 @@
-use cosmwasm_std::{CheckedMultiplyRatioError, ConversionOverflowError, OverflowError, StdError};
+use cosmwasm_std::{
+    CheckedMultiplyRatioError, ConversionOverflowError, Decimal, Decimal256, OverflowError,
+    StdError,
+};
 use thiserror::Error;
 
-use astroport::{asset::MINIMUM_LIQUIDITY_AMOUNT, pair::MAX_FEE_SHARE_BPS};
+use astroport::{
+    asset::MINIMUM_LIQUIDITY_AMOUNT, math::SlippageToleranceError, pair::MAX_FEE_SHARE_BPS,
+};
 use astroport_circular_buffer::error::BufferError;
 
 use crate::math::{MAX_AMP, MAX_AMP_CHANGE, MIN_AMP_CHANGING_TIME};
+use crate::state::MAX_TIMELOCK_DELAY;
 
 /// This enum describes stableswap pair contract errors
 #[derive(Error, Debug, PartialEq)]
@@ -60,6 +66,30 @@ pub enum ContractError {
     )]
     MinAmpChangingTimeAssertion {},
 
+    #[error("Config changes must be proposed while the timelock is enabled")]
+    TimelockActive {},
+
+    #[error(
+        "Timelock delay must be less than or equal to {} seconds",
+        MAX_TIMELOCK_DELAY
+    )]
+    TimelockDelayTooLong {},
+
+    #[error("Config change proposal {0} not found")]
+    ProposalNotFound(u64),
+
+    #[error("Config change proposal {proposal_id} can't be executed before {executable_at}")]
+    ProposalNotReady {
+        proposal_id: u64,
+        executable_at: u64,
+    },
+
+    #[error("Delay of config change proposal {0} has passed, so it can't be cancelled")]
+    ProposalDelayPassed(u64),
+
+    #[error("Config change proposal {0} has expired")]
+    ProposalExpired(u64),
+
     #[error("You need to provide init params")]
     InitParamsNotFound {},
 
@@ -90,11 +120,33 @@ pub enum ContractError {
     #[error("Failed to parse or process reply message")]
     FailedToParseReply {},
 
+    #[error("Operation is not supported")]
+    NotSupported {},
+
     #[error(
         "Fee share is 0 or exceeds maximum allowed value of {} bps",
         MAX_FEE_SHARE_BPS
     )]
     FeeShareOutOfBounds {},
+
+    #[error(
+        "Deposit of {asset} deviates from the pool ratio by {deviation}, the slippage tolerance is {allowed}"
+    )]
+    SlippageTolerance {
+        asset: String,
+        deviation: Decimal256,
+        allowed: Decimal,
+    },
+}
+
+impl From<SlippageToleranceError> for ContractError {
+    fn from(err: SlippageToleranceError) -> Self {
+        ContractError::SlippageTolerance {
+            asset: err.asset.to_string(),
+            deviation: err.deviation,
+            allowed: err.allowed,
+        }
+    }
 }
 
 impl From<OverflowError> for ContractError {
@@ -117,3 +117,92 @@ pub(crate) fn calc_y(
     // Should definitely converge in 64 iterations.
     Err(StdError::generic_err("y is not converging"))
 }
+
+/// The lowest base reserve relative to D searched by [`base_reserve_at_price`], 2**-12
+const MIN_RELATIVE_RESERVE: Decimal256 = Decimal256::raw(244140625000000);
+/// The highest base reserve relative to D searched by [`base_reserve_at_price`], 2**12
+const MAX_RELATIVE_RESERVE: Decimal256 = Decimal256::raw(4096000000000000000000);
+
+/// Computes the marginal price of the base asset denominated in the quote asset for a base
+/// reserve relative to D. The quote reserve is solved from the invariant divided by D.
+///
+/// * **Equations**
+///
+/// leverage * (x + y) + 1 = leverage + 1 / (4 * x * y)
+///
+/// y = 1 / (2 * x * (sqrt(b**2 + leverage / x) + b)), where b = leverage * x + 1 - leverage
+///
+/// price = (leverage + 1 / (4 * x**2 * y)) / (leverage + 1 / (4 * x * y**2))
+fn marginal_price(leverage: Decimal256, x: Decimal256) -> StdResult<Decimal256> {
+    let four = Decimal256::from_ratio(4u8, 1u8);
+    let b_pos = leverage.checked_mul(x)?.checked_add(Decimal256::one())?;
+    let b_abs = b_pos.abs_diff(leverage);
+    let discriminant_sqrt = b_abs
+        .checked_pow(2)?
+        .checked_add(
+            leverage
+                .checked_div(x)
+                .map_err(|e| StdError::generic_err(e.to_string()))?,
+        )?
+        .sqrt();
+
+    let y = if b_pos >= leverage {
+        Decimal256::one()
+            .checked_div(N_COINS * x * (discriminant_sqrt + b_abs))
+            .map_err(|e| StdError::generic_err(e.to_string()))?
+    } else {
+        (discriminant_sqrt + b_abs) / (N_COINS * leverage)
+    };
+
+    // 1 / (4 * x * y) is divided by x and y separately to keep the precision for tiny reserves
+    let inv_product = Decimal256::one()
+        .checked_div(four * x * y)
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+    let numerator = leverage + inv_product / x;
+    let denominator = leverage + inv_product / y;
+
+    Ok(numerator / denominator)
+}
+
+/// Computes the marginal price of the first asset denominated in the second one.
+///
+/// * **pools** the pool reserves with the same precision, all non-zero.
+pub fn spot_price(amp: Uint64, pools: &[Decimal256]) -> StdResult<Decimal256> {
+    let leverage = Decimal256::from_ratio(amp, AMP_PRECISION) * N_COINS;
+    let d = compute_d(amp, pools)?;
+
+    marginal_price(leverage, pools[0] / d)
+}
+
+/// Finds the base reserve relative to D at which the marginal price of the base asset equals
+/// `price`. The price decreases as the base reserve grows, so the reserve is found with a
+/// bisection. Prices outside of the searched range are clamped to its bounds.
+///
+/// * **price** price of the base asset denominated in the quote asset.
+pub fn base_reserve_at_price(amp: Uint64, price: Decimal256) -> StdResult<Decimal256> {
+    let leverage = Decimal256::from_ratio(amp, AMP_PRECISION) * N_COINS;
+
+    let mut low = MIN_RELATIVE_RESERVE;
+    let mut high = MAX_RELATIVE_RESERVE;
+    if price >= marginal_price(leverage, low)? {
+        return Ok(low);
+    }
+    if price <= marginal_price(leverage, high)? {
+        return Ok(high);
+    }
+
+    for _ in 0..ITERATIONS {
+        // The range spans several orders of magnitude so it is split at the geometric mean
+        let mid = low.checked_mul(high)?.sqrt();
+        if marginal_price(leverage, mid)? > price {
+            low = mid;
+        } else {
+            high = mid;
+        }
+        if high - low <= TOL {
+            break;
+        }
+    }
+
+    Ok(low)
+}
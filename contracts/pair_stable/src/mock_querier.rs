@@ -97,6 +97,7 @@ impl WasmMockQuerier {
                                 generator_address: None,
                                 whitelist_code_id: 0,
                                 coin_registry_address: Addr::unchecked("coin_registry"),
+                                multisig_module: None,
                             })
                             .into(),
                         ),
@@ -164,6 +165,7 @@ impl WasmMockQuerier {
                                 generator_address: None,
                                 whitelist_code_id: 0,
                                 coin_registry_address: Addr::unchecked("coin_registry"),
+                                multisig_module: None,
                             })
                             .into(),
                         )
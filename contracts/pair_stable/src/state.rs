@@ -1,7 +1,7 @@
 use astroport::asset::{AssetInfo, PairInfo};
 use astroport::common::OwnershipProposal;
 use astroport::observation::Observation;
-use astroport::pair::FeeShareConfig;
+use astroport::pair::{config_hash, FeeShareConfig, StablePoolUpdateParams};
 use astroport_circular_buffer::CircularBuffer;
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Addr, DepsMut, StdResult, Storage};
@@ -32,18 +32,85 @@ pub struct Config {
     pub fee_share: Option<FeeShareConfig>,
 }
 
+impl Config {
+    /// Returns the hash of the config fields which aren't updated by swaps and liquidity changes.
+    pub fn hash(&self) -> StdResult<[u8; 32]> {
+        config_hash(&Config {
+            block_time_last: 0,
+            ..self.clone()
+        })
+    }
+}
+
 /// Circular buffer to store trade size observations
 pub const OBSERVATIONS: CircularBuffer<Observation> =
     CircularBuffer::new("observations_state", "observations_buffer");
 
 pub const CONFIG: Item<Config> = Item::new("config");
 
+/// Stores the hash of the config written at instantiation, config updates and migrations
+pub const CONFIG_HASH: Item<[u8; 32]> = Item::new("config_hash");
+
+/// Saves the config along with its hash. Used for every config update except for the price
+/// accumulator updates.
+pub fn save_config_with_hash(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
+    CONFIG.save(storage, config)?;
+    CONFIG_HASH.save(storage, &config.hash()?)
+}
+
 /// Stores map of AssetInfo (as String) -> precision
 pub const PRECISIONS: Map<String, u8> = Map::new("precisions");
 
 /// Stores the latest contract ownership transfer proposal
 pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
 
+/// The maximum delay of the config change proposals in seconds (30 days)
+pub const MAX_TIMELOCK_DELAY: u64 = 30 * 86400;
+
+/// The number of seconds a config change proposal can be executed for once its delay has
+/// passed (7 days). Anyone can cancel an expired proposal.
+pub const PROPOSAL_EXPIRY_PERIOD: u64 = 7 * 86400;
+
+/// This structure stores the parameters of the config change timelock.
+#[cw_serde]
+#[derive(Default)]
+pub struct TimelockConfig {
+    /// The delay in seconds between proposing and executing a config change
+    pub min_delay: u64,
+    /// The identifier which is assigned to the next proposal
+    pub next_proposal_id: u64,
+}
+
+/// This structure describes a config change proposal.
+#[cw_serde]
+pub struct TimelockProposal {
+    /// The config change to apply
+    pub changes: StablePoolUpdateParams,
+    /// The timestamp at which the proposal was made
+    pub proposed_at: u64,
+    /// The address which made the proposal
+    pub proposer: Addr,
+}
+
+impl TimelockProposal {
+    /// Returns the timestamp from which the proposal can be executed. The current delay applies,
+    /// so increasing the delay also postpones the pending proposals.
+    pub fn executable_at(&self, timelock: &TimelockConfig) -> u64 {
+        self.proposed_at + timelock.min_delay
+    }
+
+    /// Returns the timestamp from which the proposal can't be executed anymore.
+    pub fn expires_at(&self, timelock: &TimelockConfig) -> u64 {
+        self.executable_at(timelock) + PROPOSAL_EXPIRY_PERIOD
+    }
+}
+
+/// Stores the timelock parameters. The timelock is disabled if they were never set
+pub const TIMELOCK_CONFIG: Item<TimelockConfig> = Item::new("timelock_config");
+
+/// Stores the pending config change proposals. Key: proposal identifier -> proposal
+pub const TIMELOCK_PROPOSALS: Map<u64, TimelockProposal> = Map::new("timelock_proposals");
+
 /// Store all token precisions and return the greatest one.
 pub(crate) fn store_precisions(
     deps: DepsMut,
@@ -4,23 +4,26 @@ use std::str::FromStr;
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
     attr, coin, from_json, to_json_binary, Addr, BankMsg, BlockInfo, Coin, CosmosMsg, Decimal,
-    DepsMut, Env, Reply, ReplyOn, Response, SubMsg, SubMsgResponse, SubMsgResult, Timestamp,
-    Uint128, WasmMsg,
+    Decimal256, DepsMut, Env, Reply, ReplyOn, Response, StdError, SubMsg, SubMsgResponse,
+    SubMsgResult, Timestamp, Uint128, WasmMsg,
 };
+use cw2::ContractVersion;
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse};
 use itertools::Itertools;
 use proptest::prelude::*;
 use prost::Message;
 use sim::StableSwapModel;
 
-use astroport::asset::{native_asset, native_asset_info, Asset, AssetInfo};
+use astroport::asset::{native_asset, native_asset_info, token_asset_info, Asset, AssetInfo};
 use astroport::observation::query_observation;
 use astroport::observation::Observation;
 use astroport::observation::OracleObservation;
 use astroport::pair::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolResponse, QueryMsg,
-    SimulationResponse, StablePoolParams,
+    ConfigResponse, Cw20HookMsg, InstantiateMsg, LiquidityConcentrationResponse,
+    OraclePriceResponse, PoolRatioResponse, PoolResponse, QueryMsg, SimulationResponse,
+    StablePoolParams,
 };
+use astroport::pair_stable::ExecuteMsg;
 use astroport::token::InstantiateMsg as TokenInstantiateMsg;
 use astroport_circular_buffer::BufferManager;
 
@@ -142,6 +145,16 @@ fn proper_initialization() {
             }
         ]
     );
+
+    let contract_info: ContractVersion =
+        from_json(&query(deps.as_ref(), mock_env(), QueryMsg::ContractInfo {}).unwrap()).unwrap();
+    assert_eq!(
+        contract_info,
+        ContractVersion {
+            contract: "astroport-pair-stable".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    );
 }
 
 #[test]
@@ -524,6 +537,43 @@ fn provide_liquidity() {
         }],
     );
     let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    // Deposits with an extreme asset ratio are rejected
+    let msg = ExecuteMsg::ProvideLiquidity {
+        assets: vec![
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: Addr::unchecked("asset0000"),
+                },
+                amount: Uint128::from(1_000000000000000000u128),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                amount: Uint128::from(99_000000000000000000u128),
+            },
+        ],
+        slippage_tolerance: Some(Decimal::percent(50)),
+        auto_stake: None,
+        receiver: None,
+    };
+    let info = mock_info(
+        "addr0001",
+        &[Coin {
+            denom: "uusd".to_string(),
+            amount: Uint128::from(99_000000000000000000u128),
+        }],
+    );
+    let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::SlippageTolerance {
+            asset: "uusd".to_string(),
+            deviation: Decimal256::from_ratio(98u8, 99u8),
+            allowed: Decimal::percent(50),
+        }
+    );
 }
 
 #[test]
@@ -1100,6 +1150,243 @@ fn test_query_pool() {
     assert_eq!(res.total_share, total_share_amount);
 }
 
+#[test]
+fn pool_ratio() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(520_000000),
+    }]);
+    deps.querier.with_token_balances(&[(
+        &String::from("asset0000"),
+        &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(480_000000))],
+    )]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            native_asset_info("uusd".to_string()),
+            token_asset_info(Addr::unchecked("asset0000")),
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: Some(
+            to_json_binary(&StablePoolParams {
+                amp: 100,
+                owner: None,
+            })
+            .unwrap(),
+        ),
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let res: PoolRatioResponse =
+        from_json(&query(deps.as_ref(), mock_env(), QueryMsg::PoolRatio {}).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        PoolRatioResponse {
+            ratios: vec![Decimal::percent(52), Decimal::percent(48)],
+            peg_deviation_bps: 200,
+            is_balanced: false,
+        }
+    );
+
+    let depeg_alerts = |threshold_bps| {
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::DepegAlerts { threshold_bps },
+        )
+    };
+    assert_eq!(
+        from_json::<PoolRatioResponse>(&depeg_alerts(200).unwrap()).unwrap(),
+        res
+    );
+    assert_eq!(
+        depeg_alerts(199).unwrap_err(),
+        StdError::generic_err(
+            "Asset uusd deviates 200 bps from the peg, more than the 199 bps threshold"
+        )
+    );
+
+    // Deviations below 10 bps are balanced and the sign shows the overweight asset
+    deps.querier.with_token_balances(&[(
+        &String::from("asset0000"),
+        &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(521_000000))],
+    )]);
+    let res: PoolRatioResponse =
+        from_json(&query(deps.as_ref(), mock_env(), QueryMsg::PoolRatio {}).unwrap()).unwrap();
+    assert_eq!(res.peg_deviation_bps, -4);
+    assert!(res.is_balanced);
+}
+
+#[test]
+fn liquidity_concentration() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(1_000_000_000000),
+    }]);
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("asset0000"),
+            &[(
+                &String::from(MOCK_CONTRACT_ADDR),
+                &Uint128::new(1_000_000_000000),
+            )],
+        ),
+        (
+            &String::from("liquidity0000"),
+            &[(
+                &String::from(MOCK_CONTRACT_ADDR),
+                &Uint128::new(2_000_000_000000),
+            )],
+        ),
+    ]);
+
+    let msg = InstantiateMsg {
+        asset_infos: vec![
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked("asset0000"),
+            },
+        ],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: Some(
+            to_json_binary(&StablePoolParams {
+                amp: 100,
+                owner: None,
+            })
+            .unwrap(),
+        ),
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let query_bands = |price_bands: Vec<(Decimal, Decimal)>| {
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::LiquidityConcentration { price_bands },
+        )
+        .map(|res| {
+            from_json::<LiquidityConcentrationResponse>(&res)
+                .unwrap()
+                .bands
+        })
+    };
+
+    let bands = query_bands(vec![
+        (Decimal::percent(99), Decimal::percent(101)),
+        (Decimal::permille(999), Decimal::permille(1001)),
+        (Decimal::percent(90), Decimal::percent(99)),
+    ])
+    .unwrap();
+    assert_eq!(bands.len(), 3);
+    assert_eq!(
+        bands[0].price_band,
+        (Decimal::percent(99), Decimal::percent(101))
+    );
+
+    // A third of the balanced pool reserves trade within 1% of the peg
+    assert!(
+        bands[0].liquidity_fraction > Decimal::percent(37)
+            && bands[0].liquidity_fraction < Decimal::percent(38),
+        "{}",
+        bands[0].liquidity_fraction
+    );
+    assert!(bands[1].liquidity_fraction < bands[0].liquidity_fraction);
+    assert!(bands[2].liquidity_fraction < bands[0].liquidity_fraction);
+    // The depth is measured against both reserves
+    let depth = bands[0].liquidity_fraction * Uint128::new(2_000_000_000000);
+    assert!(bands[0].depth_in_base.abs_diff(depth) <= Uint128::new(1_000000));
+
+    let err = query_bands(vec![(Decimal::percent(101), Decimal::percent(99))]).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("Invalid price band (1.01, 0.99)")
+    );
+    let err = query_bands(vec![(Decimal::percent(99), Decimal::percent(101)); 11]).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("At most 10 price bands can be queried")
+    );
+}
+
+#[test]
+fn oracle_price() {
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uusd".to_string(),
+        amount: Uint128::new(1_000_000_000000),
+    }]);
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("asset0000"),
+            &[(
+                &String::from(MOCK_CONTRACT_ADDR),
+                &Uint128::new(1_000_000_000000),
+            )],
+        ),
+        (
+            &String::from("liquidity0000"),
+            &[(
+                &String::from(MOCK_CONTRACT_ADDR),
+                &Uint128::new(2_000_000_000000),
+            )],
+        ),
+    ]);
+
+    let uusd = native_asset_info("uusd".to_string());
+    let token = token_asset_info(Addr::unchecked("asset0000"));
+    let msg = InstantiateMsg {
+        asset_infos: vec![uusd.clone(), token.clone()],
+        token_code_id: 10u64,
+        factory_addr: String::from("factory"),
+        init_params: Some(
+            to_json_binary(&StablePoolParams {
+                amp: 100,
+                owner: None,
+            })
+            .unwrap(),
+        ),
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    store_liquidity_token(deps.as_mut(), 1, "liquidity0000".to_string());
+
+    let query_price = |base: &AssetInfo, quote: &AssetInfo, amount: u128| {
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Price {
+                base: base.clone(),
+                quote: quote.clone(),
+                amount: Uint128::new(amount),
+            },
+        )
+        .map(|res| from_json::<OraclePriceResponse>(&res).unwrap())
+    };
+
+    // Without observations the spot price of the balanced pool is returned with zero confidence
+    let res = query_price(&token, &uusd, 0).unwrap();
+    assert!(
+        res.price.abs_diff(Decimal::one()) < Decimal::permille(1),
+        "{}",
+        res.price
+    );
+    assert_eq!(res.confidence, Decimal::zero());
+
+    // Large trades are priced with the price impact
+    let spot_price = query_price(&uusd, &token, 0).unwrap().price;
+    let res = query_price(&uusd, &token, 500_000_000000).unwrap();
+    assert!(res.price < spot_price, "{} {}", res.price, spot_price);
+
+    let err = query_price(&token, &token, 0).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("Base and quote assets must be different pool assets")
+    );
+}
+
 #[test]
 fn test_query_share() {
     let total_share_amount = Uint128::from(500u128);
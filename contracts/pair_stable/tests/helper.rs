@@ -16,7 +16,7 @@ use astroport::factory::{PairConfig, PairType};
 use astroport::observation::OracleObservation;
 use astroport::pair::{
     CumulativePricesResponse, Cw20HookMsg, ExecuteMsg, QueryMsg, ReverseSimulationResponse,
-    SimulationResponse, StablePoolParams,
+    SimulateProvideResponse, SimulationResponse, StablePoolParams,
 };
 pub const NATIVE_TOKEN_PRECISION: u8 = 6;
 use astroport_pair_stable::contract::{execute, instantiate, query, reply};
@@ -215,6 +215,7 @@ impl Helper {
             pair_type: PairType::Stable {},
             asset_infos: asset_infos.clone(),
             init_params: Some(to_json_binary(&StablePoolParams { amp, owner: None }).unwrap()),
+            proposal_id: None,
         };
 
         app.execute_contract(owner.clone(), factory.clone(), &init_pair_msg, &[])?;
@@ -339,6 +340,15 @@ impl Helper {
         )
     }
 
+    pub fn simulate_provide(&self, assets: &[Asset]) -> StdResult<SimulateProvideResponse> {
+        self.app.wrap().query_wasm_smart(
+            &self.pair_addr,
+            &QueryMsg::SimulateProvideLiquidity {
+                assets: assets.to_vec(),
+            },
+        )
+    }
+
     pub fn query_prices(&self) -> StdResult<CumulativePricesResponse> {
         self.app
             .wrap()
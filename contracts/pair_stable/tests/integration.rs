@@ -6,9 +6,10 @@ use astroport::factory::{
     QueryMsg as FactoryQueryMsg,
 };
 use astroport::pair::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolResponse, QueryMsg,
-    StablePoolConfig, StablePoolParams, StablePoolUpdateParams, MAX_FEE_SHARE_BPS,
+    ConfigResponse, Cw20HookMsg, InstantiateMsg, PoolResponse, QueryMsg, StablePoolConfig,
+    StablePoolParams, StablePoolUpdateParams, MAX_FEE_SHARE_BPS,
 };
+use astroport::pair_stable::ExecuteMsg;
 use astroport_pair_stable::error::ContractError;
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -20,6 +21,7 @@ use astroport_mocks::cw_multi_test::{App, BasicApp, ContractWrapper, Executor};
 use astroport_mocks::pair_stable::MockStablePairBuilder;
 use astroport_mocks::{astroport_address, MockGeneratorBuilder};
 use astroport_pair_stable::math::{MAX_AMP, MAX_AMP_CHANGE, MIN_AMP_CHANGING_TIME};
+use astroport_pair_stable::state::{MAX_TIMELOCK_DELAY, PROPOSAL_EXPIRY_PERIOD};
 use cosmwasm_std::{
     attr, from_json, to_json_binary, Addr, Coin, Decimal, QueryRequest, Uint128, WasmQuery,
 };
@@ -530,6 +532,7 @@ fn provide_lp_for_single_token() {
             })
             .unwrap(),
         ),
+        proposal_id: None,
     };
 
     app.execute_contract(owner.clone(), factory_instance.clone(), &msg, &[])
@@ -865,6 +868,7 @@ fn test_compatibility_of_tokens_with_different_precision() {
             })
             .unwrap(),
         ),
+        proposal_id: None,
     };
 
     app.execute_contract(owner.clone(), factory_instance.clone(), &msg, &[])
@@ -1438,6 +1442,288 @@ fn enable_disable_fee_sharing() {
     assert!(params.fee_share.is_none());
 }
 
+#[test]
+fn config_change_timelock() {
+    let owner = Addr::unchecked(OWNER);
+    let mut router = mock_app(owner.clone(), vec![]);
+    let pair = instantiate_pair(&mut router, &owner);
+
+    let update_config = |params: &StablePoolUpdateParams| ExecuteMsg::UpdateConfig {
+        params: to_json_binary(params).unwrap(),
+    };
+    let propose = |changes: &StablePoolUpdateParams| ExecuteMsg::ProposeConfigChange {
+        changes: to_json_binary(changes).unwrap(),
+    };
+    let enable_fee_share = StablePoolUpdateParams::EnableFeeShare {
+        fee_share_bps: 500,
+        fee_share_address: "contract".to_string(),
+    };
+
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            pair.clone(),
+            &update_config(&StablePoolUpdateParams::UpdateTimelockDelay {
+                min_delay: MAX_TIMELOCK_DELAY + 1,
+            }),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<ContractError>().unwrap(),
+        &ContractError::TimelockDelayTooLong {}
+    );
+    router
+        .execute_contract(
+            owner.clone(),
+            pair.clone(),
+            &update_config(&StablePoolUpdateParams::UpdateTimelockDelay { min_delay: 100 }),
+            &[],
+        )
+        .unwrap();
+
+    // Config changes can't be applied directly anymore, but amp changes can still be stopped
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            pair.clone(),
+            &update_config(&enable_fee_share),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<ContractError>().unwrap(),
+        &ContractError::TimelockActive {}
+    );
+    router
+        .execute_contract(
+            owner.clone(),
+            pair.clone(),
+            &update_config(&StablePoolUpdateParams::StopChangingAmp {}),
+            &[],
+        )
+        .unwrap();
+
+    let err = router
+        .execute_contract(
+            Addr::unchecked("random"),
+            pair.clone(),
+            &propose(&enable_fee_share),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<ContractError>().unwrap(),
+        &ContractError::Unauthorized {}
+    );
+
+    let res = router
+        .execute_contract(
+            owner.clone(),
+            pair.clone(),
+            &propose(&enable_fee_share),
+            &[],
+        )
+        .unwrap();
+    let executable_at = router.block_info().time.seconds() + 100;
+    assert_eq!(
+        res.events[1].attributes[1..],
+        [
+            attr("action", "propose_config_change"),
+            attr("proposal_id", "0"),
+            attr("executable_at", executable_at.to_string()),
+        ]
+    );
+
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            pair.clone(),
+            &ExecuteMsg::ExecuteProposal { proposal_id: 0 },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<ContractError>().unwrap(),
+        &ContractError::ProposalNotReady {
+            proposal_id: 0,
+            executable_at,
+        }
+    );
+
+    // The proposal is cancelled within the delay
+    router.update_block(|b| b.time = b.time.plus_seconds(50));
+    router
+        .execute_contract(
+            owner.clone(),
+            pair.clone(),
+            &ExecuteMsg::CancelProposal { proposal_id: 0 },
+            &[],
+        )
+        .unwrap();
+    router.update_block(|b| b.time = b.time.plus_seconds(50));
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            pair.clone(),
+            &ExecuteMsg::ExecuteProposal { proposal_id: 0 },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<ContractError>().unwrap(),
+        &ContractError::ProposalNotFound(0)
+    );
+
+    // The next proposal is executed after the delay and can't be cancelled anymore
+    router
+        .execute_contract(
+            owner.clone(),
+            pair.clone(),
+            &propose(&enable_fee_share),
+            &[],
+        )
+        .unwrap();
+    router.update_block(|b| b.time = b.time.plus_seconds(100));
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            pair.clone(),
+            &ExecuteMsg::CancelProposal { proposal_id: 1 },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<ContractError>().unwrap(),
+        &ContractError::ProposalDelayPassed(1)
+    );
+    router
+        .execute_contract(
+            owner.clone(),
+            pair.clone(),
+            &ExecuteMsg::ExecuteProposal { proposal_id: 1 },
+            &[],
+        )
+        .unwrap();
+
+    let res: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(pair.clone(), &QueryMsg::Config {})
+        .unwrap();
+    let params: StablePoolConfig = from_json(&res.params.unwrap()).unwrap();
+    let fee_share = params.fee_share.unwrap();
+    assert_eq!(fee_share.bps, 500);
+    assert_eq!(fee_share.recipient, "contract");
+
+    // Increasing the delay postpones the pending proposals
+    let proposed_at = router.block_info().time.seconds();
+    for changes in [
+        StablePoolUpdateParams::DisableFeeShare,
+        StablePoolUpdateParams::UpdateTimelockDelay { min_delay: 200 },
+    ] {
+        router
+            .execute_contract(owner.clone(), pair.clone(), &propose(&changes), &[])
+            .unwrap();
+    }
+    router.update_block(|b| b.time = b.time.plus_seconds(100));
+    router
+        .execute_contract(
+            owner.clone(),
+            pair.clone(),
+            &ExecuteMsg::ExecuteProposal { proposal_id: 3 },
+            &[],
+        )
+        .unwrap();
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            pair.clone(),
+            &ExecuteMsg::ExecuteProposal { proposal_id: 2 },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<ContractError>().unwrap(),
+        &ContractError::ProposalNotReady {
+            proposal_id: 2,
+            executable_at: proposed_at + 200,
+        }
+    );
+    router.update_block(|b| b.time = b.time.plus_seconds(100));
+    router
+        .execute_contract(
+            owner.clone(),
+            pair.clone(),
+            &ExecuteMsg::ExecuteProposal { proposal_id: 2 },
+            &[],
+        )
+        .unwrap();
+
+    let res: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(pair.clone(), &QueryMsg::Config {})
+        .unwrap();
+    let params: StablePoolConfig = from_json(&res.params.unwrap()).unwrap();
+    assert_eq!(params.fee_share, None);
+
+    // Only the proposer or the owner can cancel a proposal within the delay
+    router
+        .execute_contract(
+            owner.clone(),
+            pair.clone(),
+            &propose(&enable_fee_share),
+            &[],
+        )
+        .unwrap();
+    let err = router
+        .execute_contract(
+            Addr::unchecked("random"),
+            pair.clone(),
+            &ExecuteMsg::CancelProposal { proposal_id: 4 },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<ContractError>().unwrap(),
+        &ContractError::Unauthorized {}
+    );
+
+    // An expired proposal can't be executed anymore, but anyone can cancel it
+    router.update_block(|b| b.time = b.time.plus_seconds(200 + PROPOSAL_EXPIRY_PERIOD));
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            pair.clone(),
+            &ExecuteMsg::ExecuteProposal { proposal_id: 4 },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<ContractError>().unwrap(),
+        &ContractError::ProposalExpired(4)
+    );
+    router
+        .execute_contract(
+            Addr::unchecked("random"),
+            pair.clone(),
+            &ExecuteMsg::CancelProposal { proposal_id: 4 },
+            &[],
+        )
+        .unwrap();
+    let err = router
+        .execute_contract(
+            owner.clone(),
+            pair.clone(),
+            &ExecuteMsg::ExecuteProposal { proposal_id: 4 },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<ContractError>().unwrap(),
+        &ContractError::ProposalNotFound(4)
+    );
+}
+
 #[test]
 fn check_observe_queries() {
     let owner = Addr::unchecked("owner");
@@ -1943,6 +2229,7 @@ fn test_fee_share(
             })
             .unwrap(),
         ),
+        proposal_id: None,
     };
 
     app.execute_contract(owner.clone(), factory_instance.clone(), &msg, &[])
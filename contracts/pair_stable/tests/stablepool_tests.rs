@@ -177,6 +177,61 @@ fn provide_with_different_precision() {
     assert_eq!(9999999, helper.coin_balance(&test_coins[1], &user2));
 }
 
+#[test]
+fn simulate_provide() {
+    let owner = Addr::unchecked("owner");
+
+    let test_coins = vec![TestCoin::native("uluna"), TestCoin::cw20("USDC")];
+
+    let mut helper = Helper::new(&owner, test_coins.clone(), 100u64, None).unwrap();
+
+    let user1 = Addr::unchecked("user1");
+    let assets = vec![
+        helper.assets[&test_coins[0]].with_balance(100_000000u128),
+        helper.assets[&test_coins[1]].with_balance(100_000000u128),
+    ];
+    let simulation = helper.simulate_provide(&assets).unwrap();
+    assert_eq!(simulation.price_impact_bps, 0);
+    helper.give_me_money(&assets, &user1);
+    helper.provide_liquidity(&user1, &assets).unwrap();
+    assert_eq!(
+        simulation.lp_amount.u128(),
+        helper.token_balance(&helper.lp_token, &user1)
+    );
+
+    // A balanced deposit into a balanced pool has no price impact
+    let user2 = Addr::unchecked("user2");
+    let simulation = helper.simulate_provide(&assets).unwrap();
+    assert_eq!(simulation.fee, None);
+    assert_eq!(simulation.price_impact_bps, 0);
+    helper.give_me_money(&assets, &user2);
+    helper.provide_liquidity(&user2, &assets).unwrap();
+    assert_eq!(
+        simulation.lp_amount.u128(),
+        helper.token_balance(&helper.lp_token, &user2)
+    );
+
+    // A single sided deposit moves the pool away from the peg
+    let user3 = Addr::unchecked("user3");
+    let assets = vec![helper.assets[&test_coins[0]].with_balance(200_000000u128)];
+    let simulation = helper.simulate_provide(&assets).unwrap();
+    assert!(simulation.price_impact_bps > 0);
+    helper.give_me_money(&assets, &user3);
+    helper.provide_liquidity(&user3, &assets).unwrap();
+    assert_eq!(
+        simulation.lp_amount.u128(),
+        helper.token_balance(&helper.lp_token, &user3)
+    );
+
+    let err = helper
+        .simulate_provide(&[helper.assets[&test_coins[0]].with_balance(0u128)])
+        .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("Querier contract error: Generic error: Event of zero transfer")
+    );
+}
+
 #[test]
 fn swap_different_precisions() {
     let owner = Addr::unchecked("owner");
@@ -17,7 +17,7 @@ use astroport::pair::{Cw20HookMsg, ExecuteMsg, InstantiateMsg};
 use astroport::token::MinterResponse;
 
 use crate::error::ContractError;
-use crate::state::{Config, CONFIG};
+use crate::state::{save_config_with_hash, Config, CONFIG};
 use crate::utils::{
     assert_and_swap, check_asset_infos, check_assets, get_share_in_assets, pool_info,
 };
@@ -52,10 +52,13 @@ pub fn instantiate(
             liquidity_token: Addr::unchecked(""),
             asset_infos: msg.asset_infos.clone(),
             pair_type: PairType::Custom("transmuter".to_string()),
+            creator: None,
+            creation_height: 0,
+            creation_time: 0,
         },
         factory_addr,
     };
-    CONFIG.save(deps.storage, &config)?;
+    save_config_with_hash(deps.storage, &config)?;
 
     let token_name = format_lp_token_name(&msg.asset_infos, &deps.querier)?;
 
@@ -103,7 +106,7 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
 
             let init_response = parse_instantiate_response_data(data.as_slice())?;
             config.pair_info.liquidity_token = Addr::unchecked(init_response.contract_address);
-            CONFIG.save(deps.storage, &config)?;
+            save_config_with_hash(deps.storage, &config)?;
             Ok(Response::new()
                 .add_attribute("liquidity_token_addr", config.pair_info.liquidity_token))
         }
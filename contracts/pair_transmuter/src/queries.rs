@@ -1,17 +1,18 @@
-use cosmwasm_std::{entry_point, to_json_binary, Binary, Deps, Env, StdResult, Uint128};
+use cosmwasm_std::{entry_point, to_json_binary, Binary, Decimal, Deps, Env, StdResult, Uint128};
 
 use astroport::asset::{Asset, AssetInfo};
 use astroport::pair::{
-    ConfigResponse, PoolResponse, QueryMsg, ReverseSimulationResponse, SimulationResponse,
+    config_hash, ConfigResponse, OraclePriceResponse, PoolResponse, QueryMsg,
+    ReverseSimulationResponse, SimulationResponse,
 };
 use astroport::querier::query_factory_config;
 
 use crate::error::ContractError;
-use crate::state::{Config, CONFIG};
+use crate::state::{Config, CONFIG, CONFIG_HASH};
 use crate::utils::{assert_and_swap, get_share_in_assets, pool_info};
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
         QueryMsg::Pair {} => Ok(to_json_binary(&CONFIG.load(deps.storage)?.pair_info)?),
         QueryMsg::Pool {} => Ok(to_json_binary(&query_pool(deps)?)?),
@@ -41,10 +42,42 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
                 commission_amount: Uint128::zero(),
             })?)
         }
+        QueryMsg::Price { base, quote, .. } => Ok(to_json_binary(&query_oracle_price(
+            deps, env, base, quote,
+        )?)?),
+        QueryMsg::ConfigHash {} => Ok(to_json_binary(&CONFIG_HASH.load(deps.storage)?)?),
+        QueryMsg::VerifyConfig { expected_hash } => Ok(to_json_binary(
+            &(config_hash(&CONFIG.load(deps.storage)?)? == expected_hash),
+        )?),
         _ => Err(ContractError::NotSupported {}),
     }
 }
 
+/// Returns the price of `base` denominated in `quote`. The assets are always swapped 1:1 so the
+/// price doesn't depend on the amount and can not be moved by trades.
+pub fn query_oracle_price(
+    deps: Deps,
+    env: Env,
+    base: AssetInfo,
+    quote: AssetInfo,
+) -> Result<OraclePriceResponse, ContractError> {
+    let asset_infos = CONFIG.load(deps.storage)?.pair_info.asset_infos;
+    for asset_info in [&base, &quote] {
+        if !asset_infos.contains(asset_info) {
+            return Err(ContractError::InvalidAsset(asset_info.to_string()));
+        }
+    }
+    if base == quote {
+        return Err(ContractError::DoublingAssets {});
+    }
+
+    Ok(OraclePriceResponse {
+        price: Decimal::one(),
+        confidence: Decimal::one(),
+        last_updated: env.block.time.seconds(),
+    })
+}
+
 /// Returns the amounts of assets in the pair contract as well as the amount of LP
 /// tokens currently minted in an object of type [`PoolResponse`].
 pub fn query_pool(deps: Deps) -> StdResult<PoolResponse> {
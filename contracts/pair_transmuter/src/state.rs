@@ -1,8 +1,9 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, StdResult, Storage};
 use cw_storage_plus::Item;
 
 use astroport::asset::PairInfo;
+use astroport::pair::config_hash;
 
 /// This structure stores the main pair parameters.
 #[cw_serde]
@@ -14,3 +15,12 @@ pub struct Config {
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Stores the hash of the config written at instantiation
+pub const CONFIG_HASH: Item<[u8; 32]> = Item::new("config_hash");
+
+/// Saves the config along with its hash
+pub fn save_config_with_hash(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
+    CONFIG.save(storage, config)?;
+    CONFIG_HASH.save(storage, &config_hash(config)?)
+}
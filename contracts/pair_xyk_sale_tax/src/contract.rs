@@ -21,15 +21,16 @@ use astroport::factory::PairType;
 use astroport::generator::Cw20HookMsg as GeneratorHookMsg;
 use astroport::pair::{ConfigResponse, DEFAULT_SLIPPAGE, MAX_ALLOWED_SLIPPAGE};
 use astroport::pair::{
-    CumulativePricesResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolResponse, QueryMsg,
-    ReverseSimulationResponse, SimulationResponse, TWAP_PRECISION,
+    CumulativePricesResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, InvariantResponse,
+    OraclePriceResponse, PoolResponse, QueryMsg, ReverseSimulationResponse, SimulationResponse,
+    TWAP_PRECISION,
 };
 use astroport::querier::{query_factory_config, query_fee_info, query_supply};
 use astroport::{token::InstantiateMsg as TokenInstantiateMsg, U256};
 use cw_utils::parse_instantiate_response_data;
 
 use crate::error::ContractError;
-use crate::state::{Config, BALANCES, CONFIG};
+use crate::state::{save_config_with_hash, Config, BALANCES, CONFIG, CONFIG_HASH};
 use astroport::pair_xyk_sale_tax::{
     MigrateMsg, SaleTaxConfigUpdates, SaleTaxInitParams, TaxConfigChecked,
 };
@@ -70,6 +71,9 @@ pub fn instantiate(
             liquidity_token: Addr::unchecked(""),
             asset_infos: msg.asset_infos.clone(),
             pair_type: PairType::Custom(CONTRACT_NAME.to_string()),
+            creator: None,
+            creation_height: 0,
+            creation_time: 0,
         },
         factory_addr: deps.api.addr_validate(msg.factory_addr.as_str())?,
         block_time_last: 0,
@@ -86,7 +90,7 @@ pub fn instantiate(
         }
     }
 
-    CONFIG.save(deps.storage, &config)?;
+    save_config_with_hash(deps.storage, &config)?;
 
     let token_name = format_lp_token_name(&msg.asset_infos, &deps.querier)?;
 
@@ -149,7 +153,7 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
             config.pair_info.liquidity_token =
                 deps.api.addr_validate(&init_response.contract_address)?;
 
-            CONFIG.save(deps.storage, &config)?;
+            save_config_with_hash(deps.storage, &config)?;
 
             Ok(Response::new()
                 .add_attribute("liquidity_token_addr", config.pair_info.liquidity_token))
@@ -201,6 +205,7 @@ pub fn execute(
             slippage_tolerance,
             auto_stake,
             receiver,
+            None,
         ),
         ExecuteMsg::Swap {
             offer_asset,
@@ -289,6 +294,42 @@ pub fn receive_cw20(
             cw20_msg.amount,
             assets,
         ),
+        Cw20HookMsg::ProvideLiquidity {
+            pair_asset,
+            slippage_tolerance,
+            auto_stake,
+            receiver,
+        } => {
+            let config = CONFIG.load(deps.storage)?;
+            let received_asset = Asset {
+                info: AssetInfo::Token {
+                    contract_addr: info.sender,
+                },
+                amount: cw20_msg.amount,
+            };
+
+            // Only asset contract can execute this message
+            if !config.pair_info.asset_infos.contains(&received_asset.info) {
+                return Err(ContractError::Unauthorized {});
+            }
+            if pair_asset.info.equal(&received_asset.info) {
+                return Err(ContractError::DoublingAssets {});
+            }
+
+            provide_liquidity(
+                deps,
+                env,
+                MessageInfo {
+                    sender: Addr::unchecked(cw20_msg.sender),
+                    funds: vec![],
+                },
+                vec![received_asset.clone(), pair_asset],
+                slippage_tolerance,
+                Some(auto_stake),
+                receiver,
+                Some(received_asset),
+            )
+        }
     }
 }
 
@@ -305,7 +346,11 @@ pub fn receive_cw20(
 /// * **receiver** is an optional parameter which defines the receiver of the LP tokens.
 /// If no custom receiver is specified, the pair will mint LP tokens for the function caller.
 ///
+/// * **received_asset** is the CW20 asset that was already sent to the pair via
+/// [`Cw20HookMsg::ProvideLiquidity`], so it is not pulled from the caller.
+///
 /// NOTE - the address that wants to provide liquidity should approve the pair contract to pull its relevant tokens.
+#[allow(clippy::too_many_arguments)]
 pub fn provide_liquidity(
     deps: DepsMut,
     env: Env,
@@ -314,6 +359,7 @@ pub fn provide_liquidity(
     slippage_tolerance: Option<Decimal>,
     auto_stake: Option<bool>,
     receiver: Option<String>,
+    received_asset: Option<Asset>,
 ) -> Result<Response, ContractError> {
     if assets.len() != 2 {
         return Err(StdError::generic_err("asset_infos must contain exactly two elements").into());
@@ -348,8 +394,11 @@ pub fn provide_liquidity(
 
     let mut messages = vec![];
     for (i, pool) in pools.iter_mut().enumerate() {
+        let is_received = received_asset
+            .as_ref()
+            .map_or(false, |asset| asset.info.equal(&pool.info));
         // If the asset is a token contract, then we need to execute a TransferFrom msg to receive assets
-        if let AssetInfo::Token { contract_addr, .. } = &pool.info {
+        if let (AssetInfo::Token { contract_addr, .. }, false) = (&pool.info, is_received) {
             messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: contract_addr.to_string(),
                 msg: to_json_binary(&Cw20ExecuteMsg::TransferFrom {
@@ -360,7 +409,7 @@ pub fn provide_liquidity(
                 funds: vec![],
             }));
         } else {
-            // If the asset is native token, the pool balance is already increased
+            // If the asset is native token or was sent via the CW20 hook, the pool balance is already increased
             // To calculate the total amount of deposits properly, we should subtract the user deposit from the pool
             pool.amount = pool.amount.checked_sub(deposits[i])?;
         }
@@ -823,7 +872,7 @@ pub fn update_config(
         config.tax_config_admin = deps.api.addr_validate(&new_tax_config_admin)?;
     }
 
-    CONFIG.save(deps.storage, &config)?;
+    save_config_with_hash(deps.storage, &config)?;
 
     Ok(response)
 }
@@ -916,6 +965,15 @@ pub fn calculate_maker_fee(
 ///
 /// * **QueryMsg::AssetBalanceAt { asset_info, block_height }** Returns the balance of the specified asset that was in the pool
 /// just preceeding the moment of the specified block height creation.
+///
+/// * **QueryMsg::Price { base, quote, amount }** Returns the oracle price of the base asset
+/// denominated in the quote asset using an [`OraclePriceResponse`] object.
+///
+/// * **QueryMsg::ConfigHash {}** Returns the config hash stored at the last config update.
+///
+/// * **QueryMsg::VerifyConfig { expected_hash }** Returns whether the current config hash matches the expected one.
+///
+/// * **QueryMsg::Invariant {}** Returns the current pool invariant using an [`InvariantResponse`] object.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -934,10 +992,66 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             asset_info,
             block_height,
         } => to_json_binary(&query_asset_balances_at(deps, asset_info, block_height)?),
+        QueryMsg::Price {
+            base,
+            quote,
+            amount,
+        } => to_json_binary(&query_oracle_price(deps, base, quote, amount)?),
+        QueryMsg::ConfigHash {} => to_json_binary(&CONFIG_HASH.load(deps.storage)?),
+        QueryMsg::VerifyConfig { expected_hash } => {
+            to_json_binary(&(CONFIG.load(deps.storage)?.hash()? == expected_hash))
+        }
+        QueryMsg::Invariant {} => to_json_binary(&query_invariant(deps)?),
         _ => Err(StdError::generic_err("Query is not supported")),
     }
 }
 
+/// Returns the price of `amount` of `base` denominated in `quote` computed from the pool
+/// reserves. The pair doesn't keep a price history, so the confidence is always zero.
+///
+/// * **amount** amount of the base asset to price. A zero amount returns the marginal price.
+pub fn query_oracle_price(
+    deps: Deps,
+    base: AssetInfo,
+    quote: AssetInfo,
+    amount: Uint128,
+) -> StdResult<OraclePriceResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let asset_infos = &config.pair_info.asset_infos;
+    if base.equal(&quote) || !asset_infos.contains(&base) || !asset_infos.contains(&quote) {
+        return Err(StdError::generic_err(
+            "Base and quote assets must be different pool assets",
+        ));
+    }
+
+    let pools = config
+        .pair_info
+        .query_pools(&deps.querier, &config.pair_info.contract_addr)?;
+    let (base_pool, quote_pool) = if pools[0].info.equal(&base) {
+        (pools[0].amount, pools[1].amount)
+    } else {
+        (pools[1].amount, pools[0].amount)
+    };
+    let marginal_price = Decimal::checked_from_ratio(quote_pool, base_pool).unwrap_or_default();
+
+    let execution_price = if amount.is_zero() || marginal_price.is_zero() {
+        marginal_price
+    } else {
+        let simulation = query_simulation(deps, Asset { info: base, amount })?;
+        Decimal::from_ratio(
+            simulation.return_amount + simulation.commission_amount,
+            amount,
+        )
+    };
+
+    Ok(OraclePriceResponse::new(
+        marginal_price,
+        execution_price,
+        None,
+        config.block_time_last,
+    ))
+}
+
 /// Returns the amounts of assets in the pair contract as well as the amount of LP
 /// tokens currently minted in an object of type [`PoolResponse`].
 pub fn query_pool(deps: Deps) -> StdResult<PoolResponse> {
@@ -952,6 +1066,19 @@ pub fn query_pool(deps: Deps) -> StdResult<PoolResponse> {
     Ok(resp)
 }
 
+/// Returns the constant product of the pool reserves using an [`InvariantResponse`] object.
+pub fn query_invariant(deps: Deps) -> StdResult<InvariantResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let pools = config
+        .pair_info
+        .query_pools(&deps.querier, &config.pair_info.contract_addr)?;
+
+    Ok(InvariantResponse {
+        value: pools[0].amount.full_mul(pools[1].amount),
+        formula: "xy=k".to_string(),
+    })
+}
+
 /// Returns the amount of assets that could be withdrawn from the pool using a specific amount of LP tokens.
 /// The result is returned in a vector that contains objects of type [`Asset`].
 ///
@@ -1321,19 +1448,8 @@ pub fn assert_slippage_tolerance(
         return Err(ContractError::AllowedSpreadAssertion {});
     }
 
-    let slippage_tolerance: Decimal256 = Decimal256::from(slippage_tolerance);
-    let one_minus_slippage_tolerance = Decimal256::one() - slippage_tolerance;
-    let deposits: [Uint256; 2] = [deposits[0].into(), deposits[1].into()];
-    let pools: [Uint256; 2] = [pools[0].amount.into(), pools[1].amount.into()];
-
-    // Ensure each price does not change more than what the slippage tolerance allows
-    if Decimal256::from_ratio(deposits[0], deposits[1]) * one_minus_slippage_tolerance
-        > Decimal256::from_ratio(pools[0], pools[1])
-        || Decimal256::from_ratio(deposits[1], deposits[0]) * one_minus_slippage_tolerance
-            > Decimal256::from_ratio(pools[1], pools[0])
-    {
-        return Err(ContractError::MaxSlippageAssertion {});
-    }
+    let reserves = [pools[0].amount, pools[1].amount];
+    astroport::math::assert_slippage_tolerance(pools, deposits, &reserves, slippage_tolerance)?;
 
     Ok(())
 }
@@ -1370,7 +1486,7 @@ pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, Co
             price1_cumulative_last: old_config.price1_cumulative_last,
             track_asset_balances: old_config.track_asset_balances,
         };
-        CONFIG.save(deps.storage, &new_config)?;
+        save_config_with_hash(deps.storage, &new_config)?;
     } else {
         return Err(StdError::generic_err(
             "Incompatible contract name. Only astroport-pair supported.",
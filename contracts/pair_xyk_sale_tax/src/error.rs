@@ -1,5 +1,6 @@
 use astroport::asset::MINIMUM_LIQUIDITY_AMOUNT;
-use cosmwasm_std::{OverflowError, StdError};
+use astroport::math::SlippageToleranceError;
+use cosmwasm_std::{Decimal, Decimal256, OverflowError, StdError};
 use thiserror::Error;
 
 /// This enum describes pair contract errors
@@ -26,8 +27,14 @@ pub enum ContractError {
     #[error("Provided spread amount exceeds allowed limit")]
     AllowedSpreadAssertion {},
 
-    #[error("Operation exceeds max splippage tolerance")]
-    MaxSlippageAssertion {},
+    #[error(
+        "Deposit of {asset} deviates from the pool ratio by {deviation}, the slippage tolerance is {allowed}"
+    )]
+    SlippageTolerance {
+        asset: String,
+        deviation: Decimal256,
+        allowed: Decimal,
+    },
 
     #[error("Doubling assets in asset infos")]
     DoublingAssets {},
@@ -54,6 +61,16 @@ pub enum ContractError {
     FailedToParseReply {},
 }
 
+impl From<SlippageToleranceError> for ContractError {
+    fn from(err: SlippageToleranceError) -> Self {
+        ContractError::SlippageTolerance {
+            asset: err.asset.to_string(),
+            deviation: err.deviation,
+            allowed: err.allowed,
+        }
+    }
+}
+
 impl From<OverflowError> for ContractError {
     fn from(o: OverflowError) -> Self {
         StdError::from(o).into()
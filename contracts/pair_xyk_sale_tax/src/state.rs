@@ -1,9 +1,10 @@
 use astroport::{
     asset::{AssetInfo, PairInfo},
+    pair::config_hash,
     pair_xyk_sale_tax::TaxConfigs,
 };
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, StdResult, Storage, Uint128};
 use cw_storage_plus::{Item, SnapshotMap};
 
 /// This structure stores the main config parameters for a constant product pair contract.
@@ -27,9 +28,31 @@ pub struct Config {
     pub tax_config_admin: Addr,
 }
 
+impl Config {
+    /// Returns the hash of the config fields which aren't updated by swaps and liquidity changes.
+    pub fn hash(&self) -> StdResult<[u8; 32]> {
+        config_hash(&Config {
+            block_time_last: 0,
+            price0_cumulative_last: Uint128::zero(),
+            price1_cumulative_last: Uint128::zero(),
+            ..self.clone()
+        })
+    }
+}
+
 /// Stores the config struct at the given key
 pub const CONFIG: Item<Config> = Item::new("config");
 
+/// Stores the hash of the config written at instantiation, config updates and migrations
+pub const CONFIG_HASH: Item<[u8; 32]> = Item::new("config_hash");
+
+/// Saves the config along with its hash. Used for every config update except for the price
+/// accumulator updates.
+pub fn save_config_with_hash(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
+    CONFIG.save(storage, config)?;
+    CONFIG_HASH.save(storage, &config.hash()?)
+}
+
 /// Stores asset balances to query them later at any block height
 pub const BALANCES: SnapshotMap<&AssetInfo, Uint128> = SnapshotMap::new(
     "balances",
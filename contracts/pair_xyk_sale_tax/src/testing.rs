@@ -1,8 +1,9 @@
 use astroport::pair_xyk_sale_tax::{SaleTaxInitParams, TaxConfigChecked, TaxConfigsChecked};
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    attr, to_json_binary, Addr, BankMsg, BlockInfo, Coin, CosmosMsg, Decimal, DepsMut, Env, Reply,
-    ReplyOn, Response, StdError, SubMsg, SubMsgResponse, SubMsgResult, Timestamp, Uint128, WasmMsg,
+    attr, to_json_binary, Addr, BankMsg, BlockInfo, Coin, CosmosMsg, Decimal, Decimal256, DepsMut,
+    Env, Reply, ReplyOn, Response, StdError, SubMsg, SubMsgResponse, SubMsgResult, Timestamp,
+    Uint128, WasmMsg,
 };
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse};
 use proptest::prelude::*;
@@ -452,7 +453,14 @@ fn provide_liquidity() {
         }],
     );
     let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
-    assert_eq!(res, ContractError::MaxSlippageAssertion {});
+    assert_eq!(
+        res,
+        ContractError::SlippageTolerance {
+            asset: "asset0000".to_string(),
+            deviation: Decimal256::percent(2),
+            allowed: Decimal::percent(1),
+        }
+    );
 
     // Initialize token balance to 1:1
     deps.querier.with_balance(&[(
@@ -493,7 +501,14 @@ fn provide_liquidity() {
         }],
     );
     let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
-    assert_eq!(res, ContractError::MaxSlippageAssertion {});
+    assert_eq!(
+        res,
+        ContractError::SlippageTolerance {
+            asset: "asset0000".to_string(),
+            deviation: Decimal256::percent(2),
+            allowed: Decimal::percent(1),
+        }
+    );
 
     // Initialize token amount with a 1:1 ratio
     deps.querier.with_balance(&[(
@@ -1437,6 +1452,9 @@ fn test_accumulate_prices() {
                     contract_addr: Addr::unchecked("pair"),
                     liquidity_token: Addr::unchecked("lp_token"),
                     pair_type: PairType::Xyk {}, // Implemented in mock querier
+                    creator: None,
+                    creation_height: 0,
+                    creation_time: 0,
                 },
                 factory_addr: Addr::unchecked("factory"),
                 block_time_last: case.block_time_last,
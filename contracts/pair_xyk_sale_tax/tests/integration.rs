@@ -585,6 +585,7 @@ fn test_compatibility_of_tokens_with_different_precision() {
         ],
         pair_type: PairType::Custom(env!("CARGO_PKG_NAME").to_string()),
         init_params: Some(to_json_binary(&SaleTaxInitParams::default()).unwrap()),
+        proposal_id: None,
     };
 
     app.execute_contract(owner.clone(), factory_instance.clone(), &msg, &[])
@@ -968,6 +969,7 @@ fn asset_balances_tracking_works_correctly() {
             })
             .unwrap(),
         ),
+        proposal_id: None,
     };
 
     app.execute_contract(owner.clone(), factory_instance.clone(), &msg, &[])
@@ -1177,6 +1179,7 @@ fn asset_balances_tracking_works_correctly() {
             })
             .unwrap(),
         ),
+        proposal_id: None,
     };
 
     app.execute_contract(owner.clone(), factory_instance.clone(), &msg, &[])
@@ -339,6 +339,7 @@ impl Helper {
             pair_type,
             asset_infos: asset_infos.clone(),
             init_params: Some(inner_params),
+            proposal_id: None,
         };
 
         app.execute_contract(owner.clone(), factory.clone(), &init_pair_msg, &[])?;
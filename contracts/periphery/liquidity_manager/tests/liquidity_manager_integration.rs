@@ -19,6 +19,9 @@ fn test_xyk() {
         test_coins.clone(),
         PoolParams::Constant(XYKPoolParams {
             track_asset_balances: None,
+            max_spread: None,
+            lp_cooldown_secs: None,
+            track_swap_history: None,
         }),
     )
     .unwrap();
@@ -68,10 +71,10 @@ fn test_xyk() {
         .unwrap_err();
 
     // However, he is safe because of slippage assertion.
-    assert_eq!(
-        astroport_pair::error::ContractError::MaxSlippageAssertion {},
-        err.downcast().unwrap()
-    );
+    assert!(matches!(
+        err.downcast().unwrap(),
+        astroport_pair::error::ContractError::SlippageTolerance { .. }
+    ));
 
     // User intentionally allowing 50% slippage. But he is providing via liquidity manager contract.
     let slippage_tol = Some(f64_to_dec(0.5));
@@ -397,6 +400,9 @@ fn test_withdraw() {
         test_coins.clone(),
         PoolParams::Constant(XYKPoolParams {
             track_asset_balances: None,
+            max_spread: None,
+            lp_cooldown_secs: None,
+            track_swap_history: None,
         }),
     )
     .unwrap();
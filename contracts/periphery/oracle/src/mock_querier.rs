@@ -107,6 +107,9 @@ impl WasmMockQuerier {
                                 contract_addr: Addr::unchecked("pair"),
                                 liquidity_token: Addr::unchecked("lp_token"),
                                 pair_type: PairType::Xyk {},
+                                creator: None,
+                                creation_height: 0,
+                                creation_time: 0,
                             })
                             .into(),
                         ),
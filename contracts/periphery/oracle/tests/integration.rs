@@ -362,6 +362,7 @@ fn create_pair(
                 pair_type: PairType::Xyk {},
                 asset_infos: asset_infos.clone(),
                 init_params: None,
+                proposal_id: None,
             },
             &[],
         )
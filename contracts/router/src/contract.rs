@@ -1,21 +1,40 @@
 use cosmwasm_std::{
-    entry_point, from_json, to_json_binary, wasm_execute, Addr, Api, Binary, Decimal, Deps,
-    DepsMut, Env, MessageInfo, Reply, Response, StdError, StdResult, SubMsg, SubMsgResult, Uint128,
+    attr, coin, entry_point, from_json, to_json_binary, wasm_execute, Addr, Binary, Decimal, Deps,
+    DepsMut, Env, Event, MessageInfo, Order, QuerierWrapper, Reply, Response, StdError, StdResult,
+    Storage, SubMsg, SubMsgResult, Uint128, WasmMsg,
 };
 use cw2::{get_contract_version, set_contract_version};
-use cw20::Cw20ReceiveMsg;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 
-use astroport::asset::{addr_opt_validate, Asset, AssetInfo};
-use astroport::pair::{QueryMsg as PairQueryMsg, SimulationResponse};
+use astroport::asset::{addr_opt_validate, Asset, AssetInfo, AssetInfoExt};
+use astroport::factory::QueryMsg as FactoryQueryMsg;
+use astroport::native_coin_wrapper::{Config as WrapperConfig, QueryMsg as WrapperQueryMsg};
+use astroport::pair::{
+    PoolResponse, QueryMsg as PairQueryMsg, SimulationResponse, DEFAULT_SLIPPAGE,
+    MAX_ALLOWED_SLIPPAGE,
+};
 use astroport::querier::query_pair_info;
 use astroport::router::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
-    SimulateSwapOperationsResponse, SwapOperation, SwapResponseData, MAX_SWAP_OPERATIONS,
+    ChainedSimulationResponse, ConfigResponse, Cw20HookMsg, ExecuteMsg, GasEstimateResponse,
+    InstantiateMsg, MigrateMsg, MultiPathSimulationResponse, PairDepthResponse, QueryMsg,
+    SimulateSwapOperationsResponse, StreamingSwap, SwapOperation, SwapResponseData,
+    VolumeDiscountConfig, MAX_DEPTH_LEVELS, MAX_MULTI_PATHS, MAX_SWAP_OPERATIONS,
+    VOLUME_RETENTION_DAYS,
 };
 
 use crate::error::ContractError;
-use crate::operations::execute_swap_operation;
-use crate::state::{Config, ReplyData, CONFIG, REPLY_DATA};
+use crate::multi_swap::{advance_multi_asset_swap, multi_asset_swap};
+use crate::operations::{
+    execute_swap_operation, execute_wrap_operation, query_wrapper, spread_ratio,
+};
+use crate::state::{
+    Config, PendingRoute, ReplyData, CONFIG, CONFIG_V111, DEFAULT_DUST_THRESHOLD, PENDING_ROUTE,
+    PRE_RECEIVE_BALANCE, REPLY_DATA, SLIPPAGE_HISTORY, STREAMING_SWAPS, USER_DAILY_VOLUME,
+    USER_VOLUME_30D, WRAPPER_REGISTRY,
+};
+use crate::streaming::{
+    cancel_streaming_swap, create_streaming_swap, execute_streaming_swap_chunk,
+};
 
 /// Contract name that is used for migration.
 const CONTRACT_NAME: &str = "astroport-router";
@@ -23,12 +42,29 @@ const CONTRACT_NAME: &str = "astroport-router";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub const AFTER_SWAP_REPLY_ID: u64 = 1;
+/// Failures of the swap operations preceding the last one are replied with this offset plus the operation index
+pub const HOP_REPLY_ID_OFFSET: u64 = 1000;
+/// The gas used by the router itself on top of the swap operations
+const BASE_GAS_OVERHEAD: u64 = 150_000;
+/// The gas used to send a CW20 offer asset to the router
+const CW20_OFFER_GAS: u64 = 100_000;
+/// The gas of a swap in a pair type without a gas benchmark in the factory
+const DEFAULT_GAS_PER_SWAP: u64 = 300_000;
+/// The allowed difference between the sum of [`ExecuteMsg::SwapAndSplit`] recipient shares or
+/// [`QueryMsg::SimulateMultiPath`] splits and 1
+const SHARES_TOLERANCE: Decimal = Decimal::raw(1_000_000_000_000);
+/// The number of binary search iterations used to balance the split between two paths
+const OPTIMAL_SPLIT_ITERATIONS: u32 = 16;
+/// The number of passes over the path pairs when the optimal split of more than two paths is searched
+const OPTIMAL_SPLIT_SWEEPS: u32 = 2;
+/// The number of binary search iterations used to find the largest swap within a price impact level
+const DEPTH_SEARCH_ITERATIONS: u32 = 20;
 
 /// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
@@ -38,6 +74,16 @@ pub fn instantiate(
         deps.storage,
         &Config {
             astroport_factory: deps.api.addr_validate(&msg.astroport_factory)?,
+            dust_threshold: DEFAULT_DUST_THRESHOLD,
+            gas_price: Decimal::zero(),
+            check_received_amount: false,
+            volume_discount: VolumeDiscountConfig::default(),
+            chain_id: if env.block.chain_id.is_empty() {
+                msg.chain_id.unwrap_or_default()
+            } else {
+                env.block.chain_id
+            },
+            allowed_callback_contracts: None,
         },
     )?;
 
@@ -53,11 +99,64 @@ pub fn instantiate(
 /// * **ExecuteMsg::ExecuteSwapOperations {
 ///             operations,
 ///             minimum_receive,
-///             to
+///             to,
+///             max_spread,
+///             chain_id
 ///         }** Performs swap operations with the specified parameters.
 ///
+/// * **ExecuteMsg::SwapAndSplit {
+///             operations,
+///             recipients,
+///             min_total_receive
+///         }** Performs swap operations and splits the ask tokens between the recipients.
+///
+/// * **ExecuteMsg::ExecuteSwapOperationsWithCallback {
+///             operations,
+///             min_receive,
+///             callback,
+///             callback_contract
+///         }** Performs swap operations and executes the callback with the ask tokens.
+///
+/// * **ExecuteMsg::CreateStreamingSwap {
+///             offer_asset,
+///             operations,
+///             chunks,
+///             interval_secs,
+///             min_chunk_receive
+///         }** Escrows native offer assets and swaps them in chunks over time.
+///
+/// * **ExecuteMsg::ExecuteStreamingSwapChunk { swap_id }** Swaps the next chunk of a streaming swap.
+///
+/// * **ExecuteMsg::CancelStreamingSwap { swap_id }** Cancels a streaming swap and refunds the unswapped assets.
+///
+/// * **ExecuteMsg::UpdateConfig {
+///             astroport_factory,
+///             dust_threshold,
+///             gas_price,
+///             check_received_amount,
+///             volume_discount
+///         }** Updates the router configuration.
+///
+/// * **ExecuteMsg::SetWrapper { denom, wrapper }** Sets or removes the native coin wrapper for a denom.
+///
+/// * **ExecuteMsg::UpdateAllowedCallbackContracts { contracts }** Sets the contracts which can
+/// receive swap callbacks.
+///
+/// * **ExecuteMsg::MultiAssetSwap {
+///             conversions,
+///             min_total_receive,
+///             to
+///         }** Swaps several offer assets and combines the ask assets.
+///
 /// * **ExecuteMsg::ExecuteSwapOperation { operation, to }** Execute a single swap operation.
 ///
+/// * **ExecuteMsg::ExecuteWrapOperation {
+///             offer_asset_info,
+///             ask_asset_info
+///         }** Converts the router balance between the native and CW20 representations of a coin.
+///
+/// * **ExecuteMsg::AdvanceMultiAssetSwap {}** Checks the previous conversion of a multi-asset swap.
+///
 /// * **ExecuteMsg::AssertMinimumReceive {
 ///             asset_info,
 ///             prev_balance,
@@ -72,12 +171,13 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, msg),
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
         ExecuteMsg::ExecuteSwapOperations {
             operations,
             minimum_receive,
             to,
             max_spread,
+            chain_id,
         } => execute_swap_operations(
             deps,
             env,
@@ -86,14 +186,271 @@ pub fn execute(
             minimum_receive,
             to,
             max_spread,
+            chain_id,
+            vec![],
+            None,
         ),
+        ExecuteMsg::SwapAndSplit {
+            operations,
+            recipients,
+            min_total_receive,
+        } => swap_and_split(
+            deps,
+            env,
+            info.sender,
+            operations,
+            recipients,
+            min_total_receive,
+        ),
+        ExecuteMsg::ExecuteSwapOperationsWithCallback {
+            operations,
+            min_receive,
+            callback,
+            callback_contract,
+        } => execute_swap_operations_with_callback(
+            deps,
+            env,
+            info.sender,
+            operations,
+            min_receive,
+            callback,
+            callback_contract,
+        ),
+        ExecuteMsg::CreateStreamingSwap {
+            offer_asset,
+            operations,
+            chunks,
+            interval_secs,
+            min_chunk_receive,
+        } => {
+            offer_asset.info.check(deps.api)?;
+            if !offer_asset.is_native_token() {
+                return Err(ContractError::Cw20StreamingSwapMustUseHook {});
+            }
+            offer_asset.assert_sent_native_token_balance(&info)?;
+
+            create_streaming_swap(
+                deps,
+                env,
+                info.sender,
+                offer_asset,
+                operations,
+                chunks,
+                interval_secs,
+                min_chunk_receive,
+            )
+        }
+        ExecuteMsg::ExecuteStreamingSwapChunk { swap_id } => {
+            execute_streaming_swap_chunk(deps, env, swap_id)
+        }
+        ExecuteMsg::CancelStreamingSwap { swap_id } => cancel_streaming_swap(deps, info, swap_id),
+        ExecuteMsg::UpdateConfig {
+            astroport_factory,
+            dust_threshold,
+            gas_price,
+            check_received_amount,
+            volume_discount,
+        } => update_config(
+            deps,
+            env,
+            info,
+            astroport_factory,
+            dust_threshold,
+            gas_price,
+            check_received_amount,
+            volume_discount,
+        ),
+        ExecuteMsg::SetWrapper { denom, wrapper } => set_wrapper(deps, env, info, denom, wrapper),
+        ExecuteMsg::UpdateAllowedCallbackContracts { contracts } => {
+            update_allowed_callback_contracts(deps, env, info, contracts)
+        }
+        ExecuteMsg::MultiAssetSwap {
+            conversions,
+            min_total_receive,
+            to,
+        } => multi_asset_swap(deps, env, info, conversions, min_total_receive, to),
         ExecuteMsg::ExecuteSwapOperation {
             operation,
             to,
             max_spread,
             single,
         } => execute_swap_operation(deps, env, info, operation, to, max_spread, single),
+        ExecuteMsg::ExecuteWrapOperation {
+            offer_asset_info,
+            ask_asset_info,
+        } => execute_wrap_operation(deps, env, info, offer_asset_info, ask_asset_info),
+        ExecuteMsg::AdvanceMultiAssetSwap {} => advance_multi_asset_swap(deps, env, info),
+    }
+}
+
+/// Updates the router configuration.
+///
+/// * **astroport_factory** new Astroport factory address.
+///
+/// * **dust_threshold** new minimum offer amount of a swap operation.
+///
+/// * **gas_price** new native gas price used to estimate swap fees.
+///
+/// * **check_received_amount** whether swap operations only offer the amount received from the
+/// previous operation.
+///
+/// * **volume_discount** new fee discount tiers of the swappers.
+///
+/// ## Executor
+/// Only the contract admin can execute this.
+#[allow(clippy::too_many_arguments)]
+pub fn update_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    astroport_factory: Option<String>,
+    dust_threshold: Option<Uint128>,
+    gas_price: Option<Decimal>,
+    check_received_amount: Option<bool>,
+    volume_discount: Option<VolumeDiscountConfig>,
+) -> Result<Response, ContractError> {
+    assert_admin(deps.as_ref(), &env, &info.sender)?;
+
+    let mut config = CONFIG.load(deps.storage)?;
+    let mut attrs = vec![attr("action", "update_config")];
+
+    if let Some(astroport_factory) = astroport_factory {
+        config.astroport_factory = deps.api.addr_validate(&astroport_factory)?;
+        attrs.push(attr("astroport_factory", astroport_factory));
+    }
+
+    if let Some(dust_threshold) = dust_threshold {
+        config.dust_threshold = dust_threshold;
+        attrs.push(attr("dust_threshold", dust_threshold));
+    }
+
+    if let Some(gas_price) = gas_price {
+        config.gas_price = gas_price;
+        attrs.push(attr("gas_price", gas_price.to_string()));
+    }
+
+    if let Some(check_received_amount) = check_received_amount {
+        config.check_received_amount = check_received_amount;
+        attrs.push(attr(
+            "check_received_amount",
+            check_received_amount.to_string(),
+        ));
+    }
+
+    if let Some(volume_discount) = volume_discount {
+        let volumes_increase = volume_discount
+            .tiers
+            .windows(2)
+            .all(|tiers| tiers[0].0 < tiers[1].0);
+        if !volumes_increase
+            || volume_discount
+                .tiers
+                .iter()
+                .any(|(_, discount_bps)| *discount_bps > 10000)
+        {
+            return Err(ContractError::InvalidVolumeDiscountTiers {});
+        }
+
+        attrs.push(attr(
+            "volume_discount_tiers",
+            volume_discount.tiers.len().to_string(),
+        ));
+        config.volume_discount = volume_discount;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(attrs))
+}
+
+/// Registers the native coin wrapper used to convert between the native and CW20 representations
+/// of `denom`. Swap routes using both representations are converted with this wrapper.
+///
+/// * **denom** native coin denom.
+///
+/// * **wrapper** native coin wrapper contract. `None` removes the registered wrapper.
+///
+/// ## Executor
+/// Only the contract admin can execute this.
+pub fn set_wrapper(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denom: String,
+    wrapper: Option<String>,
+) -> Result<Response, ContractError> {
+    assert_admin(deps.as_ref(), &env, &info.sender)?;
+
+    let mut attrs = vec![attr("action", "set_wrapper"), attr("denom", &denom)];
+
+    if let Some(wrapper) = wrapper {
+        let wrapper = deps.api.addr_validate(&wrapper)?;
+        let wrapper_config: WrapperConfig = deps
+            .querier
+            .query_wasm_smart(&wrapper, &WrapperQueryMsg::Config {})?;
+        if wrapper_config.denom != denom {
+            return Err(ContractError::InvalidWrapper {
+                wrapper: wrapper.to_string(),
+                denom,
+            });
+        }
+
+        WRAPPER_REGISTRY.save(deps.storage, denom, &wrapper)?;
+        attrs.push(attr("wrapper", wrapper));
+    } else {
+        WRAPPER_REGISTRY.remove(deps.storage, denom);
+    }
+
+    Ok(Response::new().add_attributes(attrs))
+}
+
+/// Sets the contracts which can receive the callbacks of
+/// [`ExecuteMsg::ExecuteSwapOperationsWithCallback`].
+///
+/// * **contracts** allowed callback contracts. `None` disables callbacks.
+///
+/// ## Executor
+/// Only the contract admin can execute this.
+pub fn update_allowed_callback_contracts(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contracts: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    assert_admin(deps.as_ref(), &env, &info.sender)?;
+
+    let mut config = CONFIG.load(deps.storage)?;
+    config.allowed_callback_contracts = contracts
+        .map(|contracts| {
+            contracts
+                .iter()
+                .map(|contract| deps.api.addr_validate(contract))
+                .collect::<StdResult<Vec<_>>>()
+        })
+        .transpose()?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "update_allowed_callback_contracts"),
+        attr(
+            "allowed_callback_contracts",
+            config
+                .allowed_callback_contracts
+                .map_or("none".to_string(), |contracts| contracts.len().to_string()),
+        ),
+    ]))
+}
+
+/// Checks that the sender is the admin of the router contract.
+fn assert_admin(deps: Deps, env: &Env, sender: &Addr) -> Result<(), ContractError> {
+    let contract_info = deps
+        .querier
+        .query_wasm_contract_info(&env.contract.address)?;
+    if contract_info.admin != Some(sender.to_string()) {
+        return Err(ContractError::Unauthorized {});
     }
+
+    Ok(())
 }
 
 /// Receives a message of type [`Cw20ReceiveMsg`] and processes it depending on the received template.
@@ -102,6 +459,7 @@ pub fn execute(
 pub fn receive_cw20(
     deps: DepsMut,
     env: Env,
+    info: MessageInfo,
     cw20_msg: Cw20ReceiveMsg,
 ) -> Result<Response, ContractError> {
     match from_json(&cw20_msg.msg)? {
@@ -110,6 +468,7 @@ pub fn receive_cw20(
             minimum_receive,
             to,
             max_spread,
+            chain_id,
         } => execute_swap_operations(
             deps,
             env,
@@ -118,6 +477,55 @@ pub fn receive_cw20(
             minimum_receive,
             to,
             max_spread,
+            chain_id,
+            vec![],
+            None,
+        ),
+        Cw20HookMsg::SwapAndSplit {
+            operations,
+            recipients,
+            min_total_receive,
+        } => swap_and_split(
+            deps,
+            env,
+            Addr::unchecked(cw20_msg.sender),
+            operations,
+            recipients,
+            min_total_receive,
+        ),
+        Cw20HookMsg::ExecuteSwapOperationsWithCallback {
+            operations,
+            min_receive,
+            callback,
+            callback_contract,
+        } => execute_swap_operations_with_callback(
+            deps,
+            env,
+            Addr::unchecked(cw20_msg.sender),
+            operations,
+            min_receive,
+            callback,
+            callback_contract,
+        ),
+        Cw20HookMsg::CreateStreamingSwap {
+            operations,
+            chunks,
+            interval_secs,
+            min_chunk_receive,
+        } => create_streaming_swap(
+            deps,
+            env,
+            Addr::unchecked(cw20_msg.sender),
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: info.sender,
+                },
+                amount: cw20_msg.amount,
+            },
+            operations,
+            chunks,
+            interval_secs,
+            min_chunk_receive,
         ),
     }
 }
@@ -131,6 +539,13 @@ pub fn receive_cw20(
 /// * **minimum_receive** used to guarantee that the ask amount is above a minimum amount.
 ///
 /// * **to** recipient of the ask tokens.
+///
+/// * **recipients** addresses which split the ask tokens received by `to` along with their shares.
+/// Empty if `to` keeps the ask tokens.
+///
+/// * **callback** contract and message executed with the ask tokens received by `to`.
+///
+/// Failures of the swap operations are replied and reported with the index of the failed operation.
 #[allow(clippy::too_many_arguments)]
 pub fn execute_swap_operations(
     deps: DepsMut,
@@ -140,18 +555,76 @@ pub fn execute_swap_operations(
     minimum_receive: Option<Uint128>,
     to: Option<String>,
     max_spread: Option<Decimal>,
+    chain_id: Option<String>,
+    recipients: Vec<(Addr, Decimal)>,
+    callback: Option<(Addr, Binary)>,
 ) -> Result<Response, ContractError> {
-    assert_operations(deps.api, &operations)?;
+    let config = CONFIG.load(deps.storage)?;
+    if let Some(chain_id) = chain_id {
+        if chain_id != config.chain_id {
+            return Err(ContractError::WrongChainId {
+                expected: config.chain_id,
+                provided: chain_id,
+            });
+        }
+    }
 
-    let to = addr_opt_validate(deps.api, &to)?.unwrap_or(sender);
+    assert_operations(deps.as_ref(), &operations)?;
+
+    // The volume and the discount tiers are denominated in the ask asset of the route
     let target_asset_info = operations.last().unwrap().get_target_asset_info();
+    let volume = prune_user_volume(
+        deps.storage,
+        &sender,
+        &target_asset_info,
+        env.block.time.seconds() / 86400,
+    )?;
+    let fee_discount_bps = config.volume_discount.discount_bps(volume);
+    let max_spread = discounted_max_spread(max_spread, fee_discount_bps)?;
+
+    // The first operation offers the assets sent with this message
+    PRE_RECEIVE_BALANCE.remove(deps.storage);
+    PENDING_ROUTE.save(
+        deps.storage,
+        &PendingRoute {
+            sender: sender.clone(),
+            operations_len: operations.len() as u32,
+        },
+    )?;
+
+    let to = addr_opt_validate(deps.api, &to)?.unwrap_or(sender);
     let operations_len = operations.len();
 
-    let messages = operations
-        .into_iter()
-        .enumerate()
-        .map(|(operation_index, op)| {
-            if operation_index == operations_len - 1 {
+    let mut messages = vec![];
+    let mut prev_ask_asset_info: Option<AssetInfo> = None;
+    for (operation_index, op) in operations.into_iter().enumerate() {
+        // Converts the previous ask asset if the operation uses another representation of the coin
+        if let (
+            Some(prev_ask_asset_info),
+            SwapOperation::AstroSwap {
+                offer_asset_info, ..
+            },
+        ) = (&prev_ask_asset_info, &op)
+        {
+            if !prev_ask_asset_info.equal(offer_asset_info) {
+                // Conversion failures are reported as failures of the operation they precede
+                messages.push(SubMsg::reply_on_error(
+                    wasm_execute(
+                        env.contract.address.to_string(),
+                        &ExecuteMsg::ExecuteWrapOperation {
+                            offer_asset_info: prev_ask_asset_info.clone(),
+                            ask_asset_info: offer_asset_info.clone(),
+                        },
+                        vec![],
+                    )?,
+                    HOP_REPLY_ID_OFFSET + operation_index as u64,
+                ));
+            }
+        }
+        prev_ask_asset_info = Some(op.get_target_asset_info());
+
+        if operation_index == operations_len - 1 {
+            messages.push(SubMsg::reply_always(
                 wasm_execute(
                     env.contract.address.to_string(),
                     &ExecuteMsg::ExecuteSwapOperation {
@@ -161,9 +634,11 @@ pub fn execute_swap_operations(
                         single: operations_len == 1,
                     },
                     vec![],
-                )
-                .map(|inner_msg| SubMsg::reply_on_success(inner_msg, AFTER_SWAP_REPLY_ID))
-            } else {
+                )?,
+                AFTER_SWAP_REPLY_ID,
+            ));
+        } else {
+            messages.push(SubMsg::reply_on_error(
                 wasm_execute(
                     env.contract.address.to_string(),
                     &ExecuteMsg::ExecuteSwapOperation {
@@ -173,11 +648,11 @@ pub fn execute_swap_operations(
                         single: operations_len == 1,
                     },
                     vec![],
-                )
-                .map(SubMsg::new)
-            }
-        })
-        .collect::<StdResult<Vec<_>>>()?;
+                )?,
+                HOP_REPLY_ID_OFFSET + operation_index as u64,
+            ));
+        }
+    }
 
     let prev_balance = target_asset_info.query_pool(&deps.querier, &to)?;
     REPLY_DATA.save(
@@ -187,24 +662,224 @@ pub fn execute_swap_operations(
             prev_balance,
             minimum_receive,
             receiver: to.to_string(),
+            recipients,
+            callback,
         },
     )?;
 
-    Ok(Response::new().add_submessages(messages))
+    Ok(Response::new()
+        .add_submessages(messages)
+        .add_attribute("fee_discount_bps", fee_discount_bps.to_string()))
+}
+
+/// Widens the max spread of every swap operation by the fee discount of the swapper, capped at
+/// [`MAX_ALLOWED_SLIPPAGE`]. The max spread is left unchanged if there is no discount.
+fn discounted_max_spread(
+    max_spread: Option<Decimal>,
+    fee_discount_bps: u16,
+) -> StdResult<Option<Decimal>> {
+    if fee_discount_bps == 0 {
+        return Ok(max_spread);
+    }
+
+    let max_spread = match max_spread {
+        Some(max_spread) => max_spread,
+        None => DEFAULT_SLIPPAGE.parse()?,
+    };
+    let discounted = max_spread.checked_add(Decimal::from_ratio(fee_discount_bps, 10000u16))?;
+
+    Ok(Some(discounted.min(MAX_ALLOWED_SLIPPAGE.parse()?)))
+}
+
+/// Removes the daily volumes of `address` in `asset_info` older than [`VOLUME_RETENTION_DAYS`]
+/// before `day` and returns the remaining volume.
+fn prune_user_volume(
+    storage: &mut dyn Storage,
+    address: &Addr,
+    asset_info: &AssetInfo,
+    day: u64,
+) -> StdResult<Uint128> {
+    let asset_key = asset_info.to_string();
+    let stale_slots = USER_DAILY_VOLUME
+        .prefix((address, asset_key.as_str()))
+        .range(storage, None, None, Order::Ascending)
+        .filter(|item| {
+            item.as_ref().map_or(true, |(_, (slot_day, _))| {
+                slot_day + VOLUME_RETENTION_DAYS <= day
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut volume = USER_VOLUME_30D
+        .may_load(storage, (address, asset_key.as_str()))?
+        .unwrap_or_default();
+    for (slot, (_, stale_volume)) in stale_slots {
+        volume = volume.saturating_sub(stale_volume);
+        USER_DAILY_VOLUME.remove(storage, (address, asset_key.as_str(), slot));
+    }
+    USER_VOLUME_30D.save(storage, (address, asset_key.as_str()), &volume)?;
+
+    Ok(volume)
+}
+
+/// Adds the amount of `asset_info` returned by a route to the volume of `address`. The slot of
+/// `day` is reset first if it still holds an expired day.
+fn record_user_volume(
+    storage: &mut dyn Storage,
+    address: &Addr,
+    asset_info: &AssetInfo,
+    day: u64,
+    amount: Uint128,
+) -> StdResult<()> {
+    let asset_key = asset_info.to_string();
+    let slot_key = (address, asset_key.as_str(), day % VOLUME_RETENTION_DAYS);
+
+    let mut volume = USER_VOLUME_30D
+        .may_load(storage, (address, asset_key.as_str()))?
+        .unwrap_or_default();
+    let day_volume = match USER_DAILY_VOLUME.may_load(storage, slot_key)? {
+        Some((slot_day, day_volume)) if slot_day == day => day_volume,
+        Some((_, stale_volume)) => {
+            volume = volume.saturating_sub(stale_volume);
+            Uint128::zero()
+        }
+        None => Uint128::zero(),
+    };
+
+    USER_DAILY_VOLUME.save(storage, slot_key, &(day, day_volume.checked_add(amount)?))?;
+    USER_VOLUME_30D.save(
+        storage,
+        (address, asset_key.as_str()),
+        &volume.checked_add(amount)?,
+    )?;
+
+    Ok(())
 }
 
+/// Performs swap operations with the ask tokens sent to the router and splits them between the
+/// recipients once the last operation is done.
+///
+/// * **sender** address that swaps tokens.
+///
+/// * **operations** all swap operations to perform.
+///
+/// * **recipients** addresses which receive the ask tokens along with their shares.
+/// The shares must sum to 1 within [`SHARES_TOLERANCE`].
+///
+/// * **min_total_receive** used to guarantee that the ask amount before the split is above a minimum amount.
+pub fn swap_and_split(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    operations: Vec<SwapOperation>,
+    recipients: Vec<(String, Decimal)>,
+    min_total_receive: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let recipients = recipients
+        .into_iter()
+        .map(|(recipient, share)| Ok((deps.api.addr_validate(&recipient)?, share)))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let total_share = recipients
+        .iter()
+        .try_fold(Decimal::zero(), |total, (_, share)| {
+            if share.is_zero() {
+                Err(ContractError::RecipientSharesInvalid {})
+            } else {
+                Ok(total.checked_add(*share)?)
+            }
+        })?;
+    if total_share.abs_diff(Decimal::one()) > SHARES_TOLERANCE {
+        return Err(ContractError::RecipientSharesInvalid {});
+    }
+
+    let router = env.contract.address.to_string();
+    execute_swap_operations(
+        deps,
+        env,
+        sender,
+        operations,
+        min_total_receive,
+        Some(router),
+        None,
+        None,
+        recipients,
+        None,
+    )
+}
+
+/// Performs swap operations with the ask tokens sent to the router and executes `callback` on
+/// `callback_contract` with them once the last operation is done.
+///
+/// * **sender** address that swaps tokens.
+///
+/// * **operations** all swap operations to perform.
+///
+/// * **min_receive** used to guarantee that the ask amount is above a minimum amount.
+///
+/// * **callback** message executed on the callback contract.
+///
+/// * **callback_contract** contract receiving the ask tokens. Must be in
+/// [`Config::allowed_callback_contracts`].
+pub fn execute_swap_operations_with_callback(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    operations: Vec<SwapOperation>,
+    min_receive: Option<Uint128>,
+    callback: Binary,
+    callback_contract: String,
+) -> Result<Response, ContractError> {
+    let callback_contract = deps.api.addr_validate(&callback_contract)?;
+    let is_allowed = CONFIG
+        .load(deps.storage)?
+        .allowed_callback_contracts
+        .map_or(false, |contracts| contracts.contains(&callback_contract));
+    if !is_allowed {
+        return Err(ContractError::CallbackContractNotAllowed(
+            callback_contract.into_string(),
+        ));
+    }
+
+    let router = env.contract.address.to_string();
+    execute_swap_operations(
+        deps,
+        env,
+        sender,
+        operations,
+        min_receive,
+        Some(router),
+        None,
+        None,
+        vec![],
+        Some((callback_contract, callback)),
+    )
+}
+
+/// Checks the result of the last swap operation and translates failures of swap operations
+/// into [`ContractError::HopFailed`] errors.
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
     match msg {
         Reply {
             id: AFTER_SWAP_REPLY_ID,
             result: SubMsgResult::Ok(..),
         } => {
+            let pending_route = PENDING_ROUTE.load(deps.storage)?;
+            PENDING_ROUTE.remove(deps.storage);
+
             let reply_data = REPLY_DATA.load(deps.storage)?;
             let receiver_balance = reply_data
                 .asset_info
                 .query_pool(&deps.querier, reply_data.receiver)?;
             let swap_amount = receiver_balance.checked_sub(reply_data.prev_balance)?;
+            record_user_volume(
+                deps.storage,
+                &pending_route.sender,
+                &reply_data.asset_info,
+                env.block.time.seconds() / 86400,
+                swap_amount,
+            )?;
 
             if let Some(minimum_receive) = reply_data.minimum_receive {
                 if swap_amount < minimum_receive {
@@ -220,7 +895,83 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
                 return_amount: swap_amount,
             })?;
 
-            Ok(Response::new().set_data(data))
+            let mut response = Response::new().set_data(data).add_event(
+                Event::new("route_completed").add_attributes([
+                    attr("sender", pending_route.sender),
+                    attr("hops", pending_route.operations_len.to_string()),
+                    attr("return_amount", swap_amount),
+                ]),
+            );
+            if let Some(((first_recipient, _), other_recipients)) =
+                reply_data.recipients.split_first()
+            {
+                // Shares are rounded down and the dust is sent to the first recipient
+                let mut amounts = Vec::with_capacity(reply_data.recipients.len());
+                let mut distributed = Uint128::zero();
+                for (recipient, share) in other_recipients {
+                    let amount = swap_amount * *share;
+                    distributed = distributed.checked_add(amount)?;
+                    amounts.push((recipient, amount));
+                }
+                amounts.insert(0, (first_recipient, swap_amount.checked_sub(distributed)?));
+
+                for (recipient, amount) in amounts {
+                    if !amount.is_zero() {
+                        response = response.add_message(
+                            reply_data
+                                .asset_info
+                                .with_balance(amount)
+                                .into_msg(recipient)?,
+                        );
+                    }
+                    response = response.add_attribute("split", format!("{recipient}:{amount}"));
+                }
+            }
+
+            if let Some((callback_contract, callback)) = reply_data.callback {
+                let callback_msg = match &reply_data.asset_info {
+                    AssetInfo::Token { contract_addr } if !swap_amount.is_zero() => wasm_execute(
+                        contract_addr,
+                        &Cw20ExecuteMsg::Send {
+                            contract: callback_contract.to_string(),
+                            amount: swap_amount,
+                            msg: callback,
+                        },
+                        vec![],
+                    )?,
+                    asset_info => {
+                        // The callback is executed without funds if the route returned nothing
+                        let funds = match asset_info {
+                            AssetInfo::NativeToken { denom } if !swap_amount.is_zero() => {
+                                vec![coin(swap_amount.u128(), denom)]
+                            }
+                            _ => vec![],
+                        };
+                        WasmMsg::Execute {
+                            contract_addr: callback_contract.to_string(),
+                            msg: callback,
+                            funds,
+                        }
+                    }
+                };
+                response = response
+                    .add_message(callback_msg)
+                    .add_attribute("callback_contract", callback_contract);
+            }
+
+            Ok(response)
+        }
+        Reply {
+            id,
+            result: SubMsgResult::Err(reason),
+        } if id == AFTER_SWAP_REPLY_ID || id >= HOP_REPLY_ID_OFFSET => {
+            let hop_index = if id == AFTER_SWAP_REPLY_ID {
+                PENDING_ROUTE.load(deps.storage)?.operations_len - 1
+            } else {
+                (id - HOP_REPLY_ID_OFFSET) as u32
+            };
+
+            Err(ContractError::HopFailed { hop_index, reason })
         }
         _ => Err(StdError::generic_err("Failed to process reply").into()),
     }
@@ -229,14 +980,39 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
 /// Exposes all the queries available in the contract.
 /// ## Queries
 /// * **QueryMsg::Config {}** Returns general router parameters using a [`ConfigResponse`] object.
+/// * **QueryMsg::StreamingSwap { swap_id }** Returns the state of a streaming swap using a [`StreamingSwap`] object.
+/// * **QueryMsg::Wrapper { denom }** Returns the native coin wrapper registered for the denom.
 /// * **QueryMsg::SimulateSwapOperations {
 ///             offer_amount,
 ///             operations,
 ///         }** Simulates one or multiple swap operations and returns the end result in a [`SimulateSwapOperationsResponse`] object.
+/// * **QueryMsg::SimulateChainedSwapWithSlippage {
+///             offer_asset,
+///             operations,
+///             confidence,
+///         }** Simulates one or multiple swap operations and returns the estimated range of the end result in a [`ChainedSimulationResponse`] object.
+/// * **QueryMsg::GasEstimate {
+///             operations,
+///             offer_asset,
+///         }** Estimates the gas of one or multiple swap operations using a [`GasEstimateResponse`] object.
+/// * **QueryMsg::SimulateMultiPath {
+///             offer_asset,
+///             paths,
+///             splits,
+///         }** Simulates a swap split between parallel paths and searches for the optimal split using a [`MultiPathSimulationResponse`] object.
+/// * **QueryMsg::BatchBalances { address, assets }** Returns the balances of the address in all the specified assets.
+/// * **QueryMsg::ContractInfo {}** Returns the cw2 contract name and version.
+/// * **QueryMsg::PairDepth { offer, ask, depth_levels }** Returns the liquidity of a pair at several price impact levels using a [`PairDepthResponse`] object.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
         QueryMsg::Config {} => Ok(to_json_binary(&query_config(deps)?)?),
+        QueryMsg::StreamingSwap { swap_id } => Ok(to_json_binary(
+            &STREAMING_SWAPS.load(deps.storage, swap_id)?,
+        )?),
+        QueryMsg::Wrapper { denom } => Ok(to_json_binary(
+            &WRAPPER_REGISTRY.may_load(deps.storage, denom)?,
+        )?),
         QueryMsg::SimulateSwapOperations {
             offer_amount,
             operations,
@@ -245,14 +1021,85 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
             offer_amount,
             operations,
         )?)?),
+        QueryMsg::SimulateChainedSwapWithSlippage {
+            offer_asset,
+            operations,
+            confidence,
+        } => Ok(to_json_binary(&simulate_chained_swap_with_slippage(
+            deps,
+            offer_asset,
+            operations,
+            confidence,
+        )?)?),
+        QueryMsg::GasEstimate {
+            operations,
+            offer_asset,
+        } => Ok(to_json_binary(&estimate_gas(
+            deps,
+            operations,
+            offer_asset,
+        )?)?),
+        QueryMsg::SimulateMultiPath {
+            offer_asset,
+            paths,
+            splits,
+        } => Ok(to_json_binary(&simulate_multi_path(
+            deps,
+            offer_asset,
+            paths,
+            splits,
+        )?)?),
+        QueryMsg::BatchBalances { address, assets } => {
+            let address = deps.api.addr_validate(&address)?;
+            Ok(to_json_binary(&batch_query_balances(
+                deps.querier,
+                &address,
+                &assets,
+            )?)?)
+        }
+        QueryMsg::ContractInfo {} => Ok(to_json_binary(&get_contract_version(deps.storage)?)?),
+        QueryMsg::PairDepth {
+            offer,
+            ask,
+            depth_levels,
+        } => Ok(to_json_binary(&query_pair_depth(
+            deps,
+            offer,
+            ask,
+            depth_levels,
+        )?)?),
     }
 }
 
+/// Returns the balances of `address` in all the `assets` in the same order as the assets.
+/// Native balances are read with bank queries and CW20 balances with token balance queries
+/// as CosmWasm has no batched querier.
+pub fn batch_query_balances(
+    querier: QuerierWrapper,
+    address: &Addr,
+    assets: &[AssetInfo],
+) -> StdResult<Vec<Asset>> {
+    assets
+        .iter()
+        .map(|asset_info| {
+            asset_info
+                .query_pool(&querier, address)
+                .map(|amount| asset_info.with_balance(amount))
+        })
+        .collect()
+}
+
 /// Returns general contract settings in a [`ConfigResponse`] object.
 pub fn query_config(deps: Deps) -> Result<ConfigResponse, ContractError> {
     let state = CONFIG.load(deps.storage)?;
     let resp = ConfigResponse {
         astroport_factory: state.astroport_factory.into_string(),
+        dust_threshold: state.dust_threshold,
+        gas_price: state.gas_price,
+        check_received_amount: state.check_received_amount,
+        volume_discount: state.volume_discount,
+        chain_id: state.chain_id,
+        allowed_callback_contracts: state.allowed_callback_contracts,
     };
 
     Ok(resp)
@@ -261,12 +1108,26 @@ pub fn query_config(deps: Deps) -> Result<ConfigResponse, ContractError> {
 /// Manages contract migration.
 #[cfg(not(tarpaulin_include))]
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
     let contract_version = get_contract_version(deps.storage)?;
 
     match contract_version.contract.as_ref() {
         "astroport-router" => match contract_version.version.as_ref() {
-            "1.1.1" => {}
+            "1.1.1" => {
+                let config_v111 = CONFIG_V111.load(deps.storage)?;
+                CONFIG.save(
+                    deps.storage,
+                    &Config {
+                        astroport_factory: config_v111.astroport_factory,
+                        dust_threshold: DEFAULT_DUST_THRESHOLD,
+                        gas_price: Decimal::zero(),
+                        check_received_amount: false,
+                        volume_discount: VolumeDiscountConfig::default(),
+                        chain_id: env.block.chain_id,
+                        allowed_callback_contracts: None,
+                    },
+                )?;
+            }
             _ => return Err(ContractError::MigrationError {}),
         },
         _ => return Err(ContractError::MigrationError {}),
@@ -293,13 +1154,29 @@ fn simulate_swap_operations(
     offer_amount: Uint128,
     operations: Vec<SwapOperation>,
 ) -> Result<SimulateSwapOperationsResponse, ContractError> {
-    assert_operations(deps.api, &operations)?;
+    assert_operations(deps, &operations)?;
 
     let config = CONFIG.load(deps.storage)?;
-    let astroport_factory = config.astroport_factory;
-    let mut return_amount = offer_amount;
 
-    for operation in operations.into_iter() {
+    Ok(SimulateSwapOperationsResponse {
+        amount: simulate_path(deps, &config.astroport_factory, offer_amount, &operations)?,
+    })
+}
+
+/// Returns the amount received from swapping `offer_amount` through already validated swap
+/// operations. Zero offers receive nothing without querying the pairs.
+fn simulate_path(
+    deps: Deps,
+    astroport_factory: &Addr,
+    offer_amount: Uint128,
+    operations: &[SwapOperation],
+) -> Result<Uint128, ContractError> {
+    if offer_amount.is_zero() {
+        return Ok(Uint128::zero());
+    }
+
+    let mut return_amount = offer_amount;
+    for operation in operations {
         match operation {
             SwapOperation::AstroSwap {
                 offer_asset_info,
@@ -307,7 +1184,7 @@ fn simulate_swap_operations(
             } => {
                 let pair_info = query_pair_info(
                     &deps.querier,
-                    astroport_factory.clone(),
+                    astroport_factory,
                     &[offer_asset_info.clone(), ask_asset_info.clone()],
                 )?;
 
@@ -330,15 +1207,444 @@ fn simulate_swap_operations(
         }
     }
 
-    Ok(SimulateSwapOperationsResponse {
-        amount: return_amount,
+    Ok(return_amount)
+}
+
+/// Simulates swapping `offer_asset` split between parallel paths using a
+/// [`MultiPathSimulationResponse`] object. The optimal split is searched starting from the
+/// requested one: the amount swapped through every pair of adjacent paths is rebalanced with a
+/// binary search until the marginal outputs of both paths are equal, which is the optimal
+/// routing condition for AMM curves.
+///
+/// * **offer_asset** asset being swapped.
+///
+/// * **paths** the swap operations of every path. All the paths must start with the offer asset
+/// and end with the same ask asset.
+///
+/// * **splits** the share of the offer amount swapped through each path.
+fn simulate_multi_path(
+    deps: Deps,
+    offer_asset: Asset,
+    paths: Vec<Vec<SwapOperation>>,
+    splits: Vec<Decimal>,
+) -> Result<MultiPathSimulationResponse, ContractError> {
+    if paths.is_empty() || paths.len() > MAX_MULTI_PATHS {
+        return Err(ContractError::InvalidPathsNumber(MAX_MULTI_PATHS));
+    }
+    if offer_asset.amount.is_zero() {
+        return Err(ContractError::ZeroOfferAmount {});
+    }
+    let total_split = splits
+        .iter()
+        .try_fold(Decimal::zero(), |total, split| total.checked_add(*split))?;
+    if splits.len() != paths.len() || total_split.abs_diff(Decimal::one()) > SHARES_TOLERANCE {
+        return Err(ContractError::InvalidSplits {});
+    }
+
+    let mut ask_asset_info: Option<AssetInfo> = None;
+    for path in &paths {
+        assert_operations(deps, path)?;
+        if let (
+            Some(SwapOperation::AstroSwap {
+                offer_asset_info, ..
+            }),
+            Some(SwapOperation::AstroSwap {
+                ask_asset_info: path_ask_asset_info,
+                ..
+            }),
+        ) = (path.first(), path.last())
+        {
+            if !offer_asset_info.equal(&offer_asset.info) {
+                return Err(ContractError::OfferAssetMismatch(
+                    offer_asset.info.to_string(),
+                ));
+            }
+            if ask_asset_info.get_or_insert_with(|| path_ask_asset_info.clone())
+                != path_ask_asset_info
+            {
+                return Err(ContractError::PathsAskAssetMismatch {});
+            }
+        }
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let simulate = |path: &[SwapOperation], amount| {
+        simulate_path(deps, &config.astroport_factory, amount, path)
+    };
+
+    // The last path receives the rounding remainder
+    let mut amounts = splits
+        .iter()
+        .map(|split| offer_asset.amount * *split)
+        .collect::<Vec<_>>();
+    let distributed = amounts.iter().sum::<Uint128>();
+    if let Some(last) = amounts.last_mut() {
+        *last = (*last + offer_asset.amount).saturating_sub(distributed);
+    }
+
+    let outputs_per_path = paths
+        .iter()
+        .zip(&amounts)
+        .map(|(path, amount)| simulate(path, *amount))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let delta = (offer_asset.amount / Uint128::new(1000)).max(Uint128::one());
+    let marginal_output = |path: &[SwapOperation], amount: Uint128| {
+        Ok::<_, ContractError>(
+            simulate(path, amount + delta)?.saturating_sub(simulate(path, amount)?),
+        )
+    };
+    let sweeps = if paths.len() > 2 {
+        OPTIMAL_SPLIT_SWEEPS
+    } else {
+        1
+    };
+    for _ in 0..sweeps {
+        for i in 1..paths.len() {
+            // Finds the smallest amount through the previous path at which its marginal output
+            // doesn't exceed the marginal output of the current path
+            let pair_amount = amounts[i - 1] + amounts[i];
+            let (mut low, mut high) = (Uint128::zero(), pair_amount);
+            for _ in 0..OPTIMAL_SPLIT_ITERATIONS {
+                if low >= high {
+                    break;
+                }
+                let mid = low + (high - low) / Uint128::new(2);
+                if marginal_output(&paths[i - 1], mid)?
+                    > marginal_output(&paths[i], pair_amount - mid)?
+                {
+                    low = mid + Uint128::one();
+                } else {
+                    high = mid;
+                }
+            }
+            amounts[i - 1] = low;
+            amounts[i] = pair_amount - low;
+        }
+    }
+
+    let optimal_total = paths
+        .iter()
+        .zip(&amounts)
+        .map(|(path, amount)| simulate(path, *amount))
+        .sum::<Result<Uint128, _>>()?;
+
+    Ok(MultiPathSimulationResponse {
+        total_output: outputs_per_path.iter().sum(),
+        outputs_per_path,
+        optimal_split: amounts
+            .into_iter()
+            .map(|amount| Decimal::from_ratio(amount, offer_asset.amount))
+            .collect(),
+        optimal_total,
+    })
+}
+
+/// Returns the liquidity of the pair of `offer` and `ask` at every price impact level using a
+/// [`PairDepthResponse`] object. The price impact of a swap is its spread relative to the amount
+/// received at the pool price. As it grows with the swap size, the largest swap within each level
+/// is found with a binary search over the pair simulations, so the result follows the curve of
+/// any pair type. The search is bounded by the pool reserve of the offered asset.
+///
+/// * **depth_levels** the price impact levels in percent.
+fn query_pair_depth(
+    deps: Deps,
+    offer: AssetInfo,
+    ask: AssetInfo,
+    depth_levels: Vec<Decimal>,
+) -> Result<PairDepthResponse, ContractError> {
+    let max_level = Decimal::from_ratio(100u8, 1u8);
+    if depth_levels.is_empty()
+        || depth_levels.len() > MAX_DEPTH_LEVELS
+        || depth_levels
+            .iter()
+            .any(|level| level.is_zero() || *level >= max_level)
+    {
+        return Err(ContractError::InvalidDepthLevels(MAX_DEPTH_LEVELS));
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let pair_info = query_pair_info(
+        &deps.querier,
+        &config.astroport_factory,
+        &[offer.clone(), ask.clone()],
+    )?;
+    let pool: PoolResponse = deps
+        .querier
+        .query_wasm_smart(&pair_info.contract_addr, &PairQueryMsg::Pool {})?;
+    let reserve = |asset_info: &AssetInfo| {
+        pool.assets
+            .iter()
+            .find(|asset| asset.info.equal(asset_info))
+            .map(|asset| asset.amount)
+            .unwrap_or_default()
+    };
+    let (offer_reserve, ask_reserve) = (reserve(&offer), reserve(&ask));
+
+    let mut bids = Vec::with_capacity(depth_levels.len());
+    let mut asks = Vec::with_capacity(depth_levels.len());
+    for level in depth_levels {
+        let max_impact = level * Decimal::percent(1);
+        let (sold, _) = max_swap_within_impact(
+            deps.querier,
+            &pair_info.contract_addr,
+            &ask,
+            &offer,
+            ask_reserve,
+            max_impact,
+        )?;
+        let (_, bought) = max_swap_within_impact(
+            deps.querier,
+            &pair_info.contract_addr,
+            &offer,
+            &ask,
+            offer_reserve,
+            max_impact,
+        )?;
+        bids.push((level, sold));
+        asks.push((level, bought));
+    }
+
+    Ok(PairDepthResponse { bids, asks })
+}
+
+/// Returns the largest amount of `offer_asset_info` which can be swapped to `ask_asset_info` in
+/// `pair` with a price impact not exceeding `max_impact` together with the amount it returns.
+///
+/// * **max_offer** the upper bound of the search.
+fn max_swap_within_impact(
+    querier: QuerierWrapper,
+    pair: &Addr,
+    offer_asset_info: &AssetInfo,
+    ask_asset_info: &AssetInfo,
+    max_offer: Uint128,
+    max_impact: Decimal,
+) -> StdResult<(Uint128, Uint128)> {
+    let (mut low, mut high) = (Uint128::zero(), max_offer);
+    let mut low_return = Uint128::zero();
+    for _ in 0..DEPTH_SEARCH_ITERATIONS {
+        if low >= high {
+            break;
+        }
+        // Rounding up makes every iteration either raise the lower or lower the upper bound
+        let mid = high - (high - low) / Uint128::new(2);
+        let simulation: SimulationResponse = querier.query_wasm_smart(
+            pair,
+            &PairQueryMsg::Simulation {
+                offer_asset: offer_asset_info.with_balance(mid),
+                ask_asset_info: Some(ask_asset_info.clone()),
+            },
+        )?;
+        if spread_ratio(&simulation) <= max_impact {
+            low = mid;
+            low_return = simulation.return_amount;
+        } else {
+            high = mid - Uint128::one();
+        }
+    }
+
+    Ok((low, low_return))
+}
+
+/// Estimates the gas of one or multiple swap operations using a [`GasEstimateResponse`] object.
+/// Every hop uses the gas benchmark set in the factory for the pair type or [`DEFAULT_GAS_PER_SWAP`].
+/// The benchmarks are calibrated to overestimate the actual gas.
+///
+/// * **operations** is a vector that contains objects of type [`SwapOperation`].
+///
+/// * **offer_asset** asset being swapped. CW20 offers add the gas of the token transfer.
+fn estimate_gas(
+    deps: Deps,
+    operations: Vec<SwapOperation>,
+    offer_asset: Asset,
+) -> Result<GasEstimateResponse, ContractError> {
+    assert_operations(deps, &operations)?;
+    if let Some(SwapOperation::AstroSwap {
+        offer_asset_info, ..
+    }) = operations.first()
+    {
+        if !offer_asset_info.equal(&offer_asset.info) {
+            return Err(ContractError::OfferAssetMismatch(
+                offer_asset.info.to_string(),
+            ));
+        }
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let mut gas_per_hop = Vec::with_capacity(operations.len());
+    for operation in operations {
+        let (offer_asset_info, ask_asset_info) = match operation {
+            SwapOperation::AstroSwap {
+                offer_asset_info,
+                ask_asset_info,
+            } => (offer_asset_info, ask_asset_info),
+            SwapOperation::NativeSwap { .. } => {
+                return Err(ContractError::NativeSwapNotSupported {})
+            }
+        };
+
+        let pair_info = query_pair_info(
+            &deps.querier,
+            &config.astroport_factory,
+            &[offer_asset_info, ask_asset_info],
+        )?;
+        let gas_benchmark: Option<u64> = deps.querier.query_wasm_smart(
+            &config.astroport_factory,
+            &FactoryQueryMsg::GasBenchmark {
+                pair_type: pair_info.pair_type,
+            },
+        )?;
+        gas_per_hop.push(gas_benchmark.unwrap_or(DEFAULT_GAS_PER_SWAP));
+    }
+
+    let offer_overhead = if offer_asset.is_native_token() {
+        0
+    } else {
+        CW20_OFFER_GAS
+    };
+    let estimated_gas = gas_per_hop.iter().sum::<u64>() + BASE_GAS_OVERHEAD + offer_overhead;
+    let estimated_fee_native = Decimal::from_ratio(estimated_gas, 1u8)
+        .checked_mul(config.gas_price)?
+        .to_uint_ceil();
+
+    Ok(GasEstimateResponse {
+        estimated_gas,
+        estimated_fee_native,
+        gas_per_hop,
+    })
+}
+
+/// Simulates one or multiple swap operations and estimates the range of the end result using a
+/// [`ChainedSimulationResponse`] object. The bounds apply the simulated spread of every hop
+/// plus or minus the standard deviation of the past spreads in the pair. Pairs without a swap
+/// history don't widen the bounds.
+///
+/// * **offer_asset** asset being swapped.
+///
+/// * **operations** is a vector that contains objects of type [`SwapOperation`].
+///
+/// * **confidence** the number of standard deviations applied at every hop. Defaults to 1.
+fn simulate_chained_swap_with_slippage(
+    deps: Deps,
+    offer_asset: Asset,
+    operations: Vec<SwapOperation>,
+    confidence: Option<Decimal>,
+) -> Result<ChainedSimulationResponse, ContractError> {
+    assert_operations(deps, &operations)?;
+    if let Some(SwapOperation::AstroSwap {
+        offer_asset_info, ..
+    }) = operations.first()
+    {
+        if !offer_asset_info.equal(&offer_asset.info) {
+            return Err(ContractError::OfferAssetMismatch(
+                offer_asset.info.to_string(),
+            ));
+        }
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let confidence = confidence.unwrap_or(Decimal::one());
+    let mut expected_output = offer_asset.amount;
+    let mut lower_bound = offer_asset.amount;
+    let mut upper_bound = offer_asset.amount;
+    let mut per_hop_spreads = Vec::with_capacity(operations.len());
+
+    for operation in operations {
+        let (offer_asset_info, ask_asset_info) = match operation {
+            SwapOperation::AstroSwap {
+                offer_asset_info,
+                ask_asset_info,
+            } => (offer_asset_info, ask_asset_info),
+            SwapOperation::NativeSwap { .. } => {
+                return Err(ContractError::NativeSwapNotSupported {})
+            }
+        };
+
+        let pair_info = query_pair_info(
+            &deps.querier,
+            &config.astroport_factory,
+            &[offer_asset_info.clone(), ask_asset_info.clone()],
+        )?;
+        let res: SimulationResponse = deps.querier.query_wasm_smart(
+            &pair_info.contract_addr,
+            &PairQueryMsg::Simulation {
+                offer_asset: Asset {
+                    info: offer_asset_info,
+                    amount: expected_output,
+                },
+                ask_asset_info: Some(ask_asset_info),
+            },
+        )?;
+
+        let spread = spread_ratio(&res);
+        let deviation = spread_std_dev(
+            &SLIPPAGE_HISTORY
+                .may_load(deps.storage, &pair_info.contract_addr)?
+                .unwrap_or_default(),
+        )
+        .checked_mul(confidence)?;
+
+        // The bounds are scaled by the hop rate and the spread they are computed with
+        let kept = Decimal::one() - spread;
+        if kept.is_zero() || expected_output.is_zero() {
+            lower_bound = Uint128::zero();
+            upper_bound = res.return_amount;
+        } else {
+            let lower_kept = Decimal::one() - (spread + deviation).min(Decimal::one());
+            let upper_kept = Decimal::one() - spread.saturating_sub(deviation);
+            lower_bound = res
+                .return_amount
+                .multiply_ratio(lower_bound, expected_output)
+                .multiply_ratio(lower_kept.atomics(), kept.atomics());
+            upper_bound = res
+                .return_amount
+                .multiply_ratio(upper_bound, expected_output)
+                .multiply_ratio(upper_kept.atomics(), kept.atomics());
+        }
+
+        expected_output = res.return_amount;
+        per_hop_spreads.push(spread);
+    }
+
+    Ok(ChainedSimulationResponse {
+        expected_output,
+        lower_bound,
+        upper_bound,
+        per_hop_spreads,
     })
 }
 
+/// Returns the population standard deviation of the spreads. Less than two spreads have no
+/// deviation.
+fn spread_std_dev(spreads: &[Decimal]) -> Decimal {
+    if spreads.len() < 2 {
+        return Decimal::zero();
+    }
+
+    let count = Uint128::from(spreads.len() as u128);
+    let mean = spreads
+        .iter()
+        .fold(Decimal::zero(), |sum, spread| sum + spread)
+        / count;
+    let variance = spreads
+        .iter()
+        .map(|spread| {
+            let diff = spread.abs_diff(mean);
+            diff * diff
+        })
+        .fold(Decimal::zero(), |sum, sq_diff| sum + sq_diff)
+        / count;
+
+    variance.sqrt()
+}
+
 /// Validates swap operations.
 ///
 /// * **operations** is a vector that contains objects of type [`SwapOperation`]. These are all the swap operations we check.
-fn assert_operations(api: &dyn Api, operations: &[SwapOperation]) -> Result<(), ContractError> {
+pub(crate) fn assert_operations(
+    deps: Deps,
+    operations: &[SwapOperation],
+) -> Result<(), ContractError> {
     let operations_len = operations.len();
     if operations_len == 0 {
         return Err(ContractError::MustProvideOperations {});
@@ -361,8 +1667,8 @@ fn assert_operations(api: &dyn Api, operations: &[SwapOperation]) -> Result<(),
             }
         };
 
-        offer_asset.check(api)?;
-        ask_asset.check(api)?;
+        offer_asset.check(deps.api)?;
+        ask_asset.check(deps.api)?;
 
         if offer_asset.equal(&ask_asset) {
             return Err(ContractError::DoublingAssetsPath {
@@ -372,7 +1678,9 @@ fn assert_operations(api: &dyn Api, operations: &[SwapOperation]) -> Result<(),
         }
 
         if let Some(prev_ask_asset) = prev_ask_asset {
-            if prev_ask_asset != offer_asset {
+            if prev_ask_asset != offer_asset
+                && query_wrapper(deps, &prev_ask_asset, &offer_asset)?.is_none()
+            {
                 return Err(ContractError::InvalidPathOperations {
                     prev_ask_asset: prev_ask_asset.to_string(),
                     next_offer_asset: offer_asset.to_string(),
@@ -396,13 +1704,13 @@ mod testing {
         use cosmwasm_std::testing::mock_dependencies;
         let deps = mock_dependencies();
         // Empty error
-        assert_eq!(true, assert_operations(deps.as_ref().api, &[]).is_err());
+        assert_eq!(true, assert_operations(deps.as_ref(), &[]).is_err());
 
         // uluna output
         assert_eq!(
             true,
             assert_operations(
-                deps.as_ref().api,
+                deps.as_ref(),
                 &vec![
                     SwapOperation::AstroSwap {
                         offer_asset_info: AssetInfo::NativeToken {
@@ -429,7 +1737,7 @@ mod testing {
         assert_eq!(
             true,
             assert_operations(
-                deps.as_ref().api,
+                deps.as_ref(),
                 &vec![
                     SwapOperation::AstroSwap {
                         offer_asset_info: AssetInfo::NativeToken {
@@ -464,7 +1772,7 @@ mod testing {
         assert_eq!(
             true,
             assert_operations(
-                deps.as_ref().api,
+                deps.as_ref(),
                 &vec![
                     SwapOperation::AstroSwap {
                         offer_asset_info: AssetInfo::NativeToken {
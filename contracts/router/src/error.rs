@@ -1,3 +1,4 @@
+use astroport::asset::ZeroAmountError;
 use cosmwasm_std::{OverflowError, StdError, Uint128};
 use thiserror::Error;
 
@@ -38,9 +39,93 @@ pub enum ContractError {
     #[error("The swap operation limit was exceeded!")]
     SwapLimitExceeded {},
 
+    #[error(
+        "Swapping {amount} is prevented as it is less than the minimum offer amount {minimum}"
+    )]
+    DustSwapPrevented { amount: Uint128, minimum: Uint128 },
+
     #[error("Native swap operations are not supported!")]
     NativeSwapNotSupported {},
 
+    #[error("No wrapper is registered to convert {offer_asset} to {ask_asset}")]
+    WrapperNotFound {
+        offer_asset: String,
+        ask_asset: String,
+    },
+
+    #[error("Wrapper {wrapper} doesn't wrap {denom}")]
+    InvalidWrapper { wrapper: String, denom: String },
+
+    #[error("Swap operations are meant for chain {provided}, not {expected}")]
+    WrongChainId { expected: String, provided: String },
+
+    #[error("Contract {0} is not allowed to receive swap callbacks")]
+    CallbackContractNotAllowed(String),
+
     #[error("Contract can't be migrated!")]
     MigrationError {},
+
+    #[error(
+        "Streaming swap must be split into at least one chunk and each chunk must be non-zero"
+    )]
+    InvalidStreamingSwapChunks {},
+
+    #[error("The offer asset must match the offer asset of the first swap operation")]
+    InvalidStreamingSwapOfferAsset {},
+
+    #[error("CW20 tokens must be sent using the CreateStreamingSwap CW20 hook")]
+    Cw20StreamingSwapMustUseHook {},
+
+    #[error("The next chunk of the streaming swap can be executed at {0}")]
+    StreamingSwapChunkNotReady(u64),
+
+    #[error("Offer asset {0} doesn't match the offer asset of the first swap operation")]
+    OfferAssetMismatch(String),
+
+    #[error("Recipient shares must be positive and sum to 1")]
+    RecipientSharesInvalid {},
+
+    #[error("Must provide between 1 and {0} paths")]
+    InvalidPathsNumber(usize),
+
+    #[error("Every path must have a split and the splits must sum to 1")]
+    InvalidSplits {},
+
+    #[error("All the paths must end with the same ask asset")]
+    PathsAskAssetMismatch {},
+
+    #[error("Offer amount must be positive")]
+    ZeroOfferAmount {},
+
+    #[error("Swap operation {hop_index} failed: {reason}")]
+    HopFailed { hop_index: u32, reason: String },
+
+    #[error("Volume discount tiers must have increasing volumes and discounts up to 10000 bps")]
+    InvalidVolumeDiscountTiers {},
+
+    #[error(
+        "Must provide between 1 and {0} depth levels, each strictly between 0 and 100 percent"
+    )]
+    InvalidDepthLevels(usize),
+
+    #[error("Pair {0} is blacklisted")]
+    PairBlacklisted(String),
+
+    #[error("Must provide between 1 and {0} conversions")]
+    InvalidConversionsNumber(usize),
+
+    #[error("Asset {0} can't be swapped by one conversion while another conversion offers or receives it")]
+    ConversionAssetConflict(String),
+
+    #[error("All the conversions must end with the same ask asset to check the minimum total receive amount")]
+    ConversionsAskAssetMismatch {},
+
+    #[error("{0} was sent but no conversion offers it")]
+    UnexpectedFunds(String),
+}
+
+impl From<ZeroAmountError> for ContractError {
+    fn from(_: ZeroAmountError) -> Self {
+        ContractError::ZeroOfferAmount {}
+    }
 }
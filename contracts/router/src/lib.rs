@@ -3,7 +3,9 @@ pub mod state;
 
 pub mod error;
 
+mod multi_swap;
 mod operations;
+mod streaming;
 
 #[cfg(test)]
 mod testing;
@@ -0,0 +1,321 @@
+use cosmwasm_std::{
+    attr, wasm_execute, DepsMut, Env, MessageInfo, Response, StdError, Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+
+use astroport::asset::{addr_opt_validate, AssetInfo, AssetInfoExt};
+use astroport::router::{ConversionSpec, ExecuteMsg, SwapOperation, MAX_MULTI_ASSET_CONVERSIONS};
+
+use crate::contract::assert_operations;
+use crate::error::ContractError;
+use crate::state::{MultiAssetSwap, MULTI_ASSET_SWAP, PRE_RECEIVE_BALANCE};
+
+/// Swaps several offer assets through their own swap operations and sends the ask assets to
+/// `to`. The conversions are executed one after another and every conversion swaps the whole
+/// router balance of its offer asset, so an asset offered or received by one conversion can't be
+/// swapped by another one.
+///
+/// * **conversions** offer assets along with their swap operations and minimum amounts to receive.
+///
+/// * **min_total_receive** minimum amount to receive from all the conversions. All the conversions
+/// must end with the same ask asset if it is set.
+///
+/// * **to** recipient of the ask assets. Defaults to the sender.
+pub fn multi_asset_swap(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    conversions: Vec<ConversionSpec>,
+    min_total_receive: Option<Uint128>,
+    to: Option<String>,
+) -> Result<Response, ContractError> {
+    if conversions.is_empty() || conversions.len() > MAX_MULTI_ASSET_CONVERSIONS {
+        return Err(ContractError::InvalidConversionsNumber(
+            MAX_MULTI_ASSET_CONVERSIONS,
+        ));
+    }
+
+    let mut ask_asset_infos = Vec::with_capacity(conversions.len());
+    for conversion in &conversions {
+        assert_operations(deps.as_ref(), &conversion.operations)?;
+        conversion.offer.info.check(deps.api)?;
+        if conversion.offer.amount.is_zero() {
+            return Err(ContractError::ZeroOfferAmount {});
+        }
+        if let SwapOperation::AstroSwap {
+            offer_asset_info, ..
+        } = &conversion.operations[0]
+        {
+            if !offer_asset_info.equal(&conversion.offer.info) {
+                return Err(ContractError::OfferAssetMismatch(
+                    conversion.offer.info.to_string(),
+                ));
+            }
+        }
+        ask_asset_infos.push(
+            conversion
+                .operations
+                .last()
+                .unwrap()
+                .get_target_asset_info(),
+        );
+    }
+
+    // Assets held by the router while the conversions are executed
+    let held_asset_infos: Vec<&AssetInfo> = conversions
+        .iter()
+        .map(|conversion| &conversion.offer.info)
+        .chain(&ask_asset_infos)
+        .collect();
+    for (index, conversion) in conversions.iter().enumerate() {
+        let offered_twice = conversions
+            .iter()
+            .skip(index + 1)
+            .any(|other| other.offer.info.equal(&conversion.offer.info));
+        if offered_twice
+            || ask_asset_infos
+                .iter()
+                .any(|ask_asset_info| ask_asset_info.equal(&conversion.offer.info))
+        {
+            return Err(ContractError::ConversionAssetConflict(
+                conversion.offer.info.to_string(),
+            ));
+        }
+
+        if let Some(asset_info) = intermediate_asset_infos(&conversion.operations)
+            .find(|asset_info| held_asset_infos.iter().any(|held| held.equal(asset_info)))
+        {
+            return Err(ContractError::ConversionAssetConflict(
+                asset_info.to_string(),
+            ));
+        }
+    }
+
+    if min_total_receive.is_some()
+        && ask_asset_infos
+            .iter()
+            .any(|ask_asset_info| !ask_asset_info.equal(&ask_asset_infos[0]))
+    {
+        return Err(ContractError::ConversionsAskAssetMismatch {});
+    }
+
+    // Coins which no conversion offers would be left in the router
+    if let Some(coin) = info.funds.iter().find(|coin| {
+        !conversions
+            .iter()
+            .any(|conversion| match &conversion.offer.info {
+                AssetInfo::NativeToken { denom } => *denom == coin.denom,
+                AssetInfo::Token { .. } => false,
+            })
+    }) {
+        return Err(ContractError::UnexpectedFunds(coin.denom.clone()));
+    }
+
+    let receiver = addr_opt_validate(deps.api, &to)?.unwrap_or_else(|| info.sender.clone());
+    let router = env.contract.address.to_string();
+
+    let mut messages = vec![];
+    for conversion in &conversions {
+        match &conversion.offer.info {
+            AssetInfo::NativeToken { denom } => {
+                let sent = info
+                    .funds
+                    .iter()
+                    .find(|coin| coin.denom == *denom)
+                    .map(|coin| coin.amount)
+                    .unwrap_or_default();
+                if sent != conversion.offer.amount {
+                    return Err(StdError::generic_err(format!(
+                        "Native token balance mismatch between the argument and the transferred {denom}"
+                    ))
+                    .into());
+                }
+            }
+            AssetInfo::Token { contract_addr } => messages.push(wasm_execute(
+                contract_addr,
+                &Cw20ExecuteMsg::TransferFrom {
+                    owner: info.sender.to_string(),
+                    recipient: router.clone(),
+                    amount: conversion.offer.amount,
+                },
+                vec![],
+            )?),
+        }
+    }
+
+    MULTI_ASSET_SWAP.save(
+        deps.storage,
+        &MultiAssetSwap {
+            receiver: receiver.clone(),
+            conversions: ask_asset_infos
+                .into_iter()
+                .zip(conversions.iter().map(|conversion| conversion.min_receive))
+                .collect(),
+            min_total_receive,
+            next_conversion: 0,
+            prev_balance: Uint128::zero(),
+            received: vec![],
+        },
+    )?;
+
+    // Every conversion is preceded by a checkpoint and the last checkpoint sends the ask assets
+    let advance_msg = wasm_execute(&router, &ExecuteMsg::AdvanceMultiAssetSwap {}, vec![])?;
+    for conversion in conversions {
+        messages.push(advance_msg.clone());
+        messages.extend(conversion_messages(&router, conversion.operations)?);
+    }
+    messages.push(advance_msg);
+
+    Ok(Response::new().add_messages(messages).add_attributes([
+        attr("action", "multi_asset_swap"),
+        attr("sender", info.sender),
+        attr("receiver", receiver),
+    ]))
+}
+
+/// Checks the amount received from the previous conversion and records the router balance of
+/// the ask asset of the next one. Once all the conversions are done, the minimum total receive
+/// amount is checked and the received assets are sent to the receiver.
+pub fn advance_multi_asset_swap(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    if env.contract.address != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut multi_swap = MULTI_ASSET_SWAP.load(deps.storage)?;
+    // The first operation of a conversion offers the whole router balance of its offer asset
+    PRE_RECEIVE_BALANCE.remove(deps.storage);
+
+    let mut response = Response::new().add_attribute("action", "advance_multi_asset_swap");
+    if let Some(index) = multi_swap.next_conversion.checked_sub(1) {
+        let (ask_asset_info, min_receive) = &multi_swap.conversions[index as usize];
+        let amount = ask_asset_info
+            .query_pool(&deps.querier, &env.contract.address)?
+            .checked_sub(multi_swap.prev_balance)?;
+        if amount < *min_receive {
+            return Err(ContractError::AssertionMinimumReceive {
+                receive: *min_receive,
+                amount,
+            });
+        }
+
+        match multi_swap
+            .received
+            .iter_mut()
+            .find(|asset| asset.info.equal(ask_asset_info))
+        {
+            Some(asset) => asset.amount = asset.amount.checked_add(amount)?,
+            None => multi_swap
+                .received
+                .push(ask_asset_info.with_balance(amount)),
+        }
+        response = response.add_attributes([
+            attr("conversion", index.to_string()),
+            attr("return_amount", amount),
+        ]);
+    }
+
+    if let Some((ask_asset_info, _)) = multi_swap
+        .conversions
+        .get(multi_swap.next_conversion as usize)
+    {
+        multi_swap.prev_balance =
+            ask_asset_info.query_pool(&deps.querier, &env.contract.address)?;
+        multi_swap.next_conversion += 1;
+        MULTI_ASSET_SWAP.save(deps.storage, &multi_swap)?;
+
+        return Ok(response);
+    }
+
+    MULTI_ASSET_SWAP.remove(deps.storage);
+    if let Some(min_total_receive) = multi_swap.min_total_receive {
+        let total = multi_swap
+            .received
+            .iter()
+            .map(|asset| asset.amount)
+            .sum::<Uint128>();
+        if total < min_total_receive {
+            return Err(ContractError::AssertionMinimumReceive {
+                receive: min_total_receive,
+                amount: total,
+            });
+        }
+    }
+
+    for asset in multi_swap.received {
+        response = response.add_attribute("received", asset.to_string());
+        if !asset.amount.is_zero() {
+            response = response.add_message(asset.into_msg(&multi_swap.receiver)?);
+        }
+    }
+
+    Ok(response)
+}
+
+/// Returns the assets swap operations pass between each other, including the representations
+/// of a coin converted by wrap operations.
+fn intermediate_asset_infos(operations: &[SwapOperation]) -> impl Iterator<Item = AssetInfo> + '_ {
+    let asks = operations[..operations.len() - 1]
+        .iter()
+        .map(SwapOperation::get_target_asset_info);
+    let offers = operations[1..]
+        .iter()
+        .filter_map(|operation| match operation {
+            SwapOperation::AstroSwap {
+                offer_asset_info, ..
+            } => Some(offer_asset_info.clone()),
+            SwapOperation::NativeSwap { .. } => None,
+        });
+
+    asks.chain(offers)
+}
+
+/// Returns the messages executing the swap operations of a conversion. The ask assets stay in
+/// the router.
+fn conversion_messages(
+    router: &str,
+    operations: Vec<SwapOperation>,
+) -> Result<Vec<WasmMsg>, ContractError> {
+    let single = operations.len() == 1;
+
+    let mut messages = vec![];
+    let mut prev_ask_asset_info: Option<AssetInfo> = None;
+    for operation in operations {
+        // Converts the previous ask asset if the operation uses another representation of the coin
+        if let (
+            Some(prev_ask_asset_info),
+            SwapOperation::AstroSwap {
+                offer_asset_info, ..
+            },
+        ) = (&prev_ask_asset_info, &operation)
+        {
+            if !prev_ask_asset_info.equal(offer_asset_info) {
+                messages.push(wasm_execute(
+                    router,
+                    &ExecuteMsg::ExecuteWrapOperation {
+                        offer_asset_info: prev_ask_asset_info.clone(),
+                        ask_asset_info: offer_asset_info.clone(),
+                    },
+                    vec![],
+                )?);
+            }
+        }
+        prev_ask_asset_info = Some(operation.get_target_asset_info());
+
+        messages.push(wasm_execute(
+            router,
+            &ExecuteMsg::ExecuteSwapOperation {
+                operation,
+                to: None,
+                max_spread: None,
+                single,
+            },
+            vec![],
+        )?);
+    }
+
+    Ok(messages)
+}
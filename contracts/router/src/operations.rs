@@ -1,19 +1,26 @@
 use astroport::asset::{Asset, AssetInfo};
-use astroport::pair::ExecuteMsg as PairExecuteMsg;
-use astroport::querier::{query_balance, query_pair_info, query_token_balance};
+use astroport::native_coin_wrapper::{Config as WrapperConfig, QueryMsg as WrapperQueryMsg};
+use astroport::pair::{ExecuteMsg as PairExecuteMsg, QueryMsg as PairQueryMsg, SimulationResponse};
+use astroport::querier::{
+    query_balance, query_pair_blacklisted, query_pair_info, query_token_balance,
+};
 use astroport::router::SwapOperation;
 use cosmwasm_std::{
-    to_json_binary, Coin, CosmosMsg, Decimal, DepsMut, Env, MessageInfo, Response, StdResult,
-    WasmMsg,
+    attr, to_json_binary, Addr, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo,
+    Response, StdResult, Storage, Uint128, WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
 
 use crate::error::ContractError;
-use crate::state::CONFIG;
+use crate::state::{
+    Config, CONFIG, MAX_SLIPPAGE_HISTORY, PRE_RECEIVE_BALANCE, SLIPPAGE_HISTORY, WRAPPER_REGISTRY,
+};
+use crate::streaming::query_escrowed_balance;
 
 /// Execute a swap operation.
 ///
 /// * **operation** to perform (native or Astro swap with offer and ask asset information).
+/// Fails if the factory blacklisted the pair or if the router holds less than the configured dust threshold of the offer asset.
 ///
 /// * **to** address that receives the ask assets.
 ///
@@ -39,31 +46,69 @@ pub fn execute_swap_operation(
             let config = CONFIG.load(deps.storage)?;
             let pair_info = query_pair_info(
                 &deps.querier,
-                config.astroport_factory,
+                &config.astroport_factory,
                 &[offer_asset_info.clone(), ask_asset_info.clone()],
             )?;
+            if query_pair_blacklisted(
+                &deps.querier,
+                &config.astroport_factory,
+                &pair_info.contract_addr,
+            )? {
+                return Err(ContractError::PairBlacklisted(
+                    pair_info.contract_addr.to_string(),
+                ));
+            }
 
-            let amount = match &offer_asset_info {
+            let balance = match &offer_asset_info {
                 AssetInfo::NativeToken { denom } => {
-                    query_balance(&deps.querier, env.contract.address, denom)?
+                    query_balance(&deps.querier, &env.contract.address, denom)?
                 }
                 AssetInfo::Token { contract_addr } => {
-                    query_token_balance(&deps.querier, contract_addr, env.contract.address)?
+                    query_token_balance(&deps.querier, contract_addr, &env.contract.address)?
                 }
             };
+            let amount = received_amount(deps.storage, &config, &offer_asset_info, balance)?;
+            if config.check_received_amount {
+                if to.is_none() {
+                    // The next operation offers what this swap sends to the router
+                    let ask_balance =
+                        ask_asset_info.query_pool(&deps.querier, &env.contract.address)?;
+                    PRE_RECEIVE_BALANCE.save(deps.storage, &ask_balance)?;
+                } else {
+                    PRE_RECEIVE_BALANCE.remove(deps.storage);
+                }
+            }
             let offer_asset = Asset {
                 info: offer_asset_info,
                 amount,
             };
 
-            asset_into_swap_msg(
+            // Dust is rejected before the pair is queried. The whole route is aborted, so the
+            // output of the previous operations isn't left in the router
+            let swap_msg = asset_into_swap_msg(
                 pair_info.contract_addr.to_string(),
-                offer_asset,
-                ask_asset_info,
+                offer_asset.clone(),
+                config.dust_threshold,
+                ask_asset_info.clone(),
                 max_spread,
                 to,
                 single,
-            )?
+            )?;
+
+            let simulation: SimulationResponse = deps.querier.query_wasm_smart(
+                &pair_info.contract_addr,
+                &PairQueryMsg::Simulation {
+                    offer_asset,
+                    ask_asset_info: Some(ask_asset_info),
+                },
+            )?;
+            record_spread(
+                deps.storage,
+                &pair_info.contract_addr,
+                spread_ratio(&simulation),
+            )?;
+
+            swap_msg
         }
         SwapOperation::NativeSwap { .. } => return Err(ContractError::NativeSwapNotSupported {}),
     };
@@ -71,12 +116,134 @@ pub fn execute_swap_operation(
     Ok(Response::new().add_message(message))
 }
 
+/// Returns the amount of the offer asset an operation swaps given the router `balance` of it.
+/// Assets escrowed by streaming swaps are never swapped. If [`Config::check_received_amount`] is set
+/// and the router recorded its balance before the previous operation, only the balance increase
+/// is swapped, so the amount actually received is used for tokens which charge a fee on transfer.
+fn received_amount(
+    storage: &dyn Storage,
+    config: &Config,
+    offer_asset_info: &AssetInfo,
+    balance: Uint128,
+) -> StdResult<Uint128> {
+    match PRE_RECEIVE_BALANCE.may_load(storage)? {
+        Some(pre_receive_balance) if config.check_received_amount => {
+            Ok(balance.checked_sub(pre_receive_balance)?)
+        }
+        _ => Ok(balance.checked_sub(query_escrowed_balance(storage, offer_asset_info)?)?),
+    }
+}
+
+/// Returns the spread of a swap simulation relative to the ask amount at the pool price.
+pub(crate) fn spread_ratio(simulation: &SimulationResponse) -> Decimal {
+    let pool_price_amount =
+        simulation.return_amount + simulation.spread_amount + simulation.commission_amount;
+    if pool_price_amount.is_zero() {
+        Decimal::zero()
+    } else {
+        Decimal::from_ratio(simulation.spread_amount, pool_price_amount)
+    }
+}
+
+/// Appends the spread of a swap to the pair history. Only the latest [`MAX_SLIPPAGE_HISTORY`]
+/// spreads are kept.
+fn record_spread(storage: &mut dyn Storage, pair: &Addr, spread: Decimal) -> StdResult<()> {
+    let mut history = SLIPPAGE_HISTORY
+        .may_load(storage, pair)?
+        .unwrap_or_default();
+    if history.len() >= MAX_SLIPPAGE_HISTORY {
+        history.drain(..=history.len() - MAX_SLIPPAGE_HISTORY);
+    }
+    history.push(spread);
+
+    SLIPPAGE_HISTORY.save(storage, pair, &history)
+}
+
+/// Wraps or unwraps the whole router balance of the offer asset, except for the assets escrowed
+/// by streaming swaps. If [`Config::check_received_amount`] is set, only the amount received from
+/// the previous swap operation is converted.
+///
+/// * **offer_asset_info** representation of the coin held by the router.
+///
+/// * **ask_asset_info** representation of the coin required by the next swap operation.
+pub fn execute_wrap_operation(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    offer_asset_info: AssetInfo,
+    ask_asset_info: AssetInfo,
+) -> Result<Response, ContractError> {
+    if env.contract.address != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let wrapper = query_wrapper(deps.as_ref(), &offer_asset_info, &ask_asset_info)?.ok_or(
+        ContractError::WrapperNotFound {
+            offer_asset: offer_asset_info.to_string(),
+            ask_asset: ask_asset_info.to_string(),
+        },
+    )?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let balance = offer_asset_info.query_pool(&deps.querier, &env.contract.address)?;
+    let amount = received_amount(deps.storage, &config, &offer_asset_info, balance)?;
+    if config.check_received_amount && PRE_RECEIVE_BALANCE.exists(deps.storage) {
+        // The next operation offers the converted amount
+        let ask_balance = ask_asset_info.query_pool(&deps.querier, &env.contract.address)?;
+        PRE_RECEIVE_BALANCE.save(deps.storage, &ask_balance)?;
+    }
+
+    let message = if offer_asset_info.is_native_token() {
+        offer_asset_info.wrap_msg(amount, wrapper.clone())?
+    } else {
+        offer_asset_info.unwrap_msg(amount, wrapper.clone())?
+    };
+
+    Ok(Response::new().add_message(message).add_attributes([
+        attr("action", "execute_wrap_operation"),
+        attr("offer_asset", offer_asset_info.to_string()),
+        attr("ask_asset", ask_asset_info.to_string()),
+        attr("amount", amount),
+        attr("wrapper", wrapper),
+    ]))
+}
+
+/// Returns the wrapper registered for converting between the two assets if they are the
+/// native and CW20 representations of the same coin.
+pub fn query_wrapper(
+    deps: Deps,
+    offer_asset_info: &AssetInfo,
+    ask_asset_info: &AssetInfo,
+) -> StdResult<Option<Addr>> {
+    let (denom, token) = match (offer_asset_info, ask_asset_info) {
+        (AssetInfo::NativeToken { denom }, AssetInfo::Token { contract_addr })
+        | (AssetInfo::Token { contract_addr }, AssetInfo::NativeToken { denom }) => {
+            (denom, contract_addr)
+        }
+        _ => return Ok(None),
+    };
+
+    if let Some(wrapper) = WRAPPER_REGISTRY.may_load(deps.storage, denom.clone())? {
+        let wrapper_config: WrapperConfig = deps
+            .querier
+            .query_wasm_smart(&wrapper, &WrapperQueryMsg::Config {})?;
+        if wrapper_config.token == token {
+            return Ok(Some(wrapper));
+        }
+    }
+
+    Ok(None)
+}
+
 /// Creates a message of type [`CosmosMsg`] representing a swap operation.
 ///
 /// * **pair_contract** Astroport pair contract for which the swap operation is performed.
 ///
 /// * **offer_asset** asset that is swapped. It also mentions the amount to swap.
 ///
+/// * **min_offer_amount** the minimum amount to swap. Smaller amounts are rejected with
+/// [`ContractError::DustSwapPrevented`]. This is the only place the router dust policy is enforced.
+///
 /// * **ask_asset_info** asset that is swapped to.
 ///
 /// * **max_spread** max spread enforced for the swap.
@@ -87,11 +254,21 @@ pub fn execute_swap_operation(
 pub fn asset_into_swap_msg(
     pair_contract: String,
     offer_asset: Asset,
+    min_offer_amount: Uint128,
     ask_asset_info: AssetInfo,
     max_spread: Option<Decimal>,
     to: Option<String>,
     single: bool,
-) -> StdResult<CosmosMsg> {
+) -> Result<CosmosMsg, ContractError> {
+    // A zero amount is reported as such rather than as dust
+    offer_asset.validate_non_zero()?;
+    if offer_asset.amount < min_offer_amount {
+        return Err(ContractError::DustSwapPrevented {
+            amount: offer_asset.amount,
+            minimum: min_offer_amount,
+        });
+    }
+
     // Disabling spread assertion if this swap is part of a multi hop route
     let belief_price = if single { None } else { Some(Decimal::MAX) };
 
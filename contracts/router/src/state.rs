@@ -1,7 +1,8 @@
-use astroport::asset::AssetInfo;
+use astroport::asset::{Asset, AssetInfo};
+use astroport::router::{StreamingSwap, VolumeDiscountConfig};
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Uint128};
-use cw_storage_plus::Item;
+use cosmwasm_std::{Addr, Binary, Decimal, Uint128};
+use cw_storage_plus::{Item, Map};
 
 /// Stores the contract config at the given key
 pub const CONFIG: Item<Config> = Item::new("config");
@@ -11,14 +12,118 @@ pub const CONFIG: Item<Config> = Item::new("config");
 pub struct Config {
     /// The factory contract address
     pub astroport_factory: Addr,
+    /// Routes with swap operations offering less than this amount are aborted with
+    /// [`crate::error::ContractError::DustSwapPrevented`]. Dust hops are deliberately not
+    /// skipped: a skipped hop would leave the output of the previous hops in the router, where
+    /// the next route would sweep it
+    pub dust_threshold: Uint128,
+    /// The native gas price used to estimate swap fees
+    #[serde(default)]
+    pub gas_price: Decimal,
+    /// Whether swap operations only offer the amount the router received from the previous
+    /// operation instead of its whole balance. Required by fee-on-transfer tokens
+    #[serde(default)]
+    pub check_received_amount: bool,
+    /// The fee discount tiers of the swappers
+    #[serde(default)]
+    pub volume_discount: VolumeDiscountConfig,
+    /// The chain ID swap operations are validated against
+    #[serde(default)]
+    pub chain_id: String,
+    /// The contracts which can receive the callbacks of swaps. Callbacks are disabled if `None`
+    #[serde(default)]
+    pub allowed_callback_contracts: Option<Vec<Addr>>,
 }
 
+/// The dust threshold set at instantiation and on migration from v1.1.1
+pub const DEFAULT_DUST_THRESHOLD: Uint128 = Uint128::new(1000);
+
+/// This structure holds the router parameters before v1.2.0
+#[cw_serde]
+pub struct ConfigV111 {
+    /// The factory contract address
+    pub astroport_factory: Addr,
+}
+
+pub const CONFIG_V111: Item<ConfigV111> = Item::new("config");
+
+/// Stores the router balance of the next operation offer asset before the previous operation
+/// sent it to the router. Only used if [`Config::check_received_amount`] is set
+pub const PRE_RECEIVE_BALANCE: Item<Uint128> = Item::new("pre_receive_balance");
+
 pub const REPLY_DATA: Item<ReplyData> = Item::new("reply_data");
 
+/// Stores the route executed by the router until its last swap operation is done
+pub const PENDING_ROUTE: Item<PendingRoute> = Item::new("pending_route");
+
+/// This structure describes a route of swap operations which is being executed
+#[cw_serde]
+pub struct PendingRoute {
+    /// The address which swaps tokens
+    pub sender: Addr,
+    /// The number of swap operations in the route
+    pub operations_len: u32,
+}
+
 #[cw_serde]
 pub struct ReplyData {
     pub asset_info: AssetInfo,
     pub prev_balance: Uint128,
     pub minimum_receive: Option<Uint128>,
     pub receiver: String,
+    /// The recipients which split the swapped amount received by the router
+    pub recipients: Vec<(Addr, Decimal)>,
+    /// The contract and the message executed with the swapped amount received by the router
+    pub callback: Option<(Addr, Binary)>,
 }
+
+/// Stores the multi-asset swap which is being executed
+pub const MULTI_ASSET_SWAP: Item<MultiAssetSwap> = Item::new("multi_asset_swap");
+
+/// This structure describes a [`astroport::router::ExecuteMsg::MultiAssetSwap`] which is being executed
+#[cw_serde]
+pub struct MultiAssetSwap {
+    /// The address which receives the ask assets
+    pub receiver: Addr,
+    /// The ask asset of every conversion along with its minimum amount to receive
+    pub conversions: Vec<(AssetInfo, Uint128)>,
+    /// The minimum amount to receive from all the conversions
+    pub min_total_receive: Option<Uint128>,
+    /// The index of the conversion which is executed next
+    pub next_conversion: u32,
+    /// The router balance of the ask asset of the current conversion before it started
+    pub prev_balance: Uint128,
+    /// The ask assets received from the finished conversions, combined by asset
+    pub received: Vec<Asset>,
+}
+
+/// Stores streaming swaps which are not finished or cancelled yet. Key: swap id -> streaming swap
+pub const STREAMING_SWAPS: Map<u64, StreamingSwap> = Map::new("streaming_swaps");
+
+/// Stores the identifier which is assigned to the next streaming swap
+pub const NEXT_STREAMING_SWAP_ID: Item<u64> = Item::new("next_streaming_swap_id");
+
+/// Stores the native coin wrappers used to convert between the native and CW20 representations
+/// of a coin. Key: native denom -> wrapper contract
+pub const WRAPPER_REGISTRY: Map<String, Addr> = Map::new("wrapper_registry");
+
+/// Stores the total amount of each asset escrowed by streaming swaps. Key: asset info -> escrowed amount
+pub const ESCROWED_BALANCES: Map<String, Uint128> = Map::new("escrowed_balances");
+
+/// Stores the swap volume of every address per ask asset over the last
+/// [`astroport::router::VOLUME_RETENTION_DAYS`] days. It is the sum of the retained
+/// [`USER_DAILY_VOLUME`] entries. Key: (address, ask asset info) -> volume
+pub const USER_VOLUME_30D: Map<(&Addr, &str), Uint128> = Map::new("user_volume_30d");
+
+/// Ring buffer of the daily swap volumes of every address per ask asset. A day is stored in the
+/// slot `day % VOLUME_RETENTION_DAYS`, which is reused once the day expires.
+/// Key: (address, ask asset info, slot) -> (day since epoch, volume)
+pub const USER_DAILY_VOLUME: Map<(&Addr, &str, u64), (u64, Uint128)> =
+    Map::new("user_daily_volume");
+
+/// The number of the latest swap spreads stored for each pair
+pub const MAX_SLIPPAGE_HISTORY: usize = 30;
+
+/// Stores the spreads of the latest [`MAX_SLIPPAGE_HISTORY`] swaps routed through each pair,
+/// oldest first. Key: pair address -> spreads relative to the amounts at the pool price
+pub const SLIPPAGE_HISTORY: Map<&Addr, Vec<Decimal>> = Map::new("slippage_history");
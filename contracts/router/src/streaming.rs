@@ -0,0 +1,198 @@
+use cosmwasm_std::{attr, Addr, DepsMut, Env, MessageInfo, Response, StdResult, Storage, Uint128};
+
+use astroport::asset::{Asset, AssetInfo};
+use astroport::router::{StreamingSwap, SwapOperation};
+
+use crate::contract::{assert_operations, execute_swap_operations};
+use crate::error::ContractError;
+use crate::state::{ESCROWED_BALANCES, NEXT_STREAMING_SWAP_ID, STREAMING_SWAPS};
+
+/// Creates a streaming swap which swaps `offer_asset` in `chunks` equal parts. The offer asset must
+/// already be held by the router. The first chunk can be executed right away.
+///
+/// * **creator** address that created the streaming swap and receives the swapped assets.
+///
+/// * **offer_asset** asset escrowed for the streaming swap.
+///
+/// * **operations** swap operations performed for each chunk.
+///
+/// * **chunks** number of chunks to split the offer amount into.
+///
+/// * **interval_secs** minimum number of seconds between chunks.
+///
+/// * **min_chunk_receive** minimum amount of tokens to get from each chunk.
+#[allow(clippy::too_many_arguments)]
+pub fn create_streaming_swap(
+    deps: DepsMut,
+    env: Env,
+    creator: Addr,
+    offer_asset: Asset,
+    operations: Vec<SwapOperation>,
+    chunks: u8,
+    interval_secs: u64,
+    min_chunk_receive: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    assert_operations(deps.as_ref(), &operations)?;
+
+    if let SwapOperation::AstroSwap {
+        offer_asset_info, ..
+    } = &operations[0]
+    {
+        if !offer_asset_info.equal(&offer_asset.info) {
+            return Err(ContractError::InvalidStreamingSwapOfferAsset {});
+        }
+    }
+
+    if chunks == 0 || offer_asset.amount < Uint128::from(chunks) {
+        return Err(ContractError::InvalidStreamingSwapChunks {});
+    }
+
+    let swap_id = NEXT_STREAMING_SWAP_ID
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    NEXT_STREAMING_SWAP_ID.save(deps.storage, &(swap_id + 1))?;
+
+    escrow(deps.storage, &offer_asset.info, offer_asset.amount)?;
+
+    let streaming_swap = StreamingSwap {
+        creator,
+        chunk_amount: offer_asset.amount / Uint128::from(chunks),
+        remaining: offer_asset,
+        operations,
+        chunks_left: chunks,
+        interval_secs,
+        next_execution_time: env.block.time.seconds(),
+        min_chunk_receive,
+    };
+    STREAMING_SWAPS.save(deps.storage, swap_id, &streaming_swap)?;
+
+    Ok(Response::new().add_attributes([
+        attr("action", "create_streaming_swap"),
+        attr("swap_id", swap_id.to_string()),
+        attr("creator", &streaming_swap.creator),
+        attr("offer_asset", streaming_swap.remaining.to_string()),
+        attr("chunks", chunks.to_string()),
+    ]))
+}
+
+/// Swaps the next chunk of a streaming swap. The last chunk swaps the whole remaining amount.
+///
+/// * **swap_id** identifier of the streaming swap.
+///
+/// ## Executor
+/// Anyone can execute this once `interval_secs` have passed since the previous chunk.
+pub fn execute_streaming_swap_chunk(
+    deps: DepsMut,
+    env: Env,
+    swap_id: u64,
+) -> Result<Response, ContractError> {
+    let mut streaming_swap = STREAMING_SWAPS.load(deps.storage, swap_id)?;
+
+    let now = env.block.time.seconds();
+    if now < streaming_swap.next_execution_time {
+        return Err(ContractError::StreamingSwapChunkNotReady(
+            streaming_swap.next_execution_time,
+        ));
+    }
+
+    let amount = if streaming_swap.chunks_left == 1 {
+        streaming_swap.remaining.amount
+    } else {
+        streaming_swap.chunk_amount
+    };
+
+    // The released amount becomes available for the first swap operation
+    release(deps.storage, &streaming_swap.remaining.info, amount)?;
+    streaming_swap.remaining.amount = streaming_swap.remaining.amount.checked_sub(amount)?;
+    streaming_swap.chunks_left -= 1;
+    if streaming_swap.chunks_left == 0 {
+        STREAMING_SWAPS.remove(deps.storage, swap_id);
+    } else {
+        streaming_swap.next_execution_time = now + streaming_swap.interval_secs;
+        STREAMING_SWAPS.save(deps.storage, swap_id, &streaming_swap)?;
+    }
+
+    let response = execute_swap_operations(
+        deps,
+        env,
+        streaming_swap.creator,
+        streaming_swap.operations,
+        streaming_swap.min_chunk_receive,
+        None,
+        None,
+        None,
+        vec![],
+        None,
+    )?;
+
+    Ok(response.add_attributes([
+        attr("action", "execute_streaming_swap_chunk"),
+        attr("swap_id", swap_id.to_string()),
+        attr("amount", amount),
+        attr("chunks_left", streaming_swap.chunks_left.to_string()),
+    ]))
+}
+
+/// Cancels a streaming swap and returns the unswapped assets to its creator.
+///
+/// * **swap_id** identifier of the streaming swap.
+///
+/// ## Executor
+/// Only the creator of the streaming swap can execute this.
+pub fn cancel_streaming_swap(
+    deps: DepsMut,
+    info: MessageInfo,
+    swap_id: u64,
+) -> Result<Response, ContractError> {
+    let streaming_swap = STREAMING_SWAPS.load(deps.storage, swap_id)?;
+    if info.sender != streaming_swap.creator {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    STREAMING_SWAPS.remove(deps.storage, swap_id);
+    release(
+        deps.storage,
+        &streaming_swap.remaining.info,
+        streaming_swap.remaining.amount,
+    )?;
+
+    Ok(Response::new()
+        .add_message(
+            streaming_swap
+                .remaining
+                .clone()
+                .into_msg(&streaming_swap.creator)?,
+        )
+        .add_attributes([
+            attr("action", "cancel_streaming_swap"),
+            attr("swap_id", swap_id.to_string()),
+            attr("refund_asset", streaming_swap.remaining.to_string()),
+        ]))
+}
+
+/// Returns the amount of `asset_info` held by the router on behalf of streaming swaps.
+pub fn query_escrowed_balance(storage: &dyn Storage, asset_info: &AssetInfo) -> StdResult<Uint128> {
+    Ok(ESCROWED_BALANCES
+        .may_load(storage, asset_info.to_string())?
+        .unwrap_or_default())
+}
+
+fn escrow(storage: &mut dyn Storage, asset_info: &AssetInfo, amount: Uint128) -> StdResult<()> {
+    let escrowed = query_escrowed_balance(storage, asset_info)?;
+    ESCROWED_BALANCES.save(storage, asset_info.to_string(), &(escrowed + amount))
+}
+
+fn release(
+    storage: &mut dyn Storage,
+    asset_info: &AssetInfo,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let escrowed = query_escrowed_balance(storage, asset_info)?.checked_sub(amount)?;
+    if escrowed.is_zero() {
+        ESCROWED_BALANCES.remove(storage, asset_info.to_string());
+    } else {
+        ESCROWED_BALANCES.save(storage, asset_info.to_string(), &escrowed)?;
+    }
+
+    Ok(())
+}
@@ -1,13 +1,14 @@
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_json, to_json_binary, Addr, Binary, Coin, ContractResult, Empty, OwnedDeps, Querier,
-    QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
+    from_json, to_json_binary, Addr, Binary, Coin, ContractInfoResponse, ContractResult, Empty,
+    OwnedDeps, Querier, QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
 };
 use std::collections::HashMap;
 
 use astroport::asset::{Asset, AssetInfo, PairInfo};
 use astroport::factory::PairType;
+use astroport::native_coin_wrapper::Config as WrapperConfig;
 use astroport::pair::SimulationResponse;
 use cw20::{BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
 
@@ -20,6 +21,12 @@ pub enum QueryMsg {
         offer_asset: Asset,
         ask_asset_info: Option<AssetInfo>,
     },
+    GasBenchmark {
+        pair_type: PairType,
+    },
+    IsPairBlacklisted {
+        pair: String,
+    },
 }
 
 /// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies.
@@ -76,12 +83,14 @@ pub(crate) fn balances_to_map(
 #[derive(Clone, Default)]
 pub struct AstroportFactoryQuerier {
     pairs: HashMap<String, String>,
+    blacklisted_pairs: Vec<String>,
 }
 
 impl AstroportFactoryQuerier {
     pub fn new(pairs: &[(&String, &String)]) -> Self {
         AstroportFactoryQuerier {
             pairs: pairs_to_map(pairs),
+            blacklisted_pairs: vec![],
         }
     }
 }
@@ -123,6 +132,11 @@ impl WasmMockQuerier {
                     || contract_addr.to_string().starts_with("asset")
                 {
                     self.handle_cw20(&contract_addr, &msg)
+                } else if contract_addr.starts_with("wrapper") {
+                    SystemResult::Ok(ContractResult::from(to_json_binary(&WrapperConfig {
+                        denom: "uluna".to_string(),
+                        token: Addr::unchecked("tokenwluna"),
+                    })))
                 } else {
                     self.handle_default(&msg)
                 }
@@ -148,6 +162,9 @@ impl WasmMockQuerier {
                             },
                         ],
                         pair_type: PairType::Xyk {},
+                        creator: None,
+                        creation_height: 0,
+                        creation_time: 0,
                     }))),
                     None => SystemResult::Err(SystemError::InvalidRequest {
                         error: "No pair info exists".to_string(),
@@ -162,6 +179,21 @@ impl WasmMockQuerier {
                     spread_amount: Uint128::zero(),
                 })))
             }
+            QueryMsg::GasBenchmark { pair_type } => {
+                let gas_benchmark = match pair_type {
+                    PairType::Xyk {} => Some(250_000u64),
+                    _ => None,
+                };
+                SystemResult::Ok(ContractResult::from(to_json_binary(&gas_benchmark)))
+            }
+            QueryMsg::IsPairBlacklisted { pair } => {
+                SystemResult::Ok(ContractResult::from(to_json_binary(
+                    &self
+                        .astroport_factory_querier
+                        .blacklisted_pairs
+                        .contains(&pair),
+                )))
+            }
         }
     }
 
@@ -236,4 +268,23 @@ impl WasmMockQuerier {
     pub fn with_astroport_pairs(&mut self, pairs: &[(&String, &String)]) {
         self.astroport_factory_querier = AstroportFactoryQuerier::new(pairs);
     }
+
+    pub fn with_blacklisted_pairs(&mut self, pairs: &[&str]) {
+        self.astroport_factory_querier.blacklisted_pairs =
+            pairs.iter().map(|pair| pair.to_string()).collect();
+    }
+
+    pub fn with_contract_admin(&mut self, admin: &str) {
+        let admin = admin.to_string();
+        self.base.update_wasm(move |request| match request {
+            WasmQuery::ContractInfo { .. } => {
+                let mut contract_info = ContractInfoResponse::new(1, "creator");
+                contract_info.admin = Some(admin.clone());
+                SystemResult::Ok(ContractResult::Ok(to_json_binary(&contract_info).unwrap()))
+            }
+            _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                kind: "wasm".to_string(),
+            }),
+        });
+    }
 }
@@ -1,15 +1,29 @@
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
-use cosmwasm_std::{from_json, to_json_binary, Addr, Coin, ReplyOn, SubMsg, Uint128, WasmMsg};
+use cosmwasm_std::{
+    attr, from_json, to_json_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env,
+    Event, Reply, ReplyOn, Response, SubMsg, SubMsgResponse, SubMsgResult, Timestamp, Uint128,
+    WasmMsg,
+};
+use cw2::{set_contract_version, ContractVersion};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 
-use astroport::asset::{native_asset_info, AssetInfo};
+use astroport::asset::{native_asset_info, token_asset_info, Asset, AssetInfo, AssetInfoExt};
 use astroport::router::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg,
-    SimulateSwapOperationsResponse, SwapOperation, MAX_SWAP_OPERATIONS,
+    ChainedSimulationResponse, ConfigResponse, Cw20HookMsg, ExecuteMsg, GasEstimateResponse,
+    InstantiateMsg, MigrateMsg, MultiPathSimulationResponse, QueryMsg,
+    SimulateSwapOperationsResponse, StreamingSwap, SwapOperation, VolumeDiscountConfig,
+    MAX_MULTI_PATHS, MAX_SWAP_OPERATIONS,
 };
 
-use crate::contract::{execute, instantiate, query, AFTER_SWAP_REPLY_ID};
+use crate::contract::{
+    execute, instantiate, migrate, query, reply, AFTER_SWAP_REPLY_ID, HOP_REPLY_ID_OFFSET,
+};
 use crate::error::ContractError;
+use crate::operations::asset_into_swap_msg;
+use crate::state::{
+    ConfigV111, CONFIG_V111, PRE_RECEIVE_BALANCE, SLIPPAGE_HISTORY, USER_DAILY_VOLUME,
+    USER_VOLUME_30D,
+};
 use crate::testing::mock_querier::mock_dependencies;
 
 #[test]
@@ -18,6 +32,7 @@ fn proper_initialization() {
 
     let msg = InstantiateMsg {
         astroport_factory: String::from("astroportfactory"),
+        chain_id: None,
     };
 
     let env = mock_env();
@@ -30,6 +45,218 @@ fn proper_initialization() {
     let config: ConfigResponse =
         from_json(&query(deps.as_ref(), env, QueryMsg::Config {}).unwrap()).unwrap();
     assert_eq!("astroportfactory", config.astroport_factory.as_str());
+    assert_eq!(config.dust_threshold, Uint128::new(1000));
+}
+
+#[test]
+fn update_config() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_contract_admin("admin");
+
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            astroport_factory: String::from("astroportfactory"),
+            chain_id: None,
+        },
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        astroport_factory: Some(String::from("newfactory")),
+        dust_threshold: Some(Uint128::new(500)),
+        gas_price: None,
+        check_received_amount: None,
+        volume_discount: None,
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+
+    let config: ConfigResponse =
+        from_json(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(config.astroport_factory, "newfactory");
+    assert_eq!(config.dust_threshold, Uint128::new(500));
+}
+
+#[test]
+fn wrap_operations() {
+    let mut deps = mock_dependencies(&[Coin::new(100, "uluna")]);
+    deps.querier.with_contract_admin("admin");
+    deps.querier.with_token_balances(&[(
+        &String::from("asset0002"),
+        &[(&String::from("addr0000"), &Uint128::zero())],
+    )]);
+
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            astroport_factory: String::from("astroportfactory"),
+            chain_id: None,
+        },
+    )
+    .unwrap();
+
+    let operations = vec![
+        SwapOperation::AstroSwap {
+            offer_asset_info: native_asset_info("ukrw".to_string()),
+            ask_asset_info: native_asset_info("uluna".to_string()),
+        },
+        SwapOperation::AstroSwap {
+            offer_asset_info: AssetInfo::Token {
+                contract_addr: Addr::unchecked("tokenwluna"),
+            },
+            ask_asset_info: AssetInfo::Token {
+                contract_addr: Addr::unchecked("asset0002"),
+            },
+        },
+    ];
+    let swap_msg = ExecuteMsg::ExecuteSwapOperations {
+        operations: operations.clone(),
+        minimum_receive: None,
+        to: None,
+        max_spread: None,
+        chain_id: None,
+    };
+
+    // The route is invalid until the wrapper is registered
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        swap_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPathOperations {
+            prev_ask_asset: "uluna".to_string(),
+            next_offer_asset: "tokenwluna".to_string(),
+            next_ask_asset: "asset0002".to_string(),
+        }
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin", &[]),
+        ExecuteMsg::SetWrapper {
+            denom: "ukrw".to_string(),
+            wrapper: Some("wrapper".to_string()),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidWrapper {
+            wrapper: "wrapper".to_string(),
+            denom: "ukrw".to_string(),
+        }
+    );
+
+    let set_wrapper_msg = ExecuteMsg::SetWrapper {
+        denom: "uluna".to_string(),
+        wrapper: Some("wrapper".to_string()),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        set_wrapper_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin", &[]),
+        set_wrapper_msg,
+    )
+    .unwrap();
+
+    let wrapper: Option<Addr> = from_json(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Wrapper {
+                denom: "uluna".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(wrapper, Some(Addr::unchecked("wrapper")));
+
+    // The swapped native coins are wrapped before the next swap operation
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        swap_msg,
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 3);
+    assert_eq!(
+        res.messages[1],
+        SubMsg::reply_on_error(
+            WasmMsg::Execute {
+                contract_addr: String::from(MOCK_CONTRACT_ADDR),
+                funds: vec![],
+                msg: to_json_binary(&ExecuteMsg::ExecuteWrapOperation {
+                    offer_asset_info: native_asset_info("uluna".to_string()),
+                    ask_asset_info: AssetInfo::Token {
+                        contract_addr: Addr::unchecked("tokenwluna"),
+                    },
+                })
+                .unwrap(),
+            },
+            HOP_REPLY_ID_OFFSET + 1
+        )
+    );
+
+    let wrap_msg = ExecuteMsg::ExecuteWrapOperation {
+        offer_asset_info: native_asset_info("uluna".to_string()),
+        ask_asset_info: AssetInfo::Token {
+            contract_addr: Addr::unchecked("tokenwluna"),
+        },
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        wrap_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        wrap_msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(
+            native_asset_info("uluna".to_string())
+                .wrap_msg(Uint128::new(100), Addr::unchecked("wrapper"))
+                .unwrap()
+        )]
+    );
 }
 
 #[test]
@@ -37,6 +264,7 @@ fn execute_swap_operations() {
     let mut deps = mock_dependencies(&[]);
     let msg = InstantiateMsg {
         astroport_factory: String::from("astroportfactory"),
+        chain_id: None,
     };
 
     let env = mock_env();
@@ -50,6 +278,7 @@ fn execute_swap_operations() {
         minimum_receive: None,
         to: None,
         max_spread: None,
+        chain_id: None,
     };
 
     let env = mock_env();
@@ -87,6 +316,7 @@ fn execute_swap_operations() {
         minimum_receive: Some(Uint128::from(1000000u128)),
         to: None,
         max_spread: None,
+        chain_id: None,
     };
 
     let env = mock_env();
@@ -115,9 +345,9 @@ fn execute_swap_operations() {
                     .unwrap(),
                 }
                 .into(),
-                id: 0,
+                id: HOP_REPLY_ID_OFFSET,
                 gas_limit: None,
-                reply_on: ReplyOn::Never,
+                reply_on: ReplyOn::Error,
             },
             SubMsg {
                 msg: WasmMsg::Execute {
@@ -139,9 +369,9 @@ fn execute_swap_operations() {
                     .unwrap(),
                 }
                 .into(),
-                id: 0,
+                id: HOP_REPLY_ID_OFFSET + 1,
                 gas_limit: None,
-                reply_on: ReplyOn::Never,
+                reply_on: ReplyOn::Error,
             },
             SubMsg {
                 msg: WasmMsg::Execute {
@@ -165,7 +395,7 @@ fn execute_swap_operations() {
                 .into(),
                 id: AFTER_SWAP_REPLY_ID,
                 gas_limit: None,
-                reply_on: ReplyOn::Success,
+                reply_on: ReplyOn::Always,
             }
         ]
     );
@@ -203,6 +433,7 @@ fn execute_swap_operations() {
             minimum_receive: None,
             to: Some(String::from("addr0002")),
             max_spread: None,
+            chain_id: None,
         })
         .unwrap(),
     });
@@ -233,9 +464,9 @@ fn execute_swap_operations() {
                     .unwrap(),
                 }
                 .into(),
-                id: 0,
+                id: HOP_REPLY_ID_OFFSET,
                 gas_limit: None,
-                reply_on: ReplyOn::Never,
+                reply_on: ReplyOn::Error,
             },
             SubMsg {
                 msg: WasmMsg::Execute {
@@ -257,9 +488,9 @@ fn execute_swap_operations() {
                     .unwrap(),
                 }
                 .into(),
-                id: 0,
+                id: HOP_REPLY_ID_OFFSET + 1,
                 gas_limit: None,
-                reply_on: ReplyOn::Never,
+                reply_on: ReplyOn::Error,
             },
             SubMsg {
                 msg: WasmMsg::Execute {
@@ -283,10 +514,317 @@ fn execute_swap_operations() {
                 .into(),
                 id: AFTER_SWAP_REPLY_ID,
                 gas_limit: None,
-                reply_on: ReplyOn::Success,
+                reply_on: ReplyOn::Always,
             }
         ]
     );
+
+    // Failed swap operations are reported with their index in the route
+    let err = reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: HOP_REPLY_ID_OFFSET + 1,
+            result: SubMsgResult::Err("Invalid zero amount".to_string()),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::HopFailed {
+            hop_index: 1,
+            reason: "Invalid zero amount".to_string(),
+        }
+    );
+    let err = reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: AFTER_SWAP_REPLY_ID,
+            result: SubMsgResult::Err("Operation exceeds max spread limit".to_string()),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::HopFailed {
+            hop_index: 2,
+            reason: "Operation exceeds max spread limit".to_string(),
+        }
+    );
+}
+
+#[test]
+fn swap_and_split() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            astroport_factory: String::from("astroportfactory"),
+            chain_id: None,
+        },
+    )
+    .unwrap();
+
+    let operations = vec![SwapOperation::AstroSwap {
+        offer_asset_info: native_asset_info("ukrw".to_string()),
+        ask_asset_info: native_asset_info("uluna".to_string()),
+    }];
+    let swap_and_split = |recipients: &[(&str, Decimal)]| ExecuteMsg::SwapAndSplit {
+        operations: operations.clone(),
+        recipients: recipients
+            .iter()
+            .map(|(recipient, share)| (recipient.to_string(), *share))
+            .collect(),
+        min_total_receive: Some(Uint128::new(1000)),
+    };
+
+    for recipients in [
+        vec![],
+        vec![
+            ("addr0001", Decimal::percent(50)),
+            ("addr0002", Decimal::percent(40)),
+        ],
+        vec![("addr0001", Decimal::one()), ("addr0002", Decimal::zero())],
+    ] {
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            swap_and_split(&recipients),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::RecipientSharesInvalid {});
+    }
+
+    // The last swap sends the ask tokens to the router
+    let third = Decimal::from_ratio(1u8, 3u8);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        swap_and_split(&[
+            ("addr0001", third),
+            ("addr0002", third),
+            ("addr0003", third),
+        ]),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_always(
+            WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_json_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: operations[0].clone(),
+                    to: Some(MOCK_CONTRACT_ADDR.to_string()),
+                    max_spread: None,
+                    single: true,
+                })
+                .unwrap(),
+            },
+            AFTER_SWAP_REPLY_ID
+        )]
+    );
+
+    // The dust of the split goes to the first recipient
+    deps.querier.with_balance(&[(
+        &String::from(MOCK_CONTRACT_ADDR),
+        &[Coin::new(1001, "uluna")],
+    )]);
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: AFTER_SWAP_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            }),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        [("addr0001", 335), ("addr0002", 333), ("addr0003", 333)].map(|(recipient, amount)| {
+            SubMsg::new(BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: vec![Coin::new(amount, "uluna")],
+            })
+        })
+    );
+    assert_eq!(
+        res.events,
+        vec![Event::new("route_completed").add_attributes([
+            attr("sender", "addr0000"),
+            attr("hops", "1"),
+            attr("return_amount", "1001"),
+        ])]
+    );
+}
+
+#[test]
+fn volume_discount() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_contract_admin("admin");
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            astroport_factory: String::from("astroportfactory"),
+            chain_id: None,
+        },
+    )
+    .unwrap();
+
+    let update_config = |tiers: Vec<(u128, u16)>| ExecuteMsg::UpdateConfig {
+        astroport_factory: None,
+        dust_threshold: None,
+        gas_price: None,
+        check_received_amount: None,
+        volume_discount: Some(VolumeDiscountConfig {
+            tiers: tiers
+                .into_iter()
+                .map(|(volume, discount_bps)| (Uint128::new(volume), discount_bps))
+                .collect(),
+        }),
+    };
+    for tiers in [vec![(1000, 10), (1000, 20)], vec![(1000, 10001)]] {
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            update_config(tiers),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidVolumeDiscountTiers {});
+    }
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin", &[]),
+        update_config(vec![(1000, 10), (5000, 25)]),
+    )
+    .unwrap();
+
+    // Returns the reported discount and the max spread passed to the swap operation
+    let swap = |deps: DepsMut, env: Env, offer: &str, ask: &str| {
+        let res = execute(
+            deps,
+            env,
+            mock_info("addr0000", &[]),
+            ExecuteMsg::ExecuteSwapOperations {
+                operations: vec![SwapOperation::AstroSwap {
+                    offer_asset_info: native_asset_info(offer.to_string()),
+                    ask_asset_info: native_asset_info(ask.to_string()),
+                }],
+                minimum_receive: None,
+                to: None,
+                max_spread: None,
+                chain_id: None,
+            },
+        )
+        .unwrap();
+        let max_spread = match &res.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => match from_json(msg).unwrap() {
+                ExecuteMsg::ExecuteSwapOperation { max_spread, .. } => max_spread,
+                _ => panic!("unexpected message"),
+            },
+            _ => panic!("unexpected message"),
+        };
+        (res.attributes, max_spread)
+    };
+    let complete_route = |deps: DepsMut, env: Env| {
+        reply(
+            deps,
+            env,
+            Reply {
+                id: AFTER_SWAP_REPLY_ID,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: None,
+                }),
+            },
+        )
+        .unwrap();
+    };
+    let discounted = |fee_discount_bps: u16, max_spread: &str| {
+        (
+            vec![attr("fee_discount_bps", fee_discount_bps.to_string())],
+            Some(max_spread.parse::<Decimal>().unwrap()),
+        )
+    };
+    let user = Addr::unchecked("addr0000");
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(100 * 86400);
+
+    // The max spread is left to the pair without a discount
+    assert_eq!(
+        swap(deps.as_mut(), env.clone(), "ukrw", "uluna"),
+        (vec![attr("fee_discount_bps", "0")], None)
+    );
+    // The route returns 2000 uluna
+    deps.querier
+        .with_balance(&[(&String::from("addr0000"), &[Coin::new(2000, "uluna")])]);
+    complete_route(deps.as_mut(), env.clone());
+    assert_eq!(
+        swap(deps.as_mut(), env.clone(), "ukrw", "uluna"),
+        discounted(10, "0.006")
+    );
+    // The uluna volume doesn't count towards routes into other assets
+    assert_eq!(
+        swap(deps.as_mut(), env.clone(), "uluna", "ukrw"),
+        (vec![attr("fee_discount_bps", "0")], None)
+    );
+
+    // Another 4000 uluna a week later
+    env.block.time = env.block.time.plus_seconds(7 * 86400);
+    swap(deps.as_mut(), env.clone(), "ukrw", "uluna");
+    deps.querier
+        .with_balance(&[(&String::from("addr0000"), &[Coin::new(6000, "uluna")])]);
+    complete_route(deps.as_mut(), env.clone());
+    assert_eq!(
+        swap(deps.as_mut(), env.clone(), "ukrw", "uluna"),
+        discounted(25, "0.0075")
+    );
+
+    // The first day is removed after 30 days
+    env.block.time = Timestamp::from_seconds(129 * 86400);
+    assert_eq!(
+        swap(deps.as_mut(), env.clone(), "ukrw", "uluna"),
+        discounted(25, "0.0075")
+    );
+    env.block.time = Timestamp::from_seconds(130 * 86400);
+    assert_eq!(
+        swap(deps.as_mut(), env.clone(), "ukrw", "uluna"),
+        discounted(10, "0.006")
+    );
+    assert_eq!(
+        USER_VOLUME_30D
+            .load(deps.as_ref().storage, (&user, "uluna"))
+            .unwrap(),
+        Uint128::new(4000)
+    );
+    assert!(!USER_DAILY_VOLUME.has(deps.as_ref().storage, (&user, "uluna", 100 % 30)));
+
+    // The slot of the expired day is reused
+    deps.querier
+        .with_balance(&[(&String::from("addr0000"), &[Coin::new(7000, "uluna")])]);
+    complete_route(deps.as_mut(), env.clone());
+    assert_eq!(
+        USER_DAILY_VOLUME
+            .load(deps.as_ref().storage, (&user, "uluna", 130 % 30))
+            .unwrap(),
+        (130, Uint128::new(1000))
+    );
+    assert_eq!(
+        swap(deps.as_mut(), env, "ukrw", "uluna"),
+        discounted(25, "0.0075")
+    );
 }
 
 #[test]
@@ -294,6 +832,7 @@ fn execute_swap_operation() {
     let mut deps = mock_dependencies(&[]);
     let msg = InstantiateMsg {
         astroport_factory: String::from("astroportfactory"),
+        chain_id: None,
     };
 
     let env = mock_env();
@@ -362,6 +901,497 @@ fn execute_swap_operation() {
             reply_on: ReplyOn::Never,
         }]
     );
+
+    // The spread of the executed swap is recorded
+    assert_eq!(
+        SLIPPAGE_HISTORY
+            .load(deps.as_ref().storage, &Addr::unchecked("pair"))
+            .unwrap(),
+        vec![Decimal::zero()]
+    );
+}
+
+#[test]
+fn execute_swap_operation_rejects_dust() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_contract_admin("admin");
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            astroport_factory: String::from("astroportfactory"),
+            chain_id: None,
+        },
+    )
+    .unwrap();
+
+    deps.querier
+        .with_astroport_pairs(&[(&"assetuusd".to_string(), &String::from("pair"))]);
+    deps.querier.with_token_balances(&[(
+        &String::from("asset"),
+        &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(999u128))],
+    )]);
+    let msg = ExecuteMsg::ExecuteSwapOperation {
+        operation: SwapOperation::AstroSwap {
+            offer_asset_info: AssetInfo::Token {
+                contract_addr: Addr::unchecked("asset"),
+            },
+            ask_asset_info: native_asset_info("uusd".to_string()),
+        },
+        to: None,
+        max_spread: None,
+        single: false,
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::DustSwapPrevented {
+            amount: Uint128::new(999),
+            minimum: Uint128::new(1000),
+        }
+    );
+    assert!(SLIPPAGE_HISTORY
+        .may_load(deps.as_ref().storage, &Addr::unchecked("pair"))
+        .unwrap()
+        .is_none());
+
+    // Lowering the threshold enables the swap
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin", &[]),
+        ExecuteMsg::UpdateConfig {
+            astroport_factory: None,
+            dust_threshold: Some(Uint128::new(999)),
+            gas_price: None,
+            check_received_amount: None,
+            volume_discount: None,
+        },
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+}
+
+#[test]
+fn execute_swap_operation_rejects_blacklisted_pair() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            astroport_factory: String::from("astroportfactory"),
+            chain_id: None,
+        },
+    )
+    .unwrap();
+
+    deps.querier
+        .with_astroport_pairs(&[(&"assetuusd".to_string(), &String::from("pair"))]);
+    deps.querier.with_token_balances(&[(
+        &String::from("asset"),
+        &[(
+            &String::from(MOCK_CONTRACT_ADDR),
+            &Uint128::new(1_000000u128),
+        )],
+    )]);
+    let msg = ExecuteMsg::ExecuteSwapOperation {
+        operation: SwapOperation::AstroSwap {
+            offer_asset_info: AssetInfo::Token {
+                contract_addr: Addr::unchecked("asset"),
+            },
+            ask_asset_info: native_asset_info("uusd".to_string()),
+        },
+        to: None,
+        max_spread: None,
+        single: false,
+    };
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+
+    deps.querier.with_blacklisted_pairs(&["pair"]);
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::PairBlacklisted("pair".to_string()));
+}
+
+#[test]
+fn execute_swap_operation_checks_received_amount() {
+    let mut deps = mock_dependencies(&[Coin::new(1000, "uusd")]);
+    deps.querier.with_contract_admin("admin");
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            astroport_factory: String::from("astroportfactory"),
+            chain_id: None,
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin", &[]),
+        ExecuteMsg::UpdateConfig {
+            astroport_factory: None,
+            dust_threshold: Some(Uint128::zero()),
+            gas_price: None,
+            check_received_amount: Some(true),
+            volume_discount: None,
+        },
+    )
+    .unwrap();
+    let config: ConfigResponse =
+        from_json(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert!(config.check_received_amount);
+
+    deps.querier.with_astroport_pairs(&[
+        (&"uusdasset".to_string(), &String::from("pair0000")),
+        (&"assetuluna".to_string(), &String::from("pair0001")),
+    ]);
+    // The router holds tokens which don't belong to the route
+    deps.querier.with_token_balances(&[(
+        &String::from("asset"),
+        &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(200))],
+    )]);
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::ExecuteSwapOperation {
+            operation: SwapOperation::AstroSwap {
+                offer_asset_info: native_asset_info("uusd".to_string()),
+                ask_asset_info: token_asset_info(Addr::unchecked("asset")),
+            },
+            to: None,
+            max_spread: None,
+            single: false,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        PRE_RECEIVE_BALANCE.load(deps.as_ref().storage).unwrap(),
+        Uint128::new(200)
+    );
+
+    // The token charges a fee on transfer, so the router receives 90 tokens out of 100
+    deps.querier.with_token_balances(&[(
+        &String::from("asset"),
+        &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(290))],
+    )]);
+    let last_hop = ExecuteMsg::ExecuteSwapOperation {
+        operation: SwapOperation::AstroSwap {
+            offer_asset_info: token_asset_info(Addr::unchecked("asset")),
+            ask_asset_info: native_asset_info("uluna".to_string()),
+        },
+        to: Some("addr0000".to_string()),
+        max_spread: None,
+        single: false,
+    };
+    let offered_amount = |res: Response| match &res.messages[0].msg {
+        CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => match from_json(msg).unwrap() {
+            Cw20ExecuteMsg::Send { amount, .. } => amount,
+            _ => panic!("unexpected token message"),
+        },
+        _ => panic!("unexpected message"),
+    };
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        last_hop.clone(),
+    )
+    .unwrap();
+    assert_eq!(offered_amount(res), Uint128::new(90));
+    assert!(!PRE_RECEIVE_BALANCE.exists(deps.as_ref().storage));
+
+    // Without a recorded balance the whole router balance is swapped
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        last_hop,
+    )
+    .unwrap();
+    assert_eq!(offered_amount(res), Uint128::new(290));
+}
+
+#[test]
+fn simulate_chained_swap_with_slippage() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            astroport_factory: String::from("astroportfactory"),
+            chain_id: None,
+        },
+    )
+    .unwrap();
+    deps.querier.with_astroport_pairs(&[
+        (&"ukrwasset0000".to_string(), &String::from("pair0000")),
+        (&"asset0000uluna".to_string(), &String::from("pair0001")),
+    ]);
+
+    let operations = vec![
+        SwapOperation::AstroSwap {
+            offer_asset_info: native_asset_info("ukrw".to_string()),
+            ask_asset_info: AssetInfo::Token {
+                contract_addr: Addr::unchecked("asset0000"),
+            },
+        },
+        SwapOperation::AstroSwap {
+            offer_asset_info: AssetInfo::Token {
+                contract_addr: Addr::unchecked("asset0000"),
+            },
+            ask_asset_info: native_asset_info("uluna".to_string()),
+        },
+    ];
+    let simulate = |offer_asset: Asset, confidence: Option<Decimal>| {
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SimulateChainedSwapWithSlippage {
+                offer_asset,
+                operations: operations.clone(),
+                confidence,
+            },
+        )
+        .map(|res| from_json::<ChainedSimulationResponse>(&res).unwrap())
+    };
+    let offer_asset = native_asset_info("ukrw".to_string()).with_balance(1000000u128);
+
+    // Without a swap history the bounds match the simulation
+    assert_eq!(
+        simulate(offer_asset.clone(), None).unwrap(),
+        ChainedSimulationResponse {
+            expected_output: Uint128::new(1000000),
+            lower_bound: Uint128::new(1000000),
+            upper_bound: Uint128::new(1000000),
+            per_hop_spreads: vec![Decimal::zero(); 2],
+        }
+    );
+
+    let err = simulate(
+        native_asset_info("uluna".to_string()).with_balance(1000000u128),
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::OfferAssetMismatch("uluna".to_string()));
+
+    // The past spreads of the first pair deviate by 1%. A single spread has no deviation
+    SLIPPAGE_HISTORY
+        .save(
+            deps.as_mut().storage,
+            &Addr::unchecked("pair0000"),
+            &vec![Decimal::percent(1), Decimal::percent(3)],
+        )
+        .unwrap();
+    SLIPPAGE_HISTORY
+        .save(
+            deps.as_mut().storage,
+            &Addr::unchecked("pair0001"),
+            &vec![Decimal::percent(5)],
+        )
+        .unwrap();
+
+    let res = simulate(offer_asset.clone(), None).unwrap();
+    assert_eq!(res.expected_output, Uint128::new(1000000));
+    assert_eq!(res.lower_bound, Uint128::new(990000));
+    // The simulated spread can't go below zero
+    assert_eq!(res.upper_bound, Uint128::new(1000000));
+
+    let res = simulate(offer_asset, Some(Decimal::from_ratio(2u8, 1u8))).unwrap();
+    assert_eq!(res.lower_bound, Uint128::new(980000));
+}
+
+#[test]
+fn gas_estimate() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_contract_admin("admin");
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            astroport_factory: String::from("astroportfactory"),
+            chain_id: None,
+        },
+    )
+    .unwrap();
+    deps.querier.with_astroport_pairs(&[
+        (&"ukrwasset0000".to_string(), &String::from("pair0000")),
+        (&"asset0000uluna".to_string(), &String::from("pair0001")),
+    ]);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin", &[]),
+        ExecuteMsg::UpdateConfig {
+            astroport_factory: None,
+            dust_threshold: None,
+            gas_price: Some(Decimal::from_ratio(25u8, 1000u16)),
+            check_received_amount: None,
+            volume_discount: None,
+        },
+    )
+    .unwrap();
+
+    let operations = vec![
+        SwapOperation::AstroSwap {
+            offer_asset_info: native_asset_info("ukrw".to_string()),
+            ask_asset_info: AssetInfo::Token {
+                contract_addr: Addr::unchecked("asset0000"),
+            },
+        },
+        SwapOperation::AstroSwap {
+            offer_asset_info: AssetInfo::Token {
+                contract_addr: Addr::unchecked("asset0000"),
+            },
+            ask_asset_info: native_asset_info("uluna".to_string()),
+        },
+    ];
+    let estimate = |offer_asset: Asset| {
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GasEstimate {
+                operations: operations.clone(),
+                offer_asset,
+            },
+        )
+        .map(|res| from_json::<GasEstimateResponse>(&res).unwrap())
+    };
+
+    // Both XYK hops use the factory benchmark on top of the router overhead
+    assert_eq!(
+        estimate(native_asset_info("ukrw".to_string()).with_balance(1000000u128)).unwrap(),
+        GasEstimateResponse {
+            estimated_gas: 650_000,
+            estimated_fee_native: Uint128::new(16250),
+            gas_per_hop: vec![250_000; 2],
+        }
+    );
+
+    let err =
+        estimate(native_asset_info("uluna".to_string()).with_balance(1000000u128)).unwrap_err();
+    assert_eq!(err, ContractError::OfferAssetMismatch("uluna".to_string()));
+}
+
+#[test]
+fn simulate_multi_path_validation() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            astroport_factory: String::from("astroportfactory"),
+            chain_id: None,
+        },
+    )
+    .unwrap();
+    deps.querier.with_astroport_pairs(&[
+        (&"ukrwuluna".to_string(), &String::from("pair0000")),
+        (&"ukrwasset0000".to_string(), &String::from("pair0001")),
+        (&"asset0000uluna".to_string(), &String::from("pair0002")),
+    ]);
+
+    let swap = |offer: AssetInfo, ask: AssetInfo| SwapOperation::AstroSwap {
+        offer_asset_info: offer,
+        ask_asset_info: ask,
+    };
+    let ukrw = native_asset_info("ukrw".to_string());
+    let uluna = native_asset_info("uluna".to_string());
+    let asset0000 = AssetInfo::Token {
+        contract_addr: Addr::unchecked("asset0000"),
+    };
+    let paths = vec![
+        vec![swap(ukrw.clone(), uluna.clone())],
+        vec![
+            swap(ukrw.clone(), asset0000.clone()),
+            swap(asset0000.clone(), uluna.clone()),
+        ],
+    ];
+    let simulate = |paths: Vec<Vec<SwapOperation>>, splits: Vec<Decimal>| {
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SimulateMultiPath {
+                offer_asset: ukrw.with_balance(1000u16),
+                paths,
+                splits,
+            },
+        )
+        .map(|res| from_json::<MultiPathSimulationResponse>(&res).unwrap())
+    };
+
+    let err = simulate(vec![], vec![]).unwrap_err();
+    assert_eq!(err, ContractError::InvalidPathsNumber(MAX_MULTI_PATHS));
+
+    let err = simulate(paths.clone(), vec![Decimal::percent(50)]).unwrap_err();
+    assert_eq!(err, ContractError::InvalidSplits {});
+
+    let err = simulate(
+        paths.clone(),
+        vec![Decimal::percent(50), Decimal::percent(60)],
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidSplits {});
+
+    let err = simulate(
+        vec![paths[0].clone(), vec![swap(ukrw.clone(), asset0000)]],
+        vec![Decimal::percent(50), Decimal::percent(50)],
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::PathsAskAssetMismatch {});
+
+    let err = simulate(
+        vec![paths[0].clone(), vec![swap(uluna, ukrw.clone())]],
+        vec![Decimal::percent(50), Decimal::percent(50)],
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::OfferAssetMismatch("ukrw".to_string()));
+
+    // The mocked pairs swap 1:1, so every split returns the whole offer amount
+    let res = simulate(paths, vec![Decimal::percent(30), Decimal::percent(70)]).unwrap();
+    assert_eq!(
+        res.outputs_per_path,
+        vec![Uint128::new(300), Uint128::new(700)]
+    );
+    assert_eq!(res.total_output, Uint128::new(1000));
+    assert_eq!(res.optimal_total, Uint128::new(1000));
 }
 
 #[test]
@@ -370,6 +1400,7 @@ fn query_buy_with_routes() {
 
     let msg = InstantiateMsg {
         astroport_factory: String::from("astroportfactory"),
+        chain_id: None,
     };
 
     let env = mock_env();
@@ -436,6 +1467,7 @@ fn assert_maximum_receive_swap_operations() {
     let mut deps = mock_dependencies(&[]);
     let msg = InstantiateMsg {
         astroport_factory: String::from("astroportfactory"),
+        chain_id: None,
     };
 
     let env = mock_env();
@@ -455,6 +1487,7 @@ fn assert_maximum_receive_swap_operations() {
         minimum_receive: None,
         to: None,
         max_spread: None,
+        chain_id: None,
     };
 
     let env = mock_env();
@@ -463,3 +1496,550 @@ fn assert_maximum_receive_swap_operations() {
 
     assert_eq!(res, ContractError::SwapLimitExceeded {});
 }
+
+#[test]
+fn streaming_swap() {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        astroport_factory: String::from("astroportfactory"),
+        chain_id: None,
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+    deps.querier
+        .with_astroport_pairs(&[(&"uusdasset0001".to_string(), &String::from("pair"))]);
+    deps.querier.with_balance(&[(
+        &String::from(MOCK_CONTRACT_ADDR),
+        &[Coin {
+            amount: Uint128::new(300u128),
+            denom: "uusd".to_string(),
+        }],
+    )]);
+    deps.querier.with_token_balances(&[(
+        &String::from("asset0001"),
+        &[(&String::from("addr0000"), &Uint128::zero())],
+    )]);
+
+    let operation = SwapOperation::AstroSwap {
+        offer_asset_info: native_asset_info("uusd".to_string()),
+        ask_asset_info: AssetInfo::Token {
+            contract_addr: Addr::unchecked("asset0001"),
+        },
+    };
+    let msg = |chunks| ExecuteMsg::CreateStreamingSwap {
+        offer_asset: Asset::native("uusd", 300u128),
+        operations: vec![operation.clone()],
+        chunks,
+        interval_secs: 100,
+        min_chunk_receive: None,
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[Coin::new(300, "uusd")]);
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), msg(0)).unwrap_err();
+    assert_eq!(err, ContractError::InvalidStreamingSwapChunks {});
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[Coin::new(200, "uusd")]),
+        msg(3),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::Std(_)));
+
+    execute(deps.as_mut(), env.clone(), info, msg(3)).unwrap();
+
+    // The first chunk can be executed right away
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::ExecuteStreamingSwapChunk { swap_id: 0 },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg {
+            msg: WasmMsg::Execute {
+                contract_addr: String::from(MOCK_CONTRACT_ADDR),
+                funds: vec![],
+                msg: to_json_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: operation.clone(),
+                    to: Some(String::from("addr0000")),
+                    max_spread: None,
+                    single: true,
+                })
+                .unwrap(),
+            }
+            .into(),
+            id: AFTER_SWAP_REPLY_ID,
+            gas_limit: None,
+            reply_on: ReplyOn::Always,
+        }]
+    );
+
+    let streaming_swap: StreamingSwap = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::StreamingSwap { swap_id: 0 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(streaming_swap.remaining, Asset::native("uusd", 200u128));
+    assert_eq!(streaming_swap.chunks_left, 2);
+
+    // Only the released chunk is swapped while the rest stays escrowed
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::ExecuteSwapOperation {
+            operation,
+            to: Some(String::from("addr0000")),
+            max_spread: None,
+            single: true,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        WasmMsg::Execute {
+            contract_addr: String::from("pair"),
+            funds: vec![Coin::new(100, "uusd")],
+            msg: to_json_binary(&astroport::pair::ExecuteMsg::Swap {
+                offer_asset: Asset::native("uusd", 100u128),
+                ask_asset_info: Some(AssetInfo::Token {
+                    contract_addr: Addr::unchecked("asset0001"),
+                }),
+                belief_price: None,
+                max_spread: None,
+                to: Some(String::from("addr0000")),
+            })
+            .unwrap(),
+        }
+        .into()
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::ExecuteStreamingSwapChunk { swap_id: 0 },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::StreamingSwapChunkNotReady(env.block.time.seconds() + 100)
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::CancelStreamingSwap { swap_id: 0 },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CancelStreamingSwap { swap_id: 0 },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        BankMsg::Send {
+            to_address: String::from("addr0000"),
+            amount: vec![Coin::new(200, "uusd")],
+        }
+        .into()
+    );
+
+    query(deps.as_ref(), env, QueryMsg::StreamingSwap { swap_id: 0 }).unwrap_err();
+}
+
+#[test]
+fn batch_balances() {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        astroport_factory: String::from("astroportfactory"),
+        chain_id: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    deps.querier.with_balance(&[(
+        &String::from("addr0000"),
+        &[Coin::new(100, "uusd"), Coin::new(200, "uluna")],
+    )]);
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("asset0000"),
+            &[(&String::from("addr0000"), &Uint128::new(300))],
+        ),
+        (
+            &String::from("asset0001"),
+            &[
+                (&String::from("addr0000"), &Uint128::zero()),
+                (&String::from("addr0001"), &Uint128::new(400)),
+            ],
+        ),
+    ]);
+
+    let assets = vec![
+        token_asset_info(Addr::unchecked("asset0000")),
+        native_asset_info("uluna".to_string()),
+        token_asset_info(Addr::unchecked("asset0001")),
+        native_asset_info("uatom".to_string()),
+        native_asset_info("uusd".to_string()),
+    ];
+    let balances: Vec<Asset> = from_json(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::BatchBalances {
+                address: "addr0000".to_string(),
+                assets: assets.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        balances,
+        vec![
+            assets[0].with_balance(300u16),
+            assets[1].with_balance(200u8),
+            assets[2].with_balance(0u8),
+            assets[3].with_balance(0u8),
+            assets[4].with_balance(100u8),
+        ]
+    );
+}
+
+#[test]
+fn chain_id_validation() {
+    let mut deps = mock_dependencies(&[]);
+    let env = mock_env();
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            astroport_factory: String::from("astroportfactory"),
+            chain_id: Some("phoenix-1".to_string()),
+        },
+    )
+    .unwrap();
+
+    // The chain ID of the block info takes precedence
+    let config: ConfigResponse =
+        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(config.chain_id, env.block.chain_id);
+
+    let swap = |chain_id: Option<&str>| ExecuteMsg::ExecuteSwapOperations {
+        operations: vec![],
+        minimum_receive: None,
+        to: None,
+        max_spread: None,
+        chain_id: chain_id.map(|chain_id| chain_id.to_string()),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        swap(Some("phoenix-1")),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::WrongChainId {
+            expected: env.block.chain_id.clone(),
+            provided: "phoenix-1".to_string(),
+        }
+    );
+
+    // A matching or missing chain ID passes the check
+    for chain_id in [Some(env.block.chain_id.as_str()), None] {
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("addr0000", &[]),
+            swap(chain_id),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::MustProvideOperations {});
+    }
+
+    // The chain ID of the instantiate message is used if the block info has none
+    let mut deps = mock_dependencies(&[]);
+    let mut env = mock_env();
+    env.block.chain_id = String::new();
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            astroport_factory: String::from("astroportfactory"),
+            chain_id: Some("phoenix-1".to_string()),
+        },
+    )
+    .unwrap();
+    let config: ConfigResponse =
+        from_json(&query(deps.as_ref(), env, QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(config.chain_id, "phoenix-1");
+}
+
+#[test]
+fn contract_info() {
+    let mut deps = mock_dependencies(&[]);
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            astroport_factory: String::from("astroportfactory"),
+            chain_id: None,
+        },
+    )
+    .unwrap();
+
+    let contract_info = |deps: Deps| -> ContractVersion {
+        from_json(&query(deps, mock_env(), QueryMsg::ContractInfo {}).unwrap()).unwrap()
+    };
+    let current_version = ContractVersion {
+        contract: "astroport-router".to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    };
+    assert_eq!(contract_info(deps.as_ref()), current_version);
+
+    // A v1.1.1 router reports the new version once it is migrated
+    set_contract_version(deps.as_mut().storage, "astroport-router", "1.1.1").unwrap();
+    CONFIG_V111
+        .save(
+            deps.as_mut().storage,
+            &ConfigV111 {
+                astroport_factory: Addr::unchecked("astroportfactory"),
+            },
+        )
+        .unwrap();
+    assert_eq!(contract_info(deps.as_ref()).version, "1.1.1");
+
+    migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    assert_eq!(contract_info(deps.as_ref()), current_version);
+    let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+    assert_eq!(err, ContractError::MigrationError {});
+}
+
+#[test]
+fn swap_operations_with_callback() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_contract_admin("admin");
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        InstantiateMsg {
+            astroport_factory: String::from("astroportfactory"),
+            chain_id: None,
+        },
+    )
+    .unwrap();
+
+    let operations = vec![SwapOperation::AstroSwap {
+        offer_asset_info: native_asset_info("ukrw".to_string()),
+        ask_asset_info: native_asset_info("uluna".to_string()),
+    }];
+    let callback = to_json_binary(&"deposit").unwrap();
+    let swap_with_callback = ExecuteMsg::ExecuteSwapOperationsWithCallback {
+        operations: operations.clone(),
+        min_receive: Some(Uint128::new(1000)),
+        callback: callback.clone(),
+        callback_contract: "vault".to_string(),
+    };
+    let update_allowed =
+        |contracts: Option<Vec<&str>>| ExecuteMsg::UpdateAllowedCallbackContracts {
+            contracts: contracts.map(|contracts| {
+                contracts
+                    .iter()
+                    .map(|contract| contract.to_string())
+                    .collect()
+            }),
+        };
+
+    // Callbacks are disabled until the admin allows the callback contracts
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        swap_with_callback.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::CallbackContractNotAllowed("vault".to_string())
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        update_allowed(Some(vec!["vault"])),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin", &[]),
+        update_allowed(Some(vec!["vault"])),
+    )
+    .unwrap();
+    let config: ConfigResponse =
+        from_json(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(
+        config.allowed_callback_contracts,
+        Some(vec![Addr::unchecked("vault")])
+    );
+
+    // The last swap sends the ask tokens to the router
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        swap_with_callback.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_always(
+            WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_json_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation: operations[0].clone(),
+                    to: Some(MOCK_CONTRACT_ADDR.to_string()),
+                    max_spread: None,
+                    single: true,
+                })
+                .unwrap(),
+            },
+            AFTER_SWAP_REPLY_ID
+        )]
+    );
+
+    // The callback is executed with the ask tokens
+    deps.querier.with_balance(&[(
+        &String::from(MOCK_CONTRACT_ADDR),
+        &[Coin::new(1200, "uluna")],
+    )]);
+    let swap_reply = Reply {
+        id: AFTER_SWAP_REPLY_ID,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: None,
+        }),
+    };
+    let res = reply(deps.as_mut(), mock_env(), swap_reply.clone()).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "vault".to_string(),
+            msg: callback.clone(),
+            funds: vec![Coin::new(1200, "uluna")],
+        })]
+    );
+    assert_eq!(res.attributes, vec![attr("callback_contract", "vault")]);
+
+    // A failed minimum receive check reverts the callback
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        swap_with_callback,
+    )
+    .unwrap();
+    deps.querier.with_balance(&[(
+        &String::from(MOCK_CONTRACT_ADDR),
+        &[Coin::new(2100, "uluna")],
+    )]);
+    let err = reply(deps.as_mut(), mock_env(), swap_reply).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::AssertionMinimumReceive {
+            receive: Uint128::new(1000),
+            amount: Uint128::new(900),
+        }
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin", &[]),
+        update_allowed(None),
+    )
+    .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::ExecuteSwapOperationsWithCallback {
+            operations,
+            min_receive: None,
+            callback,
+            callback_contract: "vault".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::CallbackContractNotAllowed("vault".to_string())
+    );
+}
+
+#[test]
+fn asset_into_swap_msg_prevents_dust() {
+    let swap_msg = |amount: u128| {
+        asset_into_swap_msg(
+            "pair".to_string(),
+            native_asset_info("uusd".to_string()).with_balance(amount),
+            Uint128::new(1000),
+            native_asset_info("uluna".to_string()),
+            None,
+            None,
+            false,
+        )
+    };
+
+    assert_eq!(
+        swap_msg(999).unwrap_err(),
+        ContractError::DustSwapPrevented {
+            amount: Uint128::new(999),
+            minimum: Uint128::new(1000),
+        }
+    );
+    // Zero amounts fail the zero amount check before the dust check
+    assert_eq!(swap_msg(0).unwrap_err(), ContractError::ZeroOfferAmount {});
+    assert_eq!(
+        swap_msg(1000).unwrap(),
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "pair".to_string(),
+            funds: vec![Coin::new(1000, "uusd")],
+            msg: to_json_binary(&astroport::pair::ExecuteMsg::Swap {
+                offer_asset: native_asset_info("uusd".to_string()).with_balance(1000u128),
+                ask_asset_info: Some(native_asset_info("uluna".to_string())),
+                belief_price: Some(Decimal::MAX),
+                max_spread: None,
+                to: None,
+            })
+            .unwrap(),
+        })
+    );
+}
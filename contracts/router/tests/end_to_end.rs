@@ -0,0 +1,605 @@
+#![cfg(not(tarpaulin_include))]
+
+use cosmwasm_std::{coin, to_json_binary, Addr, Decimal, Empty, Uint128};
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+
+use astroport::asset::{token_asset_info, Asset, AssetInfoExt, PairInfo};
+use astroport::factory::{ExecuteMsg as FactoryExecuteMsg, PairConfig, PairType};
+use astroport::pair::{
+    Cw20HookMsg as PairCw20HookMsg, ExecuteMsg as PairExecuteMsg, QueryMsg as PairQueryMsg,
+    StablePoolParams,
+};
+use astroport::router::{
+    ConversionSpec, ExecuteMsg, InstantiateMsg, MultiPathSimulationResponse, PairDepthResponse,
+    QueryMsg, SimulateSwapOperationsResponse, SwapOperation,
+};
+
+use crate::factory_helper::{instantiate_token, mint, mint_native, FactoryHelper};
+
+#[allow(dead_code)]
+mod factory_helper;
+
+fn router_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new_with_empty(
+            astroport_router::contract::execute,
+            astroport_router::contract::instantiate,
+            astroport_router::contract::query,
+        )
+        .with_reply_empty(astroport_router::contract::reply),
+    )
+}
+
+fn stable_pair_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new_with_empty(
+            astroport_pair_stable::contract::execute,
+            astroport_pair_stable::contract::instantiate,
+            astroport_pair_stable::contract::query,
+        )
+        .with_reply_empty(astroport_pair_stable::contract::reply),
+    )
+}
+
+fn balance(app: &App, token: &Addr, address: &Addr) -> Uint128 {
+    let res: BalanceResponse = app
+        .wrap()
+        .query_wasm_smart(
+            token,
+            &Cw20QueryMsg::Balance {
+                address: address.to_string(),
+            },
+        )
+        .unwrap();
+    res.balance
+}
+
+fn provide_liquidity(app: &mut App, owner: &Addr, pair: &Addr, tokens: &[Addr], amount: u128) {
+    for token in tokens {
+        mint(app, owner, token, amount, owner).unwrap();
+        app.execute_contract(
+            owner.clone(),
+            token.clone(),
+            &Cw20ExecuteMsg::IncreaseAllowance {
+                spender: pair.to_string(),
+                amount: amount.into(),
+                expires: None,
+            },
+            &[],
+        )
+        .unwrap();
+    }
+
+    app.execute_contract(
+        owner.clone(),
+        pair.clone(),
+        &PairExecuteMsg::ProvideLiquidity {
+            assets: tokens
+                .iter()
+                .map(|token| Asset {
+                    info: token_asset_info(token.clone()),
+                    amount: amount.into(),
+                })
+                .collect(),
+            slippage_tolerance: None,
+            auto_stake: None,
+            receiver: None,
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+/// Deploys the factory and the router, creates two pairs of `pair_type` chaining three tokens,
+/// swaps through both pairs with the router and withdraws all the liquidity.
+fn swap_and_withdraw_through_pairs(pair_type: PairType) {
+    let mut app = App::default();
+
+    let owner = Addr::unchecked("owner");
+    let user = Addr::unchecked("user");
+    let mut helper = FactoryHelper::init(&mut app, &owner);
+
+    // The factory helper creates stable pairs with the XYK pair code
+    let stable_pair_code_id = app.store_code(stable_pair_contract());
+    app.execute_contract(
+        owner.clone(),
+        helper.factory.clone(),
+        &FactoryExecuteMsg::UpdatePairConfig {
+            config: PairConfig {
+                code_id: stable_pair_code_id,
+                pair_type: PairType::Stable {},
+                total_fee_bps: 0,
+                maker_fee_bps: 0,
+                is_disabled: false,
+                is_generator_disabled: false,
+                permissioned: false,
+            },
+            proposal_id: None,
+        },
+        &[],
+    )
+    .unwrap();
+    let init_params = match pair_type {
+        PairType::Stable {} => Some(
+            to_json_binary(&StablePoolParams {
+                amp: 100,
+                owner: None,
+            })
+            .unwrap(),
+        ),
+        _ => None,
+    };
+
+    let tokens = ["TOX", "TOY", "TOZ"]
+        .map(|name| instantiate_token(&mut app, helper.cw20_token_code_id, &owner, name, None));
+
+    let mut pairs = vec![];
+    for pair_tokens in tokens.windows(2) {
+        let pair = helper
+            .create_pair(
+                &mut app,
+                &owner,
+                pair_type.clone(),
+                [
+                    token_asset_info(pair_tokens[0].clone()),
+                    token_asset_info(pair_tokens[1].clone()),
+                ],
+                init_params.clone(),
+            )
+            .unwrap();
+        provide_liquidity(&mut app, &owner, &pair, pair_tokens, 1_000_000_000000);
+        pairs.push(pair);
+    }
+
+    let router_code = app.store_code(router_contract());
+    let router = app
+        .instantiate_contract(
+            router_code,
+            owner.clone(),
+            &InstantiateMsg {
+                astroport_factory: helper.factory.to_string(),
+                chain_id: None,
+            },
+            &[],
+            "router",
+            None,
+        )
+        .unwrap();
+
+    let operations: Vec<_> = tokens
+        .windows(2)
+        .map(|pair_tokens| SwapOperation::AstroSwap {
+            offer_asset_info: token_asset_info(pair_tokens[0].clone()),
+            ask_asset_info: token_asset_info(pair_tokens[1].clone()),
+        })
+        .collect();
+    let offer_amount = Uint128::new(1_000_000000);
+    let simulation: SimulateSwapOperationsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &router,
+            &QueryMsg::SimulateSwapOperations {
+                offer_amount,
+                operations: operations.clone(),
+            },
+        )
+        .unwrap();
+    assert!(!simulation.amount.is_zero());
+
+    mint(&mut app, &owner, &tokens[0], offer_amount.u128(), &user).unwrap();
+    app.execute_contract(
+        user.clone(),
+        tokens[0].clone(),
+        &Cw20ExecuteMsg::Send {
+            contract: router.to_string(),
+            amount: offer_amount,
+            msg: to_json_binary(&ExecuteMsg::ExecuteSwapOperations {
+                operations,
+                minimum_receive: Some(simulation.amount),
+                to: None,
+                max_spread: None,
+                chain_id: None,
+            })
+            .unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(balance(&app, &tokens[0], &user), Uint128::zero());
+    assert_eq!(balance(&app, &tokens[2], &user), simulation.amount);
+    for token in &tokens {
+        assert_eq!(balance(&app, token, &router), Uint128::zero());
+    }
+
+    for (pair, pair_tokens) in pairs.iter().zip(tokens.windows(2)) {
+        let pair_info: PairInfo = app
+            .wrap()
+            .query_wasm_smart(pair, &PairQueryMsg::Pair {})
+            .unwrap();
+        let lp_amount = balance(&app, &pair_info.liquidity_token, &owner);
+        let pools_before = pair_tokens
+            .iter()
+            .map(|token| balance(&app, token, pair))
+            .collect::<Vec<_>>();
+        let owner_before = pair_tokens
+            .iter()
+            .map(|token| balance(&app, token, &owner))
+            .collect::<Vec<_>>();
+
+        app.execute_contract(
+            owner.clone(),
+            pair_info.liquidity_token.clone(),
+            &Cw20ExecuteMsg::Send {
+                contract: pair.to_string(),
+                amount: lp_amount,
+                msg: to_json_binary(&PairCw20HookMsg::WithdrawLiquidity { assets: vec![] })
+                    .unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(
+            balance(&app, &pair_info.liquidity_token, &owner),
+            Uint128::zero()
+        );
+        for ((token, pool_before), owner_before) in
+            pair_tokens.iter().zip(pools_before).zip(owner_before)
+        {
+            let pool_after = balance(&app, token, pair);
+            let withdrawn = balance(&app, token, &owner) - owner_before;
+            // Only the liquidity locked at the first provision stays in the pool
+            assert!(!pool_after.is_zero());
+            assert_eq!(withdrawn + pool_after, pool_before);
+        }
+    }
+}
+
+#[test]
+fn xyk_swap_and_withdraw() {
+    swap_and_withdraw_through_pairs(PairType::Xyk {});
+}
+
+#[test]
+fn stable_swap_and_withdraw() {
+    swap_and_withdraw_through_pairs(PairType::Stable {});
+}
+
+#[test]
+fn multi_path_optimal_split() {
+    let mut app = App::default();
+
+    let owner = Addr::unchecked("owner");
+    let mut helper = FactoryHelper::init(&mut app, &owner);
+
+    let [tox, toy, toz] = ["TOX", "TOY", "TOZ"]
+        .map(|name| instantiate_token(&mut app, helper.cw20_token_code_id, &owner, name, None));
+
+    // The direct TOX-TOZ pool is twice as deep as the pools of the TOX-TOY-TOZ route
+    for (pair_tokens, amount) in [
+        ([tox.clone(), toz.clone()], 2_000_000000),
+        ([tox.clone(), toy.clone()], 1_000_000000),
+        ([toy.clone(), toz.clone()], 1_000_000000),
+    ] {
+        let pair = helper
+            .create_pair(
+                &mut app,
+                &owner,
+                PairType::Xyk {},
+                [
+                    token_asset_info(pair_tokens[0].clone()),
+                    token_asset_info(pair_tokens[1].clone()),
+                ],
+                None,
+            )
+            .unwrap();
+        provide_liquidity(&mut app, &owner, &pair, &pair_tokens, amount);
+    }
+
+    let router_code = app.store_code(router_contract());
+    let router = app
+        .instantiate_contract(
+            router_code,
+            owner.clone(),
+            &InstantiateMsg {
+                astroport_factory: helper.factory.to_string(),
+                chain_id: None,
+            },
+            &[],
+            "router",
+            None,
+        )
+        .unwrap();
+
+    let swap = |offer: &Addr, ask: &Addr| SwapOperation::AstroSwap {
+        offer_asset_info: token_asset_info(offer.clone()),
+        ask_asset_info: token_asset_info(ask.clone()),
+    };
+    let paths = vec![
+        vec![swap(&tox, &toz)],
+        vec![swap(&tox, &toy), swap(&toy, &toz)],
+    ];
+    let simulate = |splits: Vec<Decimal>| -> MultiPathSimulationResponse {
+        app.wrap()
+            .query_wasm_smart(
+                &router,
+                &QueryMsg::SimulateMultiPath {
+                    offer_asset: token_asset_info(tox.clone()).with_balance(100_000000u128),
+                    paths: paths.clone(),
+                    splits,
+                },
+            )
+            .unwrap()
+    };
+
+    let single_route = simulate(vec![Decimal::one(), Decimal::zero()]);
+    assert_eq!(single_route.outputs_per_path[1], Uint128::zero());
+    assert_eq!(single_route.total_output, single_route.outputs_per_path[0]);
+
+    let equal_split = simulate(vec![Decimal::percent(50), Decimal::percent(50)]);
+    assert_eq!(
+        equal_split.total_output,
+        equal_split.outputs_per_path[0] + equal_split.outputs_per_path[1]
+    );
+
+    // The optimal split doesn't depend on the requested one and beats both alternatives
+    assert_eq!(single_route.optimal_split, equal_split.optimal_split);
+    assert_eq!(single_route.optimal_total, equal_split.optimal_total);
+    assert!(single_route.optimal_total > single_route.total_output);
+    assert!(single_route.optimal_total > equal_split.total_output);
+    assert!(single_route.optimal_split[0] > Decimal::percent(50));
+    assert!(single_route.optimal_split[0] < Decimal::one());
+}
+
+#[test]
+fn pair_depth() {
+    let mut app = App::default();
+
+    let owner = Addr::unchecked("owner");
+    let mut helper = FactoryHelper::init(&mut app, &owner);
+
+    let [tox, toy] = ["TOX", "TOY"]
+        .map(|name| instantiate_token(&mut app, helper.cw20_token_code_id, &owner, name, None));
+    let pair = helper
+        .create_pair(
+            &mut app,
+            &owner,
+            PairType::Xyk {},
+            [token_asset_info(tox.clone()), token_asset_info(toy.clone())],
+            None,
+        )
+        .unwrap();
+    let reserve = 1_000_000_000000u128;
+    provide_liquidity(
+        &mut app,
+        &owner,
+        &pair,
+        &[tox.clone(), toy.clone()],
+        reserve,
+    );
+
+    let router_code = app.store_code(router_contract());
+    let router = app
+        .instantiate_contract(
+            router_code,
+            owner.clone(),
+            &InstantiateMsg {
+                astroport_factory: helper.factory.to_string(),
+                chain_id: None,
+            },
+            &[],
+            "router",
+            None,
+        )
+        .unwrap();
+
+    let pair_depth = |depth_levels: Vec<Decimal>| {
+        app.wrap().query_wasm_smart::<PairDepthResponse>(
+            &router,
+            &QueryMsg::PairDepth {
+                offer: token_asset_info(tox.clone()),
+                ask: token_asset_info(toy.clone()),
+                depth_levels,
+            },
+        )
+    };
+
+    let err = pair_depth(vec![Decimal::one(), Decimal::zero()]).unwrap_err();
+    assert!(err.to_string().contains("depth levels"));
+    let err = pair_depth(vec![Decimal::from_ratio(100u8, 1u8)]).unwrap_err();
+    assert!(err.to_string().contains("depth levels"));
+
+    let levels = vec![
+        Decimal::percent(10),
+        Decimal::one(),
+        Decimal::from_ratio(10u8, 1u8),
+    ];
+    let depth = pair_depth(levels.clone()).unwrap();
+
+    // Swapping x into a pool with equal reserves R has the price impact x / (R + x), so the
+    // largest swap within the price impact p sells R * p / (1 - p) and buys R * p
+    for ((level, (bid_level, sold)), (ask_level, bought)) in
+        levels.into_iter().zip(depth.bids).zip(depth.asks)
+    {
+        assert_eq!(bid_level, level);
+        assert_eq!(ask_level, level);
+
+        let impact = level * Decimal::percent(1);
+        let expected_sold = Uint128::new(reserve)
+            .multiply_ratio(impact.atomics(), (Decimal::one() - impact).atomics());
+        let expected_bought = Uint128::new(reserve) * impact;
+        for (amount, expected) in [(sold, expected_sold), (bought, expected_bought)] {
+            assert!(amount <= expected, "{amount} > {expected}");
+            assert!(
+                amount >= expected * Decimal::permille(998),
+                "{amount} < {expected}"
+            );
+        }
+    }
+}
+
+#[test]
+fn multi_asset_swap() {
+    let mut app = App::default();
+
+    let owner = Addr::unchecked("owner");
+    let user = Addr::unchecked("user");
+    let mut helper = FactoryHelper::init(&mut app, &owner);
+
+    let [toa, tob, toc] = ["TOA", "TOB", "TOC"]
+        .map(|name| instantiate_token(&mut app, helper.cw20_token_code_id, &owner, name, None));
+    for token in [&toa, &tob] {
+        let pair = helper
+            .create_pair(
+                &mut app,
+                &owner,
+                PairType::Xyk {},
+                [
+                    token_asset_info(token.clone()),
+                    token_asset_info(toc.clone()),
+                ],
+                None,
+            )
+            .unwrap();
+        provide_liquidity(
+            &mut app,
+            &owner,
+            &pair,
+            &[token.clone(), toc.clone()],
+            1_000_000_000000,
+        );
+    }
+
+    let router_code = app.store_code(router_contract());
+    let router = app
+        .instantiate_contract(
+            router_code,
+            owner.clone(),
+            &InstantiateMsg {
+                astroport_factory: helper.factory.to_string(),
+                chain_id: None,
+            },
+            &[],
+            "router",
+            None,
+        )
+        .unwrap();
+
+    let offer_amount = 1_000_000u128;
+    for token in [&toa, &tob] {
+        mint(&mut app, &owner, token, offer_amount, &user).unwrap();
+        app.execute_contract(
+            user.clone(),
+            token.clone(),
+            &Cw20ExecuteMsg::IncreaseAllowance {
+                spender: router.to_string(),
+                amount: offer_amount.into(),
+                expires: None,
+            },
+            &[],
+        )
+        .unwrap();
+    }
+
+    let swap_operation = |offer: &Addr, ask: &Addr| SwapOperation::AstroSwap {
+        offer_asset_info: token_asset_info(offer.clone()),
+        ask_asset_info: token_asset_info(ask.clone()),
+    };
+    let conversion = |offer: &Addr, operations: Vec<SwapOperation>| ConversionSpec {
+        offer: token_asset_info(offer.clone()).with_balance(offer_amount),
+        operations,
+        min_receive: Uint128::zero(),
+    };
+
+    let expected_returns = [&toa, &tob].map(|token| {
+        app.wrap()
+            .query_wasm_smart::<SimulateSwapOperationsResponse>(
+                &router,
+                &QueryMsg::SimulateSwapOperations {
+                    offer_amount: offer_amount.into(),
+                    operations: vec![swap_operation(token, &toc)],
+                },
+            )
+            .unwrap()
+            .amount
+    });
+    let expected_total = expected_returns[0] + expected_returns[1];
+
+    // The second conversion would swap what the first one received
+    let err = app
+        .execute_contract(
+            user.clone(),
+            router.clone(),
+            &ExecuteMsg::MultiAssetSwap {
+                conversions: vec![
+                    conversion(&toa, vec![swap_operation(&toa, &toc)]),
+                    conversion(
+                        &tob,
+                        vec![swap_operation(&tob, &toc), swap_operation(&toc, &toa)],
+                    ),
+                ],
+                min_total_receive: None,
+                to: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        format!("Asset {toc} can't be swapped by one conversion while another conversion offers or receives it")
+    );
+
+    let multi_asset_swap = |min_total_receive: Uint128| ExecuteMsg::MultiAssetSwap {
+        conversions: vec![
+            conversion(&toa, vec![swap_operation(&toa, &toc)]),
+            conversion(&tob, vec![swap_operation(&tob, &toc)]),
+        ],
+        min_total_receive: Some(min_total_receive),
+        to: None,
+    };
+    let err = app
+        .execute_contract(
+            user.clone(),
+            router.clone(),
+            &multi_asset_swap(expected_total + Uint128::one()),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        format!(
+            "Assertion failed; minimum receive amount: {}, swap amount: {expected_total}",
+            expected_total + Uint128::one()
+        )
+    );
+
+    // Coins which no conversion offers are rejected instead of being left in the router
+    mint_native(&mut app, "uluna", 100, &user).unwrap();
+    let err = app
+        .execute_contract(
+            user.clone(),
+            router.clone(),
+            &multi_asset_swap(expected_total),
+            &[coin(100, "uluna")],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "uluna was sent but no conversion offers it"
+    );
+
+    app.execute_contract(
+        user.clone(),
+        router.clone(),
+        &multi_asset_swap(expected_total),
+        &[],
+    )
+    .unwrap();
+    assert_eq!(balance(&app, &toc, &user), expected_total);
+    for token in [&toa, &tob, &toc] {
+        assert_eq!(balance(&app, token, &router), Uint128::zero());
+    }
+    assert_eq!(balance(&app, &toa, &user), Uint128::zero());
+}
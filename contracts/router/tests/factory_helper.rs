@@ -130,6 +130,7 @@ impl FactoryHelper {
             pair_type,
             asset_infos: asset_infos.to_vec(),
             init_params,
+            proposal_id: None,
         };
 
         router.execute_contract(sender.clone(), self.factory.clone(), &msg, &[])?;
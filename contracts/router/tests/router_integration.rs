@@ -59,6 +59,7 @@ fn router_does_not_enforce_spread_assertion() {
             owner.clone(),
             &InstantiateMsg {
                 astroport_factory: helper.factory.to_string(),
+                chain_id: None,
             },
             &[],
             "router",
@@ -89,6 +90,7 @@ fn router_does_not_enforce_spread_assertion() {
                     minimum_receive: None,
                     to: None,
                     max_spread: None,
+                    chain_id: None,
                 })
                 .unwrap(),
             },
@@ -119,6 +121,7 @@ fn router_does_not_enforce_spread_assertion() {
                     minimum_receive: None,
                     to: None,
                     max_spread: None,
+                    chain_id: None,
                 })
                 .unwrap(),
             },
@@ -169,6 +172,7 @@ fn route_through_pairs_with_natives() {
             owner.clone(),
             &InstantiateMsg {
                 astroport_factory: helper.factory.to_string(),
+                chain_id: None,
             },
             &[],
             "router",
@@ -209,6 +213,7 @@ fn route_through_pairs_with_natives() {
                 }],
                 to: None,
                 max_spread: None,
+                chain_id: None,
                 minimum_receive: None,
             },
             &[],
@@ -230,6 +235,7 @@ fn route_through_pairs_with_natives() {
                 }],
                 to: None,
                 max_spread: None,
+                chain_id: None,
                 minimum_receive: None,
             },
             &[],
@@ -264,6 +270,7 @@ fn route_through_pairs_with_natives() {
                 minimum_receive: None,
                 to: None,
                 max_spread: None,
+                chain_id: None,
             },
             &coins(50_000_000000, denom_x),
         )
@@ -292,6 +299,7 @@ fn route_through_pairs_with_natives() {
                 minimum_receive: Some(50_000_000000u128.into()), // <--- enforcing minimum receive with 1:1 rate (which practically impossible)
                 to: None,
                 max_spread: None,
+                chain_id: None,
             },
             &coins(50_000_000000, denom_x),
         )
@@ -348,6 +356,7 @@ fn test_swap_route() {
             owner.clone(),
             &InstantiateMsg {
                 astroport_factory: helper.factory.to_string(),
+                chain_id: None,
             },
             &[],
             "router",
@@ -478,6 +487,7 @@ fn test_swap_route() {
                 minimum_receive: None,
                 to: None,
                 max_spread: None,
+                chain_id: None,
             })
             .unwrap(),
         },
@@ -565,6 +575,7 @@ fn test_swap_route() {
                 minimum_receive: None,
                 to: None,
                 max_spread: None,
+                chain_id: None,
             })
             .unwrap(),
         },
@@ -657,11 +668,15 @@ fn test_swap_route() {
                 minimum_receive: Some(Uint128::new(9_997_000)),
                 to: None,
                 max_spread: None,
+                chain_id: None,
             },
             &[],
         )
         .unwrap_err();
-    assert_eq!(err.root_cause().to_string(), "Invalid zero amount");
+    assert!(matches!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::HopFailed { hop_index: 0, .. }
+    ));
 
     // Query attacker balance and calculate profit
     let balance_res: BalanceResponse = app
@@ -789,6 +804,7 @@ fn test_swap_route() {
                 minimum_receive: Some(donated_atom),
                 to: None,
                 max_spread: None,
+                chain_id: None,
             })
             .unwrap(),
         },
@@ -847,11 +863,15 @@ fn test_swap_route() {
                 minimum_receive: None,
                 to: None,
                 max_spread: None,
+                chain_id: None,
             },
             &[],
         )
         .unwrap_err();
-    assert_eq!(err.root_cause().to_string(), "Invalid zero amount");
+    assert!(matches!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::HopFailed { hop_index: 0, .. }
+    ));
 
     let balance_res: BalanceResponse = app
         .wrap()
@@ -880,3 +900,99 @@ fn test_swap_route() {
     let profit = balance_res.balance.saturating_sub(donated_atom);
     println!("Attacker2's profit: {:?}", profit);
 }
+
+#[test]
+fn route_with_dust_hop_is_aborted() {
+    use cw20::{BalanceResponse, Cw20QueryMsg};
+
+    let mut app = App::default();
+
+    let owner = Addr::unchecked("owner");
+    let mut helper = FactoryHelper::init(&mut app, &owner);
+
+    let token_x = instantiate_token(&mut app, helper.cw20_token_code_id, &owner, "TOX", None);
+    let token_y = instantiate_token(&mut app, helper.cw20_token_code_id, &owner, "TOY", None);
+    let token_z = instantiate_token(&mut app, helper.cw20_token_code_id, &owner, "TOZ", None);
+
+    // The first pair returns less than the default dust threshold
+    for (a, b, liq_a, liq_b) in [
+        (&token_x, &token_y, 1_000_000, 100_000),
+        (&token_y, &token_z, 1_000_000_000000, 1_000_000_000000),
+    ] {
+        let pair = helper
+            .create_pair(
+                &mut app,
+                &owner,
+                PairType::Xyk {},
+                [token_asset_info(a.clone()), token_asset_info(b.clone())],
+                None,
+            )
+            .unwrap();
+        mint(&mut app, &owner, a, liq_a, &pair).unwrap();
+        mint(&mut app, &owner, b, liq_b, &pair).unwrap();
+    }
+
+    let router_code = app.store_code(router_contract());
+    let router = app
+        .instantiate_contract(
+            router_code,
+            owner.clone(),
+            &InstantiateMsg {
+                astroport_factory: helper.factory.to_string(),
+                chain_id: None,
+            },
+            &[],
+            "router",
+            None,
+        )
+        .unwrap();
+
+    let user = Addr::unchecked("user");
+    mint(&mut app, &owner, &token_x, 5_000, &user).unwrap();
+    let err = app
+        .execute_contract(
+            user.clone(),
+            token_x.clone(),
+            &Cw20ExecuteMsg::Send {
+                contract: router.to_string(),
+                amount: 5_000u128.into(),
+                msg: to_json_binary(&ExecuteMsg::ExecuteSwapOperations {
+                    operations: vec![
+                        SwapOperation::AstroSwap {
+                            offer_asset_info: token_asset_info(token_x.clone()),
+                            ask_asset_info: token_asset_info(token_y.clone()),
+                        },
+                        SwapOperation::AstroSwap {
+                            offer_asset_info: token_asset_info(token_y.clone()),
+                            ask_asset_info: token_asset_info(token_z.clone()),
+                        },
+                    ],
+                    minimum_receive: None,
+                    to: None,
+                    max_spread: None,
+                    chain_id: None,
+                })
+                .unwrap(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::HopFailed { hop_index: 1, .. }
+    ));
+
+    // The whole route is reverted, so no intermediate tokens are left in the router
+    for (token, address, expected) in [(&token_x, &user, 5_000u128), (&token_y, &router, 0)] {
+        let balance: BalanceResponse = app
+            .wrap()
+            .query_wasm_smart(
+                token,
+                &Cw20QueryMsg::Balance {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(balance.balance.u128(), expected);
+    }
+}
@@ -0,0 +1,245 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    attr, to_json_binary, wasm_execute, Binary, Deps, DepsMut, Env, MessageInfo, Order, Reply,
+    Response, StdError, StdResult, SubMsg, SubMsgResponse, SubMsgResult, WasmMsg,
+};
+use cw2::set_contract_version;
+use cw_utils::parse_instantiate_response_data;
+
+use astroport::router::{ExecuteMsg as RouterExecuteMsg, InstantiateMsg as RouterInstantiateMsg};
+use astroport::router_factory::{
+    ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg, RouterConfig, RouterInfo,
+};
+
+use crate::error::ContractError;
+use crate::state::{Config, TmpRouterInfo, CONFIG, DEPLOYED_ROUTERS, TMP_ROUTER_INFO};
+
+/// Contract name that is used for migration.
+const CONTRACT_NAME: &str = "astroport-router-factory";
+/// Contract version that is used for migration.
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// A `reply` call code ID used in the router instantiate sub-message.
+const INSTANTIATE_ROUTER_REPLY_ID: u64 = 1;
+
+/// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let config = Config {
+        owner: deps.api.addr_validate(&msg.owner)?,
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(
+        Response::new()
+            .add_attributes([attr("action", "instantiate"), attr("owner", config.owner)]),
+    )
+}
+
+/// Exposes execute functions available in the contract.
+///
+/// ## Variants
+/// * **ExecuteMsg::DeployRouter {
+///             factory_addr,
+///             code_id,
+///             label,
+///             owner,
+///         }** Instantiates a new router for the current chain.
+///
+/// * **ExecuteMsg::UpdateRouterConfig { router, config }** Updates the config of a deployed router.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::DeployRouter {
+            factory_addr,
+            code_id,
+            label,
+            owner,
+        } => deploy_router(deps, env, info, factory_addr, code_id, label, owner),
+        ExecuteMsg::UpdateRouterConfig { router, config } => {
+            update_router_config(deps, info, router, config)
+        }
+    }
+}
+
+/// Instantiates a new router with the router factory as its admin. The router is recorded
+/// for the current chain once it is instantiated.
+///
+/// * **factory_addr** Astroport factory used by the router.
+///
+/// * **code_id** router code identifier.
+///
+/// * **label** router contract label.
+///
+/// * **owner** address allowed to update the router config through the router factory.
+///
+/// ## Executor
+/// Only the owner can execute this.
+pub fn deploy_router(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    factory_addr: String,
+    code_id: u64,
+    label: String,
+    owner: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if DEPLOYED_ROUTERS.has(deps.storage, env.block.chain_id.clone()) {
+        return Err(ContractError::RouterAlreadyDeployed(env.block.chain_id));
+    }
+
+    let tmp = TmpRouterInfo {
+        code_id,
+        label: label.clone(),
+        owner: deps.api.addr_validate(&owner)?,
+        astroport_factory: deps.api.addr_validate(&factory_addr)?,
+    };
+    TMP_ROUTER_INFO.save(deps.storage, &tmp)?;
+
+    let instantiate_msg = WasmMsg::Instantiate {
+        admin: Some(env.contract.address.to_string()),
+        code_id,
+        msg: to_json_binary(&RouterInstantiateMsg {
+            astroport_factory: factory_addr,
+            chain_id: None,
+        })?,
+        funds: vec![],
+        label,
+    };
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(
+            instantiate_msg,
+            INSTANTIATE_ROUTER_REPLY_ID,
+        ))
+        .add_attributes([
+            attr("action", "deploy_router"),
+            attr("chain_id", env.block.chain_id),
+            attr("code_id", code_id.to_string()),
+            attr("owner", tmp.owner),
+        ]))
+}
+
+/// Updates the config of a router deployed by the router factory. The router factory is the
+/// admin of the router, so the router accepts the update.
+///
+/// * **router** router address.
+///
+/// * **config** new router config.
+///
+/// ## Executor
+/// Only the owner of the router factory or the owner of the router can execute this.
+pub fn update_router_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    router: String,
+    config: RouterConfig,
+) -> Result<Response, ContractError> {
+    let router = deps.api.addr_validate(&router)?;
+    let mut router_info = DEPLOYED_ROUTERS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?
+        .into_iter()
+        .map(|(_, router_info)| router_info)
+        .find(|router_info| router_info.address == router)
+        .ok_or_else(|| ContractError::RouterNotFound(router.to_string()))?;
+
+    let factory_owner = CONFIG.load(deps.storage)?.owner;
+    if info.sender != factory_owner && info.sender != router_info.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    router_info.astroport_factory = deps.api.addr_validate(&config.astroport_factory)?;
+    DEPLOYED_ROUTERS.save(deps.storage, router_info.chain_id.clone(), &router_info)?;
+
+    let update_msg = wasm_execute(
+        &router,
+        &RouterExecuteMsg::UpdateConfig {
+            astroport_factory: Some(config.astroport_factory),
+            dust_threshold: None,
+            gas_price: None,
+            check_received_amount: None,
+            volume_discount: None,
+        },
+        vec![],
+    )?;
+
+    Ok(Response::new().add_message(update_msg).add_attributes([
+        attr("action", "update_router_config"),
+        attr("router", router),
+        attr("astroport_factory", router_info.astroport_factory),
+    ]))
+}
+
+/// The entry point to the contract for processing replies from submessages.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg {
+        Reply {
+            id: INSTANTIATE_ROUTER_REPLY_ID,
+            result:
+                SubMsgResult::Ok(SubMsgResponse {
+                    data: Some(data), ..
+                }),
+        } => {
+            let tmp = TMP_ROUTER_INFO.load(deps.storage)?;
+            TMP_ROUTER_INFO.remove(deps.storage);
+
+            let init_response = parse_instantiate_response_data(data.as_slice())
+                .map_err(|e| StdError::generic_err(format!("{e}")))?;
+
+            let router_info = RouterInfo {
+                address: deps.api.addr_validate(&init_response.contract_address)?,
+                chain_id: env.block.chain_id,
+                code_id: tmp.code_id,
+                label: tmp.label,
+                owner: tmp.owner,
+                astroport_factory: tmp.astroport_factory,
+            };
+            DEPLOYED_ROUTERS.save(deps.storage, router_info.chain_id.clone(), &router_info)?;
+
+            Ok(Response::new().add_attributes([
+                attr("action", "register_router"),
+                attr("router", router_info.address),
+                attr("chain_id", router_info.chain_id),
+            ]))
+        }
+        _ => Err(ContractError::FailedToParseReply {}),
+    }
+}
+
+/// Exposes all the queries available in the contract.
+///
+/// ## Queries
+/// * **QueryMsg::Config {}** Returns the contract configuration using a [`ConfigResponse`] object.
+///
+/// * **QueryMsg::RouterFor { chain_id }** Returns the router deployed for the chain using a
+/// [`RouterInfo`] object.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&ConfigResponse {
+            owner: CONFIG.load(deps.storage)?.owner,
+        }),
+        QueryMsg::RouterFor { chain_id } => {
+            to_json_binary(&DEPLOYED_ROUTERS.load(deps.storage, chain_id)?)
+        }
+    }
+}
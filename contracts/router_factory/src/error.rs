@@ -0,0 +1,21 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+/// This enum describes router factory contract errors
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("A router is already deployed for chain {0}")]
+    RouterAlreadyDeployed(String),
+
+    #[error("Router {0} was not deployed by the router factory")]
+    RouterNotFound(String),
+
+    #[error("Failed to parse or process reply message")]
+    FailedToParseReply {},
+}
@@ -0,0 +1,27 @@
+use astroport::router_factory::RouterInfo;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Addr;
+use cw_storage_plus::{Item, Map};
+
+/// This structure stores the main parameters for the router factory contract.
+#[cw_serde]
+pub struct Config {
+    /// The address allowed to deploy routers
+    pub owner: Addr,
+}
+
+/// This structure stores the parameters of a router which is being instantiated.
+#[cw_serde]
+pub struct TmpRouterInfo {
+    pub code_id: u64,
+    pub label: String,
+    pub owner: Addr,
+    pub astroport_factory: Addr,
+}
+
+/// Stores the contract config at the given key
+pub const CONFIG: Item<Config> = Item::new("config");
+/// Saves the router parameters while the router is instantiated
+pub const TMP_ROUTER_INFO: Item<TmpRouterInfo> = Item::new("tmp_router_info");
+/// Stores the deployed routers. Key: chain id -> router info
+pub const DEPLOYED_ROUTERS: Map<String, RouterInfo> = Map::new("deployed_routers");
@@ -0,0 +1,211 @@
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Reply, StdError, SubMsg, SubMsgResponse, SubMsgResult, WasmMsg,
+};
+use prost::Message;
+
+use astroport::router::{ExecuteMsg as RouterExecuteMsg, InstantiateMsg as RouterInstantiateMsg};
+use astroport::router_factory::{ExecuteMsg, InstantiateMsg, QueryMsg, RouterConfig, RouterInfo};
+
+use crate::contract::{execute, instantiate, query, reply};
+use crate::error::ContractError;
+
+#[derive(Clone, PartialEq, Message)]
+struct MsgInstantiateContractResponse {
+    #[prost(string, tag = "1")]
+    pub contract_address: String,
+    #[prost(bytes, tag = "2")]
+    pub data: Vec<u8>,
+}
+
+fn instantiate_reply(contract_address: &str) -> Reply {
+    let instantiate_reply = MsgInstantiateContractResponse {
+        contract_address: contract_address.to_string(),
+        data: vec![],
+    };
+    let mut encoded_instantiate_reply = Vec::<u8>::with_capacity(instantiate_reply.encoded_len());
+    instantiate_reply
+        .encode(&mut encoded_instantiate_reply)
+        .unwrap();
+
+    Reply {
+        id: 1,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: Some(encoded_instantiate_reply.into()),
+        }),
+    }
+}
+
+#[test]
+fn deploy_and_update_router() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        InstantiateMsg {
+            owner: "owner".to_string(),
+        },
+    )
+    .unwrap();
+
+    let deploy_msg = ExecuteMsg::DeployRouter {
+        factory_addr: "factory".to_string(),
+        code_id: 10,
+        label: "Astroport router".to_string(),
+        owner: "router_owner".to_string(),
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("router_owner", &[]),
+        deploy_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        deploy_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_success(
+            WasmMsg::Instantiate {
+                admin: Some(MOCK_CONTRACT_ADDR.to_string()),
+                code_id: 10,
+                msg: to_json_binary(&RouterInstantiateMsg {
+                    astroport_factory: "factory".to_string(),
+                    chain_id: None,
+                })
+                .unwrap(),
+                funds: vec![],
+                label: "Astroport router".to_string(),
+            },
+            1
+        )]
+    );
+
+    reply(deps.as_mut(), env.clone(), instantiate_reply("router")).unwrap();
+
+    let router_info: RouterInfo = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RouterFor {
+                chain_id: env.block.chain_id.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        router_info,
+        RouterInfo {
+            address: Addr::unchecked("router"),
+            chain_id: env.block.chain_id.clone(),
+            code_id: 10,
+            label: "Astroport router".to_string(),
+            owner: Addr::unchecked("router_owner"),
+            astroport_factory: Addr::unchecked("factory"),
+        }
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        deploy_msg,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::RouterAlreadyDeployed(env.block.chain_id.clone())
+    );
+
+    let err = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::RouterFor {
+            chain_id: "other-chain".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, StdError::NotFound { .. }));
+
+    let update_msg = ExecuteMsg::UpdateRouterConfig {
+        router: "router".to_string(),
+        config: RouterConfig {
+            astroport_factory: "new_factory".to_string(),
+        },
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("random", &[]),
+        update_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecuteMsg::UpdateRouterConfig {
+            router: "unknown".to_string(),
+            config: RouterConfig {
+                astroport_factory: "new_factory".to_string(),
+            },
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::RouterNotFound("unknown".to_string()));
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("router_owner", &[]),
+        update_msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "router".to_string(),
+            msg: to_json_binary(&RouterExecuteMsg::UpdateConfig {
+                astroport_factory: Some("new_factory".to_string()),
+                dust_threshold: None,
+                gas_price: None,
+                check_received_amount: None,
+                volume_discount: None,
+            })
+            .unwrap(),
+            funds: vec![],
+        })]
+    );
+
+    let router_info: RouterInfo = from_json(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RouterFor {
+                chain_id: env.block.chain_id,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        router_info.astroport_factory,
+        Addr::unchecked("new_factory")
+    );
+}
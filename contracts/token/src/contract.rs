@@ -1,16 +1,25 @@
+use cosmwasm_schema::serde::Deserialize;
 use cosmwasm_std::{
-    entry_point, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
+    attr, entry_point, to_json_binary, wasm_execute, Addr, Api, Binary, Decimal256, Deps, DepsMut,
+    Env, MessageInfo, QuerierWrapper, Reply, Response, StdError, StdResult, Storage, SubMsg,
+    SubMsgResult, Uint128,
 };
 use cw20::{EmbeddedLogo, Logo, LogoInfo, MarketingInfoResponse};
 
 use cw2::{get_contract_version, set_contract_version};
 use cw20_base::contract::{create_accounts, execute as cw20_execute, query as cw20_query};
 use cw20_base::msg::{ExecuteMsg, QueryMsg};
-use cw20_base::state::{MinterData, TokenInfo, LOGO, MARKETING_INFO, TOKEN_INFO};
+use cw20_base::state::{MinterData, TokenInfo, BALANCES, LOGO, MARKETING_INFO, TOKEN_INFO};
 use cw20_base::ContractError;
 
-use astroport::asset::addr_opt_validate;
-use astroport::token::{InstantiateMsg, MigrateMsg};
+use astroport::asset::{addr_opt_validate, AssetInfoExt};
+use astroport::pair::{FeePerShareResponse, QueryMsg as PairQueryMsg};
+use astroport::pair_xyk::{ExecuteMsg as PairExecuteMsg, LpTransfer};
+use astroport::token::{
+    FeesEarnedResponse, FeesExecuteMsg, FeesQueryMsg, InstantiateMsg, MigrateMsg,
+};
+
+use crate::state::{FEES_ACCRUED, FEES_TRACKED_SINCE, FEE_SOURCE, LAST_FEE_PER_SHARE};
 
 /// Contract name that is used for migration.
 const CONTRACT_NAME: &str = "astroport-token";
@@ -19,6 +28,12 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 const LOGO_SIZE_CAP: usize = 5 * 1024;
 
+/// A `reply` call code ID used to ignore a failed LP holders update.
+const UPDATE_LP_HOLDERS_REPLY_ID: u64 = 1;
+/// The gas limit of the LP holders update, so a failing or expensive update can't block LP
+/// transfers.
+const UPDATE_LP_HOLDERS_GAS_LIMIT: u64 = 1_000_000;
+
 /// Checks if data starts with XML preamble
 fn verify_xml_preamble(data: &[u8]) -> Result<(), ContractError> {
     // The easiest way to perform this check would be just match on regex, however regex
@@ -144,21 +159,267 @@ pub fn instantiate(
     Ok(Response::default())
 }
 
+/// This enum combines the CW20 messages with the LP fee messages.
+#[derive(Deserialize, Debug)]
+#[serde(crate = "cosmwasm_schema::serde", untagged)]
+pub enum TokenExecuteMsg {
+    Cw20(ExecuteMsg),
+    Fees(FeesExecuteMsg),
+}
+
+/// This enum combines the CW20 queries with the LP fee queries.
+#[derive(Deserialize, Debug)]
+#[serde(crate = "cosmwasm_schema::serde", untagged)]
+pub enum TokenQueryMsg {
+    Cw20(QueryMsg),
+    Fees(FeesQueryMsg),
+}
+
 /// Exposes execute functions available in the contract.
+///
+/// ## Variants
+/// * **FeesExecuteMsg::EnableFeeTracking {}** Starts tracking the LP fees with the fees per LP
+/// token reported by the minter.
+///
+/// All the CW20 messages are supported as well.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: TokenExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        TokenExecuteMsg::Cw20(msg) => execute_cw20(deps, env, info, msg),
+        TokenExecuteMsg::Fees(FeesExecuteMsg::EnableFeeTracking {}) => {
+            enable_fee_tracking(deps, info)
+        }
+    }
+}
+
+/// Executes a CW20 message. If the LP fees are tracked, the fees of every address whose balance
+/// changes are checkpointed first. The pair is notified about the changed balances afterwards,
+/// so it can keep track of the top LP holders. The notification is gas limited and its failure
+/// is ignored in [`reply`].
+fn execute_cw20(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    cw20_execute(deps, env, info, msg)
+    let mut update_holders_msg = None;
+    if let Some(pair) = FEE_SOURCE.may_load(deps.storage)? {
+        let fee_per_share = query_fee_per_share(deps.querier, &pair)?.fee_per_share_cumulative;
+        let addresses = balance_changes(deps.api, &info.sender, &msg)?;
+        for address in &addresses {
+            checkpoint_fees(deps.storage, &env, address, &fee_per_share)?;
+        }
+
+        if !addresses.is_empty() {
+            update_holders_msg = Some(
+                SubMsg::reply_on_error(
+                    wasm_execute(
+                        pair,
+                        &PairExecuteMsg::UpdateLpHolders {
+                            addresses: addresses.iter().map(Addr::to_string).collect(),
+                            transfer: lp_transfer(&info.sender, &msg),
+                        },
+                        vec![],
+                    )?,
+                    UPDATE_LP_HOLDERS_REPLY_ID,
+                )
+                .with_gas_limit(UPDATE_LP_HOLDERS_GAS_LIMIT),
+            );
+        }
+    }
+
+    let mut response = cw20_execute(deps, env, info, msg)?;
+
+    // The pair is notified before the recipient of a Send, so the LP cooldown is carried over
+    // before the recipient can use the LP tokens
+    if let Some(msg) = update_holders_msg {
+        response.messages.insert(0, msg);
+    }
+
+    Ok(response)
+}
+
+/// The entry point to the contract for processing replies from submessages.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(_deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg {
+        Reply {
+            id: UPDATE_LP_HOLDERS_REPLY_ID,
+            result: SubMsgResult::Err(err),
+        } => Ok(Response::new().add_attributes([
+            attr("action", "update_lp_holders_failed"),
+            attr("error", err),
+        ])),
+        _ => Err(StdError::generic_err("Failed to parse reply").into()),
+    }
+}
+
+/// Starts tracking the LP fees with the fees per LP token reported by the minter, so tokens
+/// minted by other contracts never query the minter for them.
+///
+/// ## Executor
+/// Only the minter can execute this.
+fn enable_fee_tracking(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let minter = TOKEN_INFO
+        .load(deps.storage)?
+        .mint
+        .map(|MinterData { minter, .. }| minter);
+    if minter != Some(info.sender.clone()) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // The minter must report the fees per LP token
+    query_fee_per_share(deps.querier, &info.sender)?;
+    FEE_SOURCE.save(deps.storage, &info.sender)?;
+
+    Ok(Response::new().add_attribute("action", "enable_fee_tracking"))
+}
+
+/// Returns the LP transfer made by the message, if any.
+fn lp_transfer(sender: &Addr, msg: &ExecuteMsg) -> Option<LpTransfer> {
+    let (from, to, amount) = match msg {
+        ExecuteMsg::Transfer { recipient, amount } => (sender.as_str(), recipient, amount),
+        ExecuteMsg::Send {
+            contract, amount, ..
+        } => (sender.as_str(), contract, amount),
+        ExecuteMsg::TransferFrom {
+            owner,
+            recipient,
+            amount,
+        } => (owner.as_str(), recipient, amount),
+        ExecuteMsg::SendFrom {
+            owner,
+            contract,
+            amount,
+            ..
+        } => (owner.as_str(), contract, amount),
+        _ => return None,
+    };
+
+    Some(LpTransfer {
+        from: from.to_string(),
+        to: to.to_string(),
+        amount: *amount,
+    })
+}
+
+/// Returns the addresses whose balances are changed by the message.
+fn balance_changes(api: &dyn Api, sender: &Addr, msg: &ExecuteMsg) -> StdResult<Vec<Addr>> {
+    let addresses = match msg {
+        ExecuteMsg::Transfer { recipient, .. } => {
+            vec![sender.clone(), api.addr_validate(recipient)?]
+        }
+        ExecuteMsg::Send { contract, .. } => vec![sender.clone(), api.addr_validate(contract)?],
+        ExecuteMsg::Burn { .. } => vec![sender.clone()],
+        ExecuteMsg::Mint { recipient, .. } => vec![api.addr_validate(recipient)?],
+        ExecuteMsg::TransferFrom {
+            owner, recipient, ..
+        } => vec![api.addr_validate(owner)?, api.addr_validate(recipient)?],
+        ExecuteMsg::SendFrom {
+            owner, contract, ..
+        } => vec![api.addr_validate(owner)?, api.addr_validate(contract)?],
+        ExecuteMsg::BurnFrom { owner, .. } => vec![api.addr_validate(owner)?],
+        _ => vec![],
+    };
+
+    Ok(addresses)
+}
+
+/// Moves the fees earned by `address` with its current balance to [`FEES_ACCRUED`].
+fn checkpoint_fees(
+    storage: &mut dyn Storage,
+    env: &Env,
+    address: &Addr,
+    fee_per_share: &[Decimal256; 2],
+) -> StdResult<()> {
+    let fees = fees_earned(storage, address, fee_per_share)?;
+    FEES_ACCRUED.save(storage, address, &fees)?;
+    LAST_FEE_PER_SHARE.save(storage, address, fee_per_share)?;
+    if !FEES_TRACKED_SINCE.has(storage, address) {
+        FEES_TRACKED_SINCE.save(storage, address, &env.block.time.seconds())?;
+    }
+
+    Ok(())
+}
+
+/// Returns the fees accrued by `address` plus the fees earned by its current balance since the
+/// last checkpoint: `balance * (fee_per_share - last_fee_per_share)`.
+fn fees_earned(
+    storage: &dyn Storage,
+    address: &Addr,
+    fee_per_share: &[Decimal256; 2],
+) -> StdResult<[Decimal256; 2]> {
+    let balance = Decimal256::from_ratio(
+        BALANCES.may_load(storage, address)?.unwrap_or_default(),
+        1u8,
+    );
+    let last_fee_per_share = LAST_FEE_PER_SHARE
+        .may_load(storage, address)?
+        .unwrap_or(*fee_per_share);
+    let mut fees = FEES_ACCRUED.may_load(storage, address)?.unwrap_or_default();
+
+    for (i, fee) in fees.iter_mut().enumerate() {
+        let earned = balance.checked_mul(fee_per_share[i].checked_sub(last_fee_per_share[i])?)?;
+        *fee = fee.checked_add(earned)?;
+    }
+
+    Ok(fees)
+}
+
+/// Queries the pair for the LP fees per LP token.
+fn query_fee_per_share(querier: QuerierWrapper, pair: &Addr) -> StdResult<FeePerShareResponse> {
+    querier.query_wasm_smart(pair, &PairQueryMsg::FeePerShare {})
 }
 
 /// Exposes queries available in the contract.
+///
+/// ## Queries
+/// * **FeesQueryMsg::FeesEarned { address }** Returns the LP fees earned by the address using a
+/// [`FeesEarnedResponse`] object.
+///
+/// All the CW20 queries are supported as well.
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    cw20_query(deps, env, msg)
+pub fn query(deps: Deps, env: Env, msg: TokenQueryMsg) -> StdResult<Binary> {
+    match msg {
+        TokenQueryMsg::Cw20(msg) => cw20_query(deps, env, msg),
+        TokenQueryMsg::Fees(FeesQueryMsg::FeesEarned { address }) => {
+            to_json_binary(&query_fees_earned(deps, env, address)?)
+        }
+    }
+}
+
+/// Returns the LP fees earned by `address` in each pool asset.
+pub fn query_fees_earned(deps: Deps, env: Env, address: String) -> StdResult<FeesEarnedResponse> {
+    let pair = FEE_SOURCE
+        .may_load(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("LP fees are not tracked for this token"))?;
+    let address = deps.api.addr_validate(&address)?;
+
+    let fee_per_share = query_fee_per_share(deps.querier, &pair)?;
+    let fees = fees_earned(
+        deps.storage,
+        &address,
+        &fee_per_share.fee_per_share_cumulative,
+    )?;
+
+    Ok(FeesEarnedResponse {
+        fees: fee_per_share
+            .asset_infos
+            .into_iter()
+            .zip(fees)
+            .map(|(asset_info, fee)| {
+                Ok(asset_info.with_balance(Uint128::try_from(fee.to_uint_floor())?))
+            })
+            .collect::<StdResult<_>>()?,
+        since: FEES_TRACKED_SINCE
+            .may_load(deps.storage, &address)?
+            .unwrap_or_else(|| env.block.time.seconds()),
+    })
 }
 
 /// Manages contract migration.
@@ -199,6 +460,238 @@ mod tests {
     use super::*;
     use astroport::token::InstantiateMarketingInfo;
 
+    mod fees_earned {
+        use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+        use cosmwasm_std::{from_json, ContractResult, OwnedDeps, SystemResult, WasmQuery};
+
+        use astroport::asset::{native_asset_info, AssetInfo};
+
+        use super::*;
+
+        fn asset_infos() -> Vec<AssetInfo> {
+            vec![
+                native_asset_info("uusd".to_string()),
+                native_asset_info("uluna".to_string()),
+            ]
+        }
+
+        fn set_fee_per_share(
+            deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+            fee_per_share: [Decimal256; 2],
+        ) {
+            deps.querier.update_wasm(move |query| match query {
+                WasmQuery::Smart { contract_addr, .. } if contract_addr == "pair" => {
+                    SystemResult::Ok(ContractResult::Ok(
+                        to_json_binary(&FeePerShareResponse {
+                            asset_infos: asset_infos(),
+                            fee_per_share_cumulative: fee_per_share,
+                        })
+                        .unwrap(),
+                    ))
+                }
+                _ => SystemResult::Ok(ContractResult::Err("Query is not supported".to_string())),
+            });
+        }
+
+        fn instantiate_msg(minter: &str) -> InstantiateMsg {
+            InstantiateMsg {
+                name: "LP Token".to_string(),
+                symbol: "uLP".to_string(),
+                decimals: 6,
+                initial_balances: vec![],
+                mint: Some(cw20::MinterResponse {
+                    minter: minter.to_string(),
+                    cap: None,
+                }),
+                marketing: None,
+            }
+        }
+
+        fn query_fees(deps: Deps, address: &str) -> StdResult<FeesEarnedResponse> {
+            query(
+                deps,
+                mock_env(),
+                from_json(
+                    to_json_binary(&FeesQueryMsg::FeesEarned {
+                        address: address.to_string(),
+                    })
+                    .unwrap(),
+                )
+                .unwrap(),
+            )
+            .map(|res| from_json(&res).unwrap())
+        }
+
+        #[test]
+        fn tracks_fees_across_transfers() {
+            let mut deps = mock_dependencies();
+            set_fee_per_share(&mut deps, [Decimal256::zero(), Decimal256::zero()]);
+
+            let env = mock_env();
+            instantiate(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("pair", &[]),
+                instantiate_msg("pair"),
+            )
+            .unwrap();
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("pair", &[]),
+                TokenExecuteMsg::Fees(FeesExecuteMsg::EnableFeeTracking {}),
+            )
+            .unwrap();
+
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("pair", &[]),
+                TokenExecuteMsg::Cw20(ExecuteMsg::Mint {
+                    recipient: "alice".to_string(),
+                    amount: Uint128::new(100),
+                }),
+            )
+            .unwrap();
+
+            set_fee_per_share(
+                &mut deps,
+                [Decimal256::percent(50), Decimal256::percent(10)],
+            );
+            let res = execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("alice", &[]),
+                TokenExecuteMsg::Cw20(ExecuteMsg::Transfer {
+                    recipient: "bob".to_string(),
+                    amount: Uint128::new(40),
+                }),
+            )
+            .unwrap();
+
+            // The pair is notified with a gas limit and its failure doesn't revert the transfer
+            assert_eq!(
+                res.messages,
+                vec![SubMsg::reply_on_error(
+                    wasm_execute(
+                        "pair",
+                        &PairExecuteMsg::UpdateLpHolders {
+                            addresses: vec!["alice".to_string(), "bob".to_string()],
+                            transfer: Some(LpTransfer {
+                                from: "alice".to_string(),
+                                to: "bob".to_string(),
+                                amount: Uint128::new(40),
+                            }),
+                        },
+                        vec![],
+                    )
+                    .unwrap(),
+                    UPDATE_LP_HOLDERS_REPLY_ID,
+                )
+                .with_gas_limit(UPDATE_LP_HOLDERS_GAS_LIMIT)]
+            );
+            let res = reply(
+                deps.as_mut(),
+                env.clone(),
+                Reply {
+                    id: UPDATE_LP_HOLDERS_REPLY_ID,
+                    result: SubMsgResult::Err("out of gas".to_string()),
+                },
+            )
+            .unwrap();
+            assert_eq!(
+                res.attributes,
+                vec![
+                    attr("action", "update_lp_holders_failed"),
+                    attr("error", "out of gas"),
+                ]
+            );
+
+            set_fee_per_share(
+                &mut deps,
+                [Decimal256::percent(100), Decimal256::percent(10)],
+            );
+
+            let res = query_fees(deps.as_ref(), "alice").unwrap();
+            assert_eq!(
+                res.fees,
+                vec![
+                    asset_infos()[0].with_balance(80u128),
+                    asset_infos()[1].with_balance(10u128),
+                ]
+            );
+            assert_eq!(res.since, env.block.time.seconds());
+
+            let res = query_fees(deps.as_ref(), "bob").unwrap();
+            assert_eq!(
+                res.fees,
+                vec![
+                    asset_infos()[0].with_balance(20u128),
+                    asset_infos()[1].with_balance(0u128),
+                ]
+            );
+
+            // The balance queries of the CW20 standard are still supported
+            let res: cw20::BalanceResponse = from_json(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    from_json(
+                        to_json_binary(&QueryMsg::Balance {
+                            address: "bob".to_string(),
+                        })
+                        .unwrap(),
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            assert_eq!(res.balance, Uint128::new(40));
+        }
+
+        #[test]
+        fn not_tracked_until_enabled() {
+            let mut deps = mock_dependencies();
+
+            instantiate(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("pair", &[]),
+                instantiate_msg("pair"),
+            )
+            .unwrap();
+
+            // The minter isn't queried while the fees aren't tracked
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("pair", &[]),
+                TokenExecuteMsg::Cw20(ExecuteMsg::Mint {
+                    recipient: "alice".to_string(),
+                    amount: Uint128::new(100),
+                }),
+            )
+            .unwrap();
+            let err = query_fees(deps.as_ref(), "alice").unwrap_err();
+            assert_eq!(
+                err,
+                StdError::generic_err("LP fees are not tracked for this token")
+            );
+
+            // Only the minter can enable the fee tracking
+            set_fee_per_share(&mut deps, [Decimal256::zero(), Decimal256::zero()]);
+            let err = execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("alice", &[]),
+                TokenExecuteMsg::Fees(FeesExecuteMsg::EnableFeeTracking {}),
+            )
+            .unwrap_err();
+            assert_eq!(err, ContractError::Unauthorized {});
+        }
+    }
+
     mod marketing {
         use cw20::DownloadLogoResponse;
         use cw20_base::contract::{query_download_logo, query_marketing_info};
@@ -0,0 +1,11 @@
+use cosmwasm_std::{Addr, Decimal256};
+use cw_storage_plus::{Item, Map};
+
+/// Stores the pair which reports the LP fees per LP token. Fees are only tracked if it is set
+pub const FEE_SOURCE: Item<Addr> = Item::new("fee_source");
+/// Stores the pair fees per LP token at the last balance change of each holder
+pub const LAST_FEE_PER_SHARE: Map<&Addr, [Decimal256; 2]> = Map::new("last_fee_per_share");
+/// Stores the fees each holder earned until their last balance change
+pub const FEES_ACCRUED: Map<&Addr, [Decimal256; 2]> = Map::new("fees_accrued");
+/// Stores the timestamp of the first balance change of each holder
+pub const FEES_TRACKED_SINCE: Map<&Addr, u64> = Map::new("fees_tracked_since");
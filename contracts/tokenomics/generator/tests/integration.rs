@@ -1142,6 +1142,7 @@ fn disabling_pool() {
             is_generator_disabled: true,
             permissioned: false,
         },
+        proposal_id: None,
     };
 
     app.execute_contract(owner.clone(), factory_instance.clone(), &msg, &[])
@@ -1172,6 +1173,7 @@ fn disabling_pool() {
             is_generator_disabled: false,
             permissioned: false,
         },
+        proposal_id: None,
     };
 
     app.execute_contract(owner.clone(), factory_instance.clone(), &msg, &[])
@@ -3434,6 +3436,7 @@ fn deactivate_pools_by_pair_types() {
             is_generator_disabled: true,
             permissioned: false,
         },
+        proposal_id: None,
     };
 
     app.execute_contract(owner.clone(), factory_instance.clone(), &msg, &[])
@@ -3538,6 +3541,7 @@ fn deactivate_pools_by_pair_types() {
             is_generator_disabled: false,
             permissioned: false,
         },
+        proposal_id: None,
     };
 
     app.execute_contract(owner.clone(), factory_instance.clone(), &msg, &[])
@@ -4291,6 +4295,7 @@ fn create_pair(
             pair_type: pair_type.unwrap_or_else(|| PairType::Xyk {}),
             asset_infos: assets.clone(),
             init_params: init_param,
+            proposal_id: None,
         },
         &[],
     )
@@ -257,6 +257,7 @@ impl ControllerHelper {
                 pair_type: PairType::Xyk {},
                 asset_infos: asset_infos.clone(),
                 init_params: None,
+                proposal_id: None,
             },
             &[],
         )?;
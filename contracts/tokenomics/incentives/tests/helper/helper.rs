@@ -530,6 +530,7 @@ impl Helper {
                     is_generator_disabled: true,
                     ..pair_config
                 },
+                proposal_id: None,
             },
             &[],
         )
@@ -923,6 +924,7 @@ impl Helper {
                     pair_type: PairType::Xyk {},
                     asset_infos: asset_infos.clone(),
                     init_params: None,
+                    proposal_id: None,
                 },
                 &[],
             )
@@ -960,6 +962,7 @@ impl Helper {
                         })
                         .unwrap(),
                     ),
+                    proposal_id: None,
                 },
                 &[],
             )
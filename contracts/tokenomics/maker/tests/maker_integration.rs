@@ -386,6 +386,7 @@ fn create_pair(
                 pair_type: pair_type.unwrap_or(PairType::Xyk {}),
                 asset_infos: asset_infos.clone(),
                 init_params: None,
+                proposal_id: None,
             },
             &[],
         )
@@ -2013,6 +2014,7 @@ fn test_collect_cooldown() {
                 pair_type: PairType::Xyk {},
                 asset_infos: asset_infos.to_vec(),
                 init_params: None,
+                proposal_id: None,
             },
             &[],
         )
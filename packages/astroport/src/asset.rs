@@ -1,4 +1,5 @@
 use std::fmt;
+use std::str::FromStr;
 
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{
@@ -14,6 +15,7 @@ use itertools::Itertools;
 
 use crate::cosmwasm_ext::DecimalToInteger;
 use crate::factory::PairType;
+use crate::native_coin_wrapper::{Cw20HookMsg as WrapperHookMsg, ExecuteMsg as WrapperExecuteMsg};
 use crate::pair::QueryMsg as PairQueryMsg;
 use crate::querier::{
     query_balance, query_token_balance, query_token_precision, query_token_symbol,
@@ -27,6 +29,54 @@ pub const ULUNA_DENOM: &str = "uluna";
 pub const MINIMUM_LIQUIDITY_AMOUNT: Uint128 = Uint128::new(1_000);
 /// Maximum denom length
 pub const DENOM_MAX_LENGTH: usize = 128;
+/// The prefix of native assets in the human-readable [`AssetInfo`] format
+pub const NATIVE_ASSET_PREFIX: &str = "native:";
+/// The prefix of CW20 tokens in the human-readable [`AssetInfo`] format
+pub const CW20_ASSET_PREFIX: &str = "cw20:";
+
+/// Describes an asset rejected by [`Asset::validate_non_zero`]
+#[derive(Debug, PartialEq)]
+pub struct ZeroAmountError {
+    /// The asset whose amount is zero
+    pub asset: AssetInfo,
+}
+
+impl fmt::Display for ZeroAmountError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Asset amount must not be zero: {}", self.asset)
+    }
+}
+
+impl From<ZeroAmountError> for StdError {
+    fn from(err: ZeroAmountError) -> Self {
+        StdError::generic_err(err.to_string())
+    }
+}
+
+/// Describes an asset rejected by [`Asset::validate_max`]
+#[derive(Debug, PartialEq)]
+pub struct AmountTooLargeError {
+    /// The asset whose amount exceeds the maximum
+    pub asset: Asset,
+    /// The maximum allowed amount
+    pub max: Uint128,
+}
+
+impl fmt::Display for AmountTooLargeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Asset amount {} exceeds the maximum allowed {}",
+            self.asset, self.max
+        )
+    }
+}
+
+impl From<AmountTooLargeError> for StdError {
+    fn from(err: AmountTooLargeError) -> Self {
+        StdError::generic_err(err.to_string())
+    }
+}
 
 /// This enum describes a Terra asset (native or CW20).
 #[cw_serde]
@@ -267,6 +317,31 @@ impl Asset {
         }
     }
 
+    /// Checks that the asset amount is not zero.
+    /// Zero transfers only waste gas and pollute event logs thus they must be rejected early.
+    pub fn validate_non_zero(&self) -> Result<(), ZeroAmountError> {
+        if self.amount.is_zero() {
+            Err(ZeroAmountError {
+                asset: self.info.clone(),
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Checks that the asset amount does not exceed **max**.
+    /// Used as an overflow guard before amounts are added up or scaled.
+    pub fn validate_max(&self, max: Uint128) -> Result<(), AmountTooLargeError> {
+        if self.amount > max {
+            Err(AmountTooLargeError {
+                asset: self.clone(),
+                max,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
     pub fn to_decimal_asset(&self, precision: impl Into<u32>) -> StdResult<DecimalAsset> {
         Ok(DecimalAsset {
             info: self.info.clone(),
@@ -405,6 +480,32 @@ impl fmt::Display for AssetInfo {
     }
 }
 
+/// Parses human-readable asset identifiers like `native:uusd` or `cw20:terra1...`.
+/// The parsed asset is not validated, use [`AssetInfo::check`] for that.
+impl FromStr for AssetInfo {
+    type Err = StdError;
+
+    fn from_str(s: &str) -> StdResult<Self> {
+        let asset_info = if let Some(denom) = s.strip_prefix(NATIVE_ASSET_PREFIX) {
+            AssetInfo::native(denom)
+        } else if let Some(contract_addr) = s.strip_prefix(CW20_ASSET_PREFIX) {
+            AssetInfo::cw20_unchecked(contract_addr)
+        } else {
+            return Err(StdError::generic_err(format!(
+                "Invalid asset {s}: expected {NATIVE_ASSET_PREFIX}<denom> or {CW20_ASSET_PREFIX}<address>"
+            )));
+        };
+
+        if asset_info.as_bytes().is_empty() {
+            return Err(StdError::generic_err(format!(
+                "Invalid asset {s}: empty identifier"
+            )));
+        }
+
+        Ok(asset_info)
+    }
+}
+
 impl From<Denom> for AssetInfo {
     fn from(denom: Denom) -> Self {
         match denom {
@@ -480,6 +581,15 @@ impl AssetInfo {
         }
     }
 
+    /// Returns the human-readable representation of the asset, e.g. `native:uusd` or `cw20:terra1...`.
+    /// It can be parsed back with [`AssetInfo::from_str`].
+    pub fn to_prefixed_string(&self) -> String {
+        match self {
+            AssetInfo::NativeToken { denom } => format!("{NATIVE_ASSET_PREFIX}{denom}"),
+            AssetInfo::Token { contract_addr } => format!("{CW20_ASSET_PREFIX}{contract_addr}"),
+        }
+    }
+
     /// Returns true if the caller is a native token. Otherwise returns false.
     pub fn is_native_token(&self) -> bool {
         match self {
@@ -550,11 +660,27 @@ impl AssetInfo {
         }
     }
 
+    /// Returns the two assets in the canonical pair order. Assets are ordered by their denom or
+    /// contract address, which is the lexicographic order of [`AssetInfo::to_string`], so
+    /// `[a, b]` and `[b, a]` always describe the same pair.
+    pub fn sort_pair<'a>(a: &'a AssetInfo, b: &'a AssetInfo) -> [&'a AssetInfo; 2] {
+        if a.as_bytes() <= b.as_bytes() {
+            [a, b]
+        } else {
+            [b, a]
+        }
+    }
+
     /// Checks that the tokens' denom or contract addr is valid.
     pub fn check(&self, api: &dyn Api) -> StdResult<()> {
         match self {
             AssetInfo::Token { contract_addr } => {
-                api.addr_validate(contract_addr.as_str())?;
+                api.addr_validate(contract_addr.as_str()).map_err(|err| {
+                    StdError::generic_err(format!(
+                        "Invalid asset {}: {err}",
+                        self.to_prefixed_string()
+                    ))
+                })?;
             }
             AssetInfo::NativeToken { denom } => {
                 validate_native_denom(denom)?;
@@ -563,6 +689,47 @@ impl AssetInfo {
 
         Ok(())
     }
+
+    /// Returns a message which wraps `amount` of the native coin into its CW20 representation.
+    /// The wrapper contract must implement the [`crate::native_coin_wrapper`] interface.
+    ///
+    /// * **wrapper_contract** contract that issues the CW20 token for the native coin.
+    pub fn wrap_msg(&self, amount: Uint128, wrapper_contract: Addr) -> StdResult<CosmosMsg> {
+        match self {
+            AssetInfo::NativeToken { denom } => Ok(wasm_execute(
+                wrapper_contract,
+                &WrapperExecuteMsg::Wrap {},
+                coins(amount.u128(), denom),
+            )?
+            .into()),
+            AssetInfo::Token { .. } => Err(StdError::generic_err(format!(
+                "Only native coins can be wrapped, got {self}"
+            ))),
+        }
+    }
+
+    /// Returns a message which sends `amount` of the CW20 token to the wrapper contract in order
+    /// to get the native coin back. The wrapper contract must implement the
+    /// [`crate::native_coin_wrapper`] interface.
+    ///
+    /// * **wrapper_contract** contract that issued the CW20 token for the native coin.
+    pub fn unwrap_msg(&self, amount: Uint128, wrapper_contract: Addr) -> StdResult<CosmosMsg> {
+        match self {
+            AssetInfo::Token { contract_addr } => Ok(wasm_execute(
+                contract_addr,
+                &Cw20ExecuteMsg::Send {
+                    contract: wrapper_contract.to_string(),
+                    amount,
+                    msg: to_json_binary(&WrapperHookMsg::Unwrap {})?,
+                },
+                vec![],
+            )?
+            .into()),
+            AssetInfo::NativeToken { .. } => Err(StdError::generic_err(format!(
+                "Only CW20 tokens can be unwrapped, got {self}"
+            ))),
+        }
+    }
 }
 
 /// Taken from https://github.com/mars-protocol/red-bank/blob/5bb0fe145588352b281803f7b870103bc6832621/packages/utils/src/helpers.rs#L68
@@ -607,6 +774,20 @@ pub struct PairInfo {
     pub liquidity_token: Addr,
     /// The pool type (xyk, stableswap etc) available in [`PairType`]
     pub pair_type: PairType,
+    /// The address which created the pair. It is tracked by the factory contract thus pairs
+    /// themselves always return None. It is optional rather than an [`Addr`] because pairs
+    /// instantiated before the tracking was added have no recorded creator and their stored
+    /// [`PairInfo`] lacks the field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub creator: Option<Addr>,
+    /// The block height at which the pair was created. It is tracked by the factory contract thus
+    /// pairs themselves always return 0. Pairs created before the tracking was added return 0 as well.
+    #[serde(default)]
+    pub creation_height: u64,
+    /// The block time (in seconds) at which the pair was created. It is tracked the same way as
+    /// `creation_height`.
+    #[serde(default)]
+    pub creation_time: u64,
 }
 
 impl PairInfo {
@@ -876,8 +1057,9 @@ impl Decimal256Ext for Decimal256 {
 
 #[cfg(test)]
 mod tests {
-    use cosmwasm_std::testing::mock_info;
+    use cosmwasm_std::testing::{mock_dependencies, mock_info};
     use cosmwasm_std::{coin, coins};
+    use proptest::prelude::*;
     use test_case::test_case;
 
     use super::*;
@@ -1046,6 +1228,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_asset_amount_validation() {
+        let asset = native_asset_info("uusd".to_string()).with_balance(0u8);
+        let err = asset.validate_non_zero().unwrap_err();
+        assert_eq!(
+            err,
+            ZeroAmountError {
+                asset: native_asset_info("uusd".to_string())
+            }
+        );
+        assert_eq!(err.to_string(), "Asset amount must not be zero: uusd");
+
+        let asset = native_asset_info("uusd".to_string()).with_balance(1000u16);
+        asset.validate_non_zero().unwrap();
+        asset.validate_max(1000u16.into()).unwrap();
+        asset.validate_max(Uint128::MAX).unwrap();
+
+        let err = asset.validate_max(999u16.into()).unwrap_err();
+        assert_eq!(
+            err,
+            AmountTooLargeError {
+                asset: asset.clone(),
+                max: 999u16.into()
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "Asset amount 1000uusd exceeds the maximum allowed 999"
+        );
+        assert_eq!(
+            StdError::from(err),
+            StdError::generic_err("Asset amount 1000uusd exceeds the maximum allowed 999")
+        );
+    }
+
     #[test]
     fn native_denom_validation() {
         let err = validate_native_denom("ab").unwrap_err();
@@ -1248,4 +1465,44 @@ mod tests {
             asset_cw20.try_into().unwrap()
         )
     }
+
+    #[test_case("native:uusd", AssetInfo::native("uusd") ; "native")]
+    #[test_case("native:ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2", AssetInfo::native("ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2") ; "ibc")]
+    #[test_case("cw20:terra1abc", AssetInfo::cw20_unchecked("terra1abc") ; "cw20")]
+    fn test_asset_info_from_str(input: &str, expected: AssetInfo) {
+        let asset_info = AssetInfo::from_str(input).unwrap();
+        assert_eq!(asset_info, expected);
+        assert_eq!(asset_info.to_prefixed_string(), input);
+    }
+
+    #[test_case("uusd" ; "no prefix")]
+    #[test_case("native:" ; "empty denom")]
+    #[test_case("cw20:" ; "empty address")]
+    #[test_case("token:terra1abc" ; "unknown prefix")]
+    fn test_asset_info_from_str_invalid(input: &str) {
+        AssetInfo::from_str(input).unwrap_err();
+    }
+
+    #[test]
+    fn test_check_reports_asset() {
+        let deps = mock_dependencies();
+        let err = AssetInfo::cw20_unchecked("a").check(&deps.api).unwrap_err();
+        assert!(err.to_string().contains("Invalid asset cw20:a"));
+    }
+
+    proptest! {
+        #[test]
+        fn asset_info_string_round_trip(
+            denom in "[a-zA-Z][a-zA-Z0-9/:._-]{2,127}",
+            contract_addr in "[a-z0-9]{1,64}",
+        ) {
+            let native = AssetInfo::from_str(&format!("native:{denom}")).unwrap();
+            prop_assert_eq!(&native, &AssetInfo::native(denom.clone()));
+            prop_assert_eq!(AssetInfo::from_str(&native.to_prefixed_string()).unwrap(), native.clone());
+            prop_assert!(native.check(&mock_dependencies().api).is_ok());
+
+            let cw20 = AssetInfo::cw20_unchecked(contract_addr);
+            prop_assert_eq!(AssetInfo::from_str(&cw20.to_prefixed_string()).unwrap(), cw20);
+        }
+    }
 }
@@ -1,7 +1,8 @@
 use crate::asset::{AssetInfo, PairInfo};
+use crate::pair::CustomHookConfig;
 
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Binary};
+use cosmwasm_std::{Addr, Binary, Decimal, Uint128};
 use std::fmt::{Display, Formatter, Result};
 
 const MAX_TOTAL_FEE_BPS: u16 = 10_000;
@@ -22,6 +23,9 @@ pub struct Config {
     pub whitelist_code_id: u64,
     /// The address of the contract that contains the coins with their precision
     pub coin_registry_address: Addr,
+    /// CW3 multisig contract which must approve sensitive operations. It must implement [`MultisigQueryMsg`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multisig_module: Option<Addr>,
 }
 
 /// This enum describes available pair types.
@@ -54,6 +58,41 @@ impl Display for PairType {
     }
 }
 
+/// This enum describes administrative roles which can be granted by the factory owner.
+/// The owner implicitly holds every role.
+#[cw_serde]
+pub enum Role {
+    /// Allowed to update pair type fee configurations
+    FeeAdmin,
+    /// Allowed to pause and unpause individual pairs
+    PairAdmin,
+    /// Allowed to pause and unpause all pairs simultaneously
+    EmergencyAdmin,
+    /// Allowed to report the USD value locked in pairs
+    TvlOracle,
+    /// Allowed to update the pair volume rankings
+    VolumeKeeper,
+}
+
+impl Role {
+    /// Returns the role name which is also used as its storage key
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::FeeAdmin => "fee_admin",
+            Role::PairAdmin => "pair_admin",
+            Role::EmergencyAdmin => "emergency_admin",
+            Role::TvlOracle => "tvl_oracle",
+            Role::VolumeKeeper => "volume_keeper",
+        }
+    }
+}
+
+impl Display for Role {
+    fn fmt(&self, fmt: &mut Formatter) -> Result {
+        fmt.write_str(self.as_str())
+    }
+}
+
 /// This structure stores a pair type's configuration.
 #[cw_serde]
 pub struct PairConfig {
@@ -127,6 +166,8 @@ pub enum ExecuteMsg {
     UpdatePairConfig {
         /// New [`PairConfig`] settings for a pair type
         config: PairConfig,
+        /// The passed multisig proposal approving this operation (required if the multisig module is set)
+        proposal_id: Option<u64>,
     },
     /// CreatePair instantiates a new pair contract.
     CreatePair {
@@ -136,6 +177,31 @@ pub enum ExecuteMsg {
         asset_infos: Vec<AssetInfo>,
         /// Optional binary serialised parameters for custom pool types
         init_params: Option<Binary>,
+        /// The passed multisig proposal approving the creation of a permissioned pair
+        /// (required if the multisig module is set)
+        proposal_id: Option<u64>,
+    },
+    /// GetOrCreatePair returns the existing pair for the assets or instantiates a new one.
+    /// Deployment scripts can run it repeatedly without failing on already created pairs.
+    GetOrCreatePair {
+        /// The pair type (exposed in [`PairType`]) used if the pair has to be created
+        pair_type: PairType,
+        /// The assets to find or create the pool for
+        asset_infos: Vec<AssetInfo>,
+        /// Optional binary serialised parameters for custom pool types
+        init_params: Option<Binary>,
+        /// The passed multisig proposal approving the creation of a permissioned pair
+        /// (required if the multisig module is set)
+        proposal_id: Option<u64>,
+    },
+    /// BatchCreatePairs instantiates multiple pair contracts in a single transaction.
+    /// The batch is limited to [`MAX_BATCH_SIZE`] pairs.
+    BatchCreatePairs {
+        /// The pairs to create
+        pairs: Vec<CreatePairParams>,
+        /// The passed multisig proposal approving the creation of permissioned pairs
+        /// (required if the multisig module is set)
+        proposal_id: Option<u64>,
     },
     /// Deregister removes a previously created pair.
     Deregister {
@@ -154,6 +220,186 @@ pub enum ExecuteMsg {
     DropOwnershipProposal {},
     /// Used to claim contract ownership.
     ClaimOwnership {},
+    /// RevokeCreatorPairs blacklists a creator and removes their pairs from the creator index.
+    /// Pairs remain fully functional, only their discoverability via [`QueryMsg::PairsByCreator`] is affected.
+    RevokeCreatorPairs {
+        /// The creator to blacklist
+        creator: String,
+    },
+    /// GrantRole grants an administrative role to an address. Only the owner can execute this.
+    GrantRole {
+        /// The address which receives the role
+        address: String,
+        /// The role to grant
+        role: Role,
+    },
+    /// RevokeRole revokes an administrative role from an address. Only the owner can execute this.
+    RevokeRole {
+        /// The address from which the role is revoked
+        address: String,
+        /// The role to revoke
+        role: Role,
+    },
+    /// PausePair disables swaps and liquidity provision in a specific pair.
+    /// The pause flag is pushed to the pair with [`crate::pair_xyk::ExecuteMsg::SetPaused`].
+    /// Requires the [`Role::PairAdmin`] role.
+    PausePair {
+        /// The assets of the pair to pause
+        asset_infos: Vec<AssetInfo>,
+        /// The passed multisig proposal approving this operation (required if the multisig module is set)
+        proposal_id: Option<u64>,
+    },
+    /// UnpausePair enables a previously paused pair.
+    /// Requires the [`Role::PairAdmin`] role.
+    UnpausePair {
+        /// The assets of the pair to unpause
+        asset_infos: Vec<AssetInfo>,
+        /// The passed multisig proposal approving this operation (required if the multisig module is set)
+        proposal_id: Option<u64>,
+    },
+    /// PauseAllPairs disables swaps and liquidity provision in all pairs at once.
+    /// The pause flag is pushed to every registered pair.
+    /// Requires the [`Role::EmergencyAdmin`] role.
+    PauseAllPairs {
+        /// The passed multisig proposal approving this operation (required if the multisig module is set)
+        proposal_id: Option<u64>,
+    },
+    /// UnpauseAllPairs lifts the global pause set by [`ExecuteMsg::PauseAllPairs`].
+    /// Requires the [`Role::EmergencyAdmin`] role.
+    UnpauseAllPairs {
+        /// The passed multisig proposal approving this operation (required if the multisig module is set)
+        proposal_id: Option<u64>,
+    },
+    /// SetMultisigModule sets or removes the CW3 multisig contract which must approve sensitive operations.
+    /// Once the multisig module is set, only the multisig contract itself can change it.
+    SetMultisigModule {
+        /// The multisig contract address. [`None`] removes the multisig module
+        multisig_module: Option<String>,
+    },
+    /// ApproveCodeId allows pairs to be instantiated from the specified code ID
+    ApproveCodeId {
+        /// The pair contract code ID
+        code_id: u64,
+    },
+    /// RevokeCodeId prevents new pairs from being instantiated from the specified code ID
+    RevokeCodeId {
+        /// The pair contract code ID
+        code_id: u64,
+    },
+    /// UpdateTvlContribution reports the USD value locked in a pair
+    UpdateTvlContribution {
+        /// The pair contract address
+        pair: String,
+        /// The USD value locked in the pair
+        tvl_usd: Uint128,
+    },
+    /// SetPairAdmin designates an address which can manage a specific pair
+    SetPairAdmin {
+        /// The pair contract address
+        pair: String,
+        /// The new pair admin
+        admin: String,
+    },
+    /// UpdatePairParams forwards a config update to a pair on behalf of its admin. Pairs only
+    /// apply the pool tuning params forwarded by the factory
+    UpdatePairParams {
+        /// The pair contract address
+        pair: String,
+        /// The parameters passed to the pair `UpdateConfig` message
+        params: Binary,
+    },
+    /// SetPairCustomHook sets the contract notified after actions in a XYK pair.
+    /// [`None`] removes the hook
+    SetPairCustomHook {
+        /// The pair contract address
+        pair: String,
+        /// The hook configuration passed to [`crate::pair_xyk::ExecuteMsg::SetCustomHook`]
+        config: Option<CustomHookConfig>,
+    },
+    /// UpdatePairVolumeRankings replaces the pair volume rankings returned by [`QueryMsg::PairsByVolume`]
+    UpdatePairVolumeRankings {
+        /// The pair contract addresses with their 24h trading volume
+        rankings: Vec<(String, Uint128)>,
+    },
+    /// SetGasBenchmark sets the gas used by a single swap in a pair of the specified type.
+    /// The router uses it to estimate the gas of swap routes
+    SetGasBenchmark {
+        /// The pair type
+        pair_type: PairType,
+        /// The gas used by a single swap
+        gas_per_swap: u64,
+    },
+    /// UpdateFeeTierGovernance sets the parameters of the fee tier proposals voted by xASTRO holders
+    UpdateFeeTierGovernance {
+        /// The xASTRO token contract whose historical balances are used as the voting power
+        xastro_token: String,
+        /// The minimum xASTRO balance required to create a proposal
+        min_stake: Uint128,
+        /// The voting period in seconds
+        voting_period: u64,
+        /// The minimum share of the xASTRO supply which must vote for a proposal to pass
+        quorum: Decimal,
+    },
+    /// ProposeFeeTierChange creates a proposal to change the total fee of an XYK pair
+    ProposeFeeTierChange {
+        /// The pair contract address
+        pair: String,
+        /// The new total fee (in bps) charged on a swap in the pair
+        new_total_fee_bps: u16,
+    },
+    /// VoteOnFeeTierProposal votes on a fee tier proposal with the xASTRO balance the voter held
+    /// when the proposal was created
+    VoteOnFeeTierProposal {
+        /// The proposal identifier
+        proposal_id: u64,
+        /// Whether the voter supports the proposal
+        vote: bool,
+    },
+    /// ExecuteFeeTierProposal applies the fee of a proposal which reached the quorum and the
+    /// majority once the voting period is over
+    ExecuteFeeTierProposal {
+        /// The proposal identifier
+        proposal_id: u64,
+    },
+    /// BlacklistPair delists a pair in an emergency. The router refuses to swap through blacklisted pairs.
+    /// Requires the [`Role::EmergencyAdmin`] role.
+    BlacklistPair {
+        /// The pair contract address
+        pair: String,
+        /// Why the pair is blacklisted
+        reason: String,
+        /// The passed multisig proposal approving this operation (required if the multisig module is set)
+        proposal_id: Option<u64>,
+    },
+}
+
+/// This structure describes the query interface the multisig module set in [`Config`] must implement.
+/// The factory queries it to check whether a proposal approving a sensitive operation has passed.
+/// Each proposal can be used only once.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum MultisigQueryMsg {
+    /// Returns whether the proposal has passed
+    #[returns(bool)]
+    IsProposalPassed { proposal_id: u64 },
+}
+
+/// The maximum number of pairs which can be created with [`ExecuteMsg::BatchCreatePairs`]
+pub const MAX_BATCH_SIZE: usize = 20;
+
+/// The maximum number of LP tokens which can be looked up with [`QueryMsg::PairInfoBatch`]
+pub const MAX_PAIR_INFO_BATCH_SIZE: usize = 30;
+
+/// This structure describes the parameters of a single pair in [`ExecuteMsg::BatchCreatePairs`].
+/// It mirrors the [`ExecuteMsg::CreatePair`] fields.
+#[cw_serde]
+pub struct CreatePairParams {
+    /// The pair type (exposed in [`PairType`])
+    pub pair_type: PairType,
+    /// The assets to create the pool for
+    pub asset_infos: Vec<AssetInfo>,
+    /// Optional binary serialised parameters for custom pool types
+    pub init_params: Option<Binary>,
 }
 
 /// This structure describes the available query messages for the factory contract.
@@ -169,6 +415,22 @@ pub enum QueryMsg {
         /// The assets for which we return a pair
         asset_infos: Vec<AssetInfo>,
     },
+    /// PairAddress returns the address of the pair registered for the specified assets without
+    /// querying the pair contract
+    #[returns(PairAddressResponse)]
+    PairAddress {
+        /// The assets for which we return a pair
+        asset_infos: Vec<AssetInfo>,
+    },
+    /// PairAtHeight returns information about the pair which was registered for the specified
+    /// assets at the end of the block at `height`. Returns `None` if no pair was registered
+    #[returns(Option<PairInfo>)]
+    PairAtHeight {
+        /// The assets for which we return a pair
+        asset_infos: Vec<AssetInfo>,
+        /// The block height
+        height: u64,
+    },
     /// Pairs returns an array of pairs and their information according to the specified parameters in `start_after` and `limit` variables.
     #[returns(PairsResponse)]
     Pairs {
@@ -186,6 +448,141 @@ pub enum QueryMsg {
     /// Returns a vector that contains blacklisted pair types
     #[returns(Vec<PairType>)]
     BlacklistedPairTypes {},
+    /// PairsByCreator returns an array of pairs created by the specified address
+    #[returns(PairsResponse)]
+    PairsByCreator {
+        /// The address which created the pairs
+        creator: String,
+        /// The pair identifier to start reading from
+        start_after: Option<u64>,
+        /// The number of pairs to read and return
+        limit: Option<u32>,
+    },
+    /// Returns whether the address holds the specified role
+    #[returns(bool)]
+    HasRole {
+        /// The address to check
+        address: String,
+        /// The role to check
+        role: Role,
+    },
+    /// Returns whether the pair is paused either individually or globally
+    #[returns(bool)]
+    IsPairPaused {
+        /// The pair contract address
+        pair_addr: String,
+    },
+    /// BatchStatus returns which pairs from a [`ExecuteMsg::BatchCreatePairs`] batch were created and which failed
+    #[returns(BatchStatusResponse)]
+    BatchStatus {
+        /// The batch identifier returned in the `batch_id` attribute
+        batch_id: u64,
+    },
+    /// Returns whether new pairs can be instantiated from the specified code ID
+    #[returns(bool)]
+    IsCodeIdApproved {
+        /// The pair contract code ID
+        code_id: u64,
+    },
+    /// Returns the number of pairs registered in the factory
+    #[returns(u64)]
+    PairCount {},
+    /// Returns the sum of the USD values reported for all pairs
+    #[returns(Uint128)]
+    TotalTvl {},
+    /// Returns the admin of the specified pair if it is set
+    #[returns(Option<Addr>)]
+    PairAdmin {
+        /// The pair contract address
+        pair: String,
+    },
+    /// AllPairTypes returns the registered pair type configurations ordered by the pair type name
+    #[returns(Vec<PairConfig>)]
+    AllPairTypes {
+        /// The pair type to start reading from
+        start_after: Option<PairType>,
+        /// The number of pair types to read and return
+        limit: Option<u32>,
+    },
+    /// Returns whether new pairs of the specified type can be created
+    #[returns(bool)]
+    IsPairTypeEnabled {
+        /// The pair type to check
+        pair_type: PairType,
+    },
+    /// PairsByVolume returns the ranked pairs ordered by their 24h trading volume, highest first
+    #[returns(Vec<PairVolumeResponse>)]
+    PairsByVolume {
+        /// The rank to start reading from
+        start_after: Option<u64>,
+        /// The number of pairs to read and return
+        limit: u32,
+    },
+    /// Returns the gas used by a single swap in a pair of the specified type if it is set
+    #[returns(Option<u64>)]
+    GasBenchmark {
+        /// The pair type
+        pair_type: PairType,
+    },
+    /// PairFeeInfo returns the fee parameters for a specific pair taking into account the fee
+    /// tier set by an executed fee tier proposal. The response is returned using a [`FeeInfoResponse`] structure
+    #[returns(FeeInfoResponse)]
+    PairFeeInfo {
+        /// The pair contract address
+        pair_addr: String,
+        /// The pair type
+        pair_type: PairType,
+    },
+    /// Returns the fee tier governance parameters if they are set
+    #[returns(Option<FeeTierGovernance>)]
+    FeeTierGovernance {},
+    /// Returns the fee tier proposal with the specified identifier
+    #[returns(FeeTierProposal)]
+    FeeTierProposal {
+        /// The proposal identifier
+        proposal_id: u64,
+    },
+    /// Returns information about the pair which minted the specified LP token
+    #[returns(PairInfo)]
+    PairByLpToken {
+        /// The LP token contract address
+        lp_token: String,
+    },
+    /// Returns information about the pairs which minted the specified LP tokens in the same
+    /// order. Unknown LP tokens return `None`. Up to [`MAX_PAIR_INFO_BATCH_SIZE`] LP tokens can
+    /// be looked up at once
+    #[returns(Vec<Option<PairInfo>>)]
+    PairInfoBatch {
+        /// The LP token contract addresses
+        lp_tokens: Vec<String>,
+    },
+    /// Returns the cw2 contract name and version
+    #[returns(cw2::ContractVersion)]
+    ContractInfo {},
+    /// Returns whether the pair was blacklisted with [`ExecuteMsg::BlacklistPair`]
+    #[returns(bool)]
+    IsPairBlacklisted {
+        /// The pair contract address
+        pair: String,
+    },
+    /// BlacklistedPairs returns the blacklisted pairs ordered by their address
+    #[returns(Vec<BlacklistedPairResponse>)]
+    BlacklistedPairs {
+        /// The pair address to start reading from
+        start_after: Option<String>,
+        /// The number of pairs to read and return
+        limit: u32,
+    },
+    /// NextHopCandidates returns the assets which can be received for `from_asset` in one swap
+    /// along with the pairs to swap in, ordered by the pair reserve of the received asset from
+    /// the deepest one. Blacklisted pairs are skipped
+    #[returns(NextHopResponse)]
+    NextHopCandidates {
+        /// The asset to swap
+        from_asset: AssetInfo,
+        /// The number of candidates to return
+        limit: Option<u32>,
+    },
 }
 
 /// A custom struct for each query response that returns general contract settings/configs.
@@ -205,6 +602,9 @@ pub struct ConfigResponse {
     pub whitelist_code_id: u64,
     /// The address of the contract that contains the coins and their accuracy
     pub coin_registry_address: Addr,
+    /// CW3 multisig contract which must approve sensitive operations
+    #[serde(default)]
+    pub multisig_module: Option<Addr>,
 }
 
 /// A custom struct for each query response that returns an array of objects of type [`PairInfo`].
@@ -214,6 +614,69 @@ pub struct PairsResponse {
     pub pairs: Vec<PairInfo>,
 }
 
+/// A custom struct for the address of a pair returned by [`QueryMsg::PairAddress`]
+#[cw_serde]
+pub struct PairAddressResponse {
+    /// The pair contract address
+    pub address: String,
+}
+
+/// This structure describes the swap candidates returned by [`QueryMsg::NextHopCandidates`].
+#[cw_serde]
+pub struct NextHopResponse {
+    /// The candidates ordered by their reserve depth from the deepest one
+    pub candidates: Vec<HopCandidate>,
+}
+
+/// This structure describes an asset which can be received in a single swap.
+#[cw_serde]
+pub struct HopCandidate {
+    /// The pair contract address
+    pub pair: Addr,
+    /// The asset received from the pair
+    pub to_asset: AssetInfo,
+    /// The pair reserve of the received asset
+    pub reserve_depth: Uint128,
+}
+
+/// This structure describes a pair in the volume rankings returned by [`QueryMsg::PairsByVolume`].
+#[cw_serde]
+pub struct PairVolumeResponse {
+    /// The rank of the pair starting from 1 for the highest volume
+    pub rank: u64,
+    /// The pair contract address
+    pub pair: Addr,
+    /// The 24h trading volume reported for the pair
+    pub volume: Uint128,
+}
+
+/// This structure describes why and when a pair was blacklisted with [`ExecuteMsg::BlacklistPair`].
+#[cw_serde]
+pub struct BlacklistReason {
+    /// The reason passed to [`ExecuteMsg::BlacklistPair`]
+    pub reason: String,
+    /// The block time (in seconds) at which the pair was blacklisted
+    pub blacklisted_at: u64,
+}
+
+/// This structure describes a pair returned by [`QueryMsg::BlacklistedPairs`].
+#[cw_serde]
+pub struct BlacklistedPairResponse {
+    /// The pair contract address
+    pub pair: Addr,
+    /// Why and when the pair was blacklisted
+    pub reason: BlacklistReason,
+}
+
+/// A custom struct for each query response that returns the result of a pair batch creation.
+#[cw_serde]
+pub struct BatchStatusResponse {
+    /// Addresses of the pairs which were created successfully
+    pub succeeded: Vec<Addr>,
+    /// Assets of the pairs which failed to be created
+    pub failed: Vec<Vec<AssetInfo>>,
+}
+
 /// A custom struct for each query response that returns an object of type [`FeeInfoResponse`].
 #[cw_serde]
 pub struct FeeInfoResponse {
@@ -225,6 +688,42 @@ pub struct FeeInfoResponse {
     pub maker_fee_bps: u16,
 }
 
+/// This structure describes the parameters of the fee tier proposals.
+#[cw_serde]
+pub struct FeeTierGovernance {
+    /// The xASTRO token contract whose historical balances are used as the voting power
+    pub xastro_token: Addr,
+    /// The minimum xASTRO balance required to create a proposal
+    pub min_stake: Uint128,
+    /// The voting period in seconds
+    pub voting_period: u64,
+    /// The minimum share of the xASTRO supply which must vote for a proposal to pass
+    pub quorum: Decimal,
+}
+
+/// This structure describes a proposal to change the total fee of a pair.
+#[cw_serde]
+pub struct FeeTierProposal {
+    /// The pair contract address
+    pub pair: Addr,
+    /// The new total fee (in bps) charged on a swap in the pair
+    pub new_total_fee_bps: u16,
+    /// The address which created the proposal
+    pub proposer: Addr,
+    /// The block at which the voting power is measured
+    pub start_block: u64,
+    /// The timestamp (in seconds) at which the voting period ends
+    pub end_time: u64,
+    /// The xASTRO supply at the start block
+    pub total_voting_power: Uint128,
+    /// The voting power in favor of the proposal
+    pub yes_votes: Uint128,
+    /// The voting power against the proposal
+    pub no_votes: Uint128,
+    /// Whether the proposal was executed
+    pub executed: bool,
+}
+
 /// This is an enum used for setting and removing a contract address.
 #[cw_serde]
 pub enum UpdateAddr {
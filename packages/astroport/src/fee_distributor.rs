@@ -0,0 +1,81 @@
+use crate::asset::AssetInfo;
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use cw4::MemberChangedHookMsg;
+
+/// The default number of seconds rewards stay claimable before they can be clawed back (180 days)
+pub const DEFAULT_CLAWBACK_WINDOW_SECS: u64 = 180 * 86400;
+
+/// This structure describes the parameters used for creating a contract.
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// The contract owner which can claw back unclaimed fees
+    pub owner: String,
+    /// The address receiving clawed back fees
+    pub treasury: String,
+    /// The number of seconds rewards stay claimable before they can be clawed back.
+    /// Defaults to [`DEFAULT_CLAWBACK_WINDOW_SECS`]
+    pub clawback_window_secs: Option<u64>,
+    /// The ASTRO token asset info
+    pub astro_token: AssetInfo,
+    /// The CW4 contract which holds the staked ASTRO balances
+    pub staked_astro: String,
+    /// The router contract address
+    pub router: String,
+    /// The maximum spread used when converting fees to ASTRO
+    pub max_spread: Option<Decimal>,
+}
+
+/// This structure describes the execute messages available in the contract.
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Swaps the contract balances of the specified assets to ASTRO via the router
+    ConvertToAstro { assets: Vec<AssetInfo> },
+    /// Distributes the undistributed ASTRO balance between the current ASTRO stakers
+    Distribute {},
+    /// Checkpoints the stakers whose staked amounts changed.
+    /// Only the CW4 contract holding the staked ASTRO balances can execute this
+    MemberChangedHook(MemberChangedHookMsg),
+    /// Sends the claimable ASTRO to the caller
+    Claim {},
+    /// Sends the unclaimed ASTRO distributed at `since_block` or earlier to the treasury.
+    /// `since_block` is a block time in seconds and must be at least `clawback_window_secs` in the past.
+    /// Stakers are processed in pages starting after `start_after`.
+    /// Only the owner can execute this
+    ClawbackUnclaimedFees {
+        since_block: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+/// This structure describes the query messages available in the contract.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Returns the contract configuration in a [`ConfigResponse`] object
+    #[returns(ConfigResponse)]
+    Config {},
+    /// Returns the amount of ASTRO the specified staker can claim
+    #[returns(Uint128)]
+    Claimable { staker: String },
+}
+
+/// This structure is used to return the fee distributor configuration.
+#[cw_serde]
+pub struct ConfigResponse {
+    /// The contract owner
+    pub owner: Addr,
+    /// The address receiving clawed back fees
+    pub treasury: Addr,
+    /// The number of seconds rewards stay claimable before they can be clawed back
+    pub clawback_window_secs: u64,
+    /// The ASTRO token asset info
+    pub astro_token: AssetInfo,
+    /// The CW4 contract which holds the staked ASTRO balances
+    pub staked_astro: Addr,
+    /// The router contract used to convert collected fees to ASTRO
+    pub router: Addr,
+    /// The maximum spread used when converting fees to ASTRO
+    pub max_spread: Decimal,
+}
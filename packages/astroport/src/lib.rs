@@ -3,12 +3,16 @@ pub mod common;
 pub mod cosmwasm_ext;
 pub mod cw20_ics20;
 pub mod factory;
+pub mod fee_distributor;
 pub mod fee_granter;
 pub mod generator;
 pub mod generator_proxy;
 #[cfg(feature = "injective")]
 pub mod injective_ext;
+pub mod lp_manager;
 pub mod maker;
+pub mod math;
+pub mod merkle_distributor;
 pub mod native_coin_registry;
 pub mod native_coin_wrapper;
 pub mod observation;
@@ -18,10 +22,15 @@ pub mod pair;
 pub mod pair_bonded;
 pub mod pair_concentrated;
 pub mod pair_concentrated_inj;
+pub mod pair_indexer;
+pub mod pair_registry;
+pub mod pair_stable;
+pub mod pair_xyk;
 pub mod pair_xyk_sale_tax;
 pub mod querier;
 pub mod restricted_vector;
 pub mod router;
+pub mod router_factory;
 pub mod shared_multisig;
 pub mod staking;
 pub mod token;
@@ -0,0 +1,51 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{to_json_binary, Addr, CosmosMsg, Env, StdResult, Uint128, WasmMsg};
+
+use crate::asset::Asset;
+
+/// This structure describes the parameters used for creating a contract.
+#[cw_serde]
+pub struct InstantiateMsg {}
+
+/// This structure describes a single withdrawal in a batch.
+#[cw_serde]
+pub struct WithdrawalRequest {
+    /// The pair to withdraw liquidity from
+    pub pair: String,
+    /// The amount of LP tokens to burn. The LP manager must have an allowance for this amount
+    pub lp_amount: Uint128,
+    /// The minimum amounts of pair assets the withdrawal must return
+    #[serde(default)]
+    pub min_assets: Vec<Asset>,
+}
+
+/// This structure describes the execute messages available in the contract.
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Withdraws liquidity from several pairs and sends all the withdrawn assets to the sender.
+    /// All the withdrawals are reverted if any of them fails
+    BatchWithdrawLiquidity { withdrawals: Vec<WithdrawalRequest> },
+    /// Callback of type [`CallbackMsg`]
+    Callback(CallbackMsg),
+}
+
+/// This structure describes the callback messages available in the contract.
+#[cw_serde]
+pub enum CallbackMsg {
+    /// Checks the assets returned by the latest withdrawal and forwards them to the receiver
+    CheckWithdrawal {
+        /// The receiver of the withdrawn assets
+        receiver: Addr,
+    },
+}
+
+impl CallbackMsg {
+    /// Converts the callback into a message executed by the contract itself.
+    pub fn to_cosmos_msg(&self, env: &Env) -> StdResult<CosmosMsg> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_json_binary(&ExecuteMsg::Callback(self.clone()))?,
+            funds: vec![],
+        }))
+    }
+}
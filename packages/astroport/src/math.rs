@@ -0,0 +1,69 @@
+use cosmwasm_std::{Decimal, Decimal256, Uint128};
+
+use crate::asset::{Asset, AssetInfo};
+
+/// Describes a deposit which deviates from the pool ratio more than the slippage tolerance allows
+#[derive(Debug, PartialEq)]
+pub struct SlippageToleranceError {
+    /// The deposited asset with the largest deviation
+    pub asset: AssetInfo,
+    /// The deviation of the deposit ratio from the reserve ratio
+    pub deviation: Decimal256,
+    /// The slippage tolerance
+    pub allowed: Decimal,
+}
+
+/// Checks that the deposits are made in the same ratio as the pool reserves.
+/// The ratio of every deposit to the first deposit is compared with the ratio of the corresponding reserves
+/// and `|deposit_ratio - reserve_ratio|` relative to the larger of the two ratios must not exceed
+/// `slippage_tolerance`. Measuring against the larger ratio makes the check independent of the asset order.
+///
+/// * **assets** the pool assets in the same order as `deposits` and `reserves`.
+///
+/// * **deposits** the deposited amounts.
+///
+/// * **reserves** the pool reserves before the deposit. Empty pools aren't checked.
+///
+/// * **slippage_tolerance** the allowed deviation.
+pub fn assert_slippage_tolerance(
+    assets: &[Asset],
+    deposits: &[Uint128],
+    reserves: &[Uint128],
+    slippage_tolerance: Decimal,
+) -> Result<(), SlippageToleranceError> {
+    if reserves.iter().any(Uint128::is_zero) {
+        return Ok(());
+    }
+
+    let mut max_deviation: Option<(usize, Decimal256)> = None;
+    for i in 1..deposits.len() {
+        // deposit_i / deposit_0 and reserve_i / reserve_0 multiplied by deposit_0 * reserve_0
+        let deposit_ratio = deposits[i].full_mul(reserves[0]);
+        let reserve_ratio = reserves[i].full_mul(deposits[0]);
+        let (larger_ratio, smaller_ratio) = if deposit_ratio > reserve_ratio {
+            (deposit_ratio, reserve_ratio)
+        } else {
+            (reserve_ratio, deposit_ratio)
+        };
+        let deviation = if larger_ratio.is_zero() {
+            Decimal256::zero()
+        } else {
+            Decimal256::from_ratio(larger_ratio - smaller_ratio, larger_ratio)
+        };
+
+        if max_deviation.map_or(true, |(_, max)| deviation > max) {
+            max_deviation = Some((i, deviation));
+        }
+    }
+
+    match max_deviation {
+        Some((i, deviation)) if deviation > Decimal256::from(slippage_tolerance) => {
+            Err(SlippageToleranceError {
+                asset: assets[i].info.clone(),
+                deviation,
+                allowed: slippage_tolerance,
+            })
+        }
+        _ => Ok(()),
+    }
+}
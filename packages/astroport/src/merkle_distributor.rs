@@ -0,0 +1,75 @@
+use crate::asset::AssetInfo;
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Uint128};
+
+/// This structure describes the parameters used for creating a contract.
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// The address allowed to set the Merkle root. It receives the unclaimed assets after expiry
+    pub owner: String,
+}
+
+/// This structure describes the execute messages available in the contract.
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Sets the Merkle root of the distribution. The contract must hold `total_amount` of `asset`
+    /// before users start claiming. Only the owner can execute this and only once
+    SetMerkleRoot {
+        /// The Merkle root built from `sha256(address || amount)` leaves
+        root: [u8; 32],
+        /// The sum of all the amounts in the tree
+        total_amount: Uint128,
+        /// The distributed asset
+        asset: AssetInfo,
+        /// The timestamp (in seconds) after which claims are no longer accepted
+        expiry: u64,
+    },
+    /// Sends `amount` of the distributed asset to the caller if the proof is valid
+    Claim {
+        /// The amount allocated to the caller in the Merkle tree
+        amount: Uint128,
+        /// The sibling hashes from the leaf up to the root
+        proof: Vec<[u8; 32]>,
+    },
+    /// Sends the unclaimed assets to the owner once the distribution is expired.
+    /// Anyone can execute this
+    Clawback {},
+}
+
+/// This structure describes the query messages available in the contract.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Returns the contract configuration in a [`ConfigResponse`] object
+    #[returns(ConfigResponse)]
+    Config {},
+    /// Returns whether the address has already claimed its allocation
+    #[returns(bool)]
+    IsClaimed { address: String },
+}
+
+/// This structure describes an airdrop distribution.
+#[cw_serde]
+pub struct Distribution {
+    /// The Merkle root built from `sha256(address || amount)` leaves
+    pub root: [u8; 32],
+    /// The sum of all the amounts in the tree
+    pub total_amount: Uint128,
+    /// The amount claimed so far
+    pub claimed_amount: Uint128,
+    /// The distributed asset
+    pub asset: AssetInfo,
+    /// The timestamp (in seconds) after which claims are no longer accepted
+    pub expiry: u64,
+    /// Whether the unclaimed assets were sent back to the owner
+    pub clawed_back: bool,
+}
+
+/// This structure is used to return the distributor configuration.
+#[cw_serde]
+pub struct ConfigResponse {
+    /// The address allowed to set the Merkle root
+    pub owner: Addr,
+    /// The distribution if the Merkle root is set
+    pub distribution: Option<Distribution>,
+}
@@ -11,6 +11,8 @@ use crate::cosmwasm_ext::AbsDiff;
 
 /// Circular buffer size which stores observations
 pub const OBSERVATIONS_SIZE: u32 = 3000;
+/// The number of intervals the period of [`query_observations_twap`] is split into
+pub const TWAP_SAMPLES: u64 = 10;
 
 /// Stores trade size observations. We use it in orderbook integration
 /// and derive prices for external contracts/users.
@@ -42,17 +44,66 @@ where
     C: CustomQuery,
 {
     let buffer = BufferManager::new(deps.storage, observations)?;
-    let target = env.block.time.seconds() - seconds_ago;
+    observation_at(
+        deps.storage,
+        &buffer,
+        env.block.time.seconds() - seconds_ago,
+    )
+}
+
+/// Returns the average of the observed prices over the last `period` seconds together with the
+/// number of seconds the average covers. The covered period is shorter than `period` if the
+/// observations don't go back that far. Returns [`None`] if no observation was committed yet.
+/// The average is computed from [`TWAP_SAMPLES`] evenly spaced samples of the observed prices.
+pub fn query_observations_twap<C>(
+    deps: Deps<C>,
+    env: &Env,
+    observations: CircularBuffer<Observation>,
+    period: u64,
+) -> StdResult<Option<(Decimal, u64)>>
+where
+    C: CustomQuery,
+{
+    let buffer = BufferManager::new(deps.storage, observations)?;
+    let oldest_ind = if buffer.exists(deps.storage, buffer.head()) {
+        buffer.head()
+    } else if buffer.head() > 0 {
+        0
+    } else {
+        return Ok(None);
+    };
+    let oldest_obs = buffer.read_single(deps.storage, oldest_ind)?.unwrap();
 
+    let now = env.block.time.seconds();
+    let period = period.min(now.saturating_sub(oldest_obs.ts));
+    if period == 0 {
+        return Ok(None);
+    }
+
+    let mut prices_sum = Decimal::zero();
+    for i in 0..=TWAP_SAMPLES {
+        let target = now - period * i / TWAP_SAMPLES;
+        prices_sum += observation_at(deps.storage, &buffer, target)?.price;
+    }
+
+    Ok(Some((prices_sum / Uint128::from(TWAP_SAMPLES + 1), period)))
+}
+
+/// Returns the price observation at the `target` timestamp.
+fn observation_at(
+    storage: &dyn Storage,
+    buffer: &BufferManager<Observation>,
+    target: u64,
+) -> StdResult<OracleObservation> {
     let mut oldest_ind = buffer.head();
     let mut newest_ind = buffer.head() + buffer.capacity() - 1;
 
-    if !buffer.exists(deps.storage, oldest_ind) {
+    if !buffer.exists(storage, oldest_ind) {
         if buffer.head() > 0 {
             oldest_ind = 0;
             newest_ind %= buffer.capacity();
         } else {
-            return match PrecommitObservation::may_load(deps.storage)? {
+            return match PrecommitObservation::may_load(storage)? {
                 // First observation after pool initialization could be captured but not committed yet
                 Some(obs) if obs.precommit_ts <= target => Ok(OracleObservation {
                     timestamp: target,
@@ -67,14 +118,14 @@ where
         }
     }
 
-    let newest_obs = buffer.read_single(deps.storage, newest_ind)?.unwrap();
+    let newest_obs = buffer.read_single(storage, newest_ind)?.unwrap();
     if target >= newest_obs.ts {
         return Ok(OracleObservation {
             timestamp: target,
             price: newest_obs.price_sma,
         });
     }
-    let oldest_obs = buffer.read_single(deps.storage, oldest_ind)?.unwrap();
+    let oldest_obs = buffer.read_single(storage, oldest_ind)?.unwrap();
     if target == oldest_obs.ts {
         return Ok(OracleObservation {
             timestamp: target,
@@ -88,7 +139,7 @@ where
         )));
     }
 
-    let (left, right) = binary_search(deps.storage, &buffer, target, oldest_ind, newest_ind)?;
+    let (left, right) = binary_search(storage, &buffer, target, oldest_ind, newest_ind)?;
 
     let price_left = left.price_sma;
     let price_right = right.price_sma;
@@ -1,20 +1,54 @@
 use crate::observation::OracleObservation;
+use cosmwasm_schema::serde::Serialize;
 use cosmwasm_schema::{cw_serde, QueryResponses};
 
 use crate::asset::{Asset, AssetInfo, PairInfo};
 
-use cosmwasm_std::{Addr, Binary, Decimal, Decimal256, Uint128, Uint64};
+use cosmwasm_std::{
+    to_json_vec, Addr, Binary, Decimal, Decimal256, StdResult, Uint128, Uint256, Uint64,
+};
 use cw20::Cw20ReceiveMsg;
+use sha2::{Digest, Sha256};
 
 /// The default swap slippage
 pub const DEFAULT_SLIPPAGE: &str = "0.005";
 /// The maximum allowed swap slippage
 pub const MAX_ALLOWED_SLIPPAGE: &str = "0.5";
+/// The maximum allowed default max spread which can be set in a pair's config, 50%
+pub const MAX_ALLOWED_SPREAD: Decimal = Decimal::raw(500_000_000_000_000_000);
 /// The maximum fee share allowed, 10%
 pub const MAX_FEE_SHARE_BPS: u16 = 1000;
+/// The number of seconds between proposing and executing an emergency withdrawal, 24 hours
+pub const EMERGENCY_WITHDRAW_TIMELOCK: u64 = 86400;
+/// The maximum number of LP holders tracked by [`QueryMsg::TopLpProviders`]
+pub const MAX_TOP_LP_HOLDERS: usize = 100;
+/// The number of price snapshots kept for [`QueryMsg::PriceHistory`]
+pub const PRICE_RING_SIZE: u32 = 256;
+/// The number of pool state snapshots kept for [`QueryMsg::Snapshot`]
+pub const MAX_POOL_SNAPSHOTS: u64 = 1000;
+/// The number of blocks of reserves history kept for [`QueryMsg::ReservesHistory`]
+pub const RESERVES_HISTORY_DEPTH: u64 = 10000;
+/// The minimum number of seconds between two price snapshots, 1 hour
+pub const SNAPSHOT_INTERVAL_SECS: u64 = 3600;
+/// The length of the rolling window aggregated by [`QueryMsg::PairFees24h`], 24 hours
+pub const FEE_WINDOW_SECS: u64 = 86400;
+/// The max number of swaps kept in the rolling fee window
+pub const MAX_FEE_WINDOW_SWAPS: u64 = 100_000;
+/// The max number of swap observations kept for [`QueryMsg::FeeAdjustedTwap`]
+pub const MAX_FEE_ADJUSTED_OBSERVATIONS: usize = 100;
+/// The max number of price bands in [`QueryMsg::LiquidityConcentration`]
+pub const MAX_PRICE_BANDS: usize = 10;
+/// Pools whose asset ratios all deviate less than this from the peg are reported as balanced by
+/// [`QueryMsg::PoolRatio`]
+pub const BALANCED_PEG_DEVIATION_BPS: u64 = 10;
+/// The TWAP period [`OracleQueryMsg::Price`] aims for, 1 hour. Averages over shorter periods are
+/// reported with a proportionally lower confidence
+pub const ORACLE_TWAP_PERIOD: u64 = 3600;
 
 /// Decimal precision for TWAP results
 pub const TWAP_PRECISION: u8 = 6;
+/// The exponent of the prices returned by [`QueryMsg::PriceFeed`]
+pub const PRICE_FEED_EXPO: i32 = -6;
 
 /// Min safe trading size (0.00001) to calculate a price. This value considers
 /// amount in decimal form with respective token precision.
@@ -88,6 +122,16 @@ pub enum Cw20HookMsg {
         #[serde(default)]
         assets: Vec<Asset>,
     },
+    /// Provide liquidity using the sent CW20 tokens together with `pair_asset`.
+    /// The counterpart is pulled from the sender, so it must be a CW20 token with an allowance
+    /// for the pair contract.
+    ProvideLiquidity {
+        pair_asset: Asset,
+        slippage_tolerance: Option<Decimal>,
+        #[serde(default)]
+        auto_stake: bool,
+        receiver: Option<String>,
+    },
 }
 
 /// This structure describes the query messages available in the contract.
@@ -133,6 +177,153 @@ pub enum QueryMsg {
     /// Query price from observations
     #[returns(OracleObservation)]
     Observe { seconds_ago: u64 },
+    /// Compares holding `initial_assets` with holding `current_lp_amount` LP tokens at the current
+    /// pool price and returns the result in an [`ImpermanentLossResponse`] object.
+    #[returns(ImpermanentLossResponse)]
+    ImpermanentLoss {
+        initial_assets: Vec<Asset>,
+        current_lp_amount: Uint128,
+    },
+    /// Returns the trading volume required for the LP fees to offset the impermanent loss of the
+    /// price moving from `initial_price` to `current_price` in a [`BreakevenVolumeResponse`]
+    /// object. Prices are of the first asset denominated in the second one
+    #[returns(BreakevenVolumeResponse)]
+    BreakevenVolume {
+        initial_price: Decimal,
+        current_price: Decimal,
+    },
+    /// Returns the result of a liquidity provision simulation in a [`SimulateProvideResponse`] object.
+    #[returns(SimulateProvideResponse)]
+    SimulateProvideLiquidity { assets: Vec<Asset> },
+    /// Returns the average prices since the specified snapshot in a [`TwapSinceResponse`] object
+    #[returns(TwapSinceResponse)]
+    TwapSince { snapshot_id: u64 },
+    /// Returns the LP fees accrued per LP token in a [`FeePerShareResponse`] object
+    #[returns(FeePerShareResponse)]
+    FeePerShare {},
+    /// Returns up to `limit` of the largest LP holders and their LP balances, ordered by balance
+    #[returns(Vec<(String, Uint128)>)]
+    TopLpProviders { limit: u32 },
+    /// Returns a composite health indicator of the pair in a [`PairHealthResponse`] object
+    #[returns(PairHealthResponse)]
+    PairHealth {},
+    /// Returns up to `limit` of the latest price snapshots, ordered from newest to oldest
+    #[returns(Vec<PriceSnapshot>)]
+    PriceHistory { limit: u32 },
+    /// Returns the price snapshot closest to `timestamp` or [`None`] if no snapshot was taken yet
+    #[returns(Option<PriceSnapshot>)]
+    PriceAt { timestamp: u64 },
+    /// Returns the trade which moves the pool price to `reference_price` in an
+    /// [`ArbitrageOpportunityResponse`] object. `reference_price` is the price of the second
+    /// asset denominated in the first one
+    #[returns(ArbitrageOpportunityResponse)]
+    ArbitrageOpportunity { reference_price: Decimal },
+    /// Returns how the stableswap reserves are distributed between the `(lower, upper)` price
+    /// bands in a [`LiquidityConcentrationResponse`] object. Prices are of the first asset
+    /// denominated in the second one. At most [`MAX_PRICE_BANDS`] bands can be queried
+    #[returns(LiquidityConcentrationResponse)]
+    LiquidityConcentration {
+        price_bands: Vec<(Decimal, Decimal)>,
+    },
+    /// Returns the LP fees of the swaps in the last [`FEE_WINDOW_SECS`] in a
+    /// [`PairFees24hResponse`] object
+    #[returns(PairFees24hResponse)]
+    PairFees24h {},
+    /// Returns the price of `amount` of `base` denominated in `quote` in an
+    /// [`OraclePriceResponse`] object. See [`OracleQueryMsg::Price`]
+    #[returns(OraclePriceResponse)]
+    Price {
+        base: AssetInfo,
+        quote: AssetInfo,
+        amount: Uint128,
+    },
+    /// Returns the config hash stored at the last config update. See [`config_hash`]
+    #[returns([u8; 32])]
+    ConfigHash {},
+    /// Returns whether the hash of the current config matches `expected_hash`
+    #[returns(bool)]
+    VerifyConfig { expected_hash: [u8; 32] },
+    /// Returns the current pool invariant in an [`InvariantResponse`] object
+    #[returns(InvariantResponse)]
+    Invariant {},
+    /// Returns the pool state snapshot with the specified identifier
+    #[returns(Snapshot)]
+    Snapshot { id: u64 },
+    /// Returns the latest pool state snapshot or [`None`] if no snapshot was taken yet
+    #[returns(Option<(u64, Snapshot)>)]
+    LatestSnapshot {},
+    /// Returns the pool reserves at the end of every block between `from_block` and `to_block`
+    /// (both inclusive) in which they changed. Only the latest [`RESERVES_HISTORY_DEPTH`] blocks are kept
+    #[returns(Vec<(u64, [Uint128; 2])>)]
+    ReservesHistory { from_block: u64, to_block: u64 },
+    /// Returns the share of every asset in the pool reserves in a [`PoolRatioResponse`] object
+    #[returns(PoolRatioResponse)]
+    PoolRatio {},
+    /// Returns the pool ratios in a [`PoolRatioResponse`] object or an error if any of them
+    /// deviates more than `threshold_bps` from the peg
+    #[returns(PoolRatioResponse)]
+    DepegAlerts { threshold_bps: u16 },
+    /// Returns the LP position of the address along with its fees and impermanent loss in a
+    /// [`UserLpStatsResponse`] object
+    #[returns(UserLpStatsResponse)]
+    UserLpStats { address: String },
+    /// Returns the spot price of `asset` denominated in the other pool asset in the Pyth
+    /// Network price feed format in a [`PythPriceResponse`] object
+    #[returns(PythPriceResponse)]
+    PriceFeed { asset: AssetInfo },
+    /// Returns the spot price of the other pool asset denominated in `ask_asset` in a
+    /// [`SpotPriceResponse`] object
+    #[returns(SpotPriceResponse)]
+    SpotPriceInAsset { ask_asset: AssetInfo },
+    /// Returns the volume weighted average price of the swaps in the last `window_secs` seconds
+    /// before the commission is deducted in a [`FeeAdjustedTwapResponse`] object
+    #[returns(FeeAdjustedTwapResponse)]
+    FeeAdjustedTwap { window_secs: u64 },
+    /// Returns the pool reserves with the rebasing asset valued in its base denomination in a
+    /// [`NormalizedReservesResponse`] object
+    #[returns(NormalizedReservesResponse)]
+    NormalizedReserves {},
+    /// Returns the cw2 contract name and version
+    #[returns(cw2::ContractVersion)]
+    ContractInfo {},
+}
+
+/// The standard oracle interface implemented by all the pair types. Serializes the same way as
+/// the respective [`QueryMsg`] variant, so it can be used to query any pair with a
+/// [`cosmwasm_std::WasmQuery::Smart`] query.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum OracleQueryMsg {
+    /// Returns the price of `amount` of `base` denominated in `quote` in an
+    /// [`OraclePriceResponse`] object. A zero `amount` returns the marginal price, otherwise the
+    /// price includes the price impact of selling `amount` of `base` in the pool
+    #[returns(OraclePriceResponse)]
+    Price {
+        base: AssetInfo,
+        quote: AssetInfo,
+        amount: Uint128,
+    },
+}
+
+/// The interface of the rate oracles used by [`RebasingAssetConfig`].
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum RateOracleQueryMsg {
+    /// Returns the amount of `base_denom` one unit of the rebasing token is worth
+    #[returns(Decimal)]
+    ExchangeRate { base_denom: String },
+}
+
+/// This structure describes an interest-bearing token of a pair whose exchange rate to its
+/// underlying asset increases over time.
+#[cw_serde]
+pub struct RebasingAssetConfig {
+    /// The CW20 token of the pair which bears interest
+    pub token: Addr,
+    /// The contract answering [`RateOracleQueryMsg::ExchangeRate`] for the token
+    pub rate_oracle: Addr,
+    /// The denomination of the underlying asset
+    pub base_denom: String,
 }
 
 /// This struct is used to return a query result with the total amount of LP tokens and assets in a specific pool.
@@ -144,6 +335,17 @@ pub struct PoolResponse {
     pub total_share: Uint128,
 }
 
+/// This struct is used to return the result of a liquidity provision simulation.
+#[cw_serde]
+pub struct SimulateProvideResponse {
+    /// The amount of LP tokens minted for the deposit
+    pub lp_amount: Uint128,
+    /// The fee charged for an imbalanced deposit (if any)
+    pub fee: Option<Vec<Asset>>,
+    /// The share of the deposit value lost because of an imbalanced deposit, in bps
+    pub price_impact_bps: u64,
+}
+
 /// This struct is used to return a query result with the general contract configuration.
 #[cw_serde]
 pub struct ConfigResponse {
@@ -166,6 +368,98 @@ pub struct FeeShareConfig {
     pub recipient: Addr,
 }
 
+/// The pair actions a custom hook is notified about
+#[cw_serde]
+pub enum HookType {
+    AfterSwap,
+    AfterProvide,
+    AfterWithdraw,
+    All,
+}
+
+impl HookType {
+    /// Returns true if the hook should be notified about the `action`
+    pub fn matches(&self, action: &HookType) -> bool {
+        self == &HookType::All || self == action
+    }
+}
+
+/// Holds the configuration of a contract notified after pair actions
+#[cw_serde]
+pub struct CustomHookConfig {
+    /// The contract executed with a [`CustomHookMsg`]
+    pub contract: Addr,
+    /// The gas limit of the hook execution. A hook which fails or runs out of gas doesn't
+    /// revert the pair action
+    pub gas_limit: u64,
+    /// The pair actions the contract is notified about
+    pub hook_type: HookType,
+}
+
+/// This structure describes the messages sent to a custom hook contract.
+#[cw_serde]
+pub enum CustomHookMsg {
+    /// Sent after a swap in the pair
+    AfterSwap {
+        sender: Addr,
+        receiver: Addr,
+        offer_asset: Asset,
+        return_asset: Asset,
+    },
+    /// Sent after liquidity is provided to the pair
+    AfterProvide {
+        sender: Addr,
+        receiver: Addr,
+        assets: Vec<Asset>,
+        share: Uint128,
+    },
+    /// Sent after liquidity is withdrawn from the pair
+    AfterWithdraw {
+        sender: Addr,
+        refund_assets: Vec<Asset>,
+        withdrawn_share: Uint128,
+    },
+}
+
+impl CustomHookMsg {
+    /// Returns the [`HookType`] of the message
+    pub fn hook_type(&self) -> HookType {
+        match self {
+            CustomHookMsg::AfterSwap { .. } => HookType::AfterSwap,
+            CustomHookMsg::AfterProvide { .. } => HookType::AfterProvide,
+            CustomHookMsg::AfterWithdraw { .. } => HookType::AfterWithdraw,
+        }
+    }
+}
+
+/// This structure describes the price alerts emitted by swaps.
+#[cw_serde]
+pub struct PriceAlertConfig {
+    /// The price of the second asset in the first one the current price is compared against.
+    /// It is set to the new price after every alert
+    pub base_price: Decimal,
+    /// The price deviation from the base price (in bps) after which an alert is emitted
+    pub alert_threshold_bps: u16,
+    /// The contract executed with a [`PriceAlertMsg`] on every alert
+    pub alert_recipient: Option<Addr>,
+}
+
+/// This structure describes the message sent to [`PriceAlertConfig::alert_recipient`].
+#[cw_serde]
+pub enum PriceAlertMsg {
+    /// Sent after a swap which moved the price more than the alert threshold
+    Alert {
+        /// The pair contract address
+        pair: Addr,
+        /// The deviation of the new price from the old price in bps
+        deviation_bps: u64,
+        /// The base price before the swap
+        old_price: Decimal,
+        /// The pool price after the swap
+        new_price: Decimal,
+    },
+}
+
 /// This structure holds the parameters that are returned from a swap simulation response
 #[cw_serde]
 pub struct SimulationResponse {
@@ -199,6 +493,309 @@ pub struct CumulativePricesResponse {
     pub cumulative_prices: Vec<(AssetInfo, AssetInfo, Uint128)>,
 }
 
+/// This structure stores the price accumulators at the moment a TWAP snapshot is saved.
+#[cw_serde]
+pub struct TwapSnapshot {
+    /// The cumulative price of the first asset denominated in the second one
+    pub price0_cumulative: Uint128,
+    /// The cumulative price of the second asset denominated in the first one
+    pub price1_cumulative: Uint128,
+    /// The timestamp (in seconds) of the snapshot
+    pub timestamp: u64,
+}
+
+/// This structure stores the pool price after a swap.
+#[cw_serde]
+pub struct PriceSnapshot {
+    /// The price of the first asset denominated in the second one
+    pub price: Decimal,
+    /// The timestamp (in seconds) of the snapshot
+    pub timestamp: u64,
+    /// The amount of the second asset swapped since the previous snapshot, including the
+    /// swap which triggered this snapshot
+    pub volume: Uint128,
+}
+
+/// This structure stores the pool state at the moment
+/// [`crate::pair_xyk::ExecuteMsg::TakeSnapshot`] is executed.
+#[cw_serde]
+pub struct Snapshot {
+    /// The pool reserves
+    pub reserves: Vec<Asset>,
+    /// The total amount of LP tokens issued
+    pub total_lp: Uint128,
+    /// The pool invariant
+    pub invariant: Uint256,
+    /// The price of the first asset denominated in the second one
+    pub price: Decimal,
+    /// The timestamp (in seconds) of the snapshot
+    pub timestamp: u64,
+    /// The block height of the snapshot
+    pub block: u64,
+}
+
+/// This enum describes the direction of a swap in a pair.
+#[cw_serde]
+pub enum TradeDirection {
+    /// Offer the first pool asset to receive the second one
+    ZeroForOne,
+    /// Offer the second pool asset to receive the first one
+    OneForZero,
+}
+
+/// This structure is used to return an arbitrage opportunity query response.
+#[cw_serde]
+pub struct ArbitrageOpportunityResponse {
+    /// The deviation of the pool price from the reference price in bps.
+    /// Positive if the pool values the second asset higher than the reference price
+    pub deviation_bps: i64,
+    /// The offer which moves the pool price to the reference price
+    pub optimal_offer: Asset,
+    /// The value of the swap return at the reference price minus the offer, denominated in the
+    /// offer asset. Zero if the fees exceed the price difference
+    pub expected_profit: Asset,
+    /// The direction of the swap
+    pub direction: TradeDirection,
+}
+
+/// This structure is used to return a rolling 24 hour fee query response.
+#[cw_serde]
+pub struct PairFees24hResponse {
+    /// The LP fees charged in every pool asset
+    pub fees: Vec<Asset>,
+    /// The timestamp from which the fees are aggregated
+    pub period_start: u64,
+    /// The number of swaps within the period
+    pub swap_count: u64,
+}
+
+/// This structure is used to return the fee adjusted average price of the recent swaps.
+#[cw_serde]
+pub struct FeeAdjustedTwapResponse {
+    /// The volume weighted average price of the first asset denominated in the second one at which
+    /// the swaps were executed before the commission was deducted
+    pub price: Decimal,
+    /// The volume weighted average commission relative to the amount returned before the commission
+    pub fee_rate_avg: Decimal,
+    /// The number of swaps within the window
+    pub observations: u32,
+}
+
+/// This structure is used to return the pool reserves valued in base units.
+#[cw_serde]
+pub struct NormalizedReservesResponse {
+    /// The pool assets with the rebasing asset amount converted to its base denomination
+    pub reserves: Vec<Asset>,
+    /// The base units one unit of the rebasing asset is worth. One if the pair has no rebasing asset
+    pub rebase_rate: Decimal,
+    /// The base denomination of the rebasing asset
+    pub base_denom: Option<String>,
+}
+
+/// This structure is used to return the LP position stats of an address.
+#[cw_serde]
+pub struct UserLpStatsResponse {
+    /// The current LP token balance
+    pub lp_balance: Uint128,
+    /// The pool assets backing the LP balance
+    pub underlying_assets: Vec<Asset>,
+    /// The value of the underlying assets denominated in the first pool asset at the pool price
+    pub position_value_in_asset0: Uint128,
+    /// The LP fees earned in every pool asset since the address first held LP tokens
+    pub fees_earned: Vec<Asset>,
+    /// The impermanent loss since the first liquidity provision of the address, in bps of the
+    /// holding value. Zero if the address never provided liquidity to the pair
+    pub impermanent_loss_bps: i64,
+    /// The timestamp of the first liquidity provision. Zero if the address never provided liquidity
+    pub first_provide_time: u64,
+    /// The timestamp of the last LP balance change
+    pub last_activity_time: u64,
+}
+
+/// This structure is used to return a pool price in the Pyth Network price feed format.
+/// The price is `price * 10^expo`.
+#[cw_serde]
+pub struct PythPriceResponse {
+    /// The price scaled by `10^-expo`
+    pub price: i64,
+    /// The confidence interval of the price scaled by `10^-expo`
+    pub conf: u64,
+    /// The price exponent, always [`PRICE_FEED_EXPO`]
+    pub expo: i32,
+    /// The timestamp of the last pool update
+    pub publish_time: u64,
+}
+
+/// This structure is used to return the spot price of a pool asset in the other one. The prices
+/// are ratios of the reserves in the smallest units of the assets (e.g. uluna), so they aren't
+/// adjusted for the asset decimals.
+#[cw_serde]
+pub struct SpotPriceResponse {
+    /// The price of one unit of the offer asset in the ask asset: `reserve_ask / reserve_offer`
+    pub price: Decimal,
+    /// The price of one unit of the ask asset in the offer asset: `reserve_offer / reserve_ask`
+    pub price_inverted: Decimal,
+}
+
+/// This structure describes the liquidity within a price band.
+#[cw_serde]
+pub struct BandInfo {
+    /// The `(lower, upper)` prices of the band
+    pub price_band: (Decimal, Decimal),
+    /// The share of the pool reserves traded while the price moves across the band
+    pub liquidity_fraction: Decimal,
+    /// The amount of the first asset traded while the price moves across the band
+    pub depth_in_base: Uint128,
+}
+
+/// This structure is used to return a liquidity concentration query response.
+#[cw_serde]
+pub struct LiquidityConcentrationResponse {
+    /// The liquidity of every queried price band in the query order
+    pub bands: Vec<BandInfo>,
+}
+
+/// This structure is used to return a pool ratio query response.
+#[cw_serde]
+pub struct PoolRatioResponse {
+    /// The share of every asset in the sum of the reserves, in the pool asset order
+    pub ratios: Vec<Decimal>,
+    /// The deviation of the ratio furthest from the `1 / n_assets` peg, in bps. Positive if the
+    /// pool holds more of that asset than at the peg
+    pub peg_deviation_bps: i64,
+    /// Whether all the ratios deviate less than [`BALANCED_PEG_DEVIATION_BPS`] from the peg
+    pub is_balanced: bool,
+}
+
+/// This structure is used to return a TWAP since snapshot query response.
+#[cw_serde]
+pub struct TwapSinceResponse {
+    /// The snapshot the average prices are computed from
+    pub snapshot: TwapSnapshot,
+    /// The vector contains the average price for each pair of assets in the pool
+    pub average_prices: Vec<(AssetInfo, AssetInfo, Decimal)>,
+}
+
+/// This structure is used to return the current pool invariant.
+#[cw_serde]
+pub struct InvariantResponse {
+    /// The invariant value computed from the current pool reserves
+    pub value: Uint256,
+    /// The invariant formula, e.g. `xy=k` or `StableSwap D`
+    pub formula: String,
+}
+
+/// This structure is used to return the result of an [`OracleQueryMsg::Price`] query.
+#[cw_serde]
+pub struct OraclePriceResponse {
+    /// The price of the base asset denominated in the quote asset
+    pub price: Decimal,
+    /// The confidence in the price between 0 and 1. It grows with the length of the TWAP period
+    /// up to [`ORACLE_TWAP_PERIOD`] and shrinks with the deviation of the spot price from the TWAP
+    pub confidence: Decimal,
+    /// The timestamp (in seconds) of the last pool state update the price is based on
+    pub last_updated: u64,
+}
+
+impl OraclePriceResponse {
+    /// Combines the spot price with the TWAP of the pool. If the TWAP is available the price is
+    /// the TWAP adjusted by the price impact of the trade, otherwise it is the spot price and the
+    /// confidence is zero.
+    ///
+    /// * **marginal_price** the current marginal price of the base asset.
+    ///
+    /// * **execution_price** the price of selling the requested amount of the base asset.
+    ///
+    /// * **twap** the average marginal price of the base asset and the period it was computed for.
+    ///
+    /// * **last_updated** the timestamp of the last pool state update.
+    pub fn new(
+        marginal_price: Decimal,
+        execution_price: Decimal,
+        twap: Option<(Decimal, u64)>,
+        last_updated: u64,
+    ) -> Self {
+        let (price, confidence) = match twap {
+            Some((average_price, period)) if !marginal_price.is_zero() => {
+                let price_impact =
+                    Decimal::from_ratio(execution_price.atomics(), marginal_price.atomics());
+                let deviation = Decimal::from_ratio(
+                    marginal_price.abs_diff(average_price).atomics(),
+                    marginal_price.max(average_price).atomics(),
+                );
+                let period_weight =
+                    Decimal::from_ratio(period.min(ORACLE_TWAP_PERIOD), ORACLE_TWAP_PERIOD);
+
+                (
+                    average_price * price_impact,
+                    period_weight * (Decimal::one() - deviation),
+                )
+            }
+            _ => (execution_price, Decimal::zero()),
+        };
+
+        Self {
+            price,
+            confidence,
+            last_updated,
+        }
+    }
+}
+
+/// This structure is used to return the LP fees accrued per LP token since the pair creation.
+#[cw_serde]
+pub struct FeePerShareResponse {
+    /// The assets in the pool
+    pub asset_infos: Vec<AssetInfo>,
+    /// The cumulative LP fees per LP token for each asset in the same order as `asset_infos`
+    pub fee_per_share_cumulative: [Decimal256; 2],
+}
+
+/// This structure is used to return the health indicators of a pair.
+#[cw_serde]
+pub struct PairHealthResponse {
+    /// The ratio between the reserves of the first and the second asset
+    pub reserve_ratio: Decimal,
+    /// The relative deviation of the pool price from the TWAP oracle price.
+    /// [`None`] if no oracle is configured or the oracle can't provide a price
+    pub price_deviation_from_oracle: Option<Decimal>,
+    /// The average spread of the swaps executed during the last 7 days.
+    /// [`None`] if there were no swaps
+    pub spread_7d_avg: Option<Decimal>,
+    /// Whether the pair is paused in the factory
+    pub circuit_breaker_active: bool,
+    /// Whether the pair is paused permanently after an emergency withdrawal
+    pub paused: bool,
+    /// The health score from 0 (unhealthy) to 100 (healthy) computed from the other fields
+    pub health_score: u8,
+}
+
+/// This structure is used to return an impermanent loss query response.
+/// All values are denominated in the second asset of the pool.
+#[cw_serde]
+pub struct ImpermanentLossResponse {
+    /// The current value of the initially deposited assets if they had simply been held
+    pub holding_value: Uint128,
+    /// The current value of the assets backing the LP position
+    pub lp_value: Uint128,
+    /// The value lost by providing liquidity relative to holding, as a share of the holding value
+    pub il_percent: Decimal,
+    /// The current pool price divided by the price implied by the initial assets
+    pub price_ratio: Decimal,
+}
+
+/// This structure is used to return a breakeven volume query response.
+#[cw_serde]
+pub struct BreakevenVolumeResponse {
+    /// The trading volume denominated in the second asset of the pool at which the LP fees
+    /// offset the impermanent loss
+    pub required_volume: Uint128,
+    /// The share of every swap which goes to the LPs
+    pub current_fee_rate: Decimal,
+    /// The impermanent loss as a share of the pool value
+    pub il_percentage: Decimal,
+}
+
 /// This structure describes a migration message.
 /// We currently take no arguments for migrations.
 #[cw_serde]
@@ -211,6 +808,14 @@ pub struct XYKPoolParams {
     /// They will not be tracked if the parameter is ignored.
     /// It can not be disabled later once enabled.
     pub track_asset_balances: Option<bool>,
+    /// The max spread applied to swaps which don't specify one.
+    /// It can not exceed [`MAX_ALLOWED_SPREAD`].
+    pub max_spread: Option<Decimal>,
+    /// The minimum number of seconds between providing and withdrawing liquidity for an address.
+    pub lp_cooldown_secs: Option<u64>,
+    /// Whether swaps and liquidity changes record the price, reserves, spread and fee histories
+    /// or not. They are recorded if the parameter is ignored.
+    pub track_swap_history: Option<bool>,
 }
 
 /// This structure stores a XYK pool's configuration.
@@ -220,6 +825,12 @@ pub struct XYKPoolConfig {
     pub track_asset_balances: bool,
     // The config for swap fee sharing
     pub fee_share: Option<FeeShareConfig>,
+    /// The max spread applied to swaps which don't specify one
+    pub max_spread: Option<Decimal>,
+    /// The minimum number of seconds between providing and withdrawing liquidity for an address
+    pub lp_cooldown_secs: Option<u64>,
+    /// Whether the price, reserves, spread and fee histories are recorded or not
+    pub track_swap_history: bool,
 }
 
 /// This enum stores the option available to enable asset balances tracking over blocks.
@@ -235,6 +846,47 @@ pub enum XYKPoolUpdateParams {
         fee_share_address: String,
     },
     DisableFeeShare,
+    /// Sets the max spread applied to swaps which don't specify one.
+    /// It can not exceed [`MAX_ALLOWED_SPREAD`].
+    UpdateMaxSpread {
+        max_spread: Option<Decimal>,
+    },
+    /// Sets the minimum number of seconds between providing and withdrawing liquidity for an address.
+    /// [`None`] disables the cooldown.
+    UpdateLpCooldown {
+        lp_cooldown_secs: Option<u64>,
+    },
+    /// Sets the TWAP oracle used to compute the price deviation in [`QueryMsg::PairHealth`].
+    /// [`None`] removes the oracle.
+    UpdateOracle {
+        oracle: Option<String>,
+    },
+    /// Sets the oracle valuing the offer assets in USD for the swap limits set with
+    /// [`crate::pair_xyk::ExecuteMsg::UpdateSwapLimits`]. It must answer
+    /// [`crate::oracle::QueryMsg::Consult`] with the USD value of the amount as the first entry.
+    /// [`None`] removes the oracle.
+    UpdateUsdOracle {
+        oracle: Option<String>,
+    },
+    /// Enables or disables the compliance mode. While it is enabled and any sender is blocked with
+    /// [`crate::pair_xyk::ExecuteMsg::BlockSender`], only the operations which check the sender
+    /// against the block list, the configuration updates and
+    /// [`crate::pair_xyk::ExecuteMsg::UpdateLpHolders`] are allowed.
+    UpdateComplianceMode {
+        compliance_mode: bool,
+    },
+    /// Sets the interest-bearing asset whose reserve [`QueryMsg::NormalizedReserves`] converts to
+    /// its base denomination. `None` removes it
+    UpdateRebasingAsset {
+        config: Option<RebasingAssetConfig>,
+    },
+    /// Enables or disables recording the histories returned by [`QueryMsg::PriceHistory`],
+    /// [`QueryMsg::ReservesHistory`], [`QueryMsg::PairFees24h`], [`QueryMsg::FeeAdjustedTwap`]
+    /// and the spread average of [`QueryMsg::PairHealth`]. The histories aren't cleared when
+    /// disabled, so they have a gap until it is enabled again.
+    UpdateSwapHistoryTracking {
+        track_swap_history: bool,
+    },
 }
 
 /// This structure holds stableswap pool parameters.
@@ -271,6 +923,18 @@ pub enum StablePoolUpdateParams {
         fee_share_address: String,
     },
     DisableFeeShare,
+    /// Sets the delay in seconds between proposing a config change with
+    /// [`crate::pair_stable::ExecuteMsg::ProposeConfigChange`] and executing it. While the delay
+    /// is not zero, the config can't be updated directly except for stopping an amp change.
+    UpdateTimelockDelay {
+        min_delay: u64,
+    },
+}
+
+/// Returns the `sha256` hash of the JSON serialized `config`. Struct fields are serialized in
+/// the declaration order, so the hash doesn't depend on the CosmWasm version.
+pub fn config_hash<T: Serialize>(config: &T) -> StdResult<[u8; 32]> {
+    Ok(Sha256::digest(to_json_vec(config)?).into())
 }
 
 #[cfg(test)]
@@ -336,4 +1000,77 @@ mod tests {
         let variant: Cw20HookMsg = from_json(br#"{"withdraw_liquidity": {} }"#).unwrap();
         assert_eq!(variant, Cw20HookMsg::WithdrawLiquidity { assets: vec![] });
     }
+
+    #[test]
+    fn oracle_query_compatibility() {
+        let base = native_asset_info("uusd".to_string());
+        let quote = native_asset_info("uluna".to_string());
+        let ser_msg = to_json_binary(&OracleQueryMsg::Price {
+            base: base.clone(),
+            quote: quote.clone(),
+            amount: Uint128::new(100),
+        })
+        .unwrap();
+
+        let msg: QueryMsg = from_json(&ser_msg).unwrap();
+        assert_eq!(
+            msg,
+            QueryMsg::Price {
+                base,
+                quote,
+                amount: Uint128::new(100),
+            }
+        );
+    }
+
+    #[test]
+    fn oracle_price_confidence() {
+        let two = Decimal::from_ratio(2u8, 1u8);
+
+        // Without a TWAP the spot price is returned with zero confidence
+        let resp = OraclePriceResponse::new(two, Decimal::from_ratio(18u8, 10u8), None, 5);
+        assert_eq!(resp.price, Decimal::from_ratio(18u8, 10u8));
+        assert_eq!(resp.confidence, Decimal::zero());
+        assert_eq!(resp.last_updated, 5);
+
+        // The TWAP is adjusted by the 10% price impact. The spot price deviates from the TWAP
+        // by 20% and the TWAP covers half of the target period
+        let resp = OraclePriceResponse::new(
+            two,
+            Decimal::from_ratio(18u8, 10u8),
+            Some((Decimal::from_ratio(25u8, 10u8), ORACLE_TWAP_PERIOD / 2)),
+            5,
+        );
+        assert_eq!(resp.price, Decimal::from_ratio(225u8, 100u8));
+        assert_eq!(resp.confidence, Decimal::from_ratio(4u8, 10u8));
+
+        let resp = OraclePriceResponse::new(two, two, Some((two, 2 * ORACLE_TWAP_PERIOD)), 5);
+        assert_eq!(resp.price, two);
+        assert_eq!(resp.confidence, Decimal::one());
+    }
+
+    #[test]
+    fn config_hash_is_deterministic() {
+        let params = StablePoolParams {
+            amp: 100,
+            owner: None,
+        };
+
+        // sha256 of {"amp":100,"owner":null}
+        assert_eq!(
+            config_hash(&params).unwrap(),
+            [
+                140, 168, 141, 184, 121, 1, 72, 1, 106, 110, 198, 207, 68, 212, 203, 123, 79, 57,
+                118, 227, 21, 65, 41, 150, 181, 28, 42, 25, 51, 122, 105, 95
+            ]
+        );
+        assert_ne!(
+            config_hash(&params).unwrap(),
+            config_hash(&StablePoolParams {
+                amp: 101,
+                owner: None,
+            })
+            .unwrap()
+        );
+    }
 }
@@ -2,8 +2,8 @@ use cosmwasm_schema::{cw_serde, QueryResponses};
 
 use crate::asset::{Asset, AssetInfo, PairInfo};
 use crate::pair::{
-    ConfigResponse, CumulativePricesResponse, PoolResponse, ReverseSimulationResponse,
-    SimulationResponse,
+    ConfigResponse, CumulativePricesResponse, OraclePriceResponse, PoolResponse,
+    ReverseSimulationResponse, SimulationResponse,
 };
 
 use cosmwasm_std::{Addr, Binary, Decimal, Uint128};
@@ -13,6 +13,12 @@ use cw20::Cw20ReceiveMsg;
 pub const DEFAULT_SLIPPAGE: &str = "0.005";
 /// The maximum allowed swap slippage
 pub const MAX_ALLOWED_SLIPPAGE: &str = "0.5";
+/// The default maximum age (in seconds) of the oracle price used for swaps
+pub const DEFAULT_MAX_ORACLE_AGE_SECS: u64 = 300;
+
+fn default_max_oracle_age_secs() -> u64 {
+    DEFAULT_MAX_ORACLE_AGE_SECS
+}
 
 /// This structure stores the main config parameters for a constant product pair contract.
 #[cw_serde]
@@ -21,8 +27,34 @@ pub struct Config {
     pub pair_info: PairInfo,
     /// The factory contract address
     pub factory_addr: Addr,
+    /// The last timestamp when a swap was made in the pair
+    pub block_time_last: u64,
+    /// The maximum age (in seconds) of the oracle price used for swaps
+    #[serde(default = "default_max_oracle_age_secs")]
+    pub max_oracle_age_secs: u64,
+}
+
+/// This structure describes the parameters used for creating a bonded pair contract.
+/// It extends the generic pair [`crate::pair::InstantiateMsg`] with bonded pair specific settings.
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// Information about assets in the pool
+    pub asset_infos: Vec<AssetInfo>,
+    /// The token contract code ID used for the tokens in the pool
+    pub token_code_id: u64,
+    /// The factory contract address
+    pub factory_addr: String,
+    /// Optional binary serialised parameters for custom pool types
+    pub init_params: Option<Binary>,
+    /// Virtual reserves added to the real reserves for price computation at pool launch.
+    /// They decay as real reserves grow and reach zero once real reserves exceed
+    /// [`VIRTUAL_RESERVES_DECAY_FACTOR`] times the virtual amounts.
+    pub initial_virtual_reserves: Option<[Uint128; 2]>,
 }
 
+/// Virtual reserves are fully decayed once real reserves exceed them by this factor
+pub const VIRTUAL_RESERVES_DECAY_FACTOR: u128 = 10;
+
 /// This structure describes the execute messages available in the contract.
 #[cw_serde]
 pub enum ExecuteMsg {
@@ -48,6 +80,9 @@ pub enum ExecuteMsg {
     },
     /// Update the pair configuration
     UpdateConfig { params: Binary },
+    /// Updates the maximum age (in seconds) of the oracle price used for swaps.
+    /// Only the factory owner can execute this
+    UpdateMaxOracleAge { max_age: u64 },
     /// Callback to process post-swap operation
     AssertAndSend {
         offer_asset: Asset,
@@ -85,4 +120,24 @@ pub enum QueryMsg {
     /// Returns information about the cumulative prices in a [`CumulativePricesResponse`] object
     #[returns(CumulativePricesResponse)]
     CumulativePrices {},
+    /// Returns the pool's virtual reserves in a [`VirtualReservesResponse`] object
+    #[returns(VirtualReservesResponse)]
+    VirtualReserves {},
+    /// Returns the oracle price of `amount` of `base` denominated in `quote`.
+    /// See [`crate::pair::OracleQueryMsg::Price`]
+    #[returns(OraclePriceResponse)]
+    Price {
+        base: AssetInfo,
+        quote: AssetInfo,
+        amount: Uint128,
+    },
+}
+
+/// This structure holds the virtual reserves of a bonded pair.
+#[cw_serde]
+pub struct VirtualReservesResponse {
+    /// The virtual reserves the pool was seeded with
+    pub initial_virtual_reserves: [Uint128; 2],
+    /// The virtual reserves currently applied after the decay
+    pub virtual_reserves: [Uint128; 2],
 }
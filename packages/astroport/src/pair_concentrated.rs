@@ -5,7 +5,7 @@ use crate::asset::PairInfo;
 use crate::asset::{Asset, AssetInfo};
 use crate::observation::OracleObservation;
 use crate::pair::{
-    ConfigResponse, CumulativePricesResponse, FeeShareConfig, PoolResponse,
+    ConfigResponse, CumulativePricesResponse, FeeShareConfig, OraclePriceResponse, PoolResponse,
     ReverseSimulationResponse, SimulationResponse,
 };
 
@@ -156,6 +156,17 @@ pub enum QueryMsg {
     /// Query price from observations
     #[returns(OracleObservation)]
     Observe { seconds_ago: u64 },
+    /// Returns the oracle price of `amount` of `base` denominated in `quote`.
+    /// See [`crate::pair::OracleQueryMsg::Price`]
+    #[returns(OraclePriceResponse)]
+    Price {
+        base: AssetInfo,
+        quote: AssetInfo,
+        amount: Uint128,
+    },
+    /// Returns the cw2 contract name and version
+    #[returns(cw2::ContractVersion)]
+    ContractInfo {},
 }
 
 #[cw_serde]
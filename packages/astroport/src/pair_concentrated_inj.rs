@@ -7,8 +7,8 @@ use crate::asset::PairInfo;
 use crate::asset::{Asset, AssetInfo};
 
 use crate::pair::{
-    ConfigResponse, CumulativePricesResponse, PoolResponse, ReverseSimulationResponse,
-    SimulationResponse,
+    ConfigResponse, CumulativePricesResponse, OraclePriceResponse, PoolResponse,
+    ReverseSimulationResponse, SimulationResponse,
 };
 use crate::pair_concentrated::{ConcentratedPoolParams, PromoteParams, UpdatePoolParams};
 
@@ -114,6 +114,14 @@ pub enum QueryMsg {
     Observe { seconds_ago: u64 },
     #[returns(OrderbookStateResponse)]
     OrderbookState {},
+    /// Returns the oracle price of `amount` of `base` denominated in `quote`.
+    /// See [`crate::pair::OracleQueryMsg::Price`]
+    #[returns(OraclePriceResponse)]
+    Price {
+        base: AssetInfo,
+        quote: AssetInfo,
+        amount: Uint128,
+    },
 }
 
 #[cw_serde]
@@ -0,0 +1,90 @@
+use crate::asset::Asset;
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Addr;
+
+/// The maximum number of swap records kept by the indexer
+pub const MAX_SWAP_RECORDS: u32 = 10_000;
+
+/// This structure describes the parameters used for creating a contract.
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// The factory contract whose pairs can record swaps
+    pub factory_addr: String,
+}
+
+/// This structure describes the execute messages available in the contract.
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Records a swap executed by the calling pair. Only pairs registered in the factory can
+    /// record swaps
+    RecordSwap {
+        /// The asset offered by the trader
+        offer: Asset,
+        /// The asset returned to the trader
+        ask: Asset,
+        /// The commission charged for the swap
+        fee: Asset,
+        /// The address that executed the swap
+        sender: String,
+    },
+}
+
+/// This structure describes the query messages available in the contract.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Returns the contract configuration in a [`ConfigResponse`] object
+    #[returns(ConfigResponse)]
+    Config {},
+    /// Returns the most recent swaps starting from the newest one
+    #[returns(Vec<SwapRecord>)]
+    RecentSwaps {
+        /// Only return swaps executed in this pair
+        pair: Option<String>,
+        /// The maximum number of swaps to return
+        limit: u32,
+    },
+    /// Returns the volume swapped in a pair during the last `window_secs` seconds
+    #[returns(SwapVolumeResponse)]
+    SwapVolume {
+        /// The pair contract address
+        pair: String,
+        /// The length of the window in seconds
+        window_secs: u64,
+    },
+}
+
+/// This structure is used to return the indexer configuration.
+#[cw_serde]
+pub struct ConfigResponse {
+    /// The factory contract whose pairs can record swaps
+    pub factory_addr: Addr,
+}
+
+/// This structure describes a swap recorded by a pair.
+#[cw_serde]
+pub struct SwapRecord {
+    /// The pair that executed the swap
+    pub pair: Addr,
+    /// The asset offered by the trader
+    pub offer: Asset,
+    /// The asset returned to the trader
+    pub ask: Asset,
+    /// The commission charged for the swap
+    pub fee: Asset,
+    /// The block time at which the swap was recorded
+    pub timestamp: u64,
+    /// The address that executed the swap
+    pub sender: Addr,
+}
+
+/// This structure is used to return the volume of a pair.
+#[cw_serde]
+pub struct SwapVolumeResponse {
+    /// The offered amount of every pool asset
+    pub offer_volume: Vec<Asset>,
+    /// The returned amount of every pool asset
+    pub ask_volume: Vec<Asset>,
+    /// The number of swaps in the window
+    pub swaps_count: u64,
+}
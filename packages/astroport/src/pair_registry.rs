@@ -0,0 +1,81 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Addr;
+
+/// The maximum number of tags a pair can have
+pub const MAX_TAGS: usize = 10;
+
+/// This structure describes the parameters used for creating a contract.
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// The Astroport factory whose pairs can be registered
+    pub factory_addr: String,
+}
+
+/// This enum describes the risk levels a pair can be labeled with.
+#[cw_serde]
+pub enum RiskLevel {
+    Low,
+    Medium,
+    High,
+}
+
+/// This structure describes the user-facing metadata of a pair.
+#[cw_serde]
+pub struct PairMetadata {
+    /// The pair name shown to users
+    pub display_name: String,
+    /// The URI of the pair icon
+    pub icon_uri: Option<String>,
+    /// Tags used to filter pairs, e.g. "stable" or "lsd"
+    pub tags: Vec<String>,
+    /// The risk level of providing liquidity to the pair
+    pub risk_level: RiskLevel,
+}
+
+/// This structure describes the execute messages available in the contract.
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Registers or replaces the metadata of a pair. Only the factory owner or the pair admin
+    /// can execute this
+    RegisterPair {
+        /// The pair address
+        pair: String,
+        /// The pair metadata
+        metadata: PairMetadata,
+    },
+}
+
+/// This structure describes the query messages available in the contract.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Returns the contract configuration in a [`ConfigResponse`] object
+    #[returns(ConfigResponse)]
+    Config {},
+    /// Returns the metadata registered for a pair
+    #[returns(PairMetadata)]
+    PairMetadata { pair: String },
+    /// Returns the pairs with the given tag ordered by pair address
+    #[returns(Vec<RegisteredPair>)]
+    PairsByTag {
+        tag: String,
+        start_after: Option<String>,
+        limit: u32,
+    },
+}
+
+/// This structure describes a custom struct used to return the contract configuration.
+#[cw_serde]
+pub struct ConfigResponse {
+    /// The Astroport factory whose pairs can be registered
+    pub factory_addr: Addr,
+}
+
+/// This structure describes a pair together with its metadata.
+#[cw_serde]
+pub struct RegisteredPair {
+    /// The pair address
+    pub pair: Addr,
+    /// The pair metadata
+    pub metadata: PairMetadata,
+}
@@ -0,0 +1,59 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Binary, Decimal};
+use cw20::Cw20ReceiveMsg;
+
+use crate::asset::{Asset, AssetInfo};
+
+/// This structure is extended version of [`crate::pair::ExecuteMsg`] with the config change
+/// timelock of the stableswap pair.
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Receives a message of type [`Cw20ReceiveMsg`]
+    Receive(Cw20ReceiveMsg),
+    /// ProvideLiquidity allows someone to provide liquidity in the pool
+    ProvideLiquidity {
+        /// The assets available in the pool
+        assets: Vec<Asset>,
+        /// The slippage tolerance that allows liquidity provision only if the price in the pool doesn't move too much
+        slippage_tolerance: Option<Decimal>,
+        /// Determines whether the LP tokens minted for the user is auto_staked in the Generator contract
+        auto_stake: Option<bool>,
+        /// The receiver of LP tokens
+        receiver: Option<String>,
+    },
+    /// Swap performs a swap in the pool
+    Swap {
+        offer_asset: Asset,
+        ask_asset_info: Option<AssetInfo>,
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+        to: Option<String>,
+    },
+    /// Update the pair configuration
+    UpdateConfig { params: Binary },
+    /// ProposeNewOwner creates a proposal to change contract ownership.
+    /// The validity period for the proposal is set in the `expires_in` variable.
+    ProposeNewOwner {
+        /// Newly proposed contract owner
+        owner: String,
+        /// The date after which this proposal expires
+        expires_in: u64,
+    },
+    /// DropOwnershipProposal removes the existing offer to change contract ownership.
+    DropOwnershipProposal {},
+    /// Used to claim contract ownership.
+    ClaimOwnership {},
+    /// ProposeConfigChange schedules a config update which can be applied with
+    /// [`ExecuteMsg::ExecuteProposal`] once the timelock delay has passed. `changes` are the
+    /// pair type specific params accepted by [`ExecuteMsg::UpdateConfig`].
+    /// Only the pair owner can execute this.
+    ProposeConfigChange { changes: Binary },
+    /// ExecuteProposal applies a config update proposed with [`ExecuteMsg::ProposeConfigChange`]
+    /// once the current timelock delay has passed since it was proposed and before it expires.
+    /// Only the pair owner can execute this.
+    ExecuteProposal { proposal_id: u64 },
+    /// CancelProposal drops a config update proposal before its delay has passed or once it has
+    /// expired. Within the delay only the proposer or the pair owner can execute this; anyone
+    /// can cancel an expired proposal.
+    CancelProposal { proposal_id: u64 },
+}
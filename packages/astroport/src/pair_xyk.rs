@@ -0,0 +1,137 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Binary, Decimal, Uint128};
+use cw20::Cw20ReceiveMsg;
+
+use crate::asset::{Asset, AssetInfo};
+use crate::pair::{CustomHookConfig, PriceAlertConfig};
+
+/// This structure describes an LP token transfer reported to the pair by the LP token.
+#[cw_serde]
+pub struct LpTransfer {
+    /// The address the LP tokens were transferred from
+    pub from: String,
+    /// The address which received the LP tokens
+    pub to: String,
+    /// The amount of transferred LP tokens
+    pub amount: Uint128,
+}
+
+/// This structure is extended version of [`crate::pair::ExecuteMsg`] with the messages
+/// supported only by the XYK pair.
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Receives a message of type [`Cw20ReceiveMsg`]
+    Receive(Cw20ReceiveMsg),
+    /// ProvideLiquidity allows someone to provide liquidity in the pool
+    ProvideLiquidity {
+        /// The assets available in the pool
+        assets: Vec<Asset>,
+        /// The slippage tolerance that allows liquidity provision only if the price in the pool doesn't move too much
+        slippage_tolerance: Option<Decimal>,
+        /// Determines whether the LP tokens minted for the user is auto_staked in the Generator contract
+        auto_stake: Option<bool>,
+        /// The receiver of LP tokens
+        receiver: Option<String>,
+    },
+    /// Swap performs a swap in the pool
+    Swap {
+        offer_asset: Asset,
+        ask_asset_info: Option<AssetInfo>,
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+        to: Option<String>,
+    },
+    /// Update the pair configuration
+    UpdateConfig { params: Binary },
+    /// ProposeNewOwner creates a proposal to change contract ownership.
+    /// The validity period for the proposal is set in the `expires_in` variable.
+    ProposeNewOwner {
+        /// Newly proposed contract owner
+        owner: String,
+        /// The date after which this proposal expires
+        expires_in: u64,
+    },
+    /// DropOwnershipProposal removes the existing offer to change contract ownership.
+    DropOwnershipProposal {},
+    /// Used to claim contract ownership.
+    ClaimOwnership {},
+    /// ProposeEmergencyWithdraw starts the timelock for draining the pair to `recipient`.
+    /// Requires the factory owner or the [`crate::factory::Role::EmergencyAdmin`] role.
+    ProposeEmergencyWithdraw { recipient: String },
+    /// DropEmergencyWithdrawProposal cancels the pending emergency withdrawal
+    DropEmergencyWithdrawProposal {},
+    /// EmergencyWithdraw transfers all the pair balances to `recipient` and pauses the pair
+    /// permanently. It can only be executed [`crate::pair::EMERGENCY_WITHDRAW_TIMELOCK`] seconds after
+    /// the withdrawal to the same recipient was proposed.
+    EmergencyWithdraw { recipient: String },
+    /// SaveTwapSnapshot stores the current price accumulators which can later be used
+    /// in [`crate::pair::QueryMsg::TwapSince`]. Anyone can execute this.
+    SaveTwapSnapshot {},
+    /// UpdateLpHolders refreshes the top LP holders using the current LP balances of `addresses`.
+    /// `transfer` is set if the balances were changed by an LP transfer, so the LP cooldown is
+    /// carried over to the recipient. Only the LP token can execute this after every balance change.
+    UpdateLpHolders {
+        addresses: Vec<String>,
+        transfer: Option<LpTransfer>,
+    },
+    /// SetCustomHook sets the contract notified with a [`crate::pair::CustomHookMsg`] after pair actions.
+    /// [`None`] removes the hook. Only the factory can execute this.
+    SetCustomHook { config: Option<CustomHookConfig> },
+    /// SetPaused stores whether the pair is paused in the factory, either individually or globally.
+    /// Until it is set the pair queries the factory instead. Only the factory can execute this.
+    SetPaused { paused: bool },
+    /// UpdateMinLiquidityThreshold sets the minimum amount of each pool asset which must be
+    /// provided in a single liquidity provision. The amounts follow the order of the pair assets.
+    /// Only the factory owner or the pair admin can execute this.
+    UpdateMinLiquidityThreshold { min_amounts: [Uint128; 2] },
+    /// TakeSnapshot stores the current pool state in a [`crate::pair::Snapshot`] under an auto-incremented
+    /// identifier. Only the latest [`crate::pair::MAX_POOL_SNAPSHOTS`] snapshots are kept. Anyone can execute this.
+    TakeSnapshot {},
+    /// SetPriceAlertThreshold sets the price movement after which swaps emit a `price_alert` event.
+    /// Only the factory owner or the pair admin can execute this.
+    SetPriceAlertThreshold { config: PriceAlertConfig },
+    /// SetFeeRebate returns `rebate_bps` of the swap commission to the swap receiver until the
+    /// `expires_at` timestamp (if any). Only the factory owner or the pair admin can execute this.
+    SetFeeRebate {
+        rebate_bps: u16,
+        expires_at: Option<u64>,
+    },
+    /// SetTvlCap sets the max reserve of every pool asset after a liquidity provision. A `None`
+    /// cap removes the limit. Only the factory owner can execute this.
+    SetTvlCap { caps: [Option<Uint128>; 2] },
+    /// AddToWhitelist allows the addresses to swap, provide and withdraw liquidity. The first
+    /// call turns an open pair into a permissioned one. Only the factory owner can execute this.
+    AddToWhitelist { addresses: Vec<String> },
+    /// RemoveFromWhitelist revokes the pair access of the addresses.
+    /// Only the factory owner can execute this.
+    RemoveFromWhitelist { addresses: Vec<String> },
+    /// RemoveLiquidityImbalanced withdraws liquidity and swaps part of the withdrawn
+    /// non-preferred asset into the preferred one against the pool, paying the pool fee.
+    /// The pair must be allowed to transfer `lp_amount` LP tokens from the sender
+    RemoveLiquidityImbalanced {
+        /// The amount of LP tokens to burn
+        lp_amount: Uint128,
+        /// The min amount of the first pool asset to receive
+        min_asset0: Uint128,
+        /// The min amount of the second pool asset to receive
+        min_asset1: Uint128,
+        /// The asset the rest of the withdrawn non-preferred asset is swapped into
+        preferred_asset: AssetInfo,
+        /// The share of the withdrawn non-preferred asset to keep. Zero exits in the preferred
+        /// asset only and one is a balanced withdrawal
+        target_ratio: Decimal,
+    },
+    /// UpdateSwapLimits sets the max USD value of a single swap and of all the swaps of a day.
+    /// A `None` limit removes it. The USD oracle must be set to enable a limit.
+    /// Only the factory owner can execute this.
+    UpdateSwapLimits {
+        max_single: Option<Uint128>,
+        max_daily: Option<Uint128>,
+    },
+    /// BlockSender prevents the address from swapping, providing and withdrawing liquidity.
+    /// Only the factory owner can execute this.
+    BlockSender { address: String, reason: String },
+    /// UnblockSender lifts the block set with [`ExecuteMsg::BlockSender`].
+    /// Only the factory owner can execute this.
+    UnblockSender { address: String },
+}
@@ -1,6 +1,7 @@
 use crate::asset::{Asset, AssetInfo, PairInfo};
 use crate::factory::{
-    Config as FactoryConfig, FeeInfoResponse, PairType, PairsResponse, QueryMsg as FactoryQueryMsg,
+    Config as FactoryConfig, FeeInfoResponse, PairAddressResponse, PairType, PairsResponse,
+    QueryMsg as FactoryQueryMsg, Role,
 };
 use crate::pair::{QueryMsg as PairQueryMsg, ReverseSimulationResponse, SimulationResponse};
 
@@ -182,6 +183,116 @@ where
     })
 }
 
+/// Returns the fee information for a specific pair. The total fee includes the fee tier set for
+/// the pair by an executed fee tier proposal.
+///
+/// * **pair_contract** address of the pair we query information for.
+///
+/// * **pair_type** type of the pair.
+pub fn query_pair_fee_info<C>(
+    querier: &QuerierWrapper<C>,
+    factory_contract: impl Into<String>,
+    pair_contract: impl Into<String>,
+    pair_type: PairType,
+) -> StdResult<FeeInfo>
+where
+    C: CustomQuery,
+{
+    let res: FeeInfoResponse = querier.query_wasm_smart(
+        factory_contract,
+        &FactoryQueryMsg::PairFeeInfo {
+            pair_addr: pair_contract.into(),
+            pair_type,
+        },
+    )?;
+
+    Ok(FeeInfo {
+        fee_address: res.fee_address,
+        total_fee_rate: Decimal::from_ratio(res.total_fee_bps, 10000u16),
+        maker_fee_rate: Decimal::from_ratio(res.maker_fee_bps, 10000u16),
+    })
+}
+
+/// Returns whether the pair is paused in the factory.
+///
+/// * **pair_contract** address of the pair to check.
+pub fn query_pair_paused<C>(
+    querier: &QuerierWrapper<C>,
+    factory_contract: impl Into<String>,
+    pair_contract: impl Into<String>,
+) -> StdResult<bool>
+where
+    C: CustomQuery,
+{
+    querier.query_wasm_smart(
+        factory_contract,
+        &FactoryQueryMsg::IsPairPaused {
+            pair_addr: pair_contract.into(),
+        },
+    )
+}
+
+/// Returns whether the pair is blacklisted in the factory.
+///
+/// * **pair_contract** address of the pair to check.
+pub fn query_pair_blacklisted<C>(
+    querier: &QuerierWrapper<C>,
+    factory_contract: impl Into<String>,
+    pair_contract: impl Into<String>,
+) -> StdResult<bool>
+where
+    C: CustomQuery,
+{
+    querier.query_wasm_smart(
+        factory_contract,
+        &FactoryQueryMsg::IsPairBlacklisted {
+            pair: pair_contract.into(),
+        },
+    )
+}
+
+/// Returns whether the address holds the role in the factory.
+///
+/// * **address** address to check.
+///
+/// * **role** role to check.
+pub fn query_has_role<C>(
+    querier: &QuerierWrapper<C>,
+    factory_contract: impl Into<String>,
+    address: impl Into<String>,
+    role: Role,
+) -> StdResult<bool>
+where
+    C: CustomQuery,
+{
+    querier.query_wasm_smart(
+        factory_contract,
+        &FactoryQueryMsg::HasRole {
+            address: address.into(),
+            role,
+        },
+    )
+}
+
+/// Returns the admin set for the pair in the factory, if any.
+///
+/// * **pair_contract** address of the pair.
+pub fn query_pair_admin<C>(
+    querier: &QuerierWrapper<C>,
+    factory_contract: impl Into<String>,
+    pair_contract: impl Into<String>,
+) -> StdResult<Option<Addr>>
+where
+    C: CustomQuery,
+{
+    querier.query_wasm_smart(
+        factory_contract,
+        &FactoryQueryMsg::PairAdmin {
+            pair: pair_contract.into(),
+        },
+    )
+}
+
 /// Accepts two tokens as input and returns a pair's information.
 pub fn query_pair_info(
     querier: &QuerierWrapper,
@@ -196,6 +307,22 @@ pub fn query_pair_info(
     )
 }
 
+/// Returns the address of the pair registered in the factory for the assets.
+pub fn query_pair_address(
+    querier: &QuerierWrapper,
+    factory_contract: impl Into<String>,
+    asset_infos: &[AssetInfo],
+) -> StdResult<Addr> {
+    let res: PairAddressResponse = querier.query_wasm_smart(
+        factory_contract,
+        &FactoryQueryMsg::PairAddress {
+            asset_infos: asset_infos.to_vec(),
+        },
+    )?;
+
+    Ok(Addr::unchecked(res.address))
+}
+
 /// Returns a vector that contains items of type [`PairInfo`] which
 /// symbolize pairs instantiated in the Astroport factory
 pub fn query_pairs_info(
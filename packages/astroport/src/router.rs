@@ -1,17 +1,28 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 
-use cosmwasm_std::{Decimal, Uint128};
+use cosmwasm_std::{Addr, Binary, Decimal, Uint128};
 use cw20::Cw20ReceiveMsg;
 
-use crate::asset::AssetInfo;
+use crate::asset::{Asset, AssetInfo};
 
 pub const MAX_SWAP_OPERATIONS: usize = 50;
+/// The maximum number of paths compared in [`QueryMsg::SimulateMultiPath`]
+pub const MAX_MULTI_PATHS: usize = 4;
+/// The maximum number of price impact levels in [`QueryMsg::PairDepth`]
+pub const MAX_DEPTH_LEVELS: usize = 10;
+/// The maximum number of conversions in [`ExecuteMsg::MultiAssetSwap`]
+pub const MAX_MULTI_ASSET_CONVERSIONS: usize = 10;
+/// The number of days of swap volume used to pick a [`VolumeDiscountConfig`] tier
+pub const VOLUME_RETENTION_DAYS: u64 = 30;
 
 /// This structure holds the parameters used for creating a contract.
 #[cw_serde]
 pub struct InstantiateMsg {
     /// The astroport factory contract address
     pub astroport_factory: String,
+    /// The chain ID swap operations are validated against. Only used if the block info has no
+    /// chain ID
+    pub chain_id: Option<String>,
 }
 
 /// This enum describes a swap operation.
@@ -55,6 +66,66 @@ pub enum ExecuteMsg {
         minimum_receive: Option<Uint128>,
         to: Option<String>,
         max_spread: Option<Decimal>,
+        /// The chain ID the operations are meant for. The swap fails on any other chain
+        chain_id: Option<String>,
+    },
+    /// SwapAndSplit processes multiple swaps and splits the ask tokens of the last swap operation
+    /// between the recipients according to their shares. The shares must sum to 1. Rounding dust
+    /// goes to the first recipient
+    SwapAndSplit {
+        operations: Vec<SwapOperation>,
+        recipients: Vec<(String, Decimal)>,
+        min_total_receive: Option<Uint128>,
+    },
+    /// ExecuteSwapOperationsWithCallback processes multiple swaps and executes `callback` on
+    /// `callback_contract` with the ask tokens of the last swap operation. Native ask tokens are
+    /// sent as funds and CW20 ask tokens with a CW20 `Send` message. The callback contract must
+    /// be allowed with [`ExecuteMsg::UpdateAllowedCallbackContracts`]
+    ExecuteSwapOperationsWithCallback {
+        operations: Vec<SwapOperation>,
+        min_receive: Option<Uint128>,
+        callback: Binary,
+        callback_contract: String,
+    },
+    /// CreateStreamingSwap escrows the offer asset and swaps it in equal chunks every `interval_secs`
+    CreateStreamingSwap {
+        offer_asset: Asset,
+        operations: Vec<SwapOperation>,
+        chunks: u8,
+        interval_secs: u64,
+        min_chunk_receive: Option<Uint128>,
+    },
+    /// ExecuteStreamingSwapChunk swaps the next chunk of a streaming swap. Anyone can execute it once the interval has passed
+    ExecuteStreamingSwapChunk { swap_id: u64 },
+    /// CancelStreamingSwap cancels a streaming swap and returns the unswapped assets to its creator
+    CancelStreamingSwap { swap_id: u64 },
+    /// UpdateConfig updates the router configuration. Only the contract admin can execute it
+    UpdateConfig {
+        astroport_factory: Option<String>,
+        dust_threshold: Option<Uint128>,
+        gas_price: Option<Decimal>,
+        check_received_amount: Option<bool>,
+        volume_discount: Option<VolumeDiscountConfig>,
+    },
+    /// SetWrapper registers the native coin wrapper used to convert between the native and CW20
+    /// representations of `denom`. A `None` wrapper removes it. Only the contract admin can execute it
+    SetWrapper {
+        denom: String,
+        wrapper: Option<String>,
+    },
+    /// UpdateAllowedCallbackContracts sets the contracts which can receive the callbacks of
+    /// [`ExecuteMsg::ExecuteSwapOperationsWithCallback`]. `None` disables callbacks. Only the
+    /// contract admin can execute it
+    UpdateAllowedCallbackContracts { contracts: Option<Vec<String>> },
+    /// MultiAssetSwap swaps several offer assets through their own swap operations one after
+    /// another and sends the ask assets to `to`. Amounts of the same ask asset are combined.
+    /// Native offer assets must be sent with the message and CW20 offer assets are transferred
+    /// from the sender using its allowances. `min_total_receive` requires all the conversions to
+    /// end with the same ask asset
+    MultiAssetSwap {
+        conversions: Vec<ConversionSpec>,
+        min_total_receive: Option<Uint128>,
+        to: Option<String>,
     },
 
     /// Internal use
@@ -65,6 +136,28 @@ pub enum ExecuteMsg {
         max_spread: Option<Decimal>,
         single: bool,
     },
+    /// Internal use
+    /// ExecuteWrapOperation wraps or unwraps the whole router balance of the offer asset
+    /// between two swap operations using different representations of the same coin
+    ExecuteWrapOperation {
+        offer_asset_info: AssetInfo,
+        ask_asset_info: AssetInfo,
+    },
+    /// Internal use
+    /// AdvanceMultiAssetSwap checks the amount received from the previous conversion of a
+    /// [`ExecuteMsg::MultiAssetSwap`] and prepares the next one. The last call sends the received assets
+    AdvanceMultiAssetSwap {},
+}
+
+/// This structure describes a conversion of [`ExecuteMsg::MultiAssetSwap`].
+#[cw_serde]
+pub struct ConversionSpec {
+    /// The asset to swap. It must be the offer asset of the first swap operation
+    pub offer: Asset,
+    /// The swap operations converting the offer asset
+    pub operations: Vec<SwapOperation>,
+    /// The minimum amount of the ask asset of the last swap operation to get from this conversion
+    pub min_receive: Uint128,
 }
 
 #[cw_serde]
@@ -83,6 +176,36 @@ pub enum Cw20HookMsg {
         to: Option<String>,
         /// Max spread
         max_spread: Option<Decimal>,
+        /// The chain ID the operations are meant for. The swap fails on any other chain
+        chain_id: Option<String>,
+    },
+    SwapAndSplit {
+        /// A vector of swap operations
+        operations: Vec<SwapOperation>,
+        /// The recipients of the ask tokens along with their shares summing to 1
+        recipients: Vec<(String, Decimal)>,
+        /// The minimum amount of tokens to get from a swap before splitting
+        min_total_receive: Option<Uint128>,
+    },
+    ExecuteSwapOperationsWithCallback {
+        /// A vector of swap operations
+        operations: Vec<SwapOperation>,
+        /// The minimum amount of tokens to get from a swap
+        min_receive: Option<Uint128>,
+        /// The message executed on the callback contract with the ask tokens
+        callback: Binary,
+        /// The contract receiving the ask tokens and the callback
+        callback_contract: String,
+    },
+    CreateStreamingSwap {
+        /// A vector of swap operations
+        operations: Vec<SwapOperation>,
+        /// The number of chunks to split the sent amount into
+        chunks: u8,
+        /// The minimum number of seconds between chunks
+        interval_secs: u64,
+        /// The minimum amount of tokens to get from each chunk
+        min_chunk_receive: Option<Uint128>,
     },
 }
 
@@ -101,6 +224,68 @@ pub enum QueryMsg {
         /// The swap operations to perform, each swap involving a specific pool
         operations: Vec<SwapOperation>,
     },
+    /// SimulateChainedSwapWithSlippage simulates multi-hop swap operations and estimates the range
+    /// of the end result from the spreads of the past swaps in every pair
+    #[returns(ChainedSimulationResponse)]
+    SimulateChainedSwapWithSlippage {
+        /// The asset to swap
+        offer_asset: Asset,
+        /// The swap operations to perform, each swap involving a specific pool
+        operations: Vec<SwapOperation>,
+        /// The number of standard deviations of the past spreads the bounds are apart from the
+        /// simulated spread of every hop. Defaults to 1
+        confidence: Option<Decimal>,
+    },
+    /// StreamingSwap returns the state of a streaming swap using a [`StreamingSwap`] object
+    #[returns(StreamingSwap)]
+    StreamingSwap { swap_id: u64 },
+    /// Wrapper returns the native coin wrapper registered for the denom
+    #[returns(Option<Addr>)]
+    Wrapper { denom: String },
+    /// GasEstimate estimates the gas used by multi-hop swap operations from the gas benchmarks
+    /// stored in the factory
+    #[returns(GasEstimateResponse)]
+    GasEstimate {
+        /// The swap operations to perform, each swap involving a specific pool
+        operations: Vec<SwapOperation>,
+        /// The asset to swap
+        offer_asset: Asset,
+    },
+    /// SimulateMultiPath simulates swapping `offer_asset` split between parallel paths ending
+    /// with the same ask asset and searches for the split which maximizes the total output
+    #[returns(MultiPathSimulationResponse)]
+    SimulateMultiPath {
+        /// The asset to swap
+        offer_asset: Asset,
+        /// The swap operations of every path. At most [`MAX_MULTI_PATHS`] paths can be compared
+        paths: Vec<Vec<SwapOperation>>,
+        /// The share of the offer amount swapped through each path. The shares must sum to 1
+        splits: Vec<Decimal>,
+    },
+    /// BatchBalances returns the balances of `address` in all the specified assets in the same
+    /// order as the assets
+    #[returns(Vec<Asset>)]
+    BatchBalances {
+        /// The address whose balances are queried
+        address: String,
+        /// The assets to query
+        assets: Vec<AssetInfo>,
+    },
+    /// ContractInfo returns the cw2 contract name and version
+    #[returns(cw2::ContractVersion)]
+    ContractInfo {},
+    /// PairDepth returns the liquidity of the pair of `offer` and `ask` at several price impact
+    /// levels as an order book of the `ask` asset priced in the `offer` asset
+    #[returns(PairDepthResponse)]
+    PairDepth {
+        /// The asset used to buy the ask asset
+        offer: AssetInfo,
+        /// The asset being bought and sold
+        ask: AssetInfo,
+        /// The price impact levels in percent, e.g. 0.5 for 0.5%. At most [`MAX_DEPTH_LEVELS`]
+        /// levels strictly between 0 and 100 can be requested
+        depth_levels: Vec<Decimal>,
+    },
 }
 
 /// This structure describes a custom struct to return a query response containing the base contract configuration.
@@ -108,6 +293,41 @@ pub enum QueryMsg {
 pub struct ConfigResponse {
     /// The Astroport factory contract address
     pub astroport_factory: String,
+    /// Routes with swap operations offering less than this amount are aborted with
+    /// `DustSwapPrevented`. Dust hops are deliberately not skipped: a skipped hop would leave the
+    /// output of the previous hops in the router, where the next route would sweep it
+    pub dust_threshold: Uint128,
+    /// The native gas price used to estimate swap fees
+    pub gas_price: Decimal,
+    /// Whether swap operations only offer the amount the router received from the previous operation
+    pub check_received_amount: bool,
+    /// The fee discount tiers of the swappers
+    pub volume_discount: VolumeDiscountConfig,
+    /// The chain ID swap operations are validated against
+    pub chain_id: String,
+    /// The contracts which can receive swap callbacks. `None` if callbacks are disabled
+    pub allowed_callback_contracts: Option<Vec<Addr>>,
+}
+
+/// This structure describes the fee discount tiers based on the swap volume of the last
+/// [`VOLUME_RETENTION_DAYS`] days.
+#[cw_serde]
+#[derive(Default)]
+pub struct VolumeDiscountConfig {
+    /// The (minimum volume, discount in bps) tiers sorted by the minimum volume. The volume is
+    /// the sum of the amounts of the route's ask asset returned by the routes of the swapper
+    pub tiers: Vec<(Uint128, u16)>,
+}
+
+impl VolumeDiscountConfig {
+    /// Returns the discount of the highest tier reached by `volume`. Zero if no tier is reached.
+    pub fn discount_bps(&self, volume: Uint128) -> u16 {
+        self.tiers
+            .iter()
+            .take_while(|(min_volume, _)| *min_volume <= volume)
+            .last()
+            .map_or(0, |(_, discount_bps)| *discount_bps)
+    }
 }
 
 /// This structure describes a custom struct to return a query response containing the end amount of a swap simulation
@@ -117,6 +337,79 @@ pub struct SimulateSwapOperationsResponse {
     pub amount: Uint128,
 }
 
+/// This structure describes a custom struct to return a query response containing the estimated
+/// range of a multi-hop swap simulation
+#[cw_serde]
+pub struct ChainedSimulationResponse {
+    /// The amount of tokens received in a swap simulation
+    pub expected_output: Uint128,
+    /// The amount received if every hop has a larger spread than simulated
+    pub lower_bound: Uint128,
+    /// The amount received if every hop has a smaller spread than simulated
+    pub upper_bound: Uint128,
+    /// The simulated spread of every hop relative to the amount at the pool price
+    pub per_hop_spreads: Vec<Decimal>,
+}
+
+/// This structure describes a custom struct to return a query response containing the estimated
+/// gas of multi-hop swap operations
+#[cw_serde]
+pub struct GasEstimateResponse {
+    /// The estimated gas of the whole route including the router overhead
+    pub estimated_gas: u64,
+    /// The estimated fee in the native gas denom using the router gas price
+    pub estimated_fee_native: Uint128,
+    /// The estimated gas of every hop
+    pub gas_per_hop: Vec<u64>,
+}
+
+/// This structure describes a custom struct to return a query response containing the
+/// simulation of a swap split between parallel paths
+#[cw_serde]
+pub struct MultiPathSimulationResponse {
+    /// The amount of tokens received from each path with the requested split
+    pub outputs_per_path: Vec<Uint128>,
+    /// The total amount of tokens received with the requested split
+    pub total_output: Uint128,
+    /// The split which equalizes the marginal output of the paths
+    pub optimal_split: Vec<Decimal>,
+    /// The total amount of tokens received with the optimal split
+    pub optimal_total: Uint128,
+}
+
+/// This structure describes a custom struct to return a query response containing the
+/// liquidity of a pair at the requested price impact levels
+#[cw_serde]
+pub struct PairDepthResponse {
+    /// Every price impact level with the amount of the ask asset which can be sold to the pair
+    /// for the offer asset without exceeding it
+    pub bids: Vec<(Decimal, Uint128)>,
+    /// Every price impact level with the amount of the ask asset which can be bought from the
+    /// pair with the offer asset without exceeding it
+    pub asks: Vec<(Decimal, Uint128)>,
+}
+
+/// This structure describes a swap which is executed gradually in chunks.
+#[cw_serde]
+pub struct StreamingSwap {
+    /// The address which created the streaming swap. It receives the swapped assets
+    pub creator: Addr,
+    /// The escrowed asset which is not swapped yet
+    pub remaining: Asset,
+    /// The swap operations performed for each chunk
+    pub operations: Vec<SwapOperation>,
+    /// The amount swapped in each chunk. The last chunk swaps the whole remaining amount
+    pub chunk_amount: Uint128,
+    /// The number of chunks which are not executed yet
+    pub chunks_left: u8,
+    /// The minimum number of seconds between chunks
+    pub interval_secs: u64,
+    /// The time (in seconds) from which the next chunk can be executed
+    pub next_execution_time: u64,
+    /// The minimum amount of tokens to get from each chunk
+    pub min_chunk_receive: Option<Uint128>,
+}
+
 /// This structure describes a migration message.
 /// We currently take no arguments for migrations.
 #[cw_serde]
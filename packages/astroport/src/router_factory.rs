@@ -0,0 +1,77 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Addr;
+
+/// This structure describes the parameters used for creating a contract.
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// The address allowed to deploy routers
+    pub owner: String,
+}
+
+/// This structure describes the router parameters which can be updated through the router factory.
+#[cw_serde]
+pub struct RouterConfig {
+    /// The Astroport factory used by the router to find pairs
+    pub astroport_factory: String,
+}
+
+/// This structure describes the execute messages available in the contract.
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Instantiates a new router with the router factory as its admin and records it for the
+    /// current chain. Only the owner can execute this
+    DeployRouter {
+        /// The Astroport factory used by the router to find pairs
+        factory_addr: String,
+        /// The router code identifier
+        code_id: u64,
+        /// The router contract label
+        label: String,
+        /// The address allowed to update the router config through the router factory
+        owner: String,
+    },
+    /// Updates the config of a deployed router. Only the owner of the router factory or the
+    /// owner of the router can execute this
+    UpdateRouterConfig {
+        /// The router address
+        router: String,
+        /// The new router config
+        config: RouterConfig,
+    },
+}
+
+/// This structure describes the query messages available in the contract.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Returns the contract configuration in a [`ConfigResponse`] object
+    #[returns(ConfigResponse)]
+    Config {},
+    /// Returns the router deployed for the chain in a [`RouterInfo`] object
+    #[returns(RouterInfo)]
+    RouterFor { chain_id: String },
+}
+
+/// This structure describes a router deployed by the router factory.
+#[cw_serde]
+pub struct RouterInfo {
+    /// The router address
+    pub address: Addr,
+    /// The chain the router is deployed on
+    pub chain_id: String,
+    /// The router code identifier
+    pub code_id: u64,
+    /// The router contract label
+    pub label: String,
+    /// The address allowed to update the router config through the router factory
+    pub owner: Addr,
+    /// The Astroport factory used by the router
+    pub astroport_factory: Addr,
+}
+
+/// This structure is used to return the router factory configuration.
+#[cw_serde]
+pub struct ConfigResponse {
+    /// The address allowed to deploy routers
+    pub owner: Addr,
+}
@@ -1,14 +1,18 @@
-use crate::asset::{format_lp_token_name, Asset, AssetInfo, PairInfo};
+use crate::asset::{
+    format_lp_token_name, native_asset_info, Asset, AssetInfo, AssetInfoExt, PairInfo,
+};
 use crate::mock_querier::mock_dependencies;
 use crate::querier::{
     query_all_balances, query_balance, query_pair_info, query_supply, query_token_balance,
 };
 
 use crate::factory::PairType;
+use crate::math::{assert_slippage_tolerance, SlippageToleranceError};
+use crate::native_coin_wrapper::{Cw20HookMsg as WrapperHookMsg, ExecuteMsg as WrapperExecuteMsg};
 use crate::DecimalCheckedOps;
 use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
 use cosmwasm_std::{
-    to_json_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, Empty, Uint128, WasmMsg,
+    to_json_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, Decimal256, Empty, Uint128, WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
 
@@ -130,6 +134,15 @@ fn test_asset_info() {
     assert_eq!(true, native_token_info.is_native_token());
     assert_eq!(false, token_info.is_native_token());
 
+    assert_eq!(
+        AssetInfo::sort_pair(&native_token_info, &token_info),
+        [&token_info, &native_token_info]
+    );
+    assert_eq!(
+        AssetInfo::sort_pair(&token_info, &native_token_info),
+        [&token_info, &native_token_info]
+    );
+
     let mut deps = mock_dependencies(&[Coin {
         denom: "uusd".to_string(),
         amount: Uint128::new(123),
@@ -217,6 +230,53 @@ fn test_asset() {
     );
 }
 
+#[test]
+fn test_wrap_msgs() {
+    let native_info = AssetInfo::NativeToken {
+        denom: "uluna".to_string(),
+    };
+    let token_info = AssetInfo::Token {
+        contract_addr: Addr::unchecked("wluna"),
+    };
+
+    assert_eq!(
+        native_info
+            .wrap_msg(Uint128::new(100), Addr::unchecked("wrapper"))
+            .unwrap(),
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("wrapper"),
+            msg: to_json_binary(&WrapperExecuteMsg::Wrap {}).unwrap(),
+            funds: vec![Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::new(100),
+            }],
+        })
+    );
+
+    assert_eq!(
+        token_info
+            .unwrap_msg(Uint128::new(100), Addr::unchecked("wrapper"))
+            .unwrap(),
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("wluna"),
+            msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                contract: String::from("wrapper"),
+                amount: Uint128::new(100),
+                msg: to_json_binary(&WrapperHookMsg::Unwrap {}).unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+
+    token_info
+        .wrap_msg(Uint128::new(100), Addr::unchecked("wrapper"))
+        .unwrap_err();
+    native_info
+        .unwrap_msg(Uint128::new(100), Addr::unchecked("wrapper"))
+        .unwrap_err();
+}
+
 #[test]
 fn query_astroport_pair_contract() {
     let mut deps = mock_dependencies(&[]);
@@ -235,6 +295,9 @@ fn query_astroport_pair_contract() {
             contract_addr: Addr::unchecked("pair0000"),
             liquidity_token: Addr::unchecked("liquidity0000"),
             pair_type: PairType::Xyk {},
+            creator: None,
+            creation_height: 0,
+            creation_time: 0,
         },
     )]);
 
@@ -273,6 +336,9 @@ fn test_format_lp_token_name() {
             contract_addr: Addr::unchecked("pair0000"),
             liquidity_token: Addr::unchecked("liquidity0000"),
             pair_type: PairType::Xyk {},
+            creator: None,
+            creation_height: 0,
+            creation_time: 0,
         },
     )]);
 
@@ -326,3 +392,54 @@ fn test_decimal_checked_ops() {
             .is_err()
     );
 }
+
+#[test]
+fn test_assert_slippage_tolerance() {
+    let assets = [
+        native_asset_info("uusd".to_string()).with_balance(0u8),
+        native_asset_info("uluna".to_string()).with_balance(0u8),
+    ];
+    let reserves = [Uint128::new(1000), Uint128::new(2000)];
+    let tolerance = Decimal::percent(1);
+
+    // The deviation doesn't depend on the asset order
+    assert_slippage_tolerance(
+        &assets,
+        &[100u128.into(), 198u128.into()],
+        &reserves,
+        tolerance,
+    )
+    .unwrap();
+    assert_slippage_tolerance(
+        &assets,
+        &[99u128.into(), 200u128.into()],
+        &reserves,
+        tolerance,
+    )
+    .unwrap();
+
+    let err = assert_slippage_tolerance(
+        &assets,
+        &[1u128.into(), 200u128.into()],
+        &reserves,
+        tolerance,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        SlippageToleranceError {
+            asset: native_asset_info("uluna".to_string()),
+            deviation: Decimal256::percent(99),
+            allowed: tolerance,
+        }
+    );
+
+    // Empty pools aren't checked
+    assert_slippage_tolerance(
+        &assets,
+        &[1u128.into(), 200u128.into()],
+        &[Uint128::zero(), Uint128::zero()],
+        tolerance,
+    )
+    .unwrap();
+}
@@ -1,5 +1,6 @@
-use cosmwasm_schema::cw_serde;
+use cosmwasm_schema::{cw_serde, QueryResponses};
 
+use crate::asset::Asset;
 use cosmwasm_std::{StdError, StdResult, Uint128};
 pub use cw20::{
     BalanceResponse, Cw20Coin, Cw20ExecuteMsg as ExecuteMsg, Cw20QueryMsg as QueryMsg, Logo,
@@ -36,6 +37,33 @@ pub struct InstantiateMsg {
     pub marketing: Option<InstantiateMarketingInfo>,
 }
 
+/// This structure describes the LP fee messages available in addition to the CW20 messages.
+#[cw_serde]
+pub enum FeesExecuteMsg {
+    /// Starts tracking the LP fees with the fees per LP token reported by the minter.
+    /// Only the minter can execute this
+    EnableFeeTracking {},
+}
+
+/// This structure describes the LP fee queries available in addition to the CW20 queries.
+/// They are only supported by LP tokens whose minter enabled the fee tracking.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum FeesQueryMsg {
+    /// Returns the LP fees earned by the address in a [`FeesEarnedResponse`] object
+    #[returns(FeesEarnedResponse)]
+    FeesEarned { address: String },
+}
+
+/// This structure is used to return the LP fees earned by an LP token holder.
+#[cw_serde]
+pub struct FeesEarnedResponse {
+    /// The fees earned in each pool asset
+    pub fees: Vec<Asset>,
+    /// The timestamp (in seconds) since which the fees are tracked for the address
+    pub since: u64,
+}
+
 /// This structure describes a migration message.
 #[cw_serde]
 pub struct MigrateMsg {}
@@ -194,6 +194,7 @@ where
                     pair_type: PairType::Xyk {},
                     asset_infos: asset_infos.to_vec(),
                     init_params: None,
+                    proposal_id: None,
                 },
                 &[],
             )
@@ -241,6 +242,7 @@ where
                     init_params: Some(
                         to_json_binary(init_params.unwrap_or(&default_params)).unwrap(),
                     ),
+                    proposal_id: None,
                 },
                 &[],
             )
@@ -311,6 +313,7 @@ where
                     init_params: Some(
                         to_json_binary(init_params.unwrap_or(&default_params)).unwrap(),
                     ),
+                    proposal_id: None,
                 },
                 &[],
             )
@@ -25,11 +25,14 @@ where
     C::QueryT: CustomQuery + DeserializeOwned + 'static,
 {
     use astroport_token as cnt;
-    let contract = Box::new(ContractWrapper::new_with_empty(
-        cnt::contract::execute,
-        cnt::contract::instantiate,
-        cnt::contract::query,
-    ));
+    let contract = Box::new(
+        ContractWrapper::new_with_empty(
+            cnt::contract::execute,
+            cnt::contract::instantiate,
+            cnt::contract::query,
+        )
+        .with_reply_empty(cnt::contract::reply),
+    );
 
     app.borrow_mut().store_code(contract)
 }
@@ -383,7 +383,7 @@ pub fn check_pair_registered<C>(
 where
     C: CustomQuery,
 {
-    astroport_factory::state::PAIRS
+    astroport_factory::state::CURRENT_PAIRS
         .query(&querier, factory.clone(), &pair_key(asset_infos))
         .map(|inner| inner.is_some())
 }
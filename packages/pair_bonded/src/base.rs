@@ -1,16 +1,19 @@
 use crate::error::ContractError;
-use crate::state::CONFIG;
+use crate::state::{CONFIG, VIRTUAL_RESERVES};
 use astroport::asset::{addr_opt_validate, Asset, AssetInfo, PairInfo};
 use astroport::factory::PairType;
 use astroport::pair::{
-    ConfigResponse, CumulativePricesResponse, Cw20HookMsg, InstantiateMsg, PoolResponse,
+    ConfigResponse, CumulativePricesResponse, Cw20HookMsg, OraclePriceResponse, PoolResponse,
     ReverseSimulationResponse, SimulationResponse,
 };
-use astroport::pair_bonded::{Config, ExecuteMsg, QueryMsg};
+use astroport::pair_bonded::{
+    Config, ExecuteMsg, InstantiateMsg, QueryMsg, VirtualReservesResponse,
+    DEFAULT_MAX_ORACLE_AGE_SECS, VIRTUAL_RESERVES_DECAY_FACTOR,
+};
 use astroport::querier::query_factory_config;
 use cosmwasm_std::{
     from_json, to_json_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Response,
-    StdResult, Uint128,
+    StdError, StdResult, Uint128,
 };
 use cw2::set_contract_version;
 use cw20::Cw20ReceiveMsg;
@@ -44,12 +47,24 @@ pub trait PairBonded<'a> {
                 liquidity_token: Addr::unchecked(""),
                 asset_infos: msg.asset_infos.clone(),
                 pair_type: PairType::Custom(String::from("Bonded")),
+                creator: None,
+                creation_height: 0,
+                creation_time: 0,
             },
             factory_addr: deps.api.addr_validate(&msg.factory_addr)?,
+            block_time_last: 0,
+            max_oracle_age_secs: DEFAULT_MAX_ORACLE_AGE_SECS,
         };
 
         CONFIG.save(deps.storage, &config)?;
 
+        if let Some(virtual_reserves) = msg.initial_virtual_reserves {
+            if virtual_reserves.iter().any(Uint128::is_zero) {
+                return Err(ContractError::InvalidVirtualReserves {});
+            }
+            VIRTUAL_RESERVES.save(deps.storage, &virtual_reserves)?;
+        }
+
         Ok(Response::new())
     }
 
@@ -58,6 +73,8 @@ pub trait PairBonded<'a> {
     /// ## Variants
     /// * **ExecuteMsg::UpdateConfig { params: Binary }**  Not supported.
     ///
+    /// * **ExecuteMsg::UpdateMaxOracleAge { max_age }** Updates the maximum age of the oracle price used for swaps.
+    ///
     /// * **ExecuteMsg::Receive(msg)** Receives a message of type [`Cw20ReceiveMsg`] and processes
     /// it depending on the received template.
     ///
@@ -92,6 +109,9 @@ pub trait PairBonded<'a> {
     ) -> Result<Response, ContractError> {
         match msg {
             ExecuteMsg::UpdateConfig { .. } => Err(ContractError::NotSupported {}),
+            ExecuteMsg::UpdateMaxOracleAge { max_age } => {
+                self.update_max_oracle_age(deps, info, max_age)
+            }
             ExecuteMsg::Receive(msg) => self.receive_cw20(deps, env, info, msg),
             ExecuteMsg::ProvideLiquidity { .. } => Err(ContractError::NotSupported {}),
             ExecuteMsg::Swap {
@@ -137,6 +157,11 @@ pub trait PairBonded<'a> {
     /// pool using a [`CumulativePricesResponse`] object.
     ///
     /// * **QueryMsg::Config {}** Returns the configuration for the pair contract using a [`ConfigResponse`] object.
+    ///
+    /// * **QueryMsg::VirtualReserves {}** Returns the pool's virtual reserves using a [`VirtualReservesResponse`] object.
+    ///
+    /// * **QueryMsg::Price { base, quote, amount }** Returns the oracle price of the base asset
+    /// denominated in the quote asset using an [`OraclePriceResponse`] object.
     fn query(&self, deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         match msg {
             QueryMsg::Pair {} => to_json_binary(&self.query_pair_info(deps)?),
@@ -152,6 +177,14 @@ pub trait PairBonded<'a> {
                 to_json_binary(&self.query_cumulative_prices(deps, env)?)
             }
             QueryMsg::Config {} => to_json_binary(&self.query_config(deps)?),
+            QueryMsg::VirtualReserves {} => {
+                to_json_binary(&self.query_virtual_reserves(deps, env)?)
+            }
+            QueryMsg::Price {
+                base,
+                quote,
+                amount,
+            } => to_json_binary(&self.query_oracle_price(deps, env, base, quote, amount)?),
         }
     }
 
@@ -188,6 +221,8 @@ pub trait PairBonded<'a> {
                     return Err(ContractError::Unauthorized {});
                 }
 
+                self.assert_oracle_heartbeat(deps.as_ref(), &env)?;
+
                 let to_addr = addr_opt_validate(deps.api, &to)?;
                 let contract_addr = info.sender.clone();
                 let sender = deps.api.addr_validate(&cw20_msg.sender)?;
@@ -205,7 +240,9 @@ pub trait PairBonded<'a> {
                     to_addr,
                 )
             }
-            Cw20HookMsg::WithdrawLiquidity { .. } => Err(ContractError::NotSupported {}),
+            Cw20HookMsg::WithdrawLiquidity { .. } | Cw20HookMsg::ProvideLiquidity { .. } => {
+                Err(ContractError::NotSupported {})
+            }
         }
     }
 
@@ -239,6 +276,8 @@ pub trait PairBonded<'a> {
             return Err(ContractError::Cw20DirectSwap {});
         }
 
+        self.assert_oracle_heartbeat(deps.as_ref(), &env)?;
+
         let to_addr = addr_opt_validate(deps.api, &to)?;
 
         self.swap(
@@ -253,6 +292,56 @@ pub trait PairBonded<'a> {
         )
     }
 
+    /// Updates the maximum age of the oracle price used for swaps.
+    ///
+    /// * **max_age** the maximum age in seconds.
+    ///
+    /// ## Executor
+    /// Only the factory owner can execute this.
+    fn update_max_oracle_age(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        max_age: u64,
+    ) -> Result<Response, ContractError> {
+        let mut config = CONFIG.load(deps.storage)?;
+
+        if info.sender != query_factory_config(&deps.querier, &config.factory_addr)?.owner {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        config.max_oracle_age_secs = max_age;
+        CONFIG.save(deps.storage, &config)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "update_max_oracle_age")
+            .add_attribute("max_age", max_age.to_string()))
+    }
+
+    /// Returns the timestamp (in seconds) at which the oracle price used by [`Self::swap`] was last
+    /// updated. The default implementation treats the price source as live and returns the current block time.
+    fn oracle_last_updated(&self, _deps: Deps, env: &Env) -> StdResult<u64> {
+        Ok(env.block.time.seconds())
+    }
+
+    /// Checks that the oracle price isn't older than [`Config::max_oracle_age_secs`].
+    /// It is called before every swap.
+    fn assert_oracle_heartbeat(&self, deps: Deps, env: &Env) -> Result<(), ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        let last_updated = self.oracle_last_updated(deps, env)?;
+        let current_time = env.block.time.seconds();
+
+        if current_time.saturating_sub(last_updated) > config.max_oracle_age_secs {
+            return Err(ContractError::StaleOraclePrice {
+                last_updated,
+                current_time,
+                max_age: config.max_oracle_age_secs,
+            });
+        }
+
+        Ok(())
+    }
+
     /// Performs a swap with the specified parameters.
     /// ### Must be implemented
     #[allow(clippy::too_many_arguments)]
@@ -322,6 +411,50 @@ pub trait PairBonded<'a> {
         Ok(resp)
     }
 
+    /// Returns the price of `amount` of `base` denominated in `quote` derived from a swap
+    /// simulation. A zero `amount` is priced as one token with 6 decimals. The pair doesn't keep
+    /// a price history, so the confidence is always zero.
+    fn query_oracle_price(
+        &self,
+        deps: Deps,
+        env: Env,
+        base: AssetInfo,
+        quote: AssetInfo,
+        amount: Uint128,
+    ) -> StdResult<OraclePriceResponse> {
+        let asset_infos = CONFIG.load(deps.storage)?.pair_info.asset_infos;
+        if base.equal(&quote) || !asset_infos.contains(&base) || !asset_infos.contains(&quote) {
+            return Err(StdError::generic_err(
+                "Base and quote assets must be different pool assets",
+            ));
+        }
+
+        let offer_amount = if amount.is_zero() {
+            Uint128::new(1_000_000)
+        } else {
+            amount
+        };
+        let simulation = self.query_simulation(
+            deps,
+            env.clone(),
+            Asset {
+                info: base,
+                amount: offer_amount,
+            },
+        )?;
+        let price = Decimal::from_ratio(
+            simulation.return_amount + simulation.commission_amount,
+            offer_amount,
+        );
+
+        Ok(OraclePriceResponse::new(
+            price,
+            price,
+            None,
+            env.block.time.seconds(),
+        ))
+    }
+
     /// Returns the pair contract configuration in a [`ConfigResponse`] object.
     fn query_config(&self, deps: Deps) -> StdResult<ConfigResponse> {
         let config = CONFIG.load(deps.storage)?;
@@ -329,13 +462,56 @@ pub trait PairBonded<'a> {
         let factory_config = query_factory_config(&deps.querier, &config.factory_addr)?;
 
         Ok(ConfigResponse {
-            block_time_last: 0u64,
+            block_time_last: config.block_time_last,
             params: None,
             owner: factory_config.owner,
             factory_addr: config.factory_addr,
         })
     }
 
+    /// Returns the pool's virtual reserves in a [`VirtualReservesResponse`] object.
+    fn query_virtual_reserves(&self, deps: Deps, env: Env) -> StdResult<VirtualReservesResponse> {
+        let config = CONFIG.load(deps.storage)?;
+        let pools = config
+            .pair_info
+            .query_pools(&deps.querier, &env.contract.address)?;
+
+        Ok(VirtualReservesResponse {
+            initial_virtual_reserves: VIRTUAL_RESERVES.may_load(deps.storage)?.unwrap_or_default(),
+            virtual_reserves: self.virtual_reserves(deps, &pools)?,
+        })
+    }
+
+    /// Returns the virtual reserves which apply to the real reserves in `pools`.
+    /// Each virtual reserve decays linearly as the respective real reserve grows and reaches zero
+    /// once the real reserve exceeds [`VIRTUAL_RESERVES_DECAY_FACTOR`] times the initial virtual amount.
+    fn virtual_reserves(&self, deps: Deps, pools: &[Asset]) -> StdResult<[Uint128; 2]> {
+        let initial = VIRTUAL_RESERVES.may_load(deps.storage)?.unwrap_or_default();
+
+        Ok([
+            initial[0]
+                .saturating_sub(pools[0].amount / Uint128::new(VIRTUAL_RESERVES_DECAY_FACTOR)),
+            initial[1]
+                .saturating_sub(pools[1].amount / Uint128::new(VIRTUAL_RESERVES_DECAY_FACTOR)),
+        ])
+    }
+
+    /// Returns the reserves in `pools` extended with the virtual reserves.
+    /// Implementations should price swaps using these reserves so that the invariant holds as
+    /// `k = (real_0 + virtual_0) * (real_1 + virtual_1)`.
+    fn reserves_with_virtual(&self, deps: Deps, pools: Vec<Asset>) -> StdResult<Vec<Asset>> {
+        let virtual_reserves = self.virtual_reserves(deps, &pools)?;
+
+        Ok(pools
+            .into_iter()
+            .zip(virtual_reserves)
+            .map(|(pool, virtual_amount)| Asset {
+                amount: pool.amount + virtual_amount,
+                ..pool
+            })
+            .collect())
+    }
+
     /// Returns the total amount of assets in the pool.
     fn pool_info(&self, config: &Config) -> StdResult<(Vec<Asset>, Uint128)> {
         let pools = vec![
@@ -383,7 +559,12 @@ pub trait PairBonded<'a> {
         }
 
         let offer_amount = offer_asset.amount;
-        let return_amount = ask_asset_info.query_pool(&deps.querier, env.contract.address)?;
+        let return_amount = ask_asset_info.query_pool(&deps.querier, &env.contract.address)?;
+
+        CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+            config.block_time_last = env.block.time.seconds();
+            Ok(config)
+        })?;
 
         let return_asset = Asset {
             info: ask_asset_info.clone(),
@@ -33,6 +33,16 @@ pub enum ContractError {
 
     #[error("Failed to migrate the contract")]
     MigrationError {},
+
+    #[error("Initial virtual reserves must not be zero")]
+    InvalidVirtualReserves {},
+
+    #[error("Oracle price is stale: last updated at {last_updated}, current time {current_time}, max age {max_age} seconds")]
+    StaleOraclePrice {
+        last_updated: u64,
+        current_time: u64,
+        max_age: u64,
+    },
 }
 
 impl From<OverflowError> for ContractError {
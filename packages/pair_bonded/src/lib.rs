@@ -1,3 +1,4 @@
 pub mod base;
 pub mod error;
+pub mod migration;
 pub mod state;
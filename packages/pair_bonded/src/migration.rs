@@ -0,0 +1,33 @@
+use astroport::asset::PairInfo;
+use astroport::pair_bonded::{Config, DEFAULT_MAX_ORACLE_AGE_SECS};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, StdResult, Storage};
+use cw_storage_plus::Item;
+
+use crate::state::CONFIG;
+
+/// This structure stores the main config parameters of bonded pairs before v2.0.0.
+#[cw_serde]
+pub struct ConfigV100 {
+    /// General pair information (e.g pair type)
+    pub pair_info: PairInfo,
+    /// The factory contract address
+    pub factory_addr: Addr,
+}
+
+pub const CONFIG_V100: Item<ConfigV100> = Item::new("config");
+
+/// Converts the v1 config to the current [`Config`] schema. New fields are set to their defaults.
+pub fn migrate_config_to_v200(storage: &mut dyn Storage) -> StdResult<Config> {
+    let cfg_v100 = CONFIG_V100.load(storage)?;
+
+    let config = Config {
+        pair_info: cfg_v100.pair_info,
+        factory_addr: cfg_v100.factory_addr,
+        block_time_last: 0,
+        max_oracle_age_secs: DEFAULT_MAX_ORACLE_AGE_SECS,
+    };
+    CONFIG.save(storage, &config)?;
+
+    Ok(config)
+}
@@ -1,5 +1,9 @@
 use astroport::pair_bonded::Config;
+use cosmwasm_std::Uint128;
 use cw_storage_plus::Item;
 
 /// Stores the config struct at the given key
 pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Stores the virtual reserves the pool was seeded with
+pub const VIRTUAL_RESERVES: Item<[Uint128; 2]> = Item::new("virtual_reserves");
@@ -1,5 +1,4 @@
-use astroport::pair::InstantiateMsg;
-use astroport::pair_bonded::{ExecuteMsg, QueryMsg};
+use astroport::pair_bonded::{ExecuteMsg, InstantiateMsg, QueryMsg};
 
 use cosmwasm_schema::write_api;
 
@@ -6,8 +6,7 @@ pub mod contract;
 pub mod state;
 
 use crate::state::MigrateMsg;
-use astroport::pair::InstantiateMsg;
-use astroport::pair_bonded::{ExecuteMsg, QueryMsg};
+use astroport::pair_bonded::{ExecuteMsg, InstantiateMsg, QueryMsg};
 use astroport_pair_bonded::base::PairBonded;
 use astroport_pair_bonded::error::ContractError;
 use cosmwasm_std::{